@@ -0,0 +1,173 @@
+//! Extraction benchmarks over a corpus of fixture pages.
+//!
+//! `cargo bench --bench extractor` measures each page with criterion. With
+//! `BENCH_CHECK_BUDGETS` set, it then compares each page's median against
+//! its budget below and exits non-zero on a regression, which is how CI and
+//! `make bench` gate on it.
+
+use bytes::Bytes;
+use chrono::Utc;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group};
+use reqwest::{StatusCode, header::HeaderMap};
+use std::{env, fs, hint::black_box, path::PathBuf, process::ExitCode, time::Duration};
+use url::Url;
+
+use capsule::{
+    extractor::extract_or_reason,
+    fetcher::{Charset, PageResponse},
+};
+
+const ENV_CHECK_BUDGETS: &str = "BENCH_CHECK_BUDGETS";
+const GROUP: &str = "extract";
+
+/// A page to extract and the median time it's allowed to take in a release
+/// build. Budgets are generous enough to absorb noisy CI machines; a blown
+/// budget means something got several times slower, not a few percent.
+struct Case {
+    name: &'static str,
+    url: &'static str,
+    html: String,
+    budget: Duration,
+}
+
+fn fixture(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| panic!("Failed to read fixture {path}: {e}"))
+}
+
+fn corpus() -> Vec<Case> {
+    let longform = fixture("benches/fixtures/longform.html");
+
+    // A very long page: the longform article's body repeated until it's
+    // around a megabyte, which is where per-stage reparsing shows up
+    let (head, rest) = longform
+        .split_once("<article>")
+        .expect("longform fixture has an article");
+    let (article, tail) = rest
+        .split_once("</article>")
+        .expect("longform fixture closes its article");
+    let large = format!(
+        "{head}<article>{}</article>{tail}",
+        article.repeat(1_000_000 / article.len())
+    );
+
+    vec![
+        Case {
+            name: "article",
+            url: "https://example.com/article",
+            html: fixture("src/extractor/tests/fixtures/article.html"),
+            budget: Duration::from_millis(5),
+        },
+        Case {
+            name: "blog",
+            url: "https://blog.example.com/post",
+            html: fixture("src/extractor/tests/fixtures/blog.html"),
+            budget: Duration::from_millis(5),
+        },
+        Case {
+            name: "news",
+            url: "https://news.example.com/2025/09/cycling-network",
+            html: fixture("benches/fixtures/news.html"),
+            budget: Duration::from_millis(15),
+        },
+        Case {
+            name: "longform",
+            url: "https://notes.example.com/small-decisions",
+            html: longform,
+            budget: Duration::from_millis(30),
+        },
+        Case {
+            name: "large",
+            url: "https://notes.example.com/small-decisions/all",
+            html: large,
+            budget: Duration::from_millis(1500),
+        },
+    ]
+}
+
+fn response(case: &Case) -> PageResponse {
+    PageResponse {
+        url_final: Url::parse(case.url).expect("Invalid fixture URL"),
+        status: StatusCode::OK,
+        headers: HeaderMap::new(),
+        body_raw: Bytes::from(case.html.clone()),
+        body_utf8: case.html.clone(),
+        charset: Charset::Utf8,
+        fetched_at: Utc::now(),
+    }
+}
+
+fn bench_extract(c: &mut Criterion) {
+    let mut group = c.benchmark_group(GROUP);
+    // The large page takes around a second per run
+    group.sample_size(10);
+
+    for case in corpus() {
+        let response = response(&case);
+        group.throughput(Throughput::Bytes(case.html.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(case.name),
+            &response,
+            |b, response| {
+                b.iter(|| futures::executor::block_on(extract_or_reason(black_box(response))))
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Where criterion keeps its results, following the same lookup it does
+fn criterion_dir() -> PathBuf {
+    if let Some(home) = env::var_os("CRITERION_HOME") {
+        return PathBuf::from(home);
+    }
+    env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("target"))
+        .join("criterion")
+}
+
+/// Median from the latest run of a case, or `None` if it has never run
+fn latest_median(case: &Case) -> Option<Duration> {
+    let path = criterion_dir()
+        .join(GROUP)
+        .join(case.name)
+        .join("new/estimates.json");
+    let estimates: serde_json::Value = serde_json::from_str(&fs::read_to_string(path).ok()?)
+        .expect("criterion wrote unreadable estimates");
+    let nanos = estimates["median"]["point_estimate"]
+        .as_f64()
+        .expect("criterion estimates have a median");
+    Some(Duration::from_nanos(nanos as u64))
+}
+
+criterion_group!(benches, bench_extract);
+
+fn main() -> ExitCode {
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+
+    if env::var_os(ENV_CHECK_BUDGETS).is_none() {
+        return ExitCode::SUCCESS;
+    }
+
+    let mut over_budget = false;
+    for case in corpus() {
+        let Some(median) = latest_median(&case) else {
+            continue;
+        };
+        if median > case.budget {
+            eprintln!(
+                "{}: median {median:?} exceeds budget {:?}",
+                case.name, case.budget
+            );
+            over_budget = true;
+        }
+    }
+
+    if over_budget {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS trg_domain_prefs_updated_at ON domain_prefs;
+DROP TABLE IF EXISTS domain_prefs;
@@ -0,0 +1,15 @@
+-- Per-user, per-domain overrides consulted by the fetch/extract pipeline
+CREATE TABLE domain_prefs (
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  domain TEXT NOT NULL,
+  headless_render BOOLEAN NOT NULL DEFAULT FALSE,
+  skip_images BOOLEAN NOT NULL DEFAULT FALSE,
+  default_tag TEXT,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (user_id, domain)
+);
+
+CREATE TRIGGER trg_domain_prefs_updated_at
+BEFORE UPDATE ON domain_prefs
+FOR EACH ROW EXECUTE FUNCTION set_updated_at();
@@ -0,0 +1,3 @@
+-- Profile fields and free-form (but application-validated) preferences
+ALTER TABLE users ADD COLUMN display_name TEXT;
+ALTER TABLE users ADD COLUMN prefs JSONB NOT NULL DEFAULT '{}';
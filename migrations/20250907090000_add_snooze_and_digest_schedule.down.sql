@@ -0,0 +1,5 @@
+ALTER TABLE users DROP COLUMN IF EXISTS next_digest_at;
+
+DROP INDEX IF EXISTS idx_items_snoozed_until;
+
+ALTER TABLE items DROP COLUMN IF EXISTS snoozed_until;
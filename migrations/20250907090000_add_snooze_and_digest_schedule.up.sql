@@ -0,0 +1,7 @@
+-- snoozed items resurface at snoozed_until
+ALTER TABLE items ADD COLUMN snoozed_until TIMESTAMPTZ;
+
+CREATE INDEX idx_items_snoozed_until ON items(snoozed_until) WHERE snoozed_until IS NOT NULL;
+
+-- next scheduled digest, used to discard stale send_digest jobs
+ALTER TABLE users ADD COLUMN next_digest_at TIMESTAMPTZ;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS quota_levels;
+DROP TABLE IF EXISTS notifications;
@@ -0,0 +1,23 @@
+-- notification events; the serial id doubles as the SSE event id
+CREATE TABLE notifications (
+  id BIGSERIAL PRIMARY KEY,
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  kind TEXT NOT NULL,
+  payload JSONB NOT NULL DEFAULT '{}',
+  -- set when the event should also go out by email; cleared by the mailer
+  email_pending BOOLEAN NOT NULL DEFAULT FALSE,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_notifications_user_id_id ON notifications(user_id, id);
+CREATE INDEX idx_notifications_email_pending ON notifications(id) WHERE email_pending;
+
+-- last quota threshold reported per user and resource, so each crossing is
+-- announced once
+CREATE TABLE quota_levels (
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  resource TEXT NOT NULL,
+  level SMALLINT NOT NULL,
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (user_id, resource)
+);
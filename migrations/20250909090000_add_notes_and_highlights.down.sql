@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS items_note_gin;
+DROP TABLE IF EXISTS highlights;
+ALTER TABLE items DROP COLUMN IF EXISTS note;
@@ -0,0 +1,18 @@
+-- free-form note attached to an item
+ALTER TABLE items ADD COLUMN note TEXT;
+
+-- highlighted passages
+CREATE TABLE highlights (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  item_id UUID NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+  user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  quote TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_highlights_item_id ON highlights(item_id);
+CREATE INDEX idx_highlights_user_id ON highlights(user_id);
+
+-- full-text search over annotations, matching contents_clean_text_gin
+CREATE INDEX items_note_gin ON items USING GIN (to_tsvector('simple', note)) WHERE note IS NOT NULL;
+CREATE INDEX highlights_quote_gin ON highlights USING GIN (to_tsvector('simple', quote));
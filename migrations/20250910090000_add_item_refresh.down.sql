@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_items_next_refresh_at;
+ALTER TABLE items DROP COLUMN IF EXISTS http_last_modified;
+ALTER TABLE items DROP COLUMN IF EXISTS http_etag;
+ALTER TABLE items DROP COLUMN IF EXISTS next_refresh_at;
+ALTER TABLE items DROP COLUMN IF EXISTS refresh_interval_secs;
@@ -0,0 +1,9 @@
+-- "living documents": items refetched every refresh_interval_secs
+ALTER TABLE items ADD COLUMN refresh_interval_secs INTEGER CHECK (refresh_interval_secs > 0);
+ALTER TABLE items ADD COLUMN next_refresh_at TIMESTAMPTZ;
+
+-- validators from the last fetch, sent back for conditional GETs
+ALTER TABLE items ADD COLUMN http_etag TEXT;
+ALTER TABLE items ADD COLUMN http_last_modified TEXT;
+
+CREATE INDEX idx_items_next_refresh_at ON items(next_refresh_at) WHERE refresh_interval_secs IS NOT NULL;
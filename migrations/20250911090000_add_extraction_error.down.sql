@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_items_extraction_failed;
+ALTER TABLE items DROP COLUMN IF EXISTS extraction_error;
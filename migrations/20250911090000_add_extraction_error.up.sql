@@ -0,0 +1,5 @@
+-- why the extractor refused the last fetched page; NULL once extraction succeeds
+ALTER TABLE items ADD COLUMN extraction_error TEXT
+    CHECK (extraction_error IN ('too_short', 'boilerplate', 'render_required'));
+
+CREATE INDEX idx_items_extraction_failed ON items(user_id) WHERE extraction_error IS NOT NULL;
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS item_state_transitions;
+ALTER TABLE items DROP COLUMN IF EXISTS processing_state_changed_at;
+ALTER TABLE items DROP COLUMN IF EXISTS processing_state;
+DROP TYPE IF EXISTS processing_state;
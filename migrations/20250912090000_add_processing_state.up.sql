@@ -0,0 +1,23 @@
+-- fine-grained pipeline progress, kept alongside the user-facing item_status
+CREATE TYPE processing_state AS ENUM (
+    'pending', 'fetching', 'fetch_failed', 'extracting', 'ready', 'failed_permanent'
+);
+
+ALTER TABLE items ADD COLUMN processing_state processing_state NOT NULL DEFAULT 'pending';
+ALTER TABLE items ADD COLUMN processing_state_changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+UPDATE items SET processing_state = CASE
+    WHEN extraction_error IS NOT NULL THEN 'failed_permanent'::processing_state
+    WHEN status <> 'pending' THEN 'ready'::processing_state
+    ELSE 'pending'::processing_state
+END;
+
+CREATE TABLE item_state_transitions (
+    id BIGSERIAL PRIMARY KEY,
+    item_id UUID NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    from_state processing_state,
+    to_state processing_state NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_item_state_transitions_item_id ON item_state_transitions(item_id, id);
@@ -0,0 +1,11 @@
+-- jobs staged in the same transaction as the domain write that needs them;
+-- the worker's relay moves them into jobs
+CREATE TABLE job_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    job_id UUID NOT NULL DEFAULT gen_random_uuid(),
+    kind TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    run_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    max_attempts INTEGER NOT NULL DEFAULT 25,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
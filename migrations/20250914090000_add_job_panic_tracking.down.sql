@@ -0,0 +1,2 @@
+ALTER TABLE jobs DROP COLUMN IF EXISTS dead_lettered_at;
+ALTER TABLE jobs DROP COLUMN IF EXISTS panic_count;
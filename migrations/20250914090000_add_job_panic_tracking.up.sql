@@ -0,0 +1,3 @@
+-- consecutive handler panics; a job that keeps crashing is dead-lettered
+ALTER TABLE jobs ADD COLUMN panic_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE jobs ADD COLUMN dead_lettered_at TIMESTAMPTZ;
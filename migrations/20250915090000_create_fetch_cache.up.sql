@@ -0,0 +1,15 @@
+-- short-lived cache of fetched and extracted pages shared across users, so a
+-- page many people save at once is fetched and extracted once
+CREATE TABLE fetch_cache (
+    url_key TEXT PRIMARY KEY,
+    final_url TEXT NOT NULL,
+    body TEXT NOT NULL,
+    checksum TEXT NOT NULL,
+    http_etag TEXT,
+    http_last_modified TEXT,
+    extraction JSONB NOT NULL,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_fetch_cache_expires_at ON fetch_cache(expires_at);
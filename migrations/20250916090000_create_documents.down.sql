@@ -0,0 +1,12 @@
+-- give every item its own copy of the shared content back before dropping it
+UPDATE contents c
+SET raw_html = d.raw_html,
+    clean_html = d.clean_html,
+    clean_text = d.clean_text
+FROM items i
+JOIN documents d ON d.id = i.document_id
+WHERE c.item_id = i.id;
+
+DROP INDEX IF EXISTS idx_items_document_id;
+ALTER TABLE items DROP COLUMN IF EXISTS document_id;
+DROP TABLE IF EXISTS documents;
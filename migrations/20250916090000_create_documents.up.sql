@@ -0,0 +1,20 @@
+-- extracted content shared by every item saved from the same canonical URL;
+-- rows are keyed by a hash of the URL so the table doesn't list what was saved
+CREATE TABLE documents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    url_hash TEXT NOT NULL UNIQUE,
+    raw_html TEXT,
+    clean_html TEXT,
+    clean_text TEXT,
+    lang VARCHAR(16),
+    checksum TEXT,
+    extracted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX documents_clean_text_gin ON documents USING GIN (to_tsvector('simple', clean_text)) WHERE clean_text IS NOT NULL;
+
+-- an item pointing at a document keeps only its metadata in `contents`
+ALTER TABLE items ADD COLUMN document_id UUID REFERENCES documents(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_items_document_id ON items(document_id) WHERE document_id IS NOT NULL;
@@ -0,0 +1,3 @@
+-- heading outline (table of contents) of the clean HTML, as an array of
+-- {id, level, text, anchor}
+ALTER TABLE contents ADD COLUMN outline JSONB;
@@ -0,0 +1,2 @@
+-- heuristic adult-content flag, so clients can blur thumbnails in shared spaces
+ALTER TABLE items ADD COLUMN nsfw BOOLEAN NOT NULL DEFAULT FALSE;
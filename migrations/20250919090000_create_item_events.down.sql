@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS trg_items_archived ON items;
+DROP TRIGGER IF EXISTS trg_items_saved ON items;
+DROP FUNCTION IF EXISTS record_item_saved_or_archived();
+DROP TABLE IF EXISTS item_events;
+DROP TYPE IF EXISTS item_event_kind;
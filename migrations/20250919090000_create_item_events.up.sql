@@ -0,0 +1,41 @@
+-- user-visible lifecycle of an item, for "why was this never fetched?" reports
+CREATE TYPE item_event_kind AS ENUM (
+    'saved', 'fetched', 'fetch_failed', 'extracted', 'extraction_failed',
+    'refetched', 'archived', 'shared'
+);
+
+CREATE TABLE item_events (
+    id BIGSERIAL PRIMARY KEY,
+    item_id UUID NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    kind item_event_kind NOT NULL,
+    detail TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_item_events_item_id ON item_events(item_id, id);
+
+-- saving and archiving happen on several write paths (API, imports, bulk
+-- updates), so the database records them rather than each caller
+CREATE OR REPLACE FUNCTION record_item_saved_or_archived() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO item_events (item_id, kind, created_at)
+        VALUES (NEW.id, 'saved', NEW.created_at);
+    ELSIF NEW.status = 'archived' AND OLD.status IS DISTINCT FROM 'archived' THEN
+        INSERT INTO item_events (item_id, kind) VALUES (NEW.id, 'archived');
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_items_saved
+AFTER INSERT ON items
+FOR EACH ROW EXECUTE FUNCTION record_item_saved_or_archived();
+
+CREATE TRIGGER trg_items_archived
+AFTER UPDATE OF status ON items
+FOR EACH ROW EXECUTE FUNCTION record_item_saved_or_archived();
+
+-- existing items at least show when they were saved
+INSERT INTO item_events (item_id, kind, created_at)
+SELECT id, 'saved', created_at FROM items;
@@ -0,0 +1,3 @@
+-- how far through the item the reader has scrolled, from 0 to 1
+ALTER TABLE items ADD COLUMN read_progress REAL NOT NULL DEFAULT 0
+  CHECK (read_progress >= 0 AND read_progress <= 1);
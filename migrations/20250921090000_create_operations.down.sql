@@ -0,0 +1,4 @@
+DROP TRIGGER IF EXISTS trg_operations_updated_at ON operations;
+DROP TABLE IF EXISTS operations;
+DROP TYPE IF EXISTS operation_state;
+DROP TYPE IF EXISTS operation_kind;
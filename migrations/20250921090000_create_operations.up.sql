@@ -0,0 +1,25 @@
+-- long-running, user-initiated work (imports, exports) that clients poll
+CREATE TYPE operation_kind AS ENUM ('import', 'export');
+CREATE TYPE operation_state AS ENUM ('pending', 'running', 'succeeded', 'failed');
+
+CREATE TABLE operations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind operation_kind NOT NULL,
+    state operation_state NOT NULL DEFAULT 'pending',
+    processed INTEGER NOT NULL DEFAULT 0 CHECK (processed >= 0),
+    -- unknown until the job has looked at its input
+    total INTEGER CHECK (total >= 0),
+    error_count INTEGER NOT NULL DEFAULT 0 CHECK (error_count >= 0),
+    -- the first few errors, as [{"subject": ..., "error": ...}]
+    error_samples JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_operations_user_id ON operations(user_id, created_at DESC);
+
+CREATE TRIGGER trg_operations_updated_at
+BEFORE UPDATE ON operations
+FOR EACH ROW EXECUTE FUNCTION set_updated_at();
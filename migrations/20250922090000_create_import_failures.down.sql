@@ -0,0 +1,4 @@
+ALTER TABLE items DROP COLUMN IF EXISTS import_row;
+ALTER TABLE items DROP COLUMN IF EXISTS import_operation_id;
+DROP TABLE IF EXISTS import_failures;
+DROP TYPE IF EXISTS import_failure_reason;
@@ -0,0 +1,20 @@
+-- rows of an import that didn't become a readable item, for the error report
+CREATE TYPE import_failure_reason AS ENUM ('bad_url', 'duplicate', 'fetch_forbidden');
+
+CREATE TABLE import_failures (
+    id BIGSERIAL PRIMARY KEY,
+    operation_id UUID NOT NULL REFERENCES operations(id) ON DELETE CASCADE,
+    -- 1-based position in the submitted list
+    row_number INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    reason import_failure_reason NOT NULL,
+    detail TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- a row fails at most once, so retried jobs and refetches don't repeat it
+    CONSTRAINT uq_import_failures_row UNIQUE (operation_id, row_number)
+);
+
+-- items created by an import remember where they came from, so a later
+-- fetch failure can be reported against the right row
+ALTER TABLE items ADD COLUMN import_operation_id UUID REFERENCES operations(id) ON DELETE SET NULL;
+ALTER TABLE items ADD COLUMN import_row INTEGER;
@@ -0,0 +1,6 @@
+-- Postgres can't drop an enum value, so 'blocked_domain' stays on
+-- import_failure_reason
+DROP TRIGGER IF EXISTS trg_domain_rules_updated_at ON domain_rules;
+DROP TABLE IF EXISTS domain_rules;
+DROP TYPE IF EXISTS domain_rule_action;
+ALTER TABLE users DROP COLUMN IF EXISTS is_admin;
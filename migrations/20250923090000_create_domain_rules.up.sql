@@ -0,0 +1,24 @@
+-- operators manage instance-wide settings such as domain rules
+ALTER TABLE users ADD COLUMN is_admin BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- instance-wide rules on which domains may be saved and fetched; a rule for
+-- example.com covers its subdomains and the most specific rule wins
+CREATE TYPE domain_rule_action AS ENUM ('block', 'allow');
+
+CREATE TABLE domain_rules (
+    -- normalized like domain_prefs.domain: lowercase, no www.
+    domain TEXT PRIMARY KEY,
+    action domain_rule_action NOT NULL,
+    reason TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_domain_rules_action ON domain_rules(action);
+
+CREATE TRIGGER trg_domain_rules_updated_at
+    BEFORE UPDATE ON domain_rules
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+ALTER TYPE import_failure_reason ADD VALUE 'blocked_domain';
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS abuse_events;
+DROP TYPE IF EXISTS abuse_event_kind;
+DROP TRIGGER IF EXISTS trg_user_throttles_updated_at ON user_throttles;
+DROP TABLE IF EXISTS user_throttles;
@@ -0,0 +1,30 @@
+-- automatic pauses on saving for users whose activity looks abusive
+CREATE TABLE user_throttles (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- saves are refused until then; NULL or past when not throttled
+    throttled_until TIMESTAMPTZ,
+    reason TEXT,
+    -- set by an admin; exempt users are never throttled automatically
+    exempt BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER trg_user_throttles_updated_at
+    BEFORE UPDATE ON user_throttles
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+-- audit trail of throttles and admin overrides
+CREATE TYPE abuse_event_kind AS ENUM ('throttled', 'lifted', 'exempted', 'unexempted');
+
+CREATE TABLE abuse_events (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind abuse_event_kind NOT NULL,
+    detail TEXT,
+    -- the admin behind a manual change; NULL for automatic throttles
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_abuse_events_user ON abuse_events(user_id, created_at DESC);
@@ -0,0 +1,2 @@
+-- health checks count jobs finished in the last hour
+CREATE INDEX idx_jobs_finished ON jobs(updated_at) WHERE status IN ('succeeded', 'failed');
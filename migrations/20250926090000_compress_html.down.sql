@@ -0,0 +1,16 @@
+-- Postgres can't decompress zstd, so refuse rather than drop the only copy
+-- of compressed pages
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM contents WHERE raw_html_zst IS NOT NULL OR clean_html_zst IS NOT NULL)
+       OR EXISTS (SELECT 1 FROM documents WHERE raw_html_zst IS NOT NULL OR clean_html_zst IS NOT NULL)
+    THEN
+        RAISE EXCEPTION 'compressed HTML is still stored; it has to be decompressed by the application before this migration can be reverted';
+    END IF;
+END
+$$;
+
+DROP INDEX IF EXISTS idx_documents_uncompressed_html;
+DROP INDEX IF EXISTS idx_contents_uncompressed_html;
+ALTER TABLE documents DROP COLUMN IF EXISTS clean_html_zst, DROP COLUMN IF EXISTS raw_html_zst;
+ALTER TABLE contents DROP COLUMN IF EXISTS clean_html_zst, DROP COLUMN IF EXISTS raw_html_zst;
@@ -0,0 +1,23 @@
+-- zstd-compressed page HTML, written and read by the repository layer. The
+-- TEXT columns are left holding only rows the compress_html job hasn't
+-- reached yet.
+ALTER TABLE contents
+    ADD COLUMN raw_html_zst BYTEA,
+    ADD COLUMN clean_html_zst BYTEA;
+ALTER TABLE documents
+    ADD COLUMN raw_html_zst BYTEA,
+    ADD COLUMN clean_html_zst BYTEA;
+
+-- already compressed, so TOAST shouldn't try again
+ALTER TABLE contents
+    ALTER COLUMN raw_html_zst SET STORAGE EXTERNAL,
+    ALTER COLUMN clean_html_zst SET STORAGE EXTERNAL;
+ALTER TABLE documents
+    ALTER COLUMN raw_html_zst SET STORAGE EXTERNAL,
+    ALTER COLUMN clean_html_zst SET STORAGE EXTERNAL;
+
+-- rows still waiting to be compressed
+CREATE INDEX idx_contents_uncompressed_html ON contents(item_id)
+    WHERE raw_html IS NOT NULL OR clean_html IS NOT NULL;
+CREATE INDEX idx_documents_uncompressed_html ON documents(id)
+    WHERE raw_html IS NOT NULL OR clean_html IS NOT NULL;
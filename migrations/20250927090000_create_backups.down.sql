@@ -0,0 +1,14 @@
+DROP TABLE IF EXISTS backups;
+
+DROP INDEX IF EXISTS idx_documents_updated_at;
+DROP INDEX IF EXISTS idx_highlights_updated_at;
+DROP INDEX IF EXISTS idx_contents_updated_at;
+DROP INDEX IF EXISTS idx_items_updated_at;
+
+DROP TRIGGER IF EXISTS trg_documents_updated_at ON documents;
+DROP TRIGGER IF EXISTS trg_highlights_updated_at ON highlights;
+DROP TRIGGER IF EXISTS trg_contents_updated_at ON contents;
+
+ALTER TABLE documents DROP COLUMN IF EXISTS updated_at;
+ALTER TABLE highlights DROP COLUMN IF EXISTS updated_at;
+ALTER TABLE contents DROP COLUMN IF EXISTS updated_at;
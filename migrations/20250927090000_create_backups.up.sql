@@ -0,0 +1,37 @@
+-- change tracking for incremental backups; items already have updated_at
+ALTER TABLE contents ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE highlights ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+ALTER TABLE documents ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE TRIGGER trg_contents_updated_at
+    BEFORE UPDATE ON contents
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+CREATE TRIGGER trg_highlights_updated_at
+    BEFORE UPDATE ON highlights
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+CREATE TRIGGER trg_documents_updated_at
+    BEFORE UPDATE ON documents
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE INDEX idx_items_updated_at ON items(updated_at);
+CREATE INDEX idx_contents_updated_at ON contents(updated_at);
+CREATE INDEX idx_highlights_updated_at ON highlights(updated_at);
+CREATE INDEX idx_documents_updated_at ON documents(updated_at);
+
+-- backups written by capsule-backup; each incremental one holds the changes
+-- since the backup before it
+CREATE TABLE backups (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- SHA-256 recorded in the file's trailer
+    checksum TEXT NOT NULL UNIQUE,
+    -- the backup this one builds on; NULL for a full backup
+    previous_checksum TEXT REFERENCES backups(checksum) ON DELETE SET NULL,
+    file_name TEXT NOT NULL,
+    -- changes from `since` (NULL for a full backup) up to `until`
+    since TIMESTAMPTZ,
+    until TIMESTAMPTZ NOT NULL,
+    row_count BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_backups_until ON backups(until DESC);
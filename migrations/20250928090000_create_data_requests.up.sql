@@ -0,0 +1,17 @@
+-- Data packages for subject access requests: everything stored about a
+-- user, generated on an admin's request. Rows are the audit trail of who
+-- asked for whose data and why, so they outlive the admin's account.
+CREATE TABLE data_requests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- whose data; no foreign key, so the record survives the user's deletion
+    subject_id UUID NOT NULL,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reason TEXT NOT NULL,
+    state operation_state NOT NULL DEFAULT 'pending',
+    package JSONB,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_data_requests_subject ON data_requests(subject_id, created_at DESC);
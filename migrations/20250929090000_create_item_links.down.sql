@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS item_links;
+DROP INDEX IF EXISTS idx_items_user_url_hash;
+ALTER TABLE items DROP COLUMN IF EXISTS url_hash;
@@ -0,0 +1,17 @@
+-- Outbound links found in items' content, so items citing each other can be
+-- connected. Links and items are matched on url_hash, the hash of the
+-- normalized URL (see repositories::url_hash); items get theirs filled in as
+-- their owner's content is processed.
+ALTER TABLE items ADD COLUMN url_hash TEXT;
+CREATE INDEX idx_items_user_url_hash ON items(user_id, url_hash);
+
+CREATE TABLE item_links (
+    item_id UUID NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    url_hash TEXT NOT NULL,
+    -- order of the link in the content
+    position INTEGER NOT NULL,
+    PRIMARY KEY (item_id, url_hash)
+);
+
+CREATE INDEX idx_item_links_url_hash ON item_links(url_hash);
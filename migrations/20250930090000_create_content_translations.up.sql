@@ -0,0 +1,14 @@
+-- Machine translations of items' extracted text, one per target language.
+-- They are derived data: dropped with the item and not backed up.
+CREATE TABLE content_translations (
+    item_id UUID NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    -- ISO 639-1 code of the translation
+    lang TEXT NOT NULL,
+    -- language the text was detected as, when known
+    source_lang TEXT,
+    text TEXT NOT NULL,
+    -- name of the provider that translated it
+    provider TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (item_id, lang)
+);
@@ -0,0 +1,4 @@
+ALTER TABLE contents
+    DROP COLUMN IF EXISTS summary,
+    DROP COLUMN IF EXISTS summary_provider,
+    DROP COLUMN IF EXISTS summarized_at;
@@ -0,0 +1,7 @@
+-- A short summary of each item's extracted text, written by the
+-- summarize_content job with whichever summarizer the worker runs
+ALTER TABLE contents
+    ADD COLUMN summary TEXT,
+    -- name of the summarizer that wrote it
+    ADD COLUMN summary_provider TEXT,
+    ADD COLUMN summarized_at TIMESTAMPTZ;
@@ -0,0 +1,24 @@
+-- Embeddings for semantic search. They need the pgvector extension; on
+-- servers without it the table isn't created and semantic search reports
+-- itself unavailable, while everything else works as before.
+--
+-- The column has no fixed dimension so the embedding model can change;
+-- vectors are only compared with those of the same model. Libraries are
+-- searched one user at a time, small enough to scan without an ANN index.
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM pg_available_extensions WHERE name = 'vector') THEN
+        CREATE EXTENSION IF NOT EXISTS vector;
+        CREATE TABLE item_embeddings (
+            item_id UUID PRIMARY KEY REFERENCES items(id) ON DELETE CASCADE,
+            -- the provider's model name
+            model TEXT NOT NULL,
+            embedding vector NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        );
+    END IF;
+EXCEPTION
+    WHEN insufficient_privilege THEN
+        RAISE NOTICE 'pgvector is available but could not be enabled; semantic search is off';
+END
+$$;
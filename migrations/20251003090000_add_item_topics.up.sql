@@ -0,0 +1,6 @@
+-- Broad topics (tech, science, ...) assigned to each item by the
+-- classify_topics job, for filtering lists and searches by topic
+ALTER TABLE items
+    ADD COLUMN topics TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX idx_items_topics ON items USING GIN (topics);
@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS idx_documents_word_count;
+DROP INDEX IF EXISTS idx_contents_word_count;
+
+ALTER TABLE documents
+    DROP COLUMN IF EXISTS word_count;
+
+ALTER TABLE contents
+    DROP COLUMN IF EXISTS word_count;
@@ -0,0 +1,16 @@
+-- Word counts of the extracted text, kept by the database so reading time
+-- filters (quick reads, long reads) can use an index instead of splitting
+-- every item's text per query. An item's text is in `contents`, or in the
+-- shared `documents` row it points at.
+ALTER TABLE contents
+    ADD COLUMN word_count INT GENERATED ALWAYS AS (
+        array_length(regexp_split_to_array(NULLIF(btrim(clean_text), ''), '\s+'), 1)
+    ) STORED;
+
+ALTER TABLE documents
+    ADD COLUMN word_count INT GENERATED ALWAYS AS (
+        array_length(regexp_split_to_array(NULLIF(btrim(clean_text), ''), '\s+'), 1)
+    ) STORED;
+
+CREATE INDEX idx_contents_word_count ON contents(word_count);
+CREATE INDEX idx_documents_word_count ON documents(word_count);
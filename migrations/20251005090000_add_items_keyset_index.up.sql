@@ -0,0 +1,3 @@
+-- Item lists page newest first with a (created_at, id) keyset cursor; this
+-- lets each page seek straight to its cursor instead of skipping rows
+CREATE INDEX idx_items_user_created_id ON items(user_id, created_at DESC, id DESC);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_items_user_favorite;
+
+ALTER TABLE items DROP COLUMN IF EXISTS favorite;
@@ -0,0 +1,4 @@
+-- Items the user starred, to find them again after archiving
+ALTER TABLE items ADD COLUMN favorite BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_items_user_favorite ON items(user_id) WHERE favorite;
@@ -0,0 +1,2 @@
+-- Living documents whose owner is notified when a refresh changes the text
+ALTER TABLE items ADD COLUMN watch_changes BOOLEAN NOT NULL DEFAULT FALSE;
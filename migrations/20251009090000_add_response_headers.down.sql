@@ -0,0 +1,2 @@
+ALTER TABLE fetch_cache DROP COLUMN IF EXISTS response_headers;
+ALTER TABLE contents DROP COLUMN IF EXISTS response_headers;
@@ -0,0 +1,4 @@
+-- Curated response headers of the last fetch (content-type, cache-control,
+-- etag, last-modified, content-language), keyed by lowercase name
+ALTER TABLE contents ADD COLUMN response_headers JSONB;
+ALTER TABLE fetch_cache ADD COLUMN response_headers JSONB;
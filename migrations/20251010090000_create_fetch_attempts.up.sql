@@ -0,0 +1,15 @@
+-- Every fetch of an item's page, successful or not, so support can see why
+-- an item is stuck without reading the job tables
+CREATE TABLE fetch_attempts (
+    id BIGSERIAL PRIMARY KEY,
+    item_id UUID NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    final_url TEXT,
+    status SMALLINT,
+    error_class TEXT,
+    error TEXT,
+    duration_ms INTEGER NOT NULL,
+    bytes BIGINT
+);
+
+CREATE INDEX idx_fetch_attempts_item_id ON fetch_attempts(item_id, id);
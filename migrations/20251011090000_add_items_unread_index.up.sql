@@ -0,0 +1,3 @@
+-- Picking a random unread item seeks into the user's ids from a random
+-- point; archived items are never candidates so they stay out of the index
+CREATE INDEX idx_items_user_unread_id ON items(user_id, id) WHERE status <> 'archived';
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_items_user_reading_time;
+ALTER TABLE items
+    DROP COLUMN IF EXISTS reading_time_minutes,
+    DROP COLUMN IF EXISTS word_count;
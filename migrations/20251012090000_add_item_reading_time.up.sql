@@ -0,0 +1,20 @@
+-- Word count and reading time of an item's extracted text, stored on the
+-- item when extraction finishes so lists and reading time filters don't
+-- have to look at contents or the shared document
+ALTER TABLE items
+    ADD COLUMN word_count INT,
+    ADD COLUMN reading_time_minutes INT;
+
+UPDATE items i
+SET word_count = w.words,
+    reading_time_minutes = CEIL(w.words / 238.0)::int
+FROM (
+    SELECT i2.id, COALESCE(c.word_count, d.word_count) AS words
+    FROM items i2
+    LEFT JOIN contents c ON c.item_id = i2.id
+    LEFT JOIN documents d ON d.id = i2.document_id
+) w
+WHERE w.id = i.id AND w.words IS NOT NULL;
+
+CREATE INDEX idx_items_user_reading_time ON items(user_id, reading_time_minutes)
+    WHERE reading_time_minutes IS NOT NULL;
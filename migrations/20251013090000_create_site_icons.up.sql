@@ -0,0 +1,10 @@
+-- icons of the sites items are saved from, shared by every user; a row with
+-- no data was looked for and not found, or is still being fetched
+CREATE TABLE site_icons (
+    -- normalized like domain_prefs.domain: lowercase, no www.
+    host TEXT PRIMARY KEY,
+    content_type TEXT,
+    data BYTEA,
+    source_url TEXT,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
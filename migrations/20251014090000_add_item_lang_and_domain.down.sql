@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_items_user_domain;
+DROP INDEX IF EXISTS idx_items_user_lang;
+ALTER TABLE items
+    DROP COLUMN IF EXISTS domain,
+    DROP COLUMN IF EXISTS lang;
@@ -0,0 +1,20 @@
+-- Content language and domain stored on the item so list filters can use
+-- an index instead of joining contents or parsing URLs. The language is
+-- copied when extraction finishes; the domain follows the URL, lowercase
+-- and without a leading www. like the site stats
+ALTER TABLE items
+    ADD COLUMN lang VARCHAR(16),
+    ADD COLUMN domain TEXT GENERATED ALWAYS AS (
+        regexp_replace(
+            lower(substring(url from '^[A-Za-z][A-Za-z0-9+.-]*://(?:[^/?#@]*@)?([^/?#:]+)')),
+            '^www\.', ''
+        )
+    ) STORED;
+
+UPDATE items i
+SET lang = lower(c.lang)
+FROM contents c
+WHERE c.item_id = i.id AND c.lang IS NOT NULL;
+
+CREATE INDEX idx_items_user_lang ON items(user_id, lang) WHERE lang IS NOT NULL;
+CREATE INDEX idx_items_user_domain ON items(user_id, domain);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_items_user_reading_time_id;
+DROP INDEX IF EXISTS idx_items_user_title_id;
+DROP INDEX IF EXISTS idx_items_user_updated_id;
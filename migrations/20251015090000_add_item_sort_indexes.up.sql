@@ -0,0 +1,5 @@
+-- one index per item list sort order, each (user_id, sort key, id) so both
+-- directions page by keyset; created_at already has idx_items_user_created_id
+CREATE INDEX idx_items_user_updated_id ON items(user_id, updated_at, id);
+CREATE INDEX idx_items_user_title_id ON items(user_id, left(COALESCE(title, url), 200), id);
+CREATE INDEX idx_items_user_reading_time_id ON items(user_id, COALESCE(reading_time_minutes, 0), id);
@@ -0,0 +1,21 @@
+ALTER TABLE items ADD COLUMN site TEXT;
+
+UPDATE items i
+SET site = s.name
+FROM sites s
+WHERE s.host = i.domain;
+
+CREATE TABLE site_icons (
+    host TEXT PRIMARY KEY,
+    content_type TEXT,
+    data BYTEA,
+    source_url TEXT,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO site_icons (host, content_type, data, source_url, fetched_at)
+SELECT host, icon_content_type, icon_data, icon_source_url, icon_fetched_at
+FROM sites
+WHERE icon_fetched_at IS NOT NULL;
+
+DROP TABLE sites;
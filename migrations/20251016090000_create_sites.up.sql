@@ -0,0 +1,52 @@
+-- the sites items are saved from, shared by every user and keyed like
+-- items.domain: lowercase, no www. Rows are written by the fetch pipeline,
+-- so an item whose page hasn't been fetched may not have one yet.
+CREATE TABLE sites (
+    host TEXT PRIMARY KEY,
+    -- what the site calls itself, e.g. its og:site_name
+    name TEXT,
+    -- RSS or Atom feed its pages link to
+    feed_url TEXT,
+    -- the most specific instance-wide domain rule covering the site
+    domain_rule TEXT REFERENCES domain_rules(domain) ON DELETE SET NULL,
+    -- no icon data means none was found, or it's still being fetched
+    icon_content_type TEXT,
+    icon_data BYTEA,
+    icon_source_url TEXT,
+    icon_fetched_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER trg_sites_updated_at
+    BEFORE UPDATE ON sites
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+-- the name most recently given by any page of the site
+INSERT INTO sites (host, name)
+SELECT DISTINCT ON (domain) domain, site
+FROM items
+WHERE domain IS NOT NULL AND site IS NOT NULL
+ORDER BY domain, updated_at DESC;
+
+INSERT INTO sites (host, icon_content_type, icon_data, icon_source_url, icon_fetched_at)
+SELECT host, content_type, data, source_url, fetched_at
+FROM site_icons
+ON CONFLICT (host) DO UPDATE
+SET icon_content_type = EXCLUDED.icon_content_type,
+    icon_data = EXCLUDED.icon_data,
+    icon_source_url = EXCLUDED.icon_source_url,
+    icon_fetched_at = EXCLUDED.icon_fetched_at;
+
+UPDATE sites s
+SET domain_rule = (
+    SELECT r.domain FROM domain_rules r
+    WHERE s.host = r.domain OR right(s.host, length(r.domain) + 1) = '.' || r.domain
+    ORDER BY length(r.domain) DESC
+    LIMIT 1
+);
+
+DROP TABLE site_icons;
+
+-- items name their site through items.domain
+ALTER TABLE items DROP COLUMN site;
@@ -0,0 +1,14 @@
+-- public read-only links to one item's cleaned article. The link's token is
+-- the row's ID signed with the server secret, so a share stops working as
+-- soon as it's revoked, expires or its item is deleted.
+CREATE TABLE shares (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    item_id UUID NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- no expiry means the link works until it's revoked
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_shares_item ON shares(item_id) WHERE revoked_at IS NULL;
@@ -0,0 +1,13 @@
+-- every time the user opens one of their items, for "recently read" views
+-- and reading statistics. Kept per open rather than per item so repeat
+-- reads show up.
+CREATE TABLE read_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    item_id UUID NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    opened_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- newest first, with the ID breaking ties for keyset pagination
+CREATE INDEX idx_read_events_user_opened ON read_events(user_id, opened_at DESC, id DESC);
+CREATE INDEX idx_read_events_item ON read_events(item_id);
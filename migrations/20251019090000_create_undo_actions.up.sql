@@ -0,0 +1,12 @@
+-- how to reverse a delete, archive or bulk change for a short while after
+-- it was made. The row's ID is the undo token handed to the client, and the
+-- steps are compensating updates applied in reverse order.
+CREATE TABLE undo_actions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    steps JSONB NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_undo_actions_user_expires ON undo_actions(user_id, expires_at);
@@ -0,0 +1,25 @@
+-- a counter of an item's user-facing edits, which clients send back in
+-- If-Match so an edit made against an older copy of the item is refused
+-- instead of overwriting a newer one. Bumped on
+-- every change to what users edit, whoever makes it; fetches that only move
+-- the item through the pipeline leave it alone.
+ALTER TABLE items ADD COLUMN version BIGINT NOT NULL DEFAULT 1;
+
+CREATE OR REPLACE FUNCTION bump_item_version() RETURNS TRIGGER AS $$
+BEGIN
+  NEW.version := OLD.version + 1;
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_items_version
+BEFORE UPDATE ON items
+FOR EACH ROW
+WHEN (
+  OLD.title IS DISTINCT FROM NEW.title
+  OR OLD.status IS DISTINCT FROM NEW.status
+  OR OLD.note IS DISTINCT FROM NEW.note
+  OR OLD.read_progress IS DISTINCT FROM NEW.read_progress
+  OR OLD.favorite IS DISTINCT FROM NEW.favorite
+)
+EXECUTE FUNCTION bump_item_version();
@@ -0,0 +1,3 @@
+-- Postgres can't drop an enum value, so 'reading' stays on item_status;
+-- items being read go back to the backlog
+UPDATE items SET status = 'fetched' WHERE status = 'reading';
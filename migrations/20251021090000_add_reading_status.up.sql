@@ -0,0 +1,3 @@
+-- the article the user is in the middle of, set apart from the fetched
+-- backlog
+ALTER TYPE item_status ADD VALUE IF NOT EXISTS 'reading' AFTER 'fetched';
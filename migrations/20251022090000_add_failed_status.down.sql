@@ -0,0 +1,2 @@
+-- Postgres can't drop an enum value, so 'failed' stays on item_status
+ALTER TABLE items DROP COLUMN IF EXISTS last_error;
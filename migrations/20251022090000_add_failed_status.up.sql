@@ -0,0 +1,9 @@
+-- saves whose page can't be fetched, so users see which ones are broken
+-- instead of waiting on them in 'pending'. Postgres won't use a new enum
+-- value in the transaction that adds it, so existing failures are marked in
+-- the next migration.
+ALTER TYPE item_status ADD VALUE IF NOT EXISTS 'failed' AFTER 'reading';
+
+-- why the last fetch of the item failed for good; cleared by the next
+-- successful one
+ALTER TABLE items ADD COLUMN last_error TEXT;
@@ -0,0 +1 @@
+UPDATE items SET status = 'pending' WHERE status = 'failed';
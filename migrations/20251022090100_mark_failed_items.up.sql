@@ -0,0 +1,11 @@
+-- saves already given up on, with the reason their last fetch failed
+UPDATE items i
+SET status = 'failed',
+    last_error = (
+        SELECT e.detail
+        FROM item_events e
+        WHERE e.item_id = i.id AND e.kind = 'fetch_failed'
+        ORDER BY e.id DESC
+        LIMIT 1
+    )
+WHERE i.status = 'pending' AND i.processing_state = 'failed_permanent';
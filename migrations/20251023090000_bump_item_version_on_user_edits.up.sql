@@ -0,0 +1,7 @@
+-- The trigger also bumped items.version when the fetch pipeline filled in
+-- the title or moved a pending item to fetched or failed, so an edit made
+-- against the version a client got back from its save was refused once the
+-- page had been fetched. The statements behind user edits bump the
+-- version themselves now.
+DROP TRIGGER IF EXISTS trg_items_version ON items;
+DROP FUNCTION IF EXISTS bump_item_version();
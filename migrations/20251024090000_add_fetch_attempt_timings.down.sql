@@ -0,0 +1,5 @@
+ALTER TABLE fetch_attempts
+    DROP COLUMN IF EXISTS dns_ms,
+    DROP COLUMN IF EXISTS connect_ms,
+    DROP COLUMN IF EXISTS ttfb_ms,
+    DROP COLUMN IF EXISTS download_ms;
@@ -0,0 +1,8 @@
+-- Where a fetch spent its time, as recorded on the fetch span. connect_ms
+-- covers the TCP and TLS handshakes together; DNS and connect are only
+-- measured on new connections.
+ALTER TABLE fetch_attempts
+    ADD COLUMN dns_ms INTEGER,
+    ADD COLUMN connect_ms INTEGER,
+    ADD COLUMN ttfb_ms INTEGER,
+    ADD COLUMN download_ms INTEGER;
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::entities::Highlight;
+
+const MAX_QUOTE_CHARS: usize = 10_000;
+pub const MAX_NOTE_CHARS: usize = 50_000;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateHighlightRequest {
+    pub quote: String,
+}
+
+impl CreateHighlightRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.quote.trim().is_empty() {
+            return Err("quote cannot be empty".to_string());
+        }
+        if self.quote.chars().count() > MAX_QUOTE_CHARS {
+            return Err("quote too long".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HighlightResponse {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub quote: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Highlight> for HighlightResponse {
+    fn from(highlight: Highlight) -> Self {
+        Self {
+            id: highlight.id,
+            item_id: highlight.item_id,
+            quote: highlight.quote,
+            created_at: highlight.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HighlightListResponse {
+    pub highlights: Vec<HighlightResponse>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetNoteRequest {
+    /// New note text; null or blank clears the note
+    pub note: Option<String>,
+}
+
+impl SetNoteRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(note) = &self.note
+            && note.chars().count() > MAX_NOTE_CHARS
+        {
+            return Err("note too long".to_string());
+        }
+        Ok(())
+    }
+
+    /// The note to store, treating blank text as no note
+    pub fn normalized_note(&self) -> Option<&str> {
+        self.note
+            .as_deref()
+            .map(str::trim)
+            .filter(|note| !note.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_highlight_request_valid() {
+        let request = CreateHighlightRequest {
+            quote: "The map is not the territory".to_string(),
+        };
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn test_create_highlight_request_blank_quote() {
+        let request = CreateHighlightRequest {
+            quote: " \n ".to_string(),
+        };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_set_note_request_blank_clears_note() {
+        let request = SetNoteRequest {
+            note: Some("   ".to_string()),
+        };
+        assert!(request.validate().is_ok());
+        assert_eq!(request.normalized_note(), None);
+
+        let request = SetNoteRequest {
+            note: Some(" remember this ".to_string()),
+        };
+        assert_eq!(request.normalized_note(), Some("remember this"));
+    }
+
+    #[test]
+    fn test_set_note_request_too_long() {
+        let request = SetNoteRequest {
+            note: Some("x".repeat(MAX_NOTE_CHARS + 1)),
+        };
+        assert!(request.validate().is_err());
+    }
+}
@@ -0,0 +1,203 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header::ETAG},
+    response::{IntoResponse, Response},
+};
+use uuid::Uuid;
+
+use crate::{
+    annotations::dtos::{
+        CreateHighlightRequest, HighlightListResponse, HighlightResponse, SetNoteRequest,
+    },
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    items::{
+        dtos::ItemResponse,
+        etag::{expected_version, item_etag},
+        handlers::edit_conflict,
+    },
+    repositories::{HighlightRepository, ItemEdit},
+};
+
+#[utoipa::path(
+    post,
+    path = "/v1/items/{id}/highlights",
+    tag = "annotations",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    request_body = CreateHighlightRequest,
+    responses(
+        (status = 201, description = "Highlight created successfully", body = HighlightResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_highlight(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<CreateHighlightRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    let repo = HighlightRepository::new(&state.db_pool);
+    match repo
+        .create(auth_user.user_id, item_id, payload.quote.trim())
+        .await
+    {
+        Ok(Some(highlight)) => (
+            StatusCode::CREATED,
+            Json(HighlightResponse::from(highlight)),
+        )
+            .into_response(),
+        Ok(None) => not_found("Item not found"),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/items/{id}/highlights",
+    tag = "annotations",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 200, description = "Highlights retrieved successfully", body = HighlightListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_highlights(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(item_id): Path<Uuid>,
+) -> Response {
+    let repo = HighlightRepository::new(&state.db_pool);
+    match repo.list_for_item(auth_user.user_id, item_id).await {
+        Ok(highlights) => (
+            StatusCode::OK,
+            Json(HighlightListResponse {
+                highlights: highlights
+                    .into_iter()
+                    .map(HighlightResponse::from)
+                    .collect(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/highlights/{id}",
+    tag = "annotations",
+    params(
+        ("id" = Uuid, Path, description = "Highlight ID")
+    ),
+    responses(
+        (status = 204, description = "Highlight deleted"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Highlight not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_highlight(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(highlight_id): Path<Uuid>,
+) -> Response {
+    let repo = HighlightRepository::new(&state.db_pool);
+    match repo.delete(auth_user.user_id, highlight_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => not_found("Highlight not found"),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/items/{id}/note",
+    tag = "annotations",
+    params(
+        ("id" = Uuid, Path, description = "Item ID"),
+        ("If-Match" = Option<String>, Header, description = "ETag of the version the change was made against")
+    ),
+    request_body = SetNoteRequest,
+    responses(
+        (status = 204, description = "Note saved", headers(("etag" = String, description = "Version of the updated item"))),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 409, description = "`If-Match` names an older version; the body is the current item", body = ItemResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_note(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(item_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<SetNoteRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    let repo = HighlightRepository::new(&state.db_pool);
+    match repo
+        .set_note(
+            auth_user.user_id,
+            item_id,
+            payload.normalized_note(),
+            expected_version(&headers),
+        )
+        .await
+    {
+        Ok(ItemEdit::Applied { version }) => {
+            (StatusCode::NO_CONTENT, [(ETAG, item_etag(version))]).into_response()
+        }
+        Ok(ItemEdit::Stale) => edit_conflict(&state, auth_user.user_id, item_id).await,
+        Ok(_) => not_found("Item not found"),
+        Err(_) => database_error(),
+    }
+}
+
+fn not_found(message: &str) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: message.to_string(),
+        }),
+    )
+        .into_response()
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
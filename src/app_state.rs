@@ -1,18 +1,26 @@
-use crate::repositories::{UserRepository, UserRepositoryTrait};
+use crate::{
+    embeddings::EmbeddingProvider,
+    repositories::{ItemRepository, ItemRepositoryTrait, UserRepository, UserRepositoryTrait},
+};
 use sqlx::{Pool, Postgres};
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppState {
     pub user_repo: Arc<dyn UserRepositoryTrait + Send + Sync>,
+    pub item_repo: Arc<dyn ItemRepositoryTrait + Send + Sync>,
     pub db_pool: Pool<Postgres>,
+    /// Embeds search queries for semantic search; None when it's off
+    pub embedder: Option<Arc<dyn EmbeddingProvider>>,
 }
 
 impl AppState {
     pub fn new(pool: Pool<Postgres>) -> Self {
         Self {
             user_repo: Arc::new(UserRepository::new(pool.clone())),
+            item_repo: Arc::new(ItemRepository::new(pool.clone())),
             db_pool: pool,
+            embedder: None,
         }
     }
 }
@@ -1,8 +1,11 @@
+use chrono::{DateTime, Utc};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::sync::LazyLock;
 use utoipa::ToSchema;
 
+use crate::auth::signed_url::{SIGNABLE_PATHS, is_signable};
+
 static EMAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"^[^\s@]+@[^\s@]+\.[^\s@]+$").expect("Failed to compile email regex")
 });
@@ -48,6 +51,56 @@ pub struct LoginResponse {
     pub token: String,
 }
 
+/// Lifetime of a signed URL when the request doesn't choose one
+pub const DEFAULT_SIGNED_URL_TTL_SECS: i64 = 60 * 60;
+/// Longest lifetime a signed URL may have
+pub const MAX_SIGNED_URL_TTL_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateSignedUrlRequest {
+    /// Download to grant GET access to, e.g. `/v1/imports/{id}/report`
+    pub path: String,
+    /// Seconds until the URL stops working; defaults to an hour
+    pub ttl_secs: Option<i64>,
+}
+
+impl CreateSignedUrlRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.path.len() > 2048 {
+            return Err("path too long".to_string());
+        }
+        if self.path.contains(['?', '#']) || self.path.contains("..") {
+            return Err("path must not contain a query, fragment or '..'".to_string());
+        }
+        if !is_signable(&self.path) {
+            return Err(format!(
+                "path must be a download, one of: {}",
+                SIGNABLE_PATHS.join(", ")
+            ));
+        }
+        if let Some(ttl) = self.ttl_secs
+            && !(1..=MAX_SIGNED_URL_TTL_SECS).contains(&ttl)
+        {
+            return Err(format!(
+                "ttl_secs must be between 1 and {}",
+                MAX_SIGNED_URL_TTL_SECS
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn ttl_secs(&self) -> i64 {
+        self.ttl_secs.unwrap_or(DEFAULT_SIGNED_URL_TTL_SECS)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SignedUrlResponse {
+    /// Path and signing query, relative to the API's origin
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
     pub error: String,
@@ -101,4 +154,33 @@ mod tests {
         };
         assert!(request.validate().is_err());
     }
+
+    #[test]
+    fn test_create_signed_url_request_valid() {
+        let request = CreateSignedUrlRequest {
+            path: "/v1/imports/abc/report".to_string(),
+            ttl_secs: None,
+        };
+        assert!(request.validate().is_ok());
+        assert_eq!(request.ttl_secs(), DEFAULT_SIGNED_URL_TTL_SECS);
+    }
+
+    #[test]
+    fn test_create_signed_url_request_invalid() {
+        for (path, ttl_secs) in [
+            ("https://evil.example/v1/x", None),
+            ("/v1/imports/abc/report?user=x", None),
+            ("/v1/../admin", None),
+            ("/v1/items", None),
+            ("/v1/admin/schema", None),
+            ("/v1/imports/abc/report", Some(0)),
+            ("/v1/imports/abc/report", Some(MAX_SIGNED_URL_TTL_SECS + 1)),
+        ] {
+            let request = CreateSignedUrlRequest {
+                path: path.to_string(),
+                ttl_secs,
+            };
+            assert!(request.validate().is_err(), "{path} {ttl_secs:?}");
+        }
+    }
 }
@@ -4,12 +4,18 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
+use chrono::{Duration, Utc};
 
 use crate::{
     app_state::AppState,
     auth::{
-        dtos::{ErrorResponse, LoginRequest, LoginResponse, SignupRequest},
+        dtos::{
+            CreateSignedUrlRequest, ErrorResponse, LoginRequest, LoginResponse, SignedUrlResponse,
+            SignupRequest,
+        },
         jwt::JwtService,
+        middleware::AuthenticatedUser,
+        signed_url::UrlSigner,
     },
     config::Config,
     passwords::Passwords,
@@ -165,10 +171,45 @@ pub async fn login(State(state): State<AppState>, Json(payload): Json<LoginReque
     (StatusCode::OK, Json(LoginResponse { token })).into_response()
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/auth/signed-urls",
+    tag = "auth",
+    request_body = CreateSignedUrlRequest,
+    responses(
+        (status = 201, description = "Signed URL created", body = SignedUrlResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_signed_url(
+    auth_user: AuthenticatedUser,
+    Json(payload): Json<CreateSignedUrlRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    let config = Config::from_env().expect("Failed to load config");
+    let expires_at = Utc::now() + Duration::seconds(payload.ttl_secs());
+    let url =
+        UrlSigner::new(config.jwt_secret()).sign(auth_user.user_id, &payload.path, expires_at);
+
+    (
+        StatusCode::CREATED,
+        Json(SignedUrlResponse { url, expires_at }),
+    )
+        .into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repositories::user::MockUserRepositoryTrait;
+    use crate::repositories::{item::MockItemRepositoryTrait, user::MockUserRepositoryTrait};
     use axum::{body::Body, http::Request};
     use sqlx::{Pool, Postgres};
     use std::sync::Arc;
@@ -188,7 +229,9 @@ mod tests {
 
         let state = AppState {
             user_repo: Arc::new(mock_repo),
+            item_repo: Arc::new(MockItemRepositoryTrait::new()),
             db_pool: create_test_pool(),
+            embedder: None,
         };
 
         let app = axum::Router::new()
@@ -222,7 +265,9 @@ mod tests {
 
         let state = AppState {
             user_repo: Arc::new(mock_repo),
+            item_repo: Arc::new(MockItemRepositoryTrait::new()),
             db_pool: create_test_pool(),
+            embedder: None,
         };
 
         let app = axum::Router::new()
@@ -255,7 +300,9 @@ mod tests {
 
         let state = AppState {
             user_repo: Arc::new(mock_repo),
+            item_repo: Arc::new(MockItemRepositoryTrait::new()),
             db_pool: create_test_pool(),
+            embedder: None,
         };
 
         let app = axum::Router::new()
@@ -8,8 +8,10 @@ use axum::{
 use uuid::Uuid;
 
 use crate::{
+    app_state::AppState,
     auth::{dtos::ErrorResponse, jwt::JwtService},
     config::Config,
+    middleware::signed_url::SignedUrlAccess,
 };
 
 #[derive(Debug, Clone)]
@@ -33,6 +35,8 @@ where
         parts: &mut Parts,
         _state: &S,
     ) -> impl std::future::Future<Output = Result<Self, Self::Rejection>> + Send {
+        // Set by `signed_url_middleware` for requests made through a signed URL
+        let signed_user = parts.extensions.get::<AuthenticatedUser>().cloned();
         let auth_header = parts
             .headers
             .get(AUTHORIZATION)
@@ -40,6 +44,10 @@ where
             .map(|s| s.to_string());
 
         async move {
+            if let Some(user) = signed_user {
+                return Ok(user);
+            }
+
             let auth_header = auth_header.ok_or(AuthError::MissingToken)?;
 
             let token = auth_header
@@ -60,11 +68,47 @@ where
     }
 }
 
+/// An authenticated operator, allowed to change instance-wide settings.
+/// Rejects users without `users.is_admin` with 403, as well as requests
+/// authenticated by a signed URL rather than a bearer token.
+#[derive(Debug, Clone)]
+pub struct AdminUser {
+    pub user_id: Uuid,
+}
+
+impl FromRequestParts<AppState> for AdminUser {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        if parts.extensions.get::<SignedUrlAccess>().is_some() {
+            return Err(AuthError::Forbidden);
+        }
+        let user = AuthenticatedUser::from_request_parts(parts, state).await?;
+
+        let is_admin: Option<bool> =
+            sqlx::query_scalar!("SELECT is_admin FROM users WHERE id = $1", user.user_id)
+                .fetch_optional(&state.db_pool)
+                .await
+                .map_err(|_| AuthError::InternalError)?;
+
+        match is_admin {
+            Some(true) => Ok(AdminUser {
+                user_id: user.user_id,
+            }),
+            _ => Err(AuthError::Forbidden),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum AuthError {
     MissingToken,
     InvalidTokenFormat,
     InvalidToken,
+    Forbidden,
     InternalError,
 }
 
@@ -74,6 +118,7 @@ impl IntoResponse for AuthError {
             AuthError::MissingToken => (StatusCode::UNAUTHORIZED, "Missing authorization token"),
             AuthError::InvalidTokenFormat => (StatusCode::UNAUTHORIZED, "Invalid token format"),
             AuthError::InvalidToken => (StatusCode::UNAUTHORIZED, "Invalid or expired token"),
+            AuthError::Forbidden => (StatusCode::FORBIDDEN, "Admin access required"),
             AuthError::InternalError => {
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
             }
@@ -96,7 +141,11 @@ pub async fn auth_middleware(req: Request, next: Next) -> Response {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{app_state::AppState, config::Config, repositories::user::MockUserRepositoryTrait};
+    use crate::{
+        app_state::AppState,
+        config::Config,
+        repositories::{item::MockItemRepositoryTrait, user::MockUserRepositoryTrait},
+    };
     use axum::{
         Json, Router,
         body::to_bytes,
@@ -122,15 +171,22 @@ mod tests {
         }))
     }
 
+    async fn admin_handler(admin: AdminUser) -> String {
+        admin.user_id.to_string()
+    }
+
     fn create_test_app() -> Router {
         let mock_repo = MockUserRepositoryTrait::new();
         let state = AppState {
             user_repo: Arc::new(mock_repo),
+            item_repo: Arc::new(MockItemRepositoryTrait::new()),
             db_pool: create_test_pool(),
+            embedder: None,
         };
 
         Router::new()
             .route("/protected", get(protected_handler))
+            .route("/admin", get(admin_handler))
             .with_state(state)
     }
 
@@ -279,4 +335,28 @@ mod tests {
         assert_eq!(json["user_id"], user_id.to_string());
         assert_eq!(json["message"], "Access granted");
     }
+
+    #[tokio::test]
+    async fn test_admin_user_refuses_signed_url_access() {
+        let app = create_test_app();
+        let user_id = Uuid::new_v4();
+
+        // As left by `signed_url_middleware`, alongside a valid bearer token
+        let mut request = Request::builder()
+            .method("GET")
+            .uri("/admin")
+            .header(
+                AUTHORIZATION,
+                format!("Bearer {}", create_jwt_token(user_id)),
+            )
+            .body(axum::body::Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(AuthenticatedUser::new(user_id));
+        request.extensions_mut().insert(SignedUrlAccess);
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
 }
@@ -2,3 +2,4 @@ pub mod dtos;
 pub mod handlers;
 pub mod jwt;
 pub mod middleware;
+pub mod signed_url;
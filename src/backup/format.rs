@@ -0,0 +1,338 @@
+//! The backup file format: JSON Lines, one [`Record`] per line. A header
+//! comes first and a trailer last; the trailer holds per-table row counts and
+//! the SHA-256 of every line before it, so a truncated or edited file is
+//! caught before anything is restored from it.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, Write},
+};
+use thiserror::Error;
+
+pub const BACKUP_FORMAT: &str = "capsule-backup";
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("line {line}: {source}")]
+    Malformed {
+        line: usize,
+        source: serde_json::Error,
+    },
+
+    #[error("not a capsule backup")]
+    NotABackup,
+
+    #[error("unsupported backup format version {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("backup is truncated: no trailer")]
+    Truncated,
+
+    #[error("data after the trailer")]
+    TrailingData,
+
+    #[error("checksum mismatch: trailer says {expected}, contents hash to {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+
+    #[error("row count mismatch for {table}: trailer says {expected}, found {actual}")]
+    CountMismatch {
+        table: String,
+        expected: u64,
+        actual: u64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupHeader {
+    pub format: String,
+    pub version: u32,
+    /// Latest migration applied to the database backed up; restores need
+    /// the same schema
+    pub schema_version: i64,
+    /// Start of the changes included; None for a full backup
+    pub since: Option<DateTime<Utc>>,
+    /// When the snapshot was taken
+    pub until: DateTime<Utc>,
+    /// Checksum of the backup this one builds on; None for a full backup
+    pub previous: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupTrailer {
+    /// Rows per table
+    pub counts: BTreeMap<String, u64>,
+    /// Hex SHA-256 of every line before the trailer, newlines included
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Record {
+    Header(BackupHeader),
+    /// A row changed since the previous backup, as a JSON object of its
+    /// columns
+    Row {
+        table: String,
+        row: Value,
+    },
+    /// Keys of rows that exist at the time of the backup, whether changed or
+    /// not; anything else was deleted. Each key is an array of the table's
+    /// key columns.
+    Keys {
+        table: String,
+        keys: Vec<Value>,
+    },
+    Trailer(BackupTrailer),
+}
+
+/// Writes records, keeping the running checksum and row counts
+pub struct BackupWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+    counts: BTreeMap<String, u64>,
+}
+
+impl<W: Write> BackupWriter<W> {
+    pub fn new(inner: W, header: BackupHeader) -> Result<Self, BackupError> {
+        let mut writer = Self {
+            inner,
+            hasher: Sha256::new(),
+            counts: BTreeMap::new(),
+        };
+        writer.write(&Record::Header(header))?;
+        Ok(writer)
+    }
+
+    pub fn row(&mut self, table: &str, row: Value) -> Result<(), BackupError> {
+        *self.counts.entry(table.to_string()).or_default() += 1;
+        self.write(&Record::Row {
+            table: table.to_string(),
+            row,
+        })
+    }
+
+    pub fn keys(&mut self, table: &str, keys: Vec<Value>) -> Result<(), BackupError> {
+        self.counts.entry(table.to_string()).or_default();
+        self.write(&Record::Keys {
+            table: table.to_string(),
+            keys,
+        })
+    }
+
+    /// Write the trailer and flush, returning the backup's checksum and the
+    /// number of rows written
+    pub fn finish(mut self) -> Result<(String, u64), BackupError> {
+        let sha256 = hex::encode(self.hasher.clone().finalize());
+        let rows = self.counts.values().sum();
+        let trailer = Record::Trailer(BackupTrailer {
+            counts: std::mem::take(&mut self.counts),
+            sha256: sha256.clone(),
+        });
+        self.write(&trailer)?;
+        self.inner.flush()?;
+        Ok((sha256, rows))
+    }
+
+    fn write(&mut self, record: &Record) -> Result<(), BackupError> {
+        let mut line = serde_json::to_vec(record).map_err(std::io::Error::from)?;
+        line.push(b'\n');
+        self.hasher.update(&line);
+        self.inner.write_all(&line)?;
+        Ok(())
+    }
+}
+
+/// Check a backup end to end without restoring it: the header, the checksum
+/// and the row counts. Returns the header and trailer.
+pub fn verify(reader: impl BufRead) -> Result<(BackupHeader, BackupTrailer), BackupError> {
+    let mut reader = BackupReader::new(reader);
+    let mut header = None;
+    let mut trailer = None;
+    let mut hasher = Sha256::new();
+    let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+
+    while let Some(record) = reader.next_record()? {
+        if trailer.is_some() {
+            return Err(BackupError::TrailingData);
+        }
+        match record {
+            Record::Header(h) if header.is_none() => header = Some(h),
+            Record::Header(_) => return Err(BackupError::NotABackup),
+            _ if header.is_none() => return Err(BackupError::NotABackup),
+            Record::Row { table, .. } => *counts.entry(table).or_default() += 1,
+            Record::Keys { table, .. } => {
+                counts.entry(table).or_default();
+            }
+            Record::Trailer(t) => {
+                trailer = Some(t);
+                continue;
+            }
+        }
+        hasher.update(reader.line());
+    }
+
+    let header = header.ok_or(BackupError::NotABackup)?;
+    let trailer = trailer.ok_or(BackupError::Truncated)?;
+
+    let actual = hex::encode(hasher.finalize());
+    if actual != trailer.sha256 {
+        return Err(BackupError::ChecksumMismatch {
+            expected: trailer.sha256,
+            actual,
+        });
+    }
+    for table in counts.keys().chain(trailer.counts.keys()) {
+        let expected = trailer.counts.get(table).copied().unwrap_or(0);
+        let actual = counts.get(table).copied().unwrap_or(0);
+        if expected != actual {
+            return Err(BackupError::CountMismatch {
+                table: table.clone(),
+                expected,
+                actual,
+            });
+        }
+    }
+
+    Ok((header, trailer))
+}
+
+/// Reads a backup a record at a time, checking the header's format and
+/// version as it passes
+pub struct BackupReader<R: BufRead> {
+    inner: R,
+    line: Vec<u8>,
+    number: usize,
+}
+
+impl<R: BufRead> BackupReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            line: Vec::new(),
+            number: 0,
+        }
+    }
+
+    /// The next record, or None at the end of the file
+    pub fn next_record(&mut self) -> Result<Option<Record>, BackupError> {
+        self.line.clear();
+        if self.inner.read_until(b'\n', &mut self.line)? == 0 {
+            return Ok(None);
+        }
+        self.number += 1;
+
+        let record: Record = serde_json::from_slice(&self.line).map_err(|source| {
+            if self.number == 1 {
+                BackupError::NotABackup
+            } else {
+                BackupError::Malformed {
+                    line: self.number,
+                    source,
+                }
+            }
+        })?;
+        if let Record::Header(header) = &record {
+            if header.format != BACKUP_FORMAT {
+                return Err(BackupError::NotABackup);
+            }
+            if header.version != BACKUP_FORMAT_VERSION {
+                return Err(BackupError::UnsupportedVersion(header.version));
+            }
+        }
+        Ok(Some(record))
+    }
+
+    /// The raw line of the last record read, newline included
+    pub fn line(&self) -> &[u8] {
+        &self.line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn header() -> BackupHeader {
+        BackupHeader {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_FORMAT_VERSION,
+            schema_version: 20250927090000,
+            since: None,
+            until: Utc::now(),
+            previous: None,
+        }
+    }
+
+    fn backup() -> (Vec<u8>, String) {
+        let mut out = Vec::new();
+        let mut writer = BackupWriter::new(&mut out, header()).unwrap();
+        writer
+            .row("items", json!({"id": "a", "title": "One"}))
+            .unwrap();
+        writer
+            .row("items", json!({"id": "b", "title": "Two"}))
+            .unwrap();
+        writer
+            .keys("items", vec![json!(["a"]), json!(["b"])])
+            .unwrap();
+        writer.keys("highlights", Vec::new()).unwrap();
+        let (checksum, rows) = writer.finish().unwrap();
+        assert_eq!(rows, 2);
+        (out, checksum)
+    }
+
+    #[test]
+    fn test_round_trip_verifies() {
+        let (bytes, checksum) = backup();
+        let (header, trailer) = verify(bytes.as_slice()).unwrap();
+        assert_eq!(header.schema_version, 20250927090000);
+        assert_eq!(trailer.sha256, checksum);
+        assert_eq!(trailer.counts["items"], 2);
+        assert_eq!(trailer.counts["highlights"], 0);
+    }
+
+    #[test]
+    fn test_edited_backup_fails_checksum() {
+        let (bytes, _) = backup();
+        let edited = String::from_utf8(bytes).unwrap().replace("One", "Uno");
+        assert!(matches!(
+            verify(edited.as_bytes()),
+            Err(BackupError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_truncated_backup() {
+        let (bytes, _) = backup();
+        let text = String::from_utf8(bytes).unwrap();
+        let without_trailer: String = text.lines().take(3).map(|l| format!("{l}\n")).collect();
+        assert!(matches!(
+            verify(without_trailer.as_bytes()),
+            Err(BackupError::Truncated)
+        ));
+    }
+
+    #[test]
+    fn test_not_a_backup() {
+        assert!(matches!(
+            verify("id,title\n1,One\n".as_bytes()),
+            Err(BackupError::NotABackup)
+        ));
+        let mut other = header();
+        other.version = 99;
+        let line = serde_json::to_string(&Record::Header(other)).unwrap();
+        assert!(matches!(
+            verify(line.as_bytes()),
+            Err(BackupError::UnsupportedVersion(99))
+        ));
+    }
+}
@@ -0,0 +1,275 @@
+//! Checksummed, incremental backups of everything users have saved, for
+//! self-hosters without Postgres tooling.
+//!
+//! The first backup holds every row; later ones hold the rows changed since
+//! the backup before them plus the keys of every row still present, so a
+//! restore replays a full backup and its incrementals in order and ends up
+//! with deletions applied too. Files are JSON Lines (see [`format`]) with
+//! page HTML decompressed, so they can be inspected with ordinary tools.
+
+pub mod format;
+
+pub use format::{
+    BACKUP_FORMAT, BACKUP_FORMAT_VERSION, BackupError, BackupHeader, BackupReader, BackupTrailer,
+    BackupWriter, Record, verify,
+};
+
+use anyhow::{Context, Result, bail};
+use chrono::Duration;
+use serde_json::Value;
+use sqlx::PgPool;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+use crate::{
+    entities::Backup,
+    repositories::{
+        BACKUP_TABLES, BackupRepository, BackupTable, SchemaRepository, backup_table,
+        decompress_html,
+    },
+};
+
+/// How far each incremental backup reaches back before the previous one
+/// ended, to catch rows written by transactions still open when it was
+/// taken. Rows in the overlap are simply restored twice.
+pub const INCREMENTAL_OVERLAP_MINUTES: i64 = 10;
+
+/// Keys written per line
+const KEYS_PER_RECORD: usize = 10_000;
+
+/// Write a backup into `dir`: the changes since the last recorded backup, or
+/// everything when `full` is set or there's no backup yet. The backup is
+/// recorded once the file is complete.
+pub async fn create(pool: &PgPool, dir: &Path, full: bool) -> Result<Backup> {
+    let backups = BackupRepository::new(pool);
+    let previous = if full { None } else { backups.latest().await? };
+    let schema_version = schema_version(pool).await?;
+
+    let (mut tx, until) = backups.snapshot().await?;
+    let since = previous
+        .as_ref()
+        .map(|backup| backup.until - Duration::minutes(INCREMENTAL_OVERLAP_MINUTES));
+
+    let file_name = format!(
+        "capsule-{}-{}.jsonl",
+        until.format("%Y%m%dT%H%M%S%.6fZ"),
+        if previous.is_some() { "incr" } else { "full" }
+    );
+    let path = dir.join(&file_name);
+    let partial = dir.join(format!("{file_name}.partial"));
+
+    let mut writer = BackupWriter::new(
+        BufWriter::new(File::create(&partial).with_context(|| partial.display().to_string())?),
+        BackupHeader {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_FORMAT_VERSION,
+            schema_version,
+            since,
+            until,
+            previous: previous.as_ref().map(|backup| backup.checksum.clone()),
+        },
+    )?;
+
+    for table in BACKUP_TABLES {
+        BackupRepository::dump_rows(&mut tx, table, since, |row| {
+            writer.row(table.name, portable_row(table.detached, row)?)?;
+            Ok(())
+        })
+        .await?;
+        BackupRepository::dump_keys(&mut tx, table, KEYS_PER_RECORD, |keys| {
+            writer.keys(table.name, keys)?;
+            Ok(())
+        })
+        .await?;
+    }
+    tx.commit().await?;
+
+    let (checksum, rows) = writer.finish()?;
+    // Only complete files get the final name
+    std::fs::rename(&partial, &path)?;
+
+    let backup = backups
+        .record(
+            &checksum,
+            previous.as_ref().map(|backup| backup.checksum.as_str()),
+            &file_name,
+            since,
+            until,
+            rows as i64,
+        )
+        .await?;
+    info!("Wrote backup {} ({} rows)", path.display(), rows);
+    Ok(backup)
+}
+
+/// Restore backups in order, each in its own transaction: a full backup
+/// and the incrementals after it, or incrementals on top of a database that
+/// already holds the backups before them. Every file is verified, and the
+/// chain checked, before anything is written.
+pub async fn restore(pool: &PgPool, paths: &[PathBuf]) -> Result<()> {
+    let schema_version = schema_version(pool).await?;
+
+    let mut previous: Option<String> = None;
+    for path in paths {
+        let (header, trailer) = verify(BufReader::new(open(path)?))
+            .with_context(|| format!("{} failed verification", path.display()))?;
+        if header.schema_version != schema_version {
+            bail!(
+                "{} was taken at schema version {} but the database is at {}",
+                path.display(),
+                header.schema_version,
+                schema_version
+            );
+        }
+        if let Some(previous) = &previous
+            && header.previous.as_ref() != Some(previous)
+        {
+            bail!(
+                "{} doesn't follow the backup before it; restore backups in the order they were taken",
+                path.display()
+            );
+        }
+        previous = Some(trailer.sha256);
+    }
+
+    for path in paths {
+        restore_file(pool, path).await?;
+    }
+    Ok(())
+}
+
+async fn restore_file(pool: &PgPool, path: &Path) -> Result<()> {
+    let mut reader = BackupReader::new(BufReader::new(open(path)?));
+    let mut tx = pool.begin().await?;
+    let mut columns: HashMap<&str, Vec<String>> = HashMap::new();
+    let mut kept: HashMap<&str, Vec<Value>> = HashMap::new();
+    let mut restored = 0u64;
+
+    while let Some(record) = reader.next_record()? {
+        match record {
+            Record::Row { table, row } => {
+                let table = known_table(&table)?;
+                if !columns.contains_key(table.name) {
+                    let names = BackupRepository::columns(&mut tx, table).await?;
+                    columns.insert(table.name, names);
+                }
+                BackupRepository::restore_row(&mut tx, table, &columns[table.name], &row).await?;
+                restored += 1;
+            }
+            Record::Keys { table, keys } => {
+                let table = known_table(&table)?;
+                kept.entry(table.name).or_default().extend(keys);
+            }
+            Record::Header(_) | Record::Trailer(_) => {}
+        }
+    }
+
+    // Children first, so nothing is deleted out from under a cascade
+    let mut deleted = 0;
+    for table in BACKUP_TABLES.iter().rev() {
+        if let Some(keep) = kept.get(table.name) {
+            deleted += BackupRepository::prune(&mut tx, table, keep).await?;
+        }
+    }
+
+    tx.commit().await?;
+    info!(
+        "Restored {}: {} rows written, {} deleted",
+        path.display(),
+        restored,
+        deleted
+    );
+    Ok(())
+}
+
+/// A row as it goes into a backup: page HTML decompressed into the plain
+/// columns, which the `compress_html` job compresses again after a restore,
+/// and references to data outside the backup cleared
+pub fn portable_row(detached: &[&str], mut row: Value) -> Result<Value> {
+    let Some(columns) = row.as_object_mut() else {
+        bail!("expected a row object, got {}", row);
+    };
+
+    for (compressed, plain) in [
+        ("raw_html_zst", "raw_html"),
+        ("clean_html_zst", "clean_html"),
+    ] {
+        if let Some(Value::String(bytea)) = columns.get(compressed) {
+            // Postgres renders bytea in JSON as \x-prefixed hex
+            let bytes = hex::decode(bytea.trim_start_matches("\\x"))
+                .with_context(|| format!("{compressed} is not hex"))?;
+            let html = decompress_html(&bytes)?;
+            columns.insert(plain.to_string(), Value::String(html));
+            columns.insert(compressed.to_string(), Value::Null);
+        }
+    }
+    for column in detached {
+        if let Some(value) = columns.get_mut(*column) {
+            *value = Value::Null;
+        }
+    }
+
+    Ok(row)
+}
+
+/// The latest migration applied to the database
+async fn schema_version(pool: &PgPool) -> Result<i64> {
+    SchemaRepository::new(pool)
+        .applied()
+        .await?
+        .iter()
+        .filter(|migration| migration.success)
+        .map(|migration| migration.version)
+        .max()
+        .context("the database has no migrations applied")
+}
+
+fn known_table(name: &str) -> Result<&'static BackupTable> {
+    backup_table(name).with_context(|| format!("backup names unknown table {name}"))
+}
+
+fn open(path: &Path) -> Result<File> {
+    File::open(path).with_context(|| format!("Failed to open {}", path.display()))
+}
+
+/// Backups taken, newest first
+pub async fn list(pool: &PgPool) -> Result<Vec<Backup>> {
+    BackupRepository::new(pool).list().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::compress_html;
+    use serde_json::json;
+
+    #[test]
+    fn test_portable_row_decompresses_html() {
+        let compressed = compress_html("<p>Hello</p>").unwrap();
+        let row = json!({
+            "item_id": "a",
+            "raw_html": null,
+            "raw_html_zst": format!("\\x{}", hex::encode(&compressed)),
+            "clean_html": "<p>plain</p>",
+            "clean_html_zst": null,
+        });
+
+        let row = portable_row(&[], row).unwrap();
+        assert_eq!(row["raw_html"], "<p>Hello</p>");
+        assert_eq!(row["raw_html_zst"], Value::Null);
+        assert_eq!(row["clean_html"], "<p>plain</p>");
+    }
+
+    #[test]
+    fn test_portable_row_clears_detached_columns() {
+        let row = json!({"id": "a", "import_operation_id": "b", "title": "t"});
+        let row = portable_row(&["import_operation_id"], row).unwrap();
+        assert_eq!(row["import_operation_id"], Value::Null);
+        assert_eq!(row["title"], "t");
+    }
+}
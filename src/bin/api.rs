@@ -1,24 +1,124 @@
 use axum::{
     Router,
-    extract::State,
-    middleware::from_fn_with_state,
-    routing::{get, patch, post},
+    extract::{DefaultBodyLimit, State},
+    middleware::{from_fn, from_fn_with_state},
+    routing::{delete, get, patch, post, put},
 };
 use capsule::{
+    annotations::{
+        self,
+        dtos::{CreateHighlightRequest, HighlightListResponse, HighlightResponse, SetNoteRequest},
+    },
     app_state::AppState,
     auth::{
-        dtos::{ErrorResponse, LoginRequest, LoginResponse, SignupRequest},
+        dtos::{
+            CreateSignedUrlRequest, ErrorResponse, LoginRequest, LoginResponse, SignedUrlResponse,
+            SignupRequest,
+        },
         handlers,
     },
     config,
-    entities::ItemStatus,
-    health, items,
-    items::dtos::{CreateItemRequest, ItemResponse, UpdateItemRequest},
-    middleware::rate_limit::{RateLimit, rate_limit_middleware},
+    data_requests::{
+        self,
+        dtos::{CreateDataRequestRequest, DataRequestListResponse, DataRequestResponse},
+    },
+    domain_prefs::{
+        self,
+        dtos::{DomainPrefListResponse, DomainPrefResponse, UpsertDomainPrefRequest},
+    },
+    domain_rules::{
+        self,
+        dtos::{DomainRuleListResponse, DomainRuleResponse, UpsertDomainRuleRequest},
+    },
+    embeddings,
+    entities::{
+        AbuseEventKind, DigestSchedule, DomainRuleAction, ExtractionFailure, ImportFailureReason,
+        ItemEventKind, ItemStatus, OperationErrorSample, OperationKind, OperationState,
+        ProcessingState, SearchMode, SearchScope, UserPreferences,
+    },
+    extractor::{
+        Heading, TextMap,
+        text_map::{ParagraphSpan, TextSpan},
+    },
+    health,
+    history::{
+        self,
+        dtos::{HistoryEntryResponse, ReadEventResponse},
+    },
+    imports::{
+        self,
+        dtos::{CreateImportRequest, ImportFailureResponse, ImportReportResponse, ReportFormat},
+        history::{HistoryFormat, MAX_HISTORY_BYTES},
+    },
+    items,
+    items::dtos::{
+        BatchGetContentRequest, BatchGetContentResponse, BulkItemResult, BulkItemStatus,
+        BulkItemsRequest, BulkItemsResponse, BulkOperation, CreateItemRequest, ExtractionFilter,
+        FetchAttemptResponse, ItemContentResponse, ItemEventListResponse, ItemEventResponse,
+        ItemLinksResponse, ItemPreviewResponse, ItemResponse, LinkedItemResponse,
+        PreviewItemRequest, RefreshPolicyResponse, RetryExtractionResponse, SetProgressRequest,
+        SetRefreshPolicyRequest, SnoozeItemRequest, SnoozeItemResponse,
+        StateTransitionListResponse, StateTransitionResponse, TriageDecision, TriageRequest,
+        TriageResponse, UpdateItemRequest,
+    },
+    middleware::rate_limit::{
+        RateLimit, RateLimitStatus, RateLimitStatusResponse, rate_limit_middleware,
+        rate_limit_status,
+    },
+    middleware::{
+        signed_url::signed_url_middleware, throttle::save_throttle_middleware,
+        transaction::transaction_middleware,
+    },
+    notifications::{self, dtos::NotificationResponse},
+    operations::{self, dtos::OperationResponse},
+    query::FieldError,
+    scheduling::SnoozePreset,
+    schema::{self, dtos::SchemaResponse},
+    search::{self, dtos::SearchHitResponse},
+    shares::{
+        self,
+        dtos::{CreateShareRequest, RevokeSharesResponse, ShareResponse},
+    },
+    sites::{self, dtos::SiteResponse},
+    stats::{
+        self,
+        dtos::{
+            ItemStatsResponse, LanguageStat, LanguageStatsResponse, SiteStat, SiteStatsResponse,
+            StatusCounts, TagStat,
+        },
+    },
+    tags::{
+        self,
+        dtos::{RenameTagRequest, TagResponse},
+    },
+    throttles::{
+        self,
+        dtos::{
+            AbuseEventListResponse, AbuseEventResponse, OverrideThrottleRequest,
+            ThrottleListResponse, ThrottleResponse,
+        },
+    },
+    topics::{
+        self,
+        dtos::{TopicListResponse, TopicStat},
+    },
+    translation::{
+        self,
+        dtos::{TranslateItemRequest, TranslationRequestedResponse, TranslationResponse},
+    },
+    undo::{self, dtos::UndoResponse},
+    users::{
+        self,
+        dtos::{UpdateProfileRequest, UserProfileResponse},
+    },
+};
+use sqlx::{
+    Pool, Postgres,
+    postgres::{PgConnectOptions, PgPoolOptions},
 };
-use sqlx::{Pool, Postgres, postgres::PgPoolOptions};
-use std::time::Duration;
+use std::{net::SocketAddr, str::FromStr, time::Duration};
 use tower_http::{
+    compression::CompressionLayer,
     request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
     trace::TraceLayer,
 };
@@ -36,28 +136,205 @@ use utoipa_swagger_ui::SwaggerUi;
         health::health_check,
         handlers::signup,
         handlers::login,
+        handlers::create_signed_url,
         items::handlers::list_items,
+        items::handlers::list_quick_reads,
+        items::handlers::list_long_reads,
+        items::handlers::random_item,
+        items::handlers::lookup_item,
         items::handlers::create_item,
+        items::handlers::preview_item,
         items::handlers::get_item,
         items::handlers::update_item,
+        items::handlers::delete_item,
+        items::handlers::bulk_items,
+        items::handlers::triage_items,
+        items::handlers::batch_get_content,
+        items::handlers::snooze_item,
+        items::handlers::set_progress,
+        items::handlers::set_refresh_policy,
+        items::handlers::retry_extraction,
+        items::handlers::list_transitions,
+        items::handlers::list_events,
+        items::handlers::list_links,
+        undo::handlers::undo,
+        translation::handlers::request_translation,
+        translation::handlers::get_translation,
+        capsule::middleware::rate_limit::rate_limit_status,
+        domain_prefs::handlers::list_domain_prefs,
+        domain_prefs::handlers::upsert_domain_pref,
+        domain_prefs::handlers::delete_domain_pref,
+        users::handlers::get_me,
+        users::handlers::update_me,
+        notifications::handlers::list_notifications,
+        notifications::handlers::stream_notifications,
+        annotations::handlers::create_highlight,
+        annotations::handlers::list_highlights,
+        annotations::handlers::delete_highlight,
+        annotations::handlers::set_note,
+        search::handlers::search,
+        history::handlers::record_open,
+        history::handlers::list_history,
+        shares::handlers::create_share,
+        shares::handlers::revoke_shares,
+        shares::handlers::shared_page,
+        sites::handlers::list_sites,
+        sites::handlers::get_site,
+        sites::handlers::site_icon,
+        stats::handlers::language_stats,
+        stats::handlers::site_stats,
+        stats::handlers::item_stats,
+        tags::handlers::rename_tag,
+        topics::handlers::list_topics,
+        operations::handlers::get_operation,
+        imports::handlers::create_import,
+        imports::handlers::import_history,
+        imports::handlers::get_import_report,
+        domain_rules::handlers::list_domain_rules,
+        domain_rules::handlers::upsert_domain_rule,
+        domain_rules::handlers::delete_domain_rule,
+        throttles::handlers::list_throttles,
+        throttles::handlers::override_throttle,
+        throttles::handlers::list_abuse_events,
+        schema::handlers::get_schema,
+        data_requests::handlers::create_data_request,
+        data_requests::handlers::list_data_requests,
+        data_requests::handlers::get_data_request,
+        data_requests::handlers::get_data_package,
     ),
     components(
         schemas(
             health::HealthResponse,
+            health::JobQueueHealth,
             SignupRequest,
             LoginRequest,
             LoginResponse,
+            CreateSignedUrlRequest,
+            SignedUrlResponse,
             ErrorResponse,
+            FieldError,
             CreateItemRequest,
+            PreviewItemRequest,
+            ItemPreviewResponse,
             UpdateItemRequest,
             ItemResponse,
             ItemStatus,
+            ExtractionFailure,
+            ExtractionFilter,
+            RetryExtractionResponse,
+            ProcessingState,
+            StateTransitionResponse,
+            StateTransitionListResponse,
+            ItemEventKind,
+            ItemEventResponse,
+            ItemEventListResponse,
+            FetchAttemptResponse,
+            LinkedItemResponse,
+            ItemLinksResponse,
+            TranslateItemRequest,
+            TranslationRequestedResponse,
+            TranslationResponse,
+            BulkItemsRequest,
+            BulkOperation,
+            BulkItemsResponse,
+            UndoResponse,
+            BulkItemResult,
+            BulkItemStatus,
+            TriageRequest,
+            TriageDecision,
+            TriageResponse,
+            BatchGetContentRequest,
+            BatchGetContentResponse,
+            ItemContentResponse,
+            Heading,
+            TextMap,
+            ParagraphSpan,
+            TextSpan,
+            SnoozeItemRequest,
+            SnoozeItemResponse,
+            SnoozePreset,
+            SetProgressRequest,
+            SetRefreshPolicyRequest,
+            RefreshPolicyResponse,
+            RateLimitStatus,
+            RateLimitStatusResponse,
+            UpsertDomainPrefRequest,
+            DomainPrefResponse,
+            DomainPrefListResponse,
+            UpdateProfileRequest,
+            UserProfileResponse,
+            UserPreferences,
+            DigestSchedule,
+            NotificationResponse,
+            CreateHighlightRequest,
+            HighlightResponse,
+            HighlightListResponse,
+            SetNoteRequest,
+            SearchMode,
+            SearchScope,
+            SearchHitResponse,
+            ReadEventResponse,
+            HistoryEntryResponse,
+            CreateShareRequest,
+            ShareResponse,
+            RevokeSharesResponse,
+            SiteResponse,
+            LanguageStat,
+            LanguageStatsResponse,
+            SiteStat,
+            SiteStatsResponse,
+            ItemStatsResponse,
+            StatusCounts,
+            TagStat,
+            RenameTagRequest,
+            TagResponse,
+            TopicStat,
+            TopicListResponse,
+            OperationResponse,
+            OperationKind,
+            OperationState,
+            OperationErrorSample,
+            CreateImportRequest,
+            HistoryFormat,
+            ReportFormat,
+            ImportReportResponse,
+            ImportFailureResponse,
+            ImportFailureReason,
+            UpsertDomainRuleRequest,
+            DomainRuleResponse,
+            DomainRuleListResponse,
+            DomainRuleAction,
+            OverrideThrottleRequest,
+            ThrottleResponse,
+            ThrottleListResponse,
+            AbuseEventKind,
+            AbuseEventResponse,
+            AbuseEventListResponse,
+            SchemaResponse,
+            CreateDataRequestRequest,
+            DataRequestResponse,
+            DataRequestListResponse,
         )
     ),
     tags(
         (name = "health", description = "Health check endpoints"),
         (name = "auth", description = "Authentication endpoints"),
-        (name = "items", description = "Item management endpoints")
+        (name = "items", description = "Item management endpoints"),
+        (name = "rate-limit", description = "Rate limit introspection endpoints"),
+        (name = "domain-prefs", description = "Per-domain fetch and extraction preferences"),
+        (name = "users", description = "User profile and preference endpoints"),
+        (name = "notifications", description = "Notification events, including quota warnings"),
+        (name = "annotations", description = "Highlights and notes on items"),
+        (name = "search", description = "Full-text search over content and annotations"),
+        (name = "history", description = "Items the user has opened, for recently read views"),
+        (name = "shares", description = "Public, revocable links to items' articles"),
+        (name = "sites", description = "The sites items are saved from, and their icons"),
+        (name = "stats", description = "Library statistics"),
+        (name = "tags", description = "Renaming and merging the user's tags"),
+        (name = "topics", description = "Broad topics assigned to items, for browsing"),
+        (name = "operations", description = "Progress of long-running imports and exports"),
+        (name = "imports", description = "Bulk URL imports and their failed-row reports"),
+        (name = "admin", description = "Instance-wide settings, for admins only")
     ),
     modifiers(&SecurityAddon)
 )]
@@ -89,37 +366,237 @@ async fn main() {
 
     let config = config::Config::from_env().expect("Failed to load configuration");
 
+    let connect_options = PgConnectOptions::from_str(config.database_url())
+        .expect("Invalid database URL")
+        .application_name(config::API_APPLICATION_NAME);
     let pool: Pool<Postgres> = PgPoolOptions::new()
-        .max_connections(5)
+        .max_connections(config.db_max_connections())
         .acquire_timeout(Duration::from_secs(5))
         .idle_timeout(Duration::from_secs(30))
-        .connect(config.database_url())
+        .connect_with(connect_options)
         .await
         .unwrap();
 
-    let app_state = AppState::new(pool);
-    let rate_limit = RateLimit::new(10, 60); // 10 requests per minute
+    schema::migrations::prepare(&pool, config.migration_mode())
+        .await
+        .expect("Database schema is not ready");
+
+    let mut app_state = AppState::new(pool.clone());
+    app_state.embedder = embeddings::provider_from_env().expect("Invalid embedding configuration");
+    let rate_limit = RateLimit::new("auth", 10, 60); // 10 requests per minute
+    // Each preview fetches a page while the client waits
+    let preview_rate_limit = RateLimit::new("preview", 30, 60);
 
     let auth_routes = Router::new()
         .route("/signup", post(handlers::signup))
         .route("/login", post(handlers::login))
-        .layer(from_fn_with_state(rate_limit, rate_limit_middleware));
+        .route("/signed-urls", post(handlers::create_signed_url))
+        .layer(from_fn_with_state(
+            rate_limit.clone(),
+            rate_limit_middleware,
+        ));
+
+    let domain_pref_routes = Router::new()
+        .route("/", get(domain_prefs::handlers::list_domain_prefs))
+        .route(
+            "/{domain}",
+            put(domain_prefs::handlers::upsert_domain_pref)
+                .delete(domain_prefs::handlers::delete_domain_pref),
+        );
+
+    let admin_routes = Router::new()
+        .route(
+            "/domain-rules",
+            get(domain_rules::handlers::list_domain_rules),
+        )
+        .route(
+            "/domain-rules/{domain}",
+            put(domain_rules::handlers::upsert_domain_rule)
+                .delete(domain_rules::handlers::delete_domain_rule),
+        )
+        .route("/throttles", get(throttles::handlers::list_throttles))
+        .route(
+            "/throttles/{user_id}",
+            put(throttles::handlers::override_throttle),
+        )
+        .route(
+            "/throttles/{user_id}/events",
+            get(throttles::handlers::list_abuse_events),
+        )
+        .route("/schema", get(schema::handlers::get_schema))
+        .route(
+            "/users/{user_id}/data-requests",
+            get(data_requests::handlers::list_data_requests),
+        )
+        .route(
+            "/users/{user_id}/data-requests",
+            post(data_requests::handlers::create_data_request)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route(
+            "/data-requests/{id}",
+            get(data_requests::handlers::get_data_request),
+        )
+        .route(
+            "/data-requests/{id}/package",
+            get(data_requests::handlers::get_data_package),
+        );
+
+    let user_routes = Router::new().route(
+        "/me",
+        get(users::handlers::get_me).patch(users::handlers::update_me),
+    );
+
+    let notification_routes = Router::new()
+        .route("/", get(notifications::handlers::list_notifications))
+        .route(
+            "/stream",
+            get(notifications::handlers::stream_notifications),
+        );
+
+    let stats_routes = Router::new()
+        .route("/languages", get(stats::handlers::language_stats))
+        .route("/sites", get(stats::handlers::site_stats));
+
+    let rate_limit_routes = Router::new()
+        .route("/", get(rate_limit_status))
+        .with_state(vec![rate_limit, preview_rate_limit.clone()]);
 
     let item_routes = Router::new()
         .route("/", get(items::handlers::list_items))
-        .route("/", post(items::handlers::create_item))
+        .route(
+            "/",
+            post(items::handlers::create_item)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware))
+                .route_layer(from_fn_with_state(pool.clone(), save_throttle_middleware)),
+        )
+        .route(
+            "/preview",
+            post(items::handlers::preview_item).route_layer(from_fn_with_state(
+                preview_rate_limit,
+                rate_limit_middleware,
+            )),
+        )
+        .route("/quick-reads", get(items::handlers::list_quick_reads))
+        .route("/long-reads", get(items::handlers::list_long_reads))
+        .route("/random", get(items::handlers::random_item))
+        .route("/lookup", get(items::handlers::lookup_item))
         .route("/{id}", get(items::handlers::get_item))
-        .route("/{id}", patch(items::handlers::update_item));
+        .route("/{id}", patch(items::handlers::update_item))
+        .route(
+            "/{id}",
+            delete(items::handlers::delete_item)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route("/{id}/snooze", post(items::handlers::snooze_item))
+        .route(
+            "/{id}/refresh-policy",
+            put(items::handlers::set_refresh_policy),
+        )
+        .route(
+            "/{id}/highlights",
+            get(annotations::handlers::list_highlights)
+                .post(annotations::handlers::create_highlight),
+        )
+        .route("/{id}/note", put(annotations::handlers::set_note))
+        .route("/{id}/progress", put(items::handlers::set_progress))
+        .route("/{id}/transitions", get(items::handlers::list_transitions))
+        .route("/{id}/events", get(items::handlers::list_events))
+        .route("/{id}/links", get(items::handlers::list_links))
+        .route("/{id}/opened", post(history::handlers::record_open))
+        .route(
+            "/{id}/share",
+            post(shares::handlers::create_share).delete(shares::handlers::revoke_shares),
+        )
+        .route(
+            "/{id}/translations",
+            post(translation::handlers::request_translation)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route(
+            "/{id}/translations/{lang}",
+            get(translation::handlers::get_translation),
+        )
+        .route(
+            "/{id}/extraction:retry",
+            post(items::handlers::retry_extraction)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route(
+            "/bulk",
+            post(items::handlers::bulk_items)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route(
+            "/triage",
+            post(items::handlers::triage_items)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route("/stats", get(stats::handlers::item_stats))
+        .route(
+            "/content:batchGet",
+            post(items::handlers::batch_get_content),
+        );
 
     let app = Router::new()
         .route("/", get(root))
         .route("/healthz", get(health::health_check))
         .nest("/v1/auth", auth_routes)
         .nest("/v1/items", item_routes)
+        .nest("/v1/domain-prefs", domain_pref_routes)
+        .nest("/v1/users", user_routes)
+        .nest("/v1/notifications", notification_routes)
+        .route(
+            "/v1/highlights/{id}",
+            delete(annotations::handlers::delete_highlight),
+        )
+        .route("/v1/search", get(search::handlers::search))
+        .route("/v1/history", get(history::handlers::list_history))
+        .route("/shared/{token}", get(shares::handlers::shared_page))
+        .route("/v1/sites", get(sites::handlers::list_sites))
+        .route("/v1/sites/{host}", get(sites::handlers::get_site))
+        .route("/v1/sites/{host}/icon", get(sites::handlers::site_icon))
+        .nest("/v1/stats", stats_routes)
+        .route(
+            "/v1/tags/{id}",
+            patch(tags::handlers::rename_tag)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route("/v1/topics", get(topics::handlers::list_topics))
+        .route(
+            "/v1/undo/{token}",
+            post(undo::handlers::undo)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route(
+            "/v1/operations/{id}",
+            get(operations::handlers::get_operation),
+        )
+        .route(
+            "/v1/imports",
+            post(imports::handlers::create_import)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware))
+                .route_layer(from_fn_with_state(pool.clone(), save_throttle_middleware)),
+        )
+        .route(
+            "/v1/imports/history",
+            post(imports::handlers::import_history)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware))
+                .route_layer(from_fn_with_state(pool.clone(), save_throttle_middleware))
+                .layer(DefaultBodyLimit::max(MAX_HISTORY_BYTES)),
+        )
+        .route(
+            "/v1/imports/{id}/report",
+            get(imports::handlers::get_import_report),
+        )
+        .nest("/v1/rate-limit", rate_limit_routes)
+        .nest("/v1/admin", admin_routes)
         .merge(SwaggerUi::new("/docs").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        .layer(from_fn(signed_url_middleware))
         .layer(PropagateRequestIdLayer::x_request_id())
         .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
         .layer(TraceLayer::new_for_http())
+        .layer(CompressionLayer::new())
         .with_state(app_state);
 
     let listener = tokio::net::TcpListener::bind(config.bind_addr())
@@ -127,7 +604,12 @@ async fn main() {
         .expect("Failed to bind to address");
 
     info!("Server starting on {}", config.bind_addr());
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
 
 async fn root(State(_state): State<AppState>) -> &'static str {
@@ -0,0 +1,86 @@
+use anyhow::{Context, Result, bail};
+use capsule::backup;
+use sqlx::{Pool, Postgres, postgres::PgPoolOptions};
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+const USAGE: &str = "\
+usage: capsule-backup create [--full] DIR
+       capsule-backup verify FILE...
+       capsule-backup restore FILE...
+       capsule-backup list";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, rest)) = args.split_first() else {
+        bail!(USAGE);
+    };
+
+    match command.as_str() {
+        "create" => {
+            let full = rest.iter().any(|arg| arg == "--full");
+            let dirs: Vec<&String> = rest.iter().filter(|arg| *arg != "--full").collect();
+            let [dir] = dirs.as_slice() else {
+                bail!(USAGE);
+            };
+            let pool = connect().await?;
+            let backup = backup::create(&pool, &PathBuf::from(dir), full).await?;
+            println!("{}\t{}", backup.file_name, backup.checksum);
+        }
+        "verify" => {
+            if rest.is_empty() {
+                bail!(USAGE);
+            }
+            for path in rest {
+                let file = File::open(path).with_context(|| format!("Failed to open {path}"))?;
+                let (header, trailer) = backup::verify(BufReader::new(file))
+                    .with_context(|| format!("{path} failed verification"))?;
+                let rows: u64 = trailer.counts.values().sum();
+                println!(
+                    "{path}: ok, {rows} rows, {} backup taken {}",
+                    if header.previous.is_some() {
+                        "incremental"
+                    } else {
+                        "full"
+                    },
+                    header.until
+                );
+            }
+        }
+        "restore" => {
+            if rest.is_empty() {
+                bail!(USAGE);
+            }
+            let paths: Vec<PathBuf> = rest.iter().map(PathBuf::from).collect();
+            let pool = connect().await?;
+            backup::restore(&pool, &paths).await?;
+        }
+        "list" => {
+            let pool = connect().await?;
+            for backup in backup::list(&pool).await? {
+                println!(
+                    "{}\t{}\t{} rows\t{}",
+                    backup.until, backup.file_name, backup.row_count, backup.checksum
+                );
+            }
+        }
+        _ => bail!(USAGE),
+    }
+
+    Ok(())
+}
+
+async fn connect() -> Result<Pool<Postgres>> {
+    let db_url =
+        std::env::var("DATABASE_URL").context("DATABASE_URL environment variable not set")?;
+
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&db_url)
+        .await?;
+    Ok(pool)
+}
@@ -0,0 +1,307 @@
+use anyhow::{Context, Result, bail};
+use capsule::{
+    config::{API_APPLICATION_NAME, Config},
+    loadtest::{LatencySummary, Mix, Operation, PoolSample, PoolUsage},
+};
+use rand::Rng;
+use reqwest::{Client, StatusCode};
+use serde_json::{Value, json};
+use sqlx::{PgPool, postgres::PgPoolOptions};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::time::interval;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+const USAGE: &str = "usage: capsule-bench [--url URL] [--duration SECS] [--concurrency N] \
+                     [--mix save=1,list=6,search=3]";
+
+/// Words searched for, common enough in saved articles to find something
+const SEARCH_TERMS: &[&str] = &["software", "database", "city", "outage", "rust", "design"];
+
+/// How often the API's connections are counted
+const POOL_SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+struct Options {
+    url: String,
+    duration: Duration,
+    concurrency: usize,
+    mix: Mix,
+}
+
+/// What one operation's requests came to
+#[derive(Default)]
+struct Tally {
+    /// Of the successful requests
+    latencies: Vec<Duration>,
+    errors: usize,
+    /// Refused with 429 by the save throttle or a rate limit
+    throttled: usize,
+}
+
+type Tallies = Arc<Mutex<HashMap<Operation, Tally>>>;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let options = parse_args(std::env::args().skip(1).collect())?;
+    // The instance under test is assumed to share this environment's setting
+    let pool_size = Config::from_env()?.db_max_connections() as i64;
+
+    let client = Client::builder().timeout(Duration::from_secs(30)).build()?;
+    let token = sign_up(&client, &options.url).await?;
+
+    // Pool use is only visible from the database side
+    let sampler = match std::env::var("DATABASE_URL") {
+        Ok(db_url) => {
+            let db = PgPoolOptions::new()
+                .max_connections(1)
+                .connect(&db_url)
+                .await?;
+            Some(db)
+        }
+        Err(_) => None,
+    };
+    let stop = CancellationToken::new();
+    let samples = Arc::new(Mutex::new(Vec::new()));
+    let sampler_handle =
+        sampler.map(|db| tokio::spawn(sample_pool(db, samples.clone(), stop.clone())));
+
+    println!(
+        "Running {}s of load with {} clients against {}",
+        options.duration.as_secs(),
+        options.concurrency,
+        options.url
+    );
+    let tallies: Tallies = Arc::new(Mutex::new(HashMap::new()));
+    let started = Instant::now();
+    let deadline = started + options.duration;
+    let mut clients = Vec::new();
+    for _ in 0..options.concurrency {
+        clients.push(tokio::spawn(run_client(
+            client.clone(),
+            options.url.clone(),
+            token.clone(),
+            options.mix,
+            deadline,
+            tallies.clone(),
+        )));
+    }
+    for handle in clients {
+        handle.await?;
+    }
+    let elapsed = started.elapsed();
+    stop.cancel();
+    if let Some(handle) = sampler_handle {
+        handle.await??;
+    }
+
+    report(
+        &tallies.lock().unwrap(),
+        elapsed,
+        &samples.lock().unwrap(),
+        pool_size,
+    );
+    Ok(())
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options> {
+    let mut options = Options {
+        url: "http://127.0.0.1:8080".to_string(),
+        duration: Duration::from_secs(30),
+        concurrency: 16,
+        mix: Mix::default(),
+    };
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        let Some(value) = args.next() else {
+            bail!(USAGE);
+        };
+        match flag.as_str() {
+            "--url" => options.url = value.trim_end_matches('/').to_string(),
+            "--duration" => options.duration = Duration::from_secs(value.parse()?),
+            "--concurrency" => options.concurrency = value.parse()?,
+            "--mix" => options.mix = Mix::parse(&value)?,
+            _ => bail!(USAGE),
+        }
+    }
+    if options.concurrency == 0 || options.duration.is_zero() {
+        bail!(USAGE);
+    }
+    Ok(options)
+}
+
+/// Sign up a throwaway user for the run and log them in
+async fn sign_up(client: &Client, url: &str) -> Result<String> {
+    let credentials = json!({
+        "email": format!("bench-{}@capsule.local", Uuid::new_v4().simple()),
+        "password": Uuid::new_v4().to_string(),
+    });
+    client
+        .post(format!("{}/v1/auth/signup", url))
+        .json(&credentials)
+        .send()
+        .await?
+        .error_for_status()
+        .context("signing up the bench user")?;
+    let login: Value = client
+        .post(format!("{}/v1/auth/login", url))
+        .json(&credentials)
+        .send()
+        .await?
+        .error_for_status()
+        .context("logging in the bench user")?
+        .json()
+        .await?;
+    login["token"]
+        .as_str()
+        .map(str::to_string)
+        .context("login response has no token")
+}
+
+/// Send requests one after another until `deadline`
+async fn run_client(
+    client: Client,
+    url: String,
+    token: String,
+    mix: Mix,
+    deadline: Instant,
+    tallies: Tallies,
+) {
+    while Instant::now() < deadline {
+        let operation = mix.pick(rand::thread_rng().gen_range(0..mix.total()));
+        let request = match operation {
+            // A host of its own per save, so the fetches failing to reach it
+            // don't add up to a throttle for a single failing domain
+            Operation::Save => client.post(format!("{}/v1/items", url)).json(&json!({
+                "url": format!("https://bench-{}.example/article", Uuid::new_v4().simple()),
+                "tags": ["bench"],
+            })),
+            Operation::List => client.get(format!("{}/v1/items?limit=20", url)),
+            Operation::Search => {
+                let term = SEARCH_TERMS[rand::thread_rng().gen_range(0..SEARCH_TERMS.len())];
+                client.get(format!("{}/v1/search?q={}", url, term))
+            }
+        };
+
+        let sent = Instant::now();
+        let status = request.bearer_auth(&token).send().await.map(|r| r.status());
+        let latency = sent.elapsed();
+
+        let mut tallies = tallies.lock().unwrap();
+        let tally = tallies.entry(operation).or_default();
+        match status {
+            Ok(status) if status.is_success() => tally.latencies.push(latency),
+            Ok(StatusCode::TOO_MANY_REQUESTS) => tally.throttled += 1,
+            _ => tally.errors += 1,
+        }
+    }
+}
+
+/// Count the API's open and busy connections until stopped
+async fn sample_pool(
+    db: PgPool,
+    samples: Arc<Mutex<Vec<PoolSample>>>,
+    stop: CancellationToken,
+) -> Result<()> {
+    let mut ticks = interval(POOL_SAMPLE_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = stop.cancelled() => return Ok(()),
+            _ = ticks.tick() => {
+                let counts = sqlx::query!(
+                    r#"
+                    SELECT COUNT(*) AS "open!", COUNT(*) FILTER (WHERE state = 'active') AS "active!"
+                    FROM pg_stat_activity
+                    WHERE application_name = $1 AND datname = current_database()
+                    "#,
+                    API_APPLICATION_NAME
+                )
+                .fetch_one(&db)
+                .await?;
+                samples.lock().unwrap().push(PoolSample {
+                    open: counts.open,
+                    active: counts.active,
+                });
+            }
+        }
+    }
+}
+
+fn report(
+    tallies: &HashMap<Operation, Tally>,
+    elapsed: Duration,
+    samples: &[PoolSample],
+    pool_size: i64,
+) {
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    println!();
+    println!(
+        "{:<8} {:>8} {:>8} {:>7} {:>9} {:>9} {:>9} {:>9} {:>9}",
+        "op", "ok", "req/s", "errors", "throttled", "p50 ms", "p90 ms", "p99 ms", "max ms"
+    );
+    let mut throttled = 0;
+    for operation in Operation::ALL {
+        let Some(tally) = tallies.get(&operation) else {
+            continue;
+        };
+        throttled += tally.throttled;
+        let requests = tally.latencies.len() + tally.errors + tally.throttled;
+        let rps = requests as f64 / elapsed.as_secs_f64();
+        match LatencySummary::from_samples(&tally.latencies) {
+            Some(summary) => println!(
+                "{:<8} {:>8} {:>8.1} {:>7} {:>9} {:>9.1} {:>9.1} {:>9.1} {:>9.1}",
+                operation.as_str(),
+                summary.count,
+                rps,
+                tally.errors,
+                tally.throttled,
+                ms(summary.p50),
+                ms(summary.p90),
+                ms(summary.p99),
+                ms(summary.max)
+            ),
+            None => println!(
+                "{:<8} {:>8} {:>8.1} {:>7} {:>9}",
+                operation.as_str(),
+                0,
+                rps,
+                tally.errors,
+                tally.throttled
+            ),
+        }
+    }
+    if throttled > 0 {
+        println!(
+            "\n{} requests were throttled; raise ABUSE_MAX_SAVES_PER_MINUTE on the instance \
+             under test or lower the save weight.",
+            throttled
+        );
+    }
+
+    println!();
+    match PoolUsage::from_samples(samples, pool_size) {
+        Some(usage) => {
+            println!(
+                "API connection pool ({} samples): size {}, peak open {}, peak busy {}, \
+                 mean busy {:.1}, saturated {:.0}% of the time",
+                usage.samples,
+                usage.pool_size,
+                usage.peak_open,
+                usage.peak_active,
+                usage.mean_active,
+                usage.saturated * 100.0
+            );
+            println!("{}", usage.advice());
+        }
+        None => println!(
+            "Set DATABASE_URL to the instance's database to report connection pool saturation."
+        ),
+    }
+}
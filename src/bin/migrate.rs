@@ -1,3 +1,4 @@
+use capsule::schema::migrations::MIGRATOR;
 use sqlx::{Pool, Postgres, postgres::PgPoolOptions};
 
 #[tokio::main]
@@ -10,7 +11,7 @@ async fn main() -> anyhow::Result<()> {
         .await?;
 
     // runs all pending migrations; no-op if up-to-date
-    sqlx::migrate!("./migrations").run(&pool).await?;
+    MIGRATOR.run(&pool).await?;
 
     Ok(())
 }
@@ -0,0 +1,33 @@
+use anyhow::{Context, Result, bail};
+use capsule::seed::{self, DEMO_EMAIL, DEMO_PASSWORD};
+use sqlx::postgres::PgPoolOptions;
+
+const USAGE: &str = "usage: capsule-seed [--reset]";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let reset = match args.as_slice() {
+        [] => false,
+        [flag] if flag == "--reset" => true,
+        _ => bail!(USAGE),
+    };
+
+    let db_url =
+        std::env::var("DATABASE_URL").context("DATABASE_URL environment variable not set")?;
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&db_url)
+        .await?;
+
+    let seeded = seed::seed(&pool, reset).await?;
+    println!(
+        "Seeded {} items for {} (password {}, user {})",
+        seeded.items, DEMO_EMAIL, DEMO_PASSWORD, seeded.user_id
+    );
+    Ok(())
+}
@@ -1,8 +1,26 @@
 use anyhow::Result;
 use capsule::{
     config::Config,
-    jobs::{ExampleJobHandler, FetchPageJobHandler, JobRegistry, WorkerConfig, WorkerSupervisor},
+    embeddings,
+    extractor::SanitizePolicy,
+    jobs::{
+        BuildDataPackageJobHandler, COMPRESS_HTML_JOB_KIND, ClassifyTopicsJobHandler,
+        CompressHtmlJobHandler, EmbedContentJobHandler, ExampleJobHandler, FetchPageConfig,
+        FetchPageJobHandler, FetchSiteIconJobHandler, FetchTitleJobHandler, ImportUrlsJobHandler,
+        JobRegistry, JobRepository, QUEUE_HEALTH_JOB_KIND, QUOTA_CHECK_JOB_KIND, QueueHealthConfig,
+        QueueHealthJobHandler, QuotaCheckJobHandler, QuotaConfig, REFRESH_SCAN_JOB_KIND,
+        RefreshConfig, RefreshItemJobHandler, RefreshScanJobHandler, SendDigestJobHandler,
+        SummarizeContentJobHandler, TranslateContentJobHandler, WorkerConfig, WorkerSupervisor,
+    },
+    schema::migrations::prepare,
+    summarizer::{
+        ChatCompletionSummarizer, ExtractiveSummarizer, Summarizer,
+        chat::{DEFAULT_CHAT_COMPLETIONS_URL, DEFAULT_CHAT_MODEL},
+    },
+    topics,
+    translation::{LibreTranslate, TranslationProvider},
 };
+use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -20,15 +38,187 @@ async fn main() -> Result<()> {
         .connect(config.database_url())
         .await?;
 
-    // Run migrations
-    sqlx::migrate!("./migrations").run(&pool).await?;
+    // Run or verify migrations, as configured
+    prepare(&pool, config.migration_mode()).await?;
 
     // Create job registry and register handlers
     let mut registry = JobRegistry::new();
     registry.register(ExampleJobHandler);
-    registry.register(FetchPageJobHandler::new());
+    let mut sanitize_policy = SanitizePolicy {
+        extra_tags: env_list("SANITIZE_EXTRA_TAGS").unwrap_or_default(),
+        removed_tags: env_list("SANITIZE_REMOVED_TAGS").unwrap_or_default(),
+        // Pairs are written as tag:attribute, e.g. "img:loading,td:colspan"
+        extra_attributes: env_list("SANITIZE_EXTRA_ATTRIBUTES")
+            .unwrap_or_default()
+            .into_iter()
+            .map(|pair| match pair.split_once(':') {
+                Some((tag, attribute))
+                    if !tag.trim().is_empty() && !attribute.trim().is_empty() =>
+                {
+                    Ok((tag.trim().to_string(), attribute.trim().to_string()))
+                }
+                _ => Err(anyhow::anyhow!(
+                    "Invalid SANITIZE_EXTRA_ATTRIBUTES entry {:?}: expected tag:attribute",
+                    pair
+                )),
+            })
+            .collect::<Result<_>>()?,
+        url_schemes: env_list("SANITIZE_URL_SCHEMES"),
+        iframe_hosts: env_list("SANITIZE_IFRAME_HOSTS").unwrap_or_default(),
+        ..SanitizePolicy::default()
+    };
+    if std::env::var("SANITIZE_EMBED_PLACEHOLDERS")
+        .ok()
+        .and_then(|v| v.parse::<bool>().ok())
+        == Some(false)
+    {
+        sanitize_policy.embed_providers.clear();
+    }
+    sanitize_policy
+        .validate()
+        .map_err(|e| anyhow::anyhow!("Invalid sanitize policy: {}", e))?;
+
+    let defaults = FetchPageConfig::default();
+    let fetch_page_config = FetchPageConfig {
+        cache_ttl_secs: std::env::var("FETCH_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.cache_ttl_secs),
+        sanitize_policy: sanitize_policy.clone(),
+        flag_nsfw: std::env::var("NSFW_FLAGGING")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.flag_nsfw),
+    };
+    let flag_nsfw = fetch_page_config.flag_nsfw;
+    registry.register(FetchPageJobHandler::with_config(fetch_page_config));
+    registry.register(FetchTitleJobHandler::new());
+    registry.register(FetchSiteIconJobHandler::new());
+    registry.register(SendDigestJobHandler::new());
+    registry.register(ImportUrlsJobHandler::new());
+
+    let defaults = QuotaConfig::default();
+    let quota_config = QuotaConfig {
+        max_items: std::env::var("QUOTA_MAX_ITEMS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.max_items),
+        max_storage_bytes: std::env::var("QUOTA_MAX_STORAGE_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.max_storage_bytes),
+        check_interval_secs: std::env::var("QUOTA_CHECK_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.check_interval_secs),
+        email_notifications: std::env::var("QUOTA_EMAIL_NOTIFICATIONS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.email_notifications),
+    };
+    registry.register(QuotaCheckJobHandler::new(quota_config));
+
+    let defaults = QueueHealthConfig::default();
+    let queue_health_config = QueueHealthConfig {
+        check_interval_secs: std::env::var("QUEUE_HEALTH_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.check_interval_secs),
+        email_notifications: std::env::var("QUEUE_HEALTH_EMAIL_NOTIFICATIONS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.email_notifications),
+    };
+    registry.register(QueueHealthJobHandler::new(queue_health_config));
+
+    let defaults = RefreshConfig::default();
+    let refresh_config = RefreshConfig {
+        scan_interval_secs: std::env::var("REFRESH_SCAN_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.scan_interval_secs),
+        batch_size: std::env::var("REFRESH_BATCH_SIZE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.batch_size),
+        max_per_domain: std::env::var("REFRESH_MAX_PER_DOMAIN")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.max_per_domain),
+        domain_spacing_secs: std::env::var("REFRESH_DOMAIN_SPACING_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.domain_spacing_secs),
+        email_notifications: std::env::var("REFRESH_EMAIL_NOTIFICATIONS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.email_notifications),
+    };
+    registry.register(RefreshItemJobHandler::new(
+        sanitize_policy,
+        refresh_config.email_notifications,
+        flag_nsfw,
+    ));
+    registry.register(RefreshScanJobHandler::new(refresh_config));
+    registry.register(CompressHtmlJobHandler::new());
+    registry.register(BuildDataPackageJobHandler::new());
+
+    // Translation is off unless a provider is configured
+    let translation_provider = match std::env::var("LIBRETRANSLATE_URL") {
+        Ok(url) => Some(Arc::new(LibreTranslate::new(
+            &url,
+            std::env::var("LIBRETRANSLATE_API_KEY").ok(),
+        )?) as Arc<dyn TranslationProvider>),
+        Err(_) => None,
+    };
+    registry.register(TranslateContentJobHandler::new(translation_provider));
+
+    // Summaries come from a language model when one is configured, and are
+    // extracted locally otherwise
+    let summarizer: Arc<dyn Summarizer> = match std::env::var("SUMMARY_LLM_API_KEY") {
+        Ok(api_key) => Arc::new(ChatCompletionSummarizer::new(
+            &std::env::var("SUMMARY_LLM_URL")
+                .unwrap_or_else(|_| DEFAULT_CHAT_COMPLETIONS_URL.to_string()),
+            api_key,
+            std::env::var("SUMMARY_LLM_MODEL").unwrap_or_else(|_| DEFAULT_CHAT_MODEL.to_string()),
+        )?),
+        Err(_) => Arc::new(ExtractiveSummarizer),
+    };
+    registry.register(SummarizeContentJobHandler::with_summarizer(summarizer));
+    registry.register(EmbedContentJobHandler::new(embeddings::provider_from_env()?));
+    registry.register(ClassifyTopicsJobHandler::with_classifier(
+        topics::classifier_from_env()?,
+    ));
+
+    // Periodic jobs reschedule themselves; make sure a run of each is queued,
+    // along with a pass of the HTML compression backfill
+    for kind in [
+        QUOTA_CHECK_JOB_KIND,
+        QUEUE_HEALTH_JOB_KIND,
+        REFRESH_SCAN_JOB_KIND,
+        COMPRESS_HTML_JOB_KIND,
+    ] {
+        JobRepository::enqueue_if_absent(&pool, kind, serde_json::json!({}), None).await?;
+    }
+
+    // Telemetry is only ever sent when built with the feature and pointed at
+    // an endpoint
+    #[cfg(feature = "telemetry")]
+    if let Some(telemetry_config) = capsule::telemetry::TelemetryConfig::from_env()? {
+        registry.register(capsule::jobs::TelemetryReportJobHandler::new(
+            telemetry_config,
+        )?);
+        JobRepository::enqueue_if_absent(
+            &pool,
+            capsule::jobs::TELEMETRY_REPORT_JOB_KIND,
+            serde_json::json!({}),
+            None,
+        )
+        .await?;
+    }
 
     // Create worker configuration
+    let defaults = WorkerConfig::default();
     let worker_config = WorkerConfig {
         concurrency: std::env::var("WORKER_CONCURRENCY")
             .ok()
@@ -46,9 +236,34 @@ async fn main() -> Result<()> {
             .ok()
             .and_then(|v| v.parse().ok())
             .unwrap_or(30),
+        outbox_relay_interval_ms: std::env::var("WORKER_OUTBOX_RELAY_INTERVAL_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.outbox_relay_interval_ms),
+        outbox_batch_size: std::env::var("WORKER_OUTBOX_BATCH_SIZE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.outbox_batch_size),
+        max_consecutive_panics: std::env::var("WORKER_MAX_CONSECUTIVE_PANICS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.max_consecutive_panics),
+        chaos: None,
     };
 
     // Create and run supervisor
     let supervisor = WorkerSupervisor::new(pool, registry, worker_config);
     supervisor.run().await
 }
+
+/// Comma-separated values of an environment variable, if it's set
+fn env_list(name: &str) -> Option<Vec<String>> {
+    std::env::var(name).ok().map(|value| {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    })
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::entities::{DataRequest, OperationState};
+
+/// Longest reason accepted for a request
+pub const MAX_DATA_REQUEST_REASON_LEN: usize = 1000;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateDataRequestRequest {
+    /// Why the data is needed, e.g. the ticket of the subject access request;
+    /// kept with the audit trail
+    pub reason: String,
+}
+
+impl CreateDataRequestRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.reason.trim().is_empty() {
+            return Err("reason must not be empty".to_string());
+        }
+        if self.reason.chars().count() > MAX_DATA_REQUEST_REASON_LEN {
+            return Err(format!(
+                "reason must be at most {} characters",
+                MAX_DATA_REQUEST_REASON_LEN
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DataRequestResponse {
+    pub id: Uuid,
+    /// The user whose data is packaged
+    pub subject_id: Uuid,
+    /// The admin who asked for it; null once their account is deleted
+    pub requested_by: Option<Uuid>,
+    pub reason: String,
+    pub state: OperationState,
+    /// Why the package couldn't be built
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DataRequestListResponse {
+    pub requests: Vec<DataRequestResponse>,
+}
+
+impl From<DataRequest> for DataRequestResponse {
+    fn from(request: DataRequest) -> Self {
+        Self {
+            id: request.id,
+            subject_id: request.subject_id,
+            requested_by: request.requested_by,
+            reason: request.reason,
+            state: request.state,
+            error: request.error,
+            created_at: request.created_at,
+            finished_at: request.finished_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reason_is_required() {
+        let request = |reason: &str| CreateDataRequestRequest {
+            reason: reason.to_string(),
+        };
+        assert!(request("SAR ticket 1234").validate().is_ok());
+        assert!(request("  ").validate().is_err());
+        assert!(
+            request(&"x".repeat(MAX_DATA_REQUEST_REASON_LEN + 1))
+                .validate()
+                .is_err()
+        );
+    }
+}
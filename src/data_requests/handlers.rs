@@ -0,0 +1,258 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{StatusCode, header::CONTENT_DISPOSITION},
+    response::{IntoResponse, Response},
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AdminUser},
+    data_requests::dtos::{CreateDataRequestRequest, DataRequestListResponse, DataRequestResponse},
+    entities::OperationState,
+    jobs::{BUILD_DATA_PACKAGE_JOB_KIND, BuildDataPackagePayload, Outbox},
+    middleware::transaction::RequestTransaction,
+    repositories::DataRequestRepository,
+};
+
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{user_id}/data-requests",
+    tag = "admin",
+    params(
+        ("user_id" = Uuid, Path, description = "User whose data to package")
+    ),
+    request_body = CreateDataRequestRequest,
+    responses(
+        (status = 202, description = "Package queued; fetch it from /v1/admin/data-requests/{id}/package once succeeded", body = DataRequestResponse),
+        (status = 400, description = "Missing or overlong reason", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_data_request(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    transaction: RequestTransaction,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<CreateDataRequestRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    match state.user_repo.find_by_id(user_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return not_found("User not found"),
+        Err(_) => return database_error(),
+    }
+
+    let request = match DataRequestRepository::create_in(
+        &mut *transaction.conn().await,
+        user_id,
+        admin.user_id,
+        payload.reason.trim(),
+    )
+    .await
+    {
+        Ok(request) => request,
+        Err(_) => return database_error(),
+    };
+
+    let job = match serde_json::to_value(BuildDataPackagePayload {
+        data_request_id: request.id,
+    }) {
+        Ok(job) => job,
+        Err(_) => return database_error(),
+    };
+
+    // Committed with the request by the transaction middleware
+    if Outbox::enqueue(
+        &mut *transaction.conn().await,
+        BUILD_DATA_PACKAGE_JOB_KIND,
+        job,
+        None,
+    )
+    .await
+    .is_err()
+    {
+        return database_error();
+    }
+
+    info!(
+        "Admin {} requested a data package for user {} ({})",
+        admin.user_id, user_id, request.id
+    );
+    (
+        StatusCode::ACCEPTED,
+        Json(DataRequestResponse::from(request)),
+    )
+        .into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/users/{user_id}/data-requests",
+    tag = "admin",
+    params(
+        ("user_id" = Uuid, Path, description = "User whose data requests to list")
+    ),
+    responses(
+        (status = 200, description = "Requests for the user's data and who made them, newest first", body = DataRequestListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_data_requests(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Response {
+    match DataRequestRepository::new(&state.db_pool)
+        .list_for_subject(user_id)
+        .await
+    {
+        Ok(requests) => (
+            StatusCode::OK,
+            Json(DataRequestListResponse {
+                requests: requests
+                    .into_iter()
+                    .map(DataRequestResponse::from)
+                    .collect(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/data-requests/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Data request ID")
+    ),
+    responses(
+        (status = 200, description = "The request and whether its package is ready", body = DataRequestResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+        (status = 404, description = "Data request not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_data_request(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match DataRequestRepository::new(&state.db_pool).find(id).await {
+        Ok(Some(request)) => {
+            (StatusCode::OK, Json(DataRequestResponse::from(request))).into_response()
+        }
+        Ok(None) => not_found("Data request not found"),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/data-requests/{id}/package",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Data request ID")
+    ),
+    responses(
+        (status = 200, description = "Every row referencing the user, by table, as a JSON attachment", content_type = "application/json"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+        (status = 404, description = "Data request not found", body = ErrorResponse),
+        (status = 409, description = "Package not built, or building it failed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_data_package(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let repo = DataRequestRepository::new(&state.db_pool);
+    let request = match repo.find(id).await {
+        Ok(Some(request)) => request,
+        Ok(None) => return not_found("Data request not found"),
+        Err(_) => return database_error(),
+    };
+
+    let error = match request.state {
+        OperationState::Succeeded => None,
+        OperationState::Failed => Some("Building the package failed; request it again"),
+        OperationState::Pending | OperationState::Running => Some("Package hasn't been built yet"),
+    };
+    if let Some(error) = error {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: error.to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let package = match repo.package(id).await {
+        Ok(Some(package)) => package,
+        Ok(None) => return not_found("Data request not found"),
+        Err(_) => return database_error(),
+    };
+
+    info!(
+        "Admin {} downloaded the data package for user {} ({})",
+        admin.user_id, request.subject_id, request.id
+    );
+    let disposition = format!(
+        "attachment; filename=\"capsule-data-{}-{}.json\"",
+        request.subject_id, request.id
+    );
+    (
+        StatusCode::OK,
+        [(CONTENT_DISPOSITION, disposition)],
+        Json(package),
+    )
+        .into_response()
+}
+
+fn not_found(error: &str) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: error.to_string(),
+        }),
+    )
+        .into_response()
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
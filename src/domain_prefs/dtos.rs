@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::entities::DomainPref;
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct UpsertDomainPrefRequest {
+    /// Fetch pages on this domain with a headless browser. No renderer is
+    /// available yet, so setting this is refused.
+    #[serde(default)]
+    pub headless_render: bool,
+    /// Drop images from extracted content for this domain
+    #[serde(default)]
+    pub skip_images: bool,
+    /// Tag applied to every item saved from this domain
+    pub default_tag: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DomainPrefResponse {
+    pub domain: String,
+    pub headless_render: bool,
+    pub skip_images: bool,
+    pub default_tag: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DomainPrefListResponse {
+    pub domain_prefs: Vec<DomainPrefResponse>,
+}
+
+impl From<DomainPref> for DomainPrefResponse {
+    fn from(pref: DomainPref) -> Self {
+        Self {
+            domain: pref.domain,
+            headless_render: pref.headless_render,
+            skip_images: pref.skip_images,
+            default_tag: pref.default_tag,
+            updated_at: pref.updated_at,
+        }
+    }
+}
+
+impl UpsertDomainPrefRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(tag) = &self.default_tag {
+            if tag.trim().is_empty() {
+                return Err("default_tag cannot be empty".to_string());
+            }
+            if tag.len() > 64 {
+                return Err("default_tag too long".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validate a domain path segment (already normalized to lowercase without `www.`).
+pub fn validate_domain(domain: &str) -> Result<(), String> {
+    if domain.is_empty() || domain.len() > 253 {
+        return Err("Invalid domain length".to_string());
+    }
+    if !domain.contains('.') {
+        return Err("Domain must contain a dot".to_string());
+    }
+    if !domain
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+    {
+        return Err("Domain contains invalid characters".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_domain_valid() {
+        assert!(validate_domain("example.com").is_ok());
+        assert!(validate_domain("blog.example-site.co.uk").is_ok());
+    }
+
+    #[test]
+    fn test_validate_domain_invalid() {
+        assert!(validate_domain("").is_err());
+        assert!(validate_domain("localhost").is_err());
+        assert!(validate_domain("exa mple.com").is_err());
+        assert!(validate_domain("example.com/path").is_err());
+    }
+
+    #[test]
+    fn test_upsert_request_rejects_blank_tag() {
+        let request = UpsertDomainPrefRequest {
+            default_tag: Some("  ".to_string()),
+            ..Default::default()
+        };
+        assert!(request.validate().is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    domain_prefs::dtos::{
+        DomainPrefListResponse, DomainPrefResponse, UpsertDomainPrefRequest, validate_domain,
+    },
+    repositories::{DomainPrefsRepository, domain_prefs::normalize_domain},
+};
+
+#[utoipa::path(
+    get,
+    path = "/v1/domain-prefs",
+    tag = "domain-prefs",
+    responses(
+        (status = 200, description = "Domain preferences listed successfully", body = DomainPrefListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_domain_prefs(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Response {
+    let repo = DomainPrefsRepository::new(&state.db_pool);
+    match repo.list(auth_user.user_id).await {
+        Ok(prefs) => (
+            StatusCode::OK,
+            Json(DomainPrefListResponse {
+                domain_prefs: prefs.into_iter().map(DomainPrefResponse::from).collect(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/domain-prefs/{domain}",
+    tag = "domain-prefs",
+    params(
+        ("domain" = String, Path, description = "Domain the preferences apply to (subdomains included)")
+    ),
+    request_body = UpsertDomainPrefRequest,
+    responses(
+        (status = 200, description = "Domain preferences saved", body = DomainPrefResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 501, description = "Headless rendering requested but not available", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn upsert_domain_pref(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(domain): Path<String>,
+    Json(payload): Json<UpsertDomainPrefRequest>,
+) -> Response {
+    let domain = normalize_domain(&domain);
+    if let Err(error) = validate_domain(&domain).and_then(|_| payload.validate()) {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+    if payload.headless_render {
+        return (
+            StatusCode::NOT_IMPLEMENTED,
+            Json(ErrorResponse {
+                error: "Headless rendering is not available".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let repo = DomainPrefsRepository::new(&state.db_pool);
+    match repo
+        .upsert(
+            auth_user.user_id,
+            &domain,
+            payload.headless_render,
+            payload.skip_images,
+            payload.default_tag.as_deref().map(str::trim),
+        )
+        .await
+    {
+        Ok(pref) => (StatusCode::OK, Json(DomainPrefResponse::from(pref))).into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/domain-prefs/{domain}",
+    tag = "domain-prefs",
+    params(
+        ("domain" = String, Path, description = "Domain whose preferences should be removed")
+    ),
+    responses(
+        (status = 204, description = "Domain preferences removed"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No preferences stored for this domain", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_domain_pref(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(domain): Path<String>,
+) -> Response {
+    let domain = normalize_domain(&domain);
+    let repo = DomainPrefsRepository::new(&state.db_pool);
+    match repo.delete(auth_user.user_id, &domain).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Domain preferences not found".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
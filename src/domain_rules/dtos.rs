@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::entities::{DomainRule, DomainRuleAction};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpsertDomainRuleRequest {
+    pub action: DomainRuleAction,
+    /// Shown to users whose saves the rule refuses
+    pub reason: Option<String>,
+}
+
+impl UpsertDomainRuleRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(reason) = &self.reason
+            && reason.len() > 256
+        {
+            return Err("reason too long".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DomainRuleResponse {
+    pub domain: String,
+    pub action: DomainRuleAction,
+    pub reason: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DomainRuleListResponse {
+    pub domain_rules: Vec<DomainRuleResponse>,
+}
+
+impl From<DomainRule> for DomainRuleResponse {
+    fn from(rule: DomainRule) -> Self {
+        Self {
+            domain: rule.domain,
+            action: rule.action,
+            reason: rule.reason,
+            updated_at: rule.updated_at,
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AdminUser},
+    domain_prefs::dtos::validate_domain,
+    domain_rules::dtos::{DomainRuleListResponse, DomainRuleResponse, UpsertDomainRuleRequest},
+    repositories::{DomainRulesRepository, domain_prefs::normalize_domain},
+};
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/domain-rules",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Domain rules listed successfully", body = DomainRuleListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_domain_rules(_admin: AdminUser, State(state): State<AppState>) -> Response {
+    let repo = DomainRulesRepository::new(&state.db_pool);
+    match repo.list().await {
+        Ok(rules) => (
+            StatusCode::OK,
+            Json(DomainRuleListResponse {
+                domain_rules: rules.into_iter().map(DomainRuleResponse::from).collect(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/admin/domain-rules/{domain}",
+    tag = "admin",
+    params(
+        ("domain" = String, Path, description = "Domain the rule applies to (subdomains included)")
+    ),
+    request_body = UpsertDomainRuleRequest,
+    responses(
+        (status = 200, description = "Domain rule saved", body = DomainRuleResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn upsert_domain_rule(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Path(domain): Path<String>,
+    Json(payload): Json<UpsertDomainRuleRequest>,
+) -> Response {
+    let domain = normalize_domain(&domain);
+    if let Err(error) = validate_domain(&domain).and_then(|_| payload.validate()) {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    let reason = payload
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty());
+    let repo = DomainRulesRepository::new(&state.db_pool);
+    match repo
+        .upsert(&domain, payload.action, reason, admin.user_id)
+        .await
+    {
+        Ok(rule) => (StatusCode::OK, Json(DomainRuleResponse::from(rule))).into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/domain-rules/{domain}",
+    tag = "admin",
+    params(
+        ("domain" = String, Path, description = "Domain whose rule should be removed")
+    ),
+    responses(
+        (status = 204, description = "Domain rule removed"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+        (status = 404, description = "No rule stored for this domain", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_domain_rule(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+    Path(domain): Path<String>,
+) -> Response {
+    let domain = normalize_domain(&domain);
+    let repo = DomainRulesRepository::new(&state.db_pool);
+    match repo.delete(&domain).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Domain rule not found".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
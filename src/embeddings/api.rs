@@ -0,0 +1,89 @@
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+
+use crate::embeddings::EmbeddingProvider;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Embeds through an OpenAI-compatible `/embeddings` endpoint, which most
+/// hosted and self-hosted model servers offer
+pub struct EmbeddingsApi {
+    client: Client,
+    endpoint: Url,
+    api_key: String,
+    model: String,
+}
+
+#[derive(Serialize)]
+struct EmbeddingsRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl EmbeddingsApi {
+    pub fn new(endpoint: &str, api_key: String, model: String) -> anyhow::Result<Self> {
+        let endpoint = Url::parse(endpoint)
+            .with_context(|| format!("Invalid embeddings URL: {}", endpoint))?;
+        let client = Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+        Ok(Self {
+            client,
+            endpoint,
+            api_key,
+            model,
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for EmbeddingsApi {
+    fn name(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let response = self
+            .client
+            .post(self.endpoint.clone())
+            .bearer_auth(&self.api_key)
+            .json(&EmbeddingsRequest {
+                model: &self.model,
+                input: texts,
+            })
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Embeddings endpoint returned {}: {}", status, body));
+        }
+        let mut data = response.json::<EmbeddingsResponse>().await?.data;
+        if data.len() != texts.len() {
+            return Err(anyhow!(
+                "Embeddings endpoint returned {} embeddings for {} texts",
+                data.len(),
+                texts.len()
+            ));
+        }
+        data.sort_by_key(|embedding| embedding.index);
+        Ok(data
+            .into_iter()
+            .map(|embedding| embedding.embedding)
+            .collect())
+    }
+}
@@ -0,0 +1,78 @@
+pub mod api;
+
+pub use api::EmbeddingsApi;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+pub const DEFAULT_EMBEDDINGS_URL: &str = "https://api.openai.com/v1/embeddings";
+pub const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+/// Most characters of an item's text that go into its embedding; the
+/// opening says what a page is about, and models truncate long input anyway
+pub const MAX_EMBEDDING_INPUT_CHARS: usize = 8000;
+
+/// Turns text into vectors whose cosine similarity reflects how close texts
+/// are in meaning. The API embeds search queries and the `embed_content` job
+/// embeds items with the same provider.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Identifies the model; vectors are only compared with vectors from the
+    /// same one
+    fn name(&self) -> &str;
+
+    /// One embedding per text, in the same order
+    async fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>>;
+}
+
+/// The provider configured by `EMBEDDING_API_KEY`, with `EMBEDDING_URL`
+/// and `EMBEDDING_MODEL` overriding the defaults. None when semantic search
+/// is off.
+pub fn provider_from_env() -> anyhow::Result<Option<Arc<dyn EmbeddingProvider>>> {
+    let Ok(api_key) = std::env::var("EMBEDDING_API_KEY") else {
+        return Ok(None);
+    };
+    let provider = EmbeddingsApi::new(
+        &std::env::var("EMBEDDING_URL").unwrap_or_else(|_| DEFAULT_EMBEDDINGS_URL.to_string()),
+        api_key,
+        std::env::var("EMBEDDING_MODEL").unwrap_or_else(|_| DEFAULT_EMBEDDING_MODEL.to_string()),
+    )?;
+    Ok(Some(Arc::new(provider)))
+}
+
+/// An embedding in pgvector's text form, e.g. `[0.1,-0.2]`, so it can be
+/// bound as text and cast without a client-side vector type
+pub fn vector_literal(embedding: &[f32]) -> String {
+    let values: Vec<String> = embedding.iter().map(f32::to_string).collect();
+    format!("[{}]", values.join(","))
+}
+
+/// Text to embed for an item: its title, then the opening of its text
+pub fn embedding_input(title: Option<&str>, text: &str) -> String {
+    let text: String = text.chars().take(MAX_EMBEDDING_INPUT_CHARS).collect();
+    match title {
+        Some(title) => format!("{}\n\n{}", title, text),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_literal() {
+        assert_eq!(vector_literal(&[0.5, -1.0, 0.25]), "[0.5,-1,0.25]");
+        assert_eq!(vector_literal(&[]), "[]");
+    }
+
+    #[test]
+    fn test_embedding_input() {
+        assert_eq!(embedding_input(Some("Title"), "Text"), "Title\n\nText");
+        let long = "x".repeat(MAX_EMBEDDING_INPUT_CHARS + 10);
+        assert_eq!(
+            embedding_input(None, &long).len(),
+            MAX_EMBEDDING_INPUT_CHARS
+        );
+    }
+}
@@ -1,9 +1,21 @@
 use chrono::{DateTime, Utc};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, types::Json};
+use std::{collections::BTreeMap, sync::LazyLock};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::items::dtos::{MAX_SAVE_TAGS, is_save_source, is_tag_name};
+
+static LOCALE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[a-z]{2,3}(-[A-Za-z0-9]{2,8})*$").expect("Failed to compile locale regex")
+});
+
+static TIMEZONE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(UTC|[A-Za-z]+(/[A-Za-z0-9_+\-]+)+)$").expect("Failed to compile timezone regex")
+});
+
 /// --- PostgreSQL Enums ---
 #[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[sqlx(type_name = "item_status", rename_all = "lowercase")]
@@ -11,7 +23,172 @@ use uuid::Uuid;
 pub enum ItemStatus {
     Pending,
     Fetched,
+    /// The article the user is in the middle of, apart from the backlog
+    Reading,
+    /// The page couldn't be fetched and won't be retried; see the item's
+    /// `last_error`
+    Failed,
+    Archived,
+}
+
+impl ItemStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ItemStatus::Pending => "pending",
+            ItemStatus::Fetched => "fetched",
+            ItemStatus::Reading => "reading",
+            ItemStatus::Failed => "failed",
+            ItemStatus::Archived => "archived",
+        }
+    }
+
+    /// Statuses a user may move an item into this one from. Only the fetch
+    /// pipeline marks items fetched, except when the user unarchives one or
+    /// puts it back in the backlog. Only fetched items can be read, and
+    /// only the pipeline marks items failed.
+    pub fn predecessors(self) -> &'static [ItemStatus] {
+        use ItemStatus::*;
+        match self {
+            Pending => &[Pending],
+            Fetched => &[Fetched, Reading, Archived],
+            Reading => &[Fetched, Reading],
+            Failed => &[Failed],
+            Archived => &[Pending, Fetched, Reading, Failed, Archived],
+        }
+    }
+
+    pub fn can_transition_to(self, next: ItemStatus) -> bool {
+        next.predecessors().contains(&self)
+    }
+}
+
+/// Where an item is in the fetch/extract pipeline. Tracked separately from
+/// `ItemStatus`, which is the user's own view of the item.
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[sqlx(type_name = "processing_state", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingState {
+    Pending,
+    Fetching,
+    FetchFailed,
+    Extracting,
+    Ready,
+    FailedPermanent,
+}
+
+impl ProcessingState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProcessingState::Pending => "pending",
+            ProcessingState::Fetching => "fetching",
+            ProcessingState::FetchFailed => "fetch_failed",
+            ProcessingState::Extracting => "extracting",
+            ProcessingState::Ready => "ready",
+            ProcessingState::FailedPermanent => "failed_permanent",
+        }
+    }
+
+    /// States an item may move into this one from. `Fetching` and
+    /// `Extracting` may repeat so a job retried after a crash can restart.
+    pub fn predecessors(self) -> &'static [ProcessingState] {
+        use ProcessingState::*;
+        match self {
+            Pending => &[],
+            Fetching => &[Pending, Fetching, FetchFailed, Ready, FailedPermanent],
+            FetchFailed => &[Fetching],
+            Extracting => &[Fetching, Extracting, Ready, FailedPermanent],
+            Ready => &[Extracting],
+            FailedPermanent => &[Fetching, FetchFailed, Extracting],
+        }
+    }
+
+    pub fn can_transition_to(self, next: ProcessingState) -> bool {
+        next.predecessors().contains(&self)
+    }
+}
+
+/// A step in an item's user-visible lifecycle, recorded in `item_events`
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[sqlx(type_name = "item_event_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ItemEventKind {
+    Saved,
+    Fetched,
+    FetchFailed,
+    Extracted,
+    ExtractionFailed,
+    Refetched,
     Archived,
+    /// The item's content moved to the shared document store
+    Shared,
+}
+
+/// What a long-running, user-initiated operation does
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[sqlx(type_name = "operation_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum OperationKind {
+    Import,
+    Export,
+}
+
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[sqlx(type_name = "operation_state", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum OperationState {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// Why a row of an import didn't become a readable item
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[sqlx(type_name = "import_failure_reason", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFailureReason {
+    /// Not an http(s) URL
+    BadUrl,
+    /// Already saved, or repeated earlier in the same import
+    Duplicate,
+    /// The site refused the fetch (401, 403 or 451)
+    FetchForbidden,
+    /// The instance's domain rules don't allow the URL's domain
+    BlockedDomain,
+}
+
+impl ImportFailureReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImportFailureReason::BadUrl => "bad_url",
+            ImportFailureReason::Duplicate => "duplicate",
+            ImportFailureReason::FetchForbidden => "fetch_forbidden",
+            ImportFailureReason::BlockedDomain => "blocked_domain",
+        }
+    }
+}
+
+/// What an instance-wide domain rule does to matching URLs
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[sqlx(type_name = "domain_rule_action", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum DomainRuleAction {
+    Block,
+    /// Once any allow rule exists, only allowed domains can be saved
+    Allow,
+}
+
+/// An entry in the audit trail of save throttles
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[sqlx(type_name = "abuse_event_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AbuseEventKind {
+    /// Saving was paused automatically
+    Throttled,
+    /// An admin lifted a throttle early
+    Lifted,
+    Exempted,
+    Unexempted,
 }
 
 #[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -24,6 +201,165 @@ pub enum JobStatus {
     Failed,
 }
 
+/// Why the extractor refused a fetched page, stored in `items.extraction_error`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionFailure {
+    TooShort,
+    Boilerplate,
+    RenderRequired,
+}
+
+impl ExtractionFailure {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExtractionFailure::TooShort => "too_short",
+            ExtractionFailure::Boilerplate => "boilerplate",
+            ExtractionFailure::RenderRequired => "render_required",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "too_short" => Some(ExtractionFailure::TooShort),
+            "boilerplate" => Some(ExtractionFailure::Boilerplate),
+            "render_required" => Some(ExtractionFailure::RenderRequired),
+            _ => None,
+        }
+    }
+}
+
+/// Which sources a search looks at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchScope {
+    Content,
+    Notes,
+    Highlights,
+    #[default]
+    All,
+}
+
+impl SearchScope {
+    pub fn includes(self, source: SearchScope) -> bool {
+        self == SearchScope::All || self == source
+    }
+}
+
+/// How search ranks items
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// Full-text matches on the query's words
+    #[default]
+    Keyword,
+    /// Closeness in meaning by embedding, blended with keyword matches
+    Semantic,
+}
+
+/// --- JSONB documents ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestSchedule {
+    Off,
+    Daily,
+    Weekly,
+}
+
+/// User preferences stored in `users.prefs`. Unknown keys are rejected so the
+/// column only ever holds fields the application understands.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UserPreferences {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reading_font_size: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest_schedule: Option<DigestSchedule>,
+    /// IANA timezone name, e.g. `Europe/Berlin`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// BCP 47 language tag, e.g. `en-US`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Whether saved pages may be stored once in the shared document store
+    /// and served from it to other users saving the same URL; on when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share_content: Option<bool>,
+    /// ISO 639-1 code of the language to translate saved pages into, e.g.
+    /// `de`; pages already in it are left alone
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translate_to: Option<String>,
+    /// Whether to get a weekly notification when unread items pile up
+    /// faster than they're read; on when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_nudges: Option<bool>,
+    /// Tags attached to newly saved items, along with any the save names
+    /// itself, e.g. `["inbox"]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_tags: Option<Vec<String>>,
+    /// Only give `default_tags` to saves from one of these sources, e.g.
+    /// `["extension"]`; every save gets them when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_tags_sources: Option<Vec<String>>,
+}
+
+impl UserPreferences {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(size) = self.reading_font_size
+            && !(10..=32).contains(&size)
+        {
+            return Err("reading_font_size must be between 10 and 32".to_string());
+        }
+        if let Some(timezone) = &self.timezone
+            && !TIMEZONE_REGEX.is_match(timezone)
+        {
+            return Err("Invalid timezone".to_string());
+        }
+        if let Some(locale) = &self.locale
+            && !LOCALE_REGEX.is_match(locale)
+        {
+            return Err("Invalid locale".to_string());
+        }
+        if let Some(lang) = &self.translate_to
+            && !crate::translation::is_language_code(lang)
+        {
+            return Err("translate_to must be an ISO 639-1 language code".to_string());
+        }
+        if let Some(tags) = &self.default_tags {
+            if tags.len() > MAX_SAVE_TAGS {
+                return Err(format!(
+                    "default_tags may name at most {} tags",
+                    MAX_SAVE_TAGS
+                ));
+            }
+            if !tags.iter().all(|tag| is_tag_name(tag)) {
+                return Err("default_tags must be between 1 and 100 characters".to_string());
+            }
+        }
+        if let Some(sources) = &self.default_tags_sources
+            && !sources.iter().all(|source| is_save_source(source))
+        {
+            return Err(
+                "default_tags_sources must be lowercase letters, digits, - or _".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Tags a save from `source` gets without asking for them
+    pub fn default_tags_for(&self, source: Option<&str>) -> &[String] {
+        let applies = match &self.default_tags_sources {
+            None => true,
+            Some(sources) => source.is_some_and(|source| sources.iter().any(|s| s == source)),
+        };
+        match &self.default_tags {
+            Some(tags) if applies => tags,
+            _ => &[],
+        }
+    }
+}
+
 /// --- Tables ---
 
 #[derive(Debug, Clone, FromRow)]
@@ -34,16 +370,113 @@ pub struct User {
     pub created_at: DateTime<Utc>,
 }
 
+/// Profile view of a user row (no credentials).
+#[derive(Debug, Clone, FromRow)]
+pub struct UserProfile {
+    pub id: Uuid,
+    pub email: String,
+    pub display_name: Option<String>,
+    pub prefs: Json<UserPreferences>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, FromRow)]
 pub struct Item {
     pub id: Uuid,
     pub user_id: Uuid,
     pub url: String,
     pub title: Option<String>,
+    /// Name of the item's site, once a page from it has been fetched
     pub site: Option<String>,
     pub status: ItemStatus,
+    pub extraction_error: Option<String>,
+    /// Why the last fetch failed for good, until one succeeds
+    pub last_error: Option<String>,
+    pub processing_state: ProcessingState,
+    pub processing_state_changed_at: DateTime<Utc>,
+    pub nsfw: bool,
+    pub read_progress: f32,
+    pub favorite: bool,
+    /// Bumped whenever what the user edits changes; see
+    /// [`ItemEdit::Stale`](crate::repositories::ItemEdit::Stale)
+    pub version: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ItemStateTransition {
+    pub id: i64,
+    pub item_id: Uuid,
+    pub from_state: Option<ProcessingState>,
+    pub to_state: ProcessingState,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Operation {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: OperationKind,
+    pub state: OperationState,
+    pub processed: i32,
+    pub total: Option<i32>,
+    pub error_count: i32,
+    pub error_samples: Json<Vec<OperationErrorSample>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// One failed input of an operation, such as an import row
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct OperationErrorSample {
+    /// What failed, e.g. the URL being imported
+    pub subject: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ImportFailure {
+    pub id: i64,
+    pub operation_id: Uuid,
+    pub row_number: i32,
+    pub url: String,
+    pub reason: ImportFailureReason,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ItemEvent {
+    pub id: i64,
+    pub item_id: Uuid,
+    pub kind: ItemEventKind,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One try at fetching an item's page
+#[derive(Debug, Clone, FromRow)]
+pub struct FetchAttempt {
+    pub id: i64,
+    pub item_id: Uuid,
+    pub attempted_at: DateTime<Utc>,
+    /// Where redirects led; None when the request didn't get a response
+    pub final_url: Option<String>,
+    pub status: Option<i16>,
+    /// [`FetchError::class`](crate::fetcher::FetchError::class) of a failed
+    /// attempt
+    pub error_class: Option<String>,
+    pub error: Option<String>,
+    pub duration_ms: i32,
+    pub bytes: Option<i64>,
+    /// Phase timings; DNS and connect are unset on a pooled connection
+    pub dns_ms: Option<i32>,
+    /// TCP and TLS handshakes together
+    pub connect_ms: Option<i32>,
+    pub ttfb_ms: Option<i32>,
+    pub download_ms: Option<i32>,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -56,6 +489,9 @@ pub struct Content {
     pub lang: Option<String>,
     pub extracted_at: Option<DateTime<Utc>>,
     pub checksum: Option<String>,
+    /// The [`PERSISTED_HEADERS`](crate::fetcher::PERSISTED_HEADERS) of the
+    /// last fetch; None for content stored before they were kept
+    pub response_headers: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -87,3 +523,303 @@ pub struct Job {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DomainPref {
+    pub user_id: Uuid,
+    pub domain: String,
+    pub headless_render: bool,
+    pub skip_images: bool,
+    pub default_tag: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A site items are saved from, shared by every user
+#[derive(Debug, Clone, FromRow)]
+pub struct Site {
+    /// Lowercase, without `www.`, as in the items' `domain`
+    pub host: String,
+    pub name: Option<String>,
+    pub feed_url: Option<String>,
+    /// The most specific domain rule covering the site
+    pub domain_rule: Option<String>,
+    pub has_icon: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A site's icon
+#[derive(Debug, Clone, FromRow)]
+pub struct SiteIcon {
+    pub host: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+    pub source_url: Option<String>,
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct DomainRule {
+    pub domain: String,
+    pub action: DomainRuleAction,
+    pub reason: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct UserThrottle {
+    pub user_id: Uuid,
+    pub throttled_until: Option<DateTime<Utc>>,
+    pub reason: Option<String>,
+    pub exempt: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AbuseEvent {
+    pub id: i64,
+    pub user_id: Uuid,
+    pub kind: AbuseEventKind,
+    pub detail: Option<String>,
+    pub actor_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Highlight {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub user_id: Uuid,
+    pub quote: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Notification {
+    pub id: i64,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub email_pending: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Backup {
+    pub id: Uuid,
+    pub checksum: String,
+    pub previous_checksum: Option<String>,
+    pub file_name: String,
+    pub since: Option<DateTime<Utc>>,
+    pub until: DateTime<Utc>,
+    pub row_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A public link to one item's cleaned article
+#[derive(Debug, Clone, FromRow)]
+pub struct Share {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub user_id: Uuid,
+    /// None when the link works until it's revoked
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The user opening one of their items
+#[derive(Debug, Clone, FromRow)]
+pub struct ReadEvent {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub user_id: Uuid,
+    pub opened_at: DateTime<Utc>,
+}
+
+/// One of the user's items at the other end of a link
+#[derive(Debug, Clone, FromRow)]
+pub struct LinkedItem {
+    pub id: Uuid,
+    pub url: String,
+    pub title: Option<String>,
+    pub site: Option<String>,
+}
+
+/// A machine translation of an item's extracted text
+#[derive(Debug, Clone, FromRow)]
+pub struct ContentTranslation {
+    pub item_id: Uuid,
+    pub lang: String,
+    pub source_lang: Option<String>,
+    pub text: String,
+    pub provider: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An admin's request for everything stored about a user. The package
+/// itself is loaded separately, see `DataRequestRepository::package`.
+#[derive(Debug, Clone, FromRow)]
+pub struct DataRequest {
+    pub id: Uuid,
+    pub subject_id: Uuid,
+    pub requested_by: Option<Uuid>,
+    pub reason: String,
+    pub state: OperationState,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_preferences_valid() {
+        let prefs = UserPreferences {
+            reading_font_size: Some(16),
+            digest_schedule: Some(DigestSchedule::Weekly),
+            timezone: Some("America/Argentina/Buenos_Aires".to_string()),
+            locale: Some("en-US".to_string()),
+            share_content: Some(false),
+            translate_to: Some("de".to_string()),
+            queue_nudges: Some(false),
+            default_tags: Some(vec!["inbox".to_string()]),
+            default_tags_sources: Some(vec!["extension".to_string()]),
+        };
+        assert!(prefs.validate().is_ok());
+        assert!(UserPreferences::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_user_preferences_invalid_values() {
+        let font = UserPreferences {
+            reading_font_size: Some(64),
+            ..Default::default()
+        };
+        assert!(font.validate().is_err());
+
+        let timezone = UserPreferences {
+            timezone: Some("not a timezone".to_string()),
+            ..Default::default()
+        };
+        assert!(timezone.validate().is_err());
+
+        let locale = UserPreferences {
+            locale: Some("EN_us".to_string()),
+            ..Default::default()
+        };
+        assert!(locale.validate().is_err());
+    }
+
+    #[test]
+    fn test_user_preferences_default_tags_for() {
+        let mut prefs = UserPreferences {
+            default_tags: Some(vec!["inbox".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(prefs.default_tags_for(None), ["inbox"]);
+
+        prefs.default_tags_sources = Some(vec!["extension".to_string()]);
+        assert_eq!(prefs.default_tags_for(Some("extension")), ["inbox"]);
+        assert!(prefs.default_tags_for(Some("mobile")).is_empty());
+        assert!(prefs.default_tags_for(None).is_empty());
+
+        let invalid = UserPreferences {
+            default_tags_sources: Some(vec!["Browser Extension".to_string()]),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_user_preferences_rejects_unknown_fields() {
+        let result = serde_json::from_value::<UserPreferences>(serde_json::json!({
+            "reading_font_size": 18,
+            "theme": "dark"
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_search_scope_includes() {
+        assert!(SearchScope::All.includes(SearchScope::Notes));
+        assert!(SearchScope::Highlights.includes(SearchScope::Highlights));
+        assert!(!SearchScope::Content.includes(SearchScope::Highlights));
+    }
+
+    #[test]
+    fn test_processing_state_happy_path() {
+        use ProcessingState::*;
+        let path = [Pending, Fetching, Extracting, Ready];
+        for pair in path.windows(2) {
+            assert!(pair[0].can_transition_to(pair[1]), "{:?}", pair);
+        }
+        // refreshes re-extract ready items
+        assert!(Ready.can_transition_to(Extracting));
+    }
+
+    #[test]
+    fn test_processing_state_failures() {
+        use ProcessingState::*;
+        assert!(Fetching.can_transition_to(FetchFailed));
+        assert!(FetchFailed.can_transition_to(Fetching));
+        assert!(Extracting.can_transition_to(FailedPermanent));
+        assert!(FailedPermanent.can_transition_to(Fetching));
+
+        assert!(!Pending.can_transition_to(Ready));
+        assert!(!FetchFailed.can_transition_to(Ready));
+        assert!(!Ready.can_transition_to(Pending));
+    }
+
+    #[test]
+    fn test_item_status_transitions() {
+        use ItemStatus::*;
+        assert!(Pending.can_transition_to(Archived));
+        assert!(Fetched.can_transition_to(Archived));
+        assert!(Archived.can_transition_to(Fetched));
+        assert!(Fetched.can_transition_to(Fetched));
+        assert!(Fetched.can_transition_to(Reading));
+        assert!(Reading.can_transition_to(Fetched));
+        assert!(Reading.can_transition_to(Archived));
+        assert!(Failed.can_transition_to(Archived));
+
+        assert!(!Archived.can_transition_to(Pending));
+        assert!(!Fetched.can_transition_to(Pending));
+        assert!(!Pending.can_transition_to(Fetched));
+        assert!(!Pending.can_transition_to(Reading));
+        assert!(!Archived.can_transition_to(Reading));
+        assert!(!Reading.can_transition_to(Pending));
+        assert!(!Pending.can_transition_to(Failed));
+        assert!(!Failed.can_transition_to(Fetched));
+    }
+
+    #[test]
+    fn test_processing_state_serializes_snake_case() {
+        assert_eq!(
+            serde_json::to_value(ProcessingState::FailedPermanent).unwrap(),
+            serde_json::json!(ProcessingState::FailedPermanent.as_str())
+        );
+    }
+
+    #[test]
+    fn test_extraction_failure_round_trip() {
+        for reason in [
+            ExtractionFailure::TooShort,
+            ExtractionFailure::Boilerplate,
+            ExtractionFailure::RenderRequired,
+        ] {
+            assert_eq!(ExtractionFailure::parse(reason.as_str()), Some(reason));
+            assert_eq!(
+                serde_json::to_value(reason).unwrap(),
+                serde_json::json!(reason.as_str())
+            );
+        }
+        assert_eq!(ExtractionFailure::parse("unknown"), None);
+    }
+}
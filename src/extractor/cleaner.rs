@@ -1,44 +1,157 @@
-use ammonia::Builder;
+use ammonia::{Builder, UrlRelative};
 use regex::Regex;
+use std::borrow::Cow;
 use url::Url;
 
-use crate::extractor::model::{ReadabilityResult, normalize_whitespace};
+use crate::extractor::{
+    embeds::{self, EmbedProvider},
+    math,
+    model::{ReadabilityResult, normalize_whitespace},
+};
+
+/// Attributes kept on iframes from trusted embed hosts
+const IFRAME_ATTRIBUTES: [&str; 5] = ["src", "width", "height", "title", "allowfullscreen"];
+
+/// Which markup survives sanitizing. The default is ammonia's own policy plus
+/// MathML and click-to-load placeholders for well-known embed providers;
+/// deployments can make the output stricter or richer without forking the
+/// cleaner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizePolicy {
+    /// Tags allowed on top of ammonia's defaults
+    pub extra_tags: Vec<String>,
+    /// Default tags to drop; their text content is kept
+    pub removed_tags: Vec<String>,
+    /// Extra `(tag, attribute)` pairs to keep
+    pub extra_attributes: Vec<(String, String)>,
+    /// URL schemes allowed in links and images; `None` keeps ammonia's list
+    pub url_schemes: Option<Vec<String>>,
+    /// Hosts whose iframes are kept, e.g. `www.youtube.com`. Iframes from
+    /// anywhere else lose their `src`; with no hosts, iframes are dropped.
+    pub iframe_hosts: Vec<String>,
+    /// Providers whose iframes become placeholders keeping the embed URL;
+    /// see [`embeds::replace_embeds`]
+    pub embed_providers: Vec<EmbedProvider>,
+}
+
+impl Default for SanitizePolicy {
+    fn default() -> Self {
+        Self {
+            extra_tags: Vec::new(),
+            removed_tags: Vec::new(),
+            extra_attributes: Vec::new(),
+            url_schemes: None,
+            iframe_hosts: Vec::new(),
+            embed_providers: embeds::default_providers(),
+        }
+    }
+}
+
+impl SanitizePolicy {
+    /// Reject policies ammonia can't apply: script and style content is always
+    /// removed, and `rel` on links is managed by the sanitizer itself.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(tag) = self
+            .extra_tags
+            .iter()
+            .find(|tag| tag.eq_ignore_ascii_case("script") || tag.eq_ignore_ascii_case("style"))
+        {
+            return Err(format!("Tag cannot be allowed: {}", tag));
+        }
+        if self
+            .extra_attributes
+            .iter()
+            .any(|(tag, attribute)| tag == "a" && attribute == "rel")
+        {
+            return Err("Attribute cannot be allowed: a rel".to_string());
+        }
+        if let Some(schemes) = &self.url_schemes
+            && schemes.is_empty()
+        {
+            return Err("url_schemes cannot be empty".to_string());
+        }
+        Ok(())
+    }
+
+    fn builder(&self, base_url: &Url) -> Builder<'_> {
+        let mut builder = Builder::default();
+        builder
+            .add_tags(math::MATHML_TAGS)
+            .add_tags(&self.extra_tags)
+            .rm_tags(&self.removed_tags)
+            .url_relative(UrlRelative::RewriteWithBase(base_url.clone()));
+        for (tag, attributes) in math::MATHML_ATTRIBUTES {
+            builder.add_tag_attributes(tag, attributes);
+        }
+        for (tag, attribute) in &self.extra_attributes {
+            builder.add_tag_attributes(tag, std::iter::once(attribute));
+        }
+        if let Some(schemes) = &self.url_schemes {
+            builder.url_schemes(schemes.iter().map(String::as_str).collect());
+        }
+        if !self.embed_providers.is_empty() {
+            builder.add_tag_attributes("figure", embeds::PLACEHOLDER_ATTRIBUTES);
+        }
+        if !self.iframe_hosts.is_empty() {
+            builder
+                .add_tags(["iframe"])
+                .add_tag_attributes("iframe", IFRAME_ATTRIBUTES);
+            let hosts = self.iframe_hosts.clone();
+            builder.attribute_filter(move |element, attribute, value| {
+                if element == "iframe" && attribute == "src" && !is_trusted_embed(value, &hosts) {
+                    return None;
+                }
+                Some(Cow::Borrowed(value))
+            });
+        }
+        builder
+    }
+}
+
+/// Whether an iframe `src` points at one of the trusted hosts over HTTPS
+fn is_trusted_embed(src: &str, hosts: &[String]) -> bool {
+    Url::parse(src).is_ok_and(|url| {
+        url.scheme() == "https"
+            && url.host_str().is_some_and(|host| {
+                hosts
+                    .iter()
+                    .any(|trusted| trusted.eq_ignore_ascii_case(host))
+            })
+    })
+}
 
 pub fn sanitize_and_resolve_links(result: &mut ReadabilityResult, base_url: &Url) {
-    // Clean the HTML with Ammonia (removes scripts, styles, dangerous elements)
-    let clean_html = Builder::default().clean(&result.html).to_string();
+    sanitize_with_policy(result, base_url, &SanitizePolicy::default());
+}
 
-    // Manually resolve relative links to absolute
-    result.html = resolve_links(&clean_html, base_url);
+/// Like [`sanitize_and_resolve_links`], with a deployment's own policy
+pub fn sanitize_with_policy(
+    result: &mut ReadabilityResult,
+    base_url: &Url,
+    policy: &SanitizePolicy,
+) {
+    // Clean the HTML with Ammonia (removes scripts, styles, dangerous
+    // elements), resolving relative links against the page URL as it goes
+    let html = embeds::replace_embeds(
+        &result.html,
+        base_url,
+        &policy.embed_providers,
+        &policy.iframe_hosts,
+    );
+    result.html = policy.builder(base_url).clean(&html).to_string();
 
     // Normalize whitespace in text content
     result.text = normalize_whitespace(&result.text);
 }
 
-fn resolve_links(html: &str, base_url: &Url) -> String {
-    // Resolve relative href attributes
-    let href_regex = Regex::new(r#"href="([^"]+)""#).unwrap();
-    let html = href_regex.replace_all(html, |caps: &regex::Captures| {
-        let url_str = &caps[1];
-        if let Ok(absolute_url) = base_url.join(url_str) {
-            format!(r#"href="{}""#, absolute_url)
-        } else {
-            caps[0].to_string()
-        }
-    });
-
-    // Resolve relative src attributes
-    let src_regex = Regex::new(r#"src="([^"]+)""#).unwrap();
-    let html = src_regex.replace_all(&html, |caps: &regex::Captures| {
-        let url_str = &caps[1];
-        if let Ok(absolute_url) = base_url.join(url_str) {
-            format!(r#"src="{}""#, absolute_url)
-        } else {
-            caps[0].to_string()
-        }
-    });
+/// Remove images (and `<picture>` wrappers) from cleaned HTML, for sites where
+/// the user has opted out of keeping images.
+pub fn strip_images(html: &str) -> String {
+    let picture_regex = Regex::new(r"(?is)<picture\b.*?</picture>").unwrap();
+    let html = picture_regex.replace_all(html, "");
 
-    html.to_string()
+    let img_regex = Regex::new(r"(?i)<img\b[^>]*>").unwrap();
+    img_regex.replace_all(&html, "").to_string()
 }
 
 #[cfg(test)]
@@ -87,6 +200,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_strip_images() {
+        let html = r#"<p>Intro<img src="https://example.com/a.jpg" alt="a"></p><picture><source srcset="b.webp"><img src="b.jpg"></picture><p>Outro</p>"#;
+        let stripped = strip_images(html);
+
+        assert!(!stripped.contains("<img"));
+        assert!(!stripped.contains("<picture"));
+        assert_eq!(stripped, "<p>Intro</p><p>Outro</p>");
+    }
+
     #[test]
     fn test_normalize_whitespace() {
         let text = "  Hello    world  \n\n\n  Test  ";
@@ -94,4 +217,110 @@ mod tests {
         // The function preserves newlines and normalizes spaces
         assert_eq!(normalized, "Hello world \n\n Test");
     }
+
+    fn result_with_html(html: &str) -> ReadabilityResult {
+        ReadabilityResult {
+            title: "Test".to_string(),
+            site_name: None,
+            byline: None,
+            text: "Test".to_string(),
+            html: html.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_default_policy_drops_iframes() {
+        let mut result = result_with_html(
+            r#"<p>Watch</p><iframe src="https://www.youtube.com/embed/abc"></iframe>"#,
+        );
+        let base_url = Url::parse("https://example.com").unwrap();
+        sanitize_with_policy(&mut result, &base_url, &SanitizePolicy::default());
+
+        assert!(!result.html.contains("<iframe"));
+        assert!(result.html.contains("<p>Watch</p>"));
+    }
+
+    #[test]
+    fn test_policy_keeps_trusted_iframes_only() {
+        let policy = SanitizePolicy {
+            iframe_hosts: vec!["www.youtube.com".to_string()],
+            ..Default::default()
+        };
+        let mut result = result_with_html(
+            r#"<iframe src="https://www.youtube.com/embed/abc" onload="x()"></iframe><iframe src="https://evil.example/embed"></iframe>"#,
+        );
+        let base_url = Url::parse("https://example.com").unwrap();
+        sanitize_with_policy(&mut result, &base_url, &policy);
+
+        assert!(
+            result
+                .html
+                .contains(r#"<iframe src="https://www.youtube.com/embed/abc"></iframe>"#)
+        );
+        assert!(!result.html.contains("evil.example"));
+        assert!(!result.html.contains("onload"));
+    }
+
+    #[test]
+    fn test_default_policy_keeps_embed_placeholders() {
+        let mut result = result_with_html(
+            r#"<p>Demo</p><iframe src="https://player.vimeo.com/video/42" onload="x()"></iframe>"#,
+        );
+        let base_url = Url::parse("https://example.com").unwrap();
+        sanitize_with_policy(&mut result, &base_url, &SanitizePolicy::default());
+
+        assert!(!result.html.contains("<iframe"));
+        assert!(result.html.contains(r#"data-embed-provider="vimeo""#));
+        assert!(
+            result
+                .html
+                .contains(r#"data-embed-src="https://player.vimeo.com/video/42""#)
+        );
+        assert!(!result.html.contains("onload"));
+    }
+
+    #[test]
+    fn test_sanitize_keeps_mathml() {
+        let mut result = result_with_html(
+            r#"<p><math display="block"><msup><mi>x</mi><mn>2</mn></msup></math></p>"#,
+        );
+        let base_url = Url::parse("https://example.com").unwrap();
+        sanitize_and_resolve_links(&mut result, &base_url);
+
+        assert!(result.html.contains(r#"<math display="block">"#));
+        assert!(result.html.contains("<msup><mi>x</mi><mn>2</mn></msup>"));
+    }
+
+    #[test]
+    fn test_policy_removes_tags_and_restricts_schemes() {
+        let policy = SanitizePolicy {
+            removed_tags: vec!["img".to_string()],
+            url_schemes: Some(vec!["https".to_string()]),
+            ..Default::default()
+        };
+        let mut result =
+            result_with_html(r#"<p><a href="mailto:a@example.com">Mail</a><img src="/a.jpg"></p>"#);
+        let base_url = Url::parse("https://example.com").unwrap();
+        sanitize_with_policy(&mut result, &base_url, &policy);
+
+        assert!(!result.html.contains("<img"));
+        assert!(!result.html.contains("mailto:"));
+    }
+
+    #[test]
+    fn test_policy_validate() {
+        assert!(SanitizePolicy::default().validate().is_ok());
+
+        let script = SanitizePolicy {
+            extra_tags: vec!["script".to_string()],
+            ..Default::default()
+        };
+        assert!(script.validate().is_err());
+
+        let rel = SanitizePolicy {
+            extra_attributes: vec![("a".to_string(), "rel".to_string())],
+            ..Default::default()
+        };
+        assert!(rel.validate().is_err());
+    }
 }
@@ -0,0 +1,171 @@
+//! Paragraph-level comparison of two extractions of the same page, used to
+//! tell watchers what changed when a living document is refreshed.
+
+use std::collections::HashMap;
+
+/// Share of a page's text that has to change before the change is worth
+/// telling anyone about; below it are typo fixes, dates and counters
+pub const CONTENT_CHANGE_THRESHOLD: f64 = 0.1;
+
+/// Most changed paragraphs quoted per side, and how much of each
+const MAX_SUMMARY_SECTIONS: usize = 5;
+const MAX_SECTION_CHARS: usize = 160;
+
+/// How two versions of a text differ
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextDiff {
+    /// Share of both versions' characters in paragraphs the other version
+    /// lacks: 0.0 when they're identical, 1.0 when nothing is shared
+    pub ratio: f64,
+    /// Number of paragraphs only the new version has
+    pub added_count: usize,
+    /// Number of paragraphs only the old version had
+    pub removed_count: usize,
+    /// Excerpts of the first few added paragraphs, in order
+    pub added: Vec<String>,
+    /// Excerpts of the first few removed paragraphs, in order
+    pub removed: Vec<String>,
+}
+
+impl TextDiff {
+    /// Whether the change reaches [`CONTENT_CHANGE_THRESHOLD`]
+    pub fn is_material(&self) -> bool {
+        self.ratio >= CONTENT_CHANGE_THRESHOLD
+    }
+}
+
+/// Compare `old` and `new` paragraph by paragraph. Paragraphs are matched
+/// by content regardless of position, so moving a section around doesn't
+/// count as a change.
+pub fn diff_text(old: &str, new: &str) -> TextDiff {
+    let old_paragraphs = paragraphs(old);
+    let new_paragraphs = paragraphs(new);
+
+    let removed = unmatched(&old_paragraphs, &new_paragraphs);
+    let added = unmatched(&new_paragraphs, &old_paragraphs);
+
+    let total = char_count(&old_paragraphs) + char_count(&new_paragraphs);
+    let changed = char_count(&removed) + char_count(&added);
+    let ratio = if total == 0 {
+        0.0
+    } else {
+        changed as f64 / total as f64
+    };
+
+    TextDiff {
+        ratio,
+        added_count: added.len(),
+        removed_count: removed.len(),
+        added: summarize(&added),
+        removed: summarize(&removed),
+    }
+}
+
+fn paragraphs(text: &str) -> Vec<&str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Paragraphs of `from` that `to` doesn't have, counting repeats
+fn unmatched<'a>(from: &[&'a str], to: &[&str]) -> Vec<&'a str> {
+    let mut available: HashMap<&str, usize> = HashMap::new();
+    for paragraph in to {
+        *available.entry(*paragraph).or_default() += 1;
+    }
+
+    from.iter()
+        .copied()
+        .filter(|paragraph| match available.get_mut(paragraph) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+fn char_count(paragraphs: &[&str]) -> usize {
+    paragraphs.iter().map(|p| p.chars().count()).sum()
+}
+
+fn summarize(paragraphs: &[&str]) -> Vec<String> {
+    paragraphs
+        .iter()
+        .take(MAX_SUMMARY_SECTIONS)
+        .map(
+            |paragraph| match paragraph.char_indices().nth(MAX_SECTION_CHARS) {
+                Some((end, _)) => format!("{}…", paragraph[..end].trim_end()),
+                None => paragraph.to_string(),
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_and_reordered_text_is_unchanged() {
+        let text = "Intro\n\nBody paragraph\n\nOutro";
+        assert_eq!(diff_text(text, text).ratio, 0.0);
+        assert_eq!(diff_text("", "").ratio, 0.0);
+
+        let reordered = diff_text(text, "Outro\n\nIntro\n\nBody paragraph");
+        assert_eq!(reordered.ratio, 0.0);
+        assert!(!reordered.is_material());
+    }
+
+    #[test]
+    fn test_small_edit_is_not_material() {
+        let body = "A long paragraph that stays the same. ".repeat(20);
+        let old = format!("{}\n\nUpdated on Monday", body);
+        let new = format!("{}\n\nUpdated on Tuesday", body);
+
+        let diff = diff_text(&old, &new);
+        assert!(diff.ratio > 0.0);
+        assert!(!diff.is_material());
+        assert_eq!(diff.added, vec!["Updated on Tuesday"]);
+        assert_eq!(diff.removed, vec!["Updated on Monday"]);
+    }
+
+    #[test]
+    fn test_rewritten_section_is_material() {
+        let old = "Intro\n\nPricing starts at $10 a month\n\nOutro";
+        let new = "Intro\n\nPricing starts at $25 a month\n\nA new free tier\n\nOutro";
+
+        let diff = diff_text(old, new);
+        assert!(diff.is_material());
+        assert_eq!(diff.added_count, 2);
+        assert_eq!(diff.removed_count, 1);
+        assert_eq!(
+            diff.added,
+            vec!["Pricing starts at $25 a month", "A new free tier"]
+        );
+        assert_eq!(diff_text("", "Everything is new").ratio, 1.0);
+    }
+
+    #[test]
+    fn test_repeated_paragraphs_are_counted() {
+        let diff = diff_text("Same\nSame", "Same");
+        assert_eq!(diff.removed, vec!["Same"]);
+        assert!(diff.added.is_empty());
+    }
+
+    #[test]
+    fn test_summary_is_bounded() {
+        let new: Vec<String> = (0..10)
+            .map(|i| format!("{} {}", i, "é".repeat(500)))
+            .collect();
+        let diff = diff_text("", &new.join("\n"));
+
+        assert_eq!(diff.added_count, 10);
+        assert_eq!(diff.added.len(), MAX_SUMMARY_SECTIONS);
+        assert!(diff.added[0].starts_with("0 é"));
+        assert!(diff.added[0].ends_with('…'));
+        assert_eq!(diff.added[0].chars().count(), MAX_SECTION_CHARS + 1);
+    }
+}
@@ -0,0 +1,185 @@
+use regex::Regex;
+use std::sync::LazyLock;
+use url::Url;
+
+static IFRAME_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<iframe\b([^>]*)>(?:.*?</iframe>)?").unwrap());
+
+static ATTRIBUTE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\b(src|title)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+
+/// Attributes carried by an embed placeholder; the sanitizer must allow them
+/// on `<figure>`
+pub const PLACEHOLDER_ATTRIBUTES: [&str; 2] = ["data-embed-provider", "data-embed-src"];
+
+/// A site whose iframe embeds are worth keeping as click-to-load placeholders
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbedProvider {
+    /// Stable identifier written to `data-embed-provider`
+    pub id: String,
+    /// Human-readable name used in the placeholder link
+    pub name: String,
+    /// Hosts serving the provider's embeds
+    pub hosts: Vec<String>,
+}
+
+impl EmbedProvider {
+    fn new(id: &str, name: &str, hosts: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            hosts: hosts.iter().map(|host| host.to_string()).collect(),
+        }
+    }
+
+    fn serves(&self, host: &str) -> bool {
+        self.hosts.iter().any(|h| h.eq_ignore_ascii_case(host))
+    }
+}
+
+/// Providers technical articles commonly embed demos and talks from
+pub fn default_providers() -> Vec<EmbedProvider> {
+    vec![
+        EmbedProvider::new(
+            "youtube",
+            "YouTube",
+            &["www.youtube.com", "youtube.com", "www.youtube-nocookie.com"],
+        ),
+        EmbedProvider::new("vimeo", "Vimeo", &["player.vimeo.com"]),
+        EmbedProvider::new("codepen", "CodePen", &["codepen.io"]),
+    ]
+}
+
+/// Replace iframes from known providers with a placeholder that keeps the
+/// embed URL but loads nothing until a reader client chooses to:
+///
+/// ```html
+/// <figure data-embed-provider="youtube" data-embed-src="https://www.youtube.com/embed/abc">
+///   <a href="https://www.youtube.com/embed/abc">YouTube embed</a>
+/// </figure>
+/// ```
+///
+/// Iframes from `live_hosts` are left alone so the sanitizer can keep them
+/// as real iframes; anything else is left for the sanitizer to drop.
+pub fn replace_embeds(
+    html: &str,
+    base_url: &Url,
+    providers: &[EmbedProvider],
+    live_hosts: &[String],
+) -> String {
+    if providers.is_empty() {
+        return html.to_string();
+    }
+
+    IFRAME_REGEX
+        .replace_all(html, |caps: &regex::Captures| {
+            let original = caps[0].to_string();
+
+            let mut src = None;
+            let mut title = None;
+            for attribute in ATTRIBUTE_REGEX.captures_iter(&caps[1]) {
+                let value = attribute
+                    .get(2)
+                    .or_else(|| attribute.get(3))
+                    .map_or(String::new(), |m| unescape(m.as_str()));
+                if attribute[1].eq_ignore_ascii_case("src") {
+                    src = Some(value);
+                } else {
+                    title = Some(value);
+                }
+            }
+
+            // Protocol-relative embeds (`//www.youtube.com/...`) are common
+            let Some(url) = src.and_then(|src| base_url.join(&src).ok()) else {
+                return original;
+            };
+            let Some(host) = url.host_str().filter(|_| url.scheme() == "https") else {
+                return original;
+            };
+            if live_hosts.iter().any(|h| h.eq_ignore_ascii_case(host)) {
+                return original;
+            }
+            let Some(provider) = providers.iter().find(|p| p.serves(host)) else {
+                return original;
+            };
+
+            let label = title
+                .map(|title| title.trim().to_string())
+                .filter(|title| !title.is_empty())
+                .unwrap_or_else(|| format!("{} embed", provider.name));
+            let src = escape(url.as_str());
+            format!(
+                r#"<figure data-embed-provider="{}" data-embed-src="{src}"><a href="{src}">{}</a></figure>"#,
+                escape(&provider.id),
+                escape(&label)
+            )
+        })
+        .to_string()
+}
+
+/// Decode the entities an HTML serializer writes into attribute values
+fn unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://blog.example.com/post").unwrap()
+    }
+
+    #[test]
+    fn test_replace_embeds_known_provider() {
+        let html = r#"<p>Demo:</p><iframe width="560" src="//www.youtube.com/embed/abc?start=5&amp;rel=0" title="Talk"></iframe>"#;
+        let replaced = replace_embeds(html, &base(), &default_providers(), &[]);
+
+        assert_eq!(
+            replaced,
+            r#"<p>Demo:</p><figure data-embed-provider="youtube" data-embed-src="https://www.youtube.com/embed/abc?start=5&amp;rel=0"><a href="https://www.youtube.com/embed/abc?start=5&amp;rel=0">Talk</a></figure>"#
+        );
+    }
+
+    #[test]
+    fn test_replace_embeds_defaults_label_to_provider() {
+        let html = r#"<iframe src="https://codepen.io/alice/embed/xyz"></iframe>"#;
+        let replaced = replace_embeds(html, &base(), &default_providers(), &[]);
+
+        assert!(replaced.contains(r#"data-embed-provider="codepen""#));
+        assert!(replaced.contains(">CodePen embed</a>"));
+    }
+
+    #[test]
+    fn test_replace_embeds_leaves_other_iframes() {
+        let html = r#"<iframe src="https://ads.example/frame"></iframe>"#;
+        assert_eq!(
+            replace_embeds(html, &base(), &default_providers(), &[]),
+            html
+        );
+
+        let live = r#"<iframe src="https://player.vimeo.com/video/1"></iframe>"#;
+        assert_eq!(
+            replace_embeds(
+                live,
+                &base(),
+                &default_providers(),
+                &["player.vimeo.com".to_string()]
+            ),
+            live
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use regex::Regex;
+use scraper::{Html, Selector};
+use std::{collections::HashMap, sync::LazyLock};
+use url::Url;
+
+static IMG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)<img\b([^>]*)>").unwrap());
+
+static ATTRIBUTE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\s([a-z][a-z0-9_-]*)\s*=\s*"([^"]*)""#).unwrap());
+
+static SIZE_ATTRIBUTE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\s(?:width|height)\s*=\s*"[^"]*""#).unwrap());
+
+static STYLE_SIZE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)(?:^|;)\s*(width|height)\s*:\s*(\d+(?:\.\d+)?)px").unwrap());
+
+/// Hosts that only serve analytics beacons, never content
+const TRACKER_HOSTS: [&str; 10] = [
+    "pixel.wp.com",
+    "stats.wp.com",
+    "www.google-analytics.com",
+    "pixel.quantserve.com",
+    "sb.scorecardresearch.com",
+    "bat.bing.com",
+    "ct.pinterest.com",
+    "px.ads.linkedin.com",
+    "googleads.g.doubleclick.net",
+    "pixel.mathtag.com",
+];
+
+/// Largest image, on either side, that counts as a tracking pixel
+const MAX_PIXEL_SIZE: u32 = 2;
+
+/// Intrinsic size of an image in CSS pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Sizes the fetched page gives its images, keyed by absolute `src`. Lazy
+/// loaders and inline styles carry sizes readability drops, so they're read
+/// from the full document before extraction.
+pub fn image_sizes(document: &Html, base: &Url) -> HashMap<String, ImageSize> {
+    let Ok(selector) = Selector::parse("img") else {
+        return HashMap::new();
+    };
+
+    let mut sizes = HashMap::new();
+    for element in document.select(&selector) {
+        let element = element.value();
+        let dimension = |name: &str| {
+            element
+                .attr(name)
+                .and_then(parse_dimension)
+                .or_else(|| {
+                    element
+                        .attr(&format!("data-{}", name))
+                        .and_then(parse_dimension)
+                })
+                .or_else(|| {
+                    element
+                        .attr("style")
+                        .and_then(|style| style_size(style, name))
+                })
+        };
+        let (Some(width), Some(height)) = (dimension("width"), dimension("height")) else {
+            continue;
+        };
+        // Lazy loaders keep the real URL in `data-src` behind a placeholder
+        for src in ["src", "data-src"]
+            .into_iter()
+            .filter_map(|name| element.attr(name))
+            .filter_map(|src| base.join(src.trim()).ok())
+        {
+            sizes
+                .entry(src.to_string())
+                .or_insert(ImageSize { width, height });
+        }
+    }
+    sizes
+}
+
+/// Give each image in cleaned HTML explicit `width` and `height` attributes
+/// when its size is known, from its own attributes or `sizes`, so readers
+/// can reserve space before it loads. Tracking pixels are removed.
+pub fn annotate_images(html: &str, sizes: &HashMap<String, ImageSize>) -> String {
+    IMG_REGEX
+        .replace_all(html, |caps: &regex::Captures| {
+            let attributes: HashMap<String, String> = ATTRIBUTE_REGEX
+                .captures_iter(&caps[1])
+                .map(|attribute| (attribute[1].to_ascii_lowercase(), unescape(&attribute[2])))
+                .collect();
+            let src = attributes.get("src").map(String::as_str).unwrap_or("");
+
+            let own_size = match (
+                attributes.get("width").and_then(|w| parse_dimension(w)),
+                attributes.get("height").and_then(|h| parse_dimension(h)),
+            ) {
+                (Some(width), Some(height)) => Some(ImageSize { width, height }),
+                _ => None,
+            };
+            let size = own_size.or_else(|| sizes.get(src).copied());
+
+            if is_tracking_pixel(src, size) {
+                return String::new();
+            }
+            match size {
+                Some(size) if own_size.is_none() => {
+                    let kept = SIZE_ATTRIBUTE_REGEX.replace_all(&caps[1], "");
+                    format!(
+                        r#"<img{} width="{}" height="{}">"#,
+                        kept.trim_end_matches('/').trim_end(),
+                        size.width,
+                        size.height
+                    )
+                }
+                _ => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// Whether an image exists only to record the page view: a pixel-sized
+/// image, or anything served by a known analytics host
+fn is_tracking_pixel(src: &str, size: Option<ImageSize>) -> bool {
+    if size.is_some_and(|size| size.width <= MAX_PIXEL_SIZE && size.height <= MAX_PIXEL_SIZE) {
+        return true;
+    }
+    Url::parse(src).is_ok_and(|url| {
+        url.host_str().is_some_and(|host| {
+            TRACKER_HOSTS
+                .iter()
+                .any(|tracker| tracker.eq_ignore_ascii_case(host))
+        })
+    })
+}
+
+/// Whole pixels in a `width` or `height` attribute; percentages don't count
+fn parse_dimension(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let value = value.strip_suffix("px").unwrap_or(value).trim();
+    let pixels: f32 = value.parse().ok()?;
+    (pixels.is_finite() && pixels >= 0.0).then(|| pixels.round() as u32)
+}
+
+fn style_size(style: &str, name: &str) -> Option<u32> {
+    STYLE_SIZE_REGEX
+        .captures_iter(style)
+        .find(|caps| caps[1].eq_ignore_ascii_case(name))
+        .and_then(|caps| parse_dimension(&caps[2]))
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sizes(page: &str) -> HashMap<String, ImageSize> {
+        let base = Url::parse("https://example.com/posts/1").unwrap();
+        image_sizes(&Html::parse_document(page), &base)
+    }
+
+    #[test]
+    fn test_image_sizes_from_page() {
+        let sizes = sizes(
+            r#"
+            <img src="/a.jpg" width="640" height="480">
+            <img data-src="/b.jpg" data-width="800" data-height="600">
+            <img src="/c.jpg" style="width: 320px; height:200.4px">
+            <img src="/d.jpg" width="100%">
+            "#,
+        );
+        assert_eq!(
+            sizes["https://example.com/a.jpg"],
+            ImageSize {
+                width: 640,
+                height: 480
+            }
+        );
+        assert_eq!(sizes["https://example.com/b.jpg"].width, 800);
+        assert_eq!(sizes["https://example.com/c.jpg"].height, 200);
+        assert!(!sizes.contains_key("https://example.com/d.jpg"));
+    }
+
+    #[test]
+    fn test_annotate_images_adds_known_sizes() {
+        let sizes =
+            sizes(r#"<img data-src="/a.jpg?w=1&amp;h=2" data-width="800" data-height="600">"#);
+        let html = annotate_images(
+            r#"<p><img src="https://example.com/a.jpg?w=1&amp;h=2" alt="A" width="100%"></p><img src="https://example.com/b.jpg">"#,
+            &sizes,
+        );
+        assert_eq!(
+            html,
+            r#"<p><img src="https://example.com/a.jpg?w=1&amp;h=2" alt="A" width="800" height="600"></p><img src="https://example.com/b.jpg">"#
+        );
+    }
+
+    #[test]
+    fn test_annotate_images_removes_tracking_pixels() {
+        let html = annotate_images(
+            r#"<p>Text<img src="https://example.com/open.gif" width="1" height="1"><img src="https://pixel.wp.com/g.gif"><img src="https://example.com/a.jpg" width="640" height="480"></p>"#,
+            &HashMap::new(),
+        );
+        assert_eq!(
+            html,
+            r#"<p>Text<img src="https://example.com/a.jpg" width="640" height="480"></p>"#
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use scraper::{Html, Selector};
+use std::collections::HashSet;
+use url::Url;
+
+use crate::urlnorm::canonicalize;
+
+/// Most links kept per page; link farms and sitemaps aren't worth recording
+/// in full
+pub const MAX_OUTBOUND_LINKS: usize = 500;
+
+/// The http(s) links in extracted content, in document order, resolved
+/// against `base` and without repeats or links back to the page itself.
+/// Links count as the same when they normalize to the same page, as for the
+/// fetch cache.
+pub fn outbound_links(html: &str, base: &Url) -> Vec<Url> {
+    let Ok(selector) = Selector::parse("a[href]") else {
+        return Vec::new();
+    };
+    let fragment = Html::parse_fragment(html);
+
+    let mut seen: HashSet<String> = canonicalize(base.as_str()).into_iter().collect();
+    let mut links = Vec::new();
+    for element in fragment.select(&selector) {
+        let Some(href) = element.value().attr("href") else {
+            continue;
+        };
+        let Ok(url) = base.join(href.trim()) else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            continue;
+        }
+        if let Some(key) = canonicalize(url.as_str())
+            && seen.insert(key)
+        {
+            links.push(url);
+            if links.len() == MAX_OUTBOUND_LINKS {
+                break;
+            }
+        }
+    }
+
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(html: &str) -> Vec<String> {
+        let base = Url::parse("https://example.com/posts/1").unwrap();
+        outbound_links(html, &base)
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_outbound_links_resolves_and_dedupes() {
+        let html = r##"
+            <p>See <a href="https://other.example/a?utm_source=x">this</a>,
+            <a href="/posts/2">the next post</a>,
+            <a href="https://other.example/a#section">this again</a>
+            and <a href="#top">the top</a>.</p>
+        "##;
+        assert_eq!(
+            links(html),
+            [
+                "https://other.example/a?utm_source=x",
+                "https://example.com/posts/2"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outbound_links_skips_other_schemes() {
+        let html = r#"<a href="mailto:me@example.com">mail</a><a href="javascript:void(0)">js</a>"#;
+        assert!(links(html).is_empty());
+    }
+
+    #[test]
+    fn test_outbound_links_are_capped() {
+        let html: String = (0..MAX_OUTBOUND_LINKS + 10)
+            .map(|n| format!(r#"<a href="https://example.org/{n}">{n}</a>"#))
+            .collect();
+        assert_eq!(links(&html).len(), MAX_OUTBOUND_LINKS);
+    }
+}
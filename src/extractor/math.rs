@@ -0,0 +1,116 @@
+use regex::Regex;
+use std::{borrow::Cow, sync::LazyLock};
+
+/// MathML presentation elements kept by the sanitizer
+pub const MATHML_TAGS: [&str; 26] = [
+    "math",
+    "semantics",
+    "annotation",
+    "mrow",
+    "mi",
+    "mn",
+    "mo",
+    "ms",
+    "mtext",
+    "mspace",
+    "msup",
+    "msub",
+    "msubsup",
+    "mfrac",
+    "msqrt",
+    "mroot",
+    "mover",
+    "munder",
+    "munderover",
+    "mtable",
+    "mtr",
+    "mtd",
+    "mstyle",
+    "mpadded",
+    "mphantom",
+    "menclose",
+];
+
+/// MathML attributes that affect rendering, as `(tag, attributes)`
+pub const MATHML_ATTRIBUTES: [(&str, &[&str]); 7] = [
+    ("math", &["display"]),
+    ("annotation", &["encoding"]),
+    (
+        "mo",
+        &["stretchy", "fence", "separator", "lspace", "rspace"],
+    ),
+    ("mspace", &["width"]),
+    ("mstyle", &["displaystyle", "scriptlevel"]),
+    ("menclose", &["notation"]),
+    ("mtd", &["columnspan", "rowspan"]),
+];
+
+/// MathJax v2 keeps its source in `<script type="math/tex">`, which every
+/// later stage strips along with other scripts
+static TEX_SCRIPT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?is)<script[^>]*\btype\s*=\s*["']math/tex(; *mode=display)?["'][^>]*>(.*?)</script>"#,
+    )
+    .unwrap()
+});
+
+/// `$$...$$`, `\(...\)` and `\[...\]`; single dollars are too easily prices
+static TEX_DELIMITER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)\$\$.+?\$\$|\\\(.+?\\\)|\\\[.+?\\\]").unwrap());
+
+/// Rewrite MathJax script blocks into text with LaTeX delimiters so the
+/// formulas survive extraction and sanitizing
+pub fn preserve_tex_scripts(html: &str) -> Cow<'_, str> {
+    if !html.contains("math/tex") {
+        return Cow::Borrowed(html);
+    }
+
+    TEX_SCRIPT_REGEX.replace_all(html, |caps: &regex::Captures| {
+        // Script content is raw text; it needs escaping once it's markup
+        let tex = caps[2]
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+        if caps.get(1).is_some() {
+            format!(r"<span>\[{}\]</span>", tex)
+        } else {
+            format!(r"<span>\({}\)</span>", tex)
+        }
+    })
+}
+
+/// Whether cleaned content contains MathML or LaTeX-delimited formulas, so
+/// reader clients know to load a math renderer
+pub fn has_math(clean_html: Option<&str>, clean_text: Option<&str>) -> bool {
+    let mathml = clean_html.is_some_and(|html| html.contains("<math"));
+    mathml || clean_text.is_some_and(|text| TEX_DELIMITER_REGEX.is_match(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preserve_tex_scripts() {
+        let html = r#"<p>Energy <script type="math/tex">E = mc^2</script> and</p><script type="math/tex; mode=display">\sum_i x_i</script>"#;
+        assert_eq!(
+            preserve_tex_scripts(html),
+            r"<p>Energy <span>\(E = mc^2\)</span> and</p><span>\[\sum_i x_i\]</span>"
+        );
+
+        let plain = "<p>No math</p><script>track()</script>";
+        assert_eq!(preserve_tex_scripts(plain), plain);
+    }
+
+    #[test]
+    fn test_has_math() {
+        assert!(has_math(Some("<p><math><mi>x</mi></math></p>"), None));
+        assert!(has_math(None, Some(r"where \(a^2 + b^2 = c^2\) holds")));
+        assert!(has_math(None, Some("$$\\int_0^1 x\\,dx$$")));
+        assert!(!has_math(
+            Some("<p>Costs $5 or $10</p>"),
+            Some("Costs $5 or $10")
+        ));
+        assert!(!has_math(None, None));
+    }
+}
@@ -0,0 +1,174 @@
+use scraper::{Html, Selector};
+use url::Url;
+
+use crate::extractor::reader;
+
+/// What a page says about itself in its head, without extracting content
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    pub site_name: Option<String>,
+    /// Absolute http(s) URL of the page's preview image
+    pub lead_image: Option<Url>,
+    /// Absolute http(s) URL of the icon the page links to, if any
+    pub icon: Option<Url>,
+    /// Absolute http(s) URL of the RSS or Atom feed the page links to, if any
+    pub feed: Option<Url>,
+}
+
+/// Read the title, site name, lead image, icon and feed from a page's
+/// metadata, resolving relative URLs against `base`
+pub fn page_metadata(document: &Html, base: &Url) -> PageMetadata {
+    PageMetadata {
+        title: reader::extract_title(document)
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty()),
+        site_name: reader::extract_site_name(document),
+        lead_image: extract_lead_image(document, base),
+        icon: extract_icon(document, base),
+        feed: extract_feed(document, base),
+    }
+}
+
+fn extract_lead_image(document: &Html, base: &Url) -> Option<Url> {
+    let candidates = [
+        ("meta[property='og:image:secure_url']", "content"),
+        ("meta[property='og:image']", "content"),
+        ("meta[name='twitter:image']", "content"),
+        ("link[rel='image_src']", "href"),
+    ];
+
+    for (selector, attribute) in candidates {
+        let Ok(selector) = Selector::parse(selector) else {
+            continue;
+        };
+        for element in document.select(&selector) {
+            if let Some(value) = element.value().attr(attribute)
+                && !value.trim().is_empty()
+                && let Ok(url) = base.join(value.trim())
+                && matches!(url.scheme(), "http" | "https")
+            {
+                return Some(url);
+            }
+        }
+    }
+
+    None
+}
+
+/// The first `<link>` whose rel includes `icon`: `icon`, `shortcut icon`
+/// and `apple-touch-icon` all count
+fn extract_icon(document: &Html, base: &Url) -> Option<Url> {
+    let selector = Selector::parse("link[rel][href]").ok()?;
+    document.select(&selector).find_map(|element| {
+        let rel = element.value().attr("rel")?;
+        let href = element.value().attr("href")?.trim();
+        if href.is_empty()
+            || !rel
+                .split_ascii_whitespace()
+                .any(|token| token.eq_ignore_ascii_case("icon"))
+        {
+            return None;
+        }
+        base.join(href)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+    })
+}
+
+/// The first `<link rel="alternate">` of an RSS or Atom type
+fn extract_feed(document: &Html, base: &Url) -> Option<Url> {
+    let selector = Selector::parse("link[rel][type][href]").ok()?;
+    document.select(&selector).find_map(|element| {
+        let rel = element.value().attr("rel")?;
+        let kind = element.value().attr("type")?.trim();
+        let href = element.value().attr("href")?.trim();
+        if href.is_empty()
+            || !rel
+                .split_ascii_whitespace()
+                .any(|token| token.eq_ignore_ascii_case("alternate"))
+            || !(kind.eq_ignore_ascii_case("application/rss+xml")
+                || kind.eq_ignore_ascii_case("application/atom+xml"))
+        {
+            return None;
+        }
+        base.join(href)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(head: &str) -> PageMetadata {
+        let html = format!("<html><head>{}</head><body></body></html>", head);
+        let base = Url::parse("https://example.com/posts/1").unwrap();
+        page_metadata(&Html::parse_document(&html), &base)
+    }
+
+    #[test]
+    fn test_page_metadata_from_open_graph() {
+        let metadata = metadata(
+            r#"<title>Fallback</title>
+            <meta property="og:title" content=" A Post ">
+            <meta property="og:site_name" content="Example">
+            <meta property="og:image" content="/images/lead.png">"#,
+        );
+        assert_eq!(metadata.title.as_deref(), Some("A Post"));
+        assert_eq!(metadata.site_name.as_deref(), Some("Example"));
+        assert_eq!(
+            metadata.lead_image.map(String::from).as_deref(),
+            Some("https://example.com/images/lead.png")
+        );
+    }
+
+    #[test]
+    fn test_page_metadata_skips_unusable_images() {
+        let page = metadata(
+            r#"<title>Plain</title>
+            <meta property="og:image" content="data:image/png;base64,AAAA">
+            <meta name="twitter:image" content="https://cdn.example.com/card.jpg">"#,
+        );
+        assert_eq!(page.title.as_deref(), Some("Plain"));
+        assert_eq!(
+            page.lead_image.map(String::from).as_deref(),
+            Some("https://cdn.example.com/card.jpg")
+        );
+
+        assert_eq!(metadata("").lead_image, None);
+    }
+
+    #[test]
+    fn test_page_metadata_icon() {
+        let page = metadata(
+            r#"<link rel="stylesheet" href="/site.css">
+            <link rel="icon" href="data:image/png;base64,AAAA">
+            <link rel="Shortcut Icon" href="/static/favicon.png">
+            <link rel="apple-touch-icon" href="/touch.png">"#,
+        );
+        assert_eq!(
+            page.icon.map(String::from).as_deref(),
+            Some("https://example.com/static/favicon.png")
+        );
+
+        assert_eq!(metadata(r#"<link rel="iconic" href="/x.png">"#).icon, None);
+    }
+
+    #[test]
+    fn test_page_metadata_feed() {
+        let page = metadata(
+            r#"<link rel="alternate" hreflang="de" href="/de/posts/1">
+            <link rel="alternate" type="application/json" href="/feed.json">
+            <link rel="alternate" type="application/atom+xml" href="/feed.atom">
+            <link rel="alternate" type="application/rss+xml" href="/rss.xml">"#,
+        );
+        assert_eq!(
+            page.feed.map(String::from).as_deref(),
+            Some("https://example.com/feed.atom")
+        );
+
+        assert_eq!(metadata("").feed, None);
+    }
+}
@@ -1,33 +1,88 @@
 pub mod cleaner;
+pub mod diff;
+pub mod embeds;
+pub mod images;
 pub mod language;
+pub mod links;
+pub mod math;
+pub mod metadata;
 pub mod model;
+pub mod nsfw;
+pub mod outline;
 pub mod reader;
 pub mod reject;
+pub mod text_map;
 
 #[cfg(test)]
 mod tests;
 
+pub use cleaner::SanitizePolicy;
+pub use diff::{CONTENT_CHANGE_THRESHOLD, TextDiff, diff_text};
+pub use embeds::EmbedProvider;
+pub use links::outbound_links;
+pub use metadata::{PageMetadata, page_metadata};
 pub use model::ExtractedContent;
+pub use outline::Heading;
+pub use text_map::TextMap;
 
-use crate::fetcher::types::PageResponse;
+use scraper::Html;
+
+use crate::{entities::ExtractionFailure, fetcher::types::PageResponse};
 
 pub async fn extract(resp: &PageResponse) -> Option<ExtractedContent> {
+    extract_or_reason(resp).await.ok()
+}
+
+/// Like [`extract`], but reports why a page was rejected so callers can
+/// surface the failure instead of dropping it.
+pub async fn extract_or_reason(resp: &PageResponse) -> Result<ExtractedContent, ExtractionFailure> {
+    extract_with_policy(resp, &SanitizePolicy::default()).await
+}
+
+/// Like [`extract_or_reason`], sanitizing the content with `policy`
+pub async fn extract_with_policy(
+    resp: &PageResponse,
+    policy: &SanitizePolicy,
+) -> Result<ExtractedContent, ExtractionFailure> {
+    // MathJax formulas live in script tags that every stage below strips
+    let source = math::preserve_tex_scripts(&resp.body_utf8);
+
+    // Parse the page once; metadata, fallback content and the rendering
+    // check all read from this tree
+    let document = Html::parse_document(&source);
+
     // 1. Extract readable content using readability
-    let mut result = reader::extract(&resp.body_utf8, resp.url_final.clone())?;
+    let Some(mut result) = reader::extract(&source, &document, &resp.url_final) else {
+        return Err(if reject::document_requires_rendering(&document, "") {
+            ExtractionFailure::RenderRequired
+        } else {
+            ExtractionFailure::TooShort
+        });
+    };
 
-    // 2. Clean and sanitize HTML, resolve links
-    cleaner::sanitize_and_resolve_links(&mut result, &resp.url_final);
+    // 2. Clean and sanitize HTML, resolving links in the same pass
+    cleaner::sanitize_with_policy(&mut result, &resp.url_final, policy);
+    let image_sizes = images::image_sizes(&document, &resp.url_final);
+    result.html = images::annotate_images(&result.html, &image_sizes);
+    let (html, outline) = outline::build_outline(&result.html);
+    result.html = html;
 
     // 3. Detect language
     let detected_language = language::detect_language(&result.text);
 
-    // 4. Check if content should be rejected
-    if reject::should_reject(&result.title, &result.text) {
-        return None;
+    // 4. Check if content should be rejected; an app shell with no text
+    //    needs a renderer rather than being written off as too short
+    if let Some(reason) = reject::rejection_reason(&result.title, &result.text) {
+        if reason == ExtractionFailure::TooShort
+            && reject::document_requires_rendering(&document, &result.text)
+        {
+            return Err(ExtractionFailure::RenderRequired);
+        }
+        return Err(reason);
     }
 
     // 5. Create final extracted content
-    Some(ExtractedContent {
+    Ok(ExtractedContent {
         url: resp.url_final.clone(),
         title: result.title,
         site_name: result.site_name,
@@ -36,5 +91,6 @@ pub async fn extract(resp: &PageResponse) -> Option<ExtractedContent> {
         text: result.text,
         html: result.html,
         fetched_at: resp.fetched_at,
+        outline,
     })
 }
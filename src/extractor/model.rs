@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use crate::extractor::outline::Heading;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractedContent {
     pub url: Url,
@@ -12,6 +14,9 @@ pub struct ExtractedContent {
     pub text: String,
     pub html: String,
     pub fetched_at: DateTime<Utc>,
+    /// Table of contents of `html`
+    #[serde(default)]
+    pub outline: Vec<Heading>,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,166 @@
+use scraper::{Html, Selector};
+use url::Url;
+
+use crate::extractor::ExtractedContent;
+
+/// Terms that on their own mark a host or title as adult
+const STRONG_TERMS: [&str; 5] = ["porn", "xxx", "nsfw", "hentai", "erotica"];
+
+/// Terms counted towards the explicit-vocabulary density of the body text
+const BODY_TERMS: [&str; 10] = [
+    "porn", "porno", "xxx", "nsfw", "hentai", "erotic", "erotica", "nude", "nudes", "camgirl",
+];
+
+/// `rating` meta values that self-label a page as adult, including the RTA
+/// label adult sites are asked to publish
+const ADULT_RATINGS: [&str; 4] = [
+    "adult",
+    "mature",
+    "restricted",
+    "rta-5042-1996-1400-1577-rta",
+];
+
+/// Body texts need at least this many explicit terms...
+const MIN_BODY_HITS: usize = 3;
+/// ...making up at least one word in this many
+const MAX_WORDS_PER_HIT: usize = 200;
+
+/// Heuristic guess at whether a page is adult content, from the page's own
+/// metadata, its host and title, and how much explicit vocabulary the body
+/// uses. Meant for blurring thumbnails, not for blocking: it favours missing
+/// a page over flagging an ordinary one.
+pub fn looks_nsfw(raw_html: &str, extracted: &ExtractedContent) -> bool {
+    let document = Html::parse_document(raw_html);
+    classify(&document, &extracted.url, &extracted.title, &extracted.text)
+}
+
+fn classify(document: &Html, url: &Url, title: &str, text: &str) -> bool {
+    adult_metadata(document)
+        || url
+            .host_str()
+            .is_some_and(|host| contains_strong_term(&host.to_lowercase()))
+        || contains_strong_term(&title.to_lowercase())
+        || explicit_body(text)
+}
+
+fn adult_metadata(document: &Html) -> bool {
+    let rated_adult = Selector::parse(r#"meta[name="rating" i], meta[name="content-rating" i]"#)
+        .is_ok_and(|selector| {
+            document.select(&selector).any(|meta| {
+                meta.value().attr("content").is_some_and(|content| {
+                    ADULT_RATINGS.contains(&content.trim().to_lowercase().as_str())
+                })
+            })
+        });
+
+    let age_restricted =
+        Selector::parse(r#"meta[property="og:restrictions:age"]"#).is_ok_and(|selector| {
+            document.select(&selector).any(|meta| {
+                meta.value()
+                    .attr("content")
+                    .and_then(|age| age.trim().trim_end_matches('+').parse::<u32>().ok())
+                    .is_some_and(|age| age >= 18)
+            })
+        });
+
+    rated_adult || age_restricted
+}
+
+fn contains_strong_term(value: &str) -> bool {
+    STRONG_TERMS.iter().any(|term| value.contains(term))
+}
+
+fn explicit_body(text: &str) -> bool {
+    let mut words = 0;
+    let mut hits = 0;
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        if word.is_empty() {
+            continue;
+        }
+        words += 1;
+        if BODY_TERMS.contains(&word.to_lowercase().as_str()) {
+            hits += 1;
+        }
+    }
+    hits >= MIN_BODY_HITS && hits * MAX_WORDS_PER_HIT >= words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn test_classify_metadata() {
+        let rated = Html::parse_document(
+            r#"<head><meta name="RATING" content="RTA-5042-1996-1400-1577-RTA"></head>"#,
+        );
+        assert!(classify(
+            &rated,
+            &url("https://example.com/a"),
+            "Title",
+            "Text"
+        ));
+
+        let age = Html::parse_document(
+            r#"<head><meta property="og:restrictions:age" content="18+"></head>"#,
+        );
+        assert!(classify(
+            &age,
+            &url("https://example.com/a"),
+            "Title",
+            "Text"
+        ));
+
+        let general =
+            Html::parse_document(r#"<head><meta name="rating" content="general"></head>"#);
+        assert!(!classify(
+            &general,
+            &url("https://example.com/a"),
+            "Title",
+            "Text"
+        ));
+    }
+
+    #[test]
+    fn test_classify_host_and_title() {
+        let document = Html::parse_document("<p>Text</p>");
+        assert!(classify(
+            &document,
+            &url("https://www.example-xxx.com/"),
+            "Title",
+            "Text"
+        ));
+        assert!(classify(
+            &document,
+            &url("https://example.com/"),
+            "NSFW gallery",
+            "Text"
+        ));
+        assert!(!classify(
+            &document,
+            &url("https://www.essex.gov.uk/"),
+            "Sussex news",
+            "Text"
+        ));
+    }
+
+    #[test]
+    fn test_classify_body_density() {
+        let document = Html::parse_document("<p>Text</p>");
+        let page = url("https://example.com/post");
+
+        let explicit = "nude photos and more nudes, erotic stories, porn links";
+        assert!(classify(&document, &page, "Title", explicit));
+
+        // An article mentioning the topic a few times in passing
+        let article = format!(
+            "{} Regulators discussed porn filters, nude art in museums and erotic literature.",
+            "The committee met to review the policy. ".repeat(200)
+        );
+        assert!(!classify(&document, &page, "Policy review", &article));
+    }
+}
@@ -0,0 +1,145 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::LazyLock};
+use utoipa::ToSchema;
+
+static HEADING_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<h([1-6])([^>]*)>(.*?)</h[1-6]\s*>").unwrap());
+
+static ID_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\bid\s*=\s*"([^"]*)""#).unwrap());
+
+static TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+/// One entry in an article's table of contents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Heading {
+    /// The heading element's `id` in the clean HTML
+    pub id: String,
+    /// 1 for `<h1>` through 6 for `<h6>`
+    pub level: u8,
+    pub text: String,
+    /// Fragment linking to the heading, e.g. `#getting-started`
+    pub anchor: String,
+}
+
+/// Collect the headings of cleaned HTML into an outline, giving each heading
+/// an `id` so the outline's anchors resolve. Headings that already carry an
+/// id keep it; empty headings are skipped.
+pub fn build_outline(html: &str) -> (String, Vec<Heading>) {
+    let mut outline = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    let html = HEADING_REGEX.replace_all(html, |caps: &regex::Captures| {
+        let level: u8 = caps[1].parse().unwrap_or(1);
+        let attributes = &caps[2];
+        let inner = &caps[3];
+
+        let text = heading_text(inner);
+        if text.is_empty() {
+            return caps[0].to_string();
+        }
+
+        let existing_id = ID_REGEX
+            .captures(attributes)
+            .map(|id| id[1].to_string())
+            .filter(|id| !id.is_empty());
+        let id = match &existing_id {
+            Some(id) => {
+                // Generated slugs must not collide with ids the page chose
+                *seen.entry(id.clone()).or_insert(0) += 1;
+                id.clone()
+            }
+            None => unique_slug(&text, &mut seen),
+        };
+
+        outline.push(Heading {
+            anchor: format!("#{}", id),
+            id: id.clone(),
+            level,
+            text,
+        });
+
+        if existing_id.is_some() {
+            caps[0].to_string()
+        } else {
+            format!(r#"<h{level}{attributes} id="{id}">{inner}</h{level}>"#)
+        }
+    });
+
+    (html.into_owned(), outline)
+}
+
+/// Visible text of a heading's inner HTML
+fn heading_text(inner: &str) -> String {
+    let text = TAG_REGEX.replace_all(inner, "");
+    let text = text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// URL-friendly slug of `text`, suffixed with a counter when an earlier
+/// heading already took it
+fn unique_slug(text: &str, seen: &mut HashMap<String, usize>) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = match slug.trim_end_matches('-') {
+        "" => "section".to_string(),
+        slug => slug.to_string(),
+    };
+
+    let count = seen.entry(slug.clone()).or_insert(0);
+    *count += 1;
+    if *count == 1 {
+        slug
+    } else {
+        format!("{}-{}", slug, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_outline() {
+        let html = r#"<h1>Getting Started</h1><p>Intro</p><h2>Install &amp; <em>Configure</em></h2><h3 id="faq">FAQ</h3><h2>Getting started</h2><h2> </h2>"#;
+        let (html, outline) = build_outline(html);
+
+        let ids: Vec<&str> = outline.iter().map(|h| h.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "getting-started",
+                "install-configure",
+                "faq",
+                "getting-started-2"
+            ]
+        );
+        assert_eq!(outline[1].text, "Install & Configure");
+        assert_eq!(outline[1].level, 2);
+        assert_eq!(outline[1].anchor, "#install-configure");
+
+        assert!(html.contains(r#"<h1 id="getting-started">Getting Started</h1>"#));
+        assert!(html.contains(r#"<h3 id="faq">FAQ</h3>"#));
+        assert!(html.contains(r#"<h2 id="getting-started-2">Getting started</h2>"#));
+    }
+
+    #[test]
+    fn test_build_outline_without_headings() {
+        let (html, outline) = build_outline("<p>Just text</p>");
+        assert_eq!(html, "<p>Just text</p>");
+        assert!(outline.is_empty());
+    }
+}
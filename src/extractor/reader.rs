@@ -4,12 +4,17 @@ use url::Url;
 
 use crate::extractor::model::ReadabilityResult;
 
-pub fn extract(html: &str, url: Url) -> Option<ReadabilityResult> {
+/// Pull the readable content out of a page. `document` is the caller's parse
+/// of `html`, reused for the metadata and fallback stages; readability needs
+/// the source since it builds its own tree.
+pub fn extract(html: &str, document: &Html, url: &Url) -> Option<ReadabilityResult> {
+    let site_name = extract_site_name(document);
+
     // Try readability first
-    if let Ok(article) = extractor::extract(&mut html.as_bytes(), &url) {
+    if let Ok(article) = extractor::extract(&mut html.as_bytes(), url) {
         return Some(ReadabilityResult {
             title: article.title,
-            site_name: extract_site_name(html),
+            site_name,
             byline: None, // readability crate doesn't provide byline
             text: article.text,
             html: article.content,
@@ -17,12 +22,10 @@ pub fn extract(html: &str, url: Url) -> Option<ReadabilityResult> {
     }
 
     // Fallback to basic scraping if readability fails
-    fallback_extract(html)
+    fallback_extract(document, site_name)
 }
 
-fn extract_site_name(html: &str) -> Option<String> {
-    let document = Html::parse_document(html);
-
+pub(crate) fn extract_site_name(document: &Html) -> Option<String> {
     // Try og:site_name first
     let selector = Selector::parse("meta[property='og:site_name']").ok()?;
     if let Some(element) = document.select(&selector).next()
@@ -47,14 +50,12 @@ fn extract_site_name(html: &str) -> Option<String> {
     None
 }
 
-fn fallback_extract(html: &str) -> Option<ReadabilityResult> {
-    let document = Html::parse_document(html);
-
+fn fallback_extract(document: &Html, site_name: Option<String>) -> Option<ReadabilityResult> {
     // Extract title
-    let title = extract_title(&document)?;
+    let title = extract_title(document)?;
 
     // Extract main content using basic heuristics
-    let (text, html_content) = extract_main_content(&document);
+    let (text, html_content) = extract_main_content(document);
 
     if text.trim().is_empty() {
         return None;
@@ -62,14 +63,14 @@ fn fallback_extract(html: &str) -> Option<ReadabilityResult> {
 
     Some(ReadabilityResult {
         title,
-        site_name: extract_site_name(html),
+        site_name,
         byline: None,
         text,
         html: html_content,
     })
 }
 
-fn extract_title(document: &Html) -> Option<String> {
+pub(crate) fn extract_title(document: &Html) -> Option<String> {
     // Try og:title first
     if let Ok(selector) = Selector::parse("meta[property='og:title']") {
         for element in document.select(&selector) {
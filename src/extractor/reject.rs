@@ -1,26 +1,72 @@
+use scraper::{Html, Selector};
+
+use crate::entities::ExtractionFailure;
+
 const MIN_CONTENT_LENGTH: usize = 250;
 const MIN_WORD_COUNT: usize = 50;
 const MAX_BOILERPLATE_RATIO: f64 = 0.3;
+/// Script-heavy pages with this many `<script>` tags and no readable text are
+/// assumed to be client-side rendered.
+const MIN_SCRIPT_TAGS_FOR_SPA: usize = 5;
 
 pub fn should_reject(title: &str, text: &str) -> bool {
+    rejection_reason(title, text).is_some()
+}
+
+/// Why extracted content isn't worth keeping, or `None` if it is.
+pub fn rejection_reason(title: &str, text: &str) -> Option<ExtractionFailure> {
     // Reject if content is too short
     if text.chars().count() < MIN_CONTENT_LENGTH {
-        return true;
+        return Some(ExtractionFailure::TooShort);
     }
 
     let word_count = text.split_whitespace().count();
 
     // Reject if both title is empty and word count is too low
     if title.trim().is_empty() && word_count < MIN_WORD_COUNT {
-        return true;
+        return Some(ExtractionFailure::TooShort);
     }
 
     // Reject if too much boilerplate content
     if has_too_much_boilerplate(text, word_count) {
-        return true;
+        return Some(ExtractionFailure::Boilerplate);
     }
 
-    false
+    None
+}
+
+/// Whether the page looks like a JavaScript app shell whose content only
+/// appears after rendering, given its raw HTML and whatever text was extracted.
+pub fn requires_rendering(html: &str, text: &str) -> bool {
+    document_requires_rendering(&Html::parse_document(html), text)
+}
+
+/// [`requires_rendering`] for a page that has already been parsed
+pub fn document_requires_rendering(document: &Html, text: &str) -> bool {
+    if text.chars().count() >= MIN_CONTENT_LENGTH {
+        return false;
+    }
+
+    let asks_for_javascript = Selector::parse("noscript").is_ok_and(|selector| {
+        document.select(&selector).any(|noscript| {
+            let message = noscript.text().collect::<String>().to_lowercase();
+            message.contains("enable javascript") || message.contains("javascript is required")
+        })
+    });
+    let empty_app_root = Selector::parse("div#root, div#app, div#__next").is_ok_and(|selector| {
+        document.select(&selector).any(|root| {
+            root.children().all(|child| {
+                child
+                    .value()
+                    .as_text()
+                    .is_some_and(|text| text.trim().is_empty())
+            })
+        })
+    });
+    let script_heavy = Selector::parse("script")
+        .is_ok_and(|selector| document.select(&selector).count() >= MIN_SCRIPT_TAGS_FOR_SPA);
+
+    asks_for_javascript || empty_app_root || script_heavy
 }
 
 fn has_too_much_boilerplate(text: &str, total_words: usize) -> bool {
@@ -108,4 +154,56 @@ mod tests {
         let good_content = "This is a high-quality article with substantial content that provides value to readers. ".repeat(10);
         assert!(!should_reject("Good Article Title", &good_content));
     }
+
+    #[test]
+    fn test_rejection_reason_classifies() {
+        assert_eq!(
+            rejection_reason("Title", "Short"),
+            Some(ExtractionFailure::TooShort)
+        );
+        let boilerplate_text =
+            "cookie consent privacy policy terms service gdpr tracking advertisement ".repeat(20);
+        assert_eq!(
+            rejection_reason("Title", &boilerplate_text),
+            Some(ExtractionFailure::Boilerplate)
+        );
+        assert_eq!(
+            rejection_reason("Title", &"Long enough content ".repeat(50)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_requires_rendering() {
+        let shell = r#"<html><body><noscript>You need to enable JavaScript to run this app.</noscript><div id="root"></div></body></html>"#;
+        assert!(requires_rendering(shell, ""));
+
+        let scripts = format!(
+            "<html><body>{}</body></html>",
+            "<script src=\"a.js\"></script>".repeat(6)
+        );
+        assert!(requires_rendering(&scripts, "Loading"));
+
+        let article = "<html><body><p>Plain static page</p></body></html>";
+        assert!(!requires_rendering(article, "Plain static page"));
+        assert!(!requires_rendering(
+            shell,
+            &"Long enough content ".repeat(50)
+        ));
+    }
+
+    #[test]
+    fn test_document_requires_rendering_checks_root_contents() {
+        let blank_root =
+            Html::parse_document("<html><body><div id=\"app\">\n  </div></body></html>");
+        assert!(document_requires_rendering(&blank_root, ""));
+
+        let server_rendered = Html::parse_document(
+            "<html><body><div id=\"app\"><p>Server rendered</p></div></body></html>",
+        );
+        assert!(!document_requires_rendering(
+            &server_rendered,
+            "Server rendered"
+        ));
+    }
 }
@@ -5,7 +5,8 @@ use reqwest::header::HeaderMap;
 use std::fs;
 use url::Url;
 
-use crate::extractor::extract;
+use crate::entities::ExtractionFailure;
+use crate::extractor::{extract, extract_or_reason};
 use crate::fetcher::types::{Charset, PageResponse};
 
 #[tokio::test]
@@ -84,6 +85,29 @@ async fn test_minimal_valid_content() {
     assert!(content.text.len() > 250);
 }
 
+#[tokio::test]
+async fn test_reject_app_shell_as_render_required() {
+    let html = format!(
+        r#"<!DOCTYPE html><html><head><title>Dashboard</title>{}</head><body><noscript>You need to enable JavaScript to run this app.</noscript><div id="root"></div></body></html>"#,
+        r#"<script src="/static/chunk.js"></script>"#.repeat(3)
+    );
+
+    let response = create_test_response(html, "https://app.example.com/");
+    let result = extract_or_reason(&response).await;
+
+    assert_eq!(result.err(), Some(ExtractionFailure::RenderRequired));
+}
+
+#[tokio::test]
+async fn test_reject_short_page_reason() {
+    let html = "<html><head><title>Stub</title></head><body><article><p>Coming soon.</p></article></body></html>".to_string();
+
+    let response = create_test_response(html, "https://example.com/stub");
+    let result = extract_or_reason(&response).await;
+
+    assert_eq!(result.err(), Some(ExtractionFailure::TooShort));
+}
+
 #[tokio::test]
 async fn test_malformed_html() {
     let html =
@@ -0,0 +1,165 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use utoipa::ToSchema;
+
+static PARAGRAPH_BREAK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\n[^\S\n]*\n\s*").unwrap());
+
+/// Terminal punctuation, optional closing quotes or brackets, then whitespace
+/// and the start of a new sentence. Requiring an uppercase letter, digit or
+/// opening quote afterwards keeps "e.g. this" in one sentence.
+static SENTENCE_END_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"[.!?…]+["'”’)\]]*\s+["'“‘(\[]?[\p{Lu}\p{N}]"#).unwrap());
+
+/// Character range `[start, end)` in normalized text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TextSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A paragraph of normalized text and the sentences within it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ParagraphSpan {
+    pub start: usize,
+    pub end: usize,
+    pub sentences: Vec<TextSpan>,
+}
+
+/// Paragraph and sentence boundaries of an article's text, for anchoring
+/// highlights.
+///
+/// Offsets count Unicode characters in the *normalized* text: paragraphs are
+/// separated by a blank line in the clean text, whitespace inside a paragraph
+/// is collapsed to single spaces, and paragraphs are joined with `"\n\n"`.
+/// Re-extraction that only changes whitespace yields the same offsets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TextMap {
+    pub paragraphs: Vec<ParagraphSpan>,
+}
+
+/// Normalize text the way [`TextMap`] offsets expect
+pub fn normalize_text(text: &str) -> String {
+    paragraphs(text).join("\n\n")
+}
+
+/// Map the paragraphs and sentences of `text`
+pub fn build_text_map(text: &str) -> TextMap {
+    let mut map = TextMap::default();
+    let mut offset = 0;
+
+    for paragraph in paragraphs(text) {
+        let length = paragraph.chars().count();
+        map.paragraphs.push(ParagraphSpan {
+            start: offset,
+            end: offset + length,
+            sentences: sentences(&paragraph, offset),
+        });
+        // Paragraphs are joined by "\n\n"
+        offset += length + 2;
+    }
+
+    map
+}
+
+/// Non-empty paragraphs of `text` with whitespace collapsed
+fn paragraphs(text: &str) -> Vec<String> {
+    PARAGRAPH_BREAK_REGEX
+        .split(text.trim())
+        .map(|paragraph| paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect()
+}
+
+/// Sentence spans of a normalized paragraph starting at character `offset`
+fn sentences(paragraph: &str, offset: usize) -> Vec<TextSpan> {
+    let mut spans = Vec::new();
+    let mut start_byte = 0;
+
+    for found in SENTENCE_END_REGEX.find_iter(paragraph) {
+        let matched = found.as_str();
+        // The sentence ends before the whitespace; the next one starts at
+        // the quote or letter that follows it
+        let whitespace = matched
+            .find(char::is_whitespace)
+            .expect("sentence boundary contains whitespace");
+        let end_byte = found.start() + whitespace;
+        let next_byte =
+            found.start() + matched.trim_end_matches(|c: char| !c.is_whitespace()).len();
+
+        spans.push(span(paragraph, start_byte, end_byte, offset));
+        start_byte = next_byte;
+    }
+    spans.push(span(paragraph, start_byte, paragraph.len(), offset));
+
+    spans
+}
+
+fn span(paragraph: &str, start_byte: usize, end_byte: usize, offset: usize) -> TextSpan {
+    let start = offset + paragraph[..start_byte].chars().count();
+    TextSpan {
+        start,
+        end: start + paragraph[start_byte..end_byte].chars().count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slice(text: &str, span: &TextSpan) -> String {
+        text.chars()
+            .skip(span.start)
+            .take(span.end - span.start)
+            .collect()
+    }
+
+    #[test]
+    fn test_build_text_map() {
+        let text = "First sentence. Second one, e.g. with an aside!\n\n\n  Größe matters.   \"Quoted\" start.";
+        let normalized = normalize_text(text);
+        let map = build_text_map(text);
+
+        assert_eq!(map.paragraphs.len(), 2);
+        let sentences: Vec<String> = map
+            .paragraphs
+            .iter()
+            .flat_map(|p| p.sentences.iter())
+            .map(|s| slice(&normalized, s))
+            .collect();
+        assert_eq!(
+            sentences,
+            [
+                "First sentence.",
+                "Second one, e.g. with an aside!",
+                "Größe matters.",
+                "\"Quoted\" start."
+            ]
+        );
+        assert_eq!(
+            slice(
+                &normalized,
+                &TextSpan {
+                    start: map.paragraphs[1].start,
+                    end: map.paragraphs[1].end
+                }
+            ),
+            "Größe matters. \"Quoted\" start."
+        );
+    }
+
+    #[test]
+    fn test_build_text_map_ignores_whitespace_changes() {
+        let original = "One. Two.\n\nThree.";
+        let reextracted = "  One.   Two.\n \n\n\tThree.\n";
+
+        assert_eq!(build_text_map(original), build_text_map(reextracted));
+        assert_eq!(normalize_text(original), normalize_text(reextracted));
+    }
+
+    #[test]
+    fn test_build_text_map_empty() {
+        assert_eq!(build_text_map("  \n\n "), TextMap::default());
+    }
+}
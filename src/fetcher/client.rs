@@ -1,20 +1,43 @@
-use crate::fetcher::{errors::FetchError, pipeline::process_response, types::PageResponse};
+use crate::fetcher::{
+    config::FetcherConfig,
+    deadline::Deadline,
+    errors::FetchError,
+    identity::FetchIdentity,
+    pipeline::{decode_prefix, process_response},
+    timing::{ConnectTimingLayer, PhaseTimings, Recorder, TimedResolver},
+    types::{CacheValidators, FetchOutcome, FetchedImage, PagePrefix, PageResponse},
+    url_policy::UrlPolicy,
+};
 use once_cell::sync::Lazy;
 use reqwest::{Client, ClientBuilder};
-use std::time::Duration;
-use tracing::instrument;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::{Span, debug, field::Empty, instrument};
 
 const MAX_BODY_SIZE: u64 = 5 * 1024 * 1024; // 5MB
-const USER_AGENT: &str = "CapsuleBot/0.1 (+https://capsule.example.com)";
+const MAX_REDIRECTS: usize = 10;
 
 static HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
-    ClientBuilder::new()
+    FetcherConfig::global()
+        .apply(ClientBuilder::new())
         .connect_timeout(Duration::from_secs(10))
         .timeout(Duration::from_secs(30))
-        .user_agent(USER_AGENT)
-        .redirect(reqwest::redirect::Policy::limited(10))
+        .redirect(reqwest::redirect::Policy::custom(|attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error("too many redirects");
+            }
+            // Every hop has to pass the same policy as the saved URL
+            match UrlPolicy::global().check(attempt.url()) {
+                Ok(()) => attempt.follow(),
+                Err(e) => attempt.error(e),
+            }
+        }))
+        .dns_resolver(Arc::new(TimedResolver))
+        .connector_layer(ConnectTimingLayer)
         .default_headers({
-            let mut headers = reqwest::header::HeaderMap::new();
+            let mut headers = FetchIdentity::global().headers();
             headers.insert(
                 reqwest::header::ACCEPT,
                 "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"
@@ -31,15 +54,114 @@ pub fn get_client() -> &'static Client {
     &HTTP_CLIENT
 }
 
-#[instrument(skip_all, fields(url = %url))]
 pub async fn fetch(url: &str) -> Result<PageResponse, FetchError> {
+    fetch_with_deadline(url, &Deadline::none()).await
+}
+
+/// [`fetch`], abandoning the request once `deadline` passes or is cancelled
+pub async fn fetch_with_deadline(
+    url: &str,
+    deadline: &Deadline,
+) -> Result<PageResponse, FetchError> {
+    fetch_with_timings(url, deadline).await.0
+}
+
+/// [`fetch_with_deadline`], also handing back the phase timings so the
+/// caller can keep them with its record of the attempt
+pub async fn fetch_with_timings(
+    url: &str,
+    deadline: &Deadline,
+) -> (Result<PageResponse, FetchError>, PhaseTimings) {
+    let (outcome, timings) =
+        fetch_conditional_with_timings(url, &CacheValidators::default(), deadline).await;
+    let result = match outcome {
+        Ok(FetchOutcome::Modified(page)) => Ok(*page),
+        Ok(FetchOutcome::NotModified) => Err(FetchError::Http {
+            status: reqwest::StatusCode::NOT_MODIFIED,
+            retriable: false,
+        }),
+        Err(e) => Err(e),
+    };
+    (result, timings)
+}
+
+/// Fetch a page, sending `If-None-Match` / `If-Modified-Since` from a
+/// previous response so unchanged pages cost a single 304.
+///
+/// Per-phase timings are recorded on the span as `dns_ms`, `connect_ms`,
+/// `ttfb_ms` and `download_ms`, whether or not the fetch succeeds.
+/// `connect_ms` covers the TCP and TLS handshakes together.
+pub async fn fetch_conditional(
+    url: &str,
+    validators: &CacheValidators,
+) -> Result<FetchOutcome, FetchError> {
+    fetch_conditional_with_deadline(url, validators, &Deadline::none()).await
+}
+
+/// [`fetch_conditional`], abandoning the request once `deadline` passes or
+/// is cancelled
+pub async fn fetch_conditional_with_deadline(
+    url: &str,
+    validators: &CacheValidators,
+    deadline: &Deadline,
+) -> Result<FetchOutcome, FetchError> {
+    fetch_conditional_with_timings(url, validators, deadline)
+        .await
+        .0
+}
+
+/// [`fetch_conditional_with_deadline`], also handing back the phase timings
+/// so the caller can keep them with its record of the attempt
+#[instrument(
+    skip_all,
+    fields(url = %url, dns_ms = Empty, connect_ms = Empty, ttfb_ms = Empty, download_ms = Empty)
+)]
+pub async fn fetch_conditional_with_timings(
+    url: &str,
+    validators: &CacheValidators,
+    deadline: &Deadline,
+) -> (Result<FetchOutcome, FetchError>, PhaseTimings) {
+    let recorder = Recorder::new();
+    let result = deadline
+        .run(fetch_timed(url, validators, &recorder))
+        .await
+        .unwrap_or_else(|exceeded| Err(exceeded.into()));
+
+    let timings = recorder.timings();
+    timings.record(&Span::current());
+    debug!(?timings, "Fetch phase timings");
+
+    (result, timings)
+}
+
+async fn fetch_timed(
+    url: &str,
+    validators: &CacheValidators,
+    recorder: &Recorder,
+) -> Result<FetchOutcome, FetchError> {
     let parsed_url = url::Url::parse(url)?;
+    UrlPolicy::global()
+        .check(&parsed_url)
+        .map_err(FetchError::UrlNotAllowed)?;
 
-    let response = HTTP_CLIENT
-        .get(parsed_url.clone())
-        .send()
+    let mut request = HTTP_CLIENT.get(parsed_url.clone());
+    if let Some(etag) = &validators.etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+
+    let started = Instant::now();
+    let response = recorder
+        .measure(request.send())
         .await
         .map_err(FetchError::from_reqwest_error)?;
+    recorder.set_ttfb(started.elapsed());
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(FetchOutcome::NotModified);
+    }
 
     // Check content length before downloading
     if let Some(content_length) = response.content_length()
@@ -72,10 +194,12 @@ pub async fn fetch(url: &str) -> Result<PageResponse, FetchError> {
         return Err(FetchError::UnsupportedContentType(content_type.clone()));
     }
 
+    let started = Instant::now();
     let body_bytes = response
         .bytes()
         .await
         .map_err(|e| FetchError::Io(e.to_string()))?;
+    recorder.set_download(started.elapsed());
 
     // Check body size after download (in case Content-Length was missing)
     if body_bytes.len() as u64 > MAX_BODY_SIZE {
@@ -83,4 +207,148 @@ pub async fn fetch(url: &str) -> Result<PageResponse, FetchError> {
     }
 
     process_response(final_url, status, headers, body_bytes, &content_type)
+        .map(|page| FetchOutcome::Modified(Box::new(page)))
+}
+
+/// Fetch no more than the first `max_bytes` of a page, enough for the
+/// metadata in its `<head>` without downloading the rest. Servers that honour
+/// the `Range` header send only that much; for the others the download is
+/// cut off once `max_bytes` have arrived.
+#[instrument(skip_all, fields(url = %url))]
+pub async fn fetch_prefix(
+    url: &str,
+    max_bytes: usize,
+    deadline: &Deadline,
+) -> Result<PagePrefix, FetchError> {
+    deadline
+        .run(fetch_prefix_inner(url, max_bytes))
+        .await
+        .unwrap_or_else(|exceeded| Err(exceeded.into()))
+}
+
+async fn fetch_prefix_inner(url: &str, max_bytes: usize) -> Result<PagePrefix, FetchError> {
+    let parsed_url = url::Url::parse(url)?;
+    UrlPolicy::global()
+        .check(&parsed_url)
+        .map_err(FetchError::UrlNotAllowed)?;
+
+    let mut response = HTTP_CLIENT
+        .get(parsed_url)
+        .header(
+            reqwest::header::RANGE,
+            format!("bytes=0-{}", max_bytes.saturating_sub(1)),
+        )
+        .send()
+        .await
+        .map_err(FetchError::from_reqwest_error)?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(FetchError::Http {
+            status,
+            retriable: status.is_server_error(),
+        });
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|ct| ct.to_str().ok())
+        .unwrap_or("text/html")
+        .to_string();
+    if !content_type.contains("text/html") && !content_type.contains("application/xhtml") {
+        return Err(FetchError::UnsupportedContentType(content_type));
+    }
+
+    let url_final = response.url().clone();
+    let mut body = Vec::new();
+    while body.len() < max_bytes
+        && let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| FetchError::Io(e.to_string()))?
+    {
+        body.extend_from_slice(&chunk);
+    }
+    body.truncate(max_bytes);
+
+    Ok(PagePrefix {
+        url_final,
+        body_utf8: decode_prefix(&content_type, &body)?,
+    })
+}
+
+/// Fetch an image of at most `max_bytes`, such as a site's icon. Anything
+/// not served as `image/*` is refused.
+#[instrument(skip_all, fields(url = %url))]
+pub async fn fetch_image(
+    url: &str,
+    max_bytes: usize,
+    deadline: &Deadline,
+) -> Result<FetchedImage, FetchError> {
+    deadline
+        .run(fetch_image_inner(url, max_bytes))
+        .await
+        .unwrap_or_else(|exceeded| Err(exceeded.into()))
+}
+
+async fn fetch_image_inner(url: &str, max_bytes: usize) -> Result<FetchedImage, FetchError> {
+    let parsed_url = url::Url::parse(url)?;
+    UrlPolicy::global()
+        .check(&parsed_url)
+        .map_err(FetchError::UrlNotAllowed)?;
+
+    let mut response = HTTP_CLIENT
+        .get(parsed_url)
+        .header(reqwest::header::ACCEPT, "image/*")
+        .send()
+        .await
+        .map_err(FetchError::from_reqwest_error)?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(FetchError::Http {
+            status,
+            retriable: status.is_server_error(),
+        });
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|ct| ct.to_str().ok())
+        .unwrap_or_default()
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    if !content_type.starts_with("image/") {
+        return Err(FetchError::UnsupportedContentType(content_type));
+    }
+
+    if let Some(content_length) = response.content_length()
+        && content_length > max_bytes as u64
+    {
+        return Err(FetchError::BodyTooLarge(content_length));
+    }
+
+    let url_final = response.url().clone();
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| FetchError::Io(e.to_string()))?
+    {
+        body.extend_from_slice(&chunk);
+        if body.len() > max_bytes {
+            return Err(FetchError::BodyTooLarge(body.len() as u64));
+        }
+    }
+
+    Ok(FetchedImage {
+        url_final,
+        content_type,
+        body: body.into(),
+    })
 }
@@ -0,0 +1,278 @@
+//! Connection handling for the shared HTTP client.
+//!
+//! Bulk imports fetch many pages from the same few hosts, so idle
+//! connections are kept around for reuse. Some hosts misbehave over HTTP/2,
+//! so deployments can fall back to HTTP/1.1, and hosts with broken IPv6 can
+//! keep fetches on IPv4. Lookups go through the
+//! [`DnsCache`](crate::fetcher::dns::DnsCache), which this also configures.
+//! The [`FetcherConfig`] is read from the environment once, when the client
+//! is built.
+
+use once_cell::sync::Lazy;
+use reqwest::ClientBuilder;
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Display,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    time::Duration,
+};
+use tracing::warn;
+
+use crate::fetcher::dns::{parse_overrides, parse_servers};
+
+pub const ENV_FETCHER_POOL_MAX_IDLE_PER_HOST: &str = "FETCHER_POOL_MAX_IDLE_PER_HOST";
+pub const ENV_FETCHER_POOL_IDLE_TIMEOUT_SECS: &str = "FETCHER_POOL_IDLE_TIMEOUT_SECS";
+pub const ENV_FETCHER_HTTP2: &str = "FETCHER_HTTP2";
+pub const ENV_FETCHER_TCP_KEEPALIVE_SECS: &str = "FETCHER_TCP_KEEPALIVE_SECS";
+pub const ENV_FETCHER_DNS_CACHE_TTL_SECS: &str = "FETCHER_DNS_CACHE_TTL_SECS";
+pub const ENV_FETCHER_DNS_OVERRIDES: &str = "FETCHER_DNS_OVERRIDES";
+pub const ENV_FETCHER_DNS_SERVERS: &str = "FETCHER_DNS_SERVERS";
+pub const ENV_FETCHER_IP_FAMILY: &str = "FETCHER_IP_FAMILY";
+
+static FETCHER_CONFIG: Lazy<FetcherConfig> = Lazy::new(FetcherConfig::from_env);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetcherConfig {
+    /// Idle connections kept open per host; zero disables reuse
+    pub pool_max_idle_per_host: usize,
+    /// How long an idle connection is kept; None keeps it until the host
+    /// closes it
+    pub pool_idle_timeout: Option<Duration>,
+    /// Negotiate HTTP/2 with hosts that offer it; off forces HTTP/1.1
+    pub http2: bool,
+    /// Interval between TCP keepalive probes; None sends none
+    pub tcp_keepalive: Option<Duration>,
+    /// How long a resolved host's addresses are reused; None resolves every
+    /// new connection
+    pub dns_cache_ttl: Option<Duration>,
+    /// Lowercase hosts pinned to addresses instead of being resolved
+    pub dns_overrides: HashMap<String, Vec<IpAddr>>,
+    /// Upstream DNS servers; empty asks those of the system configuration
+    pub dns_servers: Vec<SocketAddr>,
+    /// Which addresses fetches connect to
+    pub ip_family: IpFamily,
+}
+
+/// Address families a fetch may connect over. When a host has addresses of
+/// both families, the connector tries the family of the first address and
+/// races the other one after a short delay (happy eyeballs), so the order
+/// decides which family is preferred.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IpFamily {
+    /// Both families, in the order the resolver returned them
+    #[default]
+    Any,
+    /// Both families, IPv4 first
+    PreferIpv4,
+    /// Both families, IPv6 first
+    PreferIpv6,
+    /// Only IPv4, for networks where IPv6 is broken
+    Ipv4Only,
+    /// Only IPv6
+    Ipv6Only,
+}
+
+impl IpFamily {
+    /// Order `addrs` by preference, dropping those of an excluded family
+    pub fn apply(self, addrs: &mut Vec<SocketAddr>) {
+        match self {
+            IpFamily::Any => {}
+            IpFamily::PreferIpv4 => addrs.sort_by_key(|addr| addr.is_ipv6()),
+            IpFamily::PreferIpv6 => addrs.sort_by_key(|addr| addr.is_ipv4()),
+            IpFamily::Ipv4Only => addrs.retain(SocketAddr::is_ipv4),
+            IpFamily::Ipv6Only => addrs.retain(SocketAddr::is_ipv6),
+        }
+    }
+}
+
+impl FromStr for IpFamily {
+    type Err = String;
+
+    /// `any`, `prefer-ipv4`, `prefer-ipv6`, `ipv4` or `ipv6`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "any" => Ok(IpFamily::Any),
+            "prefer-ipv4" => Ok(IpFamily::PreferIpv4),
+            "prefer-ipv6" => Ok(IpFamily::PreferIpv6),
+            "ipv4" => Ok(IpFamily::Ipv4Only),
+            "ipv6" => Ok(IpFamily::Ipv6Only),
+            _ => Err("expected any, prefer-ipv4, prefer-ipv6, ipv4 or ipv6".to_string()),
+        }
+    }
+}
+
+impl Default for FetcherConfig {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: 8,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            http2: true,
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            dns_cache_ttl: Some(Duration::from_secs(60)),
+            dns_overrides: HashMap::new(),
+            dns_servers: Vec::new(),
+            ip_family: IpFamily::Any,
+        }
+    }
+}
+
+impl FetcherConfig {
+    /// The configuration for this process
+    pub fn global() -> &'static FetcherConfig {
+        &FETCHER_CONFIG
+    }
+
+    /// Read `FETCHER_POOL_MAX_IDLE_PER_HOST`, `FETCHER_POOL_IDLE_TIMEOUT_SECS`,
+    /// `FETCHER_HTTP2`, `FETCHER_TCP_KEEPALIVE_SECS`,
+    /// `FETCHER_DNS_CACHE_TTL_SECS`, `FETCHER_DNS_OVERRIDES`,
+    /// `FETCHER_DNS_SERVERS` and `FETCHER_IP_FAMILY`, falling back to the defaults. A zero timeout, keepalive or TTL turns it off.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| env::var(name).ok())
+    }
+
+    /// [`FetcherConfig::from_env`] with variables from `lookup`
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let mut config = Self::default();
+        if let Some(max_idle) = parse_var(&lookup, ENV_FETCHER_POOL_MAX_IDLE_PER_HOST) {
+            config.pool_max_idle_per_host = max_idle;
+        }
+        if let Some(secs) = parse_var(&lookup, ENV_FETCHER_POOL_IDLE_TIMEOUT_SECS) {
+            config.pool_idle_timeout = nonzero_secs(secs);
+        }
+        if let Some(http2) = parse_var(&lookup, ENV_FETCHER_HTTP2) {
+            config.http2 = http2;
+        }
+        if let Some(secs) = parse_var(&lookup, ENV_FETCHER_TCP_KEEPALIVE_SECS) {
+            config.tcp_keepalive = nonzero_secs(secs);
+        }
+        if let Some(secs) = parse_var(&lookup, ENV_FETCHER_DNS_CACHE_TTL_SECS) {
+            config.dns_cache_ttl = nonzero_secs(secs);
+        }
+        if let Some(spec) = lookup(ENV_FETCHER_DNS_OVERRIDES) {
+            match parse_overrides(&spec) {
+                Ok(overrides) => config.dns_overrides = overrides,
+                Err(e) => warn!("Ignoring {}: {}", ENV_FETCHER_DNS_OVERRIDES, e),
+            }
+        }
+        if let Some(spec) = lookup(ENV_FETCHER_DNS_SERVERS) {
+            match parse_servers(&spec) {
+                Ok(servers) => config.dns_servers = servers,
+                Err(e) => warn!("Ignoring {}: {}", ENV_FETCHER_DNS_SERVERS, e),
+            }
+        }
+        if let Some(ip_family) = parse_var(&lookup, ENV_FETCHER_IP_FAMILY) {
+            config.ip_family = ip_family;
+        }
+        config
+    }
+
+    /// Apply the connection settings to `builder`
+    pub fn apply(&self, builder: ClientBuilder) -> ClientBuilder {
+        let builder = builder
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .tcp_keepalive(self.tcp_keepalive);
+        if self.http2 {
+            builder
+        } else {
+            builder.http1_only()
+        }
+    }
+}
+
+/// The value of `name` parsed as `T`, if set and valid
+fn parse_var<T>(lookup: &impl Fn(&str) -> Option<String>, name: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = lookup(name)?;
+    match value.trim().parse() {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            warn!("Ignoring {}: {:?} is invalid: {}", name, value, e);
+            None
+        }
+    }
+}
+
+fn nonzero_secs(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_from(vars: &[(&str, &str)]) -> FetcherConfig {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        FetcherConfig::from_lookup(|name| vars.get(name).map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn test_unset_variables_keep_defaults() {
+        assert_eq!(config_from(&[]), FetcherConfig::default());
+        assert_eq!(
+            config_from(&[
+                (ENV_FETCHER_POOL_MAX_IDLE_PER_HOST, "lots"),
+                (ENV_FETCHER_DNS_OVERRIDES, "intranet.example.com"),
+                (ENV_FETCHER_DNS_SERVERS, "dns.example.com"),
+            ]),
+            FetcherConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_configured_pool_and_protocol() {
+        let config = config_from(&[
+            (ENV_FETCHER_POOL_MAX_IDLE_PER_HOST, "32"),
+            (ENV_FETCHER_POOL_IDLE_TIMEOUT_SECS, "0"),
+            (ENV_FETCHER_HTTP2, "false"),
+            (ENV_FETCHER_TCP_KEEPALIVE_SECS, " 15 "),
+            (ENV_FETCHER_DNS_CACHE_TTL_SECS, "0"),
+            (ENV_FETCHER_DNS_OVERRIDES, "intranet.example.com=10.0.0.5"),
+            (ENV_FETCHER_DNS_SERVERS, "10.0.0.2,10.0.0.3:5353"),
+            (ENV_FETCHER_IP_FAMILY, "IPv4"),
+        ]);
+        assert_eq!(
+            config,
+            FetcherConfig {
+                pool_max_idle_per_host: 32,
+                pool_idle_timeout: None,
+                http2: false,
+                tcp_keepalive: Some(Duration::from_secs(15)),
+                dns_cache_ttl: None,
+                dns_overrides: HashMap::from([(
+                    "intranet.example.com".to_string(),
+                    vec![IpAddr::from([10, 0, 0, 5])]
+                )]),
+                dns_servers: vec![
+                    SocketAddr::from(([10, 0, 0, 2], 53)),
+                    SocketAddr::from(([10, 0, 0, 3], 5353)),
+                ],
+                ip_family: IpFamily::Ipv4Only,
+            }
+        );
+        assert!(config.apply(ClientBuilder::new()).build().is_ok());
+    }
+
+    #[test]
+    fn test_ip_family_orders_and_filters_addresses() {
+        let v4: SocketAddr = "192.0.2.1:0".parse().unwrap();
+        let v6: SocketAddr = "[2001:db8::1]:0".parse().unwrap();
+        let family = |family: IpFamily| {
+            let mut addrs = vec![v6, v4];
+            family.apply(&mut addrs);
+            addrs
+        };
+
+        assert_eq!(family(IpFamily::Any), vec![v6, v4]);
+        assert_eq!(family(IpFamily::PreferIpv4), vec![v4, v6]);
+        assert_eq!(family(IpFamily::PreferIpv6), vec![v6, v4]);
+        assert_eq!(family(IpFamily::Ipv4Only), vec![v4]);
+        assert_eq!(family(IpFamily::Ipv6Only), vec![v6]);
+        assert!("ipv5".parse::<IpFamily>().is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! When work stops being worth finishing.
+//!
+//! A [`Deadline`] travels with a unit of work (an API request waiting on a
+//! synchronous fetch, or a job whose lease will run out) down to the HTTP
+//! calls it makes, so a fetch nobody will wait for is abandoned rather than
+//! left running to its own timeout.
+
+use chrono::{DateTime, Utc};
+use std::{future::Future, time::Duration};
+use thiserror::Error;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum DeadlineExceeded {
+    #[error("deadline exceeded")]
+    Expired,
+
+    #[error("cancelled")]
+    Cancelled,
+}
+
+/// An optional point in time plus a cancellation token. Clones share the
+/// token, so cancelling any of them cancels them all.
+#[derive(Debug, Clone, Default)]
+pub struct Deadline {
+    expires_at: Option<Instant>,
+    token: CancellationToken,
+}
+
+impl Deadline {
+    /// No time limit; the work only stops if cancelled
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn after(timeout: Duration) -> Self {
+        Self {
+            expires_at: Some(Instant::now() + timeout),
+            token: CancellationToken::new(),
+        }
+    }
+
+    /// A deadline at a wall-clock time, such as a job's lease expiry.
+    /// Times already past give a deadline that has expired.
+    pub fn at(when: DateTime<Utc>) -> Self {
+        Self::after((when - Utc::now()).to_std().unwrap_or_default())
+    }
+
+    /// This deadline or `timeout` from now, whichever comes first. The
+    /// result is cancelled along with this deadline but can also be
+    /// cancelled on its own.
+    pub fn child(&self, timeout: Duration) -> Self {
+        let expires_at = Instant::now() + timeout;
+        Self {
+            expires_at: Some(match self.expires_at {
+                Some(parent) => parent.min(expires_at),
+                None => expires_at,
+            }),
+            token: self.token.child_token(),
+        }
+    }
+
+    /// Time left, or None when there's no time limit
+    pub fn remaining(&self) -> Option<Duration> {
+        self.expires_at
+            .map(|at| at.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.token.is_cancelled() || self.remaining() == Some(Duration::ZERO)
+    }
+
+    /// Abandon the work, as when the client waiting on it has gone away
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Run `future` until it completes, the deadline passes or the deadline
+    /// is cancelled. The future is dropped, aborting any request it has in
+    /// flight, in the latter two cases.
+    pub async fn run<F: Future>(&self, future: F) -> Result<F::Output, DeadlineExceeded> {
+        // The timer may not fire on its first poll for a time that has just
+        // passed, so expired work is refused before it starts
+        if self.token.is_cancelled() {
+            return Err(DeadlineExceeded::Cancelled);
+        }
+        if self.remaining() == Some(Duration::ZERO) {
+            return Err(DeadlineExceeded::Expired);
+        }
+
+        let expiry = async {
+            match self.expires_at {
+                Some(at) => tokio::time::sleep_until(at).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            biased;
+            _ = self.token.cancelled() => Err(DeadlineExceeded::Cancelled),
+            _ = expiry => Err(DeadlineExceeded::Expired),
+            output = future => Ok(output),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_without_limit() {
+        let deadline = Deadline::none();
+        assert_eq!(deadline.remaining(), None);
+        assert!(!deadline.is_expired());
+        assert_eq!(deadline.run(async { 42 }).await, Ok(42));
+    }
+
+    #[tokio::test]
+    async fn test_run_expires() {
+        let deadline = Deadline::after(Duration::from_millis(10));
+        let slow = tokio::time::sleep(Duration::from_secs(60));
+        assert_eq!(deadline.run(slow).await, Err(DeadlineExceeded::Expired));
+        assert!(deadline.is_expired());
+    }
+
+    #[tokio::test]
+    async fn test_run_past_deadline_never_starts() {
+        let deadline = Deadline::at(Utc::now() - chrono::Duration::seconds(1));
+        assert!(deadline.is_expired());
+        assert_eq!(
+            deadline.run(async { 42 }).await,
+            Err(DeadlineExceeded::Expired)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancel_reaches_children() {
+        let deadline = Deadline::none();
+        let child = deadline.child(Duration::from_secs(60));
+        deadline.cancel();
+        assert!(child.is_expired());
+        assert_eq!(
+            child.run(std::future::pending::<()>()).await,
+            Err(DeadlineExceeded::Cancelled)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_child_keeps_the_earlier_deadline() {
+        let deadline = Deadline::after(Duration::from_secs(5));
+        let remaining = |d: Deadline| d.remaining().unwrap().as_secs_f64();
+        assert!(remaining(deadline.child(Duration::from_secs(60))) <= 5.0);
+        assert!(remaining(deadline.child(Duration::from_secs(1))) <= 1.0);
+        assert!(remaining(Deadline::none().child(Duration::from_secs(60))) > 55.0);
+    }
+}
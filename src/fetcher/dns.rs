@@ -0,0 +1,377 @@
+//! Host resolution for the fetcher.
+//!
+//! Hosts are resolved with hickory-resolver, asking the upstream servers
+//! the deployment configures, or those of `/etc/resolv.conf` when it names
+//! none. Split-horizon deployments point the fetcher at their internal
+//! servers, and can pin single hosts to addresses of their own, which are
+//! answered without asking any server at all. Bulk fetches hit the same
+//! hosts over and over, so answers are kept for a while instead of being
+//! looked up per connection. Answers are narrowed to the configured
+//! [`IpFamily`] on the way out, and to public addresses unless the
+//! [`UrlPolicy`] allows private ones; overrides are the deployment's own
+//! and kept as they are.
+
+use dashmap::DashMap;
+use hickory_resolver::{
+    TokioAsyncResolver,
+    config::{NameServerConfigGroup, ResolverConfig, ResolverOpts},
+    system_conf::read_system_conf,
+};
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    io,
+    net::{IpAddr, SocketAddr},
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+use crate::fetcher::{
+    config::{FetcherConfig, IpFamily},
+    url_policy::{UrlPolicy, UrlPolicyError, is_public},
+};
+
+/// Port of upstream servers given without one
+const DNS_PORT: u16 = 53;
+
+/// Most hosts kept; past this, expired answers are dropped, and everything
+/// if that isn't enough
+const MAX_CACHED_HOSTS: usize = 10_000;
+
+static DNS_CACHE: Lazy<DnsCache> = Lazy::new(|| {
+    let config = FetcherConfig::global();
+    DnsCache::new(config.dns_cache_ttl, config.dns_overrides.clone())
+        .with_servers(&config.dns_servers)
+        .with_ip_family(config.ip_family)
+        .with_private_addresses(UrlPolicy::global().allow_private_addresses)
+});
+
+#[derive(Debug, Clone)]
+struct CachedAddrs {
+    addrs: Vec<SocketAddr>,
+    expires_at: Instant,
+}
+
+/// Caching front for the upstream resolver
+pub struct DnsCache {
+    resolver: TokioAsyncResolver,
+    /// How long an answer is reused; None resolves every time
+    ttl: Option<Duration>,
+    /// Lowercase hosts answered with fixed addresses
+    overrides: HashMap<String, Vec<IpAddr>>,
+    ip_family: IpFamily,
+    /// Keep loopback, private and link-local addresses in upstream answers
+    allow_private_addresses: bool,
+    entries: DashMap<String, CachedAddrs>,
+}
+
+impl DnsCache {
+    /// A cache in front of the system's configured servers
+    pub fn new(ttl: Option<Duration>, overrides: HashMap<String, Vec<IpAddr>>) -> Self {
+        Self {
+            resolver: upstream_resolver(&[]),
+            ttl,
+            overrides,
+            ip_family: IpFamily::Any,
+            allow_private_addresses: false,
+            entries: DashMap::new(),
+        }
+    }
+
+    /// Ask `servers` instead of the system's configured ones, unless empty
+    pub fn with_servers(mut self, servers: &[SocketAddr]) -> Self {
+        if !servers.is_empty() {
+            self.resolver = upstream_resolver(servers);
+        }
+        self
+    }
+
+    pub fn with_ip_family(mut self, ip_family: IpFamily) -> Self {
+        self.ip_family = ip_family;
+        self
+    }
+
+    pub fn with_private_addresses(mut self, allow: bool) -> Self {
+        self.allow_private_addresses = allow;
+        self
+    }
+
+    /// The cache shared by every fetch in this process
+    pub fn global() -> &'static DnsCache {
+        &DNS_CACHE
+    }
+
+    /// Addresses for `host` in the configured family, in order of
+    /// preference. Fails when the host has none in that family, rather than
+    /// leaving the connector to time out on an unreachable one, and when it
+    /// only has private addresses that aren't allowed.
+    pub async fn lookup(&self, host: &str) -> io::Result<Vec<SocketAddr>> {
+        let mut addrs = self.resolve(host).await?;
+        if !self.allow_private_addresses && !self.overrides.contains_key(&normalize(host)) {
+            let resolved = addrs.len();
+            addrs.retain(|addr| is_public(addr.ip()));
+            if addrs.is_empty() && resolved > 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    UrlPolicyError::PrivateAddress(host.to_string()),
+                ));
+            }
+        }
+        self.ip_family.apply(&mut addrs);
+        if addrs.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} has no address usable with {:?}", host, self.ip_family),
+            ));
+        }
+        Ok(addrs)
+    }
+
+    /// Addresses for `host`, from its override, a cached answer that hasn't
+    /// expired, or the upstream servers. Ports are left as zero for the
+    /// connector to fill in.
+    async fn resolve(&self, host: &str) -> io::Result<Vec<SocketAddr>> {
+        let host = normalize(host);
+        if let Some(ips) = self.overrides.get(&host) {
+            return Ok(ips.iter().map(|ip| SocketAddr::new(*ip, 0)).collect());
+        }
+
+        let now = Instant::now();
+        if let Some(cached) = self.entries.get(&host)
+            && cached.expires_at > now
+        {
+            return Ok(cached.addrs.clone());
+        }
+
+        let addrs: Vec<SocketAddr> = self
+            .resolver
+            .lookup_ip(host.as_str())
+            .await
+            .map_err(io::Error::other)?
+            .iter()
+            .map(|ip| SocketAddr::new(ip, 0))
+            .collect();
+        if let Some(ttl) = self.ttl
+            && !addrs.is_empty()
+        {
+            self.insert(host, addrs.clone(), now + ttl);
+        }
+        Ok(addrs)
+    }
+
+    fn insert(&self, host: String, addrs: Vec<SocketAddr>, expires_at: Instant) {
+        if self.entries.len() >= MAX_CACHED_HOSTS {
+            let now = Instant::now();
+            self.entries.retain(|_, cached| cached.expires_at > now);
+            if self.entries.len() >= MAX_CACHED_HOSTS {
+                self.entries.clear();
+            }
+        }
+        self.entries.insert(host, CachedAddrs { addrs, expires_at });
+    }
+}
+
+/// `host` as overrides and cached answers are keyed
+fn normalize(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// A resolver asking `servers`, or the servers in the system configuration
+/// when there are none
+fn upstream_resolver(servers: &[SocketAddr]) -> TokioAsyncResolver {
+    if servers.is_empty() {
+        return match read_system_conf() {
+            Ok((config, opts)) => TokioAsyncResolver::tokio(config, opts),
+            Err(e) => {
+                warn!(
+                    "Can't read the system DNS configuration, falling back to the resolver's defaults: {}",
+                    e
+                );
+                TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default())
+            }
+        };
+    }
+
+    let mut name_servers = NameServerConfigGroup::new();
+    for server in servers {
+        name_servers.merge(NameServerConfigGroup::from_ips_clear(
+            &[server.ip()],
+            server.port(),
+            true,
+        ));
+    }
+    TokioAsyncResolver::tokio(
+        ResolverConfig::from_parts(None, Vec::new(), name_servers),
+        ResolverOpts::default(),
+    )
+}
+
+/// Parse upstream servers separated by `,`, each an IP address with an
+/// optional port, e.g. `10.0.0.2,10.0.0.3:5353,[fd00::53]:53`
+pub fn parse_servers(spec: &str) -> Result<Vec<SocketAddr>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|server| !server.is_empty())
+        .map(|server| {
+            server
+                .parse::<SocketAddr>()
+                .or_else(|_| {
+                    server
+                        .parse::<IpAddr>()
+                        .map(|ip| SocketAddr::new(ip, DNS_PORT))
+                })
+                .map_err(|_| format!("{:?} is not an IP address with an optional port", server))
+        })
+        .collect()
+}
+
+/// Parse `host=ip[,ip...]` pairs separated by `;`, e.g.
+/// `intranet.example.com=10.0.0.5;wiki.example.com=10.0.0.6,10.0.0.7`
+pub fn parse_overrides(spec: &str) -> Result<HashMap<String, Vec<IpAddr>>, String> {
+    let mut overrides = HashMap::new();
+    for entry in spec.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let (host, ips) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("{:?} is not host=ip", entry))?;
+        let host = host.trim().trim_end_matches('.').to_ascii_lowercase();
+        if host.is_empty() {
+            return Err(format!("{:?} has no host", entry));
+        }
+        let ips = ips
+            .split(',')
+            .map(|ip| {
+                ip.trim()
+                    .parse::<IpAddr>()
+                    .map_err(|_| format!("{:?} is not an IP address", ip.trim()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        overrides.insert(host, ips);
+    }
+    Ok(overrides)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_parse_overrides() {
+        let overrides =
+            parse_overrides(" Wiki.Example.com.=10.0.0.6, 10.0.0.7 ; db.local=::1 ;").unwrap();
+        assert_eq!(
+            overrides["wiki.example.com"],
+            vec![
+                IpAddr::V4(Ipv4Addr::new(10, 0, 0, 6)),
+                IpAddr::V4(Ipv4Addr::new(10, 0, 0, 7))
+            ]
+        );
+        assert_eq!(
+            overrides["db.local"],
+            vec!["::1".parse::<IpAddr>().unwrap()]
+        );
+        assert!(parse_overrides("").unwrap().is_empty());
+
+        assert!(parse_overrides("example.com").is_err());
+        assert!(parse_overrides("=10.0.0.1").is_err());
+        assert!(parse_overrides("example.com=not-an-ip").is_err());
+    }
+
+    #[test]
+    fn test_parse_servers() {
+        assert_eq!(
+            parse_servers(" 10.0.0.2, 10.0.0.3:5353,[fd00::53]:5300,fd00::54 ,").unwrap(),
+            vec![
+                "10.0.0.2:53".parse::<SocketAddr>().unwrap(),
+                "10.0.0.3:5353".parse().unwrap(),
+                "[fd00::53]:5300".parse().unwrap(),
+                "[fd00::54]:53".parse().unwrap(),
+            ]
+        );
+        assert!(parse_servers("").unwrap().is_empty());
+
+        assert!(parse_servers("dns.example.com").is_err());
+        assert!(parse_servers("10.0.0.2:dns").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_overrides_skip_the_upstream_servers() {
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3));
+        let cache = DnsCache::new(
+            None,
+            HashMap::from([("intranet.invalid".to_string(), vec![ip])]),
+        );
+        let addrs = cache.lookup("Intranet.Invalid.").await.unwrap();
+        assert_eq!(addrs, vec![SocketAddr::new(ip, 0)]);
+
+        // Even when the upstream servers can't be reached
+        let cache = DnsCache::new(
+            None,
+            HashMap::from([("intranet.invalid".to_string(), vec![ip])]),
+        )
+        .with_servers(&["127.0.0.1:9".parse().unwrap()]);
+        let addrs = cache.lookup("intranet.invalid").await.unwrap();
+        assert_eq!(addrs, vec![SocketAddr::new(ip, 0)]);
+    }
+
+    #[tokio::test]
+    async fn test_answers_are_cached_until_they_expire() {
+        let cache = DnsCache::new(Some(Duration::from_secs(60)), HashMap::new())
+            .with_private_addresses(true);
+        let addrs = cache.lookup("localhost").await.unwrap();
+        assert!(!addrs.is_empty());
+        assert_eq!(cache.entries.get("localhost").unwrap().addrs, addrs);
+
+        // A stale answer isn't reused
+        let stale = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)), 0);
+        cache.insert("localhost".to_string(), vec![stale], Instant::now());
+        assert!(!cache.lookup("localhost").await.unwrap().contains(&stale));
+
+        let uncached = DnsCache::new(None, HashMap::new()).with_private_addresses(true);
+        uncached.lookup("localhost").await.unwrap();
+        assert!(uncached.entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lookup_without_an_address_in_the_family_fails() {
+        let v4 = IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3));
+        let overrides = HashMap::from([("intranet.invalid".to_string(), vec![v4])]);
+
+        let cache = DnsCache::new(None, overrides.clone()).with_ip_family(IpFamily::Ipv6Only);
+        let error = cache.lookup("intranet.invalid").await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+
+        let cache = DnsCache::new(None, overrides).with_ip_family(IpFamily::Ipv4Only);
+        assert_eq!(
+            cache.lookup("intranet.invalid").await.unwrap(),
+            vec![SocketAddr::new(v4, 0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lookup_refuses_private_addresses() {
+        let cache = DnsCache::new(Some(Duration::from_secs(60)), HashMap::new());
+        let error = cache.lookup("localhost").await.unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+
+        // Cached answers are checked too
+        let public = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(93, 184, 215, 14)), 0);
+        let private = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5)), 0);
+        cache.insert(
+            "mixed.invalid".to_string(),
+            vec![private, public],
+            Instant::now() + Duration::from_secs(60),
+        );
+        assert_eq!(cache.lookup("mixed.invalid").await.unwrap(), vec![public]);
+
+        // The deployment's own overrides are trusted
+        let cache = DnsCache::new(
+            None,
+            HashMap::from([("intranet.invalid".to_string(), vec![private.ip()])]),
+        );
+        assert_eq!(
+            cache.lookup("intranet.invalid").await.unwrap(),
+            vec![private]
+        );
+    }
+}
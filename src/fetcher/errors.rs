@@ -1,3 +1,4 @@
+use crate::fetcher::{deadline::DeadlineExceeded, url_policy::UrlPolicyError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -5,6 +6,9 @@ pub enum FetchError {
     #[error("invalid url: {0}")]
     InvalidUrl(#[from] url::ParseError),
 
+    #[error("url not allowed: {0}")]
+    UrlNotAllowed(UrlPolicyError),
+
     #[error("dns failure: {0}")]
     Dns(String),
 
@@ -35,6 +39,9 @@ pub enum FetchError {
     #[error("charset error: {0}")]
     Charset(String),
 
+    #[error("fetch abandoned: {0}")]
+    Abandoned(#[from] DeadlineExceeded),
+
     #[error("io error: {0}")]
     Io(String),
 
@@ -47,6 +54,7 @@ impl FetchError {
         match self {
             // Fatal errors - don't retry
             Self::InvalidUrl(_) => false,
+            Self::UrlNotAllowed(_) => false,
             Self::BodyTooLarge(_) => false,
             Self::UnsupportedContentType(_) => false,
             Self::Charset(_) => false,
@@ -59,11 +67,48 @@ impl FetchError {
             Self::RequestTimeout => true,
             Self::RedirectLoop => true,
             Self::Io(_) => true,
+            // Nobody was waiting this time; a later attempt may have longer
+            Self::Abandoned(_) => true,
             Self::Unknown(_) => true,
         }
     }
 
+    /// Stable name of the kind of failure, for logs and the fetch attempt
+    /// history
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::InvalidUrl(_) => "invalid_url",
+            Self::UrlNotAllowed(_) => "url_not_allowed",
+            Self::Dns(_) => "dns",
+            Self::Tls(_) => "tls",
+            Self::ConnectTimeout => "connect_timeout",
+            Self::RequestTimeout => "request_timeout",
+            Self::RedirectLoop => "redirect_loop",
+            Self::Http { .. } => "http",
+            Self::BodyTooLarge(_) => "body_too_large",
+            Self::UnsupportedContentType(_) => "unsupported_content_type",
+            Self::Charset(_) => "charset",
+            Self::Abandoned(_) => "abandoned",
+            Self::Io(_) => "io",
+            Self::Unknown(_) => "unknown",
+        }
+    }
+
+    /// The HTTP status the server answered with, if it got that far
+    pub fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            Self::Http { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
     pub fn from_reqwest_error(err: reqwest::Error) -> Self {
+        // Raised by the redirect policy when a hop leads somewhere disallowed,
+        // or by the resolver when a host only has private addresses
+        if let Some(policy_error) = policy_error(&err) {
+            return Self::UrlNotAllowed(policy_error);
+        }
+
         if err.is_timeout() {
             if err.is_connect() {
                 Self::ConnectTimeout
@@ -85,3 +130,23 @@ impl FetchError {
         }
     }
 }
+
+fn policy_error(err: &reqwest::Error) -> Option<UrlPolicyError> {
+    let mut source = std::error::Error::source(err);
+    while let Some(error) = source {
+        if let Some(policy_error) = error.downcast_ref::<UrlPolicyError>() {
+            return Some(policy_error.clone());
+        }
+        // Raised by the resolver for hosts with only private addresses;
+        // io::Error doesn't expose what it wraps as its source
+        if let Some(policy_error) = error
+            .downcast_ref::<std::io::Error>()
+            .and_then(|e| e.get_ref())
+            .and_then(|e| e.downcast_ref::<UrlPolicyError>())
+        {
+            return Some(policy_error.clone());
+        }
+        source = error.source();
+    }
+    None
+}
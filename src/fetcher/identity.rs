@@ -0,0 +1,150 @@
+//! How the fetcher introduces itself to the sites it crawls.
+//!
+//! Site owners often need to reach whoever runs a crawler, so each
+//! deployment can name a contact URL and address, and tell itself apart from
+//! other deployments with an instance name. The [`FetchIdentity`] is read
+//! from the environment once and sent with every fetch.
+
+use once_cell::sync::Lazy;
+use reqwest::header::{FROM, HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+use std::env;
+use tracing::warn;
+
+pub const ENV_FETCHER_USER_AGENT: &str = "FETCHER_USER_AGENT";
+pub const ENV_FETCHER_CONTACT_URL: &str = "FETCHER_CONTACT_URL";
+pub const ENV_FETCHER_FROM: &str = "FETCHER_FROM";
+pub const ENV_FETCHER_INSTANCE: &str = "FETCHER_INSTANCE";
+
+/// Names the deployment a request came from
+pub const INSTANCE_HEADER: &str = "x-capsule-instance";
+
+const DEFAULT_USER_AGENT: &str = "CapsuleBot/0.1";
+const DEFAULT_CONTACT_URL: &str = "https://capsule.example.com";
+
+static FETCH_IDENTITY: Lazy<FetchIdentity> = Lazy::new(FetchIdentity::from_env);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchIdentity {
+    /// Product token, e.g. `CapsuleBot/0.1`
+    pub user_agent: String,
+    /// Where site owners can read about the crawler, appended to the user
+    /// agent as `(+url)`
+    pub contact_url: Option<String>,
+    /// Address sent in the `From` header
+    pub from: Option<String>,
+    /// Sent in the `X-Capsule-Instance` header
+    pub instance: Option<String>,
+}
+
+impl Default for FetchIdentity {
+    fn default() -> Self {
+        Self {
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            contact_url: Some(DEFAULT_CONTACT_URL.to_string()),
+            from: None,
+            instance: None,
+        }
+    }
+}
+
+impl FetchIdentity {
+    /// The identity configured for this process
+    pub fn global() -> &'static FetchIdentity {
+        &FETCH_IDENTITY
+    }
+
+    /// Read `FETCHER_USER_AGENT`, `FETCHER_CONTACT_URL` (empty to leave the
+    /// contact out), `FETCHER_FROM` and `FETCHER_INSTANCE`, falling back to
+    /// the defaults. Values that can't be sent in a header are skipped.
+    pub fn from_env() -> Self {
+        let mut identity = Self::default();
+        if let Some(user_agent) = header_var(ENV_FETCHER_USER_AGENT) {
+            identity.user_agent = user_agent;
+        }
+        if env::var(ENV_FETCHER_CONTACT_URL).is_ok() {
+            identity.contact_url = header_var(ENV_FETCHER_CONTACT_URL);
+        }
+        identity.from = header_var(ENV_FETCHER_FROM).filter(|from| {
+            let valid = from.contains('@');
+            if !valid {
+                warn!(
+                    "Ignoring {}: {:?} is not an email address",
+                    ENV_FETCHER_FROM, from
+                );
+            }
+            valid
+        });
+        identity.instance = header_var(ENV_FETCHER_INSTANCE);
+        identity
+    }
+
+    /// The full `User-Agent`, with the contact URL when there is one
+    pub fn user_agent_header(&self) -> String {
+        match &self.contact_url {
+            Some(url) => format!("{} (+{})", self.user_agent, url),
+            None => self.user_agent.clone(),
+        }
+    }
+
+    /// The headers to send with every fetch
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let values = [
+            (USER_AGENT, Some(self.user_agent_header())),
+            (FROM, self.from.clone()),
+            (
+                HeaderName::from_static(INSTANCE_HEADER),
+                self.instance.clone(),
+            ),
+        ];
+        for (name, value) in values {
+            if let Some(value) = value.and_then(|v| HeaderValue::from_str(&v).ok()) {
+                headers.insert(name, value);
+            }
+        }
+        headers
+    }
+}
+
+/// The trimmed value of `name`, if set, non-empty and valid in a header
+fn header_var(name: &str) -> Option<String> {
+    let value = env::var(name).ok()?.trim().to_string();
+    if value.is_empty() {
+        return None;
+    }
+    if HeaderValue::from_str(&value).is_err() {
+        warn!("Ignoring {}: {:?} can't be sent in a header", name, value);
+        return None;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_identity() {
+        let headers = FetchIdentity::default().headers();
+        assert_eq!(
+            headers[USER_AGENT],
+            "CapsuleBot/0.1 (+https://capsule.example.com)"
+        );
+        assert!(!headers.contains_key(FROM));
+        assert!(!headers.contains_key(INSTANCE_HEADER));
+    }
+
+    #[test]
+    fn test_configured_identity() {
+        let identity = FetchIdentity {
+            user_agent: "ReadLaterBot/2.0".to_string(),
+            contact_url: None,
+            from: Some("ops@example.org".to_string()),
+            instance: Some("eu-1".to_string()),
+        };
+        let headers = identity.headers();
+        assert_eq!(headers[USER_AGENT], "ReadLaterBot/2.0");
+        assert_eq!(headers[FROM], "ops@example.org");
+        assert_eq!(headers[INSTANCE_HEADER], "eu-1");
+    }
+}
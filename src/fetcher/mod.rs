@@ -1,8 +1,26 @@
 pub mod client;
+pub mod config;
+pub mod deadline;
+pub mod dns;
 pub mod errors;
+pub mod identity;
 pub mod pipeline;
+pub mod timing;
 pub mod types;
+pub mod url_policy;
 
-pub use client::{fetch, get_client};
+pub use client::{
+    fetch, fetch_conditional, fetch_conditional_with_deadline, fetch_conditional_with_timings,
+    fetch_image, fetch_prefix, fetch_with_deadline, fetch_with_timings, get_client,
+};
+pub use config::{FetcherConfig, IpFamily};
+pub use deadline::{Deadline, DeadlineExceeded};
+pub use dns::DnsCache;
 pub use errors::FetchError;
-pub use types::{Charset, PageResponse};
+pub use identity::FetchIdentity;
+pub use timing::PhaseTimings;
+pub use types::{
+    CacheValidators, Charset, FetchOutcome, FetchedImage, PERSISTED_HEADERS, PagePrefix,
+    PageResponse, persisted_headers,
+};
+pub use url_policy::{UrlPolicy, UrlPolicyError};
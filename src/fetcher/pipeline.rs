@@ -84,8 +84,16 @@ fn detect_charset(content_type: &str, body_bytes: &[u8]) -> Result<Charset, Fetc
     Ok(Charset::from_encoding(detected))
 }
 
-fn decode_to_utf8(body_bytes: &[u8], charset: &Charset) -> Result<String, FetchError> {
-    let encoding = match charset {
+/// Decode the start of a body, which may end partway through a character,
+/// replacing anything that doesn't decode rather than failing
+pub(crate) fn decode_prefix(content_type: &str, body_bytes: &[u8]) -> Result<String, FetchError> {
+    let charset = detect_charset(content_type, body_bytes)?;
+    let (decoded, _encoding, _had_errors) = encoding_for(&charset).decode(body_bytes);
+    Ok(decoded.into_owned())
+}
+
+fn encoding_for(charset: &Charset) -> &'static Encoding {
+    match charset {
         Charset::Utf8 => encoding_rs::UTF_8,
         Charset::Latin1 | Charset::Iso88591 => encoding_rs::WINDOWS_1252,
         Charset::Windows1252 => encoding_rs::WINDOWS_1252,
@@ -93,7 +101,11 @@ fn decode_to_utf8(body_bytes: &[u8], charset: &Charset) -> Result<String, FetchE
         Charset::Gb2312 => encoding_rs::GBK,
         Charset::Big5 => encoding_rs::BIG5,
         Charset::Other(name) => Encoding::for_label(name.as_bytes()).unwrap_or(encoding_rs::UTF_8),
-    };
+    }
+}
+
+fn decode_to_utf8(body_bytes: &[u8], charset: &Charset) -> Result<String, FetchError> {
+    let encoding = encoding_for(charset);
 
     let (decoded, _encoding, had_errors) = encoding.decode(body_bytes);
 
@@ -147,4 +159,12 @@ mod tests {
         let decoded = decode_to_utf8(body, &charset).unwrap();
         assert_eq!(decoded, "Hello, 世界!");
     }
+
+    #[test]
+    fn test_decode_prefix_cut_mid_character() {
+        let body = "Hello, 世界!".as_bytes();
+        // Cut through the middle of 界
+        let decoded = decode_prefix("text/html; charset=utf-8", &body[..12]).unwrap();
+        assert_eq!(decoded, "Hello, 世\u{FFFD}");
+    }
 }
@@ -0,0 +1,192 @@
+//! Per-phase timing of a fetch: DNS, connect (TCP + TLS), time to first byte
+//! and body download.
+//!
+//! The HTTP client is shared, so the resolver and connector hooks attribute
+//! their measurements to whichever fetch is polling them through a task-local
+//! set up by [`Recorder::measure`]. DNS and connect are only observed for new
+//! connections; a pooled connection leaves them unset.
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower::{Layer, Service};
+use tracing::Span;
+
+use crate::fetcher::dns::DnsCache;
+
+tokio::task_local! {
+    static CURRENT: Arc<Mutex<PhaseTimings>>;
+}
+
+/// Durations of each fetch phase that was observed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseTimings {
+    pub dns: Option<Duration>,
+    /// TCP and TLS handshakes; reqwest's connector doesn't separate the two
+    pub connect: Option<Duration>,
+    pub ttfb: Option<Duration>,
+    pub download: Option<Duration>,
+}
+
+impl PhaseTimings {
+    /// Record the observed phases as `*_ms` fields on `span`
+    pub fn record(&self, span: &Span) {
+        let fields = [
+            ("dns_ms", self.dns),
+            ("connect_ms", self.connect),
+            ("ttfb_ms", self.ttfb),
+            ("download_ms", self.download),
+        ];
+        for (field, duration) in fields {
+            if let Some(duration) = duration {
+                span.record(field, duration.as_millis() as u64);
+            }
+        }
+    }
+}
+
+/// Collects phase timings for one fetch
+#[derive(Clone, Default)]
+pub struct Recorder(Arc<Mutex<PhaseTimings>>);
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `future` with the resolver and connector reporting into this recorder
+    pub async fn measure<F: Future>(&self, future: F) -> F::Output {
+        CURRENT.scope(self.0.clone(), future).await
+    }
+
+    pub fn set_ttfb(&self, duration: Duration) {
+        self.update(|timings| timings.ttfb = Some(duration));
+    }
+
+    pub fn set_download(&self, duration: Duration) {
+        self.update(|timings| timings.download = Some(duration));
+    }
+
+    pub fn timings(&self) -> PhaseTimings {
+        *self.0.lock().expect("timings lock poisoned")
+    }
+
+    fn update(&self, f: impl FnOnce(&mut PhaseTimings)) {
+        f(&mut self.0.lock().expect("timings lock poisoned"));
+    }
+}
+
+/// Report a measurement to the fetch being polled, if any
+fn report(f: impl FnOnce(&mut PhaseTimings)) {
+    let _ = CURRENT.try_with(|timings| {
+        if let Ok(mut timings) = timings.lock() {
+            f(&mut timings);
+        }
+    });
+}
+
+/// The connector's time includes resolving the host, so take DNS back out
+fn record_connect(elapsed: Duration) {
+    report(|timings| {
+        timings.connect = Some(elapsed.saturating_sub(timings.dns.unwrap_or_default()));
+    });
+}
+
+/// Resolver going through the shared [`DnsCache`] that reports how long each
+/// lookup took
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimedResolver;
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let started = Instant::now();
+            let addrs = DnsCache::global().lookup(name.as_str()).await?;
+            let elapsed = started.elapsed();
+            report(|timings| timings.dns = Some(elapsed));
+
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// Connector layer that reports how long establishing a connection took
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConnectTimingLayer;
+
+impl<S> Layer<S> for ConnectTimingLayer {
+    type Service = ConnectTiming<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConnectTiming { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConnectTiming<S> {
+    inner: S,
+}
+
+impl<S, R> Service<R> for ConnectTiming<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let connecting = self.inner.call(request);
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = connecting.await;
+            if result.is_ok() {
+                record_connect(started.elapsed());
+            }
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_recorder_collects_reports_in_scope() {
+        let recorder = Recorder::new();
+        recorder
+            .measure(async {
+                report(|timings| timings.dns = Some(Duration::from_millis(20)));
+                record_connect(Duration::from_millis(50));
+            })
+            .await;
+        recorder.set_ttfb(Duration::from_millis(120));
+
+        let timings = recorder.timings();
+        assert_eq!(timings.dns, Some(Duration::from_millis(20)));
+        // DNS is excluded from the connect phase
+        assert_eq!(timings.connect, Some(Duration::from_millis(30)));
+        assert_eq!(timings.ttfb, Some(Duration::from_millis(120)));
+        assert_eq!(timings.download, None);
+    }
+
+    #[tokio::test]
+    async fn test_report_outside_scope_is_ignored() {
+        report(|timings| timings.dns = Some(Duration::from_millis(1)));
+        record_connect(Duration::from_millis(1));
+
+        assert_eq!(Recorder::new().timings(), PhaseTimings::default());
+    }
+}
@@ -2,8 +2,31 @@ use bytes::Bytes;
 use chrono::{DateTime, Utc};
 use reqwest::{StatusCode, header::HeaderMap};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use url::Url;
 
+/// Response headers kept with each fetched page, for conditional GETs,
+/// language hints and the like; everything else is dropped
+pub const PERSISTED_HEADERS: &[&str] = &[
+    "content-type",
+    "cache-control",
+    "etag",
+    "last-modified",
+    "content-language",
+];
+
+/// The [`PERSISTED_HEADERS`] present in `headers`, by name. Values that
+/// aren't visible ASCII are skipped.
+pub fn persisted_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    PERSISTED_HEADERS
+        .iter()
+        .filter_map(|&name| {
+            let value = headers.get(name)?.to_str().ok()?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Charset {
     Utf8,
@@ -48,3 +71,97 @@ pub struct PageResponse {
     pub charset: Charset,
     pub fetched_at: DateTime<Utc>,
 }
+
+/// The start of a page, from [`fetch_prefix`](crate::fetcher::fetch_prefix)
+#[derive(Debug)]
+pub struct PagePrefix {
+    pub url_final: Url,
+    pub body_utf8: String,
+}
+
+/// An image, from [`fetch_image`](crate::fetcher::fetch_image)
+#[derive(Debug)]
+pub struct FetchedImage {
+    pub url_final: Url,
+    /// The `image/*` media type, without parameters
+    pub content_type: String,
+    pub body: Bytes,
+}
+
+/// Result of a conditional fetch
+#[derive(Debug)]
+pub enum FetchOutcome {
+    Modified(Box<PageResponse>),
+    NotModified,
+}
+
+/// HTTP cache validators remembered from a previous response
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+
+        Self {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_validators_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(reqwest::header::ETAG, "\"abc\"".parse().unwrap());
+        headers.insert(
+            reqwest::header::LAST_MODIFIED,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+
+        let validators = CacheValidators::from_headers(&headers);
+        assert_eq!(validators.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(
+            validators.last_modified.as_deref(),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+        assert_eq!(
+            CacheValidators::from_headers(&HeaderMap::new()),
+            CacheValidators::default()
+        );
+    }
+
+    #[test]
+    fn test_persisted_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(reqwest::header::ETAG, "\"abc\"".parse().unwrap());
+        headers.insert(reqwest::header::CONTENT_LANGUAGE, "de".parse().unwrap());
+        headers.insert(reqwest::header::SET_COOKIE, "session=1".parse().unwrap());
+        headers.insert(
+            reqwest::header::CACHE_CONTROL,
+            reqwest::header::HeaderValue::from_bytes(b"max-age=60\xff").unwrap(),
+        );
+
+        let persisted = persisted_headers(&headers);
+        assert_eq!(
+            persisted.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("content-language".to_string(), "de".to_string()),
+                ("etag".to_string(), "\"abc\"".to_string()),
+            ]
+        );
+        assert!(persisted_headers(&HeaderMap::new()).is_empty());
+    }
+}
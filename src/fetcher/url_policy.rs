@@ -0,0 +1,352 @@
+//! Which URLs may be saved and fetched.
+//!
+//! The same [`UrlPolicy`] is applied when an item is created or imported and
+//! again by the fetcher, including on every redirect hop, so a URL that got
+//! into the database some other way still can't be fetched.
+//!
+//! Fetches are made on users' behalf from inside the deployment's network,
+//! so by default they may only reach public addresses: URLs naming a
+//! loopback, private or link-local address are refused here, and the
+//! [`DnsCache`](crate::fetcher::dns::DnsCache) drops such addresses from the
+//! answers for host names.
+
+use once_cell::sync::Lazy;
+use std::{
+    env,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+use thiserror::Error;
+use tracing::warn;
+use url::{Host, Url};
+
+pub const ENV_URL_ALLOWED_SCHEMES: &str = "URL_ALLOWED_SCHEMES";
+pub const ENV_URL_ALLOWED_PORTS: &str = "URL_ALLOWED_PORTS";
+pub const ENV_URL_ALLOW_PRIVATE_ADDRESSES: &str = "URL_ALLOW_PRIVATE_ADDRESSES";
+
+static URL_POLICY: Lazy<UrlPolicy> = Lazy::new(UrlPolicy::from_env);
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum UrlPolicyError {
+    #[error("invalid url: {0}")]
+    Invalid(String),
+
+    #[error("scheme {0} is not allowed")]
+    Scheme(String),
+
+    #[error("url has no host")]
+    MissingHost,
+
+    #[error("credentials are not allowed in urls")]
+    Userinfo,
+
+    #[error("port {0} is not allowed")]
+    Port(u16),
+
+    #[error("{0} is not a public address")]
+    PrivateAddress(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlPolicy {
+    /// Lowercase schemes that may be saved
+    pub allowed_schemes: Vec<String>,
+    /// Ports allowed besides the scheme's default; None allows any port
+    pub allowed_ports: Option<Vec<u16>>,
+    /// Let fetches reach loopback, private and other non-public addresses
+    pub allow_private_addresses: bool,
+}
+
+impl Default for UrlPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_schemes: vec!["http".to_string(), "https".to_string()],
+            allowed_ports: Some(Vec::new()),
+            allow_private_addresses: false,
+        }
+    }
+}
+
+impl UrlPolicy {
+    /// The policy configured for this process
+    pub fn global() -> &'static UrlPolicy {
+        &URL_POLICY
+    }
+
+    /// Read `URL_ALLOWED_SCHEMES` (comma-separated), `URL_ALLOWED_PORTS`
+    /// (comma-separated, or `*` for any port) and
+    /// `URL_ALLOW_PRIVATE_ADDRESSES` (`true` to reach non-public addresses),
+    /// falling back to the defaults of http/https on their standard ports of
+    /// public hosts. Unparseable values are skipped.
+    pub fn from_env() -> Self {
+        let mut policy = Self::default();
+        if let Ok(schemes) = env::var(ENV_URL_ALLOWED_SCHEMES) {
+            policy.allowed_schemes = split_list(&schemes)
+                .map(|scheme| scheme.to_ascii_lowercase())
+                .collect();
+        }
+        if let Ok(ports) = env::var(ENV_URL_ALLOWED_PORTS) {
+            policy.allowed_ports = if ports.trim() == "*" {
+                None
+            } else {
+                Some(
+                    split_list(&ports)
+                        .filter_map(|port| match port.parse() {
+                            Ok(port) => Some(port),
+                            Err(_) => {
+                                warn!(
+                                    "Ignoring invalid port {:?} in {}",
+                                    port, ENV_URL_ALLOWED_PORTS
+                                );
+                                None
+                            }
+                        })
+                        .collect(),
+                )
+            };
+        }
+        if let Ok(allow) = env::var(ENV_URL_ALLOW_PRIVATE_ADDRESSES) {
+            match allow.trim().parse() {
+                Ok(allow) => policy.allow_private_addresses = allow,
+                Err(_) => warn!(
+                    "Ignoring invalid {} {:?}, expected true or false",
+                    ENV_URL_ALLOW_PRIVATE_ADDRESSES, allow
+                ),
+            }
+        }
+        policy
+    }
+
+    /// Parse `url` and check it against the policy
+    pub fn parse(&self, url: &str) -> Result<Url, UrlPolicyError> {
+        let parsed = Url::parse(url).map_err(|e| UrlPolicyError::Invalid(e.to_string()))?;
+        self.check(&parsed)?;
+        Ok(parsed)
+    }
+
+    pub fn check(&self, url: &Url) -> Result<(), UrlPolicyError> {
+        if !self.allowed_schemes.iter().any(|s| s == url.scheme()) {
+            return Err(UrlPolicyError::Scheme(url.scheme().to_string()));
+        }
+        if url.host_str().is_none_or(str::is_empty) {
+            return Err(UrlPolicyError::MissingHost);
+        }
+        if !url.username().is_empty() || url.password().is_some() {
+            return Err(UrlPolicyError::Userinfo);
+        }
+        // Url::port is None when the port is the scheme's default
+        if let (Some(port), Some(allowed)) = (url.port(), &self.allowed_ports)
+            && !allowed.contains(&port)
+        {
+            return Err(UrlPolicyError::Port(port));
+        }
+        // Host names are checked once resolved, by the DNS cache
+        let ip = match url.host() {
+            Some(Host::Ipv4(ip)) => Some(IpAddr::V4(ip)),
+            Some(Host::Ipv6(ip)) => Some(IpAddr::V6(ip)),
+            _ => None,
+        };
+        if let Some(ip) = ip {
+            self.check_address(ip)?;
+        }
+        Ok(())
+    }
+
+    /// Check an address a fetch would connect to
+    pub fn check_address(&self, ip: IpAddr) -> Result<(), UrlPolicyError> {
+        if !self.allow_private_addresses && !is_public(ip) {
+            return Err(UrlPolicyError::PrivateAddress(ip.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Whether `ip` is reachable on the public internet, rather than being
+/// loopback, private, link-local or in another special-purpose range
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network", 0.0.0.0/8
+        || a == 0
+        // Carrier-grade NAT, 100.64.0.0/10
+        || (a == 100 && (64..128).contains(&b))
+        // IETF protocol assignments, 192.0.0.0/24
+        || (a == 192 && b == 0 && c == 0)
+        // Benchmarking, 198.18.0.0/15
+        || (a == 198 && (b == 18 || b == 19))
+        // Reserved, 240.0.0.0/4
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    // NAT64, 64:ff9b::/96, reaches the IPv4 address in its last 32 bits
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [.., high, low] = segments;
+        return is_public_v4(Ipv4Addr::from((u32::from(high) << 16) | u32::from(low)));
+    }
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local, fc00::/7
+        || (segments[0] & 0xfe00) == 0xfc00
+        // Link-local, fe80::/10
+        || (segments[0] & 0xffc0) == 0xfe80
+        // Documentation, 2001:db8::/32
+        || (segments[0] == 0x2001 && segments[1] == 0xdb8))
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_default_policy() {
+        let policy = UrlPolicy::default();
+        assert!(policy.parse("https://example.com/a").is_ok());
+        assert!(policy.parse("http://example.com:80/a").is_ok());
+        assert!(policy.parse("https://example.com:443/a").is_ok());
+
+        assert_eq!(
+            policy.parse("ftp://example.com/file"),
+            Err(UrlPolicyError::Scheme("ftp".to_string()))
+        );
+        assert_eq!(
+            policy.parse("javascript:alert(1)"),
+            Err(UrlPolicyError::Scheme("javascript".to_string()))
+        );
+        assert_eq!(
+            policy.parse("https://user:pw@example.com/"),
+            Err(UrlPolicyError::Userinfo)
+        );
+        assert_eq!(
+            policy.parse("https://user@example.com/"),
+            Err(UrlPolicyError::Userinfo)
+        );
+        assert_eq!(
+            policy.parse("http://example.com:8080/"),
+            Err(UrlPolicyError::Port(8080))
+        );
+        assert!(matches!(
+            policy.parse("not a url"),
+            Err(UrlPolicyError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_private_addresses() {
+        let policy = UrlPolicy::default();
+        assert!(policy.parse("https://93.184.215.14/").is_ok());
+        assert!(policy.parse("https://[2606:4700::1111]/").is_ok());
+
+        for url in [
+            "http://127.0.0.1/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://10.0.0.5/",
+            "http://172.16.0.1/",
+            "http://192.168.1.1/",
+            "http://100.64.0.1/",
+            "http://0.0.0.0/",
+            "http://2130706433/",
+            "http://[::1]/",
+            "http://[fd00::1]/",
+            "http://[fe80::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://[64:ff9b::a00:1]/",
+        ] {
+            assert!(
+                matches!(policy.parse(url), Err(UrlPolicyError::PrivateAddress(_))),
+                "{url}"
+            );
+        }
+
+        // Host names are left to the resolver
+        assert!(policy.parse("http://localhost/").is_ok());
+
+        let private = UrlPolicy {
+            allow_private_addresses: true,
+            ..UrlPolicy::default()
+        };
+        assert!(private.parse("http://169.254.169.254/").is_ok());
+    }
+
+    #[test]
+    fn test_is_public() {
+        for ip in [
+            "8.8.8.8",
+            "1.1.1.1",
+            "2001:4860:4860::8888",
+            "64:ff9b::808:808",
+        ] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "169.254.169.254",
+            "198.18.0.1",
+            "224.0.0.1",
+            "255.255.255.255",
+            "::",
+            "::1",
+            "fc00::1",
+            "2001:db8::1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[test]
+    fn test_check_configured_ports() {
+        let policy = UrlPolicy {
+            allowed_ports: Some(vec![8080]),
+            ..UrlPolicy::default()
+        };
+        assert!(policy.parse("http://example.com:8080/").is_ok());
+        assert_eq!(
+            policy.parse("http://example.com:22/"),
+            Err(UrlPolicyError::Port(22))
+        );
+
+        let any_port = UrlPolicy {
+            allowed_ports: None,
+            ..UrlPolicy::default()
+        };
+        assert!(any_port.parse("http://example.com:22/").is_ok());
+    }
+
+    #[test]
+    fn test_check_configured_schemes() {
+        let policy = UrlPolicy {
+            allowed_schemes: vec!["https".to_string()],
+            ..UrlPolicy::default()
+        };
+        assert!(policy.parse("https://example.com/").is_ok());
+        assert_eq!(
+            policy.parse("http://example.com/"),
+            Err(UrlPolicyError::Scheme("http".to_string()))
+        );
+    }
+}
@@ -1,15 +1,69 @@
 use axum::{Json, extract::State, http::StatusCode};
 use serde::Serialize;
 use sqlx::{Pool, Postgres};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use utoipa::ToSchema;
 
 use crate::app_state::AppState;
 
+/// Age of the oldest due job past which the worker counts as lagging
+pub const MAX_QUEUED_JOB_AGE_SECS: f64 = 300.0;
+/// Share of jobs finished in the last hour that may fail for good
+pub const MAX_JOB_FAILURE_RATE: f64 = 0.25;
+/// Finished jobs needed before the failure rate means anything
+const MIN_FINISHED_JOBS_FOR_RATE: i64 = 20;
+
 #[derive(Serialize, ToSchema)]
 pub struct HealthResponse {
+    /// `OK`, or `DEGRADED` when the API is up but the job queue isn't
     status: String,
     database: String,
+    jobs: Option<JobQueueHealth>,
+}
+
+/// How the background workers are keeping up
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct JobQueueHealth {
+    /// `healthy` or `lagging`
+    pub status: String,
+    /// Seconds the oldest due job (or job waiting in the outbox) has waited;
+    /// null when nothing is waiting
+    pub oldest_queued_age_secs: Option<f64>,
+    /// Jobs that finished in the last hour, successfully or not
+    pub finished_last_hour: i64,
+    /// Jobs that failed for good in the last hour
+    pub failed_last_hour: i64,
+    /// `failed_last_hour / finished_last_hour`, 0 when nothing finished
+    pub failure_rate: f64,
+}
+
+impl JobQueueHealth {
+    pub fn assess(
+        oldest_queued_age_secs: Option<f64>,
+        finished_last_hour: i64,
+        failed_last_hour: i64,
+    ) -> Self {
+        let failure_rate = if finished_last_hour > 0 {
+            failed_last_hour as f64 / finished_last_hour as f64
+        } else {
+            0.0
+        };
+        let lagging = oldest_queued_age_secs.is_some_and(|age| age > MAX_QUEUED_JOB_AGE_SECS)
+            || (finished_last_hour >= MIN_FINISHED_JOBS_FOR_RATE
+                && failure_rate > MAX_JOB_FAILURE_RATE);
+
+        Self {
+            status: if lagging { "lagging" } else { "healthy" }.to_string(),
+            oldest_queued_age_secs,
+            finished_last_hour,
+            failed_last_hour,
+            failure_rate,
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.status == "healthy"
+    }
 }
 
 #[utoipa::path(
@@ -17,29 +71,94 @@ pub struct HealthResponse {
     path = "/healthz",
     tag = "health",
     responses(
-        (status = 200, description = "Health check successful", body = HealthResponse),
+        (status = 200, description = "API is up; `status` is DEGRADED if the job queue is lagging", body = HealthResponse),
         (status = 503, description = "Service unavailable")
     )
 )]
 pub async fn health_check(
     State(state): State<AppState>,
 ) -> Result<Json<HealthResponse>, StatusCode> {
-    match check_database_health(&state.db_pool).await {
-        Ok(_) => {
-            info!("Health check passed");
-            Ok(Json(HealthResponse {
-                status: "OK".to_string(),
-                database: "healthy".to_string(),
-            }))
-        }
-        Err(_) => {
-            error!("Database health check failed");
-            Err(StatusCode::SERVICE_UNAVAILABLE)
-        }
+    if check_database_health(&state.db_pool).await.is_err() {
+        error!("Database health check failed");
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
     }
+
+    // A dead worker shouldn't take the API down with it, so queue trouble
+    // is reported in the body rather than as a failing status code
+    let jobs = match check_job_queue_health(&state.db_pool).await {
+        Ok(jobs) => Some(jobs),
+        Err(e) => {
+            warn!("Job queue health check failed: {}", e);
+            None
+        }
+    };
+    let status = match &jobs {
+        Some(jobs) if jobs.is_healthy() => "OK",
+        _ => "DEGRADED",
+    };
+
+    info!("Health check passed ({})", status);
+    Ok(Json(HealthResponse {
+        status: status.to_string(),
+        database: "healthy".to_string(),
+        jobs,
+    }))
 }
 
 async fn check_database_health(pool: &Pool<Postgres>) -> Result<(), sqlx::Error> {
     sqlx::query("SELECT 1").fetch_one(pool).await?;
     Ok(())
 }
+
+/// One aggregate over the ready-job and finished-job indexes plus the outbox
+async fn check_job_queue_health(pool: &Pool<Postgres>) -> Result<JobQueueHealth, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT
+            EXTRACT(EPOCH FROM NOW() - LEAST(
+                (SELECT MIN(run_at) FROM jobs WHERE status = 'queued' AND run_at <= NOW()),
+                (SELECT MIN(run_at) FROM job_outbox WHERE run_at <= NOW())
+            ))::float8 AS oldest_queued_age_secs,
+            COUNT(*) AS "finished_last_hour!",
+            COUNT(*) FILTER (WHERE status = 'failed') AS "failed_last_hour!"
+        FROM jobs
+        WHERE status IN ('succeeded', 'failed') AND updated_at >= NOW() - INTERVAL '1 hour'
+        "#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(JobQueueHealth::assess(
+        row.oldest_queued_age_secs,
+        row.finished_last_hour,
+        row.failed_last_hour,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assess_idle_queue_is_healthy() {
+        let health = JobQueueHealth::assess(None, 0, 0);
+        assert!(health.is_healthy());
+        assert_eq!(health.failure_rate, 0.0);
+    }
+
+    #[test]
+    fn test_assess_old_job_is_lagging() {
+        assert!(JobQueueHealth::assess(Some(30.0), 100, 0).is_healthy());
+        assert!(!JobQueueHealth::assess(Some(MAX_QUEUED_JOB_AGE_SECS + 1.0), 100, 0).is_healthy());
+    }
+
+    #[test]
+    fn test_assess_failure_rate_needs_enough_jobs() {
+        // Two failures out of three is noise, not an outage
+        assert!(JobQueueHealth::assess(None, 3, 2).is_healthy());
+
+        let health = JobQueueHealth::assess(None, 40, 20);
+        assert_eq!(health.failure_rate, 0.5);
+        assert!(!health.is_healthy());
+    }
+}
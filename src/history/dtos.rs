@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    entities::ReadEvent,
+    pagination::{KeysetCursor, SortKey},
+    query::{FieldError, ValidateQuery},
+    repositories::HistoryEntry,
+};
+
+pub const DEFAULT_HISTORY_LIMIT: i64 = 50;
+pub const MAX_HISTORY_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListHistoryQuery {
+    /// Maximum number of entries (default 50, max 200)
+    pub limit: Option<i64>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+}
+
+impl ListHistoryQuery {
+    /// Where the page starts, from the cursor; only call once validated
+    pub fn after(&self) -> Option<KeysetCursor> {
+        self.cursor
+            .as_deref()
+            .and_then(|cursor| KeysetCursor::parse(cursor).ok())
+    }
+}
+
+impl ValidateQuery for ListHistoryQuery {
+    fn validate(&self) -> Result<(), FieldError> {
+        if let Some(limit) = self.limit
+            && !(1..=MAX_HISTORY_LIMIT).contains(&limit)
+        {
+            return Err(FieldError::new(
+                "limit",
+                format!("limit must be between 1 and {}", MAX_HISTORY_LIMIT),
+            ));
+        }
+        if let Some(cursor) = &self.cursor {
+            let cursor = KeysetCursor::parse(cursor).map_err(|e| FieldError::new("cursor", e))?;
+            if !matches!(cursor.key, SortKey::Time(_)) {
+                return Err(FieldError::new(
+                    "cursor",
+                    "cursor is not valid for this endpoint",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadEventResponse {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub opened_at: DateTime<Utc>,
+}
+
+impl From<ReadEvent> for ReadEventResponse {
+    fn from(event: ReadEvent) -> Self {
+        Self {
+            id: event.id,
+            item_id: event.item_id,
+            opened_at: event.opened_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HistoryEntryResponse {
+    /// ID of the read event
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub url: String,
+    pub title: Option<String>,
+    pub site: Option<String>,
+    pub opened_at: DateTime<Utc>,
+}
+
+impl From<HistoryEntry> for HistoryEntryResponse {
+    fn from(entry: HistoryEntry) -> Self {
+        Self {
+            id: entry.id,
+            item_id: entry.item_id,
+            url: entry.url,
+            title: entry.title,
+            site: entry.site,
+            opened_at: entry.opened_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_history_query_validation() {
+        let query = |limit, cursor: Option<String>| ListHistoryQuery { limit, cursor };
+        assert!(query(None, None).validate().is_ok());
+        assert_eq!(query(Some(0), None).validate().unwrap_err().field, "limit");
+        assert_eq!(
+            query(Some(MAX_HISTORY_LIMIT + 1), None)
+                .validate()
+                .unwrap_err()
+                .field,
+            "limit"
+        );
+
+        let cursor = KeysetCursor::new(
+            DateTime::from_timestamp_micros(1_759_000_000_123_456).unwrap(),
+            Uuid::new_v4(),
+        );
+        let valid = query(None, Some(cursor.encode()));
+        assert!(valid.validate().is_ok());
+        assert_eq!(valid.after(), Some(cursor));
+
+        // Cursors from lists sorted by something other than time
+        let numeric = KeysetCursor::with_key(SortKey::Number(3), Uuid::new_v4());
+        assert_eq!(
+            query(None, Some(numeric.encode()))
+                .validate()
+                .unwrap_err()
+                .field,
+            "cursor"
+        );
+        assert_eq!(
+            query(None, Some("20".to_string()))
+                .validate()
+                .unwrap_err()
+                .field,
+            "cursor"
+        );
+    }
+}
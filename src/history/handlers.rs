@@ -0,0 +1,126 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    history::dtos::{
+        DEFAULT_HISTORY_LIMIT, HistoryEntryResponse, ListHistoryQuery, ReadEventResponse,
+    },
+    pagination::Page,
+    query::{FieldError, ValidatedQuery},
+    repositories::ReadEventRepository,
+};
+
+#[utoipa::path(
+    post,
+    path = "/v1/items/{id}/opened",
+    tag = "history",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 201, description = "The open was added to the user's reading history", body = ReadEventResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn record_open(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match ReadEventRepository::new(&state.db_pool)
+        .record(auth_user.user_id, id)
+        .await
+    {
+        Ok(Some(event)) => {
+            (StatusCode::CREATED, Json(ReadEventResponse::from(event))).into_response()
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Item not found".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/history",
+    tag = "history",
+    params(ListHistoryQuery),
+    responses(
+        (status = 200, description = "Items the user opened, most recent first, once per open", body = Page<HistoryEntryResponse>),
+        (status = 400, description = "Invalid query parameter", body = FieldError),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_history(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ValidatedQuery(query): ValidatedQuery<ListHistoryQuery>,
+) -> Response {
+    let repo = ReadEventRepository::new(&state.db_pool);
+    let limit = query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+
+    let mut entries = match repo
+        .list_for_user(auth_user.user_id, query.after(), limit + 1)
+        .await
+    {
+        Ok(entries) => entries,
+        Err(_) => return database_error(),
+    };
+    let total = match repo.count_for_user(auth_user.user_id).await {
+        Ok(total) => Some(total),
+        Err(e) => {
+            warn!("Failed to count read events: {}", e);
+            None
+        }
+    };
+
+    let has_more = entries.len() as i64 > limit;
+    entries.truncate(limit as usize);
+
+    let next_cursor = entries
+        .last()
+        .filter(|_| has_more)
+        .map(|last| last.cursor().encode());
+    let page = Page::new(
+        entries
+            .into_iter()
+            .map(HistoryEntryResponse::from)
+            .collect(),
+        next_cursor,
+        total,
+    );
+
+    (StatusCode::OK, Json(page)).into_response()
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
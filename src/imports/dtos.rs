@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    entities::{ImportFailure, ImportFailureReason},
+    imports::history::HistoryFormat,
+    jobs::MAX_IMPORT_URLS,
+    query::{FieldError, ValidateQuery},
+};
+
+/// Time a page must have been open, in seconds over all visits, to be
+/// imported from history when the request doesn't say
+pub const DEFAULT_MIN_VISIT_SECS: u32 = 30;
+/// Longest minimum visit duration accepted, one day
+pub const MAX_MIN_VISIT_SECS: u32 = 24 * 60 * 60;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateImportRequest {
+    /// URLs to save, one item each; row numbers in the report count from 1
+    pub urls: Vec<String>,
+}
+
+impl CreateImportRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.urls.is_empty() {
+            return Err("urls must not be empty".to_string());
+        }
+        if self.urls.len() > MAX_IMPORT_URLS {
+            return Err(format!(
+                "at most {} urls can be imported at once",
+                MAX_IMPORT_URLS
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryImportQuery {
+    /// What the request body is: `chrome` (the `History` database), `firefox`
+    /// (`places.sqlite`) or `json` (`[{"url": "...", "duration_secs": 42}]`)
+    pub format: HistoryFormat,
+    /// Only pages open at least this many seconds over all visits are
+    /// imported (default 30)
+    pub min_visit_secs: Option<u32>,
+}
+
+impl ValidateQuery for HistoryImportQuery {
+    fn validate(&self) -> Result<(), FieldError> {
+        if let Some(secs) = self.min_visit_secs
+            && secs > MAX_MIN_VISIT_SECS
+        {
+            return Err(FieldError::new(
+                "min_visit_secs",
+                format!("min_visit_secs must be at most {}", MAX_MIN_VISIT_SECS),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+impl ReportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Json => "json",
+            ReportFormat::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportReportQuery {
+    /// `json` (default) or `csv`
+    pub format: Option<ReportFormat>,
+}
+
+impl ValidateQuery for ImportReportQuery {
+    fn validate(&self) -> Result<(), FieldError> {
+        Ok(())
+    }
+}
+
+/// One row of the import that didn't become a readable item
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportFailureResponse {
+    /// Position of the URL in the submitted list, counting from 1
+    pub row: i32,
+    /// The URL as submitted
+    pub url: String,
+    pub reason: ImportFailureReason,
+    pub detail: Option<String>,
+}
+
+impl From<ImportFailure> for ImportFailureResponse {
+    fn from(failure: ImportFailure) -> Self {
+        Self {
+            row: failure.row_number,
+            url: failure.url,
+            reason: failure.reason,
+            detail: failure.detail,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportReportResponse {
+    pub operation_id: Uuid,
+    /// Failed rows in list order; resubmitting their URLs retries just these
+    pub failures: Vec<ImportFailureResponse>,
+}
+
+impl ImportReportResponse {
+    /// The report as CSV with a `row,url,reason,detail` header
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("row,url,reason,detail\r\n");
+        for failure in &self.failures {
+            csv.push_str(&format!(
+                "{},{},{},{}\r\n",
+                failure.row,
+                csv_field(&failure.url),
+                failure.reason.as_str(),
+                csv_field(failure.detail.as_deref().unwrap_or_default()),
+            ));
+        }
+        csv
+    }
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_import_request_validate() {
+        let request = |n: usize| CreateImportRequest {
+            urls: vec!["https://example.com".to_string(); n],
+        };
+        assert!(request(0).validate().is_err());
+        assert!(request(1).validate().is_ok());
+        assert!(request(MAX_IMPORT_URLS).validate().is_ok());
+        assert!(request(MAX_IMPORT_URLS + 1).validate().is_err());
+    }
+
+    #[test]
+    fn test_history_import_query() {
+        let query: HistoryImportQuery = crate::query::parse("format=chrome").unwrap();
+        assert_eq!(query.format, HistoryFormat::Chrome);
+        assert!(query.validate().is_ok());
+
+        let error = crate::query::parse::<HistoryImportQuery>("format=safari").unwrap_err();
+        assert_eq!(error.field, "format");
+        let error = crate::query::parse::<HistoryImportQuery>("min_visit_secs=5").unwrap_err();
+        assert_eq!(error.field, "format");
+
+        let query = HistoryImportQuery {
+            format: HistoryFormat::Json,
+            min_visit_secs: Some(MAX_MIN_VISIT_SECS + 1),
+        };
+        assert_eq!(query.validate().unwrap_err().field, "min_visit_secs");
+    }
+
+    #[test]
+    fn test_to_csv_quotes_fields() {
+        let report = ImportReportResponse {
+            operation_id: Uuid::nil(),
+            failures: vec![
+                ImportFailureResponse {
+                    row: 2,
+                    url: "not a url".to_string(),
+                    reason: ImportFailureReason::BadUrl,
+                    detail: Some("relative URL without a base".to_string()),
+                },
+                ImportFailureResponse {
+                    row: 5,
+                    url: "https://example.com/?a=1,2".to_string(),
+                    reason: ImportFailureReason::FetchForbidden,
+                    detail: Some("said \"no\"".to_string()),
+                },
+                ImportFailureResponse {
+                    row: 7,
+                    url: "https://example.com/".to_string(),
+                    reason: ImportFailureReason::Duplicate,
+                    detail: None,
+                },
+            ],
+        };
+
+        assert_eq!(
+            report.to_csv(),
+            "row,url,reason,detail\r\n\
+             2,not a url,bad_url,relative URL without a base\r\n\
+             5,\"https://example.com/?a=1,2\",fetch_forbidden,\"said \"\"no\"\"\"\r\n\
+             7,https://example.com/,duplicate,\r\n"
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Path, State},
+    http::{
+        StatusCode,
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    },
+    response::{IntoResponse, Response},
+};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    entities::{OperationKind, OperationState},
+    imports::{
+        dtos::{
+            CreateImportRequest, DEFAULT_MIN_VISIT_SECS, HistoryImportQuery, ImportFailureResponse,
+            ImportReportQuery, ImportReportResponse, ReportFormat,
+        },
+        history::{HistoryError, read_history, select_urls},
+    },
+    jobs::{IMPORT_URLS_JOB_KIND, ImportUrlsPayload, MAX_IMPORT_URLS, Outbox},
+    middleware::transaction::RequestTransaction,
+    operations::dtos::OperationResponse,
+    query::{FieldError, ValidatedQuery},
+    repositories::{ImportRepository, OperationRepository},
+};
+
+#[utoipa::path(
+    post,
+    path = "/v1/imports",
+    tag = "imports",
+    request_body = CreateImportRequest,
+    responses(
+        (status = 202, description = "Import queued; follow it at /v1/operations/{id}", body = OperationResponse),
+        (status = 400, description = "No URLs, or too many", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_import(
+    auth_user: AuthenticatedUser,
+    transaction: RequestTransaction,
+    Json(payload): Json<CreateImportRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    queue_import(&transaction, auth_user.user_id, payload.urls).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/imports/history",
+    tag = "imports",
+    params(HistoryImportQuery),
+    request_body(content = Vec<u8>, description = "The browser's history database, or a JSON list of visits", content_type = "application/octet-stream"),
+    responses(
+        (status = 202, description = "Import of the pages read long enough queued; follow it at /v1/operations/{id}", body = OperationResponse),
+        (status = 400, description = "Not a history export in the given format, or no pages met the minimum visit duration", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 413, description = "Upload too large"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn import_history(
+    auth_user: AuthenticatedUser,
+    transaction: RequestTransaction,
+    ValidatedQuery(query): ValidatedQuery<HistoryImportQuery>,
+    upload: Bytes,
+) -> Response {
+    let pages = match read_history(query.format, &upload).await {
+        Ok(pages) => pages,
+        Err(HistoryError::Invalid(error)) => {
+            return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+        }
+        Err(HistoryError::Io(_)) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Failed to read history".to_string(),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let min_visit_secs = query.min_visit_secs.unwrap_or(DEFAULT_MIN_VISIT_SECS);
+    let urls = select_urls(pages, f64::from(min_visit_secs), MAX_IMPORT_URLS);
+    if urls.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!(
+                    "No web pages in the history were open for {} seconds or more",
+                    min_visit_secs
+                ),
+            }),
+        )
+            .into_response();
+    }
+
+    queue_import(&transaction, auth_user.user_id, urls).await
+}
+
+/// Create the import's operation and stage its job, answering with the
+/// operation to follow
+async fn queue_import(
+    transaction: &RequestTransaction,
+    user_id: Uuid,
+    urls: Vec<String>,
+) -> Response {
+    let operation = match OperationRepository::create_in(
+        &mut *transaction.conn().await,
+        user_id,
+        OperationKind::Import,
+    )
+    .await
+    {
+        Ok(operation) => operation,
+        Err(_) => return database_error(),
+    };
+
+    let job = ImportUrlsPayload {
+        operation_id: operation.id,
+        urls,
+    };
+    let job = match serde_json::to_value(&job) {
+        Ok(job) => job,
+        Err(_) => return database_error(),
+    };
+
+    // Committed with the operation by the transaction middleware
+    if Outbox::enqueue(
+        &mut *transaction.conn().await,
+        IMPORT_URLS_JOB_KIND,
+        job,
+        None,
+    )
+    .await
+    .is_err()
+    {
+        return database_error();
+    }
+
+    (
+        StatusCode::ACCEPTED,
+        Json(OperationResponse::from(operation)),
+    )
+        .into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/imports/{id}/report",
+    tag = "imports",
+    params(
+        ("id" = Uuid, Path, description = "Operation ID of the import"),
+        ImportReportQuery
+    ),
+    responses(
+        (status = 200, description = "Rows that failed, as JSON or as a CSV attachment", body = ImportReportResponse),
+        (status = 400, description = "Unknown format", body = FieldError),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Import not found", body = ErrorResponse),
+        (status = 409, description = "Import hasn't finished yet", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_import_report(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidatedQuery(query): ValidatedQuery<ImportReportQuery>,
+) -> Response {
+    let operation = match OperationRepository::new(&state.db_pool)
+        .find_for_user(auth_user.user_id, id)
+        .await
+    {
+        Ok(Some(operation)) if operation.kind == OperationKind::Import => operation,
+        Ok(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "Import not found".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Err(_) => return database_error(),
+    };
+
+    if matches!(
+        operation.state,
+        OperationState::Pending | OperationState::Running
+    ) {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Import hasn't finished yet".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let failures = match ImportRepository::new(&state.db_pool)
+        .failures(operation.id)
+        .await
+    {
+        Ok(failures) => failures,
+        Err(_) => return database_error(),
+    };
+
+    let report = ImportReportResponse {
+        operation_id: operation.id,
+        failures: failures
+            .into_iter()
+            .map(ImportFailureResponse::from)
+            .collect(),
+    };
+
+    let format = query.format.unwrap_or_default();
+    let disposition = format!(
+        "attachment; filename=\"import-{}-failures.{}\"",
+        operation.id,
+        format.extension()
+    );
+    match format {
+        ReportFormat::Json => (
+            StatusCode::OK,
+            [(CONTENT_DISPOSITION, disposition)],
+            Json(report),
+        )
+            .into_response(),
+        ReportFormat::Csv => (
+            StatusCode::OK,
+            [
+                (CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (CONTENT_DISPOSITION, disposition),
+            ],
+            report.to_csv(),
+        )
+            .into_response(),
+    }
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
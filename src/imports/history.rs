@@ -0,0 +1,199 @@
+use serde::Deserialize;
+use sqlx::{
+    ConnectOptions, Connection,
+    sqlite::{SqliteConnectOptions, SqliteConnection},
+};
+use std::{collections::HashMap, path::Path};
+use thiserror::Error;
+use url::Url;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Largest history upload accepted; browser databases of a few years of
+/// browsing stay well under this
+pub const MAX_HISTORY_BYTES: usize = 64 * 1024 * 1024;
+
+/// The kinds of browser history uploads that can be imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryFormat {
+    /// A JSON array of visits, `[{"url": "...", "duration_secs": 42}]`
+    Json,
+    /// Chrome's `History` SQLite database
+    Chrome,
+    /// Firefox's `places.sqlite` database
+    Firefox,
+}
+
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    /// The upload isn't a history export in the stated format
+    #[error("{0}")]
+    Invalid(String),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// A page from the history and how long the user spent on it over all visits
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryPage {
+    pub url: String,
+    pub visit_secs: f64,
+}
+
+#[derive(Deserialize)]
+struct JsonVisit {
+    url: String,
+    #[serde(default)]
+    duration_secs: f64,
+}
+
+/// Chrome records each visit's duration in microseconds
+const CHROME_PAGES_SQL: &str = r#"
+    SELECT u.url, CAST(COALESCE(SUM(v.visit_duration), 0) AS REAL) / 1000000.0
+    FROM visits v
+    JOIN urls u ON u.id = v.url
+    GROUP BY u.id
+"#;
+
+/// Firefox keeps the time a page was in the foreground, in milliseconds, in
+/// its interaction metadata
+const FIREFOX_PAGES_SQL: &str = r#"
+    SELECT p.url, CAST(COALESCE(SUM(m.total_view_time), 0) AS REAL) / 1000.0
+    FROM moz_places_metadata m
+    JOIN moz_places p ON p.id = m.place_id
+    GROUP BY p.id
+"#;
+
+/// The pages in a history upload with the time spent on each
+pub async fn read_history(
+    format: HistoryFormat,
+    upload: &[u8],
+) -> Result<Vec<HistoryPage>, HistoryError> {
+    match format {
+        HistoryFormat::Json => read_json(upload),
+        HistoryFormat::Chrome => read_sqlite(upload, CHROME_PAGES_SQL, "Chrome").await,
+        HistoryFormat::Firefox => read_sqlite(upload, FIREFOX_PAGES_SQL, "Firefox").await,
+    }
+}
+
+fn read_json(upload: &[u8]) -> Result<Vec<HistoryPage>, HistoryError> {
+    let visits: Vec<JsonVisit> = serde_json::from_slice(upload)
+        .map_err(|e| HistoryError::Invalid(format!("Invalid JSON history: {}", e)))?;
+
+    let mut pages: Vec<HistoryPage> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for visit in visits {
+        let duration = visit.duration_secs.max(0.0);
+        match index.get(&visit.url) {
+            Some(&i) => pages[i].visit_secs += duration,
+            None => {
+                index.insert(visit.url.clone(), pages.len());
+                pages.push(HistoryPage {
+                    url: visit.url,
+                    visit_secs: duration,
+                });
+            }
+        }
+    }
+    Ok(pages)
+}
+
+/// SQLite needs a file to open, so the upload is written to a temporary one
+/// for the duration of the query
+async fn read_sqlite(
+    upload: &[u8],
+    sql: &str,
+    browser: &str,
+) -> Result<Vec<HistoryPage>, HistoryError> {
+    let path = std::env::temp_dir().join(format!("capsule-history-{}.sqlite", Uuid::new_v4()));
+    tokio::fs::write(&path, upload).await?;
+
+    let pages = query_sqlite(&path, sql).await;
+    let _ = tokio::fs::remove_file(&path).await;
+
+    pages.map_err(|_| HistoryError::Invalid(format!("Not a {} history database", browser)))
+}
+
+async fn query_sqlite(path: &Path, sql: &str) -> Result<Vec<HistoryPage>, sqlx::Error> {
+    let mut conn: SqliteConnection = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true)
+        .connect()
+        .await?;
+    let rows: Vec<(String, f64)> = sqlx::query_as(sql).fetch_all(&mut conn).await?;
+    conn.close().await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(url, visit_secs)| HistoryPage { url, visit_secs })
+        .collect())
+}
+
+/// URLs of the web pages the user spent at least `min_visit_secs` on, the
+/// longest read first, at most `limit` of them. Browser-internal pages and
+/// other non-web URLs are left out rather than reported as failed rows.
+pub fn select_urls(pages: Vec<HistoryPage>, min_visit_secs: f64, limit: usize) -> Vec<String> {
+    let mut pages: Vec<HistoryPage> = pages
+        .into_iter()
+        .filter(|page| page.visit_secs >= min_visit_secs)
+        .filter(|page| {
+            Url::parse(&page.url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+        })
+        .collect();
+    pages.sort_by(|a, b| b.visit_secs.total_cmp(&a.visit_secs));
+    pages.truncate(limit);
+    pages.into_iter().map(|page| page.url).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(url: &str, visit_secs: f64) -> HistoryPage {
+        HistoryPage {
+            url: url.to_string(),
+            visit_secs,
+        }
+    }
+
+    #[test]
+    fn test_read_json_sums_visits_per_url() {
+        let upload = br#"[
+            {"url": "https://example.com/a", "duration_secs": 20},
+            {"url": "https://example.com/b"},
+            {"url": "https://example.com/a", "duration_secs": 25.5}
+        ]"#;
+        assert_eq!(
+            read_json(upload).unwrap(),
+            vec![
+                page("https://example.com/a", 45.5),
+                page("https://example.com/b", 0.0)
+            ]
+        );
+        assert!(matches!(
+            read_json(b"{\"url\": 1}"),
+            Err(HistoryError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_select_urls_filters_and_orders_by_time_spent() {
+        let pages = vec![
+            page("https://example.com/skimmed", 5.0),
+            page("https://example.com/read", 120.0),
+            page("chrome://settings/", 600.0),
+            page("https://example.com/studied", 900.0),
+            page("not a url", 900.0),
+        ];
+        assert_eq!(
+            select_urls(pages.clone(), 60.0, 10),
+            vec!["https://example.com/studied", "https://example.com/read"]
+        );
+        assert_eq!(
+            select_urls(pages, 0.0, 1),
+            vec!["https://example.com/studied"]
+        );
+    }
+}
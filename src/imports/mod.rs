@@ -0,0 +1,3 @@
+pub mod dtos;
+pub mod handlers;
+pub mod history;
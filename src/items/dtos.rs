@@ -1,21 +1,498 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use std::{collections::HashSet, str::FromStr};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::entities::ItemStatus;
+use crate::{
+    annotations::dtos::MAX_NOTE_CHARS,
+    entities::{
+        ExtractionFailure, FetchAttempt, ItemEvent, ItemEventKind, ItemStateTransition, ItemStatus,
+        LinkedItem, ProcessingState, UserPreferences,
+    },
+    extractor::{Heading, TextMap, math, text_map},
+    fetcher::UrlPolicy,
+    jobs::{MAX_REFRESH_INTERVAL_SECS, MIN_REFRESH_INTERVAL_SECS},
+    pagination::KeysetCursor,
+    query::{FieldError, ValidateQuery},
+    repositories::{CleanContent, ContentFields, ItemDetails, ItemLinks, ItemSort, ReadingTime},
+    scheduling::SnoozePreset,
+    topics::is_topic_name,
+};
 
-#[derive(Debug, Deserialize, ToSchema)]
+/// Maximum number of items a client may request in a single content batch.
+pub const MAX_BATCH_CONTENT_ITEMS: usize = 50;
+/// Per-item size limit (bytes) for each content field returned in a batch.
+pub const MAX_BATCH_CONTENT_BYTES: usize = 512 * 1024;
+
+pub const DEFAULT_ITEM_LIST_LIMIT: i64 = 50;
+pub const MAX_ITEM_LIST_LIMIT: i64 = 200;
+
+/// Longest title a user may give an item, in bytes
+pub const MAX_TITLE_LENGTH: usize = 1000;
+
+/// Most tags a save may name, and most default tags a user may set
+pub const MAX_SAVE_TAGS: usize = 20;
+
+/// Most item IDs one bulk request may name, over all its operations
+pub const MAX_BULK_ITEMS: usize = 500;
+
+/// Largest `max_reading_time`, in minutes; a day is as long as the
+/// `reading_time` bounds go too
+pub const MAX_READING_TIME_FILTER: i32 = 1440;
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListItemsQuery {
+    /// Only items with this status
+    pub status: Option<ItemStatus>,
+    /// Only items with the tag of this name
+    pub tag: Option<String>,
+    /// Only items whose title or URL contains this text, ignoring case
+    pub q: Option<String>,
+    /// Only items assigned this topic, e.g. `tech`
+    pub topic: Option<String>,
+    /// Only items whose content was detected as this language (e.g. `en`)
+    pub lang: Option<String>,
+    /// Only items from this domain, e.g. `example.com`; a leading `www.` is
+    /// ignored, and subdomains are sites of their own
+    pub site: Option<String>,
+    /// Only items saved before this time, e.g. `2025-10-01T00:00:00Z`
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only items saved at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// `failed` lists items the extractor rejected, for triage
+    pub extraction: Option<ExtractionFilter>,
+    /// `<N` for items that take under N minutes to read, `>N` for over N.
+    /// Items whose text hasn't been extracted never match.
+    pub reading_time: Option<String>,
+    /// Only items that take at most this many minutes to read, e.g. `10`
+    /// for short reads. Items whose text hasn't been extracted never match.
+    pub max_reading_time: Option<i32>,
+    /// `created_at`, `updated_at`, `title` or `reading_time`, ascending, or
+    /// descending with a leading `-`; newest first (`-created_at`) by default
+    pub sort: Option<String>,
+    /// Maximum number of items (default 50, max 200)
+    pub limit: Option<i64>,
+    /// `next_cursor` from the previous page, listed in the same `sort` order
+    pub cursor: Option<String>,
+    /// Items to skip, to jump to a position; page with `cursor` instead,
+    /// which stays fast however deep the list goes
+    pub offset: Option<i64>,
+    /// Also list items snoozed until later, which are left out by default
+    #[serde(default)]
+    pub include_snoozed: bool,
+}
+
+impl ListItemsQuery {
+    /// The order to list in; only call once validated
+    pub fn sort(&self) -> ItemSort {
+        self.sort
+            .as_deref()
+            .and_then(|sort| sort.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Where the page starts, from the cursor; only call once validated
+    pub fn after(&self) -> Option<KeysetCursor> {
+        self.cursor
+            .as_deref()
+            .and_then(|cursor| KeysetCursor::parse(cursor).ok())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtractionFilter {
+    Failed,
+}
+
+impl ValidateQuery for ListItemsQuery {
+    fn validate(&self) -> Result<(), FieldError> {
+        if let Some(lang) = &self.lang {
+            validate_lang(lang).map_err(|e| FieldError::new("lang", e))?;
+        }
+        if let Some(site) = &self.site
+            && !is_site_domain(site)
+        {
+            return Err(FieldError::new(
+                "site",
+                "site must be a domain such as example.com",
+            ));
+        }
+        if let (Some(before), Some(after)) = (self.created_before, self.created_after)
+            && before <= after
+        {
+            return Err(FieldError::new(
+                "created_before",
+                "created_before must be later than created_after",
+            ));
+        }
+        if let Some(tag) = &self.tag
+            && (tag.trim().is_empty() || tag.len() > 100)
+        {
+            return Err(FieldError::new(
+                "tag",
+                "tag must be between 1 and 100 characters",
+            ));
+        }
+        if let Some(q) = &self.q
+            && (q.trim().is_empty() || q.len() > 500)
+        {
+            return Err(FieldError::new(
+                "q",
+                "q must be between 1 and 500 characters",
+            ));
+        }
+        if let Some(topic) = &self.topic
+            && !is_topic_name(topic)
+        {
+            return Err(FieldError::new(
+                "topic",
+                "topic must be a lowercase name such as tech",
+            ));
+        }
+        if let Some(reading_time) = &self.reading_time {
+            reading_time
+                .parse::<ReadingTime>()
+                .map_err(|e| FieldError::new("reading_time", e))?;
+        }
+        if let Some(sort) = &self.sort {
+            sort.parse::<ItemSort>()
+                .map_err(|e| FieldError::new("sort", e))?;
+        }
+        if let Some(max_reading_time) = self.max_reading_time
+            && !(1..=MAX_READING_TIME_FILTER).contains(&max_reading_time)
+        {
+            return Err(FieldError::new(
+                "max_reading_time",
+                format!(
+                    "max_reading_time must be between 1 and {} minutes",
+                    MAX_READING_TIME_FILTER
+                ),
+            ));
+        }
+        if let Some(limit) = self.limit
+            && !(1..=MAX_ITEM_LIST_LIMIT).contains(&limit)
+        {
+            return Err(FieldError::new(
+                "limit",
+                format!("limit must be between 1 and {}", MAX_ITEM_LIST_LIMIT),
+            ));
+        }
+        if let Some(offset) = self.offset
+            && offset < 0
+        {
+            return Err(FieldError::new("offset", "offset cannot be negative"));
+        }
+        if let Some(cursor) = &self.cursor {
+            if self.offset.is_some() {
+                return Err(FieldError::new(
+                    "cursor",
+                    "cursor can't be combined with offset",
+                ));
+            }
+            let cursor = KeysetCursor::parse(cursor).map_err(|e| FieldError::new("cursor", e))?;
+            if !self.sort().accepts(&cursor) {
+                return Err(FieldError::new(
+                    "cursor",
+                    "cursor is from a list in another sort order",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A host name as it appears in a URL, without scheme, port or path
+fn is_site_domain(site: &str) -> bool {
+    let site = site.trim();
+    (1..=253).contains(&site.len())
+        && site
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_'))
+}
+
+/// A tag name as users give it: 1 to 100 characters, not all blank
+pub fn is_tag_name(tag: &str) -> bool {
+    !tag.trim().is_empty() && tag.len() <= 100
+}
+
+/// What a save says it came from, e.g. `extension`: 1 to 32 lowercase
+/// letters, digits, `-` or `_`
+pub fn is_save_source(source: &str) -> bool {
+    (1..=32).contains(&source.len())
+        && source
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'-' | b'_'))
+}
+
+/// Language filters are the two or three letter codes stored by the extractor.
+pub fn validate_lang(lang: &str) -> Result<(), String> {
+    if (2..=3).contains(&lang.len()) && lang.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(())
+    } else {
+        Err("lang must be a two or three letter language code".to_string())
+    }
+}
+
+/// A URL to save, with anything to set on the new item straight away
+#[derive(Debug, Default, Deserialize, ToSchema)]
 pub struct CreateItemRequest {
     pub url: String,
+    /// Names of tags to attach, created if needed; the user's default tags
+    /// are attached as well
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Star the item
+    #[serde(default)]
+    pub favorite: bool,
+    /// A note on the item
+    pub note: Option<String>,
+    /// What the item is saved from, e.g. `extension`; decides whether the
+    /// user's `default_tags` apply
+    pub source: Option<String>,
+    /// Fetch the page again when the URL is already saved
+    #[serde(default)]
+    pub refresh: bool,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PreviewItemRequest {
+    pub url: String,
+}
+
+/// What a page will look like once saved
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ItemPreviewResponse {
+    /// Where the URL led after redirects
+    pub url: String,
+    pub title: Option<String>,
+    pub site: Option<String>,
+    /// The page's preview image, from its Open Graph or Twitter card tags
+    pub lead_image: Option<String>,
+    /// Null when the page's content couldn't be extracted
+    pub reading_time_minutes: Option<i32>,
+    pub language: Option<String>,
+    /// Why the content couldn't be extracted; the page may still be saved
+    pub extraction_error: Option<ExtractionFailure>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Fields to change on an item; absent fields are left alone
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateItemRequest {
     pub title: Option<String>,
+    /// Archive an item, or unarchive it with `fetched`. A fetched item can
+    /// be marked `reading`, and `fetched` puts it back in the backlog. Items
+    /// can't be moved back to `pending`.
     pub status: Option<ItemStatus>,
 }
 
+impl UpdateItemRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(title) = &self.title
+            && (title.trim().is_empty() || title.len() > MAX_TITLE_LENGTH)
+        {
+            return Err(format!(
+                "title must be between 1 and {} characters",
+                MAX_TITLE_LENGTH
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetProgressRequest {
+    /// Fraction of the item read, from 0 to 1
+    pub progress: f32,
+}
+
+impl SetProgressRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if (0.0..=1.0).contains(&self.progress) {
+            Ok(())
+        } else {
+            Err("progress must be between 0 and 1".to_string())
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkItemsRequest {
+    /// Applied in order, all in one transaction
+    pub operations: Vec<BulkOperation>,
+}
+
+/// One change applied to many items
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOperation {
+    Archive {
+        item_ids: Vec<Uuid>,
+    },
+    /// Also drops the items' pending jobs
+    Delete {
+        item_ids: Vec<Uuid>,
+    },
+    /// Attach the tag of this name, creating it if needed
+    Tag {
+        item_ids: Vec<Uuid>,
+        tag: String,
+    },
+    /// Star the items, or unstar them with `"favorite": false`
+    Favorite {
+        item_ids: Vec<Uuid>,
+        favorite: Option<bool>,
+    },
+}
+
+impl BulkOperation {
+    pub fn item_ids(&self) -> &[Uuid] {
+        match self {
+            BulkOperation::Archive { item_ids }
+            | BulkOperation::Delete { item_ids }
+            | BulkOperation::Tag { item_ids, .. }
+            | BulkOperation::Favorite { item_ids, .. } => item_ids,
+        }
+    }
+}
+
+impl BulkItemsRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.operations.is_empty() {
+            return Err("operations cannot be empty".to_string());
+        }
+        for operation in &self.operations {
+            if operation.item_ids().is_empty() {
+                return Err("item_ids cannot be empty".to_string());
+            }
+            if let BulkOperation::Tag { tag, .. } = operation
+                && (tag.trim().is_empty() || tag.len() > 100)
+            {
+                return Err("tag must be between 1 and 100 characters".to_string());
+            }
+        }
+        let total: usize = self
+            .operations
+            .iter()
+            .map(|operation| operation.item_ids().len())
+            .sum();
+        if total > MAX_BULK_ITEMS {
+            return Err(format!(
+                "At most {} item_ids may be given at once",
+                MAX_BULK_ITEMS
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkItemStatus {
+    Ok,
+    /// The item doesn't exist, belongs to someone else or was deleted by an
+    /// earlier operation
+    NotFound,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkItemResult {
+    /// Position of the operation in the request
+    pub operation: usize,
+    pub item_id: Uuid,
+    pub status: BulkItemStatus,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkItemsResponse {
+    /// One per item ID of each operation, in request order
+    pub results: Vec<BulkItemResult>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TriageRequest {
+    /// At most one per item, applied all together or not at all
+    pub decisions: Vec<TriageDecision>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct TriageDecision {
+    pub id: Uuid,
+    /// `archive`, `keep`, `delete`, or `tag:<name>` to attach the tag of
+    /// that name
+    pub action: String,
+}
+
+/// What to do with an item being triaged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TriageAction {
+    Archive,
+    /// Leave the item as it is
+    Keep,
+    Tag(String),
+    Delete,
+}
+
+impl FromStr for TriageAction {
+    type Err = String;
+
+    fn from_str(action: &str) -> Result<Self, Self::Err> {
+        match action {
+            "archive" => Ok(TriageAction::Archive),
+            "keep" => Ok(TriageAction::Keep),
+            "delete" => Ok(TriageAction::Delete),
+            _ => match action.strip_prefix("tag:").map(str::trim) {
+                Some(tag) if !tag.is_empty() && tag.len() <= 100 => {
+                    Ok(TriageAction::Tag(tag.to_string()))
+                }
+                Some(_) => Err("tag must be between 1 and 100 characters".to_string()),
+                None => Err(format!(
+                    "Unknown action '{}'; expected archive, keep, delete or tag:<name>",
+                    action
+                )),
+            },
+        }
+    }
+}
+
+impl TriageRequest {
+    /// The decisions' items and parsed actions, in request order
+    pub fn validate(&self) -> Result<Vec<(Uuid, TriageAction)>, String> {
+        if self.decisions.is_empty() {
+            return Err("decisions cannot be empty".to_string());
+        }
+        if self.decisions.len() > MAX_BULK_ITEMS {
+            return Err(format!(
+                "At most {} decisions may be given at once",
+                MAX_BULK_ITEMS
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        self.decisions
+            .iter()
+            .map(|decision| {
+                if !seen.insert(decision.id) {
+                    return Err(format!("Item {} has more than one decision", decision.id));
+                }
+                Ok((decision.id, decision.action.parse()?))
+            })
+            .collect()
+    }
+}
+
+/// How many items each action was applied to
+#[derive(Debug, Default, PartialEq, Serialize, ToSchema)]
+pub struct TriageResponse {
+    pub archived: i32,
+    pub kept: i32,
+    pub tagged: i32,
+    pub deleted: i32,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ItemResponse {
     pub id: Uuid,
@@ -24,22 +501,549 @@ pub struct ItemResponse {
     pub title: Option<String>,
     pub site: Option<String>,
     pub status: ItemStatus,
+    /// Set when the last fetch was rejected by the extractor
+    pub extraction_error: Option<ExtractionFailure>,
+    /// Why fetching the page failed for good; set on `failed` items, and on
+    /// others whose latest refresh failed
+    pub last_error: Option<String>,
+    /// Progress through the fetch/extract pipeline
+    pub processing_state: ProcessingState,
+    pub processing_state_changed_at: DateTime<Utc>,
+    /// The page looks like adult content; clients may blur its thumbnail
+    /// in shared spaces
+    pub nsfw: bool,
+    /// Names of the attached tags
+    pub tags: Vec<String>,
+    /// Broad topics the item was classified into, best first
+    pub topics: Vec<String>,
+    /// Words in the extracted text; null until extraction finishes
+    pub word_count: Option<i32>,
+    /// Estimated from the extracted text; null until extraction finishes
+    pub reading_time_minutes: Option<i32>,
+    /// How far the user has read, from 0 to 1
+    pub read_progress: f32,
+    /// Starred by the user
+    pub favorite: bool,
+    /// Bumped by every edit to the title, status, note, progress or star;
+    /// fetching the page leaves it alone. Edits sent with
+    /// `If-Match: "v<version>"` are refused with 409 once it has moved on.
+    pub version: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Only present with `include=content`; null until the page is extracted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<Option<ItemContentResponse>>,
+    /// Only present when a save found the URL already saved: the ID of the
+    /// item it was merged into, which is this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<Uuid>,
+}
+
+impl From<ItemDetails> for ItemResponse {
+    fn from(details: ItemDetails) -> Self {
+        let ItemDetails {
+            item,
+            tags,
+            topics,
+            word_count,
+            reading_time_minutes,
+        } = details;
+        Self {
+            id: item.id,
+            user_id: item.user_id,
+            url: item.url,
+            title: item.title,
+            site: item.site,
+            status: item.status,
+            extraction_error: item
+                .extraction_error
+                .as_deref()
+                .and_then(ExtractionFailure::parse),
+            last_error: item.last_error,
+            processing_state: item.processing_state,
+            processing_state_changed_at: item.processing_state_changed_at,
+            nsfw: item.nsfw,
+            tags,
+            topics,
+            word_count,
+            reading_time_minutes,
+            read_progress: item.read_progress,
+            favorite: item.favorite,
+            version: item.version,
+            created_at: item.created_at,
+            updated_at: item.updated_at,
+            content: None,
+            duplicate_of: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StateTransitionResponse {
+    /// Null for the first recorded transition of items created before
+    /// transitions were tracked
+    pub from_state: Option<ProcessingState>,
+    pub to_state: ProcessingState,
+    pub at: DateTime<Utc>,
+}
+
+impl From<ItemStateTransition> for StateTransitionResponse {
+    fn from(transition: ItemStateTransition) -> Self {
+        Self {
+            from_state: transition.from_state,
+            to_state: transition.to_state,
+            at: transition.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StateTransitionListResponse {
+    pub item_id: Uuid,
+    pub transitions: Vec<StateTransitionResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ItemEventResponse {
+    pub kind: ItemEventKind,
+    /// Extra context, e.g. the HTTP status of a fetch or why it failed
+    pub detail: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+impl From<ItemEvent> for ItemEventResponse {
+    fn from(event: ItemEvent) -> Self {
+        Self {
+            kind: event.kind,
+            detail: event.detail,
+            at: event.created_at,
+        }
+    }
+}
+
+/// One try at fetching the item's page
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FetchAttemptResponse {
+    pub at: DateTime<Utc>,
+    /// Where redirects led; null when no response came back
+    pub final_url: Option<String>,
+    /// HTTP status of the response, if there was one
+    pub status: Option<i16>,
+    /// Kind of failure, e.g. `dns`, `request_timeout` or `http`
+    pub error_class: Option<String>,
+    pub error: Option<String>,
+    pub duration_ms: i32,
+    /// Size of the response body
+    pub bytes: Option<i64>,
+    /// Time spent resolving the host; null when a pooled connection was reused
+    pub dns_ms: Option<i32>,
+    /// Time spent on the TCP and TLS handshakes together; null when a pooled
+    /// connection was reused
+    pub connect_ms: Option<i32>,
+    /// Time from sending the request to the response headers
+    pub ttfb_ms: Option<i32>,
+    /// Time spent downloading the body
+    pub download_ms: Option<i32>,
+}
+
+impl From<FetchAttempt> for FetchAttemptResponse {
+    fn from(attempt: FetchAttempt) -> Self {
+        Self {
+            at: attempt.attempted_at,
+            final_url: attempt.final_url,
+            status: attempt.status,
+            error_class: attempt.error_class,
+            error: attempt.error,
+            duration_ms: attempt.duration_ms,
+            bytes: attempt.bytes,
+            dns_ms: attempt.dns_ms,
+            connect_ms: attempt.connect_ms,
+            ttfb_ms: attempt.ttfb_ms,
+            download_ms: attempt.download_ms,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ItemEventListResponse {
+    pub item_id: Uuid,
+    pub events: Vec<ItemEventResponse>,
+    /// The latest fetches of the page, oldest first
+    pub fetch_attempts: Vec<FetchAttemptResponse>,
+}
+
+/// A saved item at the other end of a link
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LinkedItemResponse {
+    pub id: Uuid,
+    pub url: String,
+    pub title: Option<String>,
+    pub site: Option<String>,
+}
+
+impl From<LinkedItem> for LinkedItemResponse {
+    fn from(item: LinkedItem) -> Self {
+        Self {
+            id: item.id,
+            url: item.url,
+            title: item.title,
+            site: item.site,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ItemLinksResponse {
+    pub item_id: Uuid,
+    /// Saved items this item links to, in the order the links appear
+    pub cites: Vec<LinkedItemResponse>,
+    /// Saved items linking to this one, newest first
+    pub cited_by: Vec<LinkedItemResponse>,
+    /// Links to pages that aren't saved, in the order they appear
+    pub unsaved_links: Vec<String>,
+}
+
+impl ItemLinksResponse {
+    pub fn new(item_id: Uuid, links: ItemLinks) -> Self {
+        Self {
+            item_id,
+            cites: links.cites.into_iter().map(Into::into).collect(),
+            cited_by: links.cited_by.into_iter().map(Into::into).collect(),
+            unsaved_links: links.unsaved,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetItemQuery {
+    /// Comma-separated extras to embed in the item; `content` adds the
+    /// extracted text and HTML
+    pub include: Option<String>,
+    /// Comma-separated parts of the included content, as for
+    /// `content:batchGet`. Defaults to all of them.
+    pub fields: Option<String>,
+}
+
+impl GetItemQuery {
+    pub fn includes_content(&self) -> bool {
+        self.include
+            .as_deref()
+            .is_some_and(|include| include.split(',').any(|part| part.trim() == "content"))
+    }
+
+    pub fn content_fields(&self) -> Result<ContentFields, String> {
+        parse_content_fields(self.fields.as_deref())
+    }
+}
+
+impl ValidateQuery for GetItemQuery {
+    fn validate(&self) -> Result<(), FieldError> {
+        if let Some(other) = self
+            .include
+            .iter()
+            .flat_map(|include| include.split(','))
+            .map(str::trim)
+            .find(|part| *part != "content")
+        {
+            return Err(FieldError::new(
+                "include",
+                format!("unknown include `{}`; expected content", other),
+            ));
+        }
+        self.content_fields()
+            .map(|_| ())
+            .map_err(|e| FieldError::new("fields", e))
+    }
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RandomItemQuery {
+    /// Only pick among items with this status
+    pub status: Option<ItemStatus>,
+    /// Also pick among items snoozed until later
+    #[serde(default)]
+    pub include_snoozed: bool,
+}
+
+impl ValidateQuery for RandomItemQuery {
+    fn validate(&self) -> Result<(), FieldError> {
+        if self.status == Some(ItemStatus::Archived) {
+            return Err(FieldError::new(
+                "status",
+                "archived items are never unread; expected pending or fetched",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LookupItemQuery {
+    /// URL to look for; tracking parameters and fragments don't matter,
+    /// just as when saving
+    pub url: String,
+}
+
+impl ValidateQuery for LookupItemQuery {
+    fn validate(&self) -> Result<(), FieldError> {
+        validate_item_url(&self.url).map_err(|e| FieldError::new("url", e))
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchGetContentRequest {
+    pub item_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ContentFieldsQuery {
+    /// Comma-separated parts of the content to return: `metadata` (lang,
+    /// extracted_at, summary), `text` (clean_text, text_map) and `html` (clean_html,
+    /// outline). Defaults to all of them.
+    pub fields: Option<String>,
 }
 
+impl ContentFieldsQuery {
+    pub fn content_fields(&self) -> Result<ContentFields, String> {
+        parse_content_fields(self.fields.as_deref())
+    }
+}
+
+fn parse_content_fields(fields: Option<&str>) -> Result<ContentFields, String> {
+    let Some(fields) = fields else {
+        return Ok(ContentFields::ALL);
+    };
+
+    let mut selected = ContentFields {
+        metadata: false,
+        text: false,
+        html: false,
+    };
+    for field in fields.split(',').map(str::trim) {
+        match field {
+            "metadata" => selected.metadata = true,
+            "text" => selected.text = true,
+            "html" => selected.html = true,
+            other => {
+                return Err(format!(
+                    "unknown field `{}`; expected metadata, text or html",
+                    other
+                ));
+            }
+        }
+    }
+    Ok(selected)
+}
+
+impl ValidateQuery for ContentFieldsQuery {
+    fn validate(&self) -> Result<(), FieldError> {
+        self.content_fields()
+            .map(|_| ())
+            .map_err(|e| FieldError::new("fields", e))
+    }
+}
+
+/// Content for one item. Parts left out of the request's `fields` are null
+/// or empty.
 #[derive(Debug, Serialize, ToSchema)]
-pub struct ItemListResponse {
-    pub items: Vec<ItemResponse>,
+pub struct ItemContentResponse {
+    pub item_id: Uuid,
+    pub clean_text: Option<String>,
+    pub clean_html: Option<String>,
+    pub lang: Option<String>,
+    pub extracted_at: Option<DateTime<Utc>>,
+    /// A few sentences summing up the text; null until summarized
+    pub summary: Option<String>,
+    /// True when one or more fields exceeded the per-item size limit and were
+    /// omitted; clients should fetch the item individually.
+    pub too_large: bool,
+    /// True when the content contains MathML or LaTeX formulas, so clients
+    /// know to load a math renderer; only the selected text and HTML are
+    /// checked
+    pub has_math: bool,
+    /// Headings of `clean_html`, in document order, for in-article navigation
+    pub outline: Vec<Heading>,
+    /// Paragraph and sentence offsets of `clean_text` for anchoring
+    /// highlights; present even when `clean_text` is omitted as too large
+    pub text_map: TextMap,
+}
+
+impl ItemContentResponse {
+    /// The response for one content row. Fields longer than
+    /// `max_field_bytes` are omitted rather than cut mid-markup.
+    pub fn new(row: &CleanContent, max_field_bytes: usize) -> Self {
+        let mut too_large = false;
+        let mut within_limit = |field: &Option<String>| match field {
+            Some(value) if value.len() > max_field_bytes => {
+                too_large = true;
+                None
+            }
+            other => other.clone(),
+        };
+        let clean_text = within_limit(&row.clean_text);
+        let clean_html = within_limit(&row.clean_html);
+
+        Self {
+            item_id: row.item_id,
+            clean_text,
+            clean_html,
+            lang: row.lang.clone(),
+            extracted_at: row.extracted_at,
+            summary: row.summary.clone(),
+            too_large,
+            has_math: math::has_math(row.clean_html.as_deref(), row.clean_text.as_deref()),
+            outline: row
+                .outline
+                .as_ref()
+                .map(|outline| outline.0.clone())
+                .unwrap_or_default(),
+            text_map: row
+                .clean_text
+                .as_deref()
+                .map(text_map::build_text_map)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchGetContentResponse {
+    pub contents: Vec<ItemContentResponse>,
+    /// Requested IDs that don't exist, aren't owned by the caller, or have no
+    /// content yet.
+    pub missing: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SnoozeItemRequest {
+    /// Resolved against the user's timezone preference (UTC if unset)
+    pub preset: SnoozePreset,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SnoozeItemResponse {
+    pub item_id: Uuid,
+    pub snoozed_until: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetRefreshPolicyRequest {
+    /// Refetch the page this often (seconds); null stops refreshing
+    pub interval_secs: Option<i64>,
+    /// Notify when a refresh changes the text materially; needs an interval
+    #[serde(default)]
+    pub watch: bool,
+}
+
+impl SetRefreshPolicyRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(interval) = self.interval_secs
+            && !(MIN_REFRESH_INTERVAL_SECS..=MAX_REFRESH_INTERVAL_SECS).contains(&interval)
+        {
+            return Err(format!(
+                "interval_secs must be between {} and {}",
+                MIN_REFRESH_INTERVAL_SECS, MAX_REFRESH_INTERVAL_SECS
+            ));
+        }
+        if self.watch && self.interval_secs.is_none() {
+            return Err("watch requires interval_secs".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RefreshPolicyResponse {
+    pub item_id: Uuid,
+    pub interval_secs: Option<i64>,
+    pub watch: bool,
+    pub next_refresh_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RetryExtractionResponse {
+    pub item_id: Uuid,
+    /// The `fetch_page` job, queued once the worker relays it from the outbox
+    pub job_id: Uuid,
 }
 
 impl CreateItemRequest {
     pub fn validate(&self) -> Result<(), String> {
-        if self.url.is_empty() {
-            return Err("URL cannot be empty".to_string());
+        validate_item_url(&self.url)?;
+        if self.tags.len() > MAX_SAVE_TAGS {
+            return Err(format!("At most {} tags may be given", MAX_SAVE_TAGS));
+        }
+        if !self.tags.iter().all(|tag| is_tag_name(tag)) {
+            return Err("tags must be between 1 and 100 characters".to_string());
+        }
+        if let Some(note) = &self.note
+            && note.chars().count() > MAX_NOTE_CHARS
+        {
+            return Err("note too long".to_string());
+        }
+        if let Some(source) = &self.source
+            && !is_save_source(source)
+        {
+            return Err("source must be lowercase letters, digits, - or _".to_string());
+        }
+        Ok(())
+    }
+
+    /// Names of the tags the new item gets: those asked for and the user's
+    /// defaults for the save's source, trimmed, without repeats, in order
+    pub fn tags_with_defaults(&self, prefs: &UserPreferences) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.tags
+            .iter()
+            .chain(prefs.default_tags_for(self.source.as_deref()))
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| seen.insert(tag.clone()))
+            .collect()
+    }
+
+    /// The note to store, treating blank text as no note
+    pub fn normalized_note(&self) -> Option<&str> {
+        self.note
+            .as_deref()
+            .map(str::trim)
+            .filter(|note| !note.is_empty())
+    }
+}
+
+impl PreviewItemRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_item_url(&self.url)
+    }
+}
+
+/// URLs that may be saved, or previewed ahead of saving
+fn validate_item_url(url: &str) -> Result<(), String> {
+    if url.is_empty() {
+        return Err("URL cannot be empty".to_string());
+    }
+    if url.len() > 2048 {
+        return Err("URL too long".to_string());
+    }
+    UrlPolicy::global()
+        .parse(url)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+impl BatchGetContentRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.item_ids.is_empty() {
+            return Err("item_ids cannot be empty".to_string());
         }
-        if self.url.len() > 2048 {
-            return Err("URL too long".to_string());
+        if self.item_ids.len() > MAX_BATCH_CONTENT_ITEMS {
+            return Err(format!(
+                "At most {} item_ids may be requested at once",
+                MAX_BATCH_CONTENT_ITEMS
+            ));
         }
         Ok(())
     }
@@ -48,19 +1052,434 @@ impl CreateItemRequest {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::repositories::ItemSortField;
+
+    #[test]
+    fn test_set_refresh_policy_request_bounds() {
+        let request = |interval_secs| SetRefreshPolicyRequest {
+            interval_secs,
+            watch: false,
+        };
+        assert!(request(None).validate().is_ok());
+        assert!(request(Some(MIN_REFRESH_INTERVAL_SECS)).validate().is_ok());
+        assert!(request(Some(60)).validate().is_err());
+        assert!(
+            request(Some(MAX_REFRESH_INTERVAL_SECS + 1))
+                .validate()
+                .is_err()
+        );
+
+        let watch = |interval_secs| SetRefreshPolicyRequest {
+            interval_secs,
+            watch: true,
+        };
+        assert!(watch(Some(MIN_REFRESH_INTERVAL_SECS)).validate().is_ok());
+        assert!(watch(None).validate().is_err());
+    }
+
+    #[test]
+    fn test_create_item_request_url_policy() {
+        let request = |url: &str| CreateItemRequest {
+            url: url.to_string(),
+            ..Default::default()
+        };
+        assert!(request("https://example.com/post").validate().is_ok());
+        assert!(request("").validate().is_err());
+        assert!(request("file:///etc/passwd").validate().is_err());
+        assert!(request("https://user:pw@example.com/").validate().is_err());
+        assert!(request("http://example.com:6379/").validate().is_err());
+    }
+
+    #[test]
+    fn test_update_item_request_title() {
+        let request = |title: Option<&str>| UpdateItemRequest {
+            title: title.map(str::to_string),
+            status: Some(ItemStatus::Archived),
+        };
+        assert!(request(None).validate().is_ok());
+        assert!(request(Some("A better title")).validate().is_ok());
+        assert!(request(Some("  ")).validate().is_err());
+        assert!(
+            request(Some(&"x".repeat(MAX_TITLE_LENGTH + 1)))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_set_progress_request_bounds() {
+        let request = |progress| SetProgressRequest { progress };
+        assert!(request(0.0).validate().is_ok());
+        assert!(request(0.5).validate().is_ok());
+        assert!(request(1.0).validate().is_ok());
+        assert!(request(-0.1).validate().is_err());
+        assert!(request(1.5).validate().is_err());
+        assert!(request(f32::NAN).validate().is_err());
+    }
+
+    #[test]
+    fn test_content_fields_query() {
+        let query = |fields: Option<&str>| ContentFieldsQuery {
+            fields: fields.map(str::to_string),
+        };
+        assert_eq!(query(None).content_fields(), Ok(ContentFields::ALL));
+        assert_eq!(
+            query(Some("metadata, text")).content_fields(),
+            Ok(ContentFields {
+                metadata: true,
+                text: true,
+                html: false,
+            })
+        );
+        assert!(query(Some("html")).validate().is_ok());
+        assert_eq!(query(Some("raw")).validate().unwrap_err().field, "fields");
+        assert!(query(Some("")).validate().is_err());
+    }
+
+    #[test]
+    fn test_get_item_query_include() {
+        let query = |include: Option<&str>| GetItemQuery {
+            include: include.map(str::to_string),
+            fields: None,
+        };
+        assert!(!query(None).includes_content());
+        assert!(query(None).validate().is_ok());
+        assert!(query(Some("content")).includes_content());
+        assert!(query(Some(" content ")).validate().is_ok());
+        assert_eq!(query(Some("tags")).validate().unwrap_err().field, "include");
+        assert!(query(Some("content,")).validate().is_err());
+
+        let with_fields = |fields: &str| GetItemQuery {
+            include: Some("content".to_string()),
+            fields: Some(fields.to_string()),
+        };
+        assert_eq!(
+            with_fields("text").content_fields(),
+            Ok(ContentFields {
+                metadata: false,
+                text: true,
+                html: false,
+            })
+        );
+        assert_eq!(with_fields("raw").validate().unwrap_err().field, "fields");
+    }
+
+    #[test]
+    fn test_validate_lang() {
+        assert!(validate_lang("en").is_ok());
+        assert!(validate_lang("EPO").is_ok());
+        assert!(validate_lang("e").is_err());
+        assert!(validate_lang("english").is_err());
+        assert!(validate_lang("e1").is_err());
+    }
+
+    #[test]
+    fn test_list_items_query_filters_and_paging() {
+        let query: ListItemsQuery = serde_json::from_value(serde_json::json!({
+            "status": "archived",
+            "tag": "rust",
+            "q": "async",
+            "reading_time": "<5",
+            "limit": 10,
+            "offset": 20
+        }))
+        .unwrap();
+        assert_eq!(query.status, Some(ItemStatus::Archived));
+        assert!(query.validate().is_ok());
+
+        let invalid = |query: ListItemsQuery| query.validate().unwrap_err().field;
+        let limit = |limit| ListItemsQuery {
+            limit: Some(limit),
+            ..Default::default()
+        };
+        assert_eq!(invalid(limit(0)), "limit");
+        assert_eq!(invalid(limit(MAX_ITEM_LIST_LIMIT + 1)), "limit");
+        assert!(limit(MAX_ITEM_LIST_LIMIT).validate().is_ok());
+        assert_eq!(
+            invalid(ListItemsQuery {
+                offset: Some(-1),
+                ..Default::default()
+            }),
+            "offset"
+        );
+        assert_eq!(
+            invalid(ListItemsQuery {
+                q: Some("  ".to_string()),
+                ..Default::default()
+            }),
+            "q"
+        );
+        assert_eq!(
+            invalid(ListItemsQuery {
+                tag: Some(String::new()),
+                ..Default::default()
+            }),
+            "tag"
+        );
+        assert_eq!(
+            invalid(ListItemsQuery {
+                reading_time: Some("5".to_string()),
+                ..Default::default()
+            }),
+            "reading_time"
+        );
+        assert_eq!(
+            invalid(ListItemsQuery {
+                max_reading_time: Some(0),
+                ..Default::default()
+            }),
+            "max_reading_time"
+        );
+        assert_eq!(
+            invalid(ListItemsQuery {
+                topic: Some("Tech News".to_string()),
+                ..Default::default()
+            }),
+            "topic"
+        );
+        assert_eq!(
+            invalid(ListItemsQuery {
+                site: Some("https://example.com/".to_string()),
+                ..Default::default()
+            }),
+            "site"
+        );
+
+        let dated: ListItemsQuery = serde_json::from_value(serde_json::json!({
+            "site": "www.example.com",
+            "created_after": "2025-09-01T00:00:00Z",
+            "created_before": "2025-10-01T00:00:00+02:00"
+        }))
+        .unwrap();
+        assert!(dated.validate().is_ok());
+        assert_eq!(
+            invalid(ListItemsQuery {
+                created_before: dated.created_after,
+                ..dated
+            }),
+            "created_before"
+        );
+    }
+
+    #[test]
+    fn test_triage_request_validation() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let request = |decisions: serde_json::Value| {
+            serde_json::from_value::<TriageRequest>(serde_json::json!({ "decisions": decisions }))
+                .unwrap()
+                .validate()
+        };
+
+        assert_eq!(
+            request(serde_json::json!([
+                {"id": a, "action": "tag: rust "},
+                {"id": b, "action": "keep"},
+            ])),
+            Ok(vec![
+                (a, TriageAction::Tag("rust".to_string())),
+                (b, TriageAction::Keep)
+            ])
+        );
+        assert!(request(serde_json::json!([])).is_err());
+        assert!(request(serde_json::json!([{"id": a, "action": "snooze"}])).is_err());
+        assert!(request(serde_json::json!([{"id": a, "action": "tag:"}])).is_err());
+        assert!(
+            request(serde_json::json!([
+                {"id": a, "action": "archive"},
+                {"id": a, "action": "delete"},
+            ]))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_bulk_items_request_validation() {
+        let request: BulkItemsRequest = serde_json::from_value(serde_json::json!({
+            "operations": [
+                {"op": "tag", "item_ids": [Uuid::new_v4()], "tag": "rust"},
+                {"op": "favorite", "item_ids": [Uuid::new_v4()]},
+                {"op": "archive", "item_ids": [Uuid::new_v4(), Uuid::new_v4()]},
+            ]
+        }))
+        .unwrap();
+        assert!(request.validate().is_ok());
+        assert_eq!(request.operations[2].item_ids().len(), 2);
+
+        let invalid = |operations: serde_json::Value| {
+            serde_json::from_value::<BulkItemsRequest>(serde_json::json!({
+                "operations": operations
+            }))
+            .unwrap()
+            .validate()
+            .is_err()
+        };
+        assert!(invalid(serde_json::json!([])));
+        assert!(invalid(
+            serde_json::json!([{"op": "delete", "item_ids": []}])
+        ));
+        assert!(invalid(serde_json::json!([
+            {"op": "tag", "item_ids": [Uuid::new_v4()], "tag": " "}
+        ])));
+        let too_many: Vec<Uuid> = (0..=MAX_BULK_ITEMS).map(|_| Uuid::new_v4()).collect();
+        assert!(invalid(
+            serde_json::json!([{"op": "archive", "item_ids": too_many}])
+        ));
+
+        assert!(
+            serde_json::from_value::<BulkItemsRequest>(serde_json::json!({
+                "operations": [{"op": "explode", "item_ids": [Uuid::new_v4()]}]
+            }))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_list_items_query_cursor() {
+        let cursor = KeysetCursor::new(Utc::now(), Uuid::new_v4());
+        let query = ListItemsQuery {
+            cursor: Some(cursor.encode()),
+            ..Default::default()
+        };
+        assert!(query.validate().is_ok());
+        assert_eq!(query.after().map(|after| after.id), Some(cursor.id));
+
+        let invalid = |query: ListItemsQuery| query.validate().unwrap_err().field;
+        assert_eq!(
+            invalid(ListItemsQuery {
+                cursor: Some("20".to_string()),
+                ..Default::default()
+            }),
+            "cursor"
+        );
+        assert_eq!(
+            invalid(ListItemsQuery {
+                cursor: Some(cursor.encode()),
+                offset: Some(20),
+                ..Default::default()
+            }),
+            "cursor"
+        );
+        // A created_at cursor doesn't say where a list by title is
+        assert_eq!(
+            invalid(ListItemsQuery {
+                cursor: Some(cursor.encode()),
+                sort: Some("title".to_string()),
+                ..Default::default()
+            }),
+            "cursor"
+        );
+        assert!(
+            ListItemsQuery {
+                cursor: Some(cursor.encode()),
+                sort: Some("updated_at".to_string()),
+                ..Default::default()
+            }
+            .validate()
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_list_items_query_sort() {
+        let sort = |sort: &str| ListItemsQuery {
+            sort: Some(sort.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ListItemsQuery::default().sort(), ItemSort::default());
+        assert_eq!(
+            sort("-reading_time").sort(),
+            ItemSort {
+                field: ItemSortField::ReadingTime,
+                descending: true
+            }
+        );
+        assert_eq!(
+            sort("title").sort(),
+            ItemSort {
+                field: ItemSortField::Title,
+                descending: false
+            }
+        );
+        for invalid in ["", "url", "title:asc", "--title"] {
+            assert_eq!(sort(invalid).validate().unwrap_err().field, "sort");
+        }
+    }
+
+    #[test]
+    fn test_list_items_query_extraction_filter() {
+        let query: ListItemsQuery =
+            serde_json::from_value(serde_json::json!({"extraction": "failed"})).unwrap();
+        assert_eq!(query.extraction, Some(ExtractionFilter::Failed));
+        assert!(
+            serde_json::from_value::<ListItemsQuery>(serde_json::json!({"extraction": "ok"}))
+                .is_err()
+        );
+    }
 
     #[test]
     fn test_create_item_request_valid() {
         let request = CreateItemRequest {
             url: "https://example.com".to_string(),
+            ..Default::default()
         };
         assert!(request.validate().is_ok());
     }
 
+    #[test]
+    fn test_create_item_request_settings() {
+        let request = |body: serde_json::Value| {
+            serde_json::from_value::<CreateItemRequest>(body)
+                .unwrap()
+                .validate()
+        };
+        assert!(
+            request(serde_json::json!({
+                "url": "https://example.com",
+                "tags": ["rust"],
+                "favorite": true,
+                "note": "read later",
+                "source": "extension"
+            }))
+            .is_ok()
+        );
+        assert!(request(serde_json::json!({"url": "https://example.com", "tags": [" "]})).is_err());
+        assert!(
+            request(serde_json::json!({"url": "https://example.com", "source": "Chrome"})).is_err()
+        );
+        assert!(
+            request(serde_json::json!({
+                "url": "https://example.com",
+                "tags": vec!["tag"; MAX_SAVE_TAGS + 1]
+            }))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_create_item_request_tags_with_defaults() {
+        let prefs = UserPreferences {
+            default_tags: Some(vec!["inbox".to_string(), "rust".to_string()]),
+            default_tags_sources: Some(vec!["extension".to_string()]),
+            ..Default::default()
+        };
+        let request = |source: Option<&str>| CreateItemRequest {
+            url: "https://example.com".to_string(),
+            tags: vec![" rust ".to_string(), "async".to_string()],
+            source: source.map(str::to_string),
+            ..Default::default()
+        };
+        assert_eq!(
+            request(Some("extension")).tags_with_defaults(&prefs),
+            ["rust", "async", "inbox"]
+        );
+        assert_eq!(request(None).tags_with_defaults(&prefs), ["rust", "async"]);
+    }
+
     #[test]
     fn test_create_item_request_empty_url() {
         let request = CreateItemRequest {
             url: "".to_string(),
+            ..Default::default()
         };
         assert!(request.validate().is_err());
     }
@@ -69,6 +1488,31 @@ mod tests {
     fn test_create_item_request_url_too_long() {
         let request = CreateItemRequest {
             url: "a".repeat(2049),
+            ..Default::default()
+        };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_batch_get_content_request_valid() {
+        let request = BatchGetContentRequest {
+            item_ids: vec![Uuid::new_v4(), Uuid::new_v4()],
+        };
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn test_batch_get_content_request_empty() {
+        let request = BatchGetContentRequest { item_ids: vec![] };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_batch_get_content_request_too_many() {
+        let request = BatchGetContentRequest {
+            item_ids: (0..=MAX_BATCH_CONTENT_ITEMS)
+                .map(|_| Uuid::new_v4())
+                .collect(),
         };
         assert!(request.validate().is_err());
     }
@@ -0,0 +1,88 @@
+use axum::http::{HeaderMap, header::IF_MATCH};
+use chrono::{DateTime, Utc};
+
+/// Build a weak ETag for a collection from its size and most recent update.
+///
+/// Any insert, update, or delete on the collection changes at least one of
+/// the two inputs, so clients can safely reuse their cached copy on a match.
+pub fn collection_etag(count: i64, max_updated_at: Option<DateTime<Utc>>) -> String {
+    let stamp = max_updated_at.map_or(0, |ts| ts.timestamp_micros());
+    format!("W/\"{}-{}\"", count, stamp)
+}
+
+/// Check an `If-None-Match` header value against an ETag using weak comparison.
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let etag = strip_weak(etag);
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || strip_weak(candidate) == etag)
+}
+
+/// Strong ETag for one item at `version`
+pub fn item_etag(version: i64) -> String {
+    format!("\"v{}\"", version)
+}
+
+/// The item version an `If-Match` header holds an edit to, or None for `*`,
+/// which any version matches. Versions start at 1, so a tag that isn't one
+/// of ours, or a weak one, comes back as 0 and matches none.
+pub fn if_match_version(if_match: &str) -> Option<i64> {
+    let if_match = if_match.trim();
+    if if_match == "*" {
+        return None;
+    }
+    let version = if_match
+        .split(',')
+        .map(str::trim)
+        .find_map(|tag| tag.strip_prefix("\"v")?.strip_suffix('"')?.parse().ok())
+        .unwrap_or(0);
+    Some(version)
+}
+
+/// The item version a request's `If-Match` header makes its edit
+/// conditional on, if any
+pub fn expected_version(headers: &HeaderMap) -> Option<i64> {
+    let if_match = headers.get(IF_MATCH)?;
+    if_match.to_str().map_or(Some(0), if_match_version)
+}
+
+fn strip_weak(tag: &str) -> &str {
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_collection_etag_changes_with_count() {
+        let ts = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        assert_ne!(collection_etag(1, Some(ts)), collection_etag(2, Some(ts)));
+    }
+
+    #[test]
+    fn test_collection_etag_empty_collection() {
+        assert_eq!(collection_etag(0, None), "W/\"0-0\"");
+    }
+
+    #[test]
+    fn test_etag_matches_weak_and_strong() {
+        let etag = collection_etag(3, None);
+        assert!(etag_matches(&etag, &etag));
+        assert!(etag_matches("\"3-0\"", &etag));
+        assert!(etag_matches("\"other\", W/\"3-0\"", &etag));
+        assert!(etag_matches("*", &etag));
+        assert!(!etag_matches("W/\"4-0\"", &etag));
+    }
+
+    #[test]
+    fn test_if_match_version() {
+        assert_eq!(if_match_version(&item_etag(3)), Some(3));
+        assert_eq!(if_match_version("\"other\", \"v7\""), Some(7));
+        assert_eq!(if_match_version(" * "), None);
+        assert_eq!(if_match_version("W/\"v3\""), Some(0));
+        assert_eq!(if_match_version("\"3\""), Some(0));
+    }
+}
@@ -1,23 +1,66 @@
 use axum::{
     Json,
     extract::{Path, State},
-    http::StatusCode,
+    http::{
+        HeaderMap, StatusCode,
+        header::{ETAG, IF_NONE_MATCH},
+    },
     response::{IntoResponse, Response},
 };
+use chrono::Utc;
+use sqlx::PgConnection;
+use std::collections::HashSet;
+use url::Url;
 use uuid::Uuid;
 
 use crate::{
     app_state::AppState,
     auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
-    items::dtos::{CreateItemRequest, ItemResponse, UpdateItemRequest},
+    entities::ItemStatus,
+    fetcher::{Deadline, FetchError},
+    items::{
+        dtos::{
+            BatchGetContentRequest, BatchGetContentResponse, BulkItemResult, BulkItemStatus,
+            BulkItemsRequest, BulkItemsResponse, BulkOperation, ContentFieldsQuery,
+            CreateItemRequest, DEFAULT_ITEM_LIST_LIMIT, ExtractionFilter, FetchAttemptResponse,
+            GetItemQuery, ItemContentResponse, ItemEventListResponse, ItemEventResponse,
+            ItemLinksResponse, ItemPreviewResponse, ItemResponse, ListItemsQuery, LookupItemQuery,
+            MAX_BATCH_CONTENT_BYTES, PreviewItemRequest, RandomItemQuery, RefreshPolicyResponse,
+            RetryExtractionResponse, SetProgressRequest, SetRefreshPolicyRequest,
+            SnoozeItemRequest, SnoozeItemResponse, StateTransitionListResponse,
+            StateTransitionResponse, TriageAction, TriageRequest, TriageResponse,
+            UpdateItemRequest,
+        },
+        etag::{collection_etag, etag_matches, expected_version, item_etag},
+        preview::{PREVIEW_BUDGET, PreviewError, preview},
+    },
+    jobs::{FETCH_PAGE_JOB_KIND, FetchPagePayload, JobRepository, Outbox, stage_fetch_jobs},
+    middleware::transaction::RequestTransaction,
+    pagination::Page,
+    query::{FieldError, ValidatedQuery},
+    repositories::{
+        ContentFields, ContentRepository, DomainRulesRepository, FetchAttemptRepository,
+        HighlightRepository, ItemEdit, ItemEventRepository, ItemFilter, ItemRepository,
+        ItemStateRepository, LinkRepository, ReadingTime, StatsRepository, TagRepository,
+        UndoRepository, UndoStep, domain_prefs::normalize_domain,
+    },
+    scheduling::{TimeZone, snooze_until},
+    undo::with_undo_token,
+    urlnorm::canonicalize,
 };
 
 #[utoipa::path(
     get,
     path = "/v1/items",
     tag = "items",
+    params(
+        ListItemsQuery,
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous list response")
+    ),
     responses(
-        (status = 200, description = "List items successfully", body = [ItemResponse]),
+        (status = 200, description = "List items successfully", body = Page<ItemResponse>),
+        (status = 304, description = "Item list unchanged since the given ETag"),
+        (status = 400, description = "Invalid query parameter", body = FieldError),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -25,24 +68,160 @@ use crate::{
         ("bearer_auth" = [])
     )
 )]
-pub async fn list_items(_auth_user: AuthenticatedUser, State(_state): State<AppState>) -> Response {
-    (
-        StatusCode::NOT_IMPLEMENTED,
-        Json(ErrorResponse {
-            error: "Not implemented".to_string(),
-        }),
+pub async fn list_items(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ValidatedQuery(query): ValidatedQuery<ListItemsQuery>,
+    headers: HeaderMap,
+) -> Response {
+    // Validated above
+    let reading_time = query
+        .reading_time
+        .as_deref()
+        .and_then(|reading_time| reading_time.parse().ok());
+    list(auth_user, state, query, headers, reading_time).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/items/quick-reads",
+    tag = "items",
+    params(
+        ListItemsQuery,
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous list response")
+    ),
+    responses(
+        (status = 200, description = "Items that take under five minutes to read; `reading_time` is ignored", body = Page<ItemResponse>),
+        (status = 304, description = "Item list unchanged since the given ETag"),
+        (status = 400, description = "Invalid query parameter", body = FieldError),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
     )
-        .into_response()
+)]
+pub async fn list_quick_reads(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ValidatedQuery(query): ValidatedQuery<ListItemsQuery>,
+    headers: HeaderMap,
+) -> Response {
+    list(auth_user, state, query, headers, Some(ReadingTime::QUICK)).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/items/long-reads",
+    tag = "items",
+    params(
+        ListItemsQuery,
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous list response")
+    ),
+    responses(
+        (status = 200, description = "Items that take over twenty minutes to read; `reading_time` is ignored", body = Page<ItemResponse>),
+        (status = 304, description = "Item list unchanged since the given ETag"),
+        (status = 400, description = "Invalid query parameter", body = FieldError),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_long_reads(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ValidatedQuery(query): ValidatedQuery<ListItemsQuery>,
+    headers: HeaderMap,
+) -> Response {
+    list(auth_user, state, query, headers, Some(ReadingTime::LONG)).await
+}
+
+/// One page of the user's items matching `query`, with `reading_time` in
+/// place of the query's own
+async fn list(
+    auth_user: AuthenticatedUser,
+    state: AppState,
+    query: ListItemsQuery,
+    headers: HeaderMap,
+    reading_time: Option<ReadingTime>,
+) -> Response {
+    let filter = ItemFilter {
+        lang: query.lang.as_ref().map(|lang| lang.to_ascii_lowercase()),
+        failed_only: query.extraction == Some(ExtractionFilter::Failed),
+        status: query.status,
+        tag: query.tag.as_deref().map(|tag| tag.trim().to_string()),
+        q: query.q.as_deref().map(|q| q.trim().to_string()),
+        topic: query.topic.clone(),
+        reading_time,
+        max_reading_time: query.max_reading_time,
+        site: query.site.as_deref().map(normalize_domain),
+        created_before: query.created_before,
+        created_after: query.created_after,
+        hide_snoozed: !query.include_snoozed,
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_ITEM_LIST_LIMIT);
+    let offset = query.offset.unwrap_or(0);
+    let repo = &state.item_repo;
+
+    // Cheap fingerprint first so unchanged lists never load item rows
+    let (count, max_updated_at) = match repo.fingerprint(auth_user.user_id, &filter).await {
+        Ok(fingerprint) => fingerprint,
+        Err(_) => return database_error(),
+    };
+
+    let etag = collection_etag(count, max_updated_at);
+    if let Some(if_none_match) = headers.get(IF_NONE_MATCH).and_then(|v| v.to_str().ok())
+        && etag_matches(if_none_match, &etag)
+    {
+        return (StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response();
+    }
+
+    // One extra row tells whether there's a page after this one
+    let sort = query.sort();
+    let mut items = match repo
+        .list_for_user(
+            auth_user.user_id,
+            &filter,
+            sort,
+            query.after(),
+            limit + 1,
+            offset,
+        )
+        .await
+    {
+        Ok(items) => items,
+        Err(_) => return database_error(),
+    };
+    let has_more = items.len() as i64 > limit;
+    items.truncate(limit as usize);
+
+    let next_cursor = items
+        .last()
+        .filter(|_| has_more)
+        .map(|last| sort.cursor(last).encode());
+    let response = Page::new(
+        items.into_iter().map(ItemResponse::from).collect(),
+        next_cursor,
+        Some(count),
+    );
+
+    (StatusCode::OK, [(ETAG, etag)], Json(response)).into_response()
 }
 
 #[utoipa::path(
     post,
     path = "/v1/items",
     tag = "items",
+    request_body = CreateItemRequest,
     responses(
-        (status = 201, description = "Item created successfully", body = ItemResponse),
-        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 201, description = "Item saved, with its tags, star and note; fetching its page has been queued", body = ItemResponse),
+        (status = 200, description = "The URL was already saved; the save's tags, star and note were added to that item, which `duplicate_of` names", body = ItemResponse),
+        (status = 400, description = "URL not allowed, or invalid tags, note or source", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "The URL's domain is blocked", body = ErrorResponse),
+        (status = 429, description = "Too many items saved recently", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     security(
@@ -50,17 +229,225 @@ pub async fn list_items(_auth_user: AuthenticatedUser, State(_state): State<AppS
     )
 )]
 pub async fn create_item(
-    _auth_user: AuthenticatedUser,
-    State(_state): State<AppState>,
-    Json(_payload): Json<CreateItemRequest>,
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    transaction: RequestTransaction,
+    Json(payload): Json<CreateItemRequest>,
 ) -> Response {
-    (
-        StatusCode::NOT_IMPLEMENTED,
-        Json(ErrorResponse {
-            error: "Not implemented".to_string(),
-        }),
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    // Saved under its canonical form so the same article shared with
+    // different tracking parameters maps to one URL
+    let url = canonicalize(&payload.url).unwrap_or_else(|| payload.url.clone());
+    let host = Url::parse(&url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default();
+    match DomainRulesRepository::new(&state.db_pool)
+        .blocked_reason(&host)
+        .await
+    {
+        Ok(None) => {}
+        Ok(Some(error)) => {
+            return (StatusCode::FORBIDDEN, Json(ErrorResponse { error })).into_response();
+        }
+        Err(_) => return database_error(),
+    }
+
+    let prefs = match state.user_repo.find_profile(auth_user.user_id).await {
+        Ok(profile) => profile.map(|profile| profile.prefs.0).unwrap_or_default(),
+        Err(_) => return database_error(),
+    };
+    let tags = payload.tags_with_defaults(&prefs);
+
+    // The item, what the save sets on it and its jobs commit together, so
+    // the fetch starts as soon as the client hears back
+    let mut conn = transaction.conn().await;
+    let saved = match state
+        .item_repo
+        .find_saved_in(&mut conn, auth_user.user_id, &url)
+        .await
+    {
+        Ok(saved) => saved,
+        Err(_) => return database_error(),
+    };
+    if let Some(item_id) = saved {
+        return merge_save(
+            &state,
+            &mut conn,
+            auth_user.user_id,
+            item_id,
+            &payload,
+            tags,
+        )
+        .await;
+    }
+    let mut item = match state
+        .item_repo
+        .create_in(&mut conn, auth_user.user_id, &url)
+        .await
+    {
+        Ok(item) => item,
+        Err(_) => return database_error(),
+    };
+    let item_id = item.item.id;
+    let version = match apply_save_settings(
+        &state,
+        &mut conn,
+        auth_user.user_id,
+        item_id,
+        &payload,
+        &tags,
     )
-        .into_response()
+    .await
+    {
+        Ok(version) => version,
+        Err(_) => return database_error(),
+    };
+    if stage_fetch_jobs(&mut conn, item_id).await.is_err() {
+        return database_error();
+    }
+
+    item.tags = tags;
+    item.tags.sort();
+    item.item.favorite = payload.favorite;
+    item.item.version = version;
+    (StatusCode::CREATED, Json(ItemResponse::from(item))).into_response()
+}
+
+/// Set what a save asks for on the item it saved to, on `conn`, returning
+/// the version that leaves the item at
+async fn apply_save_settings(
+    state: &AppState,
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    item_id: Uuid,
+    payload: &CreateItemRequest,
+    tags: &[String],
+) -> anyhow::Result<i64> {
+    for tag in tags {
+        TagRepository::attach_many_in(conn, user_id, &[item_id], tag).await?;
+    }
+    if payload.favorite {
+        ItemRepository::set_favorite_many_in(conn, user_id, &[item_id], true).await?;
+    }
+    if let Some(note) = payload.normalized_note() {
+        HighlightRepository::set_note_in(conn, user_id, item_id, Some(note)).await?;
+    }
+    state
+        .item_repo
+        .version_in(conn, user_id, item_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Saved item {} is gone", item_id))
+}
+
+/// Answer a save of a URL the user already saved with the item saved
+/// before, adding the save's tags, star and note to it and fetching it
+/// again when asked to
+async fn merge_save(
+    state: &AppState,
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    item_id: Uuid,
+    payload: &CreateItemRequest,
+    tags: Vec<String>,
+) -> Response {
+    let version = match apply_save_settings(state, conn, user_id, item_id, payload, &tags).await {
+        Ok(version) => version,
+        Err(_) => return database_error(),
+    };
+    if payload.refresh && stage_fetch_jobs(conn, item_id).await.is_err() {
+        return database_error();
+    }
+
+    // Read outside the transaction, so without what the save just changed
+    let mut item = match state.item_repo.find_for_user(user_id, item_id).await {
+        Ok(Some(item)) => item,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "Item not found".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Err(_) => return database_error(),
+    };
+    for tag in tags {
+        if !item.tags.contains(&tag) {
+            item.tags.push(tag);
+        }
+    }
+    item.tags.sort();
+    item.item.favorite |= payload.favorite;
+    // The version the transaction left it at, which clients' next edits
+    // are made against
+    item.item.version = version;
+
+    let mut response = ItemResponse::from(item);
+    response.duplicate_of = Some(item_id);
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/items/preview",
+    tag = "items",
+    request_body = PreviewItemRequest,
+    responses(
+        (status = 200, description = "Title, site, lead image and reading time of the page", body = ItemPreviewResponse),
+        (status = 400, description = "URL not allowed", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Domain blocked", body = ErrorResponse),
+        (status = 502, description = "The page couldn't be fetched", body = ErrorResponse),
+        (status = 504, description = "The page took too long to fetch", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn preview_item(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<PreviewItemRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    // The client is waiting on this, so the whole preview shares one budget
+    let deadline = Deadline::after(PREVIEW_BUDGET);
+    let result = deadline
+        .run(preview(
+            &state.db_pool,
+            auth_user.user_id,
+            &payload.url,
+            &deadline,
+        ))
+        .await
+        .unwrap_or_else(|exceeded| Err(PreviewError::Fetch(exceeded.into())));
+
+    let (status, error) = match result {
+        Ok(preview) => return (StatusCode::OK, Json(preview)).into_response(),
+        Err(PreviewError::Blocked(detail)) => (StatusCode::FORBIDDEN, detail),
+        Err(PreviewError::Fetch(FetchError::Abandoned(_))) => (
+            StatusCode::GATEWAY_TIMEOUT,
+            "The page took too long to preview".to_string(),
+        ),
+        Err(PreviewError::Fetch(e @ FetchError::UrlNotAllowed(_))) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        Err(PreviewError::Fetch(e)) => (
+            StatusCode::BAD_GATEWAY,
+            format!("Failed to fetch page: {}", e),
+        ),
+        Err(PreviewError::Database(_)) => return database_error(),
+    };
+    (status, Json(ErrorResponse { error })).into_response()
 }
 
 #[utoipa::path(
@@ -68,10 +455,12 @@ pub async fn create_item(
     path = "/v1/items/{id}",
     tag = "items",
     params(
-        ("id" = Uuid, Path, description = "Item ID")
+        ("id" = Uuid, Path, description = "Item ID"),
+        GetItemQuery
     ),
     responses(
         (status = 200, description = "Item retrieved successfully", body = ItemResponse),
+        (status = 400, description = "Invalid query parameter", body = FieldError),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 404, description = "Item not found", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
@@ -81,17 +470,121 @@ pub async fn create_item(
     )
 )]
 pub async fn get_item(
-    _auth_user: AuthenticatedUser,
-    State(_state): State<AppState>,
-    Path(_id): Path<Uuid>,
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    ValidatedQuery(query): ValidatedQuery<GetItemQuery>,
 ) -> Response {
-    (
-        StatusCode::NOT_IMPLEMENTED,
-        Json(ErrorResponse {
-            error: "Not implemented".to_string(),
-        }),
+    let item = match state.item_repo.find_for_user(auth_user.user_id, id).await {
+        Ok(Some(item)) => item,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "Item not found".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Err(_) => return database_error(),
+    };
+    let mut response = ItemResponse::from(item);
+
+    if query.includes_content() {
+        let rows = match ContentRepository::new(&state.db_pool)
+            .get_clean_contents_for_user(
+                auth_user.user_id,
+                &[id],
+                query.content_fields().unwrap_or(ContentFields::ALL),
+            )
+            .await
+        {
+            Ok(rows) => rows,
+            Err(_) => return database_error(),
+        };
+        // A single item isn't held to the batch size limit
+        response.content = Some(
+            rows.first()
+                .map(|row| ItemContentResponse::new(row, usize::MAX)),
+        );
+    }
+
+    let etag = item_etag(response.version);
+    (StatusCode::OK, [(ETAG, etag)], Json(response)).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/items/random",
+    tag = "items",
+    params(RandomItemQuery),
+    responses(
+        (status = 200, description = "A randomly chosen item the user hasn't archived or read yet", body = ItemResponse),
+        (status = 400, description = "Invalid query parameter", body = FieldError),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No unread items", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
     )
-        .into_response()
+)]
+pub async fn random_item(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ValidatedQuery(query): ValidatedQuery<RandomItemQuery>,
+) -> Response {
+    match state
+        .item_repo
+        .random_unread(auth_user.user_id, query.status, query.include_snoozed)
+        .await
+    {
+        Ok(Some(item)) => (StatusCode::OK, Json(ItemResponse::from(item))).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No unread items".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/items/lookup",
+    tag = "items",
+    params(LookupItemQuery),
+    responses(
+        (status = 200, description = "The item already saved from the URL or one equivalent to it", body = ItemResponse),
+        (status = 400, description = "Invalid query parameter", body = FieldError),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "The URL hasn't been saved", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn lookup_item(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ValidatedQuery(query): ValidatedQuery<LookupItemQuery>,
+) -> Response {
+    // Looked up under the same canonical form a save would store
+    let url = canonicalize(&query.url).unwrap_or(query.url);
+    match state.item_repo.find_by_url(auth_user.user_id, &url).await {
+        Ok(Some(item)) => (StatusCode::OK, Json(ItemResponse::from(item))).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "URL not saved".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
 }
 
 #[utoipa::path(
@@ -99,13 +592,16 @@ pub async fn get_item(
     path = "/v1/items/{id}",
     tag = "items",
     params(
-        ("id" = Uuid, Path, description = "Item ID")
+        ("id" = Uuid, Path, description = "Item ID"),
+        ("If-Match" = Option<String>, Header, description = "ETag of the version the change was made against")
     ),
+    request_body = UpdateItemRequest,
     responses(
-        (status = 200, description = "Item updated successfully", body = ItemResponse),
+        (status = 200, description = "Item updated successfully; archiving it can be undone", body = ItemResponse, headers(("etag" = String, description = "Version of the updated item"), ("x-undo-token" = Uuid, description = "Undoes the change through POST /v1/undo/{token} within 30 seconds"))),
         (status = 400, description = "Bad request", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 409, description = "The item can't be moved to the requested status, or `If-Match` names an older version; the body is then the current item", body = ItemResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     security(
@@ -113,15 +609,863 @@ pub async fn get_item(
     )
 )]
 pub async fn update_item(
-    _auth_user: AuthenticatedUser,
-    State(_state): State<AppState>,
-    Path(_id): Path<Uuid>,
-    Json(_payload): Json<UpdateItemRequest>,
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateItemRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    let repo = &state.item_repo;
+    let title = payload
+        .title
+        .as_deref()
+        .map(|title| title.trim().to_string());
+    // Archiving can be undone, back to the status the item had
+    let undo_status = if payload.status == Some(ItemStatus::Archived) {
+        match repo.find_for_user(auth_user.user_id, id).await {
+            Ok(item) => item
+                .map(|item| item.item.status)
+                .filter(|status| *status != ItemStatus::Archived),
+            Err(_) => return database_error(),
+        }
+    } else {
+        None
+    };
+    match repo
+        .update(
+            auth_user.user_id,
+            id,
+            title,
+            payload.status,
+            expected_version(&headers),
+        )
+        .await
+    {
+        Ok(ItemEdit::Applied { .. }) => {}
+        Ok(ItemEdit::StatusNotAllowed) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ErrorResponse {
+                    error: "Item can't be moved to that status".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Ok(ItemEdit::Stale) => return edit_conflict(&state, auth_user.user_id, id).await,
+        Ok(ItemEdit::NotFound) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "Item not found".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Err(_) => return database_error(),
+    }
+
+    let item = match repo.find_for_user(auth_user.user_id, id).await {
+        Ok(Some(item)) => item,
+        // Deleted in the meantime
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "Item not found".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Err(_) => return database_error(),
+    };
+    let response = ItemResponse::from(item);
+    let etag = item_etag(response.version);
+    let response = (StatusCode::OK, [(ETAG, etag)], Json(response)).into_response();
+
+    let Some(status) = undo_status else {
+        return response;
+    };
+    let step = UndoStep::SetStatus {
+        items: vec![(id, status)],
+    };
+    match UndoRepository::new(&state.db_pool)
+        .record(auth_user.user_id, vec![step])
+        .await
+    {
+        Ok(action) => with_undo_token(response, &action),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/items/{id}",
+    tag = "items",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 204, description = "Item deleted along with its content, tags and pending jobs", headers(("x-undo-token" = Uuid, description = "Undoes the change through POST /v1/undo/{token} within 30 seconds"))),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_item(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    transaction: RequestTransaction,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let mut conn = transaction.conn().await;
+    let snapshot = match UndoRepository::snapshot_in(&mut conn, auth_user.user_id, &[id]).await {
+        Ok(snapshot) => snapshot,
+        Err(_) => return database_error(),
+    };
+    match state
+        .item_repo
+        .delete_in(&mut conn, auth_user.user_id, id)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "Item not found".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Err(_) => return database_error(),
+    }
+
+    // Jobs only reference the item from their payload, so they don't cascade
+    if JobRepository::cancel_for_item(&mut conn, id).await.is_err()
+        || Outbox::discard_for_item(&mut conn, id).await.is_err()
+    {
+        return database_error();
+    }
+
+    match UndoRepository::record_in(&mut conn, auth_user.user_id, vec![snapshot]).await {
+        Ok(action) => with_undo_token(StatusCode::NO_CONTENT.into_response(), &action),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/items/bulk",
+    tag = "items",
+    request_body = BulkItemsRequest,
+    responses(
+        (status = 200, description = "Operations applied; each item's outcome is in `results`", body = BulkItemsResponse, headers(("x-undo-token" = Uuid, description = "Undoes the change through POST /v1/undo/{token} within 30 seconds"))),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error; nothing was changed", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn bulk_items(
+    auth_user: AuthenticatedUser,
+    transaction: RequestTransaction,
+    Json(payload): Json<BulkItemsRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    let user_id = auth_user.user_id;
+    let mut conn = transaction.conn().await;
+    let mut results = Vec::new();
+    let mut undo_steps = Vec::new();
+
+    for (index, operation) in payload.operations.iter().enumerate() {
+        let item_ids = operation.item_ids();
+        // How to reverse the operation, read before it's applied
+        let undo_step = match operation {
+            BulkOperation::Archive { .. } => {
+                UndoRepository::statuses_in(&mut conn, user_id, item_ids).await
+            }
+            BulkOperation::Delete { .. } => {
+                UndoRepository::snapshot_in(&mut conn, user_id, item_ids).await
+            }
+            BulkOperation::Tag { tag, .. } => {
+                UndoRepository::untag_in(&mut conn, user_id, item_ids, tag.trim()).await
+            }
+            BulkOperation::Favorite { .. } => {
+                UndoRepository::favorites_in(&mut conn, user_id, item_ids).await
+            }
+        };
+        match undo_step {
+            Ok(step) => undo_steps.push(step),
+            Err(_) => return database_error(),
+        }
+
+        let applied = match operation {
+            BulkOperation::Archive { .. } => {
+                ItemRepository::archive_many_in(&mut conn, user_id, item_ids).await
+            }
+            BulkOperation::Delete { .. } => delete_items(&mut conn, user_id, item_ids).await,
+            BulkOperation::Tag { tag, .. } => {
+                TagRepository::attach_many_in(&mut conn, user_id, item_ids, tag.trim()).await
+            }
+            BulkOperation::Favorite { favorite, .. } => {
+                ItemRepository::set_favorite_many_in(
+                    &mut conn,
+                    user_id,
+                    item_ids,
+                    favorite.unwrap_or(true),
+                )
+                .await
+            }
+        };
+        let applied: HashSet<Uuid> = match applied {
+            Ok(applied) => applied.into_iter().collect(),
+            Err(_) => return database_error(),
+        };
+
+        results.extend(item_ids.iter().map(|&item_id| BulkItemResult {
+            operation: index,
+            item_id,
+            status: if applied.contains(&item_id) {
+                BulkItemStatus::Ok
+            } else {
+                BulkItemStatus::NotFound
+            },
+        }));
+    }
+
+    match UndoRepository::record_in(&mut conn, user_id, undo_steps).await {
+        Ok(action) => with_undo_token(
+            (StatusCode::OK, Json(BulkItemsResponse { results })).into_response(),
+            &action,
+        ),
+        Err(_) => database_error(),
+    }
+}
+
+/// Delete the user's items among `item_ids` with their pending jobs,
+/// returning the IDs deleted
+async fn delete_items(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    item_ids: &[Uuid],
+) -> anyhow::Result<Vec<Uuid>> {
+    let deleted = ItemRepository::delete_many_in(conn, user_id, item_ids).await?;
+    // Jobs only reference the item from their payload, so they don't cascade
+    for &item_id in &deleted {
+        JobRepository::cancel_for_item(conn, item_id).await?;
+        Outbox::discard_for_item(conn, item_id).await?;
+    }
+    Ok(deleted)
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/items/triage",
+    tag = "items",
+    request_body = TriageRequest,
+    responses(
+        (status = 200, description = "Every decision applied", body = TriageResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "An item doesn't exist; nothing was changed", body = ErrorResponse),
+        (status = 500, description = "Internal server error; nothing was changed", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn triage_items(
+    auth_user: AuthenticatedUser,
+    transaction: RequestTransaction,
+    Json(payload): Json<TriageRequest>,
+) -> Response {
+    let decisions = match payload.validate() {
+        Ok(decisions) => decisions,
+        Err(error) => {
+            return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+        }
+    };
+
+    let user_id = auth_user.user_id;
+    let mut conn = transaction.conn().await;
+
+    // Lock every item first so the session either applies in full or not at all
+    let item_ids: Vec<Uuid> = decisions.iter().map(|(id, _)| *id).collect();
+    match ItemRepository::lock_many_in(&mut conn, user_id, &item_ids).await {
+        Ok(locked) if locked.len() == item_ids.len() => {}
+        Ok(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "Item not found".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Err(_) => return database_error(),
+    }
+
+    let mut archived = Vec::new();
+    let mut deleted = Vec::new();
+    let mut tagged: Vec<(String, Vec<Uuid>)> = Vec::new();
+    let mut counts = TriageResponse::default();
+    for (item_id, action) in decisions {
+        match action {
+            TriageAction::Archive => archived.push(item_id),
+            TriageAction::Keep => counts.kept += 1,
+            TriageAction::Delete => deleted.push(item_id),
+            TriageAction::Tag(tag) => match tagged.iter().position(|(name, _)| *name == tag) {
+                Some(index) => tagged[index].1.push(item_id),
+                None => tagged.push((tag, vec![item_id])),
+            },
+        }
+    }
+
+    if !archived.is_empty() {
+        match ItemRepository::archive_many_in(&mut conn, user_id, &archived).await {
+            Ok(ids) => counts.archived = ids.len() as i32,
+            Err(_) => return database_error(),
+        }
+    }
+    for (tag, ids) in &tagged {
+        match TagRepository::attach_many_in(&mut conn, user_id, ids, tag).await {
+            Ok(ids) => counts.tagged += ids.len() as i32,
+            Err(_) => return database_error(),
+        }
+    }
+    if !deleted.is_empty() {
+        match delete_items(&mut conn, user_id, &deleted).await {
+            Ok(ids) => counts.deleted = ids.len() as i32,
+            Err(_) => return database_error(),
+        }
+    }
+
+    if StatsRepository::record_triage_session_in(
+        &mut conn,
+        user_id,
+        counts.archived,
+        counts.kept,
+        counts.tagged,
+        counts.deleted,
+    )
+    .await
+    .is_err()
+    {
+        return database_error();
+    }
+
+    (StatusCode::OK, Json(counts)).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/items/content:batchGet",
+    tag = "items",
+    params(ContentFieldsQuery),
+    request_body = BatchGetContentRequest,
+    responses(
+        (status = 200, description = "Contents retrieved successfully", body = BatchGetContentResponse),
+        (status = 400, description = "Bad request; invalid `fields` is reported as a FieldError", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn batch_get_content(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ValidatedQuery(query): ValidatedQuery<ContentFieldsQuery>,
+    Json(payload): Json<BatchGetContentRequest>,
+) -> Response {
+    let fields = query.content_fields().unwrap_or(ContentFields::ALL);
+
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    // Preserve request order while dropping duplicate IDs
+    let mut seen = HashSet::new();
+    let item_ids: Vec<Uuid> = payload
+        .item_ids
+        .into_iter()
+        .filter(|id| seen.insert(*id))
+        .collect();
+
+    let repo = ContentRepository::new(&state.db_pool);
+    let rows = match repo
+        .get_clean_contents_for_user(auth_user.user_id, &item_ids, fields)
+        .await
+    {
+        Ok(rows) => rows,
+        Err(_) => return database_error(),
+    };
+
+    let mut contents = Vec::with_capacity(rows.len());
+    let mut missing = Vec::new();
+    for item_id in item_ids {
+        let Some(row) = rows.iter().find(|row| row.item_id == item_id) else {
+            missing.push(item_id);
+            continue;
+        };
+        contents.push(ItemContentResponse::new(row, MAX_BATCH_CONTENT_BYTES));
+    }
+
+    (
+        StatusCode::OK,
+        Json(BatchGetContentResponse { contents, missing }),
+    )
+        .into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/items/{id}/snooze",
+    tag = "items",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    request_body = SnoozeItemRequest,
+    responses(
+        (status = 200, description = "Item snoozed successfully", body = SnoozeItemResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn snooze_item(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SnoozeItemRequest>,
+) -> Response {
+    let timezone = match state.user_repo.find_profile(auth_user.user_id).await {
+        Ok(profile) => profile.and_then(|profile| profile.prefs.0.timezone),
+        Err(_) => return database_error(),
+    };
+    let tz = TimeZone::named_or_utc(timezone.as_deref());
+    let until = snooze_until(payload.preset, Utc::now(), &tz);
+
+    match state.item_repo.snooze(auth_user.user_id, id, until).await {
+        Ok(true) => (
+            StatusCode::OK,
+            Json(SnoozeItemResponse {
+                item_id: id,
+                snoozed_until: until,
+            }),
+        )
+            .into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Item not found".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/items/{id}/progress",
+    tag = "items",
+    params(
+        ("id" = Uuid, Path, description = "Item ID"),
+        ("If-Match" = Option<String>, Header, description = "ETag of the version the change was made against")
+    ),
+    request_body = SetProgressRequest,
+    responses(
+        (status = 204, description = "Reading progress saved", headers(("etag" = String, description = "Version of the updated item"))),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 409, description = "`If-Match` names an older version; the body is the current item", body = ItemResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_progress(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<SetProgressRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    match state
+        .item_repo
+        .set_progress(
+            auth_user.user_id,
+            id,
+            payload.progress,
+            expected_version(&headers),
+        )
+        .await
+    {
+        Ok(ItemEdit::Applied { version }) => {
+            (StatusCode::NO_CONTENT, [(ETAG, item_etag(version))]).into_response()
+        }
+        Ok(ItemEdit::Stale) => edit_conflict(&state, auth_user.user_id, id).await,
+        Ok(_) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Item not found".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/items/{id}/refresh-policy",
+    tag = "items",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    request_body = SetRefreshPolicyRequest,
+    responses(
+        (status = 200, description = "Refresh policy updated", body = RefreshPolicyResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_refresh_policy(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SetRefreshPolicyRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    let next_refresh_at = payload
+        .interval_secs
+        .map(|interval| Utc::now() + chrono::Duration::seconds(interval));
+
+    match state
+        .item_repo
+        .set_refresh_policy(
+            auth_user.user_id,
+            id,
+            payload.interval_secs.map(|interval| interval as i32),
+            next_refresh_at,
+            payload.watch,
+        )
+        .await
+    {
+        Ok(true) => (
+            StatusCode::OK,
+            Json(RefreshPolicyResponse {
+                item_id: id,
+                interval_secs: payload.interval_secs,
+                watch: payload.watch,
+                next_refresh_at,
+            }),
+        )
+            .into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Item not found".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/items/{id}/extraction:retry",
+    tag = "items",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 202, description = "Static refetch queued, bypassing the fetch cache", body = RetryExtractionResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 409, description = "Item has no extraction failure to retry", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn retry_extraction(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    transaction: RequestTransaction,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let extraction_error = state
+        .item_repo
+        .extraction_error_in(&mut *transaction.conn().await, auth_user.user_id, id)
+        .await;
+
+    match extraction_error {
+        Ok(Some(Some(_))) => {}
+        Ok(Some(None)) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ErrorResponse {
+                    error: "Item has no extraction failure".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "Item not found".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Err(_) => return database_error(),
+    }
+
+    // No headless renderer is available, so this is a plain fetch of the
+    // page as it is now; pages flagged `render_required` may fail again
+    let payload = FetchPagePayload {
+        item_id: id,
+        refetch: true,
+    };
+    let payload = match serde_json::to_value(&payload) {
+        Ok(payload) => payload,
+        Err(_) => return database_error(),
+    };
+
+    // Committed by the transaction middleware once we return 202
+    let job_id = match Outbox::enqueue(
+        &mut *transaction.conn().await,
+        FETCH_PAGE_JOB_KIND,
+        payload,
+        None,
+    )
+    .await
+    {
+        Ok(job_id) => job_id,
+        Err(_) => return database_error(),
+    };
+
+    (
+        StatusCode::ACCEPTED,
+        Json(RetryExtractionResponse {
+            item_id: id,
+            job_id,
+        }),
+    )
+        .into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/items/{id}/transitions",
+    tag = "items",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 200, description = "Processing state history, oldest first", body = StateTransitionListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_transitions(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    match ItemStateRepository::new(&state.db_pool)
+        .history(auth_user.user_id, id)
+        .await
+    {
+        Ok(Some(transitions)) => (
+            StatusCode::OK,
+            Json(StateTransitionListResponse {
+                item_id: id,
+                transitions: transitions
+                    .into_iter()
+                    .map(StateTransitionResponse::from)
+                    .collect(),
+            }),
+        )
+            .into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Item not found".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/items/{id}/events",
+    tag = "items",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 200, description = "Item activity timeline and fetch attempts, oldest first", body = ItemEventListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_events(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let events = match ItemEventRepository::new(&state.db_pool)
+        .list_for_user(auth_user.user_id, id)
+        .await
+    {
+        Ok(events) => events,
+        Err(_) => return database_error(),
+    };
+
+    match events {
+        Some(events) => {
+            let attempts = match FetchAttemptRepository::new(&state.db_pool)
+                .list_for_item(id)
+                .await
+            {
+                Ok(attempts) => attempts,
+                Err(_) => return database_error(),
+            };
+
+            (
+                StatusCode::OK,
+                Json(ItemEventListResponse {
+                    item_id: id,
+                    events: events.into_iter().map(ItemEventResponse::from).collect(),
+                    fetch_attempts: attempts
+                        .into_iter()
+                        .map(FetchAttemptResponse::from)
+                        .collect(),
+                }),
+            )
+                .into_response()
+        }
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Item not found".to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/items/{id}/links",
+    tag = "items",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 200, description = "Saved items the item links to and is linked from, and its other links", body = ItemLinksResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_links(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
 ) -> Response {
+    match LinkRepository::new(&state.db_pool)
+        .links_for_user(auth_user.user_id, id)
+        .await
+    {
+        Ok(Some(links)) => {
+            (StatusCode::OK, Json(ItemLinksResponse::new(id, links))).into_response()
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Item not found".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+/// 409 with the item as it now stands, for an edit made against an older
+/// version of it; 404 when it's gone since
+pub async fn edit_conflict(state: &AppState, user_id: Uuid, item_id: Uuid) -> Response {
+    match state.item_repo.find_for_user(user_id, item_id).await {
+        Ok(Some(item)) => {
+            let response = ItemResponse::from(item);
+            let etag = item_etag(response.version);
+            (StatusCode::CONFLICT, [(ETAG, etag)], Json(response)).into_response()
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Item not found".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+fn database_error() -> Response {
     (
-        StatusCode::NOT_IMPLEMENTED,
+        StatusCode::INTERNAL_SERVER_ERROR,
         Json(ErrorResponse {
-            error: "Not implemented".to_string(),
+            error: "Database error".to_string(),
         }),
     )
         .into_response()
@@ -131,14 +1475,27 @@ pub async fn update_item(
 mod tests {
     use super::*;
     use crate::{
-        auth::jwt::JwtService, config::Config, repositories::user::MockUserRepositoryTrait,
+        auth::jwt::JwtService,
+        config::Config,
+        entities::{Item, ItemStatus, ProcessingState},
+        middleware::transaction::transaction_middleware,
+        repositories::{
+            ItemDetails, ItemSortField, item::MockItemRepositoryTrait,
+            user::MockUserRepositoryTrait,
+        },
     };
     use axum::{
         Router,
-        body::Body,
-        http::{Request, header::AUTHORIZATION},
-        routing::{get, patch, post},
+        body::{Body, to_bytes},
+        http::{
+            Request,
+            header::{AUTHORIZATION, IF_MATCH},
+        },
+        middleware::from_fn_with_state,
+        routing::{delete, get, patch, post},
     };
+    use mockall::predicate::eq;
+    use serde_json::{Value, json};
     use sqlx::{Pool, Postgres};
     use std::sync::Arc;
     use tower::ServiceExt;
@@ -149,20 +1506,82 @@ mod tests {
     }
 
     fn create_test_app() -> Router {
+        create_test_app_with(MockItemRepositoryTrait::new())
+    }
+
+    fn create_test_app_with(item_repo: MockItemRepositoryTrait) -> Router {
         let mock_repo = MockUserRepositoryTrait::new();
         let state = AppState {
             user_repo: Arc::new(mock_repo),
+            item_repo: Arc::new(item_repo),
             db_pool: create_test_pool(),
+            embedder: None,
         };
 
         Router::new()
             .route("/items", get(list_items))
             .route("/items", post(create_item))
+            .route("/items/quick-reads", get(list_quick_reads))
             .route("/items/{id}", get(get_item))
             .route("/items/{id}", patch(update_item))
+            .route("/items/content:batchGet", post(batch_get_content))
             .with_state(state)
     }
 
+    /// Routes that write in a request transaction, on a real database but
+    /// with `item_repo` standing in for the item queries
+    fn create_transactional_app(
+        pool: Pool<Postgres>,
+        item_repo: MockItemRepositoryTrait,
+    ) -> Router {
+        let mut user_repo = MockUserRepositoryTrait::new();
+        user_repo.expect_find_profile().returning(|_| Ok(None));
+        let state = AppState {
+            user_repo: Arc::new(user_repo),
+            item_repo: Arc::new(item_repo),
+            db_pool: pool.clone(),
+            embedder: None,
+        };
+
+        Router::new()
+            .route("/items", post(create_item))
+            .route("/items/{id}", delete(delete_item))
+            .route_layer(from_fn_with_state(pool, transaction_middleware))
+            .with_state(state)
+    }
+
+    async fn setup_test_db() -> Option<Pool<Postgres>> {
+        // Skip tests if TEST_DATABASE_URL is not set
+        let database_url = match std::env::var("TEST_DATABASE_URL") {
+            Ok(url) => url,
+            Err(_) => {
+                eprintln!("Skipping database tests: TEST_DATABASE_URL not set");
+                return None;
+            }
+        };
+
+        let pool = Pool::<Postgres>::connect(&database_url)
+            .await
+            .expect("Failed to connect to test database");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        Some(pool)
+    }
+
+    async fn insert_user(pool: &Pool<Postgres>) -> Uuid {
+        sqlx::query_scalar!(
+            "INSERT INTO users (email, pw_hash) VALUES ($1, 'x') RETURNING id",
+            format!("{}@example.com", Uuid::new_v4())
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
     fn create_jwt_token(user_id: Uuid) -> String {
         let config = Config::from_env().expect("Failed to load config");
         let jwt_service = JwtService::new(config.jwt_secret());
@@ -171,47 +1590,360 @@ mod tests {
             .expect("Failed to generate token")
     }
 
+    fn item(user_id: Uuid, item_id: Uuid, status: ItemStatus) -> ItemDetails {
+        let now = Utc::now();
+        ItemDetails {
+            item: Item {
+                id: item_id,
+                user_id,
+                url: "https://example.com/post".to_string(),
+                title: Some("A Post".to_string()),
+                site: Some("example.com".to_string()),
+                status,
+                extraction_error: None,
+                last_error: None,
+                processing_state: ProcessingState::Ready,
+                processing_state_changed_at: now,
+                nsfw: false,
+                read_progress: 0.0,
+                favorite: false,
+                version: 1,
+                created_at: now,
+                updated_at: now,
+            },
+            tags: vec!["rust".to_string()],
+            topics: Vec::new(),
+            word_count: Some(900),
+            reading_time_minutes: Some(4),
+        }
+    }
+
+    async fn send(app: Router, user_id: Uuid, request: Request<Body>) -> (StatusCode, Value) {
+        let (mut parts, body) = request.into_parts();
+        parts.headers.insert(
+            AUTHORIZATION,
+            format!("Bearer {}", create_jwt_token(user_id))
+                .parse()
+                .unwrap(),
+        );
+        let response = app.oneshot(Request::from_parts(parts, body)).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
     #[tokio::test]
     async fn test_items_routes_require_authentication() {
         let app = create_test_app();
         let user_id = Uuid::new_v4();
         let token = create_jwt_token(user_id);
 
-        // Test GET /items
+        // Test GET /items/{id}; the query is rejected before any database access
         let request = Request::builder()
             .method("GET")
-            .uri("/items")
+            .uri(format!("/items/{}?include=bogus", Uuid::new_v4()))
             .header(AUTHORIZATION, format!("Bearer {}", token))
             .body(Body::empty())
             .unwrap();
 
         let response = app.clone().oneshot(request).await.unwrap();
-        assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_items_routes_reject_unauthorized() {
+        let app = create_test_app();
 
-        // Test POST /items
         let request = Request::builder()
-            .method("POST")
+            .method("GET")
             .uri("/items")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_batch_get_content_rejects_empty_request() {
+        let app = create_test_app();
+        let token = create_jwt_token(Uuid::new_v4());
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/items/content:batchGet")
             .header(AUTHORIZATION, format!("Bearer {}", token))
             .header("content-type", "application/json")
-            .body(Body::from(r#"{"url": "https://example.com"}"#))
+            .body(Body::from(r#"{"item_ids": []}"#))
             .unwrap();
 
-        let response = app.clone().oneshot(request).await.unwrap();
-        assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
     #[tokio::test]
-    async fn test_items_routes_reject_unauthorized() {
-        let app = create_test_app();
+    async fn test_list_items_builds_filter_from_query() {
+        let user_id = Uuid::new_v4();
+        let item_id = Uuid::new_v4();
+        let expected = ItemFilter {
+            lang: Some("en".to_string()),
+            tag: Some("rust".to_string()),
+            reading_time: Some(ReadingTime::QUICK),
+            hide_snoozed: true,
+            ..Default::default()
+        };
+
+        let mut item_repo = MockItemRepositoryTrait::new();
+        let filter = expected.clone();
+        item_repo
+            .expect_fingerprint()
+            .withf(move |id, f| *id == user_id && *f == filter)
+            .returning(|_, _| Ok((1, Some(Utc::now()))));
+        let filter = expected.clone();
+        item_repo
+            .expect_list_for_user()
+            .withf(move |id, f, sort, after, limit, offset| {
+                *id == user_id
+                    && *f == filter
+                    && sort.field == ItemSortField::Title
+                    && sort.descending
+                    && after.is_none()
+                    && *limit == 11
+                    && *offset == 0
+            })
+            .returning(move |id, _, _, _, _, _| Ok(vec![item(id, item_id, ItemStatus::Fetched)]));
 
         let request = Request::builder()
-            .method("GET")
-            .uri("/items")
+            .uri(
+                "/items/quick-reads?lang=EN&tag=%20rust%20&limit=10&reading_time=%3E30&sort=-title",
+            )
             .body(Body::empty())
             .unwrap();
+        let (status, body) = send(create_test_app_with(item_repo), user_id, request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["approximate_total"], 1);
+        assert_eq!(body["items"][0]["id"], item_id.to_string());
+        assert_eq!(body["items"][0]["tags"], json!(["rust"]));
+        assert!(body["next_cursor"].is_null());
+    }
 
-        let response = app.oneshot(request).await.unwrap();
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    #[tokio::test]
+    async fn test_get_item_not_found() {
+        let user_id = Uuid::new_v4();
+        let item_id = Uuid::new_v4();
+        let mut item_repo = MockItemRepositoryTrait::new();
+        item_repo
+            .expect_find_for_user()
+            .with(eq(user_id), eq(item_id))
+            .returning(|_, _| Ok(None));
+
+        let request = Request::builder()
+            .uri(format!("/items/{}", item_id))
+            .body(Body::empty())
+            .unwrap();
+        let (status, body) = send(create_test_app_with(item_repo), user_id, request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "Item not found");
+    }
+
+    #[tokio::test]
+    async fn test_update_item_trims_title_and_reports_status_conflicts() {
+        let user_id = Uuid::new_v4();
+        let item_id = Uuid::new_v4();
+        let mut item_repo = MockItemRepositoryTrait::new();
+        item_repo
+            .expect_update()
+            .with(
+                eq(user_id),
+                eq(item_id),
+                eq(Some("New title".to_string())),
+                eq(None::<ItemStatus>),
+                eq(None::<i64>),
+            )
+            .returning(|_, _, _, _, _| Ok(ItemEdit::Applied { version: 2 }));
+        item_repo
+            .expect_update()
+            .with(
+                eq(user_id),
+                eq(item_id),
+                eq(None::<String>),
+                eq(Some(ItemStatus::Pending)),
+                eq(None::<i64>),
+            )
+            .returning(|_, _, _, _, _| Ok(ItemEdit::StatusNotAllowed));
+        item_repo
+            .expect_update()
+            .with(
+                eq(user_id),
+                eq(item_id),
+                eq(Some("Stale title".to_string())),
+                eq(None::<ItemStatus>),
+                eq(Some(3)),
+            )
+            .returning(|_, _, _, _, _| Ok(ItemEdit::Stale));
+        item_repo
+            .expect_find_for_user()
+            .with(eq(user_id), eq(item_id))
+            .returning(|user_id, item_id| Ok(Some(item(user_id, item_id, ItemStatus::Fetched))));
+        let app = create_test_app_with(item_repo);
+
+        let patch = |body: Value| {
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/items/{}", item_id))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let (status, body) = send(
+            app.clone(),
+            user_id,
+            patch(json!({"title": "  New title "})),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["id"], item_id.to_string());
+
+        let (status, body) = send(app.clone(), user_id, patch(json!({"status": "pending"}))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"], "Item can't be moved to that status");
+
+        // An edit against an older version gets the current item back
+        let mut request = patch(json!({"title": "Stale title"}));
+        request
+            .headers_mut()
+            .insert(IF_MATCH, "\"v3\"".parse().unwrap());
+        let (status, body) = send(app, user_id, request).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["title"], "A Post");
+        assert_eq!(body["version"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_item_saves_through_the_repository() {
+        let Some(pool) = setup_test_db().await else {
+            return; // Skip test if database not available
+        };
+        let user_id = insert_user(&pool).await;
+        let item_id = Uuid::new_v4();
+        let mut item_repo = MockItemRepositoryTrait::new();
+        item_repo
+            .expect_find_saved_in()
+            .withf(move |_, id, url| *id == user_id && url == "https://example.com/post")
+            .returning(|_, _, _| Ok(None));
+        item_repo
+            .expect_create_in()
+            .withf(move |_, id, url| *id == user_id && url == "https://example.com/post")
+            .returning(move |_, user_id, _| {
+                let mut created = item(user_id, item_id, ItemStatus::Pending);
+                created.tags.clear();
+                Ok(created)
+            });
+        item_repo
+            .expect_version_in()
+            .withf(move |_, user, id| *user == user_id && *id == item_id)
+            .returning(|_, _, _| Ok(Some(1)));
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/items")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"url": "https://example.com/post?utm_source=feed"}).to_string(),
+            ))
+            .unwrap();
+        let app = create_transactional_app(pool.clone(), item_repo);
+        let (status, body) = send(app, user_id, request).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["id"], item_id.to_string());
+        assert_eq!(body["version"], 1);
+        assert!(body["duplicate_of"].is_null());
+
+        // The fetch was staged in the same transaction and committed with it
+        let staged = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM job_outbox WHERE payload->>'item_id' = $1"#,
+            item_id.to_string()
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(staged > 0);
+    }
+
+    #[tokio::test]
+    async fn test_create_item_answers_an_earlier_save_with_its_item() {
+        let Some(pool) = setup_test_db().await else {
+            return; // Skip test if database not available
+        };
+        let user_id = insert_user(&pool).await;
+        let item_id = Uuid::new_v4();
+        let mut item_repo = MockItemRepositoryTrait::new();
+        item_repo
+            .expect_find_saved_in()
+            .returning(move |_, _, _| Ok(Some(item_id)));
+        item_repo.expect_create_in().never();
+        item_repo
+            .expect_version_in()
+            .returning(|_, _, _| Ok(Some(4)));
+        item_repo
+            .expect_find_for_user()
+            .with(eq(user_id), eq(item_id))
+            .returning(|user_id, item_id| Ok(Some(item(user_id, item_id, ItemStatus::Fetched))));
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/items")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"url": "https://example.com/post", "favorite": true}).to_string(),
+            ))
+            .unwrap();
+        let app = create_transactional_app(pool, item_repo);
+        let (status, body) = send(app, user_id, request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["duplicate_of"], item_id.to_string());
+        assert_eq!(body["version"], 4);
+        assert_eq!(body["favorite"], true);
+    }
+
+    #[tokio::test]
+    async fn test_delete_item_deletes_through_the_repository() {
+        let Some(pool) = setup_test_db().await else {
+            return; // Skip test if database not available
+        };
+        let user_id = insert_user(&pool).await;
+        let item_id = Uuid::new_v4();
+        let missing_id = Uuid::new_v4();
+        let mut item_repo = MockItemRepositoryTrait::new();
+        item_repo
+            .expect_delete_in()
+            .withf(move |_, user, id| *user == user_id && *id == item_id)
+            .returning(|_, _, _| Ok(true));
+        item_repo
+            .expect_delete_in()
+            .withf(move |_, user, id| *user == user_id && *id == missing_id)
+            .returning(|_, _, _| Ok(false));
+        let app = create_transactional_app(pool, item_repo);
+
+        let delete = |id: Uuid| {
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/items/{}", id))
+                .header(
+                    AUTHORIZATION,
+                    format!("Bearer {}", create_jwt_token(user_id)),
+                )
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(delete(item_id)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(response.headers().contains_key("x-undo-token"));
+
+        let (status, body) = send(app, user_id, delete(missing_id)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "Item not found");
     }
 }
@@ -1,2 +1,4 @@
 pub mod dtos;
+pub mod etag;
 pub mod handlers;
+pub mod preview;
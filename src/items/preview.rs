@@ -0,0 +1,234 @@
+//! Link previews fetched while the client waits, so it can show what a page
+//! looks like before the user confirms saving it.
+//!
+//! Previews read from the shared fetch cache the worker fills, and remember
+//! their own results for a few minutes, so the same link pasted twice or
+//! saved right after previewing doesn't cost a second fetch. Users who opted
+//! out of sharing get neither.
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use scraper::Html;
+use sqlx::PgPool;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    extractor::{self, page_metadata},
+    fetcher::{Deadline, FetchError, fetch_with_deadline},
+    items::dtos::ItemPreviewResponse,
+    repositories::{
+        DocumentRepository, DomainRulesRepository, Extraction, FetchCacheRepository,
+        item::WORDS_PER_MINUTE,
+    },
+    urlnorm::canonicalize,
+};
+
+/// How long a preview may take before the client is told to give up
+pub const PREVIEW_BUDGET: Duration = Duration::from_secs(5);
+
+/// How long a preview is reused for
+const PREVIEW_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// Previews kept in memory before the oldest are dropped
+const MAX_CACHED_PREVIEWS: usize = 1024;
+
+static PREVIEW_CACHE: Lazy<DashMap<String, (Instant, ItemPreviewResponse)>> =
+    Lazy::new(DashMap::new);
+
+#[derive(Debug, Error)]
+pub enum PreviewError {
+    #[error("{0}")]
+    Blocked(String),
+
+    #[error(transparent)]
+    Fetch(#[from] FetchError),
+
+    #[error(transparent)]
+    Database(#[from] anyhow::Error),
+}
+
+/// Fetch and extract `url` for `user_id`, giving up when `deadline` passes
+pub async fn preview(
+    pool: &PgPool,
+    user_id: Uuid,
+    url: &str,
+    deadline: &Deadline,
+) -> Result<ItemPreviewResponse, PreviewError> {
+    blocked(pool, url).await?;
+
+    let shared = DocumentRepository::new(pool)
+        .sharing_enabled(user_id)
+        .await?;
+    let key = canonicalize(url).filter(|_| shared);
+
+    if let Some(key) = &key {
+        if let Some(preview) = cached(key) {
+            return Ok(preview);
+        }
+        if let Some(fetch) = FetchCacheRepository::new(pool).get_fresh(key).await?
+            && let Ok(final_url) = Url::parse(&fetch.final_url)
+        {
+            let preview = build_preview(
+                &final_url,
+                &fetch.body,
+                &fetch.extraction.0,
+                fetch.fetched_at,
+            );
+            remember(key, &preview);
+            return Ok(preview);
+        }
+    }
+
+    let page = fetch_with_deadline(url, deadline).await?;
+    if page.url_final.as_str() != url {
+        blocked(pool, page.url_final.as_str()).await?;
+    }
+    let extraction = extractor::extract_or_reason(&page).await;
+    let preview = build_preview(
+        &page.url_final,
+        &page.body_utf8,
+        &extraction,
+        page.fetched_at,
+    );
+
+    if let Some(key) = &key {
+        remember(key, &preview);
+    }
+    Ok(preview)
+}
+
+/// Put together a preview from a page and what the extractor made of it
+pub fn build_preview(
+    final_url: &Url,
+    body: &str,
+    extraction: &Extraction,
+    fetched_at: DateTime<Utc>,
+) -> ItemPreviewResponse {
+    let metadata = page_metadata(&Html::parse_document(body), final_url);
+
+    let (title, site, reading_time_minutes, language, extraction_error) = match extraction {
+        Ok(content) => (
+            Some(content.title.trim().to_string())
+                .filter(|title| !title.is_empty())
+                .or(metadata.title),
+            content.site_name.clone().or(metadata.site_name),
+            Some(reading_time_minutes(&content.text)),
+            content.language.clone(),
+            None,
+        ),
+        Err(failure) => (
+            metadata.title,
+            metadata.site_name,
+            None,
+            None,
+            Some(*failure),
+        ),
+    };
+
+    ItemPreviewResponse {
+        url: final_url.to_string(),
+        title,
+        site,
+        lead_image: metadata.lead_image.map(String::from),
+        reading_time_minutes,
+        language,
+        extraction_error,
+        fetched_at,
+    }
+}
+
+/// Minutes to read `text`, rounded up as for saved items
+fn reading_time_minutes(text: &str) -> i32 {
+    let words = text.split_whitespace().count();
+    (words as f64 / WORDS_PER_MINUTE).ceil() as i32
+}
+
+async fn blocked(pool: &PgPool, url: &str) -> Result<(), PreviewError> {
+    let Some(host) = Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+    else {
+        return Ok(());
+    };
+    match DomainRulesRepository::new(pool)
+        .blocked_reason(&host)
+        .await?
+    {
+        Some(detail) => Err(PreviewError::Blocked(detail)),
+        None => Ok(()),
+    }
+}
+
+fn cached(key: &str) -> Option<ItemPreviewResponse> {
+    let entry = PREVIEW_CACHE.get(key)?;
+    let (stored_at, preview) = entry.value();
+    (stored_at.elapsed() < PREVIEW_CACHE_TTL).then(|| preview.clone())
+}
+
+fn remember(key: &str, preview: &ItemPreviewResponse) {
+    if PREVIEW_CACHE.len() >= MAX_CACHED_PREVIEWS {
+        PREVIEW_CACHE.retain(|_, (stored_at, _)| stored_at.elapsed() < PREVIEW_CACHE_TTL);
+    }
+    if PREVIEW_CACHE.len() >= MAX_CACHED_PREVIEWS {
+        PREVIEW_CACHE.clear();
+    }
+    PREVIEW_CACHE.insert(key.to_string(), (Instant::now(), preview.clone()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{entities::ExtractionFailure, extractor::ExtractedContent};
+
+    const PAGE: &str = r#"<html><head>
+        <title>Head Title</title>
+        <meta property="og:site_name" content="Example">
+        <meta property="og:image" content="/lead.jpg">
+        </head><body></body></html>"#;
+
+    fn content(text: &str) -> ExtractedContent {
+        ExtractedContent {
+            url: Url::parse("https://example.com/a").unwrap(),
+            title: "Extracted Title".to_string(),
+            site_name: None,
+            byline: None,
+            language: Some("en".to_string()),
+            text: text.to_string(),
+            html: String::new(),
+            fetched_at: Utc::now(),
+            outline: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_preview_from_extraction() {
+        let url = Url::parse("https://example.com/a").unwrap();
+        let text = "word ".repeat(500);
+        let preview = build_preview(&url, PAGE, &Ok(content(&text)), Utc::now());
+
+        assert_eq!(preview.title.as_deref(), Some("Extracted Title"));
+        assert_eq!(preview.site.as_deref(), Some("Example"));
+        assert_eq!(
+            preview.lead_image.as_deref(),
+            Some("https://example.com/lead.jpg")
+        );
+        // 500 words at 238 a minute
+        assert_eq!(preview.reading_time_minutes, Some(3));
+        assert_eq!(preview.language.as_deref(), Some("en"));
+        assert_eq!(preview.extraction_error, None);
+    }
+
+    #[test]
+    fn test_build_preview_without_content() {
+        let url = Url::parse("https://example.com/a").unwrap();
+        let preview = build_preview(&url, PAGE, &Err(ExtractionFailure::TooShort), Utc::now());
+
+        assert_eq!(preview.title.as_deref(), Some("Head Title"));
+        assert_eq!(preview.reading_time_minutes, None);
+        assert_eq!(preview.extraction_error, Some(ExtractionFailure::TooShort));
+    }
+}
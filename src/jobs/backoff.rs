@@ -1,21 +1,45 @@
+//! How long a failed job waits before its next attempt.
+//!
+//! The wait doubles with every attempt from the worker's base delay, up to
+//! [`MAX_BACKOFF_EXPONENT`] doublings, and is then spread by up to
+//! [`BACKOFF_JITTER`] either way so jobs that failed together don't all
+//! retry at once. [`backoff_bounds`] gives the range a retry can land in.
+
 use rand::Rng;
 use std::time::Duration;
 
-/// Calculate exponential backoff delay with jitter
-pub fn calculate_backoff_delay(attempt: i32, base_delay_secs: u32) -> Duration {
-    let attempt = attempt.max(0) as u32;
+/// Doublings after which the delay stops growing (about 8.5 hours with a
+/// 30 second base)
+pub const MAX_BACKOFF_EXPONENT: u32 = 10;
 
-    // Cap the exponent to prevent overflow (max ~8.5 hours with 30s base)
-    let capped_attempt = attempt.min(10);
+/// Fraction of the delay a retry may land either side of it
+pub const BACKOFF_JITTER: f64 = 0.3;
 
-    // Calculate base delay: base * 2^attempt
+/// The delay before retrying after `attempt` attempts, without jitter:
+/// `base_delay_secs` doubled once per attempt, never shrinking as attempts
+/// go up and never more than [`MAX_BACKOFF_EXPONENT`] doublings
+pub fn backoff_base_delay(attempt: i32, base_delay_secs: u32) -> Duration {
+    let attempt = attempt.max(0) as u32;
+    let capped_attempt = attempt.min(MAX_BACKOFF_EXPONENT);
     let base_delay = base_delay_secs.saturating_mul(2_u32.saturating_pow(capped_attempt));
+    Duration::from_secs(base_delay as u64)
+}
 
-    // Add jitter: ±30% randomness
-    let jitter_factor = rand::thread_rng().gen_range(0.7..1.3);
-    let delay_with_jitter = (base_delay as f64 * jitter_factor).round() as u64;
+/// The shortest and longest delays [`calculate_backoff_delay`] can pick
+/// for `attempt`
+pub fn backoff_bounds(attempt: i32, base_delay_secs: u32) -> (Duration, Duration) {
+    let base_delay = backoff_base_delay(attempt, base_delay_secs).as_secs() as f64;
+    (
+        Duration::from_secs((base_delay * (1.0 - BACKOFF_JITTER)).round() as u64),
+        Duration::from_secs((base_delay * (1.0 + BACKOFF_JITTER)).round() as u64),
+    )
+}
 
-    Duration::from_secs(delay_with_jitter)
+/// Calculate exponential backoff delay with jitter
+pub fn calculate_backoff_delay(attempt: i32, base_delay_secs: u32) -> Duration {
+    let base_delay = backoff_base_delay(attempt, base_delay_secs).as_secs() as f64;
+    let jitter_factor = rand::thread_rng().gen_range(1.0 - BACKOFF_JITTER..1.0 + BACKOFF_JITTER);
+    Duration::from_secs((base_delay * jitter_factor).round() as u64)
 }
 
 #[cfg(test)]
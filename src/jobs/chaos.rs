@@ -0,0 +1,78 @@
+//! Fault injection for exercising the worker under adverse conditions.
+//!
+//! With a [`ChaosConfig`] in [`WorkerConfig::chaos`](crate::jobs::WorkerConfig)
+//! the worker fails some jobs before their handler runs, stalls some of its
+//! database calls and abandons some leases as if it had died holding them.
+//! It's meant for tests of retries, lease expiry and shutdown, never for a
+//! worker doing real work, so nothing turns it on from the environment.
+
+use rand::Rng;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// Error a job fails with when chaos fails it
+pub const INJECTED_FAILURE: &str = "Injected failure";
+
+/// How often each fault happens, as rates between 0 and 1
+#[derive(Debug, Clone, Default)]
+pub struct ChaosConfig {
+    /// Jobs failed with [`INJECTED_FAILURE`] instead of running, going
+    /// through the usual retries
+    pub failure_rate: f64,
+    /// Database calls held back by `db_delay_ms` first
+    pub db_delay_rate: f64,
+    pub db_delay_ms: u64,
+    /// Leased jobs dropped without running or being released, so they only
+    /// run again once their visibility timeout passes
+    pub abandon_rate: f64,
+}
+
+impl ChaosConfig {
+    /// Whether to fail the job about to run
+    pub fn fail(&self) -> bool {
+        roll(self.failure_rate)
+    }
+
+    /// Whether to abandon the job just leased
+    pub fn abandon(&self) -> bool {
+        roll(self.abandon_rate)
+    }
+
+    /// Stall before a database call, some of the time
+    pub async fn delay_db(&self) {
+        if roll(self.db_delay_rate) {
+            sleep(Duration::from_millis(self.db_delay_ms)).await;
+        }
+    }
+}
+
+fn roll(rate: f64) -> bool {
+    rate > 0.0 && rand::thread_rng().gen_bool(rate.min(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_chaos_injects_nothing() {
+        let chaos = ChaosConfig::default();
+        for _ in 0..100 {
+            assert!(!chaos.fail());
+            assert!(!chaos.abandon());
+        }
+    }
+
+    #[test]
+    fn test_certain_faults_always_happen() {
+        let chaos = ChaosConfig {
+            failure_rate: 1.0,
+            abandon_rate: 2.0,
+            ..ChaosConfig::default()
+        };
+        for _ in 0..100 {
+            assert!(chaos.fail());
+            assert!(chaos.abandon());
+        }
+    }
+}
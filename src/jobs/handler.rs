@@ -1,3 +1,4 @@
+use crate::{fetcher::Deadline, jobs::validate_payload};
 use async_trait::async_trait;
 use serde_json::Value;
 use sqlx::PgPool;
@@ -6,11 +7,26 @@ use tracing::Span;
 /// Trait for handling specific job types
 #[async_trait]
 pub trait JobHandler: Send + Sync + 'static {
-    /// Execute the job
-    async fn run(&self, payload: Value, pool: &PgPool, span: Span) -> anyhow::Result<()>;
+    /// Execute the job. `deadline` passes when the job's lease runs out and
+    /// another worker may pick it up, so work still going by then is wasted;
+    /// handlers pass it to the fetcher and stop early where they can resume.
+    async fn run(
+        &self,
+        payload: Value,
+        pool: &PgPool,
+        span: Span,
+        deadline: Deadline,
+    ) -> anyhow::Result<()>;
 
     /// Get the job kind this handler processes
     fn kind(&self) -> &'static str;
+
+    /// Reject payloads this handler can't run. Defaults to the shared
+    /// per-kind check used at enqueue time.
+    fn validate_payload(&self, payload: &Value) -> anyhow::Result<()> {
+        validate_payload(self.kind(), payload)?;
+        Ok(())
+    }
 }
 
 /// Type-erased job handler factory
@@ -0,0 +1,91 @@
+use crate::{
+    fetcher::Deadline,
+    jobs::handler::JobHandler,
+    repositories::{ContentRepository, TopicRepository},
+    topics::{KeywordClassifier, TopicClassifier},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{Span, info, instrument};
+use uuid::Uuid;
+
+pub const CLASSIFY_TOPICS_JOB_KIND: &str = "classify_topics";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClassifyTopicsPayload {
+    pub item_id: Uuid,
+}
+
+/// Assigns broad topics to an item with the configured
+/// [`TopicClassifier`]. Queued after every successful extraction, so a
+/// refreshed page is classified again.
+#[derive(Clone)]
+pub struct ClassifyTopicsJobHandler {
+    classifier: Arc<dyn TopicClassifier>,
+}
+
+#[async_trait]
+impl JobHandler for ClassifyTopicsJobHandler {
+    #[instrument(skip(self, pool, span, _deadline), fields(item_id))]
+    async fn run(
+        &self,
+        payload: Value,
+        pool: &PgPool,
+        span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let payload: ClassifyTopicsPayload = serde_json::from_value(payload)?;
+        span.record("item_id", tracing::field::display(payload.item_id));
+
+        let Some(source) = ContentRepository::new(pool)
+            .summary_source(payload.item_id)
+            .await?
+        else {
+            info!("Item {} has no content to classify", payload.item_id);
+            return Ok(());
+        };
+        let Some(text) = source.text.filter(|text| !text.trim().is_empty()) else {
+            info!("Item {} has no extracted text", payload.item_id);
+            return Ok(());
+        };
+
+        let topics = self
+            .classifier
+            .classify(source.title.as_deref(), &text)
+            .await?;
+        TopicRepository::new(pool)
+            .set_topics(payload.item_id, &topics)
+            .await?;
+        info!(
+            "Classified item {} as {:?} with {}",
+            payload.item_id,
+            topics,
+            self.classifier.name()
+        );
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        CLASSIFY_TOPICS_JOB_KIND
+    }
+}
+
+impl ClassifyTopicsJobHandler {
+    /// A handler using the built-in keyword rules
+    pub fn new() -> Self {
+        Self::with_classifier(Arc::new(KeywordClassifier::default()))
+    }
+
+    pub fn with_classifier(classifier: Arc<dyn TopicClassifier>) -> Self {
+        Self { classifier }
+    }
+}
+
+impl Default for ClassifyTopicsJobHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{
+    fetcher::Deadline,
+    jobs::{JobRepository, handler::JobHandler},
+    repositories::{ContentRepository, DocumentRepository},
+};
+use async_trait::async_trait;
+use serde_json::json;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{Span, info};
+
+pub const COMPRESS_HTML_JOB_KIND: &str = "compress_html";
+
+/// Rows compressed per transaction
+const COMPRESS_BATCH_SIZE: i64 = 100;
+
+/// Lease left at which a run hands over to the next one, so the two never
+/// overlap
+const HANDOVER_MARGIN: Duration = Duration::from_secs(15);
+
+/// Backfill for HTML stored before it was compressed: moves item contents
+/// and shared documents over to the compressed columns a batch at a time.
+/// Queued at worker startup; finds nothing to do once the backfill is done.
+/// A run cut short by its lease queues the next one to carry on.
+#[derive(Clone)]
+pub struct CompressHtmlJobHandler;
+
+#[async_trait]
+impl JobHandler for CompressHtmlJobHandler {
+    async fn run(
+        &self,
+        _payload: serde_json::Value,
+        pool: &PgPool,
+        _span: Span,
+        deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let contents = ContentRepository::new(pool);
+        let documents = DocumentRepository::new(pool);
+        let mut compressed = 0;
+
+        loop {
+            if deadline.is_expired()
+                || deadline
+                    .remaining()
+                    .is_some_and(|left| left < HANDOVER_MARGIN)
+            {
+                info!(
+                    "Compressed HTML of {} rows; continuing in the next run",
+                    compressed
+                );
+                JobRepository::enqueue_if_absent(pool, COMPRESS_HTML_JOB_KIND, json!({}), None)
+                    .await?;
+                return Ok(());
+            }
+
+            let mut batch = contents.compress_stored_html(COMPRESS_BATCH_SIZE).await?;
+            if batch == 0 {
+                batch = documents.compress_stored_html(COMPRESS_BATCH_SIZE).await?;
+            }
+            if batch == 0 {
+                break;
+            }
+            compressed += batch;
+        }
+
+        if compressed > 0 {
+            info!("Compressed HTML of {} rows", compressed);
+        }
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        COMPRESS_HTML_JOB_KIND
+    }
+}
+
+impl CompressHtmlJobHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CompressHtmlJobHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{
+    backup::portable_row, entities::OperationState, fetcher::Deadline, jobs::handler::JobHandler,
+    repositories::DataRequestRepository,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use tracing::{Span, info, instrument, warn};
+use uuid::Uuid;
+
+pub const BUILD_DATA_PACKAGE_JOB_KIND: &str = "build_data_package";
+
+/// Identifies the package format, for whoever processes it
+pub const DATA_PACKAGE_FORMAT: &str = "capsule-data-package";
+pub const DATA_PACKAGE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuildDataPackagePayload {
+    pub data_request_id: Uuid,
+}
+
+/// Builds the data package for a subject access request: every row
+/// referencing the user, by table, with page HTML decompressed and
+/// credentials left out. A request that can't be built is marked failed
+/// rather than retried, so the admin sees it and can ask again.
+#[derive(Clone)]
+pub struct BuildDataPackageJobHandler;
+
+#[async_trait]
+impl JobHandler for BuildDataPackageJobHandler {
+    #[instrument(skip(self, pool, span, _deadline), fields(data_request_id))]
+    async fn run(
+        &self,
+        payload: Value,
+        pool: &PgPool,
+        span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let payload: BuildDataPackagePayload = serde_json::from_value(payload)?;
+        span.record(
+            "data_request_id",
+            tracing::field::display(payload.data_request_id),
+        );
+
+        let repo = DataRequestRepository::new(pool);
+        let Some(request) = repo.find(payload.data_request_id).await? else {
+            return Ok(());
+        };
+        if request.state != OperationState::Pending {
+            return Ok(());
+        }
+        repo.start(request.id).await?;
+
+        match build_package(&repo, request.subject_id).await {
+            Ok(package) => {
+                repo.complete(request.id, &package).await?;
+                info!(
+                    "Built data package for user {} requested by {:?}",
+                    request.subject_id, request.requested_by
+                );
+            }
+            Err(e) => {
+                warn!("Failed to build data package: {:#}", e);
+                repo.fail(request.id, &e.to_string()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        BUILD_DATA_PACKAGE_JOB_KIND
+    }
+}
+
+async fn build_package(
+    repo: &DataRequestRepository<'_>,
+    subject_id: Uuid,
+) -> anyhow::Result<Value> {
+    let mut tables = serde_json::Map::new();
+    for (table, rows) in repo.user_rows(subject_id).await? {
+        let rows = rows
+            .into_iter()
+            .map(|row| portable_row(&[], row))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        tables.insert(table, Value::Array(rows));
+    }
+
+    Ok(json!({
+        "format": DATA_PACKAGE_FORMAT,
+        "version": DATA_PACKAGE_FORMAT_VERSION,
+        "user_id": subject_id,
+        "generated_at": Utc::now(),
+        "tables": tables,
+    }))
+}
+
+impl BuildDataPackageJobHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for BuildDataPackageJobHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
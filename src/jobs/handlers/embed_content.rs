@@ -0,0 +1,92 @@
+use crate::{
+    embeddings::{EmbeddingProvider, embedding_input},
+    fetcher::Deadline,
+    jobs::handler::JobHandler,
+    repositories::{ContentRepository, EmbeddingRepository},
+};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{Span, info, instrument};
+use uuid::Uuid;
+
+pub const EMBED_CONTENT_JOB_KIND: &str = "embed_content";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmbedContentPayload {
+    pub item_id: Uuid,
+}
+
+/// Stores an embedding of an item's title and text for semantic search.
+/// Queued after every successful extraction; does nothing unless a provider
+/// is configured and the database has pgvector.
+#[derive(Clone)]
+pub struct EmbedContentJobHandler {
+    provider: Option<Arc<dyn EmbeddingProvider>>,
+}
+
+#[async_trait]
+impl JobHandler for EmbedContentJobHandler {
+    #[instrument(skip(self, pool, span, _deadline), fields(item_id))]
+    async fn run(
+        &self,
+        payload: Value,
+        pool: &PgPool,
+        span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let payload: EmbedContentPayload = serde_json::from_value(payload)?;
+        span.record("item_id", tracing::field::display(payload.item_id));
+
+        let Some(provider) = &self.provider else {
+            return Ok(());
+        };
+        let embeddings = EmbeddingRepository::new(pool);
+        if !embeddings.available().await? {
+            info!("pgvector is not enabled; skipping embedding");
+            return Ok(());
+        }
+
+        let Some(source) = ContentRepository::new(pool)
+            .summary_source(payload.item_id)
+            .await?
+        else {
+            info!("Item {} has no content to embed", payload.item_id);
+            return Ok(());
+        };
+        let Some(text) = source.text.filter(|text| !text.trim().is_empty()) else {
+            info!("Item {} has no extracted text", payload.item_id);
+            return Ok(());
+        };
+
+        let input = embedding_input(source.title.as_deref(), &text);
+        let embedding = provider
+            .embed(&[input])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("Embedding provider returned nothing"))?;
+        embeddings
+            .upsert(payload.item_id, provider.name(), &embedding)
+            .await?;
+        info!(
+            "Embedded item {} with {} ({} dimensions)",
+            payload.item_id,
+            provider.name(),
+            embedding.len()
+        );
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        EMBED_CONTENT_JOB_KIND
+    }
+}
+
+impl EmbedContentJobHandler {
+    pub fn new(provider: Option<Arc<dyn EmbeddingProvider>>) -> Self {
+        Self { provider }
+    }
+}
@@ -1,10 +1,12 @@
-use crate::jobs::JobHandler;
+use crate::{fetcher::Deadline, jobs::JobHandler};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::PgPool;
 use tracing::{Span, info};
 
+pub const EXAMPLE_JOB_KIND: &str = "example_job";
+
 /// Example job payload for demonstrating the job system
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExampleJobPayload {
@@ -18,7 +20,13 @@ pub struct ExampleJobHandler;
 
 #[async_trait]
 impl JobHandler for ExampleJobHandler {
-    async fn run(&self, payload: Value, _pool: &PgPool, _span: Span) -> anyhow::Result<()> {
+    async fn run(
+        &self,
+        payload: Value,
+        _pool: &PgPool,
+        _span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
         let payload: ExampleJobPayload = serde_json::from_value(payload)?;
 
         info!("Processing example job: {}", payload.message);
@@ -33,6 +41,6 @@ impl JobHandler for ExampleJobHandler {
     }
 
     fn kind(&self) -> &'static str {
-        "example_job"
+        EXAMPLE_JOB_KIND
     }
 }
@@ -1,124 +1,630 @@
-use crate::{fetcher::fetch, jobs::handler::JobHandler};
+use crate::{
+    entities::{DomainPref, ItemEventKind, ProcessingState, UserPreferences},
+    extractor::{self, SanitizePolicy, cleaner::strip_images, nsfw, page_metadata},
+    fetcher::{CacheValidators, Deadline, FetchError, PageResponse, fetch_with_timings},
+    jobs::{
+        CLASSIFY_TOPICS_JOB_KIND, ClassifyTopicsPayload, EMBED_CONTENT_JOB_KIND,
+        EmbedContentPayload, JobRepository, SUMMARIZE_CONTENT_JOB_KIND, SummarizeContentPayload,
+        TRANSLATE_CONTENT_JOB_KIND, TranslateContentPayload, handler::JobHandler, stage_site_icon,
+    },
+    repositories::{
+        CachedFetch, ContentRepository, DocumentRepository, DomainPrefsRepository,
+        DomainRulesRepository, Extraction, FetchAttemptRepository, FetchCacheRepository,
+        ImportRepository, ItemEventRepository, ItemStateRepository, LinkRepository, SiteRepository,
+        TagRepository, item::WORDS_PER_MINUTE, url_hash,
+    },
+    urlnorm::canonicalize,
+};
 use async_trait::async_trait;
+use chrono::Utc;
+use scraper::Html;
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{PgPool, types::Json};
+use std::time::Instant;
 use tracing::{Span, info, instrument, warn};
 use uuid::Uuid;
 
+pub const FETCH_PAGE_JOB_KIND: &str = "fetch_page";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FetchPagePayload {
     pub item_id: Uuid,
+    /// Fetch the page again even when a fresh copy is cached, as when
+    /// retrying a failed extraction
+    #[serde(default)]
+    pub refetch: bool,
+}
+
+/// Fetch job configuration
+#[derive(Clone, Debug)]
+pub struct FetchPageConfig {
+    /// How long a fetched page is reused for other users saving the same
+    /// URL; zero disables the shared cache
+    pub cache_ttl_secs: i64,
+    /// Markup kept in extracted content
+    pub sanitize_policy: SanitizePolicy,
+    /// Run the NSFW heuristics over extracted pages and flag matching items;
+    /// off unless the deployment opts in
+    pub flag_nsfw: bool,
+}
+
+impl Default for FetchPageConfig {
+    fn default() -> Self {
+        Self {
+            cache_ttl_secs: 600, // 10 minutes
+            sanitize_policy: SanitizePolicy::default(),
+            flag_nsfw: false,
+        }
+    }
 }
 
 #[derive(Clone)]
-pub struct FetchPageJobHandler;
+pub struct FetchPageJobHandler {
+    config: FetchPageConfig,
+}
 
 #[async_trait]
 impl JobHandler for FetchPageJobHandler {
-    #[instrument(skip(self, pool, span), fields(item_id))]
+    #[instrument(skip(self, pool, span, deadline), fields(item_id))]
     async fn run(
         &self,
         payload: serde_json::Value,
         pool: &PgPool,
         span: Span,
+        deadline: Deadline,
     ) -> anyhow::Result<()> {
         let payload: FetchPagePayload = serde_json::from_value(payload)?;
 
         // Record item_id in the span
         span.record("item_id", tracing::field::display(payload.item_id));
 
-        // Get the item URL with a lock to prevent concurrent processing
-        let item_url: Option<String> = sqlx::query_scalar!(
-            "SELECT url FROM items WHERE id = $1 FOR UPDATE",
+        // Get the item URL and owner
+        let item = sqlx::query!(
+            "SELECT url, user_id FROM items WHERE id = $1",
             payload.item_id
         )
         .fetch_optional(pool)
         .await?;
 
-        let Some(url) = item_url else {
+        let Some(item) = item else {
             anyhow::bail!("Item {} not found", payload.item_id);
         };
+        let (url, user_id) = (item.url, item.user_id);
+
+        let domain_pref = match url::Url::parse(&url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+        {
+            Some(host) => {
+                DomainPrefsRepository::new(pool)
+                    .find_for_host(user_id, &host)
+                    .await?
+            }
+            None => None,
+        };
 
         info!(
             "Fetching content for item {} from URL: {}",
             payload.item_id, url
         );
+        Self::set_state(pool, payload.item_id, ProcessingState::Fetching).await?;
 
-        // Fetch the page content
-        match fetch(&url).await {
-            Ok(response) => {
-                info!(
-                    "Successfully fetched content from {} (status: {}, charset: {:?}, size: {} bytes)",
-                    response.url_final,
-                    response.status,
-                    response.charset,
-                    response.body_utf8.len()
-                );
-
-                // Calculate a simple checksum of the content
-                let checksum = format!("{:x}", md5::compute(response.body_raw.as_ref()));
+        // The domain rules may have changed since the item was saved
+        if let Some(detail) = Self::blocked_reason(pool, &url).await? {
+            return Self::refuse_blocked(pool, payload.item_id, &detail).await;
+        }
 
-                // Insert the content
-                sqlx::query!(
-                    r#"
-                    INSERT INTO contents (item_id, raw_html, raw_text, lang, extracted_at, checksum)
-                    VALUES ($1, $2, NULL, NULL, NOW(), $3)
-                    ON CONFLICT (item_id) 
-                    DO UPDATE SET 
-                        raw_html = EXCLUDED.raw_html,
-                        extracted_at = EXCLUDED.extracted_at,
-                        checksum = EXCLUDED.checksum
-                    "#,
-                    payload.item_id,
-                    response.body_utf8,
-                    checksum
-                )
-                .execute(pool)
+        // A refetch means the last fetch wasn't good enough, so don't hand
+        // back a cached copy of it; users who opted out of sharing neither
+        // read from nor feed the cache
+        let use_cache = self.config.cache_ttl_secs > 0
+            && !payload.refetch
+            && DocumentRepository::new(pool)
+                .sharing_enabled(user_id)
                 .await?;
+        let url_key = canonicalize(&url).filter(|_| use_cache);
+        let cache = FetchCacheRepository::new(pool);
+        let cached = match &url_key {
+            Some(key) => cache.get_fresh(key).await?,
+            None => None,
+        };
 
-                // Update item status to fetched
-                sqlx::query!(
-                    "UPDATE items SET status = 'fetched', updated_at = NOW() WHERE id = $1",
-                    payload.item_id
+        if let Some(cached) = cached {
+            info!(
+                "Reusing fetch of {} from {} for item {}",
+                cached.final_url, cached.fetched_at, payload.item_id
+            );
+            ItemEventRepository::new(pool)
+                .record(
+                    payload.item_id,
+                    ItemEventKind::Fetched,
+                    Some("reused a recent fetch of the same page"),
                 )
-                .execute(pool)
                 .await?;
-
-                info!("Successfully stored content for item {}", payload.item_id);
-                Ok(())
+            Self::store_cached(pool, payload.item_id, &cached, domain_pref.as_ref()).await?;
+            if self.config.flag_nsfw {
+                Self::flag_nsfw(pool, payload.item_id, &cached.body, &cached.extraction.0).await?;
             }
-            Err(fetch_error) => {
-                warn!(
-                    "Failed to fetch content for item {}: {}",
-                    payload.item_id, fetch_error
-                );
+        } else {
+            // Fetch the page content
+            let started = Instant::now();
+            let (fetched, timings) = fetch_with_timings(&url, &deadline).await;
+            let attempts = FetchAttemptRepository::new(pool);
+            match fetched {
+                Ok(response) => {
+                    attempts
+                        .record_response(payload.item_id, &response, started.elapsed(), &timings)
+                        .await?;
+                    if response.url_final.as_str() != url
+                        && let Some(detail) =
+                            Self::blocked_reason(pool, response.url_final.as_str()).await?
+                    {
+                        return Self::refuse_blocked(pool, payload.item_id, &detail).await;
+                    }
+                    info!(
+                        "Successfully fetched content from {} (status: {}, charset: {:?}, size: {} bytes)",
+                        response.url_final,
+                        response.status,
+                        response.charset,
+                        response.body_utf8.len()
+                    );
+                    ItemEventRepository::new(pool)
+                        .record(
+                            payload.item_id,
+                            ItemEventKind::Fetched,
+                            Some(&format!("HTTP {}", response.status.as_u16())),
+                        )
+                        .await?;
 
-                if fetch_error.should_retry() {
-                    // Return error to trigger retry by job runner
-                    anyhow::bail!("Retryable fetch error: {}", fetch_error);
-                } else {
-                    // Mark as permanent failure - don't retry
+                    let extraction = Self::store_page(
+                        pool,
+                        payload.item_id,
+                        &response,
+                        domain_pref.as_ref(),
+                        &self.config.sanitize_policy,
+                    )
+                    .await?;
+                    if self.config.flag_nsfw {
+                        Self::flag_nsfw(pool, payload.item_id, &response.body_utf8, &extraction)
+                            .await?;
+                    }
+
+                    if let Some(key) = &url_key {
+                        let ttl = chrono::Duration::seconds(self.config.cache_ttl_secs);
+                        let checksum = content_checksum(&response);
+                        // The item is already stored; a cache miss later is harmless
+                        if let Err(e) = cache.put(key, &response, &checksum, &extraction, ttl).await
+                        {
+                            warn!("Failed to cache fetch of {}: {}", url, e);
+                        }
+                    }
+                }
+                Err(fetch_error) => {
                     warn!(
-                        "Permanent failure for item {}: {}",
+                        "Failed to fetch content for item {}: {}",
                         payload.item_id, fetch_error
                     );
+                    attempts
+                        .record_error(payload.item_id, &fetch_error, started.elapsed(), &timings)
+                        .await?;
+                    ItemEventRepository::new(pool)
+                        .record(
+                            payload.item_id,
+                            ItemEventKind::FetchFailed,
+                            Some(&fetch_error.to_string()),
+                        )
+                        .await?;
 
-                    // Could optionally update item status to indicate permanent failure
-                    // For now, just let the job be marked as failed
-                    anyhow::bail!("Permanent fetch error: {}", fetch_error);
+                    // Sites that refuse us won't change their mind on retry;
+                    // surface it in the report of the import the item came from
+                    if let FetchError::Http { status, .. } = &fetch_error
+                        && matches!(status.as_u16(), 401 | 403 | 451)
+                    {
+                        ImportRepository::new(pool)
+                            .record_fetch_forbidden(payload.item_id, &fetch_error.to_string())
+                            .await?;
+                    }
+
+                    if fetch_error.should_retry() {
+                        Self::set_state(pool, payload.item_id, ProcessingState::FetchFailed)
+                            .await?;
+                        // Return error to trigger retry by job runner
+                        anyhow::bail!("Retryable fetch error: {}", fetch_error);
+                    } else {
+                        // Mark as permanent failure - don't retry
+                        warn!(
+                            "Permanent failure for item {}: {}",
+                            payload.item_id, fetch_error
+                        );
+
+                        Self::set_state(pool, payload.item_id, ProcessingState::FailedPermanent)
+                            .await?;
+                        Self::mark_failed(pool, payload.item_id, &fetch_error.to_string()).await?;
+                        anyhow::bail!("Permanent fetch error: {}", fetch_error);
+                    }
                 }
             }
         }
+
+        if let Some(tag) = domain_pref.as_ref().and_then(|p| p.default_tag.as_deref()) {
+            let tags = TagRepository::new(pool);
+            let tag_id = tags.find_or_create(user_id, tag).await?;
+            tags.attach(payload.item_id, tag_id).await?;
+        }
+
+        info!("Successfully stored content for item {}", payload.item_id);
+        Ok(())
     }
 
     fn kind(&self) -> &'static str {
-        "fetch_page"
+        FETCH_PAGE_JOB_KIND
     }
 }
 
+/// Checksum of a page's raw bytes, as stored in `contents.checksum`
+fn content_checksum(response: &PageResponse) -> String {
+    format!("{:x}", md5::compute(response.body_raw.as_ref()))
+}
+
 impl FetchPageJobHandler {
     pub fn new() -> Self {
-        Self
+        Self::with_config(FetchPageConfig::default())
+    }
+
+    pub fn with_config(config: FetchPageConfig) -> Self {
+        Self { config }
+    }
+
+    /// Persist a freshly fetched page: raw HTML, fetched status, cache
+    /// validators and the extracted content. Returns what the extractor made
+    /// of the page.
+    pub(crate) async fn store_page(
+        pool: &PgPool,
+        item_id: Uuid,
+        response: &PageResponse,
+        domain_pref: Option<&DomainPref>,
+        policy: &SanitizePolicy,
+    ) -> anyhow::Result<Extraction> {
+        Self::store_raw(pool, item_id, response, &content_checksum(response)).await?;
+
+        let extraction = extractor::extract_with_policy(response, policy).await;
+        Self::store_extraction(pool, item_id, extraction.clone(), domain_pref).await?;
+        if extraction.is_ok() {
+            Self::share_document(pool, item_id, response).await?;
+            Self::record_site(pool, item_id, response).await?;
+        }
+        Ok(extraction)
+    }
+
+    /// Persist a page fetched and extracted earlier for another item. The
+    /// item still gets its own content row.
+    async fn store_cached(
+        pool: &PgPool,
+        item_id: Uuid,
+        cached: &CachedFetch,
+        domain_pref: Option<&DomainPref>,
+    ) -> anyhow::Result<()> {
+        let response = cached.page_response()?;
+        Self::store_raw(pool, item_id, &response, &cached.checksum).await?;
+        Self::store_extraction(pool, item_id, cached.extraction.0.clone(), domain_pref).await?;
+        if cached.extraction.0.is_ok() {
+            Self::share_document(pool, item_id, &response).await?;
+            Self::record_site(pool, item_id, &response).await?;
+        }
+        Ok(())
+    }
+
+    /// Note the site's name and feed from the page, and queue a fetch of
+    /// its icon using the one the page links to
+    async fn record_site(
+        pool: &PgPool,
+        item_id: Uuid,
+        response: &PageResponse,
+    ) -> anyhow::Result<()> {
+        let metadata = page_metadata(
+            &Html::parse_document(&response.body_utf8),
+            &response.url_final,
+        );
+        SiteRepository::new(pool)
+            .record_for_item(
+                item_id,
+                metadata.site_name.as_deref(),
+                metadata.feed.as_ref().map(url::Url::as_str),
+            )
+            .await?;
+        stage_site_icon(pool, &response.url_final, metadata.icon.as_ref()).await
+    }
+
+    /// Record whether the extracted page looks like adult content. Rejected
+    /// extractions leave the flag as it was.
+    pub(crate) async fn flag_nsfw(
+        pool: &PgPool,
+        item_id: Uuid,
+        raw_html: &str,
+        extraction: &Extraction,
+    ) -> anyhow::Result<()> {
+        let Ok(extracted) = extraction else {
+            return Ok(());
+        };
+
+        let nsfw = nsfw::looks_nsfw(raw_html, extracted);
+        if nsfw {
+            info!("Flagging item {} as likely NSFW", item_id);
+        }
+        sqlx::query!("UPDATE items SET nsfw = $2 WHERE id = $1", item_id, nsfw)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Hand the item's extracted content over to the shared document for the
+    /// page's final URL, if the owner allows sharing
+    async fn share_document(
+        pool: &PgPool,
+        item_id: Uuid,
+        response: &PageResponse,
+    ) -> anyhow::Result<()> {
+        if let Some(hash) = url_hash(response.url_final.as_str())
+            && DocumentRepository::new(pool).share(item_id, &hash).await?
+        {
+            info!("Item {} now reads from the shared document store", item_id);
+            ItemEventRepository::new(pool)
+                .record(item_id, ItemEventKind::Shared, None)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Store the raw page, mark the item fetched and remember its cache
+    /// validators
+    async fn store_raw(
+        pool: &PgPool,
+        item_id: Uuid,
+        response: &PageResponse,
+        checksum: &str,
+    ) -> anyhow::Result<()> {
+        ContentRepository::new(pool)
+            .upsert_raw(item_id, &response.body_utf8, checksum, &response.headers)
+            .await?;
+
+        // A first fetch, or one after a failure, marks the item fetched; a
+        // refresh leaves items being read or already archived as they are.
+        // Either way any earlier failure is forgotten.
+        sqlx::query!(
+            r#"
+            UPDATE items
+            SET status = CASE WHEN status IN ('pending', 'failed') THEN 'fetched' ELSE status END,
+                last_error = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+            item_id
+        )
+        .execute(pool)
+        .await?;
+
+        // Remember validators so later refreshes can be conditional
+        let validators = CacheValidators::from_headers(&response.headers);
+        sqlx::query!(
+            "UPDATE items SET http_etag = $2, http_last_modified = $3 WHERE id = $1",
+            item_id,
+            validators.etag,
+            validators.last_modified
+        )
+        .execute(pool)
+        .await?;
+
+        Self::set_state(pool, item_id, ProcessingState::Extracting).await
+    }
+
+    /// Why the instance's domain rules refuse `url`, if they do
+    async fn blocked_reason(pool: &PgPool, url: &str) -> anyhow::Result<Option<String>> {
+        let Some(host) = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+        else {
+            return Ok(None);
+        };
+        DomainRulesRepository::new(pool).blocked_reason(&host).await
+    }
+
+    /// Give up on an item whose domain is blocked; retrying won't help
+    async fn refuse_blocked(pool: &PgPool, item_id: Uuid, detail: &str) -> anyhow::Result<()> {
+        warn!("Refusing to fetch item {}: {}", item_id, detail);
+        ItemEventRepository::new(pool)
+            .record(item_id, ItemEventKind::FetchFailed, Some(detail))
+            .await?;
+        Self::set_state(pool, item_id, ProcessingState::FailedPermanent).await?;
+        Self::mark_failed(pool, item_id, detail).await?;
+        anyhow::bail!("Blocked domain: {}", detail);
+    }
+
+    /// Remember why the item's page can't be fetched, and mark the item
+    /// failed unless an earlier fetch already got it
+    async fn mark_failed(pool: &PgPool, item_id: Uuid, error: &str) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE items
+            SET status = CASE WHEN status = 'pending' THEN 'failed' ELSE status END,
+                last_error = $2,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+            item_id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a processing state transition, logging (rather than failing the
+    /// job) when the item's current state doesn't allow it.
+    async fn set_state(pool: &PgPool, item_id: Uuid, state: ProcessingState) -> anyhow::Result<()> {
+        if !ItemStateRepository::new(pool)
+            .transition(item_id, state)
+            .await?
+        {
+            warn!(
+                "Item {} cannot move to processing state {}",
+                item_id,
+                state.as_str()
+            );
+        }
+        Ok(())
+    }
+
+    /// Persist the extractor's result: the cleaned content, filling in the
+    /// item's title when it's still unknown, or the reason it was rejected.
+    async fn store_extraction(
+        pool: &PgPool,
+        item_id: Uuid,
+        extraction: Extraction,
+        domain_pref: Option<&DomainPref>,
+    ) -> anyhow::Result<()> {
+        let mut extracted = match extraction {
+            Ok(extracted) => extracted,
+            Err(reason) => {
+                warn!(
+                    "Extractor rejected content for item {}: {}",
+                    item_id,
+                    reason.as_str()
+                );
+                sqlx::query!(
+                    "UPDATE items SET extraction_error = $2, updated_at = NOW() WHERE id = $1",
+                    item_id,
+                    reason.as_str()
+                )
+                .execute(pool)
+                .await?;
+                ItemEventRepository::new(pool)
+                    .record(
+                        item_id,
+                        ItemEventKind::ExtractionFailed,
+                        Some(reason.as_str()),
+                    )
+                    .await?;
+                Self::set_state(pool, item_id, ProcessingState::FailedPermanent).await?;
+                return Ok(());
+            }
+        };
+
+        if domain_pref.is_some_and(|pref| pref.skip_images) {
+            extracted.html = strip_images(&extracted.html);
+        }
+
+        ContentRepository::new(pool)
+            .upsert_content(
+                item_id,
+                &extracted.html,
+                &extracted.text,
+                extracted.language.as_deref(),
+                Utc::now(),
+            )
+            .await?;
+
+        sqlx::query!(
+            "UPDATE contents SET outline = $2 WHERE item_id = $1",
+            item_id,
+            Json(&extracted.outline) as _
+        )
+        .execute(pool)
+        .await?;
+
+        LinkRepository::new(pool)
+            .record(
+                item_id,
+                &extractor::outbound_links(&extracted.html, &extracted.url),
+            )
+            .await?;
+
+        // Word count is kept by the database from the stored text; the item
+        // takes a copy, and one of the language, so lists and filters don't
+        // need to join contents
+        sqlx::query!(
+            r#"
+            UPDATE items i
+            SET title = COALESCE(i.title, $2),
+                extraction_error = NULL,
+                word_count = c.word_count,
+                reading_time_minutes = CEIL(c.word_count / $3::float8)::int,
+                lang = lower(c.lang)
+            FROM contents c
+            WHERE i.id = $1 AND c.item_id = i.id
+            "#,
+            item_id,
+            &extracted.title,
+            WORDS_PER_MINUTE
+        )
+        .execute(pool)
+        .await?;
+
+        ItemEventRepository::new(pool)
+            .record(item_id, ItemEventKind::Extracted, None)
+            .await?;
+
+        JobRepository::enqueue(
+            pool,
+            SUMMARIZE_CONTENT_JOB_KIND,
+            serde_json::to_value(SummarizeContentPayload { item_id })?,
+            None,
+            Some(5),
+        )
+        .await?;
+        JobRepository::enqueue(
+            pool,
+            EMBED_CONTENT_JOB_KIND,
+            serde_json::to_value(EmbedContentPayload { item_id })?,
+            None,
+            Some(5),
+        )
+        .await?;
+        JobRepository::enqueue(
+            pool,
+            CLASSIFY_TOPICS_JOB_KIND,
+            serde_json::to_value(ClassifyTopicsPayload { item_id })?,
+            None,
+            Some(5),
+        )
+        .await?;
+        Self::stage_translation(pool, item_id, extracted.language.as_deref()).await?;
+
+        Self::set_state(pool, item_id, ProcessingState::Ready).await
+    }
+
+    /// Queue a translation into the owner's chosen language, when they have
+    /// chosen one and the page is written in another
+    async fn stage_translation(
+        pool: &PgPool,
+        item_id: Uuid,
+        language: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let prefs: Option<Json<UserPreferences>> = sqlx::query_scalar!(
+            r#"
+            SELECT u.prefs AS "prefs: Json<UserPreferences>"
+            FROM items i JOIN users u ON u.id = i.user_id
+            WHERE i.id = $1
+            "#,
+            item_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        let Some(lang) = prefs.and_then(|Json(prefs)| prefs.translate_to) else {
+            return Ok(());
+        };
+        if language == Some(lang.as_str()) {
+            return Ok(());
+        }
+
+        JobRepository::enqueue(
+            pool,
+            TRANSLATE_CONTENT_JOB_KIND,
+            serde_json::to_value(TranslateContentPayload { item_id, lang })?,
+            None,
+            Some(5),
+        )
+        .await?;
+        Ok(())
     }
 }
 
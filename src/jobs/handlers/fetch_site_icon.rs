@@ -0,0 +1,164 @@
+use crate::{
+    fetcher::{Deadline, fetch_image},
+    jobs::{JobRepository, handler::JobHandler},
+    repositories::SiteRepository,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{Span, debug, instrument};
+use url::Url;
+
+pub const FETCH_SITE_ICON_JOB_KIND: &str = "fetch_site_icon";
+
+/// Largest icon kept; real favicons are a few kilobytes
+pub const SITE_ICON_MAX_BYTES: usize = 100 * 1024;
+
+/// How long each candidate icon may take to download
+const SITE_ICON_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FetchSiteIconPayload {
+    pub host: String,
+    /// A page of the site, whose origin `/favicon.ico` is tried last
+    pub page_url: String,
+    /// The icon the page links to, tried first
+    #[serde(default)]
+    pub icon_url: Option<String>,
+}
+
+/// Queue a fetch of the icon for the site `page_url` is on, unless it was
+/// fetched recently or is already queued
+pub async fn stage_site_icon(
+    pool: &PgPool,
+    page_url: &Url,
+    icon_url: Option<&Url>,
+) -> anyhow::Result<()> {
+    let Some(host) = page_url.host_str() else {
+        return Ok(());
+    };
+    if !SiteRepository::new(pool).claim_icon(host).await? {
+        return Ok(());
+    }
+
+    JobRepository::enqueue(
+        pool,
+        FETCH_SITE_ICON_JOB_KIND,
+        serde_json::to_value(FetchSiteIconPayload {
+            host: host.to_string(),
+            page_url: page_url.to_string(),
+            icon_url: icon_url.map(Url::to_string),
+        })?,
+        None,
+        Some(1),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Downloads a site's icon: the one its pages link to, else `/favicon.ico`.
+/// A site without a usable icon is left without one until it's looked at
+/// again; failures are logged rather than retried, since nothing but the
+/// item list's decoration depends on them.
+#[derive(Clone)]
+pub struct FetchSiteIconJobHandler;
+
+#[async_trait]
+impl JobHandler for FetchSiteIconJobHandler {
+    #[instrument(skip(self, pool, span, deadline), fields(host))]
+    async fn run(
+        &self,
+        payload: serde_json::Value,
+        pool: &PgPool,
+        span: Span,
+        deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let payload: FetchSiteIconPayload = serde_json::from_value(payload)?;
+        span.record("host", tracing::field::display(&payload.host));
+
+        for candidate in Self::candidates(&payload) {
+            match fetch_image(
+                &candidate,
+                SITE_ICON_MAX_BYTES,
+                &deadline.child(SITE_ICON_FETCH_TIMEOUT),
+            )
+            .await
+            {
+                Ok(image) => {
+                    SiteRepository::new(pool)
+                        .store_icon(
+                            &payload.host,
+                            image.url_final.as_str(),
+                            &image.content_type,
+                            &image.body,
+                        )
+                        .await?;
+                    return Ok(());
+                }
+                Err(e) => debug!("Icon {} for {} unusable: {}", candidate, payload.host, e),
+            }
+        }
+
+        debug!("No icon found for {}", payload.host);
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        FETCH_SITE_ICON_JOB_KIND
+    }
+}
+
+impl FetchSiteIconJobHandler {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// URLs to try, in order, without repeats
+    fn candidates(payload: &FetchSiteIconPayload) -> Vec<String> {
+        let mut candidates: Vec<String> = payload.icon_url.iter().cloned().collect();
+        if let Ok(page_url) = Url::parse(&payload.page_url)
+            && let Ok(favicon) = page_url.join("/favicon.ico")
+            && !candidates.contains(&favicon.to_string())
+        {
+            candidates.push(favicon.to_string());
+        }
+        candidates
+    }
+}
+
+impl Default for FetchSiteIconJobHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_try_the_linked_icon_first() {
+        let payload = FetchSiteIconPayload {
+            host: "example.com".to_string(),
+            page_url: "https://example.com/posts/1?x=y".to_string(),
+            icon_url: Some("https://cdn.example.com/icon.png".to_string()),
+        };
+        assert_eq!(
+            FetchSiteIconJobHandler::candidates(&payload),
+            vec![
+                "https://cdn.example.com/icon.png",
+                "https://example.com/favicon.ico"
+            ]
+        );
+
+        let payload = FetchSiteIconPayload {
+            icon_url: Some("https://example.com/favicon.ico".to_string()),
+            ..payload
+        };
+        assert_eq!(
+            FetchSiteIconJobHandler::candidates(&payload),
+            vec!["https://example.com/favicon.ico"]
+        );
+    }
+}
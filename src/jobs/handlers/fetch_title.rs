@@ -0,0 +1,163 @@
+use crate::{
+    extractor::page_metadata,
+    fetcher::{Deadline, fetch_prefix},
+    jobs::{FETCH_PAGE_JOB_KIND, FetchPagePayload, Outbox, handler::JobHandler},
+    repositories::{DomainRulesRepository, SiteRepository},
+};
+use async_trait::async_trait;
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgConnection, PgPool};
+use std::time::Duration;
+use tracing::{Span, debug, info, instrument};
+use uuid::Uuid;
+
+pub const FETCH_TITLE_JOB_KIND: &str = "fetch_title";
+
+/// Most of a page read for its title; enough for the `<head>` of nearly
+/// every page
+pub const TITLE_FETCH_MAX_BYTES: usize = 64 * 1024;
+
+/// How long the title fetch may take. Past this the full fetch is likely to
+/// finish first anyway.
+const TITLE_FETCH_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FetchTitlePayload {
+    pub item_id: Uuid,
+}
+
+/// Stage the jobs for a newly saved item: a quick title fetch so the item
+/// list shows a real title within a second or two, then the full fetch.
+/// The title job is staged first so it's first in line to be picked up.
+pub async fn stage_fetch_jobs(conn: &mut PgConnection, item_id: Uuid) -> anyhow::Result<()> {
+    Outbox::enqueue(
+        conn,
+        FETCH_TITLE_JOB_KIND,
+        serde_json::to_value(FetchTitlePayload { item_id })?,
+        None,
+    )
+    .await?;
+    Outbox::enqueue(
+        conn,
+        FETCH_PAGE_JOB_KIND,
+        serde_json::to_value(FetchPagePayload {
+            item_id,
+            refetch: false,
+        })?,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Fills in an item's title, and notes what its site is called, from the
+/// start of its page, ahead of the full fetch. Only the first
+/// [`TITLE_FETCH_MAX_BYTES`] are read and nothing else about the item
+/// changes. A failure is logged and dropped
+/// rather than retried: the full fetch sets the title too, and reports any
+/// real problem with the page.
+#[derive(Clone)]
+pub struct FetchTitleJobHandler;
+
+#[async_trait]
+impl JobHandler for FetchTitleJobHandler {
+    #[instrument(skip(self, pool, span, deadline), fields(item_id))]
+    async fn run(
+        &self,
+        payload: serde_json::Value,
+        pool: &PgPool,
+        span: Span,
+        deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let payload: FetchTitlePayload = serde_json::from_value(payload)?;
+        span.record("item_id", tracing::field::display(payload.item_id));
+
+        let item = sqlx::query!(
+            "SELECT url, title FROM items WHERE id = $1",
+            payload.item_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(item) = item else {
+            info!("Skipping title fetch for deleted item {}", payload.item_id);
+            return Ok(());
+        };
+        let url = item.url;
+        // Given by the user, or the full fetch got there first
+        if item.title.is_some() {
+            return Ok(());
+        }
+        // The full fetch records why the item was refused
+        if Self::blocked(pool, &url).await? {
+            return Ok(());
+        }
+
+        let page = match fetch_prefix(
+            &url,
+            TITLE_FETCH_MAX_BYTES,
+            &deadline.child(TITLE_FETCH_TIMEOUT),
+        )
+        .await
+        {
+            Ok(page) => page,
+            Err(e) => {
+                debug!("Title fetch for item {} failed: {}", payload.item_id, e);
+                return Ok(());
+            }
+        };
+
+        let metadata = page_metadata(&Html::parse_document(&page.body_utf8), &page.url_final);
+        if metadata.title.is_none() {
+            debug!("No title found for item {}", payload.item_id);
+        } else {
+            sqlx::query!(
+                "UPDATE items SET title = COALESCE(title, $2) WHERE id = $1",
+                payload.item_id,
+                metadata.title.as_deref()
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        SiteRepository::new(pool)
+            .record_for_item(
+                payload.item_id,
+                metadata.site_name.as_deref(),
+                metadata.feed.as_ref().map(url::Url::as_str),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        FETCH_TITLE_JOB_KIND
+    }
+}
+
+impl FetchTitleJobHandler {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn blocked(pool: &PgPool, url: &str) -> anyhow::Result<bool> {
+        let Some(host) = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+        else {
+            return Ok(false);
+        };
+        Ok(DomainRulesRepository::new(pool)
+            .blocked_reason(&host)
+            .await?
+            .is_some())
+    }
+}
+
+impl Default for FetchTitleJobHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{
+    entities::{ImportFailureReason, OperationState},
+    fetcher::{Deadline, UrlPolicy},
+    jobs::{handler::JobHandler, stage_fetch_jobs},
+    repositories::{DomainRulesRepository, ImportRepository, OperationRepository},
+    urlnorm::canonicalize,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use tracing::{Span, info, instrument};
+use url::Url;
+use uuid::Uuid;
+
+pub const IMPORT_URLS_JOB_KIND: &str = "import_urls";
+
+/// Most URLs accepted in one import
+pub const MAX_IMPORT_URLS: usize = 10_000;
+
+/// Longest URL an import will save, matching item creation
+const MAX_URL_LEN: usize = 2048;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportUrlsPayload {
+    pub operation_id: Uuid,
+    pub urls: Vec<String>,
+}
+
+/// Saves each URL of an import as an item and queues its fetch. Rows that
+/// can't be saved are recorded against the import's operation for the error
+/// report. Each row commits on its own and bumps `processed`, so a retried
+/// job picks up after the last row it finished.
+#[derive(Clone)]
+pub struct ImportUrlsJobHandler;
+
+#[async_trait]
+impl JobHandler for ImportUrlsJobHandler {
+    #[instrument(skip(self, pool, span, deadline), fields(operation_id))]
+    async fn run(
+        &self,
+        payload: serde_json::Value,
+        pool: &PgPool,
+        span: Span,
+        deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let payload: ImportUrlsPayload = serde_json::from_value(payload)?;
+        span.record(
+            "operation_id",
+            tracing::field::display(payload.operation_id),
+        );
+
+        let operations = OperationRepository::new(pool);
+        let Some(operation) = operations.find(payload.operation_id).await? else {
+            info!(
+                "Skipping import for deleted operation {}",
+                payload.operation_id
+            );
+            return Ok(());
+        };
+        if matches!(
+            operation.state,
+            OperationState::Succeeded | OperationState::Failed
+        ) {
+            return Ok(());
+        }
+        operations
+            .start(operation.id, Some(payload.urls.len() as i32))
+            .await?;
+
+        let imports = ImportRepository::new(pool);
+        let already_processed = operation.processed.max(0) as usize;
+        let mut seen = HashSet::new();
+        let rules = DomainRulesRepository::new(pool);
+        // Domain rule verdicts by host, as lists tend to repeat sites
+        let mut verdicts: HashMap<String, Option<String>> = HashMap::new();
+
+        for (index, raw) in payload.urls.iter().enumerate() {
+            let row_number = index as i32 + 1;
+            let classified = classify(raw, &mut seen);
+            if index < already_processed {
+                continue;
+            }
+            // Rows commit one at a time, so a retry resumes from here
+            if deadline.is_expired() {
+                anyhow::bail!(
+                    "Import {} stopped at row {} as its lease ran out",
+                    operation.id,
+                    row_number
+                );
+            }
+
+            let classified = match classified {
+                Ok(url) => {
+                    let host = Url::parse(&url)?.host_str().unwrap_or_default().to_string();
+                    let blocked = match verdicts.get(&host) {
+                        Some(verdict) => verdict.clone(),
+                        None => {
+                            let verdict = rules.blocked_reason(&host).await?;
+                            verdicts.insert(host, verdict.clone());
+                            verdict
+                        }
+                    };
+                    match blocked {
+                        Some(detail) => Err((ImportFailureReason::BlockedDomain, detail)),
+                        None => Ok(url),
+                    }
+                }
+                Err(failure) => Err(failure),
+            };
+
+            match classified {
+                Ok(url) => {
+                    let mut tx = pool.begin().await?;
+                    let created = imports
+                        .create_item_in(&mut tx, operation.id, operation.user_id, row_number, &url)
+                        .await?;
+                    match created {
+                        Some(item_id) => {
+                            stage_fetch_jobs(&mut tx, item_id).await?;
+                            tx.commit().await?;
+                        }
+                        None => {
+                            tx.rollback().await?;
+                            imports
+                                .record_row_failure(
+                                    operation.id,
+                                    row_number,
+                                    raw,
+                                    ImportFailureReason::Duplicate,
+                                    Some("already saved"),
+                                )
+                                .await?;
+                        }
+                    }
+                }
+                Err((reason, detail)) => {
+                    imports
+                        .record_row_failure(operation.id, row_number, raw, reason, Some(&detail))
+                        .await?;
+                }
+            }
+        }
+
+        operations
+            .finish(operation.id, OperationState::Succeeded)
+            .await?;
+        info!(
+            "Imported {} URLs for operation {}",
+            payload.urls.len(),
+            operation.id
+        );
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        IMPORT_URLS_JOB_KIND
+    }
+}
+
+impl ImportUrlsJobHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ImportUrlsJobHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decide what to do with one row: the canonical URL to save, as item
+/// creation would, or why it can't be.
+/// `seen` collects the pages earlier rows resolved to, so repeats within the
+/// list count as duplicates too. Pages saved before the import are caught
+/// when the row is saved.
+fn classify(
+    raw: &str,
+    seen: &mut HashSet<String>,
+) -> Result<String, (ImportFailureReason, String)> {
+    let url = raw.trim();
+    validate_url(url).map_err(|detail| (ImportFailureReason::BadUrl, detail))?;
+
+    // Replayed for rows a resumed job already saved, so it still remembers
+    // their pages
+    let key = canonicalize(url).unwrap_or_else(|| url.to_string());
+    if !seen.insert(key.clone()) {
+        return Err((
+            ImportFailureReason::Duplicate,
+            "repeated earlier in this import".to_string(),
+        ));
+    }
+    Ok(key)
+}
+
+fn validate_url(url: &str) -> Result<(), String> {
+    if url.is_empty() {
+        return Err("empty URL".to_string());
+    }
+    if url.len() > MAX_URL_LEN {
+        return Err("URL too long".to_string());
+    }
+    UrlPolicy::global()
+        .parse(url)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_bad_urls() {
+        let mut seen = HashSet::new();
+        for raw in [
+            "",
+            "not a url",
+            "ftp://example.com/file",
+            "mailto:a@b.c",
+            "https://user:pw@example.com/",
+        ] {
+            let (reason, _) = classify(raw, &mut seen).unwrap_err();
+            assert_eq!(reason, ImportFailureReason::BadUrl);
+        }
+        assert!(seen.is_empty());
+    }
+
+    #[test]
+    fn test_classify_duplicates() {
+        let mut seen = HashSet::new();
+
+        assert_eq!(
+            classify(" https://example.com/a ", &mut seen),
+            Ok("https://example.com/a".to_string())
+        );
+        // The same page with tracking parameters
+        let (reason, detail) =
+            classify("https://example.com/a?utm_source=rss", &mut seen).unwrap_err();
+        assert_eq!(reason, ImportFailureReason::Duplicate);
+        assert_eq!(detail, "repeated earlier in this import");
+
+        // Saved under the canonical URL
+        assert_eq!(
+            classify(
+                "https://Example.com/b?utm_medium=email&ref=home#top",
+                &mut seen
+            ),
+            Ok("https://example.com/b?ref=home".to_string())
+        );
+    }
+}
@@ -1,5 +1,35 @@
+pub mod classify_topics;
+pub mod compress_html;
+pub mod data_package;
+pub mod embed_content;
 pub mod example;
 pub mod fetch_page;
+pub mod fetch_site_icon;
+pub mod fetch_title;
+pub mod import_urls;
+pub mod queue_health;
+pub mod quota_check;
+pub mod refresh_content;
+pub mod send_digest;
+pub mod summarize_content;
+#[cfg(feature = "telemetry")]
+pub mod telemetry_report;
+pub mod translate_content;
 
+pub use classify_topics::*;
+pub use compress_html::*;
+pub use data_package::*;
+pub use embed_content::*;
 pub use example::*;
 pub use fetch_page::*;
+pub use fetch_site_icon::*;
+pub use fetch_title::*;
+pub use import_urls::*;
+pub use queue_health::*;
+pub use quota_check::*;
+pub use refresh_content::*;
+pub use send_digest::*;
+pub use summarize_content::*;
+#[cfg(feature = "telemetry")]
+pub use telemetry_report::*;
+pub use translate_content::*;
@@ -0,0 +1,227 @@
+use crate::{
+    fetcher::Deadline,
+    jobs::{JobRepository, handler::JobHandler},
+    repositories::{NotificationRepository, ReadingTime, item::WORDS_PER_MINUTE},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{FromRow, PgPool};
+use tracing::{Span, info};
+use uuid::Uuid;
+
+pub const QUEUE_HEALTH_JOB_KIND: &str = "queue_health";
+pub const QUEUE_NUDGE_EVENT: &str = "queue_nudge";
+
+/// Most quick reads suggested in one nudge
+const MAX_QUICK_READS: i64 = 3;
+
+/// Queue health configuration
+#[derive(Clone, Debug)]
+pub struct QueueHealthConfig {
+    /// Seconds between checks, which is also the period saves and reads are
+    /// counted over
+    pub check_interval_secs: i64,
+    /// Also flag nudges for email delivery
+    pub email_notifications: bool,
+}
+
+impl Default for QueueHealthConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_secs: 7 * 24 * 60 * 60, // weekly
+            email_notifications: false,
+        }
+    }
+}
+
+/// How a user's unread queue changed over one period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueHealth {
+    /// Items not yet archived
+    pub unread: i64,
+    /// Items saved during the period
+    pub saved: i64,
+    /// Items archived during the period
+    pub read: i64,
+}
+
+impl QueueHealth {
+    /// Whether the queue grew because more was saved than read
+    pub fn is_growing(&self) -> bool {
+        self.unread > 0 && self.saved > self.read
+    }
+}
+
+/// An unread item suggested in a nudge
+#[derive(Debug, Clone, Serialize, FromRow)]
+struct Suggestion {
+    id: Uuid,
+    title: Option<String>,
+    url: String,
+    created_at: DateTime<Utc>,
+    reading_time_minutes: Option<i32>,
+}
+
+/// Periodic job comparing how fast each user's unread queue grows with how
+/// fast they read it, and emitting a `queue_nudge` notification suggesting
+/// what to read next when it's growing. Users can opt out with the
+/// `queue_nudges` preference.
+#[derive(Clone)]
+pub struct QueueHealthJobHandler {
+    config: QueueHealthConfig,
+}
+
+#[async_trait]
+impl JobHandler for QueueHealthJobHandler {
+    async fn run(
+        &self,
+        _payload: serde_json::Value,
+        pool: &PgPool,
+        _span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let since = Utc::now() - Duration::seconds(self.config.check_interval_secs);
+
+        // Users already nudged this period are left out, so a retried run
+        // doesn't notify twice
+        let users = sqlx::query!(
+            r#"
+            SELECT
+                u.id,
+                (SELECT COUNT(*) FROM items i
+                 WHERE i.user_id = u.id AND i.status <> 'archived') AS "unread!",
+                (SELECT COUNT(*) FROM items i
+                 WHERE i.user_id = u.id AND i.created_at > $1) AS "saved!",
+                (SELECT COUNT(*) FROM item_events e
+                 JOIN items i ON i.id = e.item_id
+                 WHERE i.user_id = u.id AND e.kind = 'archived' AND e.created_at > $1) AS "read!"
+            FROM users u
+            WHERE COALESCE((u.prefs->>'queue_nudges')::boolean, TRUE)
+              AND NOT EXISTS (
+                  SELECT 1 FROM notifications n
+                  WHERE n.user_id = u.id AND n.kind = $2 AND n.created_at > $1
+              )
+            "#,
+            since,
+            QUEUE_NUDGE_EVENT
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let notifications = NotificationRepository::new(pool);
+        let mut nudges = 0;
+
+        for user in users {
+            let user_id = user.id;
+            let health = QueueHealth {
+                unread: user.unread,
+                saved: user.saved,
+                read: user.read,
+            };
+            if !health.is_growing() {
+                continue;
+            }
+
+            let oldest_unread = suggestions(pool, user_id, None, 1).await?.pop();
+            let quick_reads =
+                suggestions(pool, user_id, Some(ReadingTime::QUICK), MAX_QUICK_READS).await?;
+
+            notifications
+                .create(
+                    user_id,
+                    QUEUE_NUDGE_EVENT,
+                    json!({
+                        "unread": health.unread,
+                        "saved": health.saved,
+                        "read": health.read,
+                        "since": since,
+                        "oldest_unread": oldest_unread,
+                        "quick_reads": quick_reads,
+                    }),
+                    self.config.email_notifications,
+                )
+                .await?;
+            nudges += 1;
+        }
+
+        info!("Queue health check complete, {} nudges emitted", nudges);
+
+        JobRepository::enqueue_if_absent(
+            pool,
+            QUEUE_HEALTH_JOB_KIND,
+            json!({}),
+            Some(Utc::now() + Duration::seconds(self.config.check_interval_secs)),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        QUEUE_HEALTH_JOB_KIND
+    }
+}
+
+impl QueueHealthJobHandler {
+    pub fn new(config: QueueHealthConfig) -> Self {
+        Self { config }
+    }
+}
+
+/// The user's oldest unread items, only those within `reading_time` when
+/// given
+async fn suggestions(
+    pool: &PgPool,
+    user_id: Uuid,
+    reading_time: Option<ReadingTime>,
+    limit: i64,
+) -> anyhow::Result<Vec<Suggestion>> {
+    let suggestions = sqlx::query_as!(
+        Suggestion,
+        r#"
+        SELECT i.id, i.title, i.url, i.created_at,
+               CEIL(w.words / $2::float8)::int AS reading_time_minutes
+        FROM items i
+        LEFT JOIN contents c ON c.item_id = i.id
+        LEFT JOIN documents d ON d.id = i.document_id
+        CROSS JOIN LATERAL (
+            SELECT COALESCE(c.word_count, d.word_count) AS words
+        ) w
+        WHERE i.user_id = $1
+          AND i.status <> 'archived'
+          AND (i.snoozed_until IS NULL OR i.snoozed_until <= NOW())
+          AND ($3::bigint IS NULL OR w.words <= $3)
+        ORDER BY i.created_at, i.id
+        LIMIT $4
+        "#,
+        user_id,
+        WORDS_PER_MINUTE,
+        reading_time.and_then(ReadingTime::max_words),
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_health_is_growing() {
+        let health = |unread, saved, read| QueueHealth {
+            unread,
+            saved,
+            read,
+        };
+        assert!(health(10, 5, 2).is_growing());
+        assert!(!health(10, 2, 2).is_growing());
+        assert!(!health(10, 1, 4).is_growing());
+        // Everything saved was already read
+        assert!(!health(0, 3, 1).is_growing());
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    fetcher::Deadline,
+    jobs::{JobRepository, handler::JobHandler},
+    repositories::NotificationRepository,
+};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use tracing::{Span, info};
+use uuid::Uuid;
+
+pub const QUOTA_CHECK_JOB_KIND: &str = "quota_check";
+pub const QUOTA_WARNING_EVENT: &str = "quota_warning";
+
+/// Percent-of-quota thresholds that produce a warning, highest first
+const QUOTA_THRESHOLDS: [i16; 2] = [100, 80];
+
+/// Quota configuration
+#[derive(Clone, Debug)]
+pub struct QuotaConfig {
+    pub max_items: i64,
+    pub max_storage_bytes: i64,
+    /// Seconds between quota checks
+    pub check_interval_secs: i64,
+    /// Also flag warnings for email delivery
+    pub email_notifications: bool,
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        Self {
+            max_items: 10_000,
+            max_storage_bytes: 1024 * 1024 * 1024, // 1 GiB
+            check_interval_secs: 900,              // 15 minutes
+            email_notifications: false,
+        }
+    }
+}
+
+/// Highest threshold `used` has reached, or 0 below all of them.
+pub fn quota_level(used: i64, limit: i64) -> i16 {
+    if limit <= 0 {
+        return 0;
+    }
+
+    QUOTA_THRESHOLDS
+        .into_iter()
+        .find(|&threshold| used * 100 >= limit * i64::from(threshold))
+        .unwrap_or(0)
+}
+
+/// Periodic job comparing each user's usage against their quotas and emitting
+/// a `quota_warning` notification whenever a threshold is newly crossed
+#[derive(Clone)]
+pub struct QuotaCheckJobHandler {
+    config: QuotaConfig,
+}
+
+#[async_trait]
+impl JobHandler for QuotaCheckJobHandler {
+    async fn run(
+        &self,
+        _payload: serde_json::Value,
+        pool: &PgPool,
+        _span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let usage = sqlx::query!(
+            r#"
+            SELECT
+                u.id,
+                (SELECT COUNT(*) FROM items i WHERE i.user_id = u.id) AS "items!",
+                COALESCE((
+                    SELECT SUM(
+                        COALESCE(octet_length(c.raw_html), 0)
+                        + COALESCE(octet_length(c.raw_html_zst), 0)
+                        + COALESCE(octet_length(c.raw_text), 0)
+                        + COALESCE(octet_length(c.clean_html), 0)
+                        + COALESCE(octet_length(c.clean_html_zst), 0)
+                        + COALESCE(octet_length(c.clean_text), 0)
+                    )
+                    FROM contents c
+                    JOIN items i ON i.id = c.item_id
+                    WHERE i.user_id = u.id
+                ), 0)::BIGINT AS "storage_bytes!"
+            FROM users u
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let levels: HashMap<(Uuid, String), i16> =
+            sqlx::query!("SELECT user_id, resource, level FROM quota_levels")
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(|row| ((row.user_id, row.resource), row.level))
+                .collect();
+
+        let notifications = NotificationRepository::new(pool);
+        let mut warnings = 0;
+
+        for row in usage {
+            let user_id = row.id;
+            for (resource, used, limit) in [
+                ("items", row.items, self.config.max_items),
+                ("storage", row.storage_bytes, self.config.max_storage_bytes),
+            ] {
+                let level = quota_level(used, limit);
+                let previous = levels
+                    .get(&(user_id, resource.to_string()))
+                    .copied()
+                    .unwrap_or(0);
+
+                if level == previous {
+                    continue;
+                }
+
+                // Only upward crossings notify; dropping back re-arms the warning
+                if level > previous {
+                    notifications
+                        .create(
+                            user_id,
+                            QUOTA_WARNING_EVENT,
+                            json!({
+                                "resource": resource,
+                                "percent": level,
+                                "used": used,
+                                "limit": limit,
+                            }),
+                            self.config.email_notifications,
+                        )
+                        .await?;
+                    warnings += 1;
+                }
+
+                sqlx::query!(
+                    r#"
+                    INSERT INTO quota_levels (user_id, resource, level)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (user_id, resource)
+                    DO UPDATE SET level = EXCLUDED.level, updated_at = NOW()
+                    "#,
+                    user_id,
+                    resource,
+                    level
+                )
+                .execute(pool)
+                .await?;
+            }
+        }
+
+        info!("Quota check complete, {} warnings emitted", warnings);
+
+        JobRepository::enqueue_if_absent(
+            pool,
+            QUOTA_CHECK_JOB_KIND,
+            json!({}),
+            Some(Utc::now() + Duration::seconds(self.config.check_interval_secs)),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        QUOTA_CHECK_JOB_KIND
+    }
+}
+
+impl QuotaCheckJobHandler {
+    pub fn new(config: QuotaConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_level_thresholds() {
+        assert_eq!(quota_level(0, 100), 0);
+        assert_eq!(quota_level(79, 100), 0);
+        assert_eq!(quota_level(80, 100), 80);
+        assert_eq!(quota_level(99, 100), 80);
+        assert_eq!(quota_level(100, 100), 100);
+        assert_eq!(quota_level(250, 100), 100);
+    }
+
+    #[test]
+    fn test_quota_level_without_limit() {
+        assert_eq!(quota_level(1_000, 0), 0);
+    }
+}
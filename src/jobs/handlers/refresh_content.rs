@@ -0,0 +1,423 @@
+use crate::{
+    entities::ItemEventKind,
+    extractor::{SanitizePolicy, diff_text},
+    fetcher::{CacheValidators, Deadline, FetchOutcome, fetch_conditional_with_timings},
+    jobs::{FetchPageJobHandler, JobRepository, handler::JobHandler},
+    repositories::{
+        ContentRepository, DomainPrefsRepository, FetchAttemptRepository, ItemEventRepository,
+        NotificationRepository,
+    },
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use std::{collections::HashMap, time::Instant};
+use tracing::{Span, info, instrument, warn};
+use uuid::Uuid;
+
+pub const REFRESH_SCAN_JOB_KIND: &str = "refresh_scan";
+pub const REFRESH_ITEM_JOB_KIND: &str = "refresh_item";
+pub const CONTENT_CHANGED_EVENT: &str = "content_changed";
+
+/// Shortest and longest refresh interval a user may set on an item
+pub const MIN_REFRESH_INTERVAL_SECS: i64 = 60 * 60;
+pub const MAX_REFRESH_INTERVAL_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Living-document refresh configuration
+#[derive(Clone, Debug)]
+pub struct RefreshConfig {
+    /// Seconds between scans for due items
+    pub scan_interval_secs: i64,
+    /// Maximum number of due items considered per scan
+    pub batch_size: i64,
+    /// Maximum refreshes scheduled per domain per scan; the rest wait for the next scan
+    pub max_per_domain: usize,
+    /// Gap between refreshes of the same domain
+    pub domain_spacing_secs: i64,
+    /// Also email watchers when a page changes
+    pub email_notifications: bool,
+}
+
+impl Default for RefreshConfig {
+    fn default() -> Self {
+        Self {
+            scan_interval_secs: 300, // 5 minutes
+            batch_size: 200,
+            max_per_domain: 5,
+            domain_spacing_secs: 10,
+            email_notifications: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshItemPayload {
+    pub item_id: Uuid,
+}
+
+/// Spread due items over time so no domain gets more than `max_per_domain`
+/// requests per scan, each `domain_spacing_secs` apart. Items are expected
+/// oldest-due first; unscheduled items stay due for the next scan.
+pub fn plan_refreshes(
+    due: &[(Uuid, String)],
+    config: &RefreshConfig,
+    now: DateTime<Utc>,
+) -> Vec<(Uuid, DateTime<Utc>)> {
+    let mut per_domain: HashMap<String, usize> = HashMap::new();
+    let mut planned = Vec::new();
+
+    for (item_id, url) in due {
+        let domain = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+            .unwrap_or_default();
+
+        let slot = per_domain.entry(domain).or_default();
+        if *slot >= config.max_per_domain {
+            continue;
+        }
+
+        planned.push((
+            *item_id,
+            now + Duration::seconds(*slot as i64 * config.domain_spacing_secs),
+        ));
+        *slot += 1;
+    }
+
+    planned
+}
+
+/// Periodic job that enqueues a `refresh_item` job for every living document
+/// whose refresh is due
+#[derive(Clone)]
+pub struct RefreshScanJobHandler {
+    config: RefreshConfig,
+}
+
+#[async_trait]
+impl JobHandler for RefreshScanJobHandler {
+    async fn run(
+        &self,
+        _payload: serde_json::Value,
+        pool: &PgPool,
+        _span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let now = Utc::now();
+
+        let due: Vec<(Uuid, String)> = sqlx::query!(
+            r#"
+            SELECT id, url
+            FROM items
+            WHERE refresh_interval_secs IS NOT NULL
+              AND next_refresh_at <= $1
+            ORDER BY next_refresh_at
+            LIMIT $2
+            "#,
+            now,
+            self.config.batch_size
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.id, row.url))
+        .collect();
+
+        let planned = plan_refreshes(&due, &self.config, now);
+
+        for (item_id, run_at) in &planned {
+            JobRepository::enqueue(
+                pool,
+                REFRESH_ITEM_JOB_KIND,
+                serde_json::to_value(RefreshItemPayload { item_id: *item_id })?,
+                Some(*run_at),
+                Some(3),
+            )
+            .await?;
+
+            sqlx::query!(
+                r#"
+                UPDATE items
+                SET next_refresh_at = $2::timestamptz + make_interval(secs => refresh_interval_secs)
+                WHERE id = $1
+                "#,
+                item_id,
+                now
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        info!(
+            "Refresh scan scheduled {} of {} due items",
+            planned.len(),
+            due.len()
+        );
+
+        JobRepository::enqueue_if_absent(
+            pool,
+            REFRESH_SCAN_JOB_KIND,
+            json!({}),
+            Some(now + Duration::seconds(self.config.scan_interval_secs)),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        REFRESH_SCAN_JOB_KIND
+    }
+}
+
+impl RefreshScanJobHandler {
+    pub fn new(config: RefreshConfig) -> Self {
+        Self { config }
+    }
+}
+
+/// Refetch a single living document with a conditional GET, notifying the
+/// owner of watched items when the text changed materially
+#[derive(Clone, Default)]
+pub struct RefreshItemJobHandler {
+    sanitize_policy: SanitizePolicy,
+    email_notifications: bool,
+    flag_nsfw: bool,
+}
+
+#[async_trait]
+impl JobHandler for RefreshItemJobHandler {
+    #[instrument(skip(self, pool, span, deadline), fields(item_id))]
+    async fn run(
+        &self,
+        payload: serde_json::Value,
+        pool: &PgPool,
+        span: Span,
+        deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let payload: RefreshItemPayload = serde_json::from_value(payload)?;
+
+        span.record("item_id", tracing::field::display(payload.item_id));
+
+        let item = sqlx::query!(
+            r#"
+            SELECT url, user_id, http_etag, http_last_modified, watch_changes
+            FROM items
+            WHERE id = $1 AND refresh_interval_secs IS NOT NULL
+            "#,
+            payload.item_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        // Deleted or no longer a living document
+        let Some(item) = item else {
+            return Ok(());
+        };
+        let (url, user_id, watch) = (item.url, item.user_id, item.watch_changes);
+
+        let validators = CacheValidators {
+            etag: item.http_etag,
+            last_modified: item.http_last_modified,
+        };
+
+        let started = Instant::now();
+        let (fetched, timings) = fetch_conditional_with_timings(&url, &validators, &deadline).await;
+        let attempts = FetchAttemptRepository::new(pool);
+        match &fetched {
+            Ok(FetchOutcome::NotModified) => {
+                attempts
+                    .record_not_modified(payload.item_id, &url, started.elapsed(), &timings)
+                    .await?
+            }
+            Ok(FetchOutcome::Modified(response)) => {
+                attempts
+                    .record_response(payload.item_id, response, started.elapsed(), &timings)
+                    .await?
+            }
+            Err(fetch_error) => {
+                attempts
+                    .record_error(payload.item_id, fetch_error, started.elapsed(), &timings)
+                    .await?
+            }
+        }
+
+        match fetched {
+            Ok(FetchOutcome::NotModified) => {
+                info!("Item {} unchanged since last fetch", payload.item_id);
+                Ok(())
+            }
+            Ok(FetchOutcome::Modified(response)) => {
+                let domain_pref = match response.url_final.host_str() {
+                    Some(host) => {
+                        DomainPrefsRepository::new(pool)
+                            .find_for_host(user_id, host)
+                            .await?
+                    }
+                    None => None,
+                };
+
+                ItemEventRepository::new(pool)
+                    .record(
+                        payload.item_id,
+                        ItemEventKind::Refetched,
+                        Some(&format!("HTTP {}", response.status.as_u16())),
+                    )
+                    .await?;
+
+                let previous_text = if watch {
+                    ContentRepository::new(pool)
+                        .summary_source(payload.item_id)
+                        .await?
+                        .and_then(|source| source.text)
+                } else {
+                    None
+                };
+
+                let extraction = FetchPageJobHandler::store_page(
+                    pool,
+                    payload.item_id,
+                    &response,
+                    domain_pref.as_ref(),
+                    &self.sanitize_policy,
+                )
+                .await?;
+                if self.flag_nsfw {
+                    FetchPageJobHandler::flag_nsfw(
+                        pool,
+                        payload.item_id,
+                        &response.body_utf8,
+                        &extraction,
+                    )
+                    .await?;
+                }
+
+                if let (Some(previous_text), Ok(extracted)) = (previous_text, &extraction) {
+                    self.notify_if_changed(
+                        pool,
+                        user_id,
+                        payload.item_id,
+                        &previous_text,
+                        &extracted.text,
+                    )
+                    .await?;
+                }
+
+                info!("Refreshed content for item {}", payload.item_id);
+                Ok(())
+            }
+            Err(fetch_error) if fetch_error.should_retry() => {
+                anyhow::bail!("Retryable fetch error: {}", fetch_error)
+            }
+            Err(fetch_error) => {
+                // The next scheduled refresh will try again
+                warn!(
+                    "Skipping refresh of item {}: {}",
+                    payload.item_id, fetch_error
+                );
+                Ok(())
+            }
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        REFRESH_ITEM_JOB_KIND
+    }
+}
+
+impl RefreshItemJobHandler {
+    pub fn new(
+        sanitize_policy: SanitizePolicy,
+        email_notifications: bool,
+        flag_nsfw: bool,
+    ) -> Self {
+        Self {
+            sanitize_policy,
+            email_notifications,
+            flag_nsfw,
+        }
+    }
+
+    /// Tell the owner what changed between the stored and refetched text,
+    /// if enough of it did
+    async fn notify_if_changed(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        item_id: Uuid,
+        previous_text: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let diff = diff_text(previous_text, text);
+        if !diff.is_material() {
+            return Ok(());
+        }
+
+        info!(
+            "Content of watched item {} changed ({:.0}%)",
+            item_id,
+            diff.ratio * 100.0
+        );
+        NotificationRepository::new(pool)
+            .create(
+                user_id,
+                CONTENT_CHANGED_EVENT,
+                json!({
+                    "item_id": item_id,
+                    "change_ratio": (diff.ratio * 100.0).round() / 100.0,
+                    "added_count": diff.added_count,
+                    "removed_count": diff.removed_count,
+                    "added": diff.added,
+                    "removed": diff.removed,
+                }),
+                self.email_notifications,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn due(urls: &[&str]) -> Vec<(Uuid, String)> {
+        urls.iter()
+            .map(|url| (Uuid::new_v4(), url.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_refreshes_spaces_requests_per_domain() {
+        let config = RefreshConfig {
+            max_per_domain: 2,
+            domain_spacing_secs: 30,
+            ..Default::default()
+        };
+        let now = Utc::now();
+        let items = due(&[
+            "https://docs.rs/a",
+            "https://docs.rs/b",
+            "https://wiki.example.org/x",
+            "https://DOCS.rs/c",
+        ]);
+
+        let planned = plan_refreshes(&items, &config, now);
+
+        assert_eq!(
+            planned,
+            vec![
+                (items[0].0, now),
+                (items[1].0, now + Duration::seconds(30)),
+                (items[2].0, now),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_refreshes_empty() {
+        assert!(plan_refreshes(&[], &RefreshConfig::default(), Utc::now()).is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    entities::{DigestSchedule, UserPreferences},
+    fetcher::Deadline,
+    jobs::handler::JobHandler,
+    scheduling::{SEND_DIGEST_JOB_KIND, SendDigestPayload, schedule_next_digest},
+};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use sqlx::{PgPool, types::Json};
+use tracing::{Span, info, instrument};
+
+#[derive(Clone)]
+pub struct SendDigestJobHandler;
+
+#[async_trait]
+impl JobHandler for SendDigestJobHandler {
+    #[instrument(skip(self, pool, span, _deadline), fields(user_id))]
+    async fn run(
+        &self,
+        payload: serde_json::Value,
+        pool: &PgPool,
+        span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let payload: SendDigestPayload = serde_json::from_value(payload)?;
+
+        span.record("user_id", tracing::field::display(payload.user_id));
+
+        let user = sqlx::query!(
+            r#"SELECT prefs AS "prefs: Json<UserPreferences>", next_digest_at FROM users WHERE id = $1"#,
+            payload.user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(user) = user else {
+            info!("Skipping digest for deleted user {}", payload.user_id);
+            return Ok(());
+        };
+
+        let Json(prefs) = user.prefs;
+
+        // The schedule changed after this job was enqueued; a newer job owns it
+        if user.next_digest_at != Some(payload.scheduled_for) {
+            info!(
+                "Skipping stale digest for user {} scheduled for {}",
+                payload.user_id, payload.scheduled_for
+            );
+            return Ok(());
+        }
+
+        let period = match prefs.digest_schedule {
+            Some(DigestSchedule::Weekly) => Duration::weeks(1),
+            _ => Duration::days(1),
+        };
+
+        let new_items = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM items
+            WHERE user_id = $1 AND created_at > $2 AND created_at <= $3
+            "#,
+            payload.user_id,
+            payload.scheduled_for - period,
+            payload.scheduled_for
+        )
+        .fetch_one(pool)
+        .await?;
+
+        // No delivery channel exists yet, so the digest is only recorded here
+        info!(
+            "Digest for user {}: {} new items since {}",
+            payload.user_id,
+            new_items,
+            payload.scheduled_for - period
+        );
+
+        let now = Utc::now().max(payload.scheduled_for);
+        schedule_next_digest(pool, payload.user_id, &prefs, now).await?;
+
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        SEND_DIGEST_JOB_KIND
+    }
+}
+
+impl SendDigestJobHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SendDigestJobHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
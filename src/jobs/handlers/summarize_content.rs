@@ -0,0 +1,86 @@
+use crate::{
+    fetcher::Deadline,
+    jobs::handler::JobHandler,
+    repositories::ContentRepository,
+    summarizer::{ExtractiveSummarizer, Summarizer},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{Span, info, instrument};
+use uuid::Uuid;
+
+pub const SUMMARIZE_CONTENT_JOB_KIND: &str = "summarize_content";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SummarizeContentPayload {
+    pub item_id: Uuid,
+}
+
+/// Stores a summary of an item's extracted text, written by the configured
+/// [`Summarizer`]. Queued after every successful extraction.
+#[derive(Clone)]
+pub struct SummarizeContentJobHandler {
+    summarizer: Arc<dyn Summarizer>,
+}
+
+#[async_trait]
+impl JobHandler for SummarizeContentJobHandler {
+    #[instrument(skip(self, pool, span, _deadline), fields(item_id))]
+    async fn run(
+        &self,
+        payload: Value,
+        pool: &PgPool,
+        span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let payload: SummarizeContentPayload = serde_json::from_value(payload)?;
+        span.record("item_id", tracing::field::display(payload.item_id));
+
+        let repo = ContentRepository::new(pool);
+        let Some(source) = repo.summary_source(payload.item_id).await? else {
+            info!("Item {} has no content to summarize", payload.item_id);
+            return Ok(());
+        };
+        let Some(text) = source.text.filter(|text| !text.trim().is_empty()) else {
+            info!("Item {} has no extracted text", payload.item_id);
+            return Ok(());
+        };
+
+        let summary = self
+            .summarizer
+            .summarize(source.title.as_deref(), &text)
+            .await?;
+        repo.set_summary(payload.item_id, &summary, self.summarizer.name())
+            .await?;
+        info!(
+            "Summarized item {} with {}",
+            payload.item_id,
+            self.summarizer.name()
+        );
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        SUMMARIZE_CONTENT_JOB_KIND
+    }
+}
+
+impl SummarizeContentJobHandler {
+    /// A handler using the local extractive summarizer
+    pub fn new() -> Self {
+        Self::with_summarizer(Arc::new(ExtractiveSummarizer))
+    }
+
+    pub fn with_summarizer(summarizer: Arc<dyn Summarizer>) -> Self {
+        Self { summarizer }
+    }
+}
+
+impl Default for SummarizeContentJobHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
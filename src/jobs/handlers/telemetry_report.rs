@@ -0,0 +1,59 @@
+use crate::{
+    fetcher::Deadline,
+    jobs::{JobRepository, handler::JobHandler},
+    telemetry::{TelemetryClient, TelemetryConfig, TelemetryReport},
+};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{Span, info};
+
+pub const TELEMETRY_REPORT_JOB_KIND: &str = "telemetry_report";
+
+/// Periodic job posting the instance's anonymized [`TelemetryReport`] to the
+/// configured endpoint. Only registered when telemetry is configured.
+#[derive(Clone)]
+pub struct TelemetryReportJobHandler {
+    config: TelemetryConfig,
+    client: Arc<TelemetryClient>,
+}
+
+#[async_trait]
+impl JobHandler for TelemetryReportJobHandler {
+    async fn run(
+        &self,
+        _payload: serde_json::Value,
+        pool: &PgPool,
+        _span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let since = Utc::now() - Duration::seconds(self.config.interval_secs);
+        let report = TelemetryReport::collect(pool, since, self.config.interval_secs).await?;
+        self.client.send(&report).await?;
+
+        info!("Telemetry report sent to {}", self.config.endpoint);
+
+        JobRepository::enqueue_if_absent(
+            pool,
+            TELEMETRY_REPORT_JOB_KIND,
+            json!({}),
+            Some(Utc::now() + Duration::seconds(self.config.interval_secs)),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        TELEMETRY_REPORT_JOB_KIND
+    }
+}
+
+impl TelemetryReportJobHandler {
+    pub fn new(config: TelemetryConfig) -> anyhow::Result<Self> {
+        let client = Arc::new(TelemetryClient::new(config.endpoint.clone())?);
+        Ok(Self { config, client })
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{
+    fetcher::Deadline,
+    jobs::handler::JobHandler,
+    repositories::TranslationRepository,
+    translation::{MAX_CHUNK_CHARS, TranslationProvider, chunks},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{Span, info, instrument, warn};
+use uuid::Uuid;
+
+pub const TRANSLATE_CONTENT_JOB_KIND: &str = "translate_content";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TranslateContentPayload {
+    pub item_id: Uuid,
+    /// ISO 639-1 code of the language to translate into
+    pub lang: String,
+}
+
+/// Stores a translation of an item's extracted text into the requested
+/// language. Without a provider configured the job does nothing, so
+/// requests made before one is set up are dropped rather than retried.
+#[derive(Clone)]
+pub struct TranslateContentJobHandler {
+    provider: Option<Arc<dyn TranslationProvider>>,
+}
+
+#[async_trait]
+impl JobHandler for TranslateContentJobHandler {
+    #[instrument(skip(self, pool, span, _deadline), fields(item_id, lang))]
+    async fn run(
+        &self,
+        payload: Value,
+        pool: &PgPool,
+        span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let payload: TranslateContentPayload = serde_json::from_value(payload)?;
+        span.record("item_id", tracing::field::display(payload.item_id));
+        span.record("lang", payload.lang.as_str());
+
+        let Some(provider) = &self.provider else {
+            warn!("No translation provider configured; skipping translation");
+            return Ok(());
+        };
+
+        let repo = TranslationRepository::new(pool);
+        let Some(source) = repo.source(payload.item_id).await? else {
+            info!("Skipping translation of deleted item {}", payload.item_id);
+            return Ok(());
+        };
+        let Some(text) = source.text.filter(|text| !text.trim().is_empty()) else {
+            info!(
+                "Item {} has no extracted text to translate",
+                payload.item_id
+            );
+            return Ok(());
+        };
+        if source.lang.as_deref() == Some(payload.lang.as_str()) {
+            info!("Item {} is already in {}", payload.item_id, payload.lang);
+            return Ok(());
+        }
+
+        let mut translated = Vec::new();
+        for chunk in chunks(&text, MAX_CHUNK_CHARS) {
+            translated.push(
+                provider
+                    .translate(&chunk, source.lang.as_deref(), &payload.lang)
+                    .await?,
+            );
+        }
+
+        repo.upsert(
+            payload.item_id,
+            &payload.lang,
+            source.lang.as_deref(),
+            &translated.join("\n\n"),
+            provider.name(),
+        )
+        .await?;
+        info!(
+            "Translated item {} into {} with {}",
+            payload.item_id,
+            payload.lang,
+            provider.name()
+        );
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        TRANSLATE_CONTENT_JOB_KIND
+    }
+}
+
+impl TranslateContentJobHandler {
+    pub fn new(provider: Option<Arc<dyn TranslationProvider>>) -> Self {
+        Self { provider }
+    }
+}
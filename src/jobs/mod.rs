@@ -1,15 +1,21 @@
 pub mod backoff;
+pub mod chaos;
 pub mod entities;
 pub mod handler;
 pub mod handlers;
+pub mod outbox;
+pub mod payload;
 pub mod registry;
 pub mod repository;
 pub mod worker;
 
 pub use backoff::*;
+pub use chaos::*;
 pub use entities::*;
 pub use handler::*;
 pub use handlers::*;
+pub use outbox::*;
+pub use payload::*;
 pub use registry::*;
 pub use repository::*;
 pub use worker::*;
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::jobs::validate_payload;
+
+/// Transactional outbox for jobs.
+///
+/// Handlers stage jobs with [`Outbox::enqueue`] inside the transaction that
+/// makes the domain change, so the job exists if and only if that change
+/// commits. [`Outbox::relay`] later moves staged rows into `jobs`.
+pub struct Outbox;
+
+impl Outbox {
+    /// Stage a job on the caller's connection (usually an open transaction).
+    /// Returns the ID the job will have once relayed.
+    pub async fn enqueue(
+        conn: &mut PgConnection,
+        kind: &str,
+        payload: Value,
+        run_at: Option<DateTime<Utc>>,
+    ) -> Result<Uuid> {
+        validate_payload(kind, &payload)?;
+        let run_at = run_at.unwrap_or_else(Utc::now);
+
+        let job_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO job_outbox (kind, payload, run_at)
+            VALUES ($1, $2, $3)
+            RETURNING job_id
+            "#,
+            kind,
+            payload,
+            run_at
+        )
+        .fetch_one(conn)
+        .await?;
+
+        Ok(job_id)
+    }
+
+    /// Discard jobs staged for an item that's going away, on the caller's
+    /// connection
+    pub async fn discard_for_item(conn: &mut PgConnection, item_id: Uuid) -> Result<u64> {
+        let discarded = sqlx::query!(
+            "DELETE FROM job_outbox WHERE payload->>'item_id' = $1",
+            item_id.to_string()
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(discarded.rows_affected())
+    }
+
+    /// Move up to `limit` staged jobs into the jobs table, oldest first.
+    ///
+    /// The delete and insert are one statement, so a row is never both lost
+    /// and relayed, and concurrent relays skip each other's rows. Jobs keep
+    /// the ID handed out at staging time.
+    pub async fn relay(pool: &PgPool, limit: i64) -> Result<u64> {
+        let relayed = sqlx::query!(
+            r#"
+            WITH staged AS (
+                DELETE FROM job_outbox
+                WHERE id IN (
+                    SELECT id FROM job_outbox
+                    ORDER BY id
+                    FOR UPDATE SKIP LOCKED
+                    LIMIT $1
+                )
+                RETURNING job_id, kind, payload, run_at, max_attempts
+            )
+            INSERT INTO jobs (id, kind, payload, run_at, max_attempts)
+            SELECT job_id, kind, payload, run_at, max_attempts FROM staged
+            ON CONFLICT (id) DO NOTHING
+            "#,
+            limit
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(relayed.rows_affected())
+    }
+}
@@ -0,0 +1,103 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    jobs::{
+        BUILD_DATA_PACKAGE_JOB_KIND, BuildDataPackagePayload, CLASSIFY_TOPICS_JOB_KIND,
+        COMPRESS_HTML_JOB_KIND, ClassifyTopicsPayload, EMBED_CONTENT_JOB_KIND, EXAMPLE_JOB_KIND,
+        EmbedContentPayload, ExampleJobPayload, FETCH_PAGE_JOB_KIND, FETCH_SITE_ICON_JOB_KIND,
+        FETCH_TITLE_JOB_KIND, FetchPagePayload, FetchSiteIconPayload, FetchTitlePayload,
+        IMPORT_URLS_JOB_KIND, ImportUrlsPayload, QUEUE_HEALTH_JOB_KIND, QUOTA_CHECK_JOB_KIND,
+        REFRESH_ITEM_JOB_KIND, REFRESH_SCAN_JOB_KIND, RefreshItemPayload,
+        SUMMARIZE_CONTENT_JOB_KIND, SummarizeContentPayload, TRANSLATE_CONTENT_JOB_KIND,
+        TranslateContentPayload,
+    },
+    scheduling::{SEND_DIGEST_JOB_KIND, SendDigestPayload},
+};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Invalid payload for job kind {kind}: {reason}")]
+pub struct InvalidPayload {
+    pub kind: String,
+    pub reason: String,
+}
+
+/// Check a payload against the shape its job kind's handler expects, so
+/// malformed jobs are refused at submission instead of failing at run time.
+/// Kinds without a known payload type are accepted as-is.
+pub fn validate_payload(kind: &str, payload: &Value) -> Result<(), InvalidPayload> {
+    let result = match kind {
+        BUILD_DATA_PACKAGE_JOB_KIND => check::<BuildDataPackagePayload>(payload),
+        CLASSIFY_TOPICS_JOB_KIND => check::<ClassifyTopicsPayload>(payload),
+        EMBED_CONTENT_JOB_KIND => check::<EmbedContentPayload>(payload),
+        EXAMPLE_JOB_KIND => check::<ExampleJobPayload>(payload),
+        FETCH_PAGE_JOB_KIND => check::<FetchPagePayload>(payload),
+        FETCH_SITE_ICON_JOB_KIND => check::<FetchSiteIconPayload>(payload),
+        FETCH_TITLE_JOB_KIND => check::<FetchTitlePayload>(payload),
+        IMPORT_URLS_JOB_KIND => check::<ImportUrlsPayload>(payload),
+        REFRESH_ITEM_JOB_KIND => check::<RefreshItemPayload>(payload),
+        SEND_DIGEST_JOB_KIND => check::<SendDigestPayload>(payload),
+        SUMMARIZE_CONTENT_JOB_KIND => check::<SummarizeContentPayload>(payload),
+        TRANSLATE_CONTENT_JOB_KIND => check::<TranslateContentPayload>(payload),
+        // Periodic jobs take no parameters
+        COMPRESS_HTML_JOB_KIND
+        | QUEUE_HEALTH_JOB_KIND
+        | QUOTA_CHECK_JOB_KIND
+        | REFRESH_SCAN_JOB_KIND
+            if !payload.is_object() =>
+        {
+            Err("expected an object".to_string())
+        }
+        _ => Ok(()),
+    };
+
+    result.map_err(|reason| InvalidPayload {
+        kind: kind.to_string(),
+        reason,
+    })
+}
+
+fn check<T: DeserializeOwned>(payload: &Value) -> Result<(), String> {
+    T::deserialize(payload)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_validate_payload_accepts_well_formed() {
+        let item_id = Uuid::new_v4();
+        assert!(validate_payload(FETCH_PAGE_JOB_KIND, &json!({"item_id": item_id})).is_ok());
+        assert!(
+            validate_payload(
+                FETCH_PAGE_JOB_KIND,
+                &json!({"item_id": item_id, "refetch": true})
+            )
+            .is_ok()
+        );
+        assert!(validate_payload(FETCH_TITLE_JOB_KIND, &json!({"item_id": item_id})).is_ok());
+        assert!(validate_payload(QUOTA_CHECK_JOB_KIND, &json!({})).is_ok());
+    }
+
+    #[test]
+    fn test_validate_payload_rejects_malformed() {
+        let error = validate_payload(FETCH_PAGE_JOB_KIND, &json!({"item_id": "nope"})).unwrap_err();
+        assert_eq!(error.kind, FETCH_PAGE_JOB_KIND);
+
+        assert!(
+            validate_payload(SEND_DIGEST_JOB_KIND, &json!({"user_id": Uuid::new_v4()})).is_err()
+        );
+        assert!(validate_payload(REFRESH_SCAN_JOB_KIND, &json!(null)).is_err());
+    }
+
+    #[test]
+    fn test_validate_payload_unknown_kind() {
+        assert!(validate_payload("custom_job", &json!("anything")).is_ok());
+    }
+}
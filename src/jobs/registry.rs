@@ -43,7 +43,7 @@ impl JobRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::jobs::JobHandler;
+    use crate::{fetcher::Deadline, jobs::JobHandler};
     use async_trait::async_trait;
     use serde_json::json;
     use sqlx::PgPool;
@@ -54,7 +54,13 @@ mod tests {
 
     #[async_trait]
     impl JobHandler for TestJobHandler {
-        async fn run(&self, _payload: Value, _pool: &PgPool, _span: Span) -> anyhow::Result<()> {
+        async fn run(
+            &self,
+            _payload: Value,
+            _pool: &PgPool,
+            _span: Span,
+            _deadline: Deadline,
+        ) -> anyhow::Result<()> {
             Ok(())
         }
 
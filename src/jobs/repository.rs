@@ -1,14 +1,18 @@
-use crate::entities::{Job, JobStatus};
+use crate::{
+    entities::{Job, JobStatus},
+    jobs::validate_payload,
+};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde_json::Value;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
 pub struct JobRepository;
 
 impl JobRepository {
-    /// Enqueue a new job
+    /// Enqueue a new job. Fails without inserting if the payload doesn't fit
+    /// the job kind.
     pub async fn enqueue(
         pool: &PgPool,
         kind: &str,
@@ -16,6 +20,7 @@ impl JobRepository {
         run_at: Option<DateTime<Utc>>,
         max_attempts: Option<i32>,
     ) -> Result<Uuid> {
+        validate_payload(kind, &payload)?;
         let run_at = run_at.unwrap_or_else(Utc::now);
         let max_attempts = max_attempts.unwrap_or(25);
 
@@ -36,6 +41,36 @@ impl JobRepository {
         Ok(result.id)
     }
 
+    /// Enqueue a job unless one of the same kind is already waiting to run.
+    /// Used by self-rescheduling periodic jobs. Returns the new job's ID, if any.
+    pub async fn enqueue_if_absent(
+        pool: &PgPool,
+        kind: &str,
+        payload: Value,
+        run_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<Uuid>> {
+        validate_payload(kind, &payload)?;
+        let run_at = run_at.unwrap_or_else(Utc::now);
+
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO jobs (kind, payload, run_at)
+            SELECT $1, $2, $3
+            WHERE NOT EXISTS (
+                SELECT 1 FROM jobs WHERE kind = $1 AND status = 'queued'::job_status
+            )
+            RETURNING id
+            "#,
+            kind,
+            payload,
+            run_at
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(id)
+    }
+
     /// Fetch due jobs and reserve them for processing
     pub async fn fetch_due_jobs(
         pool: &PgPool,
@@ -146,6 +181,94 @@ impl JobRepository {
         Ok(())
     }
 
+    /// Count a handler panic for the job and return how many it has caused
+    /// in a row
+    pub async fn record_panic(pool: &PgPool, job_id: Uuid) -> Result<i32> {
+        let panics = sqlx::query_scalar!(
+            "UPDATE jobs SET panic_count = panic_count + 1 WHERE id = $1 RETURNING panic_count",
+            job_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(panics)
+    }
+
+    /// Reset the consecutive panic count after a run that didn't panic
+    pub async fn clear_panics(pool: &PgPool, job_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            "UPDATE jobs SET panic_count = 0 WHERE id = $1 AND panic_count > 0",
+            job_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Permanently fail a poisoned job so it is never retried
+    pub async fn dead_letter(pool: &PgPool, job_id: Uuid, error_message: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE jobs
+            SET status = 'failed'::job_status,
+                attempts = attempts + 1,
+                last_error = $2,
+                dead_lettered_at = now(),
+                visibility_till = NULL,
+                reserved_by = NULL,
+                updated_at = now()
+            WHERE id = $1
+            "#,
+            job_id,
+            error_message
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drop the queued jobs for an item that's going away, on the caller's
+    /// connection. Jobs already running find the item gone and stop.
+    pub async fn cancel_for_item(conn: &mut PgConnection, item_id: Uuid) -> Result<u64> {
+        let cancelled = sqlx::query!(
+            r#"
+            DELETE FROM jobs
+            WHERE status = 'queued'::job_status
+              AND (item_id = $1 OR payload->>'item_id' = $1::text)
+            "#,
+            item_id
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(cancelled.rows_affected())
+    }
+
+    /// Put jobs this worker leased but never started back in the queue.
+    /// Jobs whose lease has since passed to another worker are left alone.
+    pub async fn release(pool: &PgPool, worker_id: Uuid, job_ids: &[Uuid]) -> Result<u64> {
+        let released = sqlx::query!(
+            r#"
+            UPDATE jobs
+            SET status = 'queued'::job_status,
+                visibility_till = NULL,
+                reserved_by = NULL,
+                updated_at = now()
+            WHERE id = ANY($1)
+              AND reserved_by = $2
+              AND status = 'running'::job_status
+            "#,
+            job_ids,
+            worker_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(released.rows_affected())
+    }
+
     /// Extend visibility timeout for a running job
     pub async fn extend_visibility(
         pool: &PgPool,
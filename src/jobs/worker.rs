@@ -1,8 +1,14 @@
-use crate::jobs::{JobRegistry, JobRepository, calculate_backoff_delay};
-use anyhow::Result;
+use crate::{
+    fetcher::Deadline,
+    jobs::{
+        ChaosConfig, INJECTED_FAILURE, JobRegistry, JobRepository, Outbox, calculate_backoff_delay,
+    },
+};
+use anyhow::{Result, anyhow};
 use chrono::Utc;
+use futures::FutureExt;
 use sqlx::PgPool;
-use std::{sync::Arc, time::Duration};
+use std::{any::Any, panic::AssertUnwindSafe, sync::Arc, time::Duration};
 use tokio::{
     signal,
     sync::{Semaphore, mpsc},
@@ -19,6 +25,13 @@ pub struct WorkerConfig {
     pub poll_interval_ms: u64,
     pub visibility_timeout_secs: i64,
     pub base_backoff_secs: u32,
+    /// How often staged outbox jobs are moved into the queue
+    pub outbox_relay_interval_ms: u64,
+    pub outbox_batch_size: i64,
+    /// Dead-letter a job once its handler has panicked this many times in a row
+    pub max_consecutive_panics: i32,
+    /// Faults to inject, for tests only
+    pub chaos: Option<ChaosConfig>,
 }
 
 impl Default for WorkerConfig {
@@ -28,10 +41,31 @@ impl Default for WorkerConfig {
             poll_interval_ms: 1000,
             visibility_timeout_secs: 300, // 5 minutes
             base_backoff_secs: 30,
+            outbox_relay_interval_ms: 500,
+            outbox_batch_size: 100,
+            max_consecutive_panics: 3,
+            chaos: None,
         }
     }
 }
 
+/// How many jobs the fetcher may lease: free processing slots minus jobs
+/// already received but still waiting in the channel for one.
+pub fn reservation_limit(available_permits: usize, queued: usize) -> usize {
+    available_permits.saturating_sub(queued)
+}
+
+/// Best-effort text of a panic payload
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
+
 /// Main worker supervisor that orchestrates job processing
 pub struct WorkerSupervisor {
     pool: PgPool,
@@ -52,6 +86,16 @@ impl WorkerSupervisor {
         }
     }
 
+    /// This worker's ID, recorded in `jobs.reserved_by` while it holds a lease
+    pub fn worker_id(&self) -> Uuid {
+        self.worker_id
+    }
+
+    /// Token that stops the supervisor gracefully when cancelled, as Ctrl-C does
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown_token.clone()
+    }
+
     /// Start the worker supervisor
     pub async fn run(self) -> Result<()> {
         info!("Starting worker supervisor with ID: {}", self.worker_id);
@@ -63,7 +107,7 @@ impl WorkerSupervisor {
         );
 
         // Create bounded channel for jobs
-        let (job_sender, job_receiver) = mpsc::channel(self.config.concurrency * 2);
+        let (job_sender, job_receiver) = mpsc::channel(self.config.concurrency);
 
         // Semaphore to limit concurrent job processing
         let semaphore = Arc::new(Semaphore::new(self.config.concurrency));
@@ -84,6 +128,7 @@ impl WorkerSupervisor {
             let pool = self.pool.clone();
             let worker_id = self.worker_id;
             let config = self.config.clone();
+            let semaphore = semaphore.clone();
             let shutdown_token = self.shutdown_token.clone();
             tokio::spawn(
                 WorkerSupervisor::run_fetcher_static(
@@ -91,15 +136,28 @@ impl WorkerSupervisor {
                     worker_id,
                     config,
                     job_sender,
+                    semaphore,
                     shutdown_token,
                 )
                 .instrument(info_span!("fetcher", worker_id = %worker_id)),
             )
         };
 
+        // Spawn outbox relay
+        let relay_handle = {
+            let pool = self.pool.clone();
+            let config = self.config.clone();
+            let shutdown_token = self.shutdown_token.clone();
+            tokio::spawn(
+                WorkerSupervisor::run_outbox_relay_static(pool, config, shutdown_token)
+                    .instrument(info_span!("outbox_relay", worker_id = %self.worker_id)),
+            )
+        };
+
         // Spawn job processor
         let processor_handle = {
             let pool = self.pool.clone();
+            let worker_id = self.worker_id;
             let registry = self.registry.clone();
             let config = self.config.clone();
             let semaphore = semaphore.clone();
@@ -107,6 +165,7 @@ impl WorkerSupervisor {
             tokio::spawn(
                 WorkerSupervisor::run_processor_static(
                     pool,
+                    worker_id,
                     registry,
                     config,
                     job_receiver,
@@ -127,8 +186,8 @@ impl WorkerSupervisor {
             .await?;
         info!("All jobs completed, shutting down");
 
-        // Wait for fetcher and processor to finish
-        let _ = tokio::join!(fetcher_handle, processor_handle);
+        // Wait for fetcher, relay and processor to finish
+        let _ = tokio::join!(fetcher_handle, relay_handle, processor_handle);
 
         Ok(())
     }
@@ -139,6 +198,7 @@ impl WorkerSupervisor {
         worker_id: Uuid,
         config: WorkerConfig,
         job_sender: mpsc::Sender<crate::entities::Job>,
+        semaphore: Arc<Semaphore>,
         shutdown_token: CancellationToken,
     ) -> Result<()> {
         let mut poll_interval = interval(Duration::from_millis(config.poll_interval_ms));
@@ -150,9 +210,20 @@ impl WorkerSupervisor {
                     break;
                 }
                 _ = poll_interval.tick() => {
+                    // Only lease what can start right away, so a busy worker
+                    // doesn't sit on jobs while their visibility runs out
+                    let queued = job_sender.max_capacity() - job_sender.capacity();
+                    let limit = reservation_limit(semaphore.available_permits(), queued)
+                        .min(job_sender.capacity());
+                    if limit == 0 {
+                        continue;
+                    }
+
+                    Self::chaos_delay(&config).await;
+
                     match JobRepository::fetch_due_jobs(
                         &pool,
-                        config.concurrency as i64,
+                        limit as i64,
                         worker_id,
                         config.visibility_timeout_secs,
                     )
@@ -160,9 +231,13 @@ impl WorkerSupervisor {
                     {
                         Ok(jobs) => {
                             debug!("Fetched {} jobs", jobs.len());
-                            for job in jobs {
-                                if job_sender.send(job).await.is_err() {
+                            let mut jobs = jobs.into_iter();
+                            while let Some(job) = jobs.next() {
+                                if let Err(mpsc::error::SendError(job)) = job_sender.send(job).await {
                                     warn!("Job receiver dropped, stopping fetcher");
+                                    let unsent: Vec<Uuid> =
+                                        std::iter::once(job.id).chain(jobs.map(|j| j.id)).collect();
+                                    Self::release_static(&pool, worker_id, &unsent).await;
                                     return Ok(());
                                 }
                             }
@@ -179,9 +254,39 @@ impl WorkerSupervisor {
         Ok(())
     }
 
+    /// Outbox relay loop
+    async fn run_outbox_relay_static(
+        pool: PgPool,
+        config: WorkerConfig,
+        shutdown_token: CancellationToken,
+    ) -> Result<()> {
+        let mut relay_interval = interval(Duration::from_millis(config.outbox_relay_interval_ms));
+
+        loop {
+            tokio::select! {
+                _ = shutdown_token.cancelled() => {
+                    info!("Outbox relay shutting down");
+                    break;
+                }
+                _ = relay_interval.tick() => {
+                    match Outbox::relay(&pool, config.outbox_batch_size).await {
+                        Ok(0) => {}
+                        Ok(relayed) => debug!("Relayed {} outbox jobs", relayed),
+                        Err(e) => {
+                            error!("Failed to relay outbox jobs: {}", e);
+                            sleep(Duration::from_millis(1000)).await;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Job processing loop
     async fn run_processor_static(
         pool: PgPool,
+        worker_id: Uuid,
         registry: Arc<JobRegistry>,
         config: WorkerConfig,
         mut job_receiver: mpsc::Receiver<crate::entities::Job>,
@@ -213,10 +318,37 @@ impl WorkerSupervisor {
             );
         }
 
+        // Hand back jobs that were leased but never started so other workers
+        // can pick them up now rather than after the visibility timeout
+        job_receiver.close();
+        let mut unstarted = Vec::new();
+        while let Some(job) = job_receiver.recv().await {
+            unstarted.push(job.id);
+        }
+        Self::release_static(&pool, worker_id, &unstarted).await;
+
         info!("Processor shutting down");
         Ok(())
     }
 
+    /// Return leased-but-unstarted jobs to the queue
+    async fn release_static(pool: &PgPool, worker_id: Uuid, job_ids: &[Uuid]) {
+        if job_ids.is_empty() {
+            return;
+        }
+        match JobRepository::release(pool, worker_id, job_ids).await {
+            Ok(released) => info!("Released {} unstarted jobs back to the queue", released),
+            Err(e) => error!("Failed to release unstarted jobs: {}", e),
+        }
+    }
+
+    /// Hold back a database call when chaos says so
+    async fn chaos_delay(config: &WorkerConfig) {
+        if let Some(chaos) = &config.chaos {
+            chaos.delay_db().await;
+        }
+    }
+
     /// Process a single job
     async fn process_job(
         pool: PgPool,
@@ -226,6 +358,11 @@ impl WorkerSupervisor {
     ) {
         info!("Processing job {} (attempt {})", job.id, job.attempts + 1);
 
+        if config.chaos.as_ref().is_some_and(ChaosConfig::abandon) {
+            warn!("Chaos: abandoning job {} until its lease expires", job.id);
+            return;
+        }
+
         let span = info_span!("job_execution", id = %job.id, kind = %job.kind);
 
         // Create handler for this job
@@ -245,9 +382,62 @@ impl WorkerSupervisor {
             }
         };
 
-        // Execute the job
-        let result = handler.run(job.payload.clone(), &pool, span.clone()).await;
+        // A payload that can never run shouldn't burn through retries
+        if let Err(e) = handler.validate_payload(&job.payload) {
+            error!("Job {} has an invalid payload: {}", job.id, e);
+            if let Err(mark_err) =
+                JobRepository::mark_failure(&pool, job.id, &e.to_string(), None, 0).await
+            {
+                error!("Failed to mark job {} as failed: {}", job.id, mark_err);
+            }
+            return;
+        }
+
+        // Past the lease another worker may be running the job, so that's
+        // when this run stops being useful
+        let deadline = Deadline::at(job.visibility_till.unwrap_or_else(|| {
+            Utc::now() + chrono::Duration::seconds(config.visibility_timeout_secs)
+        }));
+        let run = handler.run(job.payload.clone(), &pool, span.clone(), deadline);
 
+        // Execute the job, turning a handler panic into an ordinary failure
+        let result = if config.chaos.as_ref().is_some_and(ChaosConfig::fail) {
+            warn!("Chaos: failing job {} without running it", job.id);
+            Err(anyhow!(INJECTED_FAILURE))
+        } else {
+            match AssertUnwindSafe(run).catch_unwind().await {
+                Ok(result) => {
+                    if let Err(e) = JobRepository::clear_panics(&pool, job.id).await {
+                        error!("Failed to reset panic count for job {}: {}", job.id, e);
+                    }
+                    result
+                }
+                Err(panic) => {
+                    let message = format!("Handler panicked: {}", panic_message(panic.as_ref()));
+                    error!("Job {} {}", job.id, message);
+
+                    match JobRepository::record_panic(&pool, job.id).await {
+                        Ok(panics) if panics >= config.max_consecutive_panics => {
+                            warn!(
+                                "Job {} dead-lettered after {} consecutive panics",
+                                job.id, panics
+                            );
+                            if let Err(e) =
+                                JobRepository::dead_letter(&pool, job.id, &message).await
+                            {
+                                error!("Failed to dead-letter job {}: {}", job.id, e);
+                            }
+                            return;
+                        }
+                        Ok(_) => {}
+                        Err(e) => error!("Failed to record panic for job {}: {}", job.id, e),
+                    }
+                    Err(anyhow!(message))
+                }
+            }
+        };
+
+        Self::chaos_delay(&config).await;
         match result {
             Ok(()) => {
                 info!("Job {} completed successfully", job.id);
@@ -302,3 +492,37 @@ impl WorkerSupervisor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_message_from_str_and_string() {
+        let payload: Box<dyn Any + Send> = Box::new("boom");
+        assert_eq!(panic_message(payload.as_ref()), "boom");
+
+        let payload: Box<dyn Any + Send> = Box::new(format!("index {} out of range", 3));
+        assert_eq!(panic_message(payload.as_ref()), "index 3 out of range");
+
+        let payload: Box<dyn Any + Send> = Box::new(42);
+        assert_eq!(panic_message(payload.as_ref()), "non-string panic payload");
+    }
+
+    #[test]
+    fn test_reservation_limit_idle_worker() {
+        assert_eq!(reservation_limit(4, 0), 4);
+    }
+
+    #[test]
+    fn test_reservation_limit_counts_queued_jobs() {
+        assert_eq!(reservation_limit(4, 3), 1);
+        assert_eq!(reservation_limit(2, 2), 0);
+    }
+
+    #[test]
+    fn test_reservation_limit_saturated_worker() {
+        assert_eq!(reservation_limit(0, 0), 0);
+        assert_eq!(reservation_limit(0, 4), 0);
+    }
+}
@@ -1,12 +1,42 @@
+pub mod annotations;
 pub mod app_state;
 pub mod auth;
+pub mod backup;
 pub mod config;
+pub mod data_requests;
+pub mod domain_prefs;
+pub mod domain_rules;
+pub mod embeddings;
 pub mod entities;
 pub mod extractor;
 pub mod fetcher;
 pub mod health;
+pub mod history;
+pub mod imports;
 pub mod items;
 pub mod jobs;
+pub mod loadtest;
 pub mod middleware;
+pub mod notifications;
+pub mod operations;
+pub mod pagination;
 pub mod passwords;
+pub mod query;
 pub mod repositories;
+pub mod scheduling;
+pub mod schema;
+pub mod search;
+pub mod seed;
+pub mod shares;
+pub mod sites;
+pub mod stats;
+pub mod summarizer;
+pub mod tags;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+pub mod throttles;
+pub mod topics;
+pub mod translation;
+pub mod undo;
+pub mod urlnorm;
+pub mod users;
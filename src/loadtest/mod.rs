@@ -0,0 +1,253 @@
+//! Measurements for the `bench` load generator.
+//!
+//! The binary drives a mix of saves, list pages and searches against a
+//! running API; this module picks requests from the [`Mix`], summarises
+//! their latencies and sizes up how busy the API's connection pool got from
+//! samples of `pg_stat_activity`.
+
+use anyhow::{Result, bail};
+use std::time::Duration;
+
+/// A kind of request the load generator sends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// `POST /v1/items`
+    Save,
+    /// `GET /v1/items`
+    List,
+    /// `GET /v1/search`
+    Search,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 3] = [Operation::Save, Operation::List, Operation::Search];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Operation::Save => "save",
+            Operation::List => "list",
+            Operation::Search => "search",
+        }
+    }
+}
+
+/// Relative weights of the operations, e.g. `save=1,list=6,search=3`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mix {
+    pub save: u32,
+    pub list: u32,
+    pub search: u32,
+}
+
+impl Default for Mix {
+    /// Mostly reading, as clients do
+    fn default() -> Self {
+        Self {
+            save: 1,
+            list: 6,
+            search: 3,
+        }
+    }
+}
+
+impl Mix {
+    /// Parse `op=weight` pairs; operations left out get no requests
+    pub fn parse(value: &str) -> Result<Self> {
+        let mut mix = Self {
+            save: 0,
+            list: 0,
+            search: 0,
+        };
+        for pair in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let Some((op, weight)) = pair.split_once('=') else {
+                bail!("expected op=weight, got {:?}", pair);
+            };
+            let weight: u32 = weight.trim().parse()?;
+            match op.trim() {
+                "save" => mix.save = weight,
+                "list" => mix.list = weight,
+                "search" => mix.search = weight,
+                other => bail!("unknown operation {:?}", other),
+            }
+        }
+        if mix.total() == 0 {
+            bail!("the mix needs at least one operation with a weight");
+        }
+        Ok(mix)
+    }
+
+    pub fn total(&self) -> u32 {
+        self.save + self.list + self.search
+    }
+
+    /// The operation for `roll`, a number below [`Mix::total`]
+    pub fn pick(&self, roll: u32) -> Operation {
+        if roll < self.save {
+            Operation::Save
+        } else if roll < self.save + self.list {
+            Operation::List
+        } else {
+            Operation::Search
+        }
+    }
+}
+
+/// Latency percentiles of one operation's requests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl LatencySummary {
+    /// None when there are no samples
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        Some(Self {
+            count: sorted.len(),
+            p50: percentile(&sorted, 50.0),
+            p90: percentile(&sorted, 90.0),
+            p99: percentile(&sorted, 99.0),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// The nearest-rank `p`th percentile of non-empty, sorted `samples`
+pub fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// The API's connections at one moment, from `pg_stat_activity`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolSample {
+    /// Connections open
+    pub open: i64,
+    /// Connections running a query
+    pub active: i64,
+}
+
+/// How busy the API's connection pool was over a run
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolUsage {
+    pub samples: usize,
+    pub pool_size: i64,
+    pub peak_open: i64,
+    pub peak_active: i64,
+    pub mean_active: f64,
+    /// Share of samples with every connection of the pool running a query,
+    /// when further requests would have waited for one
+    pub saturated: f64,
+}
+
+impl PoolUsage {
+    /// None when there are no samples
+    pub fn from_samples(samples: &[PoolSample], pool_size: i64) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let count = samples.len() as f64;
+        Some(Self {
+            samples: samples.len(),
+            pool_size,
+            peak_open: samples.iter().map(|s| s.open).max().unwrap_or(0),
+            peak_active: samples.iter().map(|s| s.active).max().unwrap_or(0),
+            mean_active: samples.iter().map(|s| s.active as f64).sum::<f64>() / count,
+            saturated: samples.iter().filter(|s| s.active >= pool_size).count() as f64 / count,
+        })
+    }
+
+    /// What the run says about `DB_MAX_CONNECTIONS`
+    pub fn advice(&self) -> String {
+        if self.saturated >= 0.2 {
+            format!(
+                "The pool was saturated in {:.0}% of samples: requests queued for a connection. \
+                 Raise DB_MAX_CONNECTIONS above {} (within Postgres' max_connections, minus the \
+                 worker's) and rerun; if latency doesn't improve the database itself is the limit.",
+                self.saturated * 100.0,
+                self.pool_size
+            )
+        } else if self.peak_active * 2 <= self.pool_size {
+            format!(
+                "At most {} of {} connections were busy. This load doesn't need a larger pool; \
+                 DB_MAX_CONNECTIONS could come down to free connections for the worker.",
+                self.peak_active, self.pool_size
+            )
+        } else {
+            format!(
+                "The pool of {} kept up, saturated in {:.0}% of samples. \
+                 DB_MAX_CONNECTIONS fits this load.",
+                self.pool_size,
+                self.saturated * 100.0
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_mix_parse_and_pick() {
+        let mix = Mix::parse("save=1, list=2,search=1").unwrap();
+        assert_eq!(mix.total(), 4);
+        assert_eq!(mix.pick(0), Operation::Save);
+        assert_eq!(mix.pick(1), Operation::List);
+        assert_eq!(mix.pick(2), Operation::List);
+        assert_eq!(mix.pick(3), Operation::Search);
+
+        let reads = Mix::parse("list=1").unwrap();
+        assert_eq!(reads.pick(0), Operation::List);
+
+        assert!(Mix::parse("save=0").is_err());
+        assert!(Mix::parse("delete=1").is_err());
+        assert!(Mix::parse("save").is_err());
+    }
+
+    #[test]
+    fn test_latency_summary_percentiles() {
+        let samples: Vec<Duration> = (1..=100).rev().map(ms).collect();
+        let summary = LatencySummary::from_samples(&samples).unwrap();
+        assert_eq!(summary.count, 100);
+        assert_eq!(summary.p50, ms(50));
+        assert_eq!(summary.p90, ms(90));
+        assert_eq!(summary.p99, ms(99));
+        assert_eq!(summary.max, ms(100));
+
+        let one = LatencySummary::from_samples(&[ms(7)]).unwrap();
+        assert_eq!((one.p50, one.p99), (ms(7), ms(7)));
+        assert_eq!(LatencySummary::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_pool_usage_advice() {
+        let sample = |active| PoolSample { open: 5, active };
+
+        let busy = PoolUsage::from_samples(&[sample(5), sample(5), sample(3)], 5).unwrap();
+        assert_eq!(busy.peak_active, 5);
+        assert!((busy.saturated - 2.0 / 3.0).abs() < 1e-9);
+        assert!(busy.advice().contains("Raise DB_MAX_CONNECTIONS"));
+
+        let idle = PoolUsage::from_samples(&[sample(1), sample(2)], 5).unwrap();
+        assert_eq!(idle.mean_active, 1.5);
+        assert!(idle.advice().contains("doesn't need a larger pool"));
+
+        let fits = PoolUsage::from_samples(&[sample(4), sample(3)], 5).unwrap();
+        assert!(fits.advice().contains("fits this load"));
+
+        assert_eq!(PoolUsage::from_samples(&[], 5), None);
+    }
+}
@@ -1,3 +1,6 @@
 pub mod rate_limit;
+pub mod signed_url;
+pub mod throttle;
+pub mod transaction;
 
 pub use crate::auth::middleware::{AuthError, AuthenticatedUser};
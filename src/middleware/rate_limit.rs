@@ -1,18 +1,21 @@
 use axum::{
     Json,
-    extract::{ConnectInfo, Request},
+    extract::{ConnectInfo, Request, State},
     http::StatusCode,
     middleware::Next,
     response::{IntoResponse, Response},
 };
 use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
+use serde::Serialize;
 use std::{net::SocketAddr, sync::Arc};
+use utoipa::ToSchema;
 
 use crate::auth::dtos::ErrorResponse;
 
 #[derive(Clone)]
 pub struct RateLimit {
+    name: &'static str,
     store: Arc<DashMap<String, RateLimitData>>,
     max_requests: u32,
     window_seconds: i64,
@@ -24,43 +27,88 @@ struct RateLimitData {
     window_start: DateTime<Utc>,
 }
 
+/// Snapshot of a caller's budget under a single rate limit policy.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RateLimitStatus {
+    pub policy: String,
+    pub limit: u32,
+    pub remaining: u32,
+    pub window_seconds: i64,
+    pub reset_in_seconds: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RateLimitStatusResponse {
+    pub policies: Vec<RateLimitStatus>,
+}
+
 impl RateLimit {
-    pub fn new(max_requests: u32, window_seconds: i64) -> Self {
+    pub fn new(name: &'static str, max_requests: u32, window_seconds: i64) -> Self {
         Self {
+            name,
             store: Arc::new(DashMap::new()),
             max_requests,
             window_seconds,
         }
     }
+
+    /// Record a request for `key`, returning false once the budget is exhausted.
+    fn hit(&self, key: &str, now: DateTime<Utc>) -> bool {
+        let mut entry = self
+            .store
+            .entry(key.to_string())
+            .or_insert_with(|| RateLimitData {
+                count: 0,
+                window_start: now,
+            });
+
+        let data = entry.value_mut();
+
+        // Check if we need to reset the window
+        if now.signed_duration_since(data.window_start) >= Duration::seconds(self.window_seconds) {
+            data.count = 0;
+            data.window_start = now;
+        }
+
+        data.count += 1;
+
+        data.count <= self.max_requests
+    }
+
+    /// Report the remaining budget for `key` without consuming any of it.
+    pub fn status(&self, key: &str, now: DateTime<Utc>) -> RateLimitStatus {
+        let window = Duration::seconds(self.window_seconds);
+        let (used, reset_in_seconds) = match self.store.get(key) {
+            Some(data) if now.signed_duration_since(data.window_start) < window => {
+                let reset_at = data.window_start + window;
+                (
+                    data.count,
+                    reset_at.signed_duration_since(now).num_seconds(),
+                )
+            }
+            _ => (0, self.window_seconds),
+        };
+
+        RateLimitStatus {
+            policy: self.name.to_string(),
+            limit: self.max_requests,
+            remaining: self.max_requests.saturating_sub(used),
+            window_seconds: self.window_seconds,
+            reset_in_seconds,
+        }
+    }
 }
 
 /// IP-based rate limiting middleware.
 pub async fn rate_limit_middleware(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    axum::extract::State(rate_limit): axum::extract::State<RateLimit>,
+    State(rate_limit): State<RateLimit>,
     req: Request,
     next: Next,
 ) -> Response {
     let ip = addr.ip().to_string();
-    let now = Utc::now();
-
-    let mut entry = rate_limit.store.entry(ip).or_insert_with(|| RateLimitData {
-        count: 0,
-        window_start: now,
-    });
-
-    let data = entry.value_mut();
-
-    // Check if we need to reset the window
-    if now.signed_duration_since(data.window_start) >= Duration::seconds(rate_limit.window_seconds)
-    {
-        data.count = 0;
-        data.window_start = now;
-    }
-
-    data.count += 1;
 
-    if data.count > rate_limit.max_requests {
+    if !rate_limit.hit(&ip, Utc::now()) {
         return (
             StatusCode::TOO_MANY_REQUESTS,
             Json(ErrorResponse {
@@ -72,3 +120,73 @@ pub async fn rate_limit_middleware(
 
     next.run(req).await
 }
+
+#[utoipa::path(
+    get,
+    path = "/v1/rate-limit",
+    tag = "rate-limit",
+    responses(
+        (status = 200, description = "Current rate limit budgets for the caller", body = RateLimitStatusResponse)
+    )
+)]
+pub async fn rate_limit_status(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(policies): State<Vec<RateLimit>>,
+) -> Json<RateLimitStatusResponse> {
+    let ip = addr.ip().to_string();
+    let now = Utc::now();
+
+    Json(RateLimitStatusResponse {
+        policies: policies
+            .iter()
+            .map(|policy| policy.status(&ip, now))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_without_requests_has_full_budget() {
+        let rate_limit = RateLimit::new("auth", 10, 60);
+        let status = rate_limit.status("127.0.0.1", Utc::now());
+
+        assert_eq!(status.policy, "auth");
+        assert_eq!(status.limit, 10);
+        assert_eq!(status.remaining, 10);
+        assert_eq!(status.reset_in_seconds, 60);
+    }
+
+    #[test]
+    fn test_status_reflects_consumed_budget() {
+        let rate_limit = RateLimit::new("auth", 10, 60);
+        let now = Utc::now();
+        for _ in 0..3 {
+            assert!(rate_limit.hit("127.0.0.1", now));
+        }
+
+        let status = rate_limit.status("127.0.0.1", now + Duration::seconds(15));
+        assert_eq!(status.remaining, 7);
+        assert_eq!(status.reset_in_seconds, 45);
+
+        // Other callers are unaffected
+        assert_eq!(rate_limit.status("10.0.0.1", now).remaining, 10);
+    }
+
+    #[test]
+    fn test_hit_rejects_after_budget_and_resets_with_window() {
+        let rate_limit = RateLimit::new("auth", 2, 60);
+        let now = Utc::now();
+
+        assert!(rate_limit.hit("127.0.0.1", now));
+        assert!(rate_limit.hit("127.0.0.1", now));
+        assert!(!rate_limit.hit("127.0.0.1", now));
+        assert_eq!(rate_limit.status("127.0.0.1", now).remaining, 0);
+
+        let later = now + Duration::seconds(61);
+        assert_eq!(rate_limit.status("127.0.0.1", later).remaining, 2);
+        assert!(rate_limit.hit("127.0.0.1", later));
+    }
+}
@@ -0,0 +1,215 @@
+use axum::{
+    Json,
+    extract::{FromRequestParts, Request, State},
+    http::{StatusCode, header::RETRY_AFTER},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use sqlx::PgPool;
+use std::env;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::{
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    repositories::ThrottleRepository,
+};
+
+static ABUSE_CONFIG: Lazy<AbuseConfig> = Lazy::new(AbuseConfig::from_env);
+
+/// When saving looks abusive enough to pause a user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbuseConfig {
+    /// Items saved one at a time within a minute
+    pub max_saves_per_minute: i64,
+    /// Permanent fetch failures on a single site within `failure_window_secs`
+    pub max_domain_failures: i64,
+    pub failure_window_secs: i64,
+    /// How long an automatic throttle lasts
+    pub throttle_secs: i64,
+}
+
+impl Default for AbuseConfig {
+    fn default() -> Self {
+        Self {
+            max_saves_per_minute: 200,
+            max_domain_failures: 25,
+            failure_window_secs: 3600, // 1 hour
+            throttle_secs: 900,        // 15 minutes
+        }
+    }
+}
+
+impl AbuseConfig {
+    /// The thresholds configured for this process
+    pub fn global() -> &'static AbuseConfig {
+        &ABUSE_CONFIG
+    }
+
+    /// Read `ABUSE_MAX_SAVES_PER_MINUTE`, `ABUSE_MAX_DOMAIN_FAILURES`,
+    /// `ABUSE_FAILURE_WINDOW_SECS` and `ABUSE_THROTTLE_SECS`, keeping the
+    /// default for any that are missing or unparseable
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str, default: i64| {
+            env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+        Self {
+            max_saves_per_minute: var("ABUSE_MAX_SAVES_PER_MINUTE", defaults.max_saves_per_minute),
+            max_domain_failures: var("ABUSE_MAX_DOMAIN_FAILURES", defaults.max_domain_failures),
+            failure_window_secs: var("ABUSE_FAILURE_WINDOW_SECS", defaults.failure_window_secs),
+            throttle_secs: var("ABUSE_THROTTLE_SECS", defaults.throttle_secs),
+        }
+    }
+}
+
+/// Why recent activity warrants a throttle, if it does
+pub fn detect(
+    config: &AbuseConfig,
+    saves_last_minute: i64,
+    worst_failing_domain: Option<(&str, i64)>,
+) -> Option<String> {
+    if saves_last_minute >= config.max_saves_per_minute {
+        return Some(format!("{} saves in the last minute", saves_last_minute));
+    }
+    if let Some((domain, failures)) = worst_failing_domain
+        && failures >= config.max_domain_failures
+    {
+        return Some(format!("{} failed fetches from {}", failures, domain));
+    }
+    None
+}
+
+/// Refuse saves from throttled users with 429, throttling users whose recent
+/// activity trips [`AbuseConfig`]. Install it with `route_layer` on routes
+/// that create items. Unauthenticated requests pass through for the handler
+/// to reject.
+pub async fn save_throttle_middleware(
+    State(pool): State<PgPool>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let (mut parts, body) = req.into_parts();
+    let user = AuthenticatedUser::from_request_parts(&mut parts, &()).await;
+    let req = Request::from_parts(parts, body);
+    let Ok(user) = user else {
+        return next.run(req).await;
+    };
+
+    match check(&pool, user.user_id, AbuseConfig::global()).await {
+        Ok(None) => next.run(req).await,
+        Ok(Some((until, reason))) => throttled(until, &reason),
+        Err(e) => {
+            error!("Failed to check save throttle for {}: {}", user.user_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Database error".to_string(),
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Until when and why the user's saves are paused, throttling them first if
+/// their activity calls for it
+async fn check(
+    pool: &PgPool,
+    user_id: Uuid,
+    config: &AbuseConfig,
+) -> anyhow::Result<Option<(DateTime<Utc>, String)>> {
+    let repo = ThrottleRepository::new(pool);
+    let now = Utc::now();
+
+    if let Some(throttle) = repo.find(user_id).await? {
+        if throttle.exempt {
+            return Ok(None);
+        }
+        if let Some(until) = throttle.throttled_until
+            && until > now
+        {
+            return Ok(Some((until, throttle.reason.unwrap_or_default())));
+        }
+    }
+
+    let saves = repo
+        .saves_since(user_id, now - Duration::minutes(1))
+        .await?;
+    let failing = repo
+        .worst_failing_domain(user_id, now - Duration::seconds(config.failure_window_secs))
+        .await?;
+    let Some(reason) = detect(
+        config,
+        saves,
+        failing
+            .as_ref()
+            .map(|(domain, failures)| (domain.as_str(), *failures)),
+    ) else {
+        return Ok(None);
+    };
+
+    let until = now + Duration::seconds(config.throttle_secs);
+    if repo.throttle(user_id, until, &reason).await? {
+        warn!(
+            "Throttled saves for user {} until {}: {}",
+            user_id, until, reason
+        );
+        return Ok(Some((until, reason)));
+    }
+
+    // Exempted, or throttled by a concurrent request, in the meantime
+    Ok(repo
+        .find(user_id)
+        .await?
+        .filter(|throttle| !throttle.exempt)
+        .and_then(|throttle| {
+            Some((
+                throttle.throttled_until?,
+                throttle.reason.unwrap_or_default(),
+            ))
+        })
+        .filter(|(until, _)| *until > now))
+}
+
+fn throttled(until: DateTime<Utc>, reason: &str) -> Response {
+    let retry_after = (until - Utc::now()).num_seconds().max(1);
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(RETRY_AFTER, retry_after.to_string())],
+        Json(ErrorResponse {
+            error: format!("Saving is paused until {} ({})", until.to_rfc3339(), reason),
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_save_bursts() {
+        let config = AbuseConfig::default();
+        assert_eq!(detect(&config, 10, None), None);
+        assert_eq!(
+            detect(&config, config.max_saves_per_minute, None),
+            Some("200 saves in the last minute".to_string())
+        );
+    }
+
+    #[test]
+    fn test_detect_failing_domains() {
+        let config = AbuseConfig::default();
+        assert_eq!(detect(&config, 0, Some(("example.com", 3))), None);
+        assert_eq!(
+            detect(&config, 0, Some(("spam.example", 25))),
+            Some("25 failed fetches from spam.example".to_string())
+        );
+    }
+}
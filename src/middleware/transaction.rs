@@ -0,0 +1,102 @@
+use axum::{
+    Json,
+    extract::{FromRequestParts, Request, State},
+    http::{StatusCode, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use std::sync::Arc;
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+use tracing::error;
+
+use crate::auth::dtos::ErrorResponse;
+
+/// The database transaction wrapping the current request, opened by
+/// [`transaction_middleware`].
+///
+/// Handlers (and the repositories they call) take it as an extractor and run
+/// their queries on [`RequestTransaction::conn`]. The middleware commits once
+/// the handler returns a success or redirect, and rolls back on anything
+/// else, so multi-step handlers don't need their own begin/commit dance.
+#[derive(Clone)]
+pub struct RequestTransaction(Arc<Mutex<Option<Transaction<'static, Postgres>>>>);
+
+impl RequestTransaction {
+    /// Lock the transaction for a query or a batch of queries
+    pub async fn conn(&self) -> MappedMutexGuard<'_, PgConnection> {
+        MutexGuard::map(self.0.lock().await, |tx| {
+            &mut **tx
+                .as_mut()
+                .expect("request transaction used after the handler returned")
+        })
+    }
+
+    async fn take(&self) -> Option<Transaction<'static, Postgres>> {
+        self.0.lock().await.take()
+    }
+}
+
+impl<S> FromRequestParts<S> for RequestTransaction
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<RequestTransaction>()
+            .cloned()
+            .ok_or_else(|| {
+                error!("RequestTransaction extracted on a route without transaction_middleware");
+                database_error()
+            })
+    }
+}
+
+/// Run the request inside a database transaction, committing if the handler
+/// succeeds. Install it with `route_layer` on the mutating routes that need
+/// it; read-only routes don't pay for a transaction.
+pub async fn transaction_middleware(
+    State(pool): State<PgPool>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!("Failed to begin request transaction: {}", e);
+            return database_error();
+        }
+    };
+    let transaction = RequestTransaction(Arc::new(Mutex::new(Some(tx))));
+    req.extensions_mut().insert(transaction.clone());
+
+    let response = next.run(req).await;
+
+    let Some(tx) = transaction.take().await else {
+        return response;
+    };
+    let status = response.status();
+    if status.is_success() || status.is_redirection() {
+        if let Err(e) = tx.commit().await {
+            error!("Failed to commit request transaction: {}", e);
+            return database_error();
+        }
+    } else if let Err(e) = tx.rollback().await {
+        error!("Failed to roll back request transaction: {}", e);
+    }
+
+    response
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    entities::Notification,
+    query::{FieldError, ValidateQuery},
+};
+
+/// Maximum number of notifications returned by a single list request.
+pub const MAX_NOTIFICATIONS_PER_PAGE: i64 = 100;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListNotificationsQuery {
+    /// Only return notifications with an ID greater than this; the
+    /// `next_cursor` of the previous page, which may also be passed as
+    /// `cursor`
+    #[serde(alias = "cursor")]
+    pub after: Option<i64>,
+}
+
+impl ValidateQuery for ListNotificationsQuery {
+    fn validate(&self) -> Result<(), FieldError> {
+        match self.after {
+            Some(after) if after < 0 => Err(FieldError::new("after", "after cannot be negative")),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationResponse {
+    pub id: i64,
+    pub kind: String,
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Notification> for NotificationResponse {
+    fn from(notification: Notification) -> Self {
+        Self {
+            id: notification.id,
+            kind: notification.kind,
+            payload: notification.payload,
+            created_at: notification.created_at,
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures::stream;
+use sqlx::PgPool;
+use std::{collections::VecDeque, convert::Infallible, time::Duration};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    entities::Notification,
+    notifications::dtos::{
+        ListNotificationsQuery, MAX_NOTIFICATIONS_PER_PAGE, NotificationResponse,
+    },
+    pagination::Page,
+    query::{FieldError, ValidatedQuery},
+    repositories::NotificationRepository,
+};
+
+/// How often an open stream checks for new events
+const STREAM_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[utoipa::path(
+    get,
+    path = "/v1/notifications",
+    tag = "notifications",
+    params(ListNotificationsQuery),
+    responses(
+        (status = 200, description = "Notifications retrieved successfully", body = Page<NotificationResponse>),
+        (status = 400, description = "Invalid query parameter", body = FieldError),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_notifications(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ValidatedQuery(query): ValidatedQuery<ListNotificationsQuery>,
+) -> Response {
+    let repo = NotificationRepository::new(&state.db_pool);
+    let after = query.after.unwrap_or(0);
+    let notifications = match repo
+        .list_after(auth_user.user_id, after, MAX_NOTIFICATIONS_PER_PAGE)
+        .await
+    {
+        Ok(notifications) => notifications,
+        Err(_) => return database_error(),
+    };
+    let approximate_total = match repo.estimate_after(auth_user.user_id, after).await {
+        Ok(estimate) => estimate,
+        Err(e) => {
+            warn!("Failed to estimate notification count: {}", e);
+            None
+        }
+    };
+
+    let next_cursor = match notifications.last() {
+        Some(last) if notifications.len() as i64 >= MAX_NOTIFICATIONS_PER_PAGE => {
+            Some(last.id.to_string())
+        }
+        _ => None,
+    };
+    let page = Page::new(
+        notifications
+            .into_iter()
+            .map(NotificationResponse::from)
+            .collect(),
+        next_cursor,
+        approximate_total,
+    );
+
+    (StatusCode::OK, Json(page)).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/notifications/stream",
+    tag = "notifications",
+    params(
+        ("Last-Event-ID" = Option<String>, Header, description = "Resume after this event ID")
+    ),
+    responses(
+        (status = 200, description = "Server-sent stream of notification events", body = String, content_type = "text/event-stream"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn stream_notifications(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Response {
+    let resume_from = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<i64>().ok());
+
+    // Without a resume point only events from now on are streamed
+    let last_id = match resume_from {
+        Some(id) => id,
+        None => match NotificationRepository::new(&state.db_pool)
+            .latest_id(auth_user.user_id)
+            .await
+        {
+            Ok(id) => id,
+            Err(_) => return database_error(),
+        },
+    };
+
+    let events = notification_events(state.db_pool, auth_user.user_id, last_id);
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+fn notification_events(
+    pool: PgPool,
+    user_id: Uuid,
+    last_id: i64,
+) -> impl futures::Stream<Item = Result<Event, Infallible>> {
+    let pending: VecDeque<Notification> = VecDeque::new();
+
+    stream::unfold(
+        (pool, last_id, pending),
+        move |(pool, mut last_id, mut pending)| async move {
+            loop {
+                if let Some(notification) = pending.pop_front() {
+                    let event = to_event(notification);
+                    return Some((Ok(event), (pool, last_id, pending)));
+                }
+
+                tokio::time::sleep(STREAM_POLL_INTERVAL).await;
+
+                match NotificationRepository::new(&pool)
+                    .list_after(user_id, last_id, MAX_NOTIFICATIONS_PER_PAGE)
+                    .await
+                {
+                    Ok(batch) => {
+                        if let Some(last) = batch.last() {
+                            last_id = last.id;
+                        }
+                        pending.extend(batch);
+                    }
+                    Err(e) => warn!("Failed to poll notifications for {}: {}", user_id, e),
+                }
+            }
+        },
+    )
+}
+
+fn to_event(notification: Notification) -> Event {
+    let id = notification.id.to_string();
+    let kind = notification.kind.clone();
+
+    Event::default()
+        .id(id)
+        .event(kind)
+        .json_data(NotificationResponse::from(notification))
+        .unwrap_or_else(|_| Event::default().comment("failed to encode notification"))
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+    use chrono::Utc;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_to_event_includes_id_and_kind() {
+        let event = to_event(Notification {
+            id: 42,
+            user_id: Uuid::new_v4(),
+            kind: "quota_warning".to_string(),
+            payload: json!({"resource": "items", "percent": 80}),
+            email_pending: false,
+            created_at: Utc::now(),
+        });
+
+        let response = Sse::new(stream::iter([Ok::<_, Infallible>(event)])).into_response();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains("id: 42\n"));
+        assert!(body.contains("event: quota_warning\n"));
+        assert!(body.contains(r#""percent":80"#));
+    }
+}
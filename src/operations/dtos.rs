@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::entities::{Operation, OperationErrorSample, OperationKind, OperationState};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OperationResponse {
+    pub id: Uuid,
+    pub kind: OperationKind,
+    pub state: OperationState,
+    /// Inputs handled so far, including failed ones
+    pub processed: i32,
+    /// Null until the job knows how much work there is
+    pub total: Option<i32>,
+    pub error_count: i32,
+    /// The first few failures; `error_count` includes the rest
+    pub error_samples: Vec<OperationErrorSample>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl From<Operation> for OperationResponse {
+    fn from(operation: Operation) -> Self {
+        Self {
+            id: operation.id,
+            kind: operation.kind,
+            state: operation.state,
+            processed: operation.processed,
+            total: operation.total,
+            error_count: operation.error_count,
+            error_samples: operation.error_samples.0,
+            created_at: operation.created_at,
+            updated_at: operation.updated_at,
+            finished_at: operation.finished_at,
+        }
+    }
+}
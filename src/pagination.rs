@@ -0,0 +1,194 @@
+//! Common response envelope for paginated collections.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// One page of a collection
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Opaque cursor for the next page; absent on the last page. Pass it
+    /// back as the `cursor` (or endpoint-specific) query parameter.
+    pub next_cursor: Option<String>,
+    /// Rough size of the whole collection, for progress indicators. May be
+    /// an estimate from the query planner rather than an exact count, and is
+    /// absent when no estimate was available.
+    pub approximate_total: Option<i64>,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, next_cursor: Option<String>, approximate_total: Option<i64>) -> Self {
+        Self {
+            items,
+            next_cursor,
+            approximate_total,
+        }
+    }
+
+    /// Convert each item, keeping the cursor and total
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            approximate_total: self.approximate_total,
+        }
+    }
+}
+
+/// Offset-based cursor for endpoints that page by position: the next page
+/// starts at `offset + page_len`, if the page was full.
+pub fn next_offset_cursor(offset: i64, page_len: usize, limit: i64) -> Option<String> {
+    (page_len as i64 >= limit).then(|| (offset + page_len as i64).to_string())
+}
+
+/// Parse a cursor made by [`next_offset_cursor`]
+pub fn parse_offset_cursor(cursor: &str) -> Result<i64, String> {
+    cursor
+        .parse::<i64>()
+        .ok()
+        .filter(|offset| *offset >= 0)
+        .ok_or_else(|| "cursor is not valid for this endpoint".to_string())
+}
+
+/// Keyset cursor for collections ordered by a sort key, then `id`: the
+/// next page holds the rows sorting after this one. Unlike an offset it
+/// stays put when rows are added or removed ahead of it, and the database
+/// can seek straight to it through an index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeysetCursor {
+    pub key: SortKey,
+    pub id: Uuid,
+}
+
+/// The sort key of the row a [`KeysetCursor`] points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SortKey {
+    Time(DateTime<Utc>),
+    Number(i64),
+    Text(String),
+}
+
+impl SortKey {
+    /// The key as text Postgres can cast back to the key's column type
+    pub fn to_sql_text(&self) -> String {
+        match self {
+            SortKey::Time(time) => time.to_rfc3339_opts(SecondsFormat::Micros, true),
+            SortKey::Number(number) => number.to_string(),
+            SortKey::Text(text) => text.clone(),
+        }
+    }
+}
+
+impl KeysetCursor {
+    /// Cursor for a collection ordered by `created_at`
+    pub fn new(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self::with_key(SortKey::Time(created_at), id)
+    }
+
+    pub fn with_key(key: SortKey, id: Uuid) -> Self {
+        Self { key, id }
+    }
+
+    /// Opaque form handed to clients. Postgres keeps microseconds, so times
+    /// round-trip exactly.
+    pub fn encode(&self) -> String {
+        let key = match &self.key {
+            SortKey::Time(time) => time.timestamp_micros().to_string(),
+            SortKey::Number(number) => format!("n{}", number),
+            SortKey::Text(text) => format!("s{}", text),
+        };
+        hex::encode(format!("{}:{}", key, self.id))
+    }
+
+    /// Parse a cursor made by [`KeysetCursor::encode`]
+    pub fn parse(cursor: &str) -> Result<Self, String> {
+        let error = || "cursor is not valid for this endpoint".to_string();
+        let decoded = hex::decode(cursor).map_err(|_| error())?;
+        let decoded = String::from_utf8(decoded).map_err(|_| error())?;
+        // Text keys may hold colons; IDs never do
+        let (key, id) = decoded.rsplit_once(':').ok_or_else(error)?;
+        let key = if let Some(text) = key.strip_prefix('s') {
+            SortKey::Text(text.to_string())
+        } else if let Some(number) = key.strip_prefix('n') {
+            SortKey::Number(number.parse().map_err(|_| error())?)
+        } else {
+            SortKey::Time(
+                key.parse()
+                    .ok()
+                    .and_then(DateTime::from_timestamp_micros)
+                    .ok_or_else(error)?,
+            )
+        };
+        let id = id.parse().map_err(|_| error())?;
+        Ok(Self::with_key(key, id))
+    }
+}
+
+/// `EXPLAIN` for `sql`, whose single JSON row [`planner_estimate`] reads.
+/// Bind the same parameters as the query itself.
+pub fn explain(sql: &str) -> String {
+    format!("EXPLAIN (FORMAT JSON) {}", sql)
+}
+
+/// The planner's row estimate from an [`explain`] plan. Much cheaper than a
+/// `COUNT(*)` on large collections since nothing is executed; how close it
+/// gets depends on the table statistics.
+pub fn planner_estimate(plan: &serde_json::Value) -> Option<i64> {
+    plan[0]["Plan"]["Plan Rows"]
+        .as_f64()
+        .map(|rows| rows.round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_cursor_round_trip() {
+        assert_eq!(next_offset_cursor(0, 20, 20), Some("20".to_string()));
+        assert_eq!(next_offset_cursor(40, 7, 20), None);
+        assert_eq!(parse_offset_cursor("20"), Ok(20));
+        assert!(parse_offset_cursor("-1").is_err());
+        assert!(parse_offset_cursor("abc").is_err());
+    }
+
+    #[test]
+    fn test_keyset_cursor_round_trip() {
+        let cursor = KeysetCursor::new(
+            DateTime::from_timestamp_micros(1_759_000_000_123_456).unwrap(),
+            Uuid::new_v4(),
+        );
+        assert_eq!(KeysetCursor::parse(&cursor.encode()), Ok(cursor.clone()));
+        for key in [SortKey::Number(-3), SortKey::Text("a: b".to_string())] {
+            let cursor = KeysetCursor::with_key(key, cursor.id);
+            assert_eq!(KeysetCursor::parse(&cursor.encode()), Ok(cursor));
+        }
+        for invalid in [
+            "",
+            "20",
+            "zz",
+            &hex::encode("123"),
+            &hex::encode("x:y"),
+            &hex::encode(format!("nx:{}", Uuid::nil())),
+        ] {
+            assert!(KeysetCursor::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_page_map() {
+        let page = Page::new(vec![1, 2], Some("2".to_string()), Some(10)).map(|n| n * 10);
+        assert_eq!(page.items, vec![10, 20]);
+        assert_eq!(page.next_cursor.as_deref(), Some("2"));
+        assert_eq!(page.approximate_total, Some(10));
+    }
+
+    #[test]
+    fn test_planner_estimate() {
+        let plan = serde_json::json!([{"Plan": {"Node Type": "Seq Scan", "Plan Rows": 42}}]);
+        assert_eq!(planner_estimate(&plan), Some(42));
+        assert_eq!(planner_estimate(&serde_json::json!([])), None);
+    }
+}
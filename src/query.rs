@@ -0,0 +1,132 @@
+//! Typed, validated query-string parameters.
+//!
+//! List and search endpoints take their parameters as [`ValidatedQuery<T>`]
+//! instead of axum's `Query<T>`, so a bad parameter is reported as a 400 that
+//! names the offending field, whether it failed to parse (`limit=ten`, an
+//! unknown enum value) or failed the endpoint's own checks.
+
+use axum::{
+    Json,
+    extract::FromRequestParts,
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use utoipa::ToSchema;
+
+/// A query parameter that was missing, unparseable or out of range
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FieldError {
+    /// Name of the offending parameter
+    pub field: String,
+    /// What's wrong with it
+    pub error: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            error: error.into(),
+        }
+    }
+}
+
+impl IntoResponse for FieldError {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, Json(self)).into_response()
+    }
+}
+
+/// Checks a query type runs once it has been parsed
+pub trait ValidateQuery {
+    fn validate(&self) -> Result<(), FieldError>;
+}
+
+/// Query-string extractor that parses into `T` and runs its validation,
+/// rejecting with a [`FieldError`]
+#[derive(Debug, Clone)]
+pub struct ValidatedQuery<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned + ValidateQuery,
+    S: Send + Sync,
+{
+    type Rejection = FieldError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let value: T = parse(parts.uri.query().unwrap_or_default())?;
+        value.validate()?;
+        Ok(ValidatedQuery(value))
+    }
+}
+
+/// Parse a query string, naming the field that failed
+pub fn parse<T: DeserializeOwned>(query: &str) -> Result<T, FieldError> {
+    let deserializer =
+        serde_urlencoded::Deserializer::new(url::form_urlencoded::parse(query.as_bytes()));
+
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let path = e.path().to_string();
+        let error = e.inner().to_string();
+        // Missing fields are reported against the whole query
+        let field = match path.as_str() {
+            "." => missing_field(&error).unwrap_or("query").to_string(),
+            _ => path,
+        };
+        FieldError::new(field, error)
+    })
+}
+
+/// The field named in serde's "missing field `x`" error
+fn missing_field(error: &str) -> Option<&str> {
+    error
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split('`').next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Order {
+        Asc,
+        Desc,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Params {
+        q: String,
+        limit: Option<i64>,
+        order: Option<Order>,
+    }
+
+    #[test]
+    fn test_parse_valid() {
+        let params: Params = parse("q=rust&limit=5&order=desc").unwrap();
+        assert_eq!(params.q, "rust");
+        assert_eq!(params.limit, Some(5));
+        assert!(matches!(params.order, Some(Order::Desc)));
+        assert!(matches!(
+            parse::<Params>("q=x&order=asc").unwrap().order,
+            Some(Order::Asc)
+        ));
+    }
+
+    #[test]
+    fn test_parse_names_the_bad_field() {
+        let error = parse::<Params>("q=rust&limit=ten").unwrap_err();
+        assert_eq!(error.field, "limit");
+
+        let error = parse::<Params>("q=rust&order=sideways").unwrap_err();
+        assert_eq!(error.field, "order");
+        assert!(error.error.contains("unknown variant"));
+
+        let error = parse::<Params>("limit=5").unwrap_err();
+        assert_eq!(error.field, "q");
+    }
+}
@@ -0,0 +1,316 @@
+use crate::entities::Backup;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use serde_json::Value;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+
+/// A table included in backups
+#[derive(Debug)]
+pub struct BackupTable {
+    pub name: &'static str,
+    /// Primary key columns, comma-separated
+    pub key_columns: &'static str,
+    /// Whether rows carry an `updated_at` that incremental backups can
+    /// filter on; the rest are backed up in full every time
+    pub tracks_changes: bool,
+    /// Columns pointing at data the backup leaves out, cleared on export
+    pub detached: &'static [&'static str],
+}
+
+/// Everything a backup holds, parents before children so rows restore in
+/// order
+pub const BACKUP_TABLES: &[BackupTable] = &[
+    BackupTable {
+        name: "users",
+        key_columns: "id",
+        tracks_changes: false,
+        detached: &[],
+    },
+    BackupTable {
+        name: "documents",
+        key_columns: "id",
+        tracks_changes: true,
+        detached: &[],
+    },
+    BackupTable {
+        name: "tags",
+        key_columns: "id",
+        tracks_changes: false,
+        detached: &[],
+    },
+    BackupTable {
+        name: "items",
+        key_columns: "id",
+        tracks_changes: true,
+        // Import bookkeeping isn't backed up
+        detached: &["import_operation_id"],
+    },
+    BackupTable {
+        name: "contents",
+        key_columns: "item_id",
+        tracks_changes: true,
+        detached: &[],
+    },
+    BackupTable {
+        name: "highlights",
+        key_columns: "id",
+        tracks_changes: true,
+        detached: &[],
+    },
+    BackupTable {
+        name: "item_tags",
+        key_columns: "item_id, tag_id",
+        tracks_changes: false,
+        detached: &[],
+    },
+];
+
+pub fn backup_table(name: &str) -> Option<&'static BackupTable> {
+    BACKUP_TABLES.iter().find(|table| table.name == name)
+}
+
+/// Repository for backup bookkeeping and for reading and writing the tables
+/// backups cover. Table and column names in the SQL built here only ever come
+/// from [`BACKUP_TABLES`] and the database's own catalog.
+pub struct BackupRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> BackupRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// The most recent backup, which the next incremental one builds on
+    pub async fn latest(&self) -> Result<Option<Backup>> {
+        let backup = sqlx::query_as!(
+            Backup,
+            r#"
+            SELECT id, checksum, previous_checksum, file_name, since, until, row_count, created_at
+            FROM backups
+            ORDER BY until DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(backup)
+    }
+
+    /// Backups taken, newest first
+    pub async fn list(&self) -> Result<Vec<Backup>> {
+        let backups = sqlx::query_as!(
+            Backup,
+            r#"
+            SELECT id, checksum, previous_checksum, file_name, since, until, row_count, created_at
+            FROM backups
+            ORDER BY until DESC
+            "#
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(backups)
+    }
+
+    /// Remember a backup that was written out successfully
+    pub async fn record(
+        &self,
+        checksum: &str,
+        previous_checksum: Option<&str>,
+        file_name: &str,
+        since: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+        row_count: i64,
+    ) -> Result<Backup> {
+        let backup = sqlx::query_as!(
+            Backup,
+            r#"
+            INSERT INTO backups (checksum, previous_checksum, file_name, since, until, row_count)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, checksum, previous_checksum, file_name, since, until, row_count, created_at
+            "#,
+            checksum,
+            previous_checksum,
+            file_name,
+            since,
+            until,
+            row_count
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(backup)
+    }
+
+    /// A read-only transaction seeing one consistent snapshot of every
+    /// table, and the time the snapshot was taken
+    pub async fn snapshot(&self) -> Result<(Transaction<'a, Postgres>, DateTime<Utc>)> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+        let taken_at: DateTime<Utc> = sqlx::query_scalar!(r#"SELECT NOW() AS "now!""#)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        Ok((tx, taken_at))
+    }
+
+    /// Stream the table's rows as JSON objects, only those changed since
+    /// `since` when the table tracks changes
+    pub async fn dump_rows(
+        conn: &mut PgConnection,
+        table: &BackupTable,
+        since: Option<DateTime<Utc>>,
+        mut each: impl FnMut(Value) -> Result<()>,
+    ) -> Result<()> {
+        let since = since.filter(|_| table.tracks_changes);
+        let filter = if since.is_some() {
+            "WHERE t.updated_at >= $1"
+        } else {
+            ""
+        };
+        let sql = format!(
+            "SELECT to_jsonb(t) FROM {} t {} ORDER BY {}",
+            table.name, filter, table.key_columns
+        );
+
+        let mut query = sqlx::query_scalar::<_, Value>(&sql);
+        if let Some(since) = since {
+            query = query.bind(since);
+        }
+        let mut rows = query.fetch(conn);
+        while let Some(row) = rows.try_next().await? {
+            each(row)?;
+        }
+        Ok(())
+    }
+
+    /// Stream the keys of every row in the table, each as a JSON array of
+    /// its key columns, in chunks of `chunk_size`
+    pub async fn dump_keys(
+        conn: &mut PgConnection,
+        table: &BackupTable,
+        chunk_size: usize,
+        mut each: impl FnMut(Vec<Value>) -> Result<()>,
+    ) -> Result<()> {
+        let sql = format!(
+            "SELECT jsonb_build_array({keys}) FROM {table} ORDER BY {keys}",
+            keys = table.key_columns,
+            table = table.name
+        );
+
+        let mut chunk = Vec::with_capacity(chunk_size);
+        let mut keys = sqlx::query_scalar::<_, Value>(&sql).fetch(conn);
+        while let Some(key) = keys.try_next().await? {
+            chunk.push(key);
+            if chunk.len() >= chunk_size {
+                each(std::mem::take(&mut chunk))?;
+            }
+        }
+        if !chunk.is_empty() {
+            each(chunk)?;
+        }
+        Ok(())
+    }
+
+    /// The table's columns as the database has them, in order. Generated
+    /// columns are left out; the database computes them again on restore.
+    pub async fn columns(conn: &mut PgConnection, table: &BackupTable) -> Result<Vec<String>> {
+        let columns = sqlx::query_scalar!(
+            r#"
+            SELECT column_name::text AS "column_name!"
+            FROM information_schema.columns
+            WHERE table_schema = current_schema() AND table_name = $1
+              AND is_generated = 'NEVER'
+            ORDER BY ordinal_position
+            "#,
+            table.name
+        )
+        .fetch_all(conn)
+        .await?;
+
+        Ok(columns)
+    }
+
+    /// Insert a backed-up row, or overwrite the row with its key.
+    /// `columns` is the table's column list from [`columns`](Self::columns).
+    pub async fn restore_row(
+        conn: &mut PgConnection,
+        table: &BackupTable,
+        columns: &[String],
+        row: &Value,
+    ) -> Result<()> {
+        let keys: Vec<&str> = table.key_columns.split(',').map(str::trim).collect();
+        let updates: Vec<String> = columns
+            .iter()
+            .filter(|column| !keys.contains(&column.as_str()))
+            .map(|column| format!("\"{column}\" = EXCLUDED.\"{column}\""))
+            .collect();
+        let on_conflict = if updates.is_empty() {
+            "DO NOTHING".to_string()
+        } else {
+            format!("DO UPDATE SET {}", updates.join(", "))
+        };
+        let columns = columns
+            .iter()
+            .map(|column| format!("\"{column}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "INSERT INTO {table} ({columns}) \
+             SELECT {columns} FROM jsonb_populate_record(NULL::{table}, $1) \
+             ON CONFLICT ({keys}) {on_conflict}",
+            table = table.name,
+            keys = table.key_columns,
+        );
+
+        sqlx::query(&sql).bind(row).execute(conn).await?;
+        Ok(())
+    }
+
+    /// Delete the table's rows whose keys aren't in `keep`, returning how
+    /// many went
+    pub async fn prune(
+        conn: &mut PgConnection,
+        table: &BackupTable,
+        keep: &[Value],
+    ) -> Result<u64> {
+        let sql = format!(
+            "DELETE FROM {table} WHERE jsonb_build_array({keys}) NOT IN \
+             (SELECT value FROM jsonb_array_elements($1))",
+            table = table.name,
+            keys = table.key_columns,
+        );
+
+        let result = sqlx::query(&sql)
+            .bind(Value::Array(keep.to_vec()))
+            .execute(conn)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_tables_come_after_their_parents() {
+        let position = |name| {
+            BACKUP_TABLES
+                .iter()
+                .position(|table| table.name == name)
+                .unwrap()
+        };
+        assert!(position("users") < position("items"));
+        assert!(position("documents") < position("items"));
+        assert!(position("items") < position("contents"));
+        assert!(position("items") < position("highlights"));
+        assert!(position("tags") < position("item_tags"));
+        assert!(backup_table("jobs").is_none());
+    }
+}
@@ -0,0 +1,68 @@
+//! zstd compression for stored page HTML.
+//!
+//! Article HTML compresses 5-10x and is most of the database, so the
+//! repositories write it to the `*_html_zst` columns and decompress on read.
+//! Rows stored before compression keep their HTML in the plain TEXT columns
+//! until the `compress_html` job gets to them; reads accept either.
+
+use anyhow::{Context, Result};
+
+/// Level used for stored HTML. Pages are written once and read many times,
+/// so a little more effort than zstd's default pays off.
+pub const HTML_COMPRESSION_LEVEL: i32 = 9;
+
+pub fn compress_html(html: &str) -> Result<Vec<u8>> {
+    zstd::encode_all(html.as_bytes(), HTML_COMPRESSION_LEVEL).context("Failed to compress HTML")
+}
+
+pub fn decompress_html(compressed: &[u8]) -> Result<String> {
+    let bytes = zstd::decode_all(compressed).context("Failed to decompress HTML")?;
+    String::from_utf8(bytes).context("Decompressed HTML is not UTF-8")
+}
+
+/// The stored HTML from whichever column holds it: `plain` for rows not yet
+/// compressed, `compressed` otherwise
+pub fn stored_html(plain: Option<String>, compressed: Option<&[u8]>) -> Result<Option<String>> {
+    match (plain, compressed) {
+        (Some(html), _) => Ok(Some(html)),
+        (None, Some(compressed)) => decompress_html(compressed).map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Compress an optional HTML body for binding to a `*_html_zst` column
+pub fn compress_optional(html: Option<&str>) -> Result<Option<Vec<u8>>> {
+    html.map(compress_html).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let html = "<article><p>Hello, 世界!</p></article>".repeat(100);
+        let compressed = compress_html(&html).unwrap();
+        assert!(compressed.len() < html.len() / 5);
+        assert_eq!(decompress_html(&compressed).unwrap(), html);
+    }
+
+    #[test]
+    fn test_stored_html_prefers_plain_column() {
+        let compressed = compress_html("<p>new</p>").unwrap();
+        assert_eq!(
+            stored_html(Some("<p>old</p>".to_string()), Some(&compressed)).unwrap(),
+            Some("<p>old</p>".to_string())
+        );
+        assert_eq!(
+            stored_html(None, Some(&compressed)).unwrap(),
+            Some("<p>new</p>".to_string())
+        );
+        assert_eq!(stored_html(None, None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_decompress_garbage() {
+        assert!(decompress_html(b"not zstd").is_err());
+    }
+}
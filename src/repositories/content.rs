@@ -1,10 +1,121 @@
-use crate::entities::Content;
+use crate::{
+    entities::Content,
+    extractor::Heading,
+    fetcher::persisted_headers,
+    repositories::compression::{compress_html, compress_optional, stored_html},
+};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use md5::Context;
-use sqlx::PgPool;
+use reqwest::header::HeaderMap;
+use sqlx::{FromRow, PgPool, types::Json};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
+/// Cleaned content projection used by read paths that don't need the raw page.
+#[derive(Debug, Clone, FromRow)]
+pub struct CleanContent {
+    pub item_id: Uuid,
+    pub clean_html: Option<String>,
+    pub clean_text: Option<String>,
+    pub lang: Option<String>,
+    pub extracted_at: Option<DateTime<Utc>>,
+    pub outline: Option<Json<Vec<Heading>>>,
+    pub summary: Option<String>,
+}
+
+/// What a summarizer gets to read for an item
+#[derive(Debug, Clone, FromRow)]
+pub struct SummarySource {
+    pub title: Option<String>,
+    pub text: Option<String>,
+}
+
+/// [`CleanContent`] as stored, with the HTML in whichever of its columns
+/// holds it
+#[derive(FromRow)]
+struct StoredCleanContent {
+    item_id: Uuid,
+    clean_html: Option<String>,
+    clean_html_zst: Option<Vec<u8>>,
+    clean_text: Option<String>,
+    lang: Option<String>,
+    extracted_at: Option<DateTime<Utc>>,
+    outline: Option<Json<Vec<Heading>>>,
+    summary: Option<String>,
+}
+
+impl TryFrom<StoredCleanContent> for CleanContent {
+    type Error = anyhow::Error;
+
+    fn try_from(row: StoredCleanContent) -> Result<Self> {
+        Ok(Self {
+            item_id: row.item_id,
+            clean_html: stored_html(row.clean_html, row.clean_html_zst.as_deref())?,
+            clean_text: row.clean_text,
+            lang: row.lang,
+            extracted_at: row.extracted_at,
+            outline: row.outline,
+            summary: row.summary,
+        })
+    }
+}
+
+/// [`Content`] as stored
+#[derive(FromRow)]
+struct StoredContent {
+    item_id: Uuid,
+    raw_html: Option<String>,
+    raw_html_zst: Option<Vec<u8>>,
+    raw_text: Option<String>,
+    clean_html: Option<String>,
+    clean_html_zst: Option<Vec<u8>>,
+    clean_text: Option<String>,
+    lang: Option<String>,
+    extracted_at: Option<DateTime<Utc>>,
+    checksum: Option<String>,
+    response_headers: Option<Json<BTreeMap<String, String>>>,
+}
+
+impl TryFrom<StoredContent> for Content {
+    type Error = anyhow::Error;
+
+    fn try_from(row: StoredContent) -> Result<Self> {
+        Ok(Self {
+            item_id: row.item_id,
+            raw_html: stored_html(row.raw_html, row.raw_html_zst.as_deref())?,
+            raw_text: row.raw_text,
+            clean_html: stored_html(row.clean_html, row.clean_html_zst.as_deref())?,
+            clean_text: row.clean_text,
+            lang: row.lang,
+            extracted_at: row.extracted_at,
+            checksum: row.checksum,
+            response_headers: row.response_headers.map(|Json(headers)| headers),
+        })
+    }
+}
+
+/// Which parts of the cleaned content a read should load. Unselected columns
+/// come back as NULL without being read, so clients that only want metadata
+/// don't pay for large HTML bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentFields {
+    /// Language, extraction time and summary
+    pub metadata: bool,
+    /// Clean text
+    pub text: bool,
+    /// Clean HTML and its outline
+    pub html: bool,
+}
+
+impl ContentFields {
+    pub const ALL: Self = Self {
+        metadata: true,
+        text: true,
+        html: true,
+    };
+}
+
 /// Repository for managing content persistence with checksum-based deduplication
 pub struct ContentRepository<'a> {
     pool: &'a PgPool,
@@ -16,7 +127,7 @@ impl<'a> ContentRepository<'a> {
     }
 
     /// Upsert content using checksum to avoid unnecessary writes when content hasn't changed.
-    /// Large payloads are handled efficiently by streaming to the database.
+    /// The HTML is stored compressed.
     pub async fn upsert_content(
         &self,
         item_id: Uuid,
@@ -39,21 +150,55 @@ impl<'a> ContentRepository<'a> {
         sqlx::query!(
             r#"
             INSERT INTO contents
-                  (item_id, clean_html, clean_text, lang, extracted_at, checksum)
-            VALUES ($1,       $2,         $3,         $4,   $5,          $6)
+                  (item_id, clean_html_zst, clean_text, lang, extracted_at, checksum)
+            VALUES ($1,       $2,             $3,         $4,   $5,          $6)
             ON CONFLICT (item_id) DO UPDATE
-              SET clean_html   = EXCLUDED.clean_html,
-                  clean_text   = EXCLUDED.clean_text,
-                  lang         = EXCLUDED.lang,
-                  extracted_at = EXCLUDED.extracted_at,
-                  checksum     = EXCLUDED.checksum
+              SET clean_html     = NULL,
+                  clean_html_zst = EXCLUDED.clean_html_zst,
+                  clean_text     = EXCLUDED.clean_text,
+                  lang           = EXCLUDED.lang,
+                  extracted_at   = EXCLUDED.extracted_at,
+                  checksum       = EXCLUDED.checksum
             "#,
             item_id,
-            clean_html,
+            compress_html(clean_html)?,
             clean_text,
             lang,
             extracted_at,
+            checksum
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store the page as fetched, compressed, ahead of extraction, with the
+    /// response headers worth keeping
+    pub async fn upsert_raw(
+        &self,
+        item_id: Uuid,
+        raw_html: &str,
+        checksum: &str,
+        headers: &HeaderMap,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO contents
+                  (item_id, raw_html_zst, raw_text, lang, extracted_at, checksum, response_headers)
+            VALUES ($1, $2, NULL, NULL, NOW(), $3, $4)
+            ON CONFLICT (item_id)
+            DO UPDATE SET
+                raw_html = NULL,
+                raw_html_zst = EXCLUDED.raw_html_zst,
+                extracted_at = EXCLUDED.extracted_at,
+                checksum = EXCLUDED.checksum,
+                response_headers = EXCLUDED.response_headers
+            "#,
+            item_id,
+            compress_html(raw_html)?,
             checksum,
+            Json(persisted_headers(headers)) as _
         )
         .execute(self.pool)
         .await?;
@@ -63,16 +208,104 @@ impl<'a> ContentRepository<'a> {
 
     /// Get content by item ID
     pub async fn get_content(&self, item_id: Uuid) -> Result<Option<Content>> {
-        let content = sqlx::query_as!(
-            Content,
-            "SELECT item_id, raw_html, raw_text, clean_html, clean_text, lang, extracted_at, checksum
-             FROM contents WHERE item_id = $1",
+        let row = sqlx::query_as!(
+            StoredContent,
+            r#"
+            SELECT item_id, raw_html, raw_html_zst, raw_text, clean_html, clean_html_zst,
+                   clean_text, lang, extracted_at, checksum,
+                   response_headers AS "response_headers: Json<BTreeMap<String, String>>"
+            FROM contents WHERE item_id = $1
+            "#,
+            item_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        row.map(Content::try_from).transpose()
+    }
+
+    /// Get cleaned content for several items at once, restricted to items owned by `user_id`.
+    /// Items that don't exist, belong to someone else, or have no content are simply absent.
+    /// Columns outside `fields` are NULL.
+    pub async fn get_clean_contents_for_user(
+        &self,
+        user_id: Uuid,
+        item_ids: &[Uuid],
+        fields: ContentFields,
+    ) -> Result<Vec<CleanContent>> {
+        // Postgres only detoasts the large columns in the branches taken.
+        // An item's own copy wins over the document's, whichever column of
+        // each holds the HTML.
+        let rows = sqlx::query_as!(
+            StoredCleanContent,
+            r#"
+            SELECT c.item_id,
+                   CASE WHEN $5 THEN
+                       CASE WHEN c.clean_html IS NOT NULL OR c.clean_html_zst IS NOT NULL
+                            THEN c.clean_html ELSE d.clean_html END
+                   END AS clean_html,
+                   CASE WHEN $5 THEN
+                       CASE WHEN c.clean_html IS NOT NULL OR c.clean_html_zst IS NOT NULL
+                            THEN c.clean_html_zst ELSE d.clean_html_zst END
+                   END AS clean_html_zst,
+                   CASE WHEN $4 THEN COALESCE(c.clean_text, d.clean_text) END AS clean_text,
+                   CASE WHEN $3 THEN c.lang END AS lang,
+                   CASE WHEN $3 THEN c.extracted_at END AS extracted_at,
+                   CASE WHEN $5 THEN c.outline END AS "outline: Json<Vec<Heading>>",
+                   CASE WHEN $3 THEN c.summary END AS summary
+            FROM contents c
+            JOIN items i ON i.id = c.item_id
+            LEFT JOIN documents d ON d.id = i.document_id
+            WHERE i.user_id = $1 AND c.item_id = ANY($2)
+            "#,
+            user_id,
+            item_ids,
+            fields.metadata,
+            fields.text,
+            fields.html
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        rows.into_iter().map(CleanContent::try_from).collect()
+    }
+
+    /// The item's title and extracted text, from the shared document when
+    /// the item points at one. Returns None if the item has no content.
+    pub async fn summary_source(&self, item_id: Uuid) -> Result<Option<SummarySource>> {
+        let source = sqlx::query_as!(
+            SummarySource,
+            r#"
+            SELECT i.title, COALESCE(c.clean_text, d.clean_text) AS text
+            FROM contents c
+            JOIN items i ON i.id = c.item_id
+            LEFT JOIN documents d ON d.id = i.document_id
+            WHERE c.item_id = $1
+            "#,
             item_id
         )
         .fetch_optional(self.pool)
         .await?;
 
-        Ok(content)
+        Ok(source)
+    }
+
+    /// Store the summary of an item's content, replacing any earlier one
+    pub async fn set_summary(&self, item_id: Uuid, summary: &str, provider: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE contents
+            SET summary = $2, summary_provider = $3, summarized_at = NOW()
+            WHERE item_id = $1
+            "#,
+            item_id,
+            summary,
+            provider
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
     }
 
     /// Delete content by item ID
@@ -84,6 +317,46 @@ impl<'a> ContentRepository<'a> {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Compress up to `limit` items' HTML still stored in the plain columns,
+    /// returning how many were compressed
+    pub async fn compress_stored_html(&self, limit: i64) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT item_id, raw_html, clean_html
+            FROM contents
+            WHERE raw_html IS NOT NULL OR clean_html IS NOT NULL
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+            "#,
+            limit
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for row in &rows {
+            sqlx::query!(
+                r#"
+                UPDATE contents
+                SET raw_html       = NULL,
+                    raw_html_zst   = COALESCE($2, raw_html_zst),
+                    clean_html     = NULL,
+                    clean_html_zst = COALESCE($3, clean_html_zst)
+                WHERE item_id = $1
+                "#,
+                row.item_id,
+                compress_optional(row.raw_html.as_deref())?,
+                compress_optional(row.clean_html.as_deref())?
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(rows.len() as u64)
+    }
+
     /// Compute MD5 checksum from normalized content
     fn compute_checksum(&self, clean_html: &str, clean_text: &str) -> String {
         let mut hasher = Context::new();
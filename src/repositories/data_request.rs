@@ -0,0 +1,297 @@
+use crate::entities::{DataRequest, OperationState};
+use anyhow::Result;
+use serde_json::Value;
+use sqlx::{PgConnection, PgPool};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Tables left out of data packages: the requests themselves hold earlier
+/// packages
+const PACKAGE_EXCLUDED_TABLES: &[&str] = &["data_requests"];
+
+/// Columns never handed out, even to the user they describe
+const PACKAGE_REDACTED_COLUMNS: &[(&str, &str)] = &[("users", "pw_hash")];
+
+/// A single-column foreign key, as found in the catalog
+#[derive(Debug, Clone)]
+struct ForeignKey {
+    table: String,
+    column: String,
+    parent: String,
+    parent_column: String,
+}
+
+/// Repository for subject access requests and the data packages built for
+/// them
+pub struct DataRequestRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> DataRequestRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a request on the caller's connection, so it commits with the
+    /// job that builds its package
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        subject_id: Uuid,
+        requested_by: Uuid,
+        reason: &str,
+    ) -> Result<DataRequest> {
+        let request = sqlx::query_as!(
+            DataRequest,
+            r#"
+            INSERT INTO data_requests (subject_id, requested_by, reason)
+            VALUES ($1, $2, $3)
+            RETURNING id, subject_id, requested_by, reason, state AS "state: OperationState",
+                      error, created_at, finished_at
+            "#,
+            subject_id,
+            requested_by,
+            reason
+        )
+        .fetch_one(conn)
+        .await?;
+
+        Ok(request)
+    }
+
+    pub async fn find(&self, id: Uuid) -> Result<Option<DataRequest>> {
+        let request = sqlx::query_as!(
+            DataRequest,
+            r#"
+            SELECT id, subject_id, requested_by, reason, state AS "state: OperationState",
+                   error, created_at, finished_at
+            FROM data_requests
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(request)
+    }
+
+    /// Every request for the user's data, newest first
+    pub async fn list_for_subject(&self, subject_id: Uuid) -> Result<Vec<DataRequest>> {
+        let requests = sqlx::query_as!(
+            DataRequest,
+            r#"
+            SELECT id, subject_id, requested_by, reason, state AS "state: OperationState",
+                   error, created_at, finished_at
+            FROM data_requests
+            WHERE subject_id = $1
+            ORDER BY created_at DESC
+            "#,
+            subject_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(requests)
+    }
+
+    pub async fn start(&self, id: Uuid) -> Result<()> {
+        sqlx::query!(
+            "UPDATE data_requests SET state = 'running' WHERE id = $1",
+            id
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Store the finished package
+    pub async fn complete(&self, id: Uuid, package: &Value) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE data_requests
+            SET state = $2, package = $3, error = NULL, finished_at = NOW()
+            WHERE id = $1
+            "#,
+            id,
+            OperationState::Succeeded as OperationState,
+            package
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fail(&self, id: Uuid, error: &str) -> Result<()> {
+        sqlx::query!(
+            "UPDATE data_requests SET state = $2, error = $3, finished_at = NOW() WHERE id = $1",
+            id,
+            OperationState::Failed as OperationState,
+            error
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The request's package; None until it's been built
+    pub async fn package(&self, id: Uuid) -> Result<Option<Value>> {
+        let package: Option<Option<Value>> =
+            sqlx::query_scalar!("SELECT package FROM data_requests WHERE id = $1", id)
+                .fetch_optional(self.pool)
+                .await?;
+
+        Ok(package.flatten())
+    }
+
+    /// Every row referencing the user, by table: the user's own row, rows
+    /// with a foreign key to it, and rows with a foreign key to those (an
+    /// item's content, say). Tables are found from the schema's foreign keys
+    /// so new ones are covered without changes here. Read from one snapshot.
+    pub async fn user_rows(&self, subject_id: Uuid) -> Result<BTreeMap<String, Vec<Value>>> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+
+        let foreign_keys = sqlx::query_as!(
+            ForeignKey,
+            r#"
+            SELECT child.relname::text AS "table!", a.attname::text AS "column!",
+                   parent.relname::text AS "parent!", pa.attname::text AS "parent_column!"
+            FROM pg_constraint c
+            JOIN pg_class child ON child.oid = c.conrelid
+            JOIN pg_class parent ON parent.oid = c.confrelid
+            JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = c.conkey[1]
+            JOIN pg_attribute pa ON pa.attrelid = c.confrelid AND pa.attnum = c.confkey[1]
+            WHERE c.contype = 'f'
+              AND cardinality(c.conkey) = 1
+              AND child.relnamespace = current_schema()::regnamespace
+            "#
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut rows = BTreeMap::new();
+        for (table, sql) in package_queries(&foreign_keys) {
+            let table_rows: Vec<Value> = sqlx::query_scalar(&sql)
+                .bind(subject_id)
+                .fetch_all(&mut *tx)
+                .await?;
+            rows.insert(table, table_rows);
+        }
+        tx.commit().await?;
+
+        Ok(rows)
+    }
+}
+
+/// One query per table for [`DataRequestRepository::user_rows`], each
+/// taking the user's ID as `$1` and returning rows as JSON objects
+fn package_queries(foreign_keys: &[ForeignKey]) -> Vec<(String, String)> {
+    let included = |table: &str| table != "users" && !PACKAGE_EXCLUDED_TABLES.contains(&table);
+
+    // Tables pointing at users, with how each row names its user
+    let mut direct: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for fk in foreign_keys {
+        if fk.parent == "users" && fk.parent_column == "id" && included(&fk.table) {
+            direct
+                .entry(fk.table.as_str())
+                .or_default()
+                .push(format!("{{alias}}.\"{}\" = $1", fk.column));
+        }
+    }
+    let owned_by_user = |table: &str, alias: &str| {
+        direct[table]
+            .iter()
+            .map(|condition| condition.replace("{alias}", alias))
+            .collect::<Vec<_>>()
+            .join(" OR ")
+    };
+
+    let mut conditions: BTreeMap<&str, Vec<String>> = direct
+        .keys()
+        .map(|table| (*table, vec![owned_by_user(table, "t")]))
+        .collect();
+
+    // Tables pointing at those, for rows hanging off the user's rows
+    for fk in foreign_keys {
+        if direct.contains_key(fk.parent.as_str())
+            && !direct.contains_key(fk.table.as_str())
+            && included(&fk.table)
+        {
+            conditions
+                .entry(fk.table.as_str())
+                .or_default()
+                .push(format!(
+                    "t.\"{}\" IN (SELECT p.\"{}\" FROM \"{}\" p WHERE {})",
+                    fk.column,
+                    fk.parent_column,
+                    fk.parent,
+                    owned_by_user(&fk.parent, "p")
+                ));
+        }
+    }
+    conditions.insert("users", vec!["t.\"id\" = $1".to_string()]);
+
+    conditions
+        .into_iter()
+        .map(|(table, conditions)| {
+            let redacted: String = PACKAGE_REDACTED_COLUMNS
+                .iter()
+                .filter(|(redacted_table, _)| *redacted_table == table)
+                .map(|(_, column)| format!(" - '{column}'"))
+                .collect();
+            let sql = format!(
+                "SELECT to_jsonb(t){redacted} FROM \"{table}\" t WHERE {}",
+                conditions.join(" OR ")
+            );
+            (table.to_string(), sql)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fk(table: &str, column: &str, parent: &str) -> ForeignKey {
+        ForeignKey {
+            table: table.to_string(),
+            column: column.to_string(),
+            parent: parent.to_string(),
+            parent_column: "id".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_package_queries_follow_foreign_keys() {
+        let queries: BTreeMap<String, String> = package_queries(&[
+            fk("items", "user_id", "users"),
+            fk("abuse_events", "user_id", "users"),
+            fk("abuse_events", "actor_id", "users"),
+            fk("contents", "item_id", "items"),
+            fk("items", "document_id", "documents"),
+            fk("data_requests", "requested_by", "users"),
+        ])
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            queries.keys().collect::<Vec<_>>(),
+            ["abuse_events", "contents", "items", "users"]
+        );
+        assert_eq!(
+            queries["abuse_events"],
+            "SELECT to_jsonb(t) FROM \"abuse_events\" t WHERE t.\"user_id\" = $1 OR t.\"actor_id\" = $1"
+        );
+        assert_eq!(
+            queries["contents"],
+            "SELECT to_jsonb(t) FROM \"contents\" t WHERE t.\"item_id\" IN \
+             (SELECT p.\"id\" FROM \"items\" p WHERE p.\"user_id\" = $1)"
+        );
+        assert_eq!(
+            queries["users"],
+            "SELECT to_jsonb(t) - 'pw_hash' FROM \"users\" t WHERE t.\"id\" = $1"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{repositories::compression::compress_optional, urlnorm::canonicalize};
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Hash identifying a page in the shared document store: the MD5 of its
+/// normalized URL, so equivalent links land on the same document
+pub fn url_hash(url: &str) -> Option<String> {
+    canonicalize(url).map(|key| format!("{:x}", md5::compute(key)))
+}
+
+/// Repository for the shared store of extracted content. Items whose owner
+/// allows sharing point at a document instead of keeping their own copy.
+pub struct DocumentRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> DocumentRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Whether the user lets their content go to the shared store. Sharing is
+    /// on unless the `share_content` preference turns it off.
+    pub async fn sharing_enabled(&self, user_id: Uuid) -> Result<bool> {
+        let enabled: Option<bool> = sqlx::query_scalar!(
+            r#"SELECT COALESCE((prefs->>'share_content')::boolean, TRUE) AS "enabled!" FROM users WHERE id = $1"#,
+            user_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(enabled.unwrap_or(false))
+    }
+
+    /// Move an item's freshly stored content into the document for `url_hash`
+    /// and point the item at it. Does nothing when the owner has opted out or
+    /// the item has no extracted content; returns whether the item was linked.
+    pub async fn share(&self, item_id: Uuid, url_hash: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let enabled: Option<bool> = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE((u.prefs->>'share_content')::boolean, TRUE) AS "enabled!"
+            FROM items i
+            JOIN users u ON u.id = i.user_id
+            WHERE i.id = $1
+            "#,
+            item_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if enabled != Some(true) {
+            return Ok(false);
+        }
+
+        // The latest extraction of a URL replaces the shared copy for everyone
+        let document_id: Option<Uuid> = sqlx::query_scalar!(
+            r#"
+            INSERT INTO documents
+                  (url_hash, raw_html, raw_html_zst, clean_html, clean_html_zst,
+                   clean_text, lang, checksum, extracted_at)
+            SELECT $2, raw_html, raw_html_zst, clean_html, clean_html_zst,
+                   clean_text, lang, checksum, extracted_at
+            FROM contents
+            WHERE item_id = $1 AND clean_text IS NOT NULL
+            ON CONFLICT (url_hash) DO UPDATE
+              SET raw_html       = EXCLUDED.raw_html,
+                  raw_html_zst   = EXCLUDED.raw_html_zst,
+                  clean_html     = EXCLUDED.clean_html,
+                  clean_html_zst = EXCLUDED.clean_html_zst,
+                  clean_text     = EXCLUDED.clean_text,
+                  lang           = EXCLUDED.lang,
+                  checksum       = EXCLUDED.checksum,
+                  extracted_at   = EXCLUDED.extracted_at
+            RETURNING id
+            "#,
+            item_id,
+            url_hash
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(document_id) = document_id else {
+            return Ok(false);
+        };
+
+        sqlx::query!(
+            "UPDATE items SET document_id = $2 WHERE id = $1",
+            item_id,
+            document_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Keep lang, checksum and timestamps per item; the bodies now live
+        // in the document
+        sqlx::query!(
+            r#"
+            UPDATE contents
+            SET raw_html = NULL, raw_html_zst = NULL,
+                clean_html = NULL, clean_html_zst = NULL,
+                clean_text = NULL
+            WHERE item_id = $1
+            "#,
+            item_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Give each of a user's shared items its own copy of the content again
+    /// and unlink it, e.g. after they opt out of sharing. Returns how many
+    /// items were unlinked.
+    pub async fn unshare_user(&self, user_id: Uuid) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE contents c
+            SET raw_html       = d.raw_html,
+                raw_html_zst   = d.raw_html_zst,
+                clean_html     = d.clean_html,
+                clean_html_zst = d.clean_html_zst,
+                clean_text     = d.clean_text
+            FROM items i
+            JOIN documents d ON d.id = i.document_id
+            WHERE c.item_id = i.id AND i.user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let unlinked = sqlx::query!(
+            "UPDATE items SET document_id = NULL WHERE user_id = $1 AND document_id IS NOT NULL",
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+
+        self.prune_orphans().await?;
+        Ok(unlinked)
+    }
+
+    /// Compress up to `limit` documents' HTML still stored in the plain
+    /// columns, returning how many were compressed
+    pub async fn compress_stored_html(&self, limit: i64) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, raw_html, clean_html
+            FROM documents
+            WHERE raw_html IS NOT NULL OR clean_html IS NOT NULL
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+            "#,
+            limit
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for row in &rows {
+            sqlx::query!(
+                r#"
+                UPDATE documents
+                SET raw_html       = NULL,
+                    raw_html_zst   = COALESCE($2, raw_html_zst),
+                    clean_html     = NULL,
+                    clean_html_zst = COALESCE($3, clean_html_zst)
+                WHERE id = $1
+                "#,
+                row.id,
+                compress_optional(row.raw_html.as_deref())?,
+                compress_optional(row.clean_html.as_deref())?
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(rows.len() as u64)
+    }
+
+    /// Delete documents no item points at any more
+    pub async fn prune_orphans(&self) -> Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM documents d
+            WHERE NOT EXISTS (SELECT 1 FROM items i WHERE i.document_id = d.id)
+            "#
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_hash_ignores_tracking_params() {
+        assert_eq!(
+            url_hash("https://example.com/post?utm_source=x#top"),
+            url_hash("https://example.com/post")
+        );
+        assert_ne!(
+            url_hash("https://example.com/post"),
+            url_hash("https://example.com/other")
+        );
+    }
+
+    #[test]
+    fn test_url_hash_invalid_url() {
+        assert_eq!(url_hash("not a url"), None);
+    }
+}
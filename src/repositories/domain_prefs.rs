@@ -0,0 +1,152 @@
+use crate::entities::DomainPref;
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for per-user domain preferences
+pub struct DomainPrefsRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> DomainPrefsRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List all domain preferences for a user
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<DomainPref>> {
+        let prefs = sqlx::query_as!(
+            DomainPref,
+            r#"
+            SELECT user_id, domain, headless_render, skip_images, default_tag, created_at, updated_at
+            FROM domain_prefs
+            WHERE user_id = $1
+            ORDER BY domain
+            "#,
+            user_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(prefs)
+    }
+
+    /// Create or replace the preferences for a single domain
+    pub async fn upsert(
+        &self,
+        user_id: Uuid,
+        domain: &str,
+        headless_render: bool,
+        skip_images: bool,
+        default_tag: Option<&str>,
+    ) -> Result<DomainPref> {
+        let pref = sqlx::query_as!(
+            DomainPref,
+            r#"
+            INSERT INTO domain_prefs (user_id, domain, headless_render, skip_images, default_tag)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, domain) DO UPDATE
+              SET headless_render = EXCLUDED.headless_render,
+                  skip_images     = EXCLUDED.skip_images,
+                  default_tag     = EXCLUDED.default_tag
+            RETURNING user_id, domain, headless_render, skip_images, default_tag, created_at, updated_at
+            "#,
+            user_id,
+            domain,
+            headless_render,
+            skip_images,
+            default_tag
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(pref)
+    }
+
+    /// Delete the preferences for a single domain
+    pub async fn delete(&self, user_id: Uuid, domain: &str) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM domain_prefs WHERE user_id = $1 AND domain = $2",
+            user_id,
+            domain
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Find the most specific preferences applying to `host`.
+    /// Preferences stored for `example.com` also apply to `blog.example.com`.
+    pub async fn find_for_host(&self, user_id: Uuid, host: &str) -> Result<Option<DomainPref>> {
+        let candidates = domain_candidates(host);
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+
+        let pref = sqlx::query_as!(
+            DomainPref,
+            r#"
+            SELECT user_id, domain, headless_render, skip_images, default_tag, created_at, updated_at
+            FROM domain_prefs
+            WHERE user_id = $1 AND domain = ANY($2)
+            ORDER BY length(domain) DESC
+            LIMIT 1
+            "#,
+            user_id,
+            &candidates
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(pref)
+    }
+}
+
+/// Normalize a host into the stored domain form (lowercase, no `www.` prefix).
+pub fn normalize_domain(host: &str) -> String {
+    let host = host.trim().trim_end_matches('.').to_lowercase();
+    match host.strip_prefix("www.") {
+        Some(stripped) => stripped.to_string(),
+        None => host,
+    }
+}
+
+/// Every registrable suffix of `host`, most specific first.
+/// `a.b.example.com` yields `a.b.example.com`, `b.example.com`, `example.com`.
+pub(crate) fn domain_candidates(host: &str) -> Vec<String> {
+    let host = normalize_domain(host);
+    let labels: Vec<&str> = host.split('.').filter(|l| !l.is_empty()).collect();
+    if labels.len() < 2 {
+        return Vec::new();
+    }
+
+    (0..labels.len() - 1)
+        .map(|start| labels[start..].join("."))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_domain() {
+        assert_eq!(normalize_domain("WWW.Example.COM"), "example.com");
+        assert_eq!(normalize_domain("blog.example.com."), "blog.example.com");
+    }
+
+    #[test]
+    fn test_domain_candidates_most_specific_first() {
+        assert_eq!(
+            domain_candidates("a.b.example.com"),
+            vec!["a.b.example.com", "b.example.com", "example.com"]
+        );
+        assert_eq!(domain_candidates("www.example.com"), vec!["example.com"]);
+    }
+
+    #[test]
+    fn test_domain_candidates_bare_host() {
+        assert!(domain_candidates("localhost").is_empty());
+    }
+}
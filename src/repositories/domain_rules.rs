@@ -0,0 +1,126 @@
+use crate::{
+    entities::{DomainRule, DomainRuleAction},
+    repositories::{
+        SiteRepository,
+        domain_prefs::{domain_candidates, normalize_domain},
+    },
+};
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for the instance-wide domain block and allow lists
+pub struct DomainRulesRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> DomainRulesRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List every rule, by domain
+    pub async fn list(&self) -> Result<Vec<DomainRule>> {
+        let rules = sqlx::query_as!(
+            DomainRule,
+            r#"
+            SELECT domain, action AS "action: DomainRuleAction", reason, created_by,
+                   created_at, updated_at
+            FROM domain_rules
+            ORDER BY domain
+            "#
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(rules)
+    }
+
+    /// Create or replace the rule for a single domain, pointing the sites
+    /// it covers at it where it's now their most specific rule
+    pub async fn upsert(
+        &self,
+        domain: &str,
+        action: DomainRuleAction,
+        reason: Option<&str>,
+        created_by: Uuid,
+    ) -> Result<DomainRule> {
+        let rule = sqlx::query_as!(
+            DomainRule,
+            r#"
+            INSERT INTO domain_rules (domain, action, reason, created_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (domain) DO UPDATE
+              SET action = EXCLUDED.action,
+                  reason = EXCLUDED.reason
+            RETURNING domain, action AS "action: DomainRuleAction", reason, created_by,
+                      created_at, updated_at
+            "#,
+            domain,
+            action as DomainRuleAction,
+            reason,
+            created_by
+        )
+        .fetch_one(self.pool)
+        .await?;
+        SiteRepository::new(self.pool)
+            .refresh_domain_rules(domain)
+            .await?;
+
+        Ok(rule)
+    }
+
+    /// Delete the rule for a single domain; the sites it covered fall back
+    /// to the next most specific rule
+    pub async fn delete(&self, domain: &str) -> Result<bool> {
+        let result = sqlx::query!("DELETE FROM domain_rules WHERE domain = $1", domain)
+            .execute(self.pool)
+            .await?;
+        SiteRepository::new(self.pool)
+            .refresh_domain_rules(domain)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Why URLs on `host` may not be saved or fetched, or None if they may.
+    /// The most specific rule covering the host decides; a host no rule
+    /// covers is refused only while an allowlist is in use.
+    pub async fn blocked_reason(&self, host: &str) -> Result<Option<String>> {
+        let candidates = domain_candidates(host);
+
+        let rule = sqlx::query_as!(
+            DomainRule,
+            r#"
+            SELECT domain, action AS "action: DomainRuleAction", reason, created_by,
+                   created_at, updated_at
+            FROM domain_rules
+            WHERE domain = ANY($1)
+            ORDER BY length(domain) DESC
+            LIMIT 1
+            "#,
+            &candidates
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        if let Some(rule) = rule {
+            return Ok(match rule.action {
+                DomainRuleAction::Allow => None,
+                DomainRuleAction::Block => Some(match rule.reason {
+                    Some(reason) => format!("domain {} is blocked: {}", rule.domain, reason),
+                    None => format!("domain {} is blocked", rule.domain),
+                }),
+            });
+        }
+
+        let allowlist_in_use: bool = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM domain_rules WHERE action = 'allow') AS "exists!""#
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(allowlist_in_use
+            .then(|| format!("domain {} is not on the allowlist", normalize_domain(host))))
+    }
+}
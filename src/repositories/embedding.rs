@@ -0,0 +1,92 @@
+use crate::{embeddings::vector_literal, repositories::SearchHit};
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for item embeddings in the optional pgvector table. Check
+/// [`available`](Self::available) before anything else; the other queries
+/// fail on servers without pgvector.
+pub struct EmbeddingRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> EmbeddingRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Whether the database has the embeddings table, i.e. pgvector was
+    /// enabled when migrating
+    pub async fn available(&self) -> Result<bool> {
+        let available = sqlx::query_scalar("SELECT to_regclass('item_embeddings') IS NOT NULL")
+            .fetch_one(self.pool)
+            .await?;
+        Ok(available)
+    }
+
+    /// Store the item's embedding from `model`, replacing an earlier one
+    pub async fn upsert(&self, item_id: Uuid, model: &str, embedding: &[f32]) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO item_embeddings (item_id, model, embedding)
+            VALUES ($1, $2, $3::vector)
+            ON CONFLICT (item_id) DO UPDATE
+            SET model = EXCLUDED.model,
+                embedding = EXCLUDED.embedding,
+                created_at = NOW()
+            "#,
+        )
+        .bind(item_id)
+        .bind(model)
+        .bind(vector_literal(embedding))
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The user's items closest in meaning to `embedding`, best first,
+    /// optionally only those in one content language or with one topic.
+    /// Rank is the cosine similarity; the snippet is the opening of the
+    /// text.
+    pub async fn nearest(
+        &self,
+        user_id: Uuid,
+        model: &str,
+        embedding: &[f32],
+        lang: Option<&str>,
+        topic: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<SearchHit>> {
+        let hits = sqlx::query_as::<_, SearchHit>(
+            r#"
+            WITH q AS (SELECT $3::vector AS embedding)
+            SELECT i.id AS item_id, i.title, i.url, 'content' AS source,
+                   left(COALESCE(c.clean_text, d.clean_text, ''), 200) AS snippet,
+                   (1 - (e.embedding <=> q.embedding))::real AS rank
+            FROM item_embeddings e
+            JOIN items i ON i.id = e.item_id
+            LEFT JOIN contents c ON c.item_id = i.id
+            LEFT JOIN documents d ON d.id = i.document_id
+            CROSS JOIN q
+            WHERE i.user_id = $1
+              AND e.model = $2
+              AND vector_dims(e.embedding) = vector_dims(q.embedding)
+              AND ($4::text IS NULL OR lower(c.lang) = $4)
+              AND ($5::text IS NULL OR $5 = ANY(i.topics))
+            ORDER BY e.embedding <=> q.embedding, i.id
+            LIMIT $6
+            "#,
+        )
+        .bind(user_id)
+        .bind(model)
+        .bind(vector_literal(embedding))
+        .bind(lang)
+        .bind(topic)
+        .bind(limit)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(hits)
+    }
+}
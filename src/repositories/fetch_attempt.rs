@@ -0,0 +1,145 @@
+use crate::{
+    entities::FetchAttempt,
+    fetcher::{FetchError, PageResponse, PhaseTimings},
+};
+use anyhow::Result;
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Most recent attempts listed per item
+pub const MAX_LISTED_FETCH_ATTEMPTS: i64 = 50;
+
+/// Repository for the log of every fetch of an item's page
+pub struct FetchAttemptRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> FetchAttemptRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Log a fetch that got a response
+    pub async fn record_response(
+        &self,
+        item_id: Uuid,
+        response: &PageResponse,
+        duration: Duration,
+        timings: &PhaseTimings,
+    ) -> Result<()> {
+        self.insert(
+            item_id,
+            Some(response.url_final.as_str()),
+            Some(response.status.as_u16()),
+            None,
+            duration,
+            timings,
+            Some(response.body_raw.len()),
+        )
+        .await
+    }
+
+    /// Log a fetch that the server answered with 304 Not Modified
+    pub async fn record_not_modified(
+        &self,
+        item_id: Uuid,
+        url: &str,
+        duration: Duration,
+        timings: &PhaseTimings,
+    ) -> Result<()> {
+        self.insert(
+            item_id,
+            Some(url),
+            Some(reqwest::StatusCode::NOT_MODIFIED.as_u16()),
+            None,
+            duration,
+            timings,
+            None,
+        )
+        .await
+    }
+
+    /// Log a failed fetch
+    pub async fn record_error(
+        &self,
+        item_id: Uuid,
+        error: &FetchError,
+        duration: Duration,
+        timings: &PhaseTimings,
+    ) -> Result<()> {
+        self.insert(
+            item_id,
+            None,
+            error.status().map(|status| status.as_u16()),
+            Some(error),
+            duration,
+            timings,
+            None,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn insert(
+        &self,
+        item_id: Uuid,
+        final_url: Option<&str>,
+        status: Option<u16>,
+        error: Option<&FetchError>,
+        duration: Duration,
+        timings: &PhaseTimings,
+        bytes: Option<usize>,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO fetch_attempts
+                  (item_id, final_url, status, error_class, error, duration_ms, bytes,
+                   dns_ms, connect_ms, ttfb_ms, download_ms)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#,
+            item_id,
+            final_url,
+            status.map(|status| status as i16),
+            error.map(FetchError::class),
+            error.map(ToString::to_string),
+            millis(duration),
+            bytes.map(|bytes| bytes as i64),
+            timings.dns.map(millis),
+            timings.connect.map(millis),
+            timings.ttfb.map(millis),
+            timings.download.map(millis)
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The item's latest [`MAX_LISTED_FETCH_ATTEMPTS`] attempts, oldest first
+    pub async fn list_for_item(&self, item_id: Uuid) -> Result<Vec<FetchAttempt>> {
+        let attempts = sqlx::query_as!(
+            FetchAttempt,
+            r#"
+            SELECT * FROM (
+                SELECT id, item_id, attempted_at, final_url, status, error_class, error,
+                       duration_ms, bytes, dns_ms, connect_ms, ttfb_ms, download_ms
+                FROM fetch_attempts
+                WHERE item_id = $1
+                ORDER BY id DESC
+                LIMIT $2
+            ) latest
+            ORDER BY id
+            "#,
+            item_id,
+            MAX_LISTED_FETCH_ATTEMPTS
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(attempts)
+    }
+}
+
+fn millis(duration: Duration) -> i32 {
+    i32::try_from(duration.as_millis()).unwrap_or(i32::MAX)
+}
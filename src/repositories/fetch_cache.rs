@@ -0,0 +1,142 @@
+use crate::{
+    entities::ExtractionFailure,
+    extractor::ExtractedContent,
+    fetcher::{CacheValidators, Charset, PageResponse, persisted_headers},
+};
+use anyhow::Result;
+use bytes::Bytes;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::{
+    StatusCode,
+    header::{HeaderMap, HeaderName},
+};
+use sqlx::{FromRow, PgPool, types::Json};
+use std::collections::BTreeMap;
+
+/// What the extractor made of a page
+pub type Extraction = Result<ExtractedContent, ExtractionFailure>;
+
+/// A page fetched (and extracted) recently on behalf of some user
+#[derive(Debug, Clone, FromRow)]
+pub struct CachedFetch {
+    pub url_key: String,
+    pub final_url: String,
+    pub body: String,
+    pub checksum: String,
+    pub http_etag: Option<String>,
+    pub http_last_modified: Option<String>,
+    /// The persisted headers of the original response; None for entries
+    /// cached before they were kept
+    pub response_headers: Option<Json<BTreeMap<String, String>>>,
+    pub extraction: Json<Extraction>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl CachedFetch {
+    /// Rebuild the response the page was cached from. Only the
+    /// [`PERSISTED_HEADERS`](crate::fetcher::PERSISTED_HEADERS) survive from
+    /// the original headers, and the body is already UTF-8.
+    pub fn page_response(&self) -> Result<PageResponse> {
+        let mut headers = HeaderMap::new();
+        if let Some(etag) = &self.http_etag {
+            headers.insert(reqwest::header::ETAG, etag.parse()?);
+        }
+        if let Some(last_modified) = &self.http_last_modified {
+            headers.insert(reqwest::header::LAST_MODIFIED, last_modified.parse()?);
+        }
+        for (name, value) in self.response_headers.iter().flat_map(|Json(stored)| stored) {
+            headers.insert(HeaderName::from_bytes(name.as_bytes())?, value.parse()?);
+        }
+
+        Ok(PageResponse {
+            url_final: url::Url::parse(&self.final_url)?,
+            status: StatusCode::OK,
+            headers,
+            body_raw: Bytes::from(self.body.clone()),
+            body_utf8: self.body.clone(),
+            charset: Charset::Utf8,
+            fetched_at: self.fetched_at,
+        })
+    }
+}
+
+/// Repository for the cross-user fetch cache
+pub struct FetchCacheRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> FetchCacheRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// The cached fetch for a URL key, unless it has expired
+    pub async fn get_fresh(&self, url_key: &str) -> Result<Option<CachedFetch>> {
+        let cached = sqlx::query_as!(
+            CachedFetch,
+            r#"
+            SELECT url_key, final_url, body, checksum, http_etag, http_last_modified,
+                   response_headers AS "response_headers: Json<BTreeMap<String, String>>",
+                   extraction AS "extraction: Json<Extraction>", fetched_at
+            FROM fetch_cache
+            WHERE url_key = $1 AND expires_at > NOW()
+            "#,
+            url_key
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(cached)
+    }
+
+    /// Cache a fetched page and its extraction for `ttl`, replacing any older
+    /// entry and clearing out expired ones
+    pub async fn put(
+        &self,
+        url_key: &str,
+        response: &PageResponse,
+        checksum: &str,
+        extraction: &Extraction,
+        ttl: Duration,
+    ) -> Result<()> {
+        let validators = CacheValidators::from_headers(&response.headers);
+
+        sqlx::query!("DELETE FROM fetch_cache WHERE expires_at <= NOW()")
+            .execute(self.pool)
+            .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO fetch_cache (
+                url_key, final_url, body, checksum, http_etag, http_last_modified,
+                response_headers, extraction, fetched_at, expires_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (url_key) DO UPDATE SET
+                final_url = EXCLUDED.final_url,
+                body = EXCLUDED.body,
+                checksum = EXCLUDED.checksum,
+                http_etag = EXCLUDED.http_etag,
+                http_last_modified = EXCLUDED.http_last_modified,
+                response_headers = EXCLUDED.response_headers,
+                extraction = EXCLUDED.extraction,
+                fetched_at = EXCLUDED.fetched_at,
+                expires_at = EXCLUDED.expires_at
+            "#,
+            url_key,
+            response.url_final.as_str(),
+            &response.body_utf8,
+            checksum,
+            validators.etag,
+            validators.last_modified,
+            Json(persisted_headers(&response.headers)) as _,
+            Json(extraction) as _,
+            response.fetched_at,
+            response.fetched_at + ttl
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
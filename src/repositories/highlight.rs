@@ -0,0 +1,132 @@
+use crate::{entities::Highlight, repositories::ItemEdit};
+use anyhow::Result;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+/// Repository for highlighted passages of items
+pub struct HighlightRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> HighlightRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Add a highlight to one of the user's items. Returns None if the item
+    /// doesn't exist or belongs to someone else.
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+        quote: &str,
+    ) -> Result<Option<Highlight>> {
+        let highlight = sqlx::query_as!(
+            Highlight,
+            r#"
+            INSERT INTO highlights (item_id, user_id, quote)
+            SELECT id, user_id, $3
+            FROM items
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, item_id, user_id, quote, created_at
+            "#,
+            item_id,
+            user_id,
+            quote
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(highlight)
+    }
+
+    /// List the highlights on one of the user's items, oldest first
+    pub async fn list_for_item(&self, user_id: Uuid, item_id: Uuid) -> Result<Vec<Highlight>> {
+        let highlights = sqlx::query_as!(
+            Highlight,
+            r#"
+            SELECT id, item_id, user_id, quote, created_at
+            FROM highlights
+            WHERE item_id = $1 AND user_id = $2
+            ORDER BY created_at
+            "#,
+            item_id,
+            user_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(highlights)
+    }
+
+    /// Delete a highlight, returning whether it existed
+    pub async fn delete(&self, user_id: Uuid, highlight_id: Uuid) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM highlights WHERE id = $1 AND user_id = $2",
+            highlight_id,
+            user_id
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Set or clear the note on one of the user's items, unless the item
+    /// has moved on from `version`, when given
+    pub async fn set_note(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+        note: Option<&str>,
+        version: Option<i64>,
+    ) -> Result<ItemEdit> {
+        let row = sqlx::query!(
+            r#"
+            WITH item AS (
+                SELECT id, version FROM items WHERE id = $1 AND user_id = $2 FOR UPDATE
+            ),
+            updated AS (
+                UPDATE items
+                SET note = $3, version = version + 1
+                WHERE id = (SELECT id FROM item)
+                  AND ($4::bigint IS NULL OR version = $4)
+                RETURNING version
+            )
+            SELECT EXISTS (SELECT 1 FROM updated) AS "updated!",
+                   COALESCE((SELECT version FROM updated), item.version) AS "version!"
+            FROM item
+            "#,
+            item_id,
+            user_id,
+            note,
+            version
+        )
+        .fetch_optional(self.pool)
+        .await?
+        .map(|row| (row.updated, row.version));
+
+        Ok(ItemEdit::from_row(row, version))
+    }
+
+    /// Set or clear the note on one of the user's items, on `conn` so the
+    /// caller can note an item in the transaction that saves it. Returns
+    /// whether the item exists.
+    pub async fn set_note_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_id: Uuid,
+        note: Option<&str>,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE items SET note = $3, version = version + 1 WHERE id = $1 AND user_id = $2",
+            item_id,
+            user_id,
+            note
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
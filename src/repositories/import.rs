@@ -0,0 +1,168 @@
+use crate::{
+    entities::{ImportFailure, ImportFailureReason},
+    repositories::{ItemRepository, ItemRepositoryTrait, MAX_OPERATION_ERROR_SAMPLES},
+};
+use anyhow::Result;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+/// Repository for URL imports and the rows that failed in them
+pub struct ImportRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> ImportRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Save one imported URL as an item that remembers its import row, and
+    /// count the row as processed. Returns None without saving anything if
+    /// the user already saved the URL or one equivalent to it, checked
+    /// under the same lock as saves from the API. Runs on the caller's
+    /// transaction so the fetch job staged alongside it commits with it.
+    pub async fn create_item_in(
+        &self,
+        conn: &mut PgConnection,
+        operation_id: Uuid,
+        user_id: Uuid,
+        row_number: i32,
+        url: &str,
+    ) -> Result<Option<Uuid>> {
+        let items = ItemRepository::new(self.pool.clone());
+        if items.find_saved_in(conn, user_id, url).await?.is_some() {
+            return Ok(None);
+        }
+        let item_id = items.create_in(conn, user_id, url).await?.item.id;
+
+        sqlx::query!(
+            "UPDATE items SET import_operation_id = $2, import_row = $3 WHERE id = $1",
+            item_id,
+            operation_id,
+            row_number
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE operations SET processed = processed + 1 WHERE id = $1",
+            operation_id
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(Some(item_id))
+    }
+
+    /// Record a row the import job rejected, counting it as processed
+    pub async fn record_row_failure(
+        &self,
+        operation_id: Uuid,
+        row_number: i32,
+        url: &str,
+        reason: ImportFailureReason,
+        detail: Option<&str>,
+    ) -> Result<()> {
+        self.record_failure(operation_id, row_number, url, reason, detail, true)
+            .await
+    }
+
+    /// Report a refused fetch against the import row the item came from.
+    /// Does nothing for items that weren't imported.
+    pub async fn record_fetch_forbidden(&self, item_id: Uuid, detail: &str) -> Result<()> {
+        let row = sqlx::query!(
+            r#"
+            SELECT import_operation_id AS "operation_id!", import_row AS "row_number!", url
+            FROM items
+            WHERE id = $1 AND import_operation_id IS NOT NULL AND import_row IS NOT NULL
+            "#,
+            item_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(());
+        };
+
+        self.record_failure(
+            row.operation_id,
+            row.row_number,
+            &row.url,
+            ImportFailureReason::FetchForbidden,
+            Some(detail),
+            false,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Record a failed row and fold it into the operation's error count and
+    /// samples in one statement. A row that already failed is left alone, so
+    /// replays are harmless. `processed` says whether the row also counts as
+    /// processed, which is true while the import job is working through the
+    /// list but not for failures found later by the fetcher.
+    async fn record_failure(
+        &self,
+        operation_id: Uuid,
+        row_number: i32,
+        url: &str,
+        reason: ImportFailureReason,
+        detail: Option<&str>,
+        processed: bool,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            WITH failure AS (
+                INSERT INTO import_failures (operation_id, row_number, url, reason, detail)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (operation_id, row_number) DO NOTHING
+                RETURNING operation_id, url, reason, detail
+            )
+            UPDATE operations o
+            SET processed = o.processed + CASE WHEN $6 THEN 1 ELSE 0 END,
+                error_count = o.error_count + 1,
+                error_samples = CASE
+                    WHEN jsonb_array_length(o.error_samples) < $7 THEN o.error_samples || jsonb_build_array(
+                        jsonb_build_object(
+                            'subject', f.url,
+                            'error', f.reason::text || COALESCE(': ' || f.detail, '')
+                        )
+                    )
+                    ELSE o.error_samples
+                END
+            FROM failure f
+            WHERE o.id = f.operation_id
+            "#,
+            operation_id,
+            row_number,
+            url,
+            reason as ImportFailureReason,
+            detail,
+            processed,
+            MAX_OPERATION_ERROR_SAMPLES
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Failed rows of an import, in list order
+    pub async fn failures(&self, operation_id: Uuid) -> Result<Vec<ImportFailure>> {
+        let failures = sqlx::query_as!(
+            ImportFailure,
+            r#"
+            SELECT id, operation_id, row_number, url,
+                   reason AS "reason: ImportFailureReason", detail, created_at
+            FROM import_failures
+            WHERE operation_id = $1
+            ORDER BY row_number
+            "#,
+            operation_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(failures)
+    }
+}
@@ -0,0 +1,1054 @@
+use crate::{
+    entities::{Item, ItemStatus, ProcessingState},
+    pagination::{KeysetCursor, SortKey},
+    repositories::{READ_THRESHOLD, url_hash},
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgConnection, PgPool};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Average adult silent-reading speed used for reading time estimates
+pub const WORDS_PER_MINUTE: f64 = 238.0;
+
+/// An item with what list views show alongside it
+#[derive(Debug, Clone, FromRow)]
+pub struct ItemDetails {
+    #[sqlx(flatten)]
+    pub item: Item,
+    /// Names of the attached tags, alphabetically
+    pub tags: Vec<String>,
+    /// Topics assigned by the classifier, best first
+    pub topics: Vec<String>,
+    /// None until the item's text has been extracted
+    pub word_count: Option<i32>,
+    /// None until the item's text has been extracted
+    pub reading_time_minutes: Option<i32>,
+}
+
+/// Which of a user's items a list shows; every field left unset matches
+/// all items
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemFilter {
+    /// Lowercase content language
+    pub lang: Option<String>,
+    /// Only items the extractor rejected
+    pub failed_only: bool,
+    pub status: Option<ItemStatus>,
+    /// Only items with the tag of this name
+    pub tag: Option<String>,
+    /// Text the title or URL must contain, ignoring case
+    pub q: Option<String>,
+    /// Only items assigned this topic
+    pub topic: Option<String>,
+    /// Only extracted items whose reading time is within this bound
+    pub reading_time: Option<ReadingTime>,
+    /// Only extracted items that take at most this many minutes to read
+    pub max_reading_time: Option<i32>,
+    /// Only items from this domain, normalized like
+    /// [`normalize_domain`](crate::repositories::domain_prefs::normalize_domain)
+    pub site: Option<String>,
+    /// Only items saved before this time
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only items saved at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Leave out items snoozed until later
+    pub hide_snoozed: bool,
+}
+
+/// A bound on an item's estimated reading time, in whole minutes as
+/// reported in `reading_time_minutes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadingTime {
+    Under(i32),
+    Over(i32),
+}
+
+impl ReadingTime {
+    /// Items that take less than five minutes to read
+    pub const QUICK: ReadingTime = ReadingTime::Under(5);
+    /// Items that take more than twenty minutes to read
+    pub const LONG: ReadingTime = ReadingTime::Over(20);
+
+    /// Most words an item within the bound may have
+    pub fn max_words(self) -> Option<i64> {
+        match self {
+            // Reading time is rounded up, so under n minutes means at most
+            // n - 1 full minutes of words
+            ReadingTime::Under(minutes) => {
+                Some((f64::from(minutes - 1) * WORDS_PER_MINUTE).floor() as i64)
+            }
+            ReadingTime::Over(_) => None,
+        }
+    }
+
+    /// Words an item within the bound must have more than
+    pub fn min_words(self) -> Option<i64> {
+        match self {
+            ReadingTime::Under(_) => None,
+            ReadingTime::Over(minutes) => {
+                Some((f64::from(minutes) * WORDS_PER_MINUTE).floor() as i64)
+            }
+        }
+    }
+}
+
+impl FromStr for ReadingTime {
+    type Err = String;
+
+    /// `<5` or `>20`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || "reading_time must be <N or >N minutes, e.g. <5".to_string();
+        let (bound, minutes) = s.trim().split_at_checked(1).ok_or_else(error)?;
+        let minutes: i32 = minutes.trim().parse().map_err(|_| error())?;
+        match bound {
+            "<" if (1..=1440).contains(&minutes) => Ok(ReadingTime::Under(minutes)),
+            ">" if (0..=1440).contains(&minutes) => Ok(ReadingTime::Over(minutes)),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// Longest prefix of an item's title that sorting by title looks at; enough
+/// to order any real list, and short enough for an index entry. The title
+/// sort key and its index spell it out as well.
+pub const TITLE_SORT_CHARS: usize = 200;
+
+/// Column an item list is ordered by. Ties are broken by ID, in the same
+/// direction, so every order is total and can be paged by keyset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ItemSortField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    /// The title, or the URL of items without one
+    Title,
+    /// Items whose text hasn't been extracted count as zero minutes
+    ReadingTime,
+}
+
+/// Order of an item list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemSort {
+    pub field: ItemSortField,
+    pub descending: bool,
+}
+
+impl Default for ItemSort {
+    /// Newest first
+    fn default() -> Self {
+        Self {
+            field: ItemSortField::CreatedAt,
+            descending: true,
+        }
+    }
+}
+
+impl ItemSort {
+    /// The sort key of items aliased `i`. Each has an index on
+    /// `(user_id, key, id)`.
+    fn key_sql(self) -> &'static str {
+        match self.field {
+            ItemSortField::CreatedAt => "i.created_at",
+            ItemSortField::UpdatedAt => "i.updated_at",
+            ItemSortField::Title => "left(COALESCE(i.title, i.url), 200)",
+            ItemSortField::ReadingTime => "COALESCE(i.reading_time_minutes, 0)",
+        }
+    }
+
+    /// Type a cursor's key is cast to for comparing with [`Self::key_sql`]
+    fn key_type(self) -> &'static str {
+        match self.field {
+            ItemSortField::CreatedAt | ItemSortField::UpdatedAt => "timestamptz",
+            ItemSortField::Title => "text",
+            ItemSortField::ReadingTime => "int",
+        }
+    }
+
+    /// Where the next page starts after `item`
+    pub fn cursor(self, item: &ItemDetails) -> KeysetCursor {
+        let key = match self.field {
+            ItemSortField::CreatedAt => SortKey::Time(item.item.created_at),
+            ItemSortField::UpdatedAt => SortKey::Time(item.item.updated_at),
+            ItemSortField::Title => SortKey::Text(
+                item.item
+                    .title
+                    .as_deref()
+                    .unwrap_or(&item.item.url)
+                    .chars()
+                    .take(TITLE_SORT_CHARS)
+                    .collect(),
+            ),
+            ItemSortField::ReadingTime => {
+                SortKey::Number(item.reading_time_minutes.unwrap_or(0).into())
+            }
+        };
+        KeysetCursor::with_key(key, item.item.id)
+    }
+
+    /// Whether `cursor` could have come from a list in this order
+    pub fn accepts(self, cursor: &KeysetCursor) -> bool {
+        matches!(
+            (self.field, &cursor.key),
+            (
+                ItemSortField::CreatedAt | ItemSortField::UpdatedAt,
+                SortKey::Time(_)
+            ) | (ItemSortField::Title, SortKey::Text(_))
+                | (ItemSortField::ReadingTime, SortKey::Number(_))
+        )
+    }
+}
+
+impl FromStr for ItemSort {
+    type Err = String;
+
+    /// `created_at`, `updated_at`, `title` or `reading_time`, ascending, or
+    /// descending with a leading `-`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (descending, name) = match s.strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, s),
+        };
+        let field = match name {
+            "created_at" => ItemSortField::CreatedAt,
+            "updated_at" => ItemSortField::UpdatedAt,
+            "title" => ItemSortField::Title,
+            "reading_time" => ItemSortField::ReadingTime,
+            _ => {
+                return Err(
+                    "sort must be created_at, updated_at, title or reading_time, with a leading - for descending"
+                        .to_string(),
+                );
+            }
+        };
+        Ok(Self { field, descending })
+    }
+}
+
+/// Conditions for an [`ItemFilter`] on items aliased `i`. Binds the user,
+/// then the filter's fields in declaration order, with `q` as a LIKE
+/// pattern and `reading_time` as its most and fewest words. Every condition
+/// is on a column of `i`, so callers don't need to join contents.
+const ITEM_FILTER_SQL: &str = r#"
+    i.user_id = $1
+    AND ($2::text IS NULL OR i.lang = $2)
+    AND (NOT $3 OR i.extraction_error IS NOT NULL)
+    AND ($4::item_status IS NULL OR i.status = $4)
+    AND ($5::text IS NULL OR EXISTS (
+        SELECT 1 FROM item_tags ft
+        JOIN tags ftg ON ftg.id = ft.tag_id
+        WHERE ft.item_id = i.id AND ftg.name = $5
+    ))
+    AND ($6::text IS NULL OR i.title ILIKE $6 OR i.url ILIKE $6)
+    AND ($7::text IS NULL OR $7 = ANY(i.topics))
+    AND (($8::bigint IS NULL AND $9::bigint IS NULL) OR (
+        i.word_count <= COALESCE($8, i.word_count) AND i.word_count > COALESCE($9, -1)
+    ))
+    AND ($10::int IS NULL OR i.reading_time_minutes <= $10)
+    AND ($11::text IS NULL OR i.domain = $11)
+    AND ($12::timestamptz IS NULL OR i.created_at < $12)
+    AND ($13::timestamptz IS NULL OR i.created_at >= $13)
+    AND (NOT $14 OR i.snoozed_until IS NULL OR i.snoozed_until <= NOW())
+"#;
+
+/// A LIKE pattern matching `text` anywhere, with its own wildcards escaped
+fn like_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ItemRepositoryTrait {
+    /// Save a new item for the user. A fresh item has no tags, topics or
+    /// text yet.
+    async fn create(&self, user_id: Uuid, url: &str) -> Result<ItemDetails>;
+    /// Like [`ItemRepositoryTrait::create`], on `conn` so the caller can
+    /// stage the item's fetch jobs in the same transaction
+    async fn create_in(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        url: &str,
+    ) -> Result<ItemDetails>;
+    /// The user's item saved from `url` or a URL equivalent to it, on
+    /// `conn`. Holds a lock on the URL until the transaction ends, so two
+    /// saves of the same page can't both miss each other and create it
+    /// twice.
+    async fn find_saved_in(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        url: &str,
+    ) -> Result<Option<Uuid>>;
+    /// The version one of the user's items is at, on `conn`, so the caller
+    /// sees its own edits in the transaction making them
+    async fn version_in(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_id: Uuid,
+    ) -> Result<Option<i64>>;
+    /// One of the user's items with its tags and reading time. Returns None
+    /// if the item doesn't exist or belongs to someone else.
+    async fn find_for_user(&self, user_id: Uuid, item_id: Uuid) -> Result<Option<ItemDetails>>;
+    /// The user's item saved from `url`, already canonical, or from a URL
+    /// equivalent to it
+    async fn find_by_url(&self, user_id: Uuid, url: &str) -> Result<Option<ItemDetails>>;
+    /// How many of the user's items match `filter` and when the newest
+    /// change among them was, which is enough to tell an unchanged list
+    /// apart without loading it
+    async fn fingerprint(
+        &self,
+        user_id: Uuid,
+        filter: &ItemFilter,
+    ) -> Result<(i64, Option<DateTime<Utc>>)>;
+    /// One page of the user's items matching `filter` in `sort` order,
+    /// starting after `after` when given
+    async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        filter: &ItemFilter,
+        sort: ItemSort,
+        after: Option<KeysetCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ItemDetails>>;
+    /// Apply the user's edits to one of their items, keeping fields left as
+    /// None. Nothing changes unless the item is at `version`, when given,
+    /// and its status can move to `status`.
+    async fn update(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+        title: Option<String>,
+        status: Option<ItemStatus>,
+        version: Option<i64>,
+    ) -> Result<ItemEdit>;
+    /// Move one of the user's items to `status` if its current status
+    /// allows it, with the same results as [`ItemRepositoryTrait::update`]
+    async fn transition(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+        status: ItemStatus,
+    ) -> Result<ItemEdit>;
+    /// Delete one of the user's items. Returns false if the item doesn't
+    /// exist or belongs to someone else.
+    async fn delete(&self, user_id: Uuid, item_id: Uuid) -> Result<bool>;
+    /// Like [`ItemRepositoryTrait::delete`], on `conn` so the caller can
+    /// drop the item's pending jobs in the same transaction. Its content,
+    /// tags and other per-item rows go with it.
+    async fn delete_in(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_id: Uuid,
+    ) -> Result<bool>;
+    /// Record how far the user has read one of their items, unless it has
+    /// moved on from `version`, when given
+    async fn set_progress(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+        progress: f32,
+        version: Option<i64>,
+    ) -> Result<ItemEdit>;
+    /// A randomly chosen item the user hasn't archived or read yet, only
+    /// among items with `status` when given. Items snoozed until later are
+    /// left out unless `include_snoozed`. Returns None when there's nothing
+    /// left to read.
+    async fn random_unread(
+        &self,
+        user_id: Uuid,
+        status: Option<ItemStatus>,
+        include_snoozed: bool,
+    ) -> Result<Option<ItemDetails>>;
+    /// Keep one of the user's items out of lists until `until`. Returns
+    /// false if the item doesn't exist or belongs to someone else.
+    async fn snooze(&self, user_id: Uuid, item_id: Uuid, until: DateTime<Utc>) -> Result<bool>;
+    /// Refetch one of the user's items every `interval_secs`, starting at
+    /// `next_refresh_at`, or never when None, and whether to record changes
+    /// to its text. Returns false if the item doesn't exist or belongs to
+    /// someone else.
+    async fn set_refresh_policy(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+        interval_secs: Option<i32>,
+        next_refresh_at: Option<DateTime<Utc>>,
+        watch: bool,
+    ) -> Result<bool>;
+    /// Why extracting one of the user's items failed, if it did, on `conn`,
+    /// locking the item until the transaction ends. Returns None if the
+    /// item doesn't exist or belongs to someone else.
+    async fn extraction_error_in(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_id: Uuid,
+    ) -> Result<Option<Option<String>>>;
+}
+
+/// What became of an edit to one of the user's items
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemEdit {
+    /// Made, leaving the item at `version`
+    Applied { version: i64 },
+    /// The item doesn't exist or belongs to someone else
+    NotFound,
+    /// The edit was made against an older version of the item, which has
+    /// changed since; nothing was changed
+    Stale,
+    /// The item's status can't move to the one asked for; nothing was
+    /// changed
+    StatusNotAllowed,
+}
+
+impl ItemEdit {
+    /// The outcome of a conditional write whose statement reported whether
+    /// it changed the item (None when the item wasn't found) and the
+    /// version the item is at now
+    pub(crate) fn from_row(row: Option<(bool, i64)>, version: Option<i64>) -> Self {
+        match row {
+            None => ItemEdit::NotFound,
+            Some((true, version)) => ItemEdit::Applied { version },
+            Some((false, current)) if version.is_some_and(|version| version != current) => {
+                ItemEdit::Stale
+            }
+            Some((false, _)) => ItemEdit::StatusNotAllowed,
+        }
+    }
+}
+
+/// Repository for items together with their tags and content stats
+#[derive(Clone)]
+pub struct ItemRepository {
+    pool: PgPool,
+}
+
+impl ItemRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Items matching `filter` in `sort` order, and only `item_id` when
+    /// given. Each order has a matching `(user_id, key, id)` index, so a
+    /// page after a cursor is an index range scan.
+    #[allow(clippy::too_many_arguments)]
+    async fn details(
+        &self,
+        user_id: Uuid,
+        filter: &ItemFilter,
+        sort: ItemSort,
+        item_id: Option<Uuid>,
+        after: Option<KeysetCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ItemDetails>> {
+        let (direction, past) = if sort.descending {
+            ("DESC", "<")
+        } else {
+            ("ASC", ">")
+        };
+        let items = sqlx::query_as::<_, ItemDetails>(&format!(
+            r#"
+            SELECT i.id, i.user_id, i.url, i.title, s.name AS site, i.status, i.extraction_error,
+                   i.last_error, i.processing_state, i.processing_state_changed_at, i.nsfw,
+                   i.read_progress, i.favorite, i.version, i.created_at, i.updated_at, i.topics,
+                   i.word_count, i.reading_time_minutes,
+                   COALESCE(t.tags, '{{}}') AS tags
+            FROM items i
+            LEFT JOIN sites s ON s.host = i.domain
+            LEFT JOIN LATERAL (
+                SELECT array_agg(tg.name ORDER BY tg.name) AS tags
+                FROM item_tags it
+                JOIN tags tg ON tg.id = it.tag_id
+                WHERE it.item_id = i.id
+            ) t ON TRUE
+            WHERE {filter}
+              AND ($17::uuid IS NULL OR i.id = $17)
+              AND ($18::text IS NULL OR ({key}, i.id) {past} ($18::{key_type}, $19))
+            ORDER BY {key} {direction}, i.id {direction}
+            LIMIT $15 OFFSET $16
+            "#,
+            filter = ITEM_FILTER_SQL,
+            key = sort.key_sql(),
+            key_type = sort.key_type(),
+        ))
+        .bind(user_id)
+        .bind(filter.lang.as_deref())
+        .bind(filter.failed_only)
+        .bind(filter.status)
+        .bind(filter.tag.as_deref())
+        .bind(filter.q.as_deref().map(like_pattern))
+        .bind(filter.topic.as_deref())
+        .bind(filter.reading_time.and_then(ReadingTime::max_words))
+        .bind(filter.reading_time.and_then(ReadingTime::min_words))
+        .bind(filter.max_reading_time)
+        .bind(filter.site.as_deref())
+        .bind(filter.created_before)
+        .bind(filter.created_after)
+        .bind(filter.hide_snoozed)
+        .bind(limit)
+        .bind(offset)
+        .bind(item_id)
+        .bind(after.as_ref().map(|after| after.key.to_sql_text()))
+        .bind(after.map(|after| after.id))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(items)
+    }
+
+    /// The first item the user saved from `url` or one with its hash, on
+    /// `conn`
+    async fn first_saved_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        url: &str,
+    ) -> Result<Option<Uuid>> {
+        let saved = sqlx::query_scalar!(
+            r#"
+            SELECT id FROM items
+            WHERE user_id = $1 AND (url_hash = $2 OR url = $3)
+            ORDER BY created_at
+            LIMIT 1
+            "#,
+            user_id,
+            url_hash(url),
+            url
+        )
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(saved)
+    }
+
+    /// Lock those of `item_ids` that are the user's until `conn`'s
+    /// transaction ends, returning their IDs
+    pub async fn lock_many_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_ids: &[Uuid],
+    ) -> Result<Vec<Uuid>> {
+        let locked = sqlx::query_scalar!(
+            "SELECT id FROM items WHERE id = ANY($1) AND user_id = $2 FOR UPDATE",
+            item_ids,
+            user_id
+        )
+        .fetch_all(conn)
+        .await?;
+
+        Ok(locked)
+    }
+
+    /// Archive those of `item_ids` that are the user's, on `conn`. Returns
+    /// the IDs archived; any item can be archived whatever its status.
+    pub async fn archive_many_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_ids: &[Uuid],
+    ) -> Result<Vec<Uuid>> {
+        let archived = sqlx::query_scalar!(
+            r#"
+            UPDATE items
+            SET status = 'archived', version = version + 1, updated_at = NOW()
+            WHERE id = ANY($1) AND user_id = $2
+            RETURNING id
+            "#,
+            item_ids,
+            user_id
+        )
+        .fetch_all(conn)
+        .await?;
+
+        Ok(archived)
+    }
+
+    /// Star or unstar those of `item_ids` that are the user's, on `conn`.
+    /// Returns the IDs updated.
+    pub async fn set_favorite_many_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_ids: &[Uuid],
+        favorite: bool,
+    ) -> Result<Vec<Uuid>> {
+        let updated = sqlx::query_scalar!(
+            r#"
+            UPDATE items
+            SET favorite = $3, version = version + 1, updated_at = NOW()
+            WHERE id = ANY($1) AND user_id = $2
+            RETURNING id
+            "#,
+            item_ids,
+            user_id,
+            favorite
+        )
+        .fetch_all(conn)
+        .await?;
+
+        Ok(updated)
+    }
+
+    /// Delete those of `item_ids` that are the user's, on `conn`, like
+    /// [`ItemRepositoryTrait::delete_in`]. Returns the IDs deleted.
+    pub async fn delete_many_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_ids: &[Uuid],
+    ) -> Result<Vec<Uuid>> {
+        let deleted = sqlx::query_scalar!(
+            "DELETE FROM items WHERE id = ANY($1) AND user_id = $2 RETURNING id",
+            item_ids,
+            user_id
+        )
+        .fetch_all(conn)
+        .await?;
+
+        Ok(deleted)
+    }
+
+    /// Those of `item_ids` still making their way through the fetch
+    /// pipeline, on `conn`
+    pub async fn unfinished_in(conn: &mut PgConnection, item_ids: &[Uuid]) -> Result<Vec<Uuid>> {
+        let unfinished = sqlx::query_scalar!(
+            r#"
+            SELECT id FROM items
+            WHERE id = ANY($1)
+              AND processing_state NOT IN ('ready', 'failed_permanent')
+            "#,
+            item_ids
+        )
+        .fetch_all(conn)
+        .await?;
+
+        Ok(unfinished)
+    }
+}
+
+#[async_trait]
+impl ItemRepositoryTrait for ItemRepository {
+    async fn create(&self, user_id: Uuid, url: &str) -> Result<ItemDetails> {
+        let mut conn = self.pool.acquire().await?;
+        self.create_in(&mut conn, user_id, url).await
+    }
+
+    async fn create_in(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        url: &str,
+    ) -> Result<ItemDetails> {
+        let item = sqlx::query_as!(
+            Item,
+            r#"
+            WITH i AS (
+                INSERT INTO items (user_id, url, url_hash)
+                VALUES ($1, $2, $3)
+                RETURNING *
+            )
+            SELECT i.id, i.user_id, i.url, i.title, s.name AS "site?",
+                   i.status AS "status: ItemStatus", i.extraction_error, i.last_error,
+                   i.processing_state AS "processing_state: ProcessingState",
+                   i.processing_state_changed_at, i.nsfw, i.read_progress, i.favorite,
+                   i.version, i.created_at, i.updated_at
+            FROM i
+            LEFT JOIN sites s ON s.host = i.domain
+            "#,
+            user_id,
+            url,
+            url_hash(url)
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(ItemDetails {
+            item,
+            tags: Vec::new(),
+            topics: Vec::new(),
+            word_count: None,
+            reading_time_minutes: None,
+        })
+    }
+
+    async fn find_saved_in(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        url: &str,
+    ) -> Result<Option<Uuid>> {
+        let hash = url_hash(url);
+        sqlx::query!(
+            "SELECT pg_advisory_xact_lock(hashtextextended($1::text || ':' || $2::text, 0))",
+            user_id.to_string(),
+            hash.as_deref().unwrap_or(url)
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Self::first_saved_in(conn, user_id, url).await
+    }
+
+    async fn version_in(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_id: Uuid,
+    ) -> Result<Option<i64>> {
+        let version = sqlx::query_scalar!(
+            "SELECT version FROM items WHERE id = $1 AND user_id = $2",
+            item_id,
+            user_id
+        )
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(version)
+    }
+
+    async fn delete_in(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_id: Uuid,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM items WHERE id = $1 AND user_id = $2",
+            item_id,
+            user_id
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn find_for_user(&self, user_id: Uuid, item_id: Uuid) -> Result<Option<ItemDetails>> {
+        let mut items = self
+            .details(
+                user_id,
+                &ItemFilter::default(),
+                ItemSort::default(),
+                Some(item_id),
+                None,
+                1,
+                0,
+            )
+            .await?;
+        Ok(items.pop())
+    }
+
+    async fn find_by_url(&self, user_id: Uuid, url: &str) -> Result<Option<ItemDetails>> {
+        let mut conn = self.pool.acquire().await?;
+        let item_id = Self::first_saved_in(&mut conn, user_id, url).await?;
+
+        match item_id {
+            Some(item_id) => self.find_for_user(user_id, item_id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn fingerprint(
+        &self,
+        user_id: Uuid,
+        filter: &ItemFilter,
+    ) -> Result<(i64, Option<DateTime<Utc>>)> {
+        let fingerprint = sqlx::query_as::<_, (i64, Option<DateTime<Utc>>)>(&format!(
+            r#"
+            SELECT COUNT(*), MAX(i.updated_at)
+            FROM items i
+            WHERE {}
+            "#,
+            ITEM_FILTER_SQL
+        ))
+        .bind(user_id)
+        .bind(filter.lang.as_deref())
+        .bind(filter.failed_only)
+        .bind(filter.status)
+        .bind(filter.tag.as_deref())
+        .bind(filter.q.as_deref().map(like_pattern))
+        .bind(filter.topic.as_deref())
+        .bind(filter.reading_time.and_then(ReadingTime::max_words))
+        .bind(filter.reading_time.and_then(ReadingTime::min_words))
+        .bind(filter.max_reading_time)
+        .bind(filter.site.as_deref())
+        .bind(filter.created_before)
+        .bind(filter.created_after)
+        .bind(filter.hide_snoozed)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(fingerprint)
+    }
+
+    /// Tags and reading time are aggregated in the same query rather than
+    /// loaded per item
+    async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        filter: &ItemFilter,
+        sort: ItemSort,
+        after: Option<KeysetCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ItemDetails>> {
+        self.details(user_id, filter, sort, None, after, limit, offset)
+            .await
+    }
+
+    async fn update(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+        title: Option<String>,
+        status: Option<ItemStatus>,
+        version: Option<i64>,
+    ) -> Result<ItemEdit> {
+        let allowed: Vec<&str> = status
+            .map(|status| status.predecessors().iter().map(|s| s.as_str()).collect())
+            .unwrap_or_default();
+
+        let row = sqlx::query!(
+            r#"
+            WITH item AS (
+                SELECT id, version FROM items WHERE id = $1 AND user_id = $2 FOR UPDATE
+            ),
+            updated AS (
+                UPDATE items
+                SET title = COALESCE($3, title),
+                    status = COALESCE($4, status),
+                    version = version + 1,
+                    updated_at = NOW()
+                WHERE id = (SELECT id FROM item)
+                  AND ($4::item_status IS NULL OR status::text = ANY($5))
+                  AND ($6::bigint IS NULL OR version = $6)
+                RETURNING version
+            )
+            SELECT EXISTS (SELECT 1 FROM updated) AS "updated!",
+                   COALESCE((SELECT version FROM updated), item.version) AS "version!"
+            FROM item
+            "#,
+            item_id,
+            user_id,
+            title,
+            status as Option<ItemStatus>,
+            &allowed as &[&str],
+            version
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .map(|row| (row.updated, row.version));
+
+        Ok(ItemEdit::from_row(row, version))
+    }
+
+    async fn transition(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+        status: ItemStatus,
+    ) -> Result<ItemEdit> {
+        self.update(user_id, item_id, None, Some(status), None)
+            .await
+    }
+
+    async fn delete(&self, user_id: Uuid, item_id: Uuid) -> Result<bool> {
+        let mut conn = self.pool.acquire().await?;
+        self.delete_in(&mut conn, user_id, item_id).await
+    }
+
+    async fn set_progress(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+        progress: f32,
+        version: Option<i64>,
+    ) -> Result<ItemEdit> {
+        let row = sqlx::query!(
+            r#"
+            WITH item AS (
+                SELECT id, version FROM items WHERE id = $1 AND user_id = $2 FOR UPDATE
+            ),
+            updated AS (
+                UPDATE items
+                SET read_progress = $3, version = version + 1
+                WHERE id = (SELECT id FROM item)
+                  AND ($4::bigint IS NULL OR version = $4)
+                RETURNING version
+            )
+            SELECT EXISTS (SELECT 1 FROM updated) AS "updated!",
+                   COALESCE((SELECT version FROM updated), item.version) AS "version!"
+            FROM item
+            "#,
+            item_id,
+            user_id,
+            progress,
+            version
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .map(|row| (row.updated, row.version));
+
+        Ok(ItemEdit::from_row(row, version))
+    }
+
+    /// Seeks to a random point in the id space and takes the first unread
+    /// item at or after it, wrapping around to the smallest id. Item ids are
+    /// random, so this is close to uniform, and each branch is a single
+    /// probe of `idx_items_user_unread_id` however large the library is,
+    /// unlike `ORDER BY random()`, which reads every unread row.
+    async fn random_unread(
+        &self,
+        user_id: Uuid,
+        status: Option<ItemStatus>,
+        include_snoozed: bool,
+    ) -> Result<Option<ItemDetails>> {
+        let item_id: Option<Uuid> = sqlx::query_scalar!(
+            r#"
+            (
+                SELECT i.id AS "id!" FROM items i
+                WHERE i.user_id = $1 AND i.status <> 'archived' AND i.read_progress < $2
+                  AND ($3::item_status IS NULL OR i.status = $3)
+                  AND ($5 OR i.snoozed_until IS NULL OR i.snoozed_until <= NOW())
+                  AND i.id >= $4
+                ORDER BY i.id
+                LIMIT 1
+            )
+            UNION ALL
+            (
+                SELECT i.id FROM items i
+                WHERE i.user_id = $1 AND i.status <> 'archived' AND i.read_progress < $2
+                  AND ($3::item_status IS NULL OR i.status = $3)
+                  AND ($5 OR i.snoozed_until IS NULL OR i.snoozed_until <= NOW())
+                ORDER BY i.id
+                LIMIT 1
+            )
+            LIMIT 1
+            "#,
+            user_id,
+            READ_THRESHOLD,
+            status as Option<ItemStatus>,
+            Uuid::new_v4(),
+            include_snoozed
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match item_id {
+            Some(item_id) => self.find_for_user(user_id, item_id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn snooze(&self, user_id: Uuid, item_id: Uuid, until: DateTime<Utc>) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE items SET snoozed_until = $3 WHERE id = $1 AND user_id = $2",
+            item_id,
+            user_id,
+            until
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn set_refresh_policy(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+        interval_secs: Option<i32>,
+        next_refresh_at: Option<DateTime<Utc>>,
+        watch: bool,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE items
+            SET refresh_interval_secs = $3, next_refresh_at = $4, watch_changes = $5
+            WHERE id = $1 AND user_id = $2
+            "#,
+            item_id,
+            user_id,
+            interval_secs,
+            next_refresh_at,
+            watch
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn extraction_error_in(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_id: Uuid,
+    ) -> Result<Option<Option<String>>> {
+        let extraction_error = sqlx::query_scalar!(
+            "SELECT extraction_error FROM items WHERE id = $1 AND user_id = $2 FOR UPDATE",
+            item_id,
+            user_id
+        )
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(extraction_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_like_pattern_escapes_wildcards() {
+        assert_eq!(like_pattern("rust"), "%rust%");
+        assert_eq!(like_pattern("100%_done\\"), "%100\\%\\_done\\\\%");
+    }
+
+    #[test]
+    fn test_reading_time_parse_and_word_bounds() {
+        assert_eq!("<5".parse(), Ok(ReadingTime::Under(5)));
+        assert_eq!(" >20 ".parse(), Ok(ReadingTime::Over(20)));
+        for invalid in ["5", "<", "<0", "<x", "=5", ">-1", ""] {
+            assert!(invalid.parse::<ReadingTime>().is_err(), "{}", invalid);
+        }
+
+        // 952 words round up to 4 minutes, 953 to 5
+        assert_eq!(ReadingTime::QUICK.max_words(), Some(952));
+        assert_eq!(ReadingTime::QUICK.min_words(), None);
+        // 4760 words are exactly 20 minutes
+        assert_eq!(ReadingTime::LONG.min_words(), Some(4760));
+        assert_eq!(ReadingTime::LONG.max_words(), None);
+    }
+
+    #[test]
+    fn test_item_edit_from_row() {
+        assert_eq!(ItemEdit::from_row(None, Some(1)), ItemEdit::NotFound);
+        assert_eq!(
+            ItemEdit::from_row(Some((true, 4)), Some(3)),
+            ItemEdit::Applied { version: 4 }
+        );
+        assert_eq!(
+            ItemEdit::from_row(Some((false, 4)), Some(3)),
+            ItemEdit::Stale
+        );
+        // At the version asked for, so it was the status that held it back
+        assert_eq!(
+            ItemEdit::from_row(Some((false, 3)), Some(3)),
+            ItemEdit::StatusNotAllowed
+        );
+        assert_eq!(
+            ItemEdit::from_row(Some((false, 3)), None),
+            ItemEdit::StatusNotAllowed
+        );
+    }
+}
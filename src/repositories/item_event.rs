@@ -0,0 +1,67 @@
+use crate::entities::{ItemEvent, ItemEventKind};
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for the lifecycle events shown in an item's activity timeline
+pub struct ItemEventRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> ItemEventRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Append an event to an item's timeline
+    pub async fn record(
+        &self,
+        item_id: Uuid,
+        kind: ItemEventKind,
+        detail: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO item_events (item_id, kind, detail) VALUES ($1, $2, $3)",
+            item_id,
+            kind as ItemEventKind,
+            detail
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Timeline of one of the user's items, oldest first. Returns None if the
+    /// item doesn't exist or belongs to someone else.
+    pub async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+    ) -> Result<Option<Vec<ItemEvent>>> {
+        let owned: Option<Uuid> = sqlx::query_scalar!(
+            "SELECT id FROM items WHERE id = $1 AND user_id = $2",
+            item_id,
+            user_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+        if owned.is_none() {
+            return Ok(None);
+        }
+
+        let events = sqlx::query_as!(
+            ItemEvent,
+            r#"
+            SELECT id, item_id, kind AS "kind: ItemEventKind", detail, created_at
+            FROM item_events
+            WHERE item_id = $1
+            ORDER BY id
+            "#,
+            item_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(Some(events))
+    }
+}
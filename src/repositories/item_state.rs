@@ -0,0 +1,81 @@
+use crate::entities::{ItemStateTransition, ProcessingState};
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Repository for an item's processing state and its transition history
+pub struct ItemStateRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> ItemStateRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Move an item into `to` and record the transition. Returns false without
+    /// changing anything when the item's current state can't lead to `to`.
+    pub async fn transition(&self, item_id: Uuid, to: ProcessingState) -> Result<bool> {
+        let allowed: Vec<&str> = to.predecessors().iter().map(|s| s.as_str()).collect();
+
+        let recorded: Option<i64> = sqlx::query_scalar!(
+            r#"
+            WITH prev AS (
+                SELECT processing_state FROM items WHERE id = $1 FOR UPDATE
+            ),
+            updated AS (
+                UPDATE items
+                SET processing_state = $2, processing_state_changed_at = NOW()
+                WHERE id = $1 AND processing_state::text = ANY($3)
+                RETURNING id
+            )
+            INSERT INTO item_state_transitions (item_id, from_state, to_state)
+            SELECT $1, prev.processing_state, $2
+            FROM prev, updated
+            RETURNING id
+            "#,
+            item_id,
+            to as ProcessingState,
+            &allowed as &[&str]
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(recorded.is_some())
+    }
+
+    /// Transition history for one of the user's items, oldest first. Returns
+    /// None if the item doesn't exist or belongs to someone else.
+    pub async fn history(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+    ) -> Result<Option<Vec<ItemStateTransition>>> {
+        let owned: Option<Uuid> = sqlx::query_scalar!(
+            "SELECT id FROM items WHERE id = $1 AND user_id = $2",
+            item_id,
+            user_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+        if owned.is_none() {
+            return Ok(None);
+        }
+
+        let transitions = sqlx::query_as!(
+            ItemStateTransition,
+            r#"
+            SELECT id, item_id, from_state AS "from_state: ProcessingState",
+                   to_state AS "to_state: ProcessingState", created_at
+            FROM item_state_transitions
+            WHERE item_id = $1
+            ORDER BY id
+            "#,
+            item_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(Some(transitions))
+    }
+}
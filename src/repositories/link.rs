@@ -0,0 +1,178 @@
+use crate::{entities::LinkedItem, repositories::url_hash};
+use anyhow::Result;
+use sqlx::PgPool;
+use url::Url;
+use uuid::Uuid;
+
+/// Items hashed per pass when filling in missing URL hashes
+const URL_HASH_BATCH_SIZE: i64 = 1000;
+
+/// An item's place in the user's link graph
+#[derive(Debug, Clone, Default)]
+pub struct ItemLinks {
+    /// Saved items the item links to, in the order the links appear
+    pub cites: Vec<LinkedItem>,
+    /// Saved items linking to the item, newest first
+    pub cited_by: Vec<LinkedItem>,
+    /// Links to pages that aren't saved, in the order they appear
+    pub unsaved: Vec<String>,
+}
+
+/// Repository for the links between items, recorded from extracted content
+/// and matched to items by the hash of their normalized URL
+pub struct LinkRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> LinkRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Replace the item's outbound links with `links`, and make sure the
+    /// owner's items can be found as link targets
+    pub async fn record(&self, item_id: Uuid, links: &[Url]) -> Result<()> {
+        let (urls, hashes): (Vec<String>, Vec<String>) = links
+            .iter()
+            .filter_map(|url| url_hash(url.as_str()).map(|hash| (url.to_string(), hash)))
+            .unzip();
+        let positions: Vec<i32> = (0..urls.len() as i32).collect();
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!("DELETE FROM item_links WHERE item_id = $1", item_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO item_links (item_id, url, url_hash, position)
+            SELECT $1, url, url_hash, position
+            FROM UNNEST($2::text[], $3::text[], $4::int[]) AS l(url, url_hash, position)
+            ON CONFLICT (item_id, url_hash) DO NOTHING
+            "#,
+            item_id,
+            &urls,
+            &hashes,
+            &positions
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        let owner: Option<Uuid> =
+            sqlx::query_scalar!("SELECT user_id FROM items WHERE id = $1", item_id)
+                .fetch_optional(self.pool)
+                .await?;
+        if let Some(owner) = owner {
+            self.hash_urls(owner).await?;
+        }
+        Ok(())
+    }
+
+    /// Fill in the URL hash of the user's items that don't have one yet,
+    /// returning how many were hashed. URLs that don't parse get an empty
+    /// hash, which no link matches.
+    pub async fn hash_urls(&self, user_id: Uuid) -> Result<u64> {
+        let mut hashed = 0;
+        loop {
+            let items = sqlx::query!(
+                "SELECT id, url FROM items WHERE user_id = $1 AND url_hash IS NULL LIMIT $2",
+                user_id,
+                URL_HASH_BATCH_SIZE
+            )
+            .fetch_all(self.pool)
+            .await?;
+            if items.is_empty() {
+                return Ok(hashed);
+            }
+
+            let (ids, hashes): (Vec<Uuid>, Vec<String>) = items
+                .into_iter()
+                .map(|item| (item.id, url_hash(&item.url).unwrap_or_default()))
+                .unzip();
+            hashed += sqlx::query!(
+                r#"
+                UPDATE items SET url_hash = h.url_hash
+                FROM UNNEST($1::uuid[], $2::text[]) AS h(id, url_hash)
+                WHERE items.id = h.id
+                "#,
+                &ids,
+                &hashes
+            )
+            .execute(self.pool)
+            .await?
+            .rows_affected();
+        }
+    }
+
+    /// Links from and to one of the user's items. Returns None if the item
+    /// doesn't exist or belongs to someone else.
+    pub async fn links_for_user(&self, user_id: Uuid, item_id: Uuid) -> Result<Option<ItemLinks>> {
+        let owned: Option<Uuid> = sqlx::query_scalar!(
+            "SELECT id FROM items WHERE id = $1 AND user_id = $2",
+            item_id,
+            user_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+        if owned.is_none() {
+            return Ok(None);
+        }
+        // Items saved since their owner's last extraction aren't hashed yet
+        self.hash_urls(user_id).await?;
+
+        let cites = sqlx::query_as!(
+            LinkedItem,
+            r#"
+            SELECT DISTINCT ON (l.position) i.id, i.url, i.title, s.name AS site
+            FROM item_links l
+            JOIN items i ON i.url_hash = l.url_hash AND i.user_id = $2
+            LEFT JOIN sites s ON s.host = i.domain
+            WHERE l.item_id = $1 AND i.id <> $1
+            ORDER BY l.position, i.created_at
+            "#,
+            item_id,
+            user_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let cited_by = sqlx::query_as!(
+            LinkedItem,
+            r#"
+            SELECT i.id, i.url, i.title, s.name AS site
+            FROM items target
+            JOIN item_links l ON l.url_hash = target.url_hash
+            JOIN items i ON i.id = l.item_id AND i.user_id = $2
+            LEFT JOIN sites s ON s.host = i.domain
+            WHERE target.id = $1 AND i.id <> $1
+            ORDER BY i.created_at DESC
+            "#,
+            item_id,
+            user_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        let unsaved = sqlx::query_scalar!(
+            r#"
+            SELECT l.url
+            FROM item_links l
+            WHERE l.item_id = $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM items i WHERE i.user_id = $2 AND i.url_hash = l.url_hash
+              )
+            ORDER BY l.position
+            "#,
+            item_id,
+            user_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(Some(ItemLinks {
+            cites,
+            cited_by,
+            unsaved,
+        }))
+    }
+}
@@ -1,5 +1,64 @@
+pub mod backup;
+pub mod compression;
 pub mod content;
+pub mod data_request;
+pub mod document;
+pub mod domain_prefs;
+pub mod domain_rules;
+pub mod embedding;
+pub mod fetch_attempt;
+pub mod fetch_cache;
+pub mod highlight;
+pub mod import;
+pub mod item;
+pub mod item_event;
+pub mod item_state;
+pub mod link;
+pub mod notification;
+pub mod operation;
+pub mod read_event;
+pub mod schema;
+pub mod search;
+pub mod share;
+pub mod site;
+pub mod stats;
+pub mod tag;
+pub mod throttle;
+pub mod topic;
+pub mod translation;
+pub mod undo;
 pub mod user;
 
-pub use content::ContentRepository;
+pub use backup::{BACKUP_TABLES, BackupRepository, BackupTable, backup_table};
+pub use compression::{compress_html, decompress_html};
+pub use content::{CleanContent, ContentFields, ContentRepository, SummarySource};
+pub use data_request::DataRequestRepository;
+pub use document::{DocumentRepository, url_hash};
+pub use domain_prefs::DomainPrefsRepository;
+pub use domain_rules::DomainRulesRepository;
+pub use embedding::EmbeddingRepository;
+pub use fetch_attempt::{FetchAttemptRepository, MAX_LISTED_FETCH_ATTEMPTS};
+pub use fetch_cache::{CachedFetch, Extraction, FetchCacheRepository};
+pub use highlight::HighlightRepository;
+pub use import::ImportRepository;
+pub use item::{
+    ItemDetails, ItemEdit, ItemFilter, ItemRepository, ItemRepositoryTrait, ItemSort,
+    ItemSortField, ReadingTime,
+};
+pub use item_event::ItemEventRepository;
+pub use item_state::ItemStateRepository;
+pub use link::{ItemLinks, LinkRepository};
+pub use notification::NotificationRepository;
+pub use operation::{MAX_OPERATION_ERROR_SAMPLES, OperationRepository};
+pub use read_event::{HistoryEntry, ReadEventRepository};
+pub use schema::{AppliedMigration, SchemaRepository};
+pub use search::{SearchHit, SearchRepository};
+pub use share::{ShareRepository, SharedItem};
+pub use site::{SITE_ICON_REFRESH_DAYS, SiteRepository, UserSite};
+pub use stats::{ItemStats, LanguageCount, READ_THRESHOLD, SiteStats, StatsRepository, TagCount};
+pub use tag::{RenamedTag, TagRepository};
+pub use throttle::ThrottleRepository;
+pub use topic::{TopicCount, TopicRepository};
+pub use translation::{TranslationRepository, TranslationSource};
+pub use undo::{UNDO_WINDOW_SECS, UndoAction, UndoRepository, UndoStep};
 pub use user::{UserRepository, UserRepositoryTrait};
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{entities::Notification, pagination};
+
+/// Repository for per-user notification events
+pub struct NotificationRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> NotificationRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a notification event for a user
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        kind: &str,
+        payload: Value,
+        email_pending: bool,
+    ) -> Result<Notification> {
+        let notification = sqlx::query_as!(
+            Notification,
+            r#"
+            INSERT INTO notifications (user_id, kind, payload, email_pending)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, user_id, kind, payload, email_pending, created_at
+            "#,
+            user_id,
+            kind,
+            payload,
+            email_pending
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(notification)
+    }
+
+    /// Events newer than `after_id`, oldest first
+    pub async fn list_after(
+        &self,
+        user_id: Uuid,
+        after_id: i64,
+        limit: i64,
+    ) -> Result<Vec<Notification>> {
+        let notifications = sqlx::query_as!(
+            Notification,
+            r#"
+            SELECT id, user_id, kind, payload, email_pending, created_at
+            FROM notifications
+            WHERE user_id = $1 AND id > $2
+            ORDER BY id
+            LIMIT $3
+            "#,
+            user_id,
+            after_id,
+            limit
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(notifications)
+    }
+
+    /// The planner's guess at how many events are newer than `after_id`
+    pub async fn estimate_after(&self, user_id: Uuid, after_id: i64) -> Result<Option<i64>> {
+        let plan: Value = sqlx::query_scalar(&pagination::explain(
+            "SELECT id FROM notifications WHERE user_id = $1 AND id > $2",
+        ))
+        .bind(user_id)
+        .bind(after_id)
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(pagination::planner_estimate(&plan))
+    }
+
+    /// ID of the user's most recent event, or 0 if there are none
+    pub async fn latest_id(&self, user_id: Uuid) -> Result<i64> {
+        let latest: Option<i64> = sqlx::query_scalar!(
+            "SELECT MAX(id) FROM notifications WHERE user_id = $1",
+            user_id
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(latest.unwrap_or(0))
+    }
+}
@@ -0,0 +1,151 @@
+use crate::entities::{Operation, OperationErrorSample, OperationKind, OperationState};
+use anyhow::Result;
+use sqlx::{PgConnection, PgPool, types::Json};
+use uuid::Uuid;
+
+/// How many errors an operation keeps as samples; later ones are only counted
+pub const MAX_OPERATION_ERROR_SAMPLES: i32 = 10;
+
+/// Repository for long-running operations, created by the API and advanced
+/// by the job handlers doing the work
+pub struct OperationRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> OperationRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Register a pending operation for the user
+    pub async fn create(&self, user_id: Uuid, kind: OperationKind) -> Result<Operation> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_in(&mut conn, user_id, kind).await
+    }
+
+    /// [`create`](Self::create) on the caller's connection, so the operation
+    /// can be registered in the same transaction that stages its job
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        kind: OperationKind,
+    ) -> Result<Operation> {
+        let operation = sqlx::query_as!(
+            Operation,
+            r#"
+            INSERT INTO operations (user_id, kind)
+            VALUES ($1, $2)
+            RETURNING id, user_id, kind AS "kind: OperationKind", state AS "state: OperationState",
+                      processed, total, error_count,
+                      error_samples AS "error_samples: Json<Vec<OperationErrorSample>>",
+                      created_at, updated_at, finished_at
+            "#,
+            user_id,
+            kind as OperationKind
+        )
+        .fetch_one(conn)
+        .await?;
+
+        Ok(operation)
+    }
+
+    /// Mark the operation as running, with its total once known
+    pub async fn start(&self, id: Uuid, total: Option<i32>) -> Result<()> {
+        sqlx::query!(
+            "UPDATE operations SET state = 'running', total = COALESCE($2, total) WHERE id = $1",
+            id,
+            total
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Count `processed` more inputs as handled, successfully or not
+    pub async fn advance(&self, id: Uuid, processed: i32) -> Result<()> {
+        sqlx::query!(
+            "UPDATE operations SET processed = processed + $2 WHERE id = $1",
+            id,
+            processed
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Count an input as failed, keeping it as a sample if there's room
+    pub async fn record_error(&self, id: Uuid, sample: &OperationErrorSample) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE operations
+            SET error_count = error_count + 1,
+                error_samples = CASE
+                    WHEN jsonb_array_length(error_samples) < $3
+                        THEN error_samples || jsonb_build_array($2::jsonb)
+                    ELSE error_samples
+                END
+            WHERE id = $1
+            "#,
+            id,
+            Json(sample) as _,
+            MAX_OPERATION_ERROR_SAMPLES
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Settle the operation as succeeded or failed
+    pub async fn finish(&self, id: Uuid, state: OperationState) -> Result<()> {
+        sqlx::query!(
+            "UPDATE operations SET state = $2, finished_at = NOW() WHERE id = $1",
+            id,
+            state as OperationState
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Look up an operation regardless of owner, for the jobs working on it
+    pub async fn find(&self, id: Uuid) -> Result<Option<Operation>> {
+        let operation = sqlx::query_as!(
+            Operation,
+            r#"
+            SELECT id, user_id, kind AS "kind: OperationKind", state AS "state: OperationState",
+                   processed, total, error_count,
+                   error_samples AS "error_samples: Json<Vec<OperationErrorSample>>",
+                   created_at, updated_at, finished_at
+            FROM operations
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(operation)
+    }
+
+    /// One of the user's operations; None if it doesn't exist or belongs to
+    /// someone else
+    pub async fn find_for_user(&self, user_id: Uuid, id: Uuid) -> Result<Option<Operation>> {
+        let operation = sqlx::query_as!(
+            Operation,
+            r#"
+            SELECT id, user_id, kind AS "kind: OperationKind", state AS "state: OperationState",
+                   processed, total, error_count,
+                   error_samples AS "error_samples: Json<Vec<OperationErrorSample>>",
+                   created_at, updated_at, finished_at
+            FROM operations
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(operation)
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{entities::ReadEvent, pagination::KeysetCursor};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// A read event together with what the opened item was
+#[derive(Debug, Clone, FromRow)]
+pub struct HistoryEntry {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub url: String,
+    pub title: Option<String>,
+    pub site: Option<String>,
+    pub opened_at: DateTime<Utc>,
+}
+
+impl HistoryEntry {
+    /// Cursor for the page after this entry
+    pub fn cursor(&self) -> KeysetCursor {
+        KeysetCursor::new(self.opened_at, self.id)
+    }
+}
+
+/// Repository for the user's reading history
+pub struct ReadEventRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> ReadEventRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record the user opening one of their items. Returns None when the
+    /// item isn't theirs.
+    pub async fn record(&self, user_id: Uuid, item_id: Uuid) -> Result<Option<ReadEvent>> {
+        let event = sqlx::query_as!(
+            ReadEvent,
+            r#"
+            INSERT INTO read_events (item_id, user_id)
+            SELECT id, user_id
+            FROM items
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, item_id, user_id, opened_at
+            "#,
+            item_id,
+            user_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// One page of the user's reading history, most recently opened first,
+    /// starting after `after` when given. Items opened several times appear
+    /// once per open.
+    pub async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        after: Option<KeysetCursor>,
+        limit: i64,
+    ) -> Result<Vec<HistoryEntry>> {
+        let entries = sqlx::query_as!(
+            HistoryEntry,
+            r#"
+            SELECT r.id, r.item_id, i.url, i.title, s.name AS site, r.opened_at
+            FROM read_events r
+            JOIN items i ON i.id = r.item_id
+            LEFT JOIN sites s ON s.host = i.domain
+            WHERE r.user_id = $1
+              AND ($2::text IS NULL OR (r.opened_at, r.id) < ($2::timestamptz, $3))
+            ORDER BY r.opened_at DESC, r.id DESC
+            LIMIT $4
+            "#,
+            user_id,
+            after.as_ref().map(|after| after.key.to_sql_text()),
+            after.map(|after| after.id),
+            limit
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// How many times the user has opened their items
+    pub async fn count_for_user(&self, user_id: Uuid) -> Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM read_events WHERE user_id = $1"#,
+            user_id
+        )
+        .fetch_one(self.pool)
+        .await?;
+        Ok(count)
+    }
+}
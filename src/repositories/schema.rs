@@ -0,0 +1,48 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+
+/// A row of sqlx's `_sqlx_migrations` bookkeeping table
+#[derive(Debug, Clone, FromRow)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub description: String,
+    pub installed_on: DateTime<Utc>,
+    /// False when the migration failed part way, leaving the schema dirty
+    pub success: bool,
+    pub checksum: Vec<u8>,
+}
+
+/// Repository for what migrations the database has seen
+pub struct SchemaRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> SchemaRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Migrations recorded in the database, oldest first; empty when none
+    /// have ever run
+    pub async fn applied(&self) -> Result<Vec<AppliedMigration>> {
+        let exists: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(self.pool)
+            .await?;
+        if !exists {
+            return Ok(Vec::new());
+        }
+
+        let migrations = sqlx::query_as::<_, AppliedMigration>(
+            r#"
+            SELECT version, description, installed_on, success, checksum
+            FROM _sqlx_migrations
+            ORDER BY version
+            "#,
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(migrations)
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{entities::SearchScope, pagination};
+use anyhow::Result;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// A single ranked search match
+#[derive(Debug, Clone, FromRow)]
+pub struct SearchHit {
+    pub item_id: Uuid,
+    pub title: Option<String>,
+    pub url: String,
+    /// Which source matched: `content`, `notes` or `highlights`
+    pub source: String,
+    /// Unescaped text with matches between the markers of
+    /// [`search::snippet`](crate::search::snippet)
+    pub snippet: String,
+    pub rank: f32,
+}
+
+/// Every hit for a query, unordered. Binds the user, the query, whether to
+/// search content, notes and highlights, then the language and topic
+/// filters. Each branch filters on the same expression as its GIN index so
+/// Postgres can use it. Snippets mark matches with the code points of
+/// [`HIGHLIGHT_START`](crate::search::snippet::HIGHLIGHT_START) and
+/// [`HIGHLIGHT_STOP`](crate::search::snippet::HIGHLIGHT_STOP) and leave the
+/// surrounding text as stored.
+const SEARCH_HITS_SQL: &str = r#"
+    WITH q AS (
+        SELECT websearch_to_tsquery('simple', $2) AS query,
+               format('MaxFragments=1, MaxWords=30, MinWords=10, StartSel="%s", StopSel="%s"',
+                      chr(57344), chr(57345)) AS headline_options
+    )
+    SELECT item_id, title, url, source, snippet, rank
+    FROM (
+        SELECT i.id AS item_id, i.title, i.url, 'content' AS source,
+               ts_headline('simple', t.clean_text, q.query, q.headline_options) AS snippet,
+               ts_rank(to_tsvector('simple', t.clean_text), q.query) AS rank
+        FROM items i
+        JOIN contents c ON c.item_id = i.id
+        LEFT JOIN documents d ON d.id = i.document_id
+        CROSS JOIN LATERAL (
+            SELECT COALESCE(c.clean_text, d.clean_text) AS clean_text
+        ) t
+        CROSS JOIN q
+        WHERE $3
+          AND i.user_id = $1
+          AND t.clean_text IS NOT NULL
+          AND to_tsvector('simple', t.clean_text) @@ q.query
+          AND ($6::text IS NULL OR lower(c.lang) = $6)
+          AND ($7::text IS NULL OR $7 = ANY(i.topics))
+
+        UNION ALL
+
+        SELECT i.id, i.title, i.url, 'notes',
+               ts_headline('simple', i.note, q.query, q.headline_options),
+               ts_rank(to_tsvector('simple', i.note), q.query)
+        FROM items i
+        CROSS JOIN q
+        WHERE $4
+          AND i.user_id = $1
+          AND i.note IS NOT NULL
+          AND to_tsvector('simple', i.note) @@ q.query
+          AND ($6::text IS NULL OR EXISTS (
+              SELECT 1 FROM contents lc WHERE lc.item_id = i.id AND lower(lc.lang) = $6
+          ))
+          AND ($7::text IS NULL OR $7 = ANY(i.topics))
+
+        UNION ALL
+
+        SELECT i.id, i.title, i.url, 'highlights',
+               ts_headline('simple', h.quote, q.query, q.headline_options),
+               ts_rank(to_tsvector('simple', h.quote), q.query)
+        FROM highlights h
+        JOIN items i ON i.id = h.item_id
+        CROSS JOIN q
+        WHERE $5
+          AND h.user_id = $1
+          AND to_tsvector('simple', h.quote) @@ q.query
+          AND ($6::text IS NULL OR EXISTS (
+              SELECT 1 FROM contents lc WHERE lc.item_id = i.id AND lower(lc.lang) = $6
+          ))
+          AND ($7::text IS NULL OR $7 = ANY(i.topics))
+    ) hits
+"#;
+
+/// Full-text search over item content and the user's own annotations
+pub struct SearchRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> SearchRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// One page of the user's hits, best first, optionally only from items
+    /// in one content language or with one topic
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search(
+        &self,
+        user_id: Uuid,
+        query: &str,
+        scope: SearchScope,
+        lang: Option<&str>,
+        topic: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<SearchHit>> {
+        let hits = sqlx::query_as::<_, SearchHit>(&format!(
+            "{} ORDER BY rank DESC, item_id LIMIT $8 OFFSET $9",
+            SEARCH_HITS_SQL
+        ))
+        .bind(user_id)
+        .bind(query)
+        .bind(scope.includes(SearchScope::Content))
+        .bind(scope.includes(SearchScope::Notes))
+        .bind(scope.includes(SearchScope::Highlights))
+        .bind(lang)
+        .bind(topic)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(hits)
+    }
+
+    /// The planner's guess at how many hits [`search`](Self::search) finds
+    /// across all pages, without running the search
+    pub async fn estimate_total(
+        &self,
+        user_id: Uuid,
+        query: &str,
+        scope: SearchScope,
+        lang: Option<&str>,
+        topic: Option<&str>,
+    ) -> Result<Option<i64>> {
+        let plan: serde_json::Value = sqlx::query_scalar(&pagination::explain(SEARCH_HITS_SQL))
+            .bind(user_id)
+            .bind(query)
+            .bind(scope.includes(SearchScope::Content))
+            .bind(scope.includes(SearchScope::Notes))
+            .bind(scope.includes(SearchScope::Highlights))
+            .bind(lang)
+            .bind(topic)
+            .fetch_one(self.pool)
+            .await?;
+
+        Ok(pagination::planner_estimate(&plan))
+    }
+}
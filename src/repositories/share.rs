@@ -0,0 +1,126 @@
+use crate::{entities::Share, repositories::compression::stored_html};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// What a share link shows of its item
+#[derive(Debug, Clone)]
+pub struct SharedItem {
+    pub url: String,
+    pub title: Option<String>,
+    pub site: Option<String>,
+    /// None until the item's page has been extracted
+    pub clean_html: Option<String>,
+}
+
+/// [`SharedItem`] as stored, with the HTML in whichever of its columns
+/// holds it
+struct StoredSharedItem {
+    url: String,
+    title: Option<String>,
+    site: Option<String>,
+    clean_html: Option<String>,
+    clean_html_zst: Option<Vec<u8>>,
+}
+
+/// Repository for public share links to items
+pub struct ShareRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> ShareRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Share one of the user's items until `expires_at`, or until revoked
+    /// when None. Returns None when the item isn't theirs.
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<Share>> {
+        let share = sqlx::query_as!(
+            Share,
+            r#"
+            INSERT INTO shares (item_id, user_id, expires_at)
+            SELECT id, user_id, $3
+            FROM items
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, item_id, user_id, expires_at, revoked_at, created_at
+            "#,
+            item_id,
+            user_id,
+            expires_at
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(share)
+    }
+
+    /// Revoke every live share of one of the user's items. Returns how many
+    /// were revoked, or None when the item isn't theirs.
+    pub async fn revoke_for_item(&self, user_id: Uuid, item_id: Uuid) -> Result<Option<u64>> {
+        let mut tx = self.pool.begin().await?;
+        let owned: Option<Uuid> = sqlx::query_scalar!(
+            "SELECT id FROM items WHERE id = $1 AND user_id = $2",
+            item_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if owned.is_none() {
+            return Ok(None);
+        }
+
+        let result = sqlx::query!(
+            "UPDATE shares SET revoked_at = NOW() WHERE item_id = $1 AND revoked_at IS NULL",
+            item_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(Some(result.rows_affected()))
+    }
+
+    /// The item shared by `share_id`, while the share is neither revoked
+    /// nor expired. The item's own copy of the HTML wins over the shared
+    /// document's, whichever column of each holds it.
+    pub async fn find_shared(&self, share_id: Uuid) -> Result<Option<SharedItem>> {
+        let row = sqlx::query_as!(
+            StoredSharedItem,
+            r#"
+            SELECT i.url, i.title, s.name AS "site?",
+                   CASE WHEN c.clean_html IS NOT NULL OR c.clean_html_zst IS NOT NULL
+                        THEN c.clean_html ELSE d.clean_html END AS clean_html,
+                   CASE WHEN c.clean_html IS NOT NULL OR c.clean_html_zst IS NOT NULL
+                        THEN c.clean_html_zst ELSE d.clean_html_zst END AS clean_html_zst
+            FROM shares sh
+            JOIN items i ON i.id = sh.item_id
+            LEFT JOIN sites s ON s.host = i.domain
+            LEFT JOIN contents c ON c.item_id = i.id
+            LEFT JOIN documents d ON d.id = i.document_id
+            WHERE sh.id = $1
+              AND sh.revoked_at IS NULL
+              AND (sh.expires_at IS NULL OR sh.expires_at > NOW())
+            "#,
+            share_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        row.map(|row| {
+            Ok(SharedItem {
+                clean_html: stored_html(row.clean_html, row.clean_html_zst.as_deref())?,
+                url: row.url,
+                title: row.title,
+                site: row.site,
+            })
+        })
+        .transpose()
+    }
+}
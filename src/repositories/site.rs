@@ -0,0 +1,238 @@
+use crate::{
+    entities::{Site, SiteIcon},
+    repositories::domain_prefs::normalize_domain,
+};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// How long an icon, or the finding that a site has none, is kept before
+/// the site is looked at again
+pub const SITE_ICON_REFRESH_DAYS: i64 = 7;
+
+/// Subquery for the most specific domain rule covering `host`, a column or
+/// parameter
+fn domain_rule_sql(host: &str) -> String {
+    format!(
+        r#"(
+            SELECT r.domain FROM domain_rules r
+            WHERE {host} = r.domain OR right({host}, length(r.domain) + 1) = '.' || r.domain
+            ORDER BY length(r.domain) DESC
+            LIMIT 1
+        )"#
+    )
+}
+
+/// A site with how many of one user's items come from it. Sites whose pages
+/// haven't been fetched yet are listed with only their host.
+#[derive(Debug, Clone, FromRow)]
+pub struct UserSite {
+    pub host: String,
+    pub name: Option<String>,
+    pub feed_url: Option<String>,
+    pub has_icon: bool,
+    pub item_count: i64,
+    pub last_saved_at: DateTime<Utc>,
+}
+
+/// Repository for the sites items come from and their icons
+pub struct SiteRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> SiteRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Note what a fetched page of the item's site says about the site,
+    /// keeping what's already known where the page says nothing. Returns
+    /// the site's host, or None when the item's URL has none.
+    pub async fn record_for_item(
+        &self,
+        item_id: Uuid,
+        name: Option<&str>,
+        feed_url: Option<&str>,
+    ) -> Result<Option<String>> {
+        let host = sqlx::query_scalar(&format!(
+            r#"
+            INSERT INTO sites AS s (host, name, feed_url, domain_rule)
+            SELECT i.domain, $2, $3, {}
+            FROM items i
+            WHERE i.id = $1 AND i.domain IS NOT NULL
+            ON CONFLICT (host) DO UPDATE
+            SET name        = COALESCE(EXCLUDED.name, s.name),
+                feed_url    = COALESCE(EXCLUDED.feed_url, s.feed_url),
+                domain_rule = EXCLUDED.domain_rule
+            RETURNING s.host
+            "#,
+            domain_rule_sql("i.domain")
+        ))
+        .bind(item_id)
+        .bind(name)
+        .bind(feed_url)
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(host)
+    }
+
+    /// Point the sites `domain` covers at their most specific rule again,
+    /// after a rule for `domain` was added, changed or removed
+    pub async fn refresh_domain_rules(&self, domain: &str) -> Result<()> {
+        sqlx::query(&format!(
+            r#"
+            UPDATE sites s
+            SET domain_rule = {}
+            WHERE s.host = $1 OR right(s.host, length($1) + 1) = '.' || $1
+            "#,
+            domain_rule_sql("s.host")
+        ))
+        .bind(normalize_domain(domain))
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// One site, whatever its items
+    pub async fn find(&self, host: &str) -> Result<Option<Site>> {
+        let site = sqlx::query_as!(
+            Site,
+            r#"
+            SELECT host, name, feed_url, domain_rule, icon_data IS NOT NULL AS "has_icon!",
+                   created_at, updated_at
+            FROM sites
+            WHERE host = $1
+            "#,
+            normalize_domain(host)
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(site)
+    }
+
+    /// The sites the user has saved items from, those with the most items
+    /// first
+    pub async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        host: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<UserSite>> {
+        let sites = sqlx::query_as!(
+            UserSite,
+            r#"
+            SELECT i.domain AS "host!", s.name AS "name?", s.feed_url AS "feed_url?",
+                   COALESCE(s.icon_data IS NOT NULL, FALSE) AS "has_icon!",
+                   COUNT(*) AS "item_count!", MAX(i.created_at) AS "last_saved_at!"
+            FROM items i
+            LEFT JOIN sites s ON s.host = i.domain
+            WHERE i.user_id = $1 AND i.domain IS NOT NULL
+              AND ($2::text IS NULL OR i.domain = $2)
+            GROUP BY i.domain, s.host
+            ORDER BY COUNT(*) DESC, i.domain
+            LIMIT $3 OFFSET $4
+            "#,
+            user_id,
+            host.map(normalize_domain),
+            limit,
+            offset
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(sites)
+    }
+
+    /// How many sites the user has saved items from
+    pub async fn count_for_user(&self, user_id: Uuid) -> Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(DISTINCT domain) AS "count!" FROM items WHERE user_id = $1 AND domain IS NOT NULL"#,
+            user_id
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// One of the sites the user has saved items from
+    pub async fn find_for_user(&self, user_id: Uuid, host: &str) -> Result<Option<UserSite>> {
+        Ok(self.list_for_user(user_id, Some(host), 1, 0).await?.pop())
+    }
+
+    /// Take the job of fetching `host`'s icon. True when it has never been
+    /// looked for or was last looked for too long ago; false when another
+    /// fetch is recent or under way, so a site is fetched once however many
+    /// of its pages are saved.
+    pub async fn claim_icon(&self, host: &str) -> Result<bool> {
+        let stale_before = Utc::now() - Duration::days(SITE_ICON_REFRESH_DAYS);
+        let claimed = sqlx::query_scalar::<_, String>(&format!(
+            r#"
+            INSERT INTO sites (host, icon_fetched_at, domain_rule)
+            VALUES ($1, NOW(), {})
+            ON CONFLICT (host) DO UPDATE SET icon_fetched_at = NOW()
+            WHERE sites.icon_fetched_at IS NULL OR sites.icon_fetched_at < $2
+            RETURNING host
+            "#,
+            domain_rule_sql("$1::text")
+        ))
+        .bind(normalize_domain(host))
+        .bind(stale_before)
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(claimed.is_some())
+    }
+
+    /// Keep the icon fetched for `host` from `source_url`
+    pub async fn store_icon(
+        &self,
+        host: &str,
+        source_url: &str,
+        content_type: &str,
+        data: &[u8],
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO sites (host, icon_source_url, icon_content_type, icon_data, icon_fetched_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (host) DO UPDATE
+            SET icon_source_url   = EXCLUDED.icon_source_url,
+                icon_content_type = EXCLUDED.icon_content_type,
+                icon_data         = EXCLUDED.icon_data,
+                icon_fetched_at   = NOW()
+            "#,
+            normalize_domain(host),
+            source_url,
+            content_type,
+            data
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// `host`'s icon, when one has been fetched
+    pub async fn find_icon(&self, host: &str) -> Result<Option<SiteIcon>> {
+        let icon = sqlx::query_as!(
+            SiteIcon,
+            r#"
+            SELECT host, icon_content_type AS "content_type!", icon_data AS "data!",
+                   icon_source_url AS source_url, icon_fetched_at AS fetched_at
+            FROM sites
+            WHERE host = $1 AND icon_data IS NOT NULL AND icon_content_type IS NOT NULL
+            "#,
+            normalize_domain(host)
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(icon)
+    }
+}
@@ -0,0 +1,195 @@
+use crate::repositories::item::WORDS_PER_MINUTE;
+use anyhow::Result;
+use serde::Deserialize;
+use sqlx::{FromRow, PgConnection, PgPool, types::Json};
+use uuid::Uuid;
+
+/// Read progress at which an item counts as finished
+pub const READ_THRESHOLD: f32 = 0.9;
+
+/// Number of items per detected content language
+#[derive(Debug, Clone, FromRow)]
+pub struct LanguageCount {
+    /// None for items without extracted content or a confident detection
+    pub lang: Option<String>,
+    pub count: i64,
+}
+
+/// How the user gets on with one site's items
+#[derive(Debug, Clone, FromRow)]
+pub struct SiteStats {
+    /// Host of the items' URLs, without a leading `www.`
+    pub domain: String,
+    pub saved: i64,
+    /// Items read to at least [`READ_THRESHOLD`]
+    pub read: i64,
+    /// Mean reading time of the items with extracted text; None if none have
+    /// any
+    pub avg_reading_time_minutes: Option<f64>,
+}
+
+/// Number of the user's items with one tag
+#[derive(Debug, Clone, Deserialize)]
+pub struct TagCount {
+    pub name: String,
+    pub count: i64,
+}
+
+/// Counts over all of a user's items
+#[derive(Debug, Clone, FromRow)]
+pub struct ItemStats {
+    pub total: i64,
+    pub pending: i64,
+    pub fetched: i64,
+    pub reading: i64,
+    pub failed: i64,
+    pub archived: i64,
+    /// Items not archived and not yet read to [`READ_THRESHOLD`]
+    pub unread: i64,
+    /// Bytes of stored page content, as counted against storage quotas
+    pub storage_bytes: i64,
+    /// Every tag of the user's, most used first
+    pub tags: Json<Vec<TagCount>>,
+}
+
+/// Repository for per-user library statistics
+pub struct StatsRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> StatsRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a finished triage session and how many items got each
+    /// action, on `conn` so it commits with the changes themselves
+    pub async fn record_triage_session_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        archived: i32,
+        kept: i32,
+        tagged: i32,
+        deleted: i32,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO triage_sessions (user_id, archived, kept, tagged, deleted)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            user_id,
+            archived,
+            kept,
+            tagged,
+            deleted
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Item counts by status and tag, the unread backlog and storage used,
+    /// in one query
+    pub async fn item_stats(&self, user_id: Uuid) -> Result<ItemStats> {
+        let stats = sqlx::query_as!(
+            ItemStats,
+            r#"
+            SELECT
+                COUNT(*) AS "total!",
+                COUNT(*) FILTER (WHERE i.status = 'pending') AS "pending!",
+                COUNT(*) FILTER (WHERE i.status = 'fetched') AS "fetched!",
+                COUNT(*) FILTER (WHERE i.status = 'reading') AS "reading!",
+                COUNT(*) FILTER (WHERE i.status = 'failed') AS "failed!",
+                COUNT(*) FILTER (WHERE i.status = 'archived') AS "archived!",
+                COUNT(*) FILTER (WHERE i.status <> 'archived' AND i.read_progress < $2) AS "unread!",
+                COALESCE(SUM(
+                    COALESCE(octet_length(c.raw_html), 0)
+                    + COALESCE(octet_length(c.raw_html_zst), 0)
+                    + COALESCE(octet_length(c.raw_text), 0)
+                    + COALESCE(octet_length(c.clean_html), 0)
+                    + COALESCE(octet_length(c.clean_html_zst), 0)
+                    + COALESCE(octet_length(c.clean_text), 0)
+                ), 0)::BIGINT AS "storage_bytes!",
+                (
+                    SELECT COALESCE(
+                        jsonb_agg(
+                            jsonb_build_object('name', t.name, 'count', t.count)
+                            ORDER BY t.count DESC, t.name
+                        ),
+                        '[]'::jsonb
+                    )
+                    FROM (
+                        SELECT tg.name, COUNT(it.item_id) AS count
+                        FROM tags tg
+                        LEFT JOIN item_tags it ON it.tag_id = tg.id
+                        WHERE tg.user_id = $1
+                        GROUP BY tg.id, tg.name
+                    ) t
+                ) AS "tags!: Json<Vec<TagCount>>"
+            FROM items i
+            LEFT JOIN contents c ON c.item_id = i.id
+            WHERE i.user_id = $1
+            "#,
+            user_id,
+            READ_THRESHOLD
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// Item counts grouped by content language, most common first
+    pub async fn language_breakdown(&self, user_id: Uuid) -> Result<Vec<LanguageCount>> {
+        let counts = sqlx::query_as!(
+            LanguageCount,
+            r#"
+            SELECT lower(c.lang) AS lang, COUNT(*) AS "count!"
+            FROM items i
+            LEFT JOIN contents c ON c.item_id = i.id
+            WHERE i.user_id = $1
+            GROUP BY lower(c.lang)
+            ORDER BY COUNT(*) DESC, lower(c.lang) NULLS LAST
+            "#,
+            user_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Per-domain counts of saved and read items and their mean reading
+    /// time, most saved first
+    pub async fn site_stats(&self, user_id: Uuid, limit: i64) -> Result<Vec<SiteStats>> {
+        let stats = sqlx::query_as!(
+            SiteStats,
+            r#"
+            SELECT i.domain AS "domain!",
+                   COUNT(*) AS "saved!",
+                   COUNT(*) FILTER (WHERE i.read_progress >= $2) AS "read!",
+                   AVG(CEIL(array_length(regexp_split_to_array(s.text, '\s+'), 1) / $3::float8))::float8
+                       AS avg_reading_time_minutes
+            FROM items i
+            LEFT JOIN contents c ON c.item_id = i.id
+            LEFT JOIN documents doc ON doc.id = i.document_id
+            CROSS JOIN LATERAL (
+                SELECT NULLIF(btrim(COALESCE(c.clean_text, doc.clean_text)), '') AS text
+            ) s
+            WHERE i.user_id = $1 AND i.domain IS NOT NULL
+            GROUP BY i.domain
+            ORDER BY COUNT(*) DESC, i.domain
+            LIMIT $4
+            "#,
+            user_id,
+            READ_THRESHOLD,
+            WORDS_PER_MINUTE,
+            limit
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+}
@@ -0,0 +1,175 @@
+use anyhow::Result;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+/// A user's tag as it is after a rename
+#[derive(Debug, Clone)]
+pub struct RenamedTag {
+    pub id: Uuid,
+    pub name: String,
+    pub item_count: i64,
+    /// Whether the tag was merged into one already called its new name,
+    /// whose ID `id` is
+    pub merged: bool,
+}
+
+/// Repository for user tags and their attachment to items
+pub struct TagRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> TagRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Return the ID of the user's tag called `name`, creating it if needed
+    pub async fn find_or_create(&self, user_id: Uuid, name: &str) -> Result<Uuid> {
+        // DO UPDATE (rather than DO NOTHING) so RETURNING yields the existing row
+        let tag_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO tags (user_id, name)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id, name) DO UPDATE SET name = EXCLUDED.name
+            RETURNING id
+            "#,
+            user_id,
+            name
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(tag_id)
+    }
+
+    /// Attach a tag to an item; attaching twice is a no-op
+    pub async fn attach(&self, item_id: Uuid, tag_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO item_tags (item_id, tag_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            item_id,
+            tag_id
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Attach the user's tag called `name` to those of `item_ids` that are
+    /// theirs, on `conn`, creating the tag if needed. Returns the IDs of the
+    /// user's items among `item_ids`, tagged now or already.
+    pub async fn attach_many_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_ids: &[Uuid],
+        name: &str,
+    ) -> Result<Vec<Uuid>> {
+        let owned: Vec<Uuid> = sqlx::query_scalar!(
+            "SELECT id FROM items WHERE id = ANY($1) AND user_id = $2",
+            item_ids,
+            user_id
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+        // No tag is created when none of the items are the user's
+        if owned.is_empty() {
+            return Ok(owned);
+        }
+
+        sqlx::query!(
+            r#"
+            WITH tag AS (
+                INSERT INTO tags (user_id, name)
+                VALUES ($1, $2)
+                ON CONFLICT (user_id, name) DO UPDATE SET name = EXCLUDED.name
+                RETURNING id
+            )
+            INSERT INTO item_tags (item_id, tag_id)
+            SELECT item_id, tag.id FROM unnest($3::uuid[]) AS item_id, tag
+            ON CONFLICT DO NOTHING
+            "#,
+            user_id,
+            name,
+            &owned
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(owned)
+    }
+
+    /// Rename the user's tag `tag_id` to `name`, on `conn`. When they
+    /// already have a tag called `name` the two are merged: the items
+    /// carrying the old tag get the existing one instead and the old tag is
+    /// deleted. None when the tag isn't theirs.
+    pub async fn rename_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        tag_id: Uuid,
+        name: &str,
+    ) -> Result<Option<RenamedTag>> {
+        let found: Option<String> = sqlx::query_scalar!(
+            "SELECT name FROM tags WHERE id = $1 AND user_id = $2 FOR UPDATE",
+            tag_id,
+            user_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+        let Some(current) = found else {
+            return Ok(None);
+        };
+
+        let existing: Option<Uuid> = if current == name {
+            None
+        } else {
+            sqlx::query_scalar!(
+                "SELECT id FROM tags WHERE user_id = $1 AND name = $2 FOR UPDATE",
+                user_id,
+                name
+            )
+            .fetch_optional(&mut *conn)
+            .await?
+        };
+
+        let id = match existing {
+            Some(existing) => {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO item_tags (item_id, tag_id)
+                    SELECT item_id, $2 FROM item_tags WHERE tag_id = $1
+                    ON CONFLICT DO NOTHING
+                    "#,
+                    tag_id,
+                    existing
+                )
+                .execute(&mut *conn)
+                .await?;
+                // Its remaining item_tags rows, the duplicates, cascade away
+                sqlx::query!("DELETE FROM tags WHERE id = $1", tag_id)
+                    .execute(&mut *conn)
+                    .await?;
+                existing
+            }
+            None => {
+                sqlx::query!("UPDATE tags SET name = $2 WHERE id = $1", tag_id, name)
+                    .execute(&mut *conn)
+                    .await?;
+                tag_id
+            }
+        };
+
+        let item_count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM item_tags WHERE tag_id = $1"#,
+            id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(Some(RenamedTag {
+            id,
+            name: name.to_string(),
+            item_count,
+            merged: existing.is_some(),
+        }))
+    }
+}
@@ -0,0 +1,222 @@
+use crate::entities::{AbuseEvent, AbuseEventKind, UserThrottle};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Host of an item URL, for grouping failures by site
+const URL_HOST_SQL: &str = r"lower(substring(url from '^[A-Za-z][A-Za-z0-9+.-]*://([^/:?#]+)'))";
+
+/// Repository for save throttles and their audit trail
+pub struct ThrottleRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> ThrottleRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find(&self, user_id: Uuid) -> Result<Option<UserThrottle>> {
+        let throttle = sqlx::query_as!(
+            UserThrottle,
+            r#"
+            SELECT user_id, throttled_until, reason, exempt, created_at, updated_at
+            FROM user_throttles
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(throttle)
+    }
+
+    /// Items the user saved one at a time since `since`; imports are
+    /// throttled as a whole rather than per row
+    pub async fn saves_since(&self, user_id: Uuid, since: DateTime<Utc>) -> Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM items
+            WHERE user_id = $1 AND created_at >= $2 AND import_operation_id IS NULL
+            "#,
+            user_id,
+            since
+        )
+        .fetch_one(self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// The site where most of the user's items permanently failed to fetch
+    /// since `since`, with how many did
+    pub async fn worst_failing_domain(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Option<(String, i64)>> {
+        let worst: Option<(Option<String>, i64)> = sqlx::query_as(&format!(
+            r#"
+            SELECT {URL_HOST_SQL} AS domain, COUNT(*) AS failures
+            FROM items
+            WHERE user_id = $1
+              AND processing_state = 'failed_permanent'
+              AND processing_state_changed_at >= $2
+            GROUP BY 1
+            ORDER BY 2 DESC
+            LIMIT 1
+            "#
+        ))
+        .bind(user_id)
+        .bind(since)
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(worst.and_then(|(domain, failures)| domain.map(|domain| (domain, failures))))
+    }
+
+    /// Pause the user's saves until `until`, recording why. Does nothing if
+    /// the user is exempt or already throttled, so concurrent requests tripping
+    /// the same limit produce one audit event. Returns whether it throttled.
+    pub async fn throttle(
+        &self,
+        user_id: Uuid,
+        until: DateTime<Utc>,
+        reason: &str,
+    ) -> Result<bool> {
+        let throttled: Option<Uuid> = sqlx::query_scalar!(
+            r#"
+            WITH throttled AS (
+                INSERT INTO user_throttles (user_id, throttled_until, reason)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (user_id) DO UPDATE
+                  SET throttled_until = EXCLUDED.throttled_until,
+                      reason = EXCLUDED.reason
+                  WHERE NOT user_throttles.exempt
+                    AND (user_throttles.throttled_until IS NULL
+                         OR user_throttles.throttled_until <= NOW())
+                RETURNING user_id
+            )
+            INSERT INTO abuse_events (user_id, kind, detail)
+            SELECT user_id, 'throttled', $3 FROM throttled
+            RETURNING user_id
+            "#,
+            user_id,
+            until,
+            reason
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(throttled.is_some())
+    }
+
+    /// Admin override: lift any current throttle and set whether the user is
+    /// exempt from automatic throttling, auditing what changed
+    pub async fn override_throttle(
+        &self,
+        user_id: Uuid,
+        exempt: bool,
+        actor_id: Uuid,
+    ) -> Result<UserThrottle> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous = sqlx::query!(
+            "SELECT throttled_until, exempt FROM user_throttles WHERE user_id = $1 FOR UPDATE",
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let throttle = sqlx::query_as!(
+            UserThrottle,
+            r#"
+            INSERT INTO user_throttles (user_id, exempt)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+              SET throttled_until = NULL,
+                  reason = NULL,
+                  exempt = EXCLUDED.exempt
+            RETURNING user_id, throttled_until, reason, exempt, created_at, updated_at
+            "#,
+            user_id,
+            exempt
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let (was_throttled, was_exempt) = match previous {
+            Some(previous) => (
+                previous
+                    .throttled_until
+                    .is_some_and(|until| until > Utc::now()),
+                previous.exempt,
+            ),
+            None => (false, false),
+        };
+        let mut events = Vec::new();
+        if was_throttled {
+            events.push(AbuseEventKind::Lifted);
+        }
+        if exempt != was_exempt {
+            events.push(if exempt {
+                AbuseEventKind::Exempted
+            } else {
+                AbuseEventKind::Unexempted
+            });
+        }
+        for kind in events {
+            sqlx::query!(
+                "INSERT INTO abuse_events (user_id, kind, actor_id) VALUES ($1, $2, $3)",
+                user_id,
+                kind as AbuseEventKind,
+                actor_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(throttle)
+    }
+
+    /// Users currently throttled or exempt, most recently changed first
+    pub async fn list(&self) -> Result<Vec<UserThrottle>> {
+        let throttles = sqlx::query_as!(
+            UserThrottle,
+            r#"
+            SELECT user_id, throttled_until, reason, exempt, created_at, updated_at
+            FROM user_throttles
+            WHERE exempt OR throttled_until > NOW()
+            ORDER BY updated_at DESC
+            "#
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(throttles)
+    }
+
+    /// The user's audit trail, newest first
+    pub async fn events(&self, user_id: Uuid, limit: i64) -> Result<Vec<AbuseEvent>> {
+        let events = sqlx::query_as!(
+            AbuseEvent,
+            r#"
+            SELECT id, user_id, kind AS "kind: AbuseEventKind", detail, actor_id, created_at
+            FROM abuse_events
+            WHERE user_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#,
+            user_id,
+            limit
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(events)
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::Result;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// Number of a user's items assigned one topic
+#[derive(Debug, Clone, FromRow)]
+pub struct TopicCount {
+    pub topic: String,
+    pub count: i64,
+}
+
+/// Repository for the topics assigned to items
+pub struct TopicRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> TopicRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Replace the item's topics
+    pub async fn set_topics(&self, item_id: Uuid, topics: &[String]) -> Result<()> {
+        sqlx::query!(
+            "UPDATE items SET topics = $2 WHERE id = $1",
+            item_id,
+            topics
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Item counts per topic across the user's library, most common first
+    pub async fn counts(&self, user_id: Uuid) -> Result<Vec<TopicCount>> {
+        let counts = sqlx::query_as!(
+            TopicCount,
+            r#"
+            SELECT t.topic AS "topic!", COUNT(*) AS "count!"
+            FROM items i
+            CROSS JOIN LATERAL unnest(i.topics) AS t(topic)
+            WHERE i.user_id = $1
+            GROUP BY t.topic
+            ORDER BY COUNT(*) DESC, t.topic
+            "#,
+            user_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+}
@@ -0,0 +1,100 @@
+use crate::entities::ContentTranslation;
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// An item's extracted text, ready to be translated
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TranslationSource {
+    pub text: Option<String>,
+    pub lang: Option<String>,
+}
+
+/// Repository for machine translations of items' extracted text
+pub struct TranslationRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> TranslationRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// The item's extracted text and language, from the shared document when
+    /// the item points at one. Returns None if the item doesn't exist.
+    pub async fn source(&self, item_id: Uuid) -> Result<Option<TranslationSource>> {
+        let source = sqlx::query_as!(
+            TranslationSource,
+            r#"
+            SELECT COALESCE(c.clean_text, d.clean_text) AS text,
+                   COALESCE(c.lang, d.lang) AS lang
+            FROM items i
+            LEFT JOIN contents c ON c.item_id = i.id
+            LEFT JOIN documents d ON d.id = i.document_id
+            WHERE i.id = $1
+            "#,
+            item_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(source)
+    }
+
+    /// Store the item's translation into `lang`, replacing an earlier one
+    pub async fn upsert(
+        &self,
+        item_id: Uuid,
+        lang: &str,
+        source_lang: Option<&str>,
+        text: &str,
+        provider: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO content_translations (item_id, lang, source_lang, text, provider)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (item_id, lang) DO UPDATE
+            SET source_lang = EXCLUDED.source_lang,
+                text = EXCLUDED.text,
+                provider = EXCLUDED.provider,
+                created_at = NOW()
+            "#,
+            item_id,
+            lang,
+            source_lang,
+            text,
+            provider
+        )
+        .execute(self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The translation of one of the user's items into `lang`, if it has
+    /// been made
+    pub async fn find_for_user(
+        &self,
+        user_id: Uuid,
+        item_id: Uuid,
+        lang: &str,
+    ) -> Result<Option<ContentTranslation>> {
+        let translation = sqlx::query_as!(
+            ContentTranslation,
+            r#"
+            SELECT t.item_id, t.lang, t.source_lang, t.text, t.provider, t.created_at
+            FROM content_translations t
+            JOIN items i ON i.id = t.item_id
+            WHERE t.item_id = $1 AND i.user_id = $2 AND t.lang = $3
+            "#,
+            item_id,
+            user_id,
+            lang
+        )
+        .fetch_optional(self.pool)
+        .await?;
+
+        Ok(translation)
+    }
+}
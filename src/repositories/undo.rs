@@ -0,0 +1,366 @@
+use crate::{
+    entities::ItemStatus,
+    repositories::backup::{BACKUP_TABLES, BackupRepository, BackupTable, backup_table},
+};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, PgConnection, PgPool, types::Json};
+use uuid::Uuid;
+
+/// How long a change can be undone for
+pub const UNDO_WINDOW_SECS: i64 = 30;
+
+/// Backed-up tables holding an item's own rows, which an undone delete
+/// puts back. Timelines, read history, share links and the like aren't
+/// restored.
+const ITEM_TABLES: &[&str] = &["items", "contents", "highlights", "item_tags"];
+
+/// Rows derived from an item's content that backups leave out but an undone
+/// delete puts back too, restored after [`ITEM_TABLES`]. Topics live on the
+/// item itself; embeddings are computed again instead, as pgvector is
+/// optional.
+const DERIVED_TABLES: &[BackupTable] = &[BackupTable {
+    name: "item_links",
+    key_columns: "item_id, url_hash",
+    tracks_changes: false,
+    detached: &[],
+}];
+
+/// The table an undo step restores rows of by `name`
+fn undo_table(name: &str) -> Option<&'static BackupTable> {
+    backup_table(name)
+        .filter(|table| ITEM_TABLES.contains(&table.name))
+        .or_else(|| DERIVED_TABLES.iter().find(|table| table.name == name))
+}
+
+/// One compensating update, reversing part of a change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UndoStep {
+    /// Put deleted items back from their rows, by table in restore order
+    Restore { tables: Vec<(String, Vec<Value>)> },
+    /// Move items back to the statuses they had
+    SetStatus { items: Vec<(Uuid, ItemStatus)> },
+    /// Star or unstar items as they were
+    SetFavorite { items: Vec<(Uuid, bool)> },
+    /// Take a tag back off the items it was newly attached to
+    Untag { tag: String, item_ids: Vec<Uuid> },
+}
+
+/// A recorded change that can still be undone
+#[derive(Debug, Clone, FromRow)]
+pub struct UndoAction {
+    /// The undo token handed to the client
+    pub id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Repository for undoing recent destructive changes to items
+pub struct UndoRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> UndoRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record how to reverse a change made outside a transaction, like
+    /// [`UndoRepository::record_in`]
+    pub async fn record(&self, user_id: Uuid, steps: Vec<UndoStep>) -> Result<UndoAction> {
+        let mut conn = self.pool.acquire().await?;
+        Self::record_in(&mut conn, user_id, steps).await
+    }
+
+    /// Record how to reverse a change, on `conn`, and clear away the user's
+    /// expired undo actions
+    pub async fn record_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        steps: Vec<UndoStep>,
+    ) -> Result<UndoAction> {
+        sqlx::query!(
+            "DELETE FROM undo_actions WHERE user_id = $1 AND expires_at <= NOW()",
+            user_id
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        let action = sqlx::query_as!(
+            UndoAction,
+            r#"
+            INSERT INTO undo_actions (user_id, steps, expires_at)
+            VALUES ($1, $2, $3)
+            RETURNING id, expires_at
+            "#,
+            user_id,
+            Json(steps) as _,
+            Utc::now() + Duration::seconds(UNDO_WINDOW_SECS)
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(action)
+    }
+
+    /// Take the user's undo action `id` off the books, on `conn`, returning
+    /// its steps. None when it isn't theirs, has expired or was already
+    /// used.
+    pub async fn take_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<Vec<UndoStep>>> {
+        let steps: Option<Json<Vec<UndoStep>>> = sqlx::query_scalar!(
+            r#"
+            DELETE FROM undo_actions
+            WHERE id = $1 AND user_id = $2 AND expires_at > NOW()
+            RETURNING steps AS "steps: Json<Vec<UndoStep>>"
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(steps.map(|steps| steps.0))
+    }
+
+    /// The rows of those of `item_ids` that are the user's, on `conn`, for
+    /// putting them back after they're deleted
+    pub async fn snapshot_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_ids: &[Uuid],
+    ) -> Result<UndoStep> {
+        let mut tables = Vec::new();
+        for table in BACKUP_TABLES
+            .iter()
+            .filter(|table| ITEM_TABLES.contains(&table.name))
+            .chain(DERIVED_TABLES)
+        {
+            let item_column = if table.name == "items" {
+                "id"
+            } else {
+                "item_id"
+            };
+            let sql = format!(
+                "SELECT to_jsonb(t) FROM {table} t WHERE t.{item_column} IN \
+                 (SELECT id FROM items WHERE id = ANY($1) AND user_id = $2)",
+                table = table.name,
+            );
+            let rows: Vec<Value> = sqlx::query_scalar(&sql)
+                .bind(item_ids)
+                .bind(user_id)
+                .fetch_all(&mut *conn)
+                .await?;
+            tables.push((table.name.to_string(), rows));
+        }
+
+        Ok(UndoStep::Restore { tables })
+    }
+
+    /// The current statuses of those of `item_ids` that are the user's, on
+    /// `conn`
+    pub async fn statuses_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_ids: &[Uuid],
+    ) -> Result<UndoStep> {
+        let items = sqlx::query!(
+            r#"SELECT id, status AS "status: ItemStatus" FROM items WHERE id = ANY($1) AND user_id = $2"#,
+            item_ids,
+            user_id
+        )
+        .fetch_all(conn)
+        .await?
+        .into_iter()
+        .map(|row| (row.id, row.status))
+        .collect();
+
+        Ok(UndoStep::SetStatus { items })
+    }
+
+    /// Whether each of those of `item_ids` that are the user's is starred,
+    /// on `conn`
+    pub async fn favorites_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_ids: &[Uuid],
+    ) -> Result<UndoStep> {
+        let items = sqlx::query!(
+            "SELECT id, favorite FROM items WHERE id = ANY($1) AND user_id = $2",
+            item_ids,
+            user_id
+        )
+        .fetch_all(conn)
+        .await?
+        .into_iter()
+        .map(|row| (row.id, row.favorite))
+        .collect();
+
+        Ok(UndoStep::SetFavorite { items })
+    }
+
+    /// Those of `item_ids` that are the user's and don't carry their tag
+    /// called `tag` yet, on `conn`
+    pub async fn untag_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        item_ids: &[Uuid],
+        tag: &str,
+    ) -> Result<UndoStep> {
+        let untagged = sqlx::query_scalar!(
+            r#"
+            SELECT i.id
+            FROM items i
+            WHERE i.id = ANY($1) AND i.user_id = $2
+              AND NOT EXISTS (
+                  SELECT 1
+                  FROM item_tags it
+                  JOIN tags t ON t.id = it.tag_id
+                  WHERE it.item_id = i.id AND t.user_id = $2 AND t.name = $3
+              )
+            "#,
+            item_ids,
+            user_id,
+            tag
+        )
+        .fetch_all(conn)
+        .await?;
+
+        Ok(UndoStep::Untag {
+            tag: tag.to_string(),
+            item_ids: untagged,
+        })
+    }
+
+    /// Apply one compensating update for the user, on `conn`. Returns the
+    /// IDs of the items it touched; items deleted since are skipped.
+    pub async fn apply_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        step: &UndoStep,
+    ) -> Result<Vec<Uuid>> {
+        match step {
+            UndoStep::Restore { tables } => {
+                let mut restored = Vec::new();
+                for (name, rows) in tables {
+                    let Some(table) = undo_table(name) else {
+                        continue;
+                    };
+                    let columns = BackupRepository::columns(conn, table).await?;
+                    for row in rows {
+                        BackupRepository::restore_row(conn, table, &columns, row).await?;
+                        if table.name == "items"
+                            && let Some(id) = row["id"].as_str().and_then(|id| id.parse().ok())
+                        {
+                            restored.push(id);
+                        }
+                    }
+                }
+                Ok(restored)
+            }
+            UndoStep::SetStatus { items } => {
+                let (ids, statuses): (Vec<Uuid>, Vec<ItemStatus>) = items.iter().cloned().unzip();
+                let updated = sqlx::query_scalar!(
+                    r#"
+                    UPDATE items i
+                    SET status = s.status, version = i.version + 1, updated_at = NOW()
+                    FROM unnest($1::uuid[], $2::item_status[]) AS s(id, status)
+                    WHERE i.id = s.id AND i.user_id = $3
+                    RETURNING i.id
+                    "#,
+                    &ids,
+                    &statuses as &[ItemStatus],
+                    user_id
+                )
+                .fetch_all(conn)
+                .await?;
+                Ok(updated)
+            }
+            UndoStep::SetFavorite { items } => {
+                let (ids, favorites): (Vec<Uuid>, Vec<bool>) = items.iter().cloned().unzip();
+                let updated = sqlx::query_scalar!(
+                    r#"
+                    UPDATE items i
+                    SET favorite = s.favorite, version = i.version + 1, updated_at = NOW()
+                    FROM unnest($1::uuid[], $2::bool[]) AS s(id, favorite)
+                    WHERE i.id = s.id AND i.user_id = $3
+                    RETURNING i.id
+                    "#,
+                    &ids,
+                    &favorites,
+                    user_id
+                )
+                .fetch_all(conn)
+                .await?;
+                Ok(updated)
+            }
+            UndoStep::Untag { tag, item_ids } => {
+                let untagged = sqlx::query_scalar!(
+                    r#"
+                    DELETE FROM item_tags it
+                    USING tags t, items i
+                    WHERE t.id = it.tag_id AND t.user_id = $2 AND t.name = $3
+                      AND i.id = it.item_id AND i.user_id = $2
+                      AND it.item_id = ANY($1)
+                    RETURNING it.item_id
+                    "#,
+                    item_ids,
+                    user_id,
+                    tag
+                )
+                .fetch_all(conn)
+                .await?;
+                Ok(untagged)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_steps_round_trip_through_json() {
+        let id = Uuid::new_v4();
+        let steps = vec![
+            UndoStep::Restore {
+                tables: vec![("items".to_string(), vec![serde_json::json!({ "id": id })])],
+            },
+            UndoStep::SetStatus {
+                items: vec![(id, ItemStatus::Fetched)],
+            },
+            UndoStep::SetFavorite {
+                items: vec![(id, false)],
+            },
+            UndoStep::Untag {
+                tag: "later".to_string(),
+                item_ids: vec![id],
+            },
+        ];
+        let json = serde_json::to_value(&steps).unwrap();
+        assert_eq!(json[1]["kind"], "set_status");
+        assert_eq!(
+            serde_json::from_value::<Vec<UndoStep>>(json).unwrap(),
+            steps
+        );
+    }
+
+    #[test]
+    fn test_item_tables_are_backed_up() {
+        for name in ITEM_TABLES {
+            assert!(
+                BACKUP_TABLES.iter().any(|table| table.name == *name),
+                "{}",
+                name
+            );
+        }
+        assert!(undo_table("item_links").is_some());
+        assert!(undo_table("users").is_none());
+    }
+}
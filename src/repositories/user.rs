@@ -1,6 +1,7 @@
-use crate::entities::User;
+use crate::entities::{User, UserPreferences, UserProfile};
 use anyhow::Result;
-use sqlx::{Pool, Postgres};
+use serde_json::Value;
+use sqlx::{Pool, Postgres, types::Json};
 use uuid::Uuid;
 
 #[cfg_attr(test, mockall::automock)]
@@ -11,6 +12,14 @@ pub trait UserRepositoryTrait {
     async fn find_by_email(&self, email: &str) -> Result<Option<User>>;
     async fn update_password(&self, id: Uuid, new_pw_hash: &str) -> Result<bool>;
     async fn delete(&self, id: Uuid) -> Result<bool>;
+    async fn find_profile(&self, id: Uuid) -> Result<Option<UserProfile>>;
+    /// Update profile fields; `prefs_patch` is shallow-merged into the stored preferences.
+    async fn update_profile(
+        &self,
+        id: Uuid,
+        display_name: Option<String>,
+        prefs_patch: Value,
+    ) -> Result<Option<UserProfile>>;
 }
 
 #[derive(Clone)]
@@ -104,4 +113,45 @@ impl UserRepositoryTrait for UserRepository {
 
         Ok(result.rows_affected() > 0)
     }
+
+    async fn find_profile(&self, id: Uuid) -> Result<Option<UserProfile>> {
+        let profile = sqlx::query_as!(
+            UserProfile,
+            r#"
+            SELECT id, email, display_name, prefs AS "prefs: Json<UserPreferences>", created_at
+            FROM users
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(profile)
+    }
+
+    async fn update_profile(
+        &self,
+        id: Uuid,
+        display_name: Option<String>,
+        prefs_patch: Value,
+    ) -> Result<Option<UserProfile>> {
+        let profile = sqlx::query_as!(
+            UserProfile,
+            r#"
+            UPDATE users
+            SET display_name = COALESCE($2, display_name),
+                prefs = prefs || $3
+            WHERE id = $1
+            RETURNING id, email, display_name, prefs AS "prefs: Json<UserPreferences>", created_at
+            "#,
+            id,
+            display_name,
+            prefs_patch
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(profile)
+    }
 }
@@ -0,0 +1,152 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    entities::{DigestSchedule, UserPreferences},
+    jobs::Outbox,
+    scheduling::tz::{TimeZone, next_weekday},
+};
+
+pub const SEND_DIGEST_JOB_KIND: &str = "send_digest";
+
+const DIGEST_HOUR: u32 = 8;
+
+/// Payload of a `send_digest` job. `scheduled_for` must match
+/// `users.next_digest_at`; anything else is a stale job left behind by a
+/// schedule change.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SendDigestPayload {
+    pub user_id: Uuid,
+    pub scheduled_for: DateTime<Utc>,
+}
+
+/// Next digest send time strictly after `now`, at 08:00 in the user's timezone.
+pub fn next_digest_at(
+    schedule: DigestSchedule,
+    now: DateTime<Utc>,
+    tz: &TimeZone,
+) -> Option<DateTime<Utc>> {
+    let local_now = tz.to_local(now);
+    let today = local_now.date();
+    let send_time = NaiveTime::from_hms_opt(DIGEST_HOUR, 0, 0).expect("valid hour");
+
+    let date = match schedule {
+        DigestSchedule::Off => return None,
+        DigestSchedule::Daily if local_now.time() < send_time => today,
+        DigestSchedule::Daily => today + Duration::days(1),
+        DigestSchedule::Weekly
+            if today.weekday() == Weekday::Mon && local_now.time() < send_time =>
+        {
+            today
+        }
+        DigestSchedule::Weekly => next_weekday(today, Weekday::Mon),
+    };
+
+    Some(tz.resolve_local(date.and_time(send_time)))
+}
+
+/// Recompute the user's next digest from their preferences, record it and
+/// enqueue the job that sends it. Returns the new send time, if any.
+pub async fn schedule_next_digest(
+    pool: &PgPool,
+    user_id: Uuid,
+    prefs: &UserPreferences,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>> {
+    let tz = TimeZone::named_or_utc(prefs.timezone.as_deref());
+    let next = prefs
+        .digest_schedule
+        .and_then(|schedule| next_digest_at(schedule, now, &tz));
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        "UPDATE users SET next_digest_at = $2 WHERE id = $1",
+        user_id,
+        next
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    if let Some(scheduled_for) = next {
+        let payload = SendDigestPayload {
+            user_id,
+            scheduled_for,
+        };
+        Outbox::enqueue(
+            &mut tx,
+            SEND_DIGEST_JOB_KIND,
+            serde_json::to_value(&payload)?,
+            Some(scheduled_for),
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone as _;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn new_york() -> TimeZone {
+        TimeZone::named("America/New_York").unwrap()
+    }
+
+    #[test]
+    fn test_next_digest_off() {
+        assert_eq!(
+            next_digest_at(DigestSchedule::Off, Utc::now(), &TimeZone::utc()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_next_daily_digest_same_day_before_send_time() {
+        // 06:00 EDT
+        assert_eq!(
+            next_digest_at(DigestSchedule::Daily, utc(2025, 9, 10, 10, 0), &new_york()),
+            Some(utc(2025, 9, 10, 12, 0))
+        );
+    }
+
+    #[test]
+    fn test_next_daily_digest_across_dst_transitions() {
+        let tz = new_york();
+        // Saturday 10:00 EST; Sunday 08:00 is already EDT
+        assert_eq!(
+            next_digest_at(DigestSchedule::Daily, utc(2025, 3, 8, 15, 0), &tz),
+            Some(utc(2025, 3, 9, 12, 0))
+        );
+        // Saturday 10:00 EDT; Sunday 08:00 is back on EST
+        assert_eq!(
+            next_digest_at(DigestSchedule::Daily, utc(2025, 11, 1, 14, 0), &tz),
+            Some(utc(2025, 11, 2, 13, 0))
+        );
+    }
+
+    #[test]
+    fn test_next_weekly_digest_across_dst_transition() {
+        let berlin = TimeZone::named("Europe/Berlin").unwrap();
+        // Thursday 23 October 2025 is CEST; the following Monday is CET
+        assert_eq!(
+            next_digest_at(DigestSchedule::Weekly, utc(2025, 10, 23, 12, 0), &berlin),
+            Some(utc(2025, 10, 27, 7, 0))
+        );
+        // Monday 07:30 local is still before the send time
+        assert_eq!(
+            next_digest_at(DigestSchedule::Weekly, utc(2025, 10, 27, 6, 30), &berlin),
+            Some(utc(2025, 10, 27, 7, 0))
+        );
+    }
+}
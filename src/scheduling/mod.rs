@@ -0,0 +1,7 @@
+pub mod digest;
+pub mod snooze;
+pub mod tz;
+
+pub use digest::*;
+pub use snooze::*;
+pub use tz::*;
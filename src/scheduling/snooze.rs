@@ -0,0 +1,144 @@
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::scheduling::tz::{TimeZone, next_weekday};
+
+const MORNING_HOUR: u32 = 8;
+const WEEKEND_HOUR: u32 = 9;
+const EVENING_HOUR: u32 = 18;
+const LATER_TODAY_HOURS: i64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SnoozePreset {
+    LaterToday,
+    ThisEvening,
+    Tomorrow,
+    ThisWeekend,
+    NextWeek,
+}
+
+fn at_hour(hour: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, 0, 0).expect("valid hour")
+}
+
+/// Resolve a snooze preset to an instant using the user's local wall clock.
+pub fn snooze_until(preset: SnoozePreset, now: DateTime<Utc>, tz: &TimeZone) -> DateTime<Utc> {
+    let local_now = tz.to_local(now);
+    let today = local_now.date();
+
+    let local_target = match preset {
+        SnoozePreset::LaterToday => return now + Duration::hours(LATER_TODAY_HOURS),
+        SnoozePreset::ThisEvening => {
+            let evening = today.and_time(at_hour(EVENING_HOUR));
+            if local_now < evening {
+                evening
+            } else {
+                (today + Duration::days(1)).and_time(at_hour(EVENING_HOUR))
+            }
+        }
+        SnoozePreset::Tomorrow => (today + Duration::days(1)).and_time(at_hour(MORNING_HOUR)),
+        SnoozePreset::ThisWeekend => {
+            let saturday_morning = today.and_time(at_hour(WEEKEND_HOUR));
+            if today.weekday() == Weekday::Sat && local_now < saturday_morning {
+                saturday_morning
+            } else {
+                next_weekday(today, Weekday::Sat).and_time(at_hour(WEEKEND_HOUR))
+            }
+        }
+        SnoozePreset::NextWeek => next_weekday(today, Weekday::Mon).and_time(at_hour(MORNING_HOUR)),
+    };
+
+    tz.resolve_local(local_target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone as _;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn new_york() -> TimeZone {
+        TimeZone::named("America/New_York").unwrap()
+    }
+
+    #[test]
+    fn test_snooze_this_evening_uses_local_time() {
+        // 10:00 EDT on a Wednesday
+        let now = utc(2025, 9, 10, 14, 0);
+        assert_eq!(
+            snooze_until(SnoozePreset::ThisEvening, now, &new_york()),
+            utc(2025, 9, 10, 22, 0)
+        );
+        // The same instant in UTC is already past 18:00 in Tokyo
+        let tokyo = TimeZone::named("Asia/Tokyo").unwrap();
+        assert_eq!(
+            snooze_until(SnoozePreset::ThisEvening, now, &tokyo),
+            utc(2025, 9, 11, 9, 0)
+        );
+    }
+
+    #[test]
+    fn test_snooze_tomorrow_across_spring_forward() {
+        // Saturday 20:00 EST, clocks spring forward overnight
+        let now = utc(2025, 3, 9, 1, 0);
+        assert_eq!(
+            snooze_until(SnoozePreset::Tomorrow, now, &new_york()),
+            utc(2025, 3, 9, 12, 0)
+        );
+    }
+
+    #[test]
+    fn test_snooze_this_weekend_across_fall_back() {
+        // Saturday 25 October 2025 12:00 CEST; clocks fall back before next weekend
+        let berlin = TimeZone::named("Europe/Berlin").unwrap();
+        let now = utc(2025, 10, 25, 10, 0);
+        assert_eq!(
+            snooze_until(SnoozePreset::ThisWeekend, now, &berlin),
+            utc(2025, 11, 1, 8, 0)
+        );
+    }
+
+    #[test]
+    fn test_snooze_next_week_across_fall_back() {
+        // Friday 31 October 2025 12:00 EDT; Monday is already on EST
+        let now = utc(2025, 10, 31, 16, 0);
+        assert_eq!(
+            snooze_until(SnoozePreset::NextWeek, now, &new_york()),
+            utc(2025, 11, 3, 13, 0)
+        );
+    }
+
+    #[test]
+    fn test_snooze_this_weekend_on_saturday() {
+        let tz = new_york();
+        // Saturday 07:00 EDT: later this morning
+        assert_eq!(
+            snooze_until(SnoozePreset::ThisWeekend, utc(2025, 9, 13, 11, 0), &tz),
+            utc(2025, 9, 13, 13, 0)
+        );
+        // Sunday: the following weekend
+        assert_eq!(
+            snooze_until(SnoozePreset::ThisWeekend, utc(2025, 9, 14, 15, 0), &tz),
+            utc(2025, 9, 20, 13, 0)
+        );
+    }
+
+    #[test]
+    fn test_snooze_next_week_and_later_today() {
+        let tz = new_york();
+        let monday = utc(2025, 11, 3, 15, 0);
+        assert_eq!(
+            snooze_until(SnoozePreset::NextWeek, monday, &tz),
+            utc(2025, 11, 10, 13, 0)
+        );
+        assert_eq!(
+            snooze_until(SnoozePreset::LaterToday, monday, &tz),
+            utc(2025, 11, 3, 18, 0)
+        );
+    }
+}
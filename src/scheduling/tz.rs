@@ -0,0 +1,195 @@
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, Offset, TimeZone as _,
+    Utc, Weekday,
+};
+use chrono_tz::Tz;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TimeZoneError {
+    #[error("unknown timezone: {0}")]
+    Unknown(String),
+}
+
+/// An IANA timezone, from the database compiled in by `chrono-tz`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeZone(Tz);
+
+impl TimeZone {
+    pub fn utc() -> Self {
+        Self(Tz::UTC)
+    }
+
+    /// Look up an IANA timezone by name, e.g. `Europe/Berlin`.
+    pub fn named(name: &str) -> Result<Self, TimeZoneError> {
+        name.parse::<Tz>()
+            .map(Self)
+            .map_err(|_| TimeZoneError::Unknown(name.to_string()))
+    }
+
+    /// Load a timezone by name, falling back to UTC when it is missing or unknown.
+    pub fn named_or_utc(name: Option<&str>) -> Self {
+        match name {
+            Some(name) => Self::named(name).unwrap_or_else(|e| {
+                tracing::warn!("Falling back to UTC: {}", e);
+                Self::utc()
+            }),
+            None => Self::utc(),
+        }
+    }
+
+    /// Offset east of UTC, in seconds, in effect at the given instant.
+    pub fn offset_at(&self, instant: DateTime<Utc>) -> i32 {
+        self.0
+            .offset_from_utc_datetime(&instant.naive_utc())
+            .fix()
+            .local_minus_utc()
+    }
+
+    /// Convert an instant to wall-clock time in this zone.
+    pub fn to_local(&self, instant: DateTime<Utc>) -> NaiveDateTime {
+        instant.with_timezone(&self.0).naive_local()
+    }
+
+    /// Convert wall-clock time in this zone to an instant.
+    ///
+    /// Ambiguous times (when clocks fall back) resolve to the earlier instant.
+    /// Times skipped when clocks spring forward are pushed forward by the
+    /// length of the gap, so 02:30 on a spring-forward night becomes 03:30.
+    pub fn resolve_local(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        match self.0.from_local_datetime(&local) {
+            LocalResult::Single(instant) => instant.with_timezone(&Utc),
+            LocalResult::Ambiguous(earlier, _) => earlier.with_timezone(&Utc),
+            LocalResult::None => {
+                // Read with the offset from before the gap, the time lands
+                // as far past the gap as it was into it
+                let before = self.offset_at((local - Duration::days(1)).and_utc());
+                (local - Duration::seconds(i64::from(before))).and_utc()
+            }
+        }
+    }
+}
+
+/// Next occurrence of `weekday` strictly after `date`.
+pub fn next_weekday(date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let days_ahead =
+        (weekday.num_days_from_monday() + 7 - date.weekday().num_days_from_monday()) % 7;
+    date + Duration::days(if days_ahead == 0 {
+        7
+    } else {
+        i64::from(days_ahead)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    fn new_york() -> TimeZone {
+        TimeZone::named("America/New_York").unwrap()
+    }
+
+    fn berlin() -> TimeZone {
+        TimeZone::named("Europe/Berlin").unwrap()
+    }
+
+    #[test]
+    fn test_offsets_across_dst() {
+        let tz = new_york();
+        // 2025-03-09 02:00 EST is the spring-forward instant (07:00 UTC)
+        assert_eq!(tz.offset_at(utc(2025, 3, 9, 6, 59)), -5 * 3600);
+        assert_eq!(tz.offset_at(utc(2025, 3, 9, 7, 0)), -4 * 3600);
+        // 2025-11-02 02:00 EDT is the fall-back instant (06:00 UTC)
+        assert_eq!(tz.offset_at(utc(2025, 11, 2, 5, 59)), -4 * 3600);
+        assert_eq!(tz.offset_at(utc(2025, 11, 2, 6, 0)), -5 * 3600);
+        // DST started in April in 2000
+        assert_eq!(tz.offset_at(utc(2000, 3, 20, 12, 0)), -5 * 3600);
+    }
+
+    #[test]
+    fn test_offsets_at_transition_time() {
+        let tz = berlin();
+        // Clocks go back at 03:00 CEST on the last Sunday of October (01:00 UTC)
+        assert_eq!(tz.offset_at(utc(2025, 10, 26, 0, 59)), 7200);
+        assert_eq!(tz.offset_at(utc(2025, 10, 26, 1, 0)), 3600);
+    }
+
+    #[test]
+    fn test_offsets_southern_hemisphere() {
+        let tz = TimeZone::named("Australia/Sydney").unwrap();
+        assert_eq!(tz.offset_at(utc(2025, 1, 15, 0, 0)), 11 * 3600);
+        assert_eq!(tz.offset_at(utc(2025, 6, 15, 0, 0)), 10 * 3600);
+        assert_eq!(tz.offset_at(utc(2025, 12, 15, 0, 0)), 11 * 3600);
+    }
+
+    #[test]
+    fn test_offsets_without_dst() {
+        let tz = TimeZone::named("Asia/Kolkata").unwrap();
+        assert_eq!(tz.offset_at(utc(2025, 1, 1, 0, 0)), 5 * 3600 + 1800);
+        assert_eq!(tz.offset_at(utc(2025, 7, 1, 0, 0)), 5 * 3600 + 1800);
+    }
+
+    #[test]
+    fn test_resolve_local_skips_spring_forward_gap() {
+        let tz = new_york();
+        // 02:30 does not exist on 2025-03-09; it resolves to 03:30 EDT
+        assert_eq!(
+            tz.resolve_local(local(2025, 3, 9, 2, 30)),
+            utc(2025, 3, 9, 7, 30)
+        );
+        assert_eq!(
+            tz.resolve_local(local(2025, 3, 9, 8, 0)),
+            utc(2025, 3, 9, 12, 0)
+        );
+    }
+
+    #[test]
+    fn test_resolve_local_ambiguous_uses_earlier_instant() {
+        let tz = new_york();
+        // 01:30 happens twice on 2025-11-02; the EDT reading comes first
+        assert_eq!(
+            tz.resolve_local(local(2025, 11, 2, 1, 30)),
+            utc(2025, 11, 2, 5, 30)
+        );
+    }
+
+    #[test]
+    fn test_to_local_round_trip() {
+        let tz = berlin();
+        let instant = utc(2025, 7, 1, 16, 0);
+        assert_eq!(tz.to_local(instant), local(2025, 7, 1, 18, 0));
+        assert_eq!(tz.resolve_local(tz.to_local(instant)), instant);
+    }
+
+    #[test]
+    fn test_named_rejects_unknown_names() {
+        assert!(TimeZone::named("Mars/Olympus_Mons").is_err());
+        assert!(TimeZone::named("../etc/passwd").is_err());
+        assert_eq!(TimeZone::named("UTC").unwrap(), TimeZone::utc());
+        assert_eq!(TimeZone::named_or_utc(Some("Nowhere")), TimeZone::utc());
+    }
+
+    #[test]
+    fn test_next_weekday() {
+        let wednesday = NaiveDate::from_ymd_opt(2025, 9, 10).unwrap();
+        assert_eq!(
+            next_weekday(wednesday, Weekday::Sat),
+            NaiveDate::from_ymd_opt(2025, 9, 13).unwrap()
+        );
+        assert_eq!(
+            next_weekday(wednesday, Weekday::Wed),
+            NaiveDate::from_ymd_opt(2025, 9, 17).unwrap()
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::schema::migrations::SchemaStatus;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SchemaResponse {
+    /// Newest migration applied to the database; null on an empty database
+    pub version: Option<i64>,
+    pub description: Option<String>,
+    pub installed_on: Option<DateTime<Utc>>,
+    /// Newest migration this build ships with
+    pub expected_version: Option<i64>,
+    /// True when the database has exactly this build's migrations applied
+    pub up_to_date: bool,
+    /// Migrations this build has that the database doesn't
+    pub pending: Vec<i64>,
+    /// Migrations the database has that this build doesn't
+    pub unknown: Vec<i64>,
+    /// Migrations edited after they were applied
+    pub modified: Vec<i64>,
+    /// Migrations that failed part way
+    pub failed: Vec<i64>,
+}
+
+impl From<SchemaStatus> for SchemaResponse {
+    fn from(status: SchemaStatus) -> Self {
+        let up_to_date = status.is_current();
+        let (version, description, installed_on) = match status.current {
+            Some(m) => (Some(m.version), Some(m.description), Some(m.installed_on)),
+            None => (None, None, None),
+        };
+        Self {
+            version,
+            description,
+            installed_on,
+            expected_version: status.expected_version,
+            up_to_date,
+            pending: status.pending,
+            unknown: status.unknown,
+            modified: status.modified,
+            failed: status.failed,
+        }
+    }
+}
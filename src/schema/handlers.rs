@@ -0,0 +1,39 @@
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AdminUser},
+    schema::{dtos::SchemaResponse, migrations},
+};
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/schema",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Applied migration version and any drift from this build", body = SchemaResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_schema(_admin: AdminUser, State(state): State<AppState>) -> Response {
+    match migrations::status(&state.db_pool).await {
+        Ok(status) => (StatusCode::OK, Json(SchemaResponse::from(status))).into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Database error".to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,196 @@
+//! Startup migration gating.
+//!
+//! Every binary embeds the same [`MIGRATOR`] and calls [`prepare`] with the
+//! configured [`MigrationMode`] before doing anything else, so whether a
+//! process migrates or merely checks the schema is a deployment choice
+//! rather than something each binary decides for itself.
+
+use anyhow::{Result, bail};
+use sqlx::{PgPool, migrate::Migrator};
+use tracing::{info, warn};
+
+use crate::{
+    config::MigrationMode,
+    repositories::{AppliedMigration, SchemaRepository},
+};
+
+/// The migrations this build was compiled with
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// How the database's migrations compare to the ones this build expects
+#[derive(Debug, Clone)]
+pub struct SchemaStatus {
+    /// The newest migration applied successfully
+    pub current: Option<AppliedMigration>,
+    /// The newest migration this build knows about
+    pub expected_version: Option<i64>,
+    /// Known migrations not yet applied
+    pub pending: Vec<i64>,
+    /// Applied migrations this build doesn't know, i.e. the database was
+    /// migrated by a newer build
+    pub unknown: Vec<i64>,
+    /// Applied migrations whose SQL has since been edited
+    pub modified: Vec<i64>,
+    /// Migrations that failed part way
+    pub failed: Vec<i64>,
+}
+
+impl SchemaStatus {
+    /// Compare the known `(version, checksum)` pairs with what was applied
+    pub fn compare(expected: &[(i64, &[u8])], applied: &[AppliedMigration]) -> Self {
+        let find = |version: i64| applied.iter().find(|m| m.version == version);
+
+        Self {
+            current: applied.iter().rev().find(|m| m.success).cloned(),
+            expected_version: expected.iter().map(|(version, _)| *version).max(),
+            pending: expected
+                .iter()
+                .filter(|(version, _)| find(*version).is_none())
+                .map(|(version, _)| *version)
+                .collect(),
+            unknown: applied
+                .iter()
+                .filter(|m| !expected.iter().any(|(version, _)| *version == m.version))
+                .map(|m| m.version)
+                .collect(),
+            modified: expected
+                .iter()
+                .filter(|(version, checksum)| {
+                    find(*version).is_some_and(|m| m.success && m.checksum != *checksum)
+                })
+                .map(|(version, _)| *version)
+                .collect(),
+            failed: applied
+                .iter()
+                .filter(|m| !m.success)
+                .map(|m| m.version)
+                .collect(),
+        }
+    }
+
+    /// Whether the database is exactly what this build expects
+    pub fn is_current(&self) -> bool {
+        self.pending.is_empty()
+            && self.unknown.is_empty()
+            && self.modified.is_empty()
+            && self.failed.is_empty()
+    }
+
+    /// Human-readable list of what's wrong, for startup errors
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (label, versions) in [
+            ("pending", &self.pending),
+            ("unknown to this build", &self.unknown),
+            ("modified since applied", &self.modified),
+            ("failed", &self.failed),
+        ] {
+            if !versions.is_empty() {
+                let versions: Vec<String> = versions.iter().map(i64::to_string).collect();
+                problems.push(format!("{}: {}", label, versions.join(", ")));
+            }
+        }
+        problems
+    }
+}
+
+/// Compare the database with [`MIGRATOR`]
+pub async fn status(pool: &PgPool) -> Result<SchemaStatus> {
+    let applied = SchemaRepository::new(pool).applied().await?;
+    let expected: Vec<(i64, &[u8])> = MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| (m.version, m.checksum.as_ref()))
+        .collect();
+    Ok(SchemaStatus::compare(&expected, &applied))
+}
+
+/// Run or verify migrations according to `mode`. Fails when verifying a
+/// database that doesn't match this build.
+pub async fn prepare(pool: &PgPool, mode: MigrationMode) -> Result<()> {
+    match mode {
+        MigrationMode::Run => {
+            MIGRATOR.run(pool).await?;
+            info!("Database migrations are up to date");
+        }
+        MigrationMode::Verify => {
+            let status = status(pool).await?;
+            if !status.is_current() {
+                bail!(
+                    "Database schema doesn't match this build ({})",
+                    status.problems().join("; ")
+                );
+            }
+            info!(
+                "Verified database schema at version {:?}",
+                status.expected_version
+            );
+        }
+        MigrationMode::Skip => {
+            warn!("Skipping database migration checks");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn applied(version: i64, success: bool, checksum: &[u8]) -> AppliedMigration {
+        AppliedMigration {
+            version,
+            description: format!("migration {}", version),
+            installed_on: Utc::now(),
+            success,
+            checksum: checksum.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_compare_up_to_date() {
+        let status = SchemaStatus::compare(
+            &[(1, b"a"), (2, b"b")],
+            &[applied(1, true, b"a"), applied(2, true, b"b")],
+        );
+        assert!(status.is_current());
+        assert_eq!(status.current.as_ref().map(|m| m.version), Some(2));
+        assert_eq!(status.expected_version, Some(2));
+        assert!(status.problems().is_empty());
+    }
+
+    #[test]
+    fn test_compare_drift() {
+        let status = SchemaStatus::compare(
+            &[(1, b"a"), (2, b"b"), (3, b"c")],
+            &[
+                applied(1, true, b"edited"),
+                applied(2, false, b"b"),
+                applied(9, true, b"z"),
+            ],
+        );
+        assert!(!status.is_current());
+        assert_eq!(status.current.as_ref().map(|m| m.version), Some(9));
+        assert_eq!(status.pending, vec![3]);
+        assert_eq!(status.unknown, vec![9]);
+        assert_eq!(status.modified, vec![1]);
+        assert_eq!(status.failed, vec![2]);
+        assert_eq!(
+            status.problems(),
+            vec![
+                "pending: 3",
+                "unknown to this build: 9",
+                "modified since applied: 1",
+                "failed: 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare_fresh_database() {
+        let status = SchemaStatus::compare(&[(1, b"a")], &[]);
+        assert!(status.current.is_none());
+        assert_eq!(status.pending, vec![1]);
+    }
+}
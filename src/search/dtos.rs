@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    entities::{SearchMode, SearchScope},
+    items::dtos::validate_lang,
+    pagination::parse_offset_cursor,
+    query::{FieldError, ValidateQuery},
+    repositories::SearchHit,
+    search::snippet,
+    topics::is_topic_name,
+};
+
+pub const DEFAULT_SEARCH_LIMIT: i64 = 20;
+pub const MAX_SEARCH_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    /// Search terms; supports quoted phrases, `or` and `-exclusions`
+    pub q: String,
+    /// Sources to search (default: all)
+    #[serde(default)]
+    pub scope: SearchScope,
+    /// `keyword` (default) or `semantic`, which needs embeddings enabled
+    #[serde(default)]
+    pub mode: SearchMode,
+    /// Only items whose content was detected as this language (e.g. `en`)
+    pub lang: Option<String>,
+    /// Only items assigned this topic (e.g. `tech`)
+    pub topic: Option<String>,
+    /// Maximum number of hits (default 20, max 100)
+    pub limit: Option<i64>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+}
+
+impl SearchQuery {
+    /// Hits to skip, from the cursor; only call once validated
+    pub fn offset(&self) -> i64 {
+        self.cursor
+            .as_deref()
+            .and_then(|cursor| parse_offset_cursor(cursor).ok())
+            .unwrap_or(0)
+    }
+}
+
+impl ValidateQuery for SearchQuery {
+    fn validate(&self) -> Result<(), FieldError> {
+        if self.q.trim().is_empty() {
+            return Err(FieldError::new("q", "q cannot be empty"));
+        }
+        if self.q.len() > 500 {
+            return Err(FieldError::new("q", "q too long"));
+        }
+        if let Some(limit) = self.limit
+            && !(1..=MAX_SEARCH_LIMIT).contains(&limit)
+        {
+            return Err(FieldError::new(
+                "limit",
+                format!("limit must be between 1 and {}", MAX_SEARCH_LIMIT),
+            ));
+        }
+        if let Some(lang) = &self.lang {
+            validate_lang(lang).map_err(|e| FieldError::new("lang", e))?;
+        }
+        if let Some(topic) = &self.topic
+            && !is_topic_name(topic)
+        {
+            return Err(FieldError::new("topic", "topic is not a valid topic name"));
+        }
+        if let Some(cursor) = &self.cursor {
+            parse_offset_cursor(cursor).map_err(|e| FieldError::new("cursor", e))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SearchHitResponse {
+    pub item_id: Uuid,
+    pub title: Option<String>,
+    pub url: String,
+    /// Which source matched: `content`, `notes` or `highlights`
+    pub source: String,
+    /// Matching fragment as HTML-escaped text, with terms wrapped in `<b>`
+    /// tags
+    pub snippet: String,
+    pub rank: f32,
+}
+
+impl From<SearchHit> for SearchHitResponse {
+    fn from(hit: SearchHit) -> Self {
+        Self {
+            item_id: hit.item_id,
+            title: hit.title,
+            url: hit.url,
+            source: hit.source,
+            snippet: snippet::render(&hit.snippet),
+            rank: hit.rank,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(q: &str, limit: Option<i64>) -> SearchQuery {
+        SearchQuery {
+            q: q.to_string(),
+            scope: SearchScope::All,
+            mode: SearchMode::Keyword,
+            lang: None,
+            topic: None,
+            limit,
+            cursor: None,
+        }
+    }
+
+    #[test]
+    fn test_search_query_valid() {
+        assert!(query("rust async", None).validate().is_ok());
+        assert!(query("rust", Some(MAX_SEARCH_LIMIT)).validate().is_ok());
+    }
+
+    #[test]
+    fn test_search_query_blank() {
+        assert!(query("   ", None).validate().is_err());
+    }
+
+    #[test]
+    fn test_search_query_limit_out_of_range() {
+        assert!(query("rust", Some(0)).validate().is_err());
+        assert!(
+            query("rust", Some(MAX_SEARCH_LIMIT + 1))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_search_query_invalid_lang() {
+        let mut invalid = query("rust", None);
+        invalid.lang = Some("english".to_string());
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_search_query_invalid_topic() {
+        let mut filtered = query("rust", None);
+        filtered.topic = Some("tech".to_string());
+        assert!(filtered.validate().is_ok());
+        filtered.topic = Some("Tech!".to_string());
+        assert_eq!(filtered.validate().unwrap_err().field, "topic");
+    }
+
+    #[test]
+    fn test_search_query_cursor() {
+        let mut paged = query("rust", None);
+        assert_eq!(paged.offset(), 0);
+        paged.cursor = Some("40".to_string());
+        assert!(paged.validate().is_ok());
+        assert_eq!(paged.offset(), 40);
+        paged.cursor = Some("page-2".to_string());
+        assert!(paged.validate().is_err());
+    }
+
+    #[test]
+    fn test_search_query_scope_from_query_string() {
+        let parsed: SearchQuery =
+            serde_json::from_value(serde_json::json!({"q": "rust", "scope": "highlights"}))
+                .unwrap();
+        assert_eq!(parsed.scope, SearchScope::Highlights);
+
+        let parsed: SearchQuery = serde_json::from_value(serde_json::json!({"q": "rust"})).unwrap();
+        assert_eq!(parsed.scope, SearchScope::All);
+        assert_eq!(parsed.mode, SearchMode::Keyword);
+    }
+
+    #[test]
+    fn test_search_query_mode_from_query_string() {
+        let parsed: SearchQuery =
+            serde_json::from_value(serde_json::json!({"q": "rust", "mode": "semantic"})).unwrap();
+        assert_eq!(parsed.mode, SearchMode::Semantic);
+
+        let invalid: Result<SearchQuery, _> =
+            serde_json::from_value(serde_json::json!({"q": "rust", "mode": "fuzzy"}));
+        assert!(invalid.is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    entities::SearchMode,
+    pagination::{Page, next_offset_cursor},
+    query::{FieldError, ValidatedQuery},
+    repositories::{EmbeddingRepository, SearchRepository},
+    search::{
+        dtos::{DEFAULT_SEARCH_LIMIT, SearchHitResponse, SearchQuery},
+        hybrid,
+    },
+};
+
+/// Hits taken from each of keyword and semantic search before blending;
+/// semantic results are paged within these
+const HYBRID_CANDIDATES: i64 = 200;
+
+#[utoipa::path(
+    get,
+    path = "/v1/search",
+    tag = "search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Search results ranked by relevance", body = Page<SearchHitResponse>),
+        (status = 400, description = "Invalid query parameter", body = FieldError),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 502, description = "The embedding provider failed", body = ErrorResponse),
+        (status = 503, description = "Semantic search is not enabled", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn search(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ValidatedQuery(query): ValidatedQuery<SearchQuery>,
+) -> Response {
+    let repo = SearchRepository::new(&state.db_pool);
+    let q = query.q.trim();
+    let lang = query.lang.as_ref().map(|lang| lang.to_ascii_lowercase());
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let offset = query.offset();
+
+    if query.mode == SearchMode::Semantic {
+        return semantic_search(&state, auth_user.user_id, &query, lang.as_deref()).await;
+    }
+
+    let hits = match repo
+        .search(
+            auth_user.user_id,
+            q,
+            query.scope,
+            lang.as_deref(),
+            query.topic.as_deref(),
+            limit,
+            offset,
+        )
+        .await
+    {
+        Ok(hits) => hits,
+        Err(_) => return database_error(),
+    };
+    let approximate_total = match repo
+        .estimate_total(
+            auth_user.user_id,
+            q,
+            query.scope,
+            lang.as_deref(),
+            query.topic.as_deref(),
+        )
+        .await
+    {
+        Ok(estimate) => estimate,
+        Err(e) => {
+            warn!("Failed to estimate search hit count: {}", e);
+            None
+        }
+    };
+
+    let next_cursor = next_offset_cursor(offset, hits.len(), limit);
+    let page = Page::new(
+        hits.into_iter().map(SearchHitResponse::from).collect(),
+        next_cursor,
+        approximate_total,
+    );
+
+    (StatusCode::OK, Json(page)).into_response()
+}
+
+/// Keyword and semantic hits blended into one ranking. Both lists are capped
+/// at [`HYBRID_CANDIDATES`], so there's no total to estimate.
+async fn semantic_search(
+    state: &AppState,
+    user_id: Uuid,
+    query: &SearchQuery,
+    lang: Option<&str>,
+) -> Response {
+    let embeddings = EmbeddingRepository::new(&state.db_pool);
+    let Some(embedder) = &state.embedder else {
+        return semantic_unavailable();
+    };
+    match embeddings.available().await {
+        Ok(true) => {}
+        Ok(false) => return semantic_unavailable(),
+        Err(_) => return database_error(),
+    }
+
+    let q = query.q.trim();
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    let offset = query.offset();
+
+    let embedding = match embedder.embed(&[q.to_string()]).await {
+        Ok(mut vectors) if vectors.len() == 1 => vectors.remove(0),
+        Ok(vectors) => {
+            error!(
+                "Embedding provider returned {} vectors for one query",
+                vectors.len()
+            );
+            return embedding_error();
+        }
+        Err(e) => {
+            error!("Failed to embed search query: {}", e);
+            return embedding_error();
+        }
+    };
+
+    let keyword = match SearchRepository::new(&state.db_pool)
+        .search(
+            user_id,
+            q,
+            query.scope,
+            lang,
+            query.topic.as_deref(),
+            HYBRID_CANDIDATES,
+            0,
+        )
+        .await
+    {
+        Ok(hits) => hits,
+        Err(_) => return database_error(),
+    };
+    let semantic = match embeddings
+        .nearest(
+            user_id,
+            embedder.name(),
+            &embedding,
+            lang,
+            query.topic.as_deref(),
+            HYBRID_CANDIDATES,
+        )
+        .await
+    {
+        Ok(hits) => hits,
+        Err(_) => return database_error(),
+    };
+
+    let blended = hybrid::blend(keyword, semantic);
+    let remaining = blended.len().saturating_sub(offset as usize);
+    let hits: Vec<SearchHitResponse> = blended
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .map(SearchHitResponse::from)
+        .collect();
+    let next_cursor = if remaining > hits.len() {
+        next_offset_cursor(offset, hits.len(), limit)
+    } else {
+        None
+    };
+
+    (StatusCode::OK, Json(Page::new(hits, next_cursor, None))).into_response()
+}
+
+fn semantic_unavailable() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ErrorResponse {
+            error: "Semantic search is not enabled".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+fn embedding_error() -> Response {
+    (
+        StatusCode::BAD_GATEWAY,
+        Json(ErrorResponse {
+            error: "Failed to embed the query".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::repositories::SearchHit;
+
+/// Damps the weight of the top few positions in reciprocal rank fusion; 60
+/// is the value from the original paper and works well without tuning
+const RRF_K: f32 = 60.0;
+
+/// Blend keyword and semantic hits, each ordered best first, into one list
+/// of items using reciprocal rank fusion: an item scores `1 / (k + position)`
+/// for its best position in each list. Items found both ways rise to the
+/// top. An item keeps its keyword hit, whose snippet shows the matched
+/// terms, and its rank becomes the fused score.
+pub fn blend(keyword: Vec<SearchHit>, semantic: Vec<SearchHit>) -> Vec<SearchHit> {
+    let mut fused: HashMap<Uuid, (SearchHit, f32)> = HashMap::new();
+    for hits in [keyword, semantic] {
+        let mut seen = Vec::new();
+        for hit in hits {
+            // Keyword search can match an item in several sources
+            if seen.contains(&hit.item_id) {
+                continue;
+            }
+            seen.push(hit.item_id);
+            let score = 1.0 / (RRF_K + seen.len() as f32);
+            fused
+                .entry(hit.item_id)
+                .and_modify(|(_, total)| *total += score)
+                .or_insert((hit, score));
+        }
+    }
+
+    let mut blended: Vec<SearchHit> = fused
+        .into_values()
+        .map(|(mut hit, score)| {
+            hit.rank = score;
+            hit
+        })
+        .collect();
+    blended.sort_by(|a, b| b.rank.total_cmp(&a.rank).then(a.item_id.cmp(&b.item_id)));
+    blended
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(item_id: Uuid, source: &str) -> SearchHit {
+        SearchHit {
+            item_id,
+            title: None,
+            url: format!("https://example.com/{}", item_id),
+            source: source.to_string(),
+            snippet: source.to_string(),
+            rank: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_blend_favours_items_found_both_ways() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let blended = blend(
+            vec![hit(a, "content"), hit(b, "content")],
+            vec![hit(c, "semantic"), hit(b, "semantic")],
+        );
+
+        let order: Vec<Uuid> = blended.iter().map(|hit| hit.item_id).collect();
+        assert_eq!(order[0], b);
+        assert_eq!(order.len(), 3);
+        assert!(blended[0].rank > blended[1].rank);
+    }
+
+    #[test]
+    fn test_blend_keeps_keyword_snippet_and_dedupes_sources() {
+        let a = Uuid::new_v4();
+        let blended = blend(
+            vec![hit(a, "notes"), hit(a, "content")],
+            vec![hit(a, "semantic")],
+        );
+
+        assert_eq!(blended.len(), 1);
+        assert_eq!(blended[0].snippet, "notes");
+        assert_eq!(blended[0].rank, 2.0 / (RRF_K + 1.0));
+    }
+}
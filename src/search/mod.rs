@@ -0,0 +1,4 @@
+pub mod dtos;
+pub mod handlers;
+pub mod hybrid;
+pub mod snippet;
@@ -0,0 +1,62 @@
+//! Search snippets as safe markup.
+//!
+//! Snippets are cut from fetched pages and from users' notes and quotes,
+//! so their text can hold anything, tags included. `ts_headline` doesn't
+//! escape the text around a match, so the search query has it mark matches
+//! with private-use characters instead of `<b>`, and [`render`] escapes the
+//! text before turning the markers into tags.
+
+/// Marks the start of a match in a raw snippet
+pub const HIGHLIGHT_START: char = '\u{E000}';
+
+/// Marks the end of a match in a raw snippet
+pub const HIGHLIGHT_STOP: char = '\u{E001}';
+
+/// The snippet HTML-escaped, with its matches wrapped in `<b>` tags
+pub fn render(raw: &str) -> String {
+    let mut html = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            HIGHLIGHT_START => html.push_str("<b>"),
+            HIGHLIGHT_STOP => html.push_str("</b>"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_escapes_text_and_keeps_highlights() {
+        let raw = format!(
+            "<img src=x onerror=\"alert('hi')\"> &{}zettelkasten{} notes",
+            HIGHLIGHT_START, HIGHLIGHT_STOP
+        );
+        assert_eq!(
+            render(&raw),
+            "&lt;img src=x onerror=&quot;alert(&#39;hi&#39;)&quot;&gt; \
+             &amp;<b>zettelkasten</b> notes"
+        );
+    }
+
+    #[test]
+    fn test_markers_match_the_search_query() {
+        // SEARCH_HITS_SQL passes them to ts_headline as chr(57344), chr(57345)
+        assert_eq!(HIGHLIGHT_START as u32, 57344);
+        assert_eq!(HIGHLIGHT_STOP as u32, 57345);
+    }
+
+    #[test]
+    fn test_render_plain_text() {
+        assert_eq!(render("no matches here"), "no matches here");
+        assert_eq!(render("<b>bold</b>"), "&lt;b&gt;bold&lt;/b&gt;");
+    }
+}
@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::entities::Share;
+
+/// Longest lifetime a share link may be given; without one it lasts until
+/// revoked
+pub const MAX_SHARE_TTL_SECS: i64 = 365 * 24 * 60 * 60;
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CreateShareRequest {
+    /// Seconds until the link stops working; it works until revoked when
+    /// absent
+    pub ttl_secs: Option<i64>,
+}
+
+impl CreateShareRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(ttl) = self.ttl_secs
+            && !(1..=MAX_SHARE_TTL_SECS).contains(&ttl)
+        {
+            return Err(format!(
+                "ttl_secs must be between 1 and {}",
+                MAX_SHARE_TTL_SECS
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShareResponse {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    /// Secret part of the link; anyone holding it can read the article
+    pub token: String,
+    /// Public path of the shared article, relative to the API's origin
+    pub url: String,
+    /// Null when the link works until revoked
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ShareResponse {
+    pub fn new(share: Share, token: String) -> Self {
+        Self {
+            id: share.id,
+            item_id: share.item_id,
+            url: format!("/shared/{}", token),
+            token,
+            expires_at: share.expires_at,
+            created_at: share.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevokeSharesResponse {
+    /// How many live links to the item were revoked
+    pub revoked: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_share_request_ttl() {
+        let request = |ttl_secs| CreateShareRequest { ttl_secs };
+        assert!(request(None).validate().is_ok());
+        assert!(request(Some(3600)).validate().is_ok());
+        assert!(request(Some(0)).validate().is_err());
+        assert!(request(Some(MAX_SHARE_TTL_SECS + 1)).validate().is_err());
+    }
+}
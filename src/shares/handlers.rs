@@ -0,0 +1,181 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{
+        StatusCode,
+        header::{
+            CACHE_CONTROL, CONTENT_SECURITY_POLICY, CONTENT_TYPE, REFERRER_POLICY,
+            X_CONTENT_TYPE_OPTIONS,
+        },
+    },
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    config::Config,
+    repositories::ShareRepository,
+    shares::{
+        ShareSigner,
+        dtos::{CreateShareRequest, RevokeSharesResponse, ShareResponse},
+        render_page,
+    },
+};
+
+/// Shared articles may show the images they link to, and nothing else runs
+const SHARED_PAGE_CSP: &str =
+    "default-src 'none'; img-src http: https: data:; style-src 'unsafe-inline'";
+
+#[utoipa::path(
+    post,
+    path = "/v1/items/{id}/share",
+    tag = "shares",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    request_body = CreateShareRequest,
+    responses(
+        (status = 201, description = "Share link created", body = ShareResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_share(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<CreateShareRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    let expires_at = payload
+        .ttl_secs
+        .map(|ttl| Utc::now() + Duration::seconds(ttl));
+    let repo = ShareRepository::new(&state.db_pool);
+    let share = match repo.create(auth_user.user_id, item_id, expires_at).await {
+        Ok(Some(share)) => share,
+        Ok(None) => return item_not_found(),
+        Err(_) => return database_error(),
+    };
+
+    let config = Config::from_env().expect("Failed to load config");
+    let token = ShareSigner::new(config.jwt_secret()).token(share.id);
+    (StatusCode::CREATED, Json(ShareResponse::new(share, token))).into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/items/{id}/share",
+    tag = "shares",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    responses(
+        (status = 200, description = "Every link to the item revoked", body = RevokeSharesResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn revoke_shares(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(item_id): Path<Uuid>,
+) -> Response {
+    let repo = ShareRepository::new(&state.db_pool);
+    match repo.revoke_for_item(auth_user.user_id, item_id).await {
+        Ok(Some(revoked)) => {
+            (StatusCode::OK, Json(RevokeSharesResponse { revoked })).into_response()
+        }
+        Ok(None) => item_not_found(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/shared/{token}",
+    tag = "shares",
+    params(
+        ("token" = String, Path, description = "Token from the share link")
+    ),
+    responses(
+        (status = 200, description = "The shared article as a standalone page", content_type = "text/html"),
+        (status = 404, description = "No such link, or it was revoked or expired", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn shared_page(State(state): State<AppState>, Path(token): Path<String>) -> Response {
+    let config = Config::from_env().expect("Failed to load config");
+    let Some(share_id) = ShareSigner::new(config.jwt_secret()).verify(&token) else {
+        return share_not_found();
+    };
+
+    let repo = ShareRepository::new(&state.db_pool);
+    let page = match repo.find_shared(share_id).await {
+        Ok(Some(item)) => render_page(&item),
+        Ok(None) => return share_not_found(),
+        Err(_) => return database_error(),
+    };
+    // Saved, but not extracted yet
+    let Some(page) = page else {
+        return share_not_found();
+    };
+
+    (
+        StatusCode::OK,
+        [
+            (CONTENT_TYPE, "text/html; charset=utf-8"),
+            // Revoking a link must take effect at once
+            (CACHE_CONTROL, "no-store"),
+            (X_CONTENT_TYPE_OPTIONS, "nosniff"),
+            (REFERRER_POLICY, "no-referrer"),
+            (CONTENT_SECURITY_POLICY, SHARED_PAGE_CSP),
+        ],
+        page,
+    )
+        .into_response()
+}
+
+fn item_not_found() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: "Item not found".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+fn share_not_found() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: "Shared item not found".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
@@ -0,0 +1,72 @@
+pub mod dtos;
+pub mod handlers;
+pub mod token;
+
+pub use token::ShareSigner;
+
+use crate::repositories::SharedItem;
+
+/// The standalone page a share link serves: the item's title, a link back
+/// to where it was saved from, and its cleaned article. None until the
+/// item's page has been extracted.
+pub fn render_page(item: &SharedItem) -> Option<String> {
+    let article = item.clean_html.as_deref()?;
+    let title = escape(item.title.as_deref().unwrap_or(&item.url));
+    let source = escape(item.site.as_deref().unwrap_or(&item.url));
+    Some(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="robots" content="noindex">
+<title>{title}</title>
+</head>
+<body>
+<article>
+<h1>{title}</h1>
+<p><a href="{url}" rel="noopener noreferrer">{source}</a></p>
+{article}
+</article>
+</body>
+</html>
+"#,
+        url = escape(&item.url),
+    ))
+}
+
+/// Escape text for an HTML element or double-quoted attribute
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(clean_html: Option<&str>) -> SharedItem {
+        SharedItem {
+            url: "https://example.com/post?a=1&b=2".to_string(),
+            title: Some("Tags like <b> & \"quotes\"".to_string()),
+            site: None,
+            clean_html: clean_html.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_render_page_escapes_metadata() {
+        let page = render_page(&item(Some("<p>Hello</p>"))).unwrap();
+        assert!(page.contains("<title>Tags like &lt;b&gt; &amp; &quot;quotes&quot;</title>"));
+        assert!(page.contains(r#"<a href="https://example.com/post?a=1&amp;b=2""#));
+        assert!(page.contains("<p>Hello</p>"));
+    }
+
+    #[test]
+    fn test_render_page_needs_content() {
+        assert_eq!(render_page(&item(None)), None);
+    }
+}
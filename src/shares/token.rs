@@ -0,0 +1,83 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Keeps share-token MACs distinct from anything else made with the same
+/// secret
+const DOMAIN: &str = "capsule-share";
+
+/// Signs and verifies share tokens: a share's ID and a MAC over it, so
+/// tokens can't be guessed or made up. Whether the share is still live is
+/// up to its row.
+pub struct ShareSigner {
+    secret: Vec<u8>,
+}
+
+impl ShareSigner {
+    pub fn new(secret: &str) -> Self {
+        Self {
+            secret: secret.as_bytes().to_vec(),
+        }
+    }
+
+    /// The token for `share_id`, safe to use as a URL path segment
+    pub fn token(&self, share_id: Uuid) -> String {
+        let signature = hex::encode(self.mac(share_id).finalize().into_bytes());
+        format!("{}.{}", share_id.simple(), signature)
+    }
+
+    /// The share a token was made for, or None when it wasn't made with
+    /// this secret
+    pub fn verify(&self, token: &str) -> Option<Uuid> {
+        let (id, signature) = token.split_once('.')?;
+        let share_id = Uuid::try_parse(id).ok()?;
+        let signature = hex::decode(signature).ok()?;
+
+        // Constant-time comparison
+        self.mac(share_id).verify_slice(&signature).ok()?;
+        Some(share_id)
+    }
+
+    fn mac(&self, share_id: Uuid) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(format!("{DOMAIN}\n{share_id}").as_bytes());
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_round_trip() {
+        let signer = ShareSigner::new("test-secret");
+        let share_id = Uuid::new_v4();
+        let token = signer.token(share_id);
+
+        assert!(!token.contains(['/', '?', '#', '-']));
+        assert_eq!(signer.verify(&token), Some(share_id));
+    }
+
+    #[test]
+    fn test_verify_rejects_forged_tokens() {
+        let signer = ShareSigner::new("test-secret");
+        let share_id = Uuid::new_v4();
+        let token = signer.token(share_id);
+
+        let other = ShareSigner::new("other-secret");
+        assert_eq!(other.verify(&token), None);
+
+        // Another share's ID with this share's signature
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!("{}.{}", Uuid::new_v4().simple(), signature);
+        assert_eq!(signer.verify(&forged), None);
+
+        assert_eq!(signer.verify(""), None);
+        assert_eq!(signer.verify(&share_id.simple().to_string()), None);
+        assert_eq!(signer.verify(&format!("{}.zz", share_id.simple())), None);
+    }
+}
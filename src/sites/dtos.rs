@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    pagination::parse_offset_cursor,
+    query::{FieldError, ValidateQuery},
+    repositories::UserSite,
+};
+
+pub const DEFAULT_SITES_LIMIT: i64 = 50;
+pub const MAX_SITES_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListSitesQuery {
+    /// Maximum number of sites (default 50, max 200)
+    pub limit: Option<i64>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+}
+
+impl ListSitesQuery {
+    /// Sites to skip, from the cursor; only call once validated
+    pub fn offset(&self) -> i64 {
+        self.cursor
+            .as_deref()
+            .and_then(|cursor| parse_offset_cursor(cursor).ok())
+            .unwrap_or(0)
+    }
+}
+
+impl ValidateQuery for ListSitesQuery {
+    fn validate(&self) -> Result<(), FieldError> {
+        if let Some(limit) = self.limit
+            && !(1..=MAX_SITES_LIMIT).contains(&limit)
+        {
+            return Err(FieldError::new(
+                "limit",
+                format!("limit must be between 1 and {}", MAX_SITES_LIMIT),
+            ));
+        }
+        if let Some(cursor) = &self.cursor {
+            parse_offset_cursor(cursor).map_err(|e| FieldError::new("cursor", e))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SiteResponse {
+    /// Host the items were saved from, without a leading `www.`; pass it as
+    /// `site` to `GET /v1/items` to list them
+    pub host: String,
+    /// What the site calls itself, or null until one of its pages says
+    pub name: Option<String>,
+    /// RSS or Atom feed the site's pages link to
+    pub feed_url: Option<String>,
+    /// Path of the site's icon, or null until one has been fetched
+    pub icon_url: Option<String>,
+    /// How many of the user's items come from the site
+    pub item_count: i64,
+    /// When the user last saved an item from the site
+    pub last_saved_at: DateTime<Utc>,
+}
+
+impl From<UserSite> for SiteResponse {
+    fn from(site: UserSite) -> Self {
+        Self {
+            icon_url: site
+                .has_icon
+                .then(|| format!("/v1/sites/{}/icon", site.host)),
+            host: site.host,
+            name: site.name,
+            feed_url: site.feed_url,
+            item_count: site.item_count,
+            last_saved_at: site.last_saved_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_site_response_icon_url() {
+        let site = UserSite {
+            host: "example.com".to_string(),
+            name: Some("Example".to_string()),
+            feed_url: None,
+            has_icon: true,
+            item_count: 3,
+            last_saved_at: Utc::now(),
+        };
+        assert_eq!(
+            SiteResponse::from(site.clone()).icon_url.as_deref(),
+            Some("/v1/sites/example.com/icon")
+        );
+
+        let site = UserSite {
+            has_icon: false,
+            ..site
+        };
+        assert_eq!(SiteResponse::from(site).icon_url, None);
+    }
+
+    #[test]
+    fn test_list_sites_query_validation() {
+        let query = |limit, cursor: Option<&str>| ListSitesQuery {
+            limit,
+            cursor: cursor.map(str::to_string),
+        };
+        assert!(query(None, None).validate().is_ok());
+        assert_eq!(query(Some(10), Some("20")).offset(), 20);
+        assert_eq!(query(Some(0), None).validate().unwrap_err().field, "limit");
+        assert_eq!(
+            query(None, Some("-1")).validate().unwrap_err().field,
+            "cursor"
+        );
+    }
+}
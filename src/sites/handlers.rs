@@ -0,0 +1,166 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{
+        StatusCode,
+        header::{CACHE_CONTROL, CONTENT_SECURITY_POLICY, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS},
+    },
+    response::{IntoResponse, Response},
+};
+
+use tracing::warn;
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    pagination::{Page, next_offset_cursor},
+    query::{FieldError, ValidatedQuery},
+    repositories::SiteRepository,
+    sites::dtos::{DEFAULT_SITES_LIMIT, ListSitesQuery, SiteResponse},
+};
+
+#[utoipa::path(
+    get,
+    path = "/v1/sites",
+    tag = "sites",
+    params(ListSitesQuery),
+    responses(
+        (status = 200, description = "Sites the user has saved items from, those with the most items first", body = Page<SiteResponse>),
+        (status = 400, description = "Invalid query parameter", body = FieldError),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_sites(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ValidatedQuery(query): ValidatedQuery<ListSitesQuery>,
+) -> Response {
+    let repo = SiteRepository::new(&state.db_pool);
+    let limit = query.limit.unwrap_or(DEFAULT_SITES_LIMIT);
+    let offset = query.offset();
+
+    let sites = match repo
+        .list_for_user(auth_user.user_id, None, limit, offset)
+        .await
+    {
+        Ok(sites) => sites,
+        Err(_) => return database_error(),
+    };
+    let total = match repo.count_for_user(auth_user.user_id).await {
+        Ok(total) => Some(total),
+        Err(e) => {
+            warn!("Failed to count sites: {}", e);
+            None
+        }
+    };
+
+    let next_cursor = next_offset_cursor(offset, sites.len(), limit);
+    let page = Page::new(
+        sites.into_iter().map(SiteResponse::from).collect(),
+        next_cursor,
+        total,
+    );
+
+    (StatusCode::OK, Json(page)).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/sites/{host}",
+    tag = "sites",
+    params(
+        ("host" = String, Path, description = "Host of the site, e.g. example.com")
+    ),
+    responses(
+        (status = 200, description = "The site, with how many of the user's items come from it", body = SiteResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No items saved from the site", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_site(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(host): Path<String>,
+) -> Response {
+    let repo = SiteRepository::new(&state.db_pool);
+    match repo.find_for_user(auth_user.user_id, &host).await {
+        Ok(Some(site)) => (StatusCode::OK, Json(SiteResponse::from(site))).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Site not found".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/sites/{host}/icon",
+    tag = "sites",
+    params(
+        ("host" = String, Path, description = "Host of the site, e.g. example.com")
+    ),
+    responses(
+        (status = 200, description = "The site's icon, as served by the site", content_type = "image/*"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Icon not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn site_icon(
+    _auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(host): Path<String>,
+) -> Response {
+    let repo = SiteRepository::new(&state.db_pool);
+    let icon = match repo.find_icon(&host).await {
+        Ok(Some(icon)) => icon,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "Icon not found".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Err(_) => return database_error(),
+    };
+
+    (
+        StatusCode::OK,
+        [
+            (CONTENT_TYPE, icon.content_type),
+            (CACHE_CONTROL, "private, max-age=86400".to_string()),
+            (X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+            // Icons are whatever the site served; an SVG mustn't run scripts
+            (CONTENT_SECURITY_POLICY, "default-src 'none'".to_string()),
+        ],
+        icon.data,
+    )
+        .into_response()
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    query::{FieldError, ValidateQuery},
+    repositories::{ItemStats, LanguageCount, SiteStats, TagCount},
+};
+
+pub const DEFAULT_SITE_STATS_LIMIT: i64 = 50;
+pub const MAX_SITE_STATS_LIMIT: i64 = 200;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LanguageStat {
+    /// Detected language code, or null when unknown
+    pub lang: Option<String>,
+    pub count: i64,
+}
+
+impl From<LanguageCount> for LanguageStat {
+    fn from(count: LanguageCount) -> Self {
+        Self {
+            lang: count.lang,
+            count: count.count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LanguageStatsResponse {
+    pub languages: Vec<LanguageStat>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SiteStatsQuery {
+    /// Maximum number of sites (default 50, max 200)
+    pub limit: Option<i64>,
+}
+
+impl ValidateQuery for SiteStatsQuery {
+    fn validate(&self) -> Result<(), FieldError> {
+        if let Some(limit) = self.limit
+            && !(1..=MAX_SITE_STATS_LIMIT).contains(&limit)
+        {
+            return Err(FieldError::new(
+                "limit",
+                format!("limit must be between 1 and {}", MAX_SITE_STATS_LIMIT),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SiteStat {
+    /// Host the items were saved from, without a leading `www.`
+    pub domain: String,
+    pub saved: i64,
+    /// Items read at least 90% of the way through
+    pub read: i64,
+    /// `read` as a fraction of `saved`
+    pub read_ratio: f64,
+    /// Mean reading time of the site's items, or null until any have been
+    /// extracted
+    pub avg_reading_time_minutes: Option<f64>,
+}
+
+impl From<SiteStats> for SiteStat {
+    fn from(stats: SiteStats) -> Self {
+        Self {
+            read_ratio: if stats.saved > 0 {
+                stats.read as f64 / stats.saved as f64
+            } else {
+                0.0
+            },
+            domain: stats.domain,
+            saved: stats.saved,
+            read: stats.read,
+            avg_reading_time_minutes: stats
+                .avg_reading_time_minutes
+                .map(|minutes| (minutes * 10.0).round() / 10.0),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SiteStatsResponse {
+    /// Sites with the most saved items first
+    pub sites: Vec<SiteStat>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StatusCounts {
+    pub pending: i64,
+    pub fetched: i64,
+    pub reading: i64,
+    pub failed: i64,
+    pub archived: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TagStat {
+    pub name: String,
+    pub count: i64,
+}
+
+impl From<TagCount> for TagStat {
+    fn from(tag: TagCount) -> Self {
+        Self {
+            name: tag.name,
+            count: tag.count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ItemStatsResponse {
+    pub total: i64,
+    pub by_status: StatusCounts,
+    /// Every tag, most used first, including unused ones
+    pub tags: Vec<TagStat>,
+    /// Items not archived and not yet read 90% of the way through
+    pub unread: i64,
+    /// Bytes of stored page content, as counted against the storage quota
+    pub storage_bytes: i64,
+}
+
+impl From<ItemStats> for ItemStatsResponse {
+    fn from(stats: ItemStats) -> Self {
+        Self {
+            total: stats.total,
+            by_status: StatusCounts {
+                pending: stats.pending,
+                fetched: stats.fetched,
+                reading: stats.reading,
+                failed: stats.failed,
+                archived: stats.archived,
+            },
+            tags: stats.tags.0.into_iter().map(TagStat::from).collect(),
+            unread: stats.unread,
+            storage_bytes: stats.storage_bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_site_stat_ratio_and_rounding() {
+        let stat = SiteStat::from(SiteStats {
+            domain: "example.com".to_string(),
+            saved: 4,
+            read: 1,
+            avg_reading_time_minutes: Some(7.333),
+        });
+        assert_eq!(stat.read_ratio, 0.25);
+        assert_eq!(stat.avg_reading_time_minutes, Some(7.3));
+    }
+
+    #[test]
+    fn test_site_stats_limit_bounds() {
+        assert!(SiteStatsQuery { limit: None }.validate().is_ok());
+        assert!(SiteStatsQuery { limit: Some(0) }.validate().is_err());
+        assert!(
+            SiteStatsQuery {
+                limit: Some(MAX_SITE_STATS_LIMIT + 1)
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    query::{FieldError, ValidatedQuery},
+    repositories::StatsRepository,
+    stats::dtos::{
+        DEFAULT_SITE_STATS_LIMIT, ItemStatsResponse, LanguageStat, LanguageStatsResponse, SiteStat,
+        SiteStatsQuery, SiteStatsResponse,
+    },
+};
+
+#[utoipa::path(
+    get,
+    path = "/v1/stats/languages",
+    tag = "stats",
+    responses(
+        (status = 200, description = "Item counts per content language", body = LanguageStatsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn language_stats(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Response {
+    let repo = StatsRepository::new(&state.db_pool);
+    match repo.language_breakdown(auth_user.user_id).await {
+        Ok(counts) => (
+            StatusCode::OK,
+            Json(LanguageStatsResponse {
+                languages: counts.into_iter().map(LanguageStat::from).collect(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/stats/sites",
+    tag = "stats",
+    params(SiteStatsQuery),
+    responses(
+        (status = 200, description = "Items saved and read per site, with mean reading time", body = SiteStatsResponse),
+        (status = 400, description = "Invalid query parameter", body = FieldError),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn site_stats(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    ValidatedQuery(query): ValidatedQuery<SiteStatsQuery>,
+) -> Response {
+    let repo = StatsRepository::new(&state.db_pool);
+    match repo
+        .site_stats(
+            auth_user.user_id,
+            query.limit.unwrap_or(DEFAULT_SITE_STATS_LIMIT),
+        )
+        .await
+    {
+        Ok(sites) => (
+            StatusCode::OK,
+            Json(SiteStatsResponse {
+                sites: sites.into_iter().map(SiteStat::from).collect(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/items/stats",
+    tag = "stats",
+    responses(
+        (status = 200, description = "Item counts per status and tag, unread backlog and storage used", body = ItemStatsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn item_stats(auth_user: AuthenticatedUser, State(state): State<AppState>) -> Response {
+    let repo = StatsRepository::new(&state.db_pool);
+    match repo.item_stats(auth_user.user_id).await {
+        Ok(stats) => (StatusCode::OK, Json(ItemStatsResponse::from(stats))).into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
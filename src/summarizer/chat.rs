@@ -0,0 +1,121 @@
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+
+use crate::summarizer::Summarizer;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Most characters of the text sent to the model; the opening of an article
+/// is enough to summarize it and keeps requests within context limits
+const MAX_INPUT_CHARS: usize = 24_000;
+
+const SYSTEM_PROMPT: &str = "Summarize the article the user sends in two or three plain sentences, \
+     in the language it is written in. Reply with the summary only.";
+
+pub const DEFAULT_CHAT_COMPLETIONS_URL: &str = "https://api.openai.com/v1/chat/completions";
+pub const DEFAULT_CHAT_MODEL: &str = "gpt-4o-mini";
+
+/// Summarizes with a large language model behind an OpenAI-compatible chat
+/// completions endpoint, which most hosted and self-hosted model servers
+/// offer
+pub struct ChatCompletionSummarizer {
+    client: Client,
+    endpoint: Url,
+    api_key: String,
+    model: String,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'static str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatReply,
+}
+
+#[derive(Deserialize)]
+struct ChatReply {
+    content: Option<String>,
+}
+
+impl ChatCompletionSummarizer {
+    pub fn new(endpoint: &str, api_key: String, model: String) -> anyhow::Result<Self> {
+        let endpoint = Url::parse(endpoint)
+            .with_context(|| format!("Invalid chat completions URL: {}", endpoint))?;
+        let client = Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+        Ok(Self {
+            client,
+            endpoint,
+            api_key,
+            model,
+        })
+    }
+}
+
+#[async_trait]
+impl Summarizer for ChatCompletionSummarizer {
+    fn name(&self) -> &'static str {
+        "chat-completion"
+    }
+
+    async fn summarize(&self, title: Option<&str>, text: &str) -> anyhow::Result<String> {
+        let text: String = text.chars().take(MAX_INPUT_CHARS).collect();
+        let article = match title {
+            Some(title) => format!("{}\n\n{}", title, text),
+            None => text,
+        };
+
+        let response = self
+            .client
+            .post(self.endpoint.clone())
+            .bearer_auth(&self.api_key)
+            .json(&ChatRequest {
+                model: &self.model,
+                messages: [
+                    ChatMessage {
+                        role: "system",
+                        content: SYSTEM_PROMPT,
+                    },
+                    ChatMessage {
+                        role: "user",
+                        content: &article,
+                    },
+                ],
+            })
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Chat completions returned {}: {}", status, body));
+        }
+        response
+            .json::<ChatResponse>()
+            .await?
+            .choices
+            .into_iter()
+            .find_map(|choice| choice.message.content)
+            .map(|summary| summary.trim().to_string())
+            .filter(|summary| !summary.is_empty())
+            .ok_or_else(|| anyhow!("Chat completions returned no summary"))
+    }
+}
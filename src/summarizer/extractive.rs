@@ -0,0 +1,112 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::{
+    extractor::text_map::{build_text_map, normalize_text},
+    summarizer::Summarizer,
+};
+
+/// Sentences picked for a summary
+const SUMMARY_SENTENCES: usize = 3;
+
+/// Sentences longer than this are usually lists or run-ons, not summaries
+const MAX_SENTENCE_CHARS: usize = 400;
+
+/// Words shorter than this are mostly function words in the languages we
+/// extract, so they don't count towards a sentence's score
+const MIN_WORD_CHARS: usize = 4;
+
+/// Picks the sentences whose words recur most across the text, in their
+/// original order. Runs locally without any model, so it's the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExtractiveSummarizer;
+
+#[async_trait]
+impl Summarizer for ExtractiveSummarizer {
+    fn name(&self) -> &'static str {
+        "extractive"
+    }
+
+    async fn summarize(&self, title: Option<&str>, text: &str) -> anyhow::Result<String> {
+        Ok(summarize(title, text, SUMMARY_SENTENCES))
+    }
+}
+
+/// The `count` best sentences of `text`. Words from the title count double,
+/// since the title usually names what the text is about.
+pub fn summarize(title: Option<&str>, text: &str, count: usize) -> String {
+    let normalized: Vec<char> = normalize_text(text).chars().collect();
+    let sentences: Vec<String> = build_text_map(text)
+        .paragraphs
+        .iter()
+        .flat_map(|paragraph| paragraph.sentences.iter())
+        .map(|span| normalized[span.start..span.end].iter().collect())
+        .collect();
+
+    let mut frequencies: HashMap<String, f64> = HashMap::new();
+    for word in sentences.iter().flat_map(|sentence| words(sentence)) {
+        *frequencies.entry(word).or_default() += 1.0;
+    }
+    for word in title.map(words).unwrap_or_default() {
+        *frequencies.entry(word).or_default() *= 2.0;
+    }
+
+    let mut scored: Vec<(usize, f64)> = sentences
+        .iter()
+        .enumerate()
+        .filter(|(_, sentence)| sentence.chars().count() <= MAX_SENTENCE_CHARS)
+        .map(|(index, sentence)| {
+            let words = words(sentence);
+            let score = if words.is_empty() {
+                0.0
+            } else {
+                words.iter().map(|word| frequencies[word]).sum::<f64>() / words.len() as f64
+            };
+            (index, score)
+        })
+        .collect();
+    // Stable, so equally good sentences keep their order
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(count);
+    scored.sort_by_key(|(index, _)| *index);
+
+    scored
+        .into_iter()
+        .map(|(index, _)| sentences[index].as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Lowercased words of `text` long enough to carry meaning
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= MIN_WORD_CHARS)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_picks_central_sentences_in_order() {
+        let text = "Rust compilers check memory safety. The weather was nice.\n\n\
+                    Memory safety without garbage collection is what Rust offers. \
+                    Lunch was late.";
+        assert_eq!(
+            summarize(None, text, 2),
+            "Rust compilers check memory safety. \
+             Memory safety without garbage collection is what Rust offers."
+        );
+    }
+
+    #[test]
+    fn test_summarize_short_text_is_kept() {
+        assert_eq!(
+            summarize(Some("Hi"), "Only one sentence.", 3),
+            "Only one sentence."
+        );
+        assert_eq!(summarize(None, "  ", 3), "");
+    }
+}
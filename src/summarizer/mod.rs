@@ -0,0 +1,20 @@
+pub mod chat;
+pub mod extractive;
+
+pub use chat::ChatCompletionSummarizer;
+pub use extractive::ExtractiveSummarizer;
+
+use async_trait::async_trait;
+
+/// Produces a short summary of an item's extracted text. The
+/// `summarize_content` job runs whichever implementation the worker was
+/// configured with; the rest of the crate only sees this trait.
+#[async_trait]
+pub trait Summarizer: Send + Sync {
+    /// Recorded with each summary, e.g. `extractive`
+    fn name(&self) -> &'static str;
+
+    /// Summarize plain `text`, paragraphs separated by blank lines. `title`
+    /// is the page's title when known.
+    async fn summarize(&self, title: Option<&str>, text: &str) -> anyhow::Result<String>;
+}
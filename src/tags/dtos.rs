@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{items::dtos::is_tag_name, repositories::RenamedTag};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RenameTagRequest {
+    /// New name; when another of the user's tags already has it, the two
+    /// are merged into that one
+    pub name: String,
+}
+
+impl RenameTagRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_tag_name(&self.name) {
+            return Err("name must be between 1 and 100 characters".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TagResponse {
+    /// The tag's ID, which is the other tag's when they were merged
+    pub id: Uuid,
+    pub name: String,
+    /// How many of the user's items carry the tag
+    pub item_count: i64,
+    /// Whether the tag was merged into an existing one of the same name
+    pub merged: bool,
+}
+
+impl From<RenamedTag> for TagResponse {
+    fn from(tag: RenamedTag) -> Self {
+        Self {
+            id: tag.id,
+            name: tag.name,
+            item_count: tag.item_count,
+            merged: tag.merged,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_request_validation() {
+        let request = |name: &str| RenameTagRequest {
+            name: name.to_string(),
+        };
+        assert!(request("reading list").validate().is_ok());
+        assert!(request("   ").validate().is_err());
+        assert!(request(&"x".repeat(101)).validate().is_err());
+    }
+}
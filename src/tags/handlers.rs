@@ -0,0 +1,68 @@
+use axum::{
+    Json,
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use uuid::Uuid;
+
+use crate::{
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    middleware::transaction::RequestTransaction,
+    repositories::TagRepository,
+    tags::dtos::{RenameTagRequest, TagResponse},
+};
+
+#[utoipa::path(
+    patch,
+    path = "/v1/tags/{id}",
+    tag = "tags",
+    params(
+        ("id" = Uuid, Path, description = "Tag ID")
+    ),
+    request_body = RenameTagRequest,
+    responses(
+        (status = 200, description = "Tag renamed, or merged into the existing tag of that name", body = TagResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error; nothing was changed", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn rename_tag(
+    auth_user: AuthenticatedUser,
+    transaction: RequestTransaction,
+    Path(tag_id): Path<Uuid>,
+    Json(payload): Json<RenameTagRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    let mut conn = transaction.conn().await;
+    match TagRepository::rename_in(&mut conn, auth_user.user_id, tag_id, payload.name.trim()).await
+    {
+        Ok(Some(tag)) => (StatusCode::OK, Json(TagResponse::from(tag))).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Tag not found".to_string(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
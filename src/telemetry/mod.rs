@@ -0,0 +1,180 @@
+//! Opt-in, anonymized instance telemetry.
+//!
+//! Only compiled with the `telemetry` feature, and even then nothing is sent
+//! unless `TELEMETRY_ENDPOINT` is set. Reports hold the version and coarse
+//! aggregates, never anything identifying a user, an item or the instance.
+
+use anyhow::{Context, anyhow};
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::time::Duration;
+use url::Url;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Telemetry configuration
+#[derive(Clone, Debug)]
+pub struct TelemetryConfig {
+    /// Where reports are posted as JSON
+    pub endpoint: Url,
+    /// Seconds between reports, which is also the period rates cover
+    pub interval_secs: i64,
+}
+
+impl TelemetryConfig {
+    /// Daily reports to `endpoint`
+    pub fn new(endpoint: Url) -> Self {
+        Self {
+            endpoint,
+            interval_secs: 24 * 60 * 60, // daily
+        }
+    }
+
+    /// The configuration from `TELEMETRY_ENDPOINT` and
+    /// `TELEMETRY_INTERVAL_SECS`, or `None` when no endpoint is set
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Ok(endpoint) = std::env::var("TELEMETRY_ENDPOINT") else {
+            return Ok(None);
+        };
+        let endpoint = Url::parse(&endpoint)
+            .with_context(|| format!("Invalid TELEMETRY_ENDPOINT: {}", endpoint))?;
+
+        let mut config = Self::new(endpoint);
+        if let Some(interval_secs) = std::env::var("TELEMETRY_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.interval_secs = interval_secs;
+        }
+        Ok(Some(config))
+    }
+}
+
+/// What an instance reports
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TelemetryReport {
+    pub version: &'static str,
+    /// Seconds the rates below cover
+    pub period_secs: i64,
+    pub users: &'static str,
+    pub items: &'static str,
+    /// Jobs finished during the period
+    pub jobs: &'static str,
+    /// Share of the jobs finished during the period that failed
+    pub job_failure_rate: Option<f64>,
+    /// Share of the page fetches during the period that failed
+    pub fetch_failure_rate: Option<f64>,
+}
+
+impl TelemetryReport {
+    /// Gather the report for the period starting at `since`
+    pub async fn collect(
+        pool: &PgPool,
+        since: DateTime<Utc>,
+        period_secs: i64,
+    ) -> anyhow::Result<Self> {
+        let counts = sqlx::query!(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM users) AS "users!",
+                (SELECT COUNT(*) FROM items) AS "items!",
+                (SELECT COUNT(*) FROM jobs
+                 WHERE status IN ('succeeded', 'failed') AND updated_at > $1) AS "jobs!",
+                (SELECT COUNT(*) FROM jobs
+                 WHERE status = 'failed' AND updated_at > $1) AS "failed_jobs!",
+                (SELECT COUNT(*) FROM item_events
+                 WHERE kind IN ('fetched', 'fetch_failed') AND created_at > $1) AS "fetches!",
+                (SELECT COUNT(*) FROM item_events
+                 WHERE kind = 'fetch_failed' AND created_at > $1) AS "failed_fetches!"
+            "#,
+            since
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(Self {
+            version: env!("CARGO_PKG_VERSION"),
+            period_secs,
+            users: bucket(counts.users),
+            items: bucket(counts.items),
+            jobs: bucket(counts.jobs),
+            job_failure_rate: rate(counts.failed_jobs, counts.jobs),
+            fetch_failure_rate: rate(counts.failed_fetches, counts.fetches),
+        })
+    }
+}
+
+/// Posts reports to the configured endpoint
+pub struct TelemetryClient {
+    client: Client,
+    endpoint: Url,
+}
+
+impl TelemetryClient {
+    pub fn new(endpoint: Url) -> anyhow::Result<Self> {
+        let client = Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+        Ok(Self { client, endpoint })
+    }
+
+    pub async fn send(&self, report: &TelemetryReport) -> anyhow::Result<()> {
+        let response = self
+            .client
+            .post(self.endpoint.clone())
+            .json(report)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("Telemetry endpoint returned {}", status));
+        }
+        Ok(())
+    }
+}
+
+/// The bucket `count` falls in, e.g. "10-99"
+pub fn bucket(count: i64) -> &'static str {
+    match count {
+        i64::MIN..=0 => "0",
+        1..=9 => "1-9",
+        10..=99 => "10-99",
+        100..=999 => "100-999",
+        1_000..=9_999 => "1000-9999",
+        10_000..=99_999 => "10000-99999",
+        _ => "100000+",
+    }
+}
+
+/// `part` as a share of `whole`, rounded to two decimals so small instances
+/// don't report exact counts; `None` when there was nothing to measure
+pub fn rate(part: i64, whole: i64) -> Option<f64> {
+    (whole > 0).then(|| (part as f64 / whole as f64 * 100.0).round() / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket() {
+        assert_eq!(bucket(0), "0");
+        assert_eq!(bucket(1), "1-9");
+        assert_eq!(bucket(99), "10-99");
+        assert_eq!(bucket(1_000), "1000-9999");
+        assert_eq!(bucket(5_000_000), "100000+");
+        // Every bound starts a bucket of its own
+        for bound in [10, 100, 1_000, 10_000, 100_000] {
+            assert_ne!(bucket(bound - 1), bucket(bound));
+        }
+    }
+
+    #[test]
+    fn test_rate() {
+        assert_eq!(rate(0, 0), None);
+        assert_eq!(rate(0, 10), Some(0.0));
+        assert_eq!(rate(1, 3), Some(0.33));
+        assert_eq!(rate(3, 3), Some(1.0));
+    }
+}
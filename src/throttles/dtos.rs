@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::entities::{AbuseEvent, AbuseEventKind, UserThrottle};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct OverrideThrottleRequest {
+    /// Keep the user from being throttled automatically again
+    #[serde(default)]
+    pub exempt: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ThrottleResponse {
+    pub user_id: Uuid,
+    /// Saves are refused until then; null when not throttled
+    pub throttled_until: Option<DateTime<Utc>>,
+    /// What tripped the throttle
+    pub reason: Option<String>,
+    pub exempt: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ThrottleListResponse {
+    pub throttles: Vec<ThrottleResponse>,
+}
+
+impl From<UserThrottle> for ThrottleResponse {
+    fn from(throttle: UserThrottle) -> Self {
+        Self {
+            user_id: throttle.user_id,
+            throttled_until: throttle.throttled_until.filter(|until| *until > Utc::now()),
+            reason: throttle.reason,
+            exempt: throttle.exempt,
+            updated_at: throttle.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AbuseEventResponse {
+    pub id: i64,
+    pub kind: AbuseEventKind,
+    pub detail: Option<String>,
+    /// The admin behind a manual change; null for automatic throttles
+    pub actor_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AbuseEventListResponse {
+    pub events: Vec<AbuseEventResponse>,
+}
+
+impl From<AbuseEvent> for AbuseEventResponse {
+    fn from(event: AbuseEvent) -> Self {
+        Self {
+            id: event.id,
+            kind: event.kind,
+            detail: event.detail,
+            actor_id: event.actor_id,
+            created_at: event.created_at,
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AdminUser},
+    repositories::ThrottleRepository,
+    throttles::dtos::{
+        AbuseEventListResponse, AbuseEventResponse, OverrideThrottleRequest, ThrottleListResponse,
+        ThrottleResponse,
+    },
+};
+
+/// Most audit events returned for a user
+const MAX_ABUSE_EVENTS: i64 = 100;
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/throttles",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Users currently throttled or exempt", body = ThrottleListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_throttles(_admin: AdminUser, State(state): State<AppState>) -> Response {
+    let repo = ThrottleRepository::new(&state.db_pool);
+    match repo.list().await {
+        Ok(throttles) => (
+            StatusCode::OK,
+            Json(ThrottleListResponse {
+                throttles: throttles.into_iter().map(ThrottleResponse::from).collect(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/v1/admin/throttles/{user_id}",
+    tag = "admin",
+    params(
+        ("user_id" = Uuid, Path, description = "User whose throttle to override")
+    ),
+    request_body = OverrideThrottleRequest,
+    responses(
+        (status = 200, description = "Throttle lifted and exemption set", body = ThrottleResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn override_throttle(
+    admin: AdminUser,
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<OverrideThrottleRequest>,
+) -> Response {
+    match state.user_repo.find_by_id(user_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "User not found".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Err(_) => return database_error(),
+    }
+
+    let repo = ThrottleRepository::new(&state.db_pool);
+    match repo
+        .override_throttle(user_id, payload.exempt, admin.user_id)
+        .await
+    {
+        Ok(throttle) => (StatusCode::OK, Json(ThrottleResponse::from(throttle))).into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/admin/throttles/{user_id}/events",
+    tag = "admin",
+    params(
+        ("user_id" = Uuid, Path, description = "User whose audit trail to list")
+    ),
+    responses(
+        (status = 200, description = "Throttles and overrides, newest first", body = AbuseEventListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_abuse_events(
+    _admin: AdminUser,
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Response {
+    let repo = ThrottleRepository::new(&state.db_pool);
+    match repo.events(user_id, MAX_ABUSE_EVENTS).await {
+        Ok(events) => (
+            StatusCode::OK,
+            Json(AbuseEventListResponse {
+                events: events.into_iter().map(AbuseEventResponse::from).collect(),
+            }),
+        )
+            .into_response(),
+        Err(_) => database_error(),
+    }
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
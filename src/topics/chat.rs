@@ -0,0 +1,156 @@
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+
+use crate::topics::{MAX_TOPICS_PER_ITEM, TOPICS, TopicClassifier};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Most characters of the text sent to the model; the opening of an article
+/// says what it's about
+const MAX_INPUT_CHARS: usize = 8000;
+
+/// Asks a large language model behind an OpenAI-compatible chat completions
+/// endpoint which of [`TOPICS`] an article is about
+pub struct ChatCompletionClassifier {
+    client: Client,
+    endpoint: Url,
+    api_key: String,
+    model: String,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'static str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatReply,
+}
+
+#[derive(Deserialize)]
+struct ChatReply {
+    content: Option<String>,
+}
+
+impl ChatCompletionClassifier {
+    pub fn new(endpoint: &str, api_key: String, model: String) -> anyhow::Result<Self> {
+        let endpoint = Url::parse(endpoint)
+            .with_context(|| format!("Invalid chat completions URL: {}", endpoint))?;
+        let client = Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+        Ok(Self {
+            client,
+            endpoint,
+            api_key,
+            model,
+        })
+    }
+}
+
+#[async_trait]
+impl TopicClassifier for ChatCompletionClassifier {
+    fn name(&self) -> &'static str {
+        "chat-completion"
+    }
+
+    async fn classify(&self, title: Option<&str>, text: &str) -> anyhow::Result<Vec<String>> {
+        let text: String = text.chars().take(MAX_INPUT_CHARS).collect();
+        let article = match title {
+            Some(title) => format!("{}\n\n{}", title, text),
+            None => text,
+        };
+        let prompt = format!(
+            "Pick at most {} of these topics that the article the user sends is mainly about: \
+             {}. Reply with the topics separated by commas, or `none`.",
+            MAX_TOPICS_PER_ITEM,
+            TOPICS.join(", ")
+        );
+
+        let response = self
+            .client
+            .post(self.endpoint.clone())
+            .bearer_auth(&self.api_key)
+            .json(&ChatRequest {
+                model: &self.model,
+                messages: [
+                    ChatMessage {
+                        role: "system",
+                        content: &prompt,
+                    },
+                    ChatMessage {
+                        role: "user",
+                        content: &article,
+                    },
+                ],
+            })
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Chat completions returned {}: {}", status, body));
+        }
+        let reply = response
+            .json::<ChatResponse>()
+            .await?
+            .choices
+            .into_iter()
+            .find_map(|choice| choice.message.content)
+            .ok_or_else(|| anyhow!("Chat completions returned no topics"))?;
+        Ok(parse_topics(&reply))
+    }
+}
+
+/// Known topics named in a model's reply, in order; models don't always
+/// stick to the requested format, so anything else is ignored
+fn parse_topics(reply: &str) -> Vec<String> {
+    let mut topics: Vec<String> = Vec::new();
+    for word in reply.split([',', '\n', ';']) {
+        let word = word
+            .trim()
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        if TOPICS.contains(&word.as_str()) && !topics.contains(&word) {
+            topics.push(word);
+        }
+    }
+    topics.truncate(MAX_TOPICS_PER_ITEM);
+    topics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_topics() {
+        assert_eq!(parse_topics("science, tech"), vec!["science", "tech"]);
+        assert_eq!(
+            parse_topics("`Health`\n- sports."),
+            vec!["health", "sports"]
+        );
+        assert_eq!(parse_topics("tech, tech, cooking"), vec!["tech"]);
+        assert!(parse_topics("none").is_empty());
+        assert_eq!(
+            parse_topics("tech, science, politics"),
+            vec!["tech", "science"]
+        );
+    }
+}
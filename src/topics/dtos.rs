@@ -0,0 +1,26 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::repositories::TopicCount;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TopicStat {
+    /// Topic name, e.g. `tech`; pass it as `topic` to the item list or
+    /// search to browse its items
+    pub topic: String,
+    pub count: i64,
+}
+
+impl From<TopicCount> for TopicStat {
+    fn from(count: TopicCount) -> Self {
+        Self {
+            topic: count.topic,
+            count: count.count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TopicListResponse {
+    pub topics: Vec<TopicStat>,
+}
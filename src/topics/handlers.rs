@@ -0,0 +1,46 @@
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    repositories::TopicRepository,
+    topics::dtos::{TopicListResponse, TopicStat},
+};
+
+#[utoipa::path(
+    get,
+    path = "/v1/topics",
+    tag = "topics",
+    responses(
+        (status = 200, description = "Topics in the user's library with their item counts, most common first", body = TopicListResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_topics(auth_user: AuthenticatedUser, State(state): State<AppState>) -> Response {
+    let repo = TopicRepository::new(&state.db_pool);
+    match repo.counts(auth_user.user_id).await {
+        Ok(counts) => (
+            StatusCode::OK,
+            Json(TopicListResponse {
+                topics: counts.into_iter().map(TopicStat::from).collect(),
+            }),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: "Database error".to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,327 @@
+use anyhow::{Context, bail};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::topics::{MAX_TOPICS_PER_ITEM, TopicClassifier, is_topic_name};
+
+/// Keyword matches an item needs before a topic is assigned, so a passing
+/// mention doesn't count
+const MIN_KEYWORD_HITS: usize = 3;
+
+/// Title words say more about what a page is about than body words
+const TITLE_WEIGHT: usize = 3;
+
+/// Keywords of the built-in rules, per topic
+const DEFAULT_RULES: [(&str, &[&str]); 8] = [
+    (
+        "tech",
+        &[
+            "software",
+            "programming",
+            "developer",
+            "developers",
+            "code",
+            "computer",
+            "rust",
+            "python",
+            "javascript",
+            "linux",
+            "database",
+            "api",
+            "cloud",
+            "startup",
+            "app",
+            "apps",
+            "internet",
+            "algorithm",
+            "compiler",
+            "ai",
+            "smartphone",
+            "server",
+        ],
+    ),
+    (
+        "science",
+        &[
+            "research",
+            "researchers",
+            "scientists",
+            "study",
+            "physics",
+            "chemistry",
+            "biology",
+            "astronomy",
+            "experiment",
+            "species",
+            "climate",
+            "genome",
+            "quantum",
+            "telescope",
+            "evolution",
+            "molecules",
+            "laboratory",
+        ],
+    ),
+    (
+        "politics",
+        &[
+            "election",
+            "elections",
+            "government",
+            "parliament",
+            "senate",
+            "congress",
+            "president",
+            "minister",
+            "policy",
+            "vote",
+            "voters",
+            "campaign",
+            "democrats",
+            "republicans",
+            "legislation",
+            "party",
+            "diplomacy",
+        ],
+    ),
+    (
+        "business",
+        &[
+            "market",
+            "markets",
+            "company",
+            "companies",
+            "revenue",
+            "profit",
+            "investors",
+            "stock",
+            "shares",
+            "economy",
+            "inflation",
+            "earnings",
+            "ceo",
+            "acquisition",
+            "funding",
+            "sales",
+            "bank",
+        ],
+    ),
+    (
+        "health",
+        &[
+            "health",
+            "medical",
+            "doctor",
+            "doctors",
+            "patients",
+            "disease",
+            "vaccine",
+            "hospital",
+            "treatment",
+            "symptoms",
+            "nutrition",
+            "exercise",
+            "mental",
+            "cancer",
+            "therapy",
+            "sleep",
+        ],
+    ),
+    (
+        "sports",
+        &[
+            "football",
+            "soccer",
+            "basketball",
+            "tennis",
+            "baseball",
+            "league",
+            "season",
+            "coach",
+            "players",
+            "championship",
+            "tournament",
+            "goal",
+            "match",
+            "olympics",
+            "team",
+        ],
+    ),
+    (
+        "culture",
+        &[
+            "film",
+            "movie",
+            "music",
+            "album",
+            "novel",
+            "book",
+            "books",
+            "art",
+            "artist",
+            "museum",
+            "theatre",
+            "theater",
+            "poetry",
+            "festival",
+            "painting",
+            "literature",
+        ],
+    ),
+    (
+        "travel",
+        &[
+            "travel",
+            "trip",
+            "flight",
+            "flights",
+            "hotel",
+            "tourists",
+            "tourism",
+            "beach",
+            "island",
+            "itinerary",
+            "passport",
+            "destination",
+            "backpacking",
+            "airport",
+        ],
+    ),
+];
+
+/// Assigns the topics whose keywords appear most often in an item. Runs
+/// locally without any model, so it's the default. Keywords are single
+/// lowercase words.
+#[derive(Debug, Clone)]
+pub struct KeywordClassifier {
+    /// Topic for each keyword; a keyword may belong to several topics
+    keywords: HashMap<String, Vec<String>>,
+}
+
+impl Default for KeywordClassifier {
+    fn default() -> Self {
+        Self::new(DEFAULT_RULES.iter().map(|(topic, words)| {
+            (
+                topic.to_string(),
+                words.iter().map(|word| word.to_string()).collect(),
+            )
+        }))
+    }
+}
+
+impl KeywordClassifier {
+    pub fn new(rules: impl IntoIterator<Item = (String, Vec<String>)>) -> Self {
+        let mut keywords: HashMap<String, Vec<String>> = HashMap::new();
+        for (topic, words) in rules {
+            let words: HashSet<String> = words.iter().map(|word| word.to_lowercase()).collect();
+            for word in words {
+                keywords.entry(word).or_default().push(topic.clone());
+            }
+        }
+        Self { keywords }
+    }
+
+    /// Rules from a JSON file mapping each topic to its keywords, e.g.
+    /// `{"gardening": ["compost", "seedlings"]}`
+    pub fn from_file(path: &str) -> anyhow::Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read topic keywords from {}", path))?;
+        let rules: BTreeMap<String, Vec<String>> = serde_json::from_str(&json)
+            .with_context(|| format!("Invalid topic keywords in {}", path))?;
+        if let Some(topic) = rules.keys().find(|topic| !is_topic_name(topic)) {
+            bail!("Invalid topic name in {}: {:?}", path, topic);
+        }
+        Ok(Self::new(rules))
+    }
+
+    /// The best topics for the text, by weighted keyword hits
+    pub fn topics(&self, title: Option<&str>, text: &str) -> Vec<String> {
+        let mut hits: HashMap<&str, usize> = HashMap::new();
+        let weighted = words(title.unwrap_or_default())
+            .map(|word| (word, TITLE_WEIGHT))
+            .chain(words(text).map(|word| (word, 1)));
+        for (word, weight) in weighted {
+            for topic in self.keywords.get(&word).into_iter().flatten() {
+                *hits.entry(topic.as_str()).or_default() += weight;
+            }
+        }
+
+        let mut ranked: Vec<(&str, usize)> = hits
+            .into_iter()
+            .filter(|(_, count)| *count >= MIN_KEYWORD_HITS)
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        // A second topic only counts when it's nearly as strong as the first
+        let best = ranked.first().map_or(0, |(_, count)| *count);
+        ranked
+            .into_iter()
+            .take_while(|(_, count)| count * 2 >= best)
+            .take(MAX_TOPICS_PER_ITEM)
+            .map(|(topic, _)| topic.to_string())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl TopicClassifier for KeywordClassifier {
+    fn name(&self) -> &'static str {
+        "keywords"
+    }
+
+    async fn classify(&self, title: Option<&str>, text: &str) -> anyhow::Result<Vec<String>> {
+        Ok(self.topics(title, text))
+    }
+}
+
+/// Lowercased words of `text`
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topics_picks_strongest_topic() {
+        let classifier = KeywordClassifier::default();
+        let text = "The compiler rejects the code. Developers rewrote the software in Rust, \
+                    and the server now runs on Linux.";
+        assert_eq!(classifier.topics(None, text), vec!["tech"]);
+    }
+
+    #[test]
+    fn test_topics_title_counts_more_and_passing_mentions_do_not() {
+        let classifier = KeywordClassifier::default();
+        assert!(classifier.topics(None, "We took a flight home.").is_empty());
+        assert_eq!(
+            classifier.topics(Some("Travel"), "We took a flight home."),
+            vec!["travel"]
+        );
+    }
+
+    #[test]
+    fn test_topics_keeps_a_close_second() {
+        let classifier = KeywordClassifier::default();
+        let text = "The election campaign focused on the economy. Voters worried about \
+                    inflation while the stock market fell and the government set new policy.";
+        assert_eq!(classifier.topics(None, text), vec!["politics", "business"]);
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let classifier = KeywordClassifier::new([(
+            "gardening".to_string(),
+            vec!["Compost".to_string(), "seedlings".to_string()],
+        )]);
+        let text = "Compost the leaves, then water the seedlings. More compost helps.";
+        assert_eq!(classifier.topics(None, text), vec!["gardening"]);
+        assert!(
+            classifier
+                .topics(None, "Rust developers write code")
+                .is_empty()
+        );
+    }
+}
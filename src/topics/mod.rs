@@ -0,0 +1,77 @@
+pub mod chat;
+pub mod dtos;
+pub mod handlers;
+pub mod keywords;
+
+pub use chat::ChatCompletionClassifier;
+pub use keywords::KeywordClassifier;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::summarizer::chat::{DEFAULT_CHAT_COMPLETIONS_URL, DEFAULT_CHAT_MODEL};
+
+/// The broad topics the built-in classifiers assign
+pub const TOPICS: [&str; 8] = [
+    "tech", "science", "politics", "business", "health", "sports", "culture", "travel",
+];
+
+/// Most topics kept per item; beyond a couple, topics stop being useful
+/// for browsing
+pub const MAX_TOPICS_PER_ITEM: usize = 2;
+
+/// Assigns broad topics to an item from its extracted text. The
+/// `classify_topics` job runs whichever implementation the worker was
+/// configured with; the rest of the crate only sees this trait.
+#[async_trait]
+pub trait TopicClassifier: Send + Sync {
+    /// Identifies the classifier in logs, e.g. `keywords`
+    fn name(&self) -> &'static str;
+
+    /// Up to [`MAX_TOPICS_PER_ITEM`] topic names, best first, or none when
+    /// nothing fits. `title` is the page's title when known.
+    async fn classify(&self, title: Option<&str>, text: &str) -> anyhow::Result<Vec<String>>;
+}
+
+/// The classifier configured by the environment: a language model behind an
+/// OpenAI-compatible endpoint when `TOPIC_LLM_API_KEY` is set (with
+/// `TOPIC_LLM_URL` and `TOPIC_LLM_MODEL` overriding the defaults), else
+/// keyword rules, read from `TOPIC_KEYWORDS_FILE` when set
+pub fn classifier_from_env() -> anyhow::Result<Arc<dyn TopicClassifier>> {
+    if let Ok(api_key) = std::env::var("TOPIC_LLM_API_KEY") {
+        return Ok(Arc::new(ChatCompletionClassifier::new(
+            &std::env::var("TOPIC_LLM_URL")
+                .unwrap_or_else(|_| DEFAULT_CHAT_COMPLETIONS_URL.to_string()),
+            api_key,
+            std::env::var("TOPIC_LLM_MODEL").unwrap_or_else(|_| DEFAULT_CHAT_MODEL.to_string()),
+        )?));
+    }
+    let classifier = match std::env::var("TOPIC_KEYWORDS_FILE") {
+        Ok(path) => KeywordClassifier::from_file(&path)?,
+        Err(_) => KeywordClassifier::default(),
+    };
+    Ok(Arc::new(classifier))
+}
+
+/// Whether `topic` is a usable topic name: a short lowercase slug
+pub fn is_topic_name(topic: &str) -> bool {
+    (1..=32).contains(&topic.len())
+        && topic
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_topic_name() {
+        assert!(TOPICS.iter().all(|topic| is_topic_name(topic)));
+        assert!(is_topic_name("home-improvement"));
+        assert!(!is_topic_name(""));
+        assert!(!is_topic_name("Tech"));
+        assert!(!is_topic_name("tech news"));
+        assert!(!is_topic_name(&"a".repeat(33)));
+    }
+}
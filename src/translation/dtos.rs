@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{entities::ContentTranslation, translation::is_language_code};
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct TranslateItemRequest {
+    /// ISO 639-1 code of the language to translate into; defaults to the
+    /// user's `translate_to` preference
+    pub lang: Option<String>,
+}
+
+impl TranslateItemRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(lang) = &self.lang
+            && !is_language_code(lang)
+        {
+            return Err("lang must be an ISO 639-1 language code".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TranslationRequestedResponse {
+    pub item_id: Uuid,
+    pub lang: String,
+    /// The `translate_content` job, queued once the worker relays it from
+    /// the outbox
+    pub job_id: Uuid,
+}
+
+/// The item's text in another language, as an alternative to its extracted
+/// content
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TranslationResponse {
+    pub item_id: Uuid,
+    pub lang: String,
+    /// Language the original was detected as, if known
+    pub source_lang: Option<String>,
+    /// Translated plain text, paragraphs separated by blank lines
+    pub text: String,
+    /// Service that made the translation
+    pub provider: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ContentTranslation> for TranslationResponse {
+    fn from(translation: ContentTranslation) -> Self {
+        Self {
+            item_id: translation.item_id,
+            lang: translation.lang,
+            source_lang: translation.source_lang,
+            text: translation.text,
+            provider: translation.provider,
+            created_at: translation.created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_item_request_validate() {
+        assert!(TranslateItemRequest::default().validate().is_ok());
+        let request = TranslateItemRequest {
+            lang: Some("de".to_string()),
+        };
+        assert!(request.validate().is_ok());
+        let request = TranslateItemRequest {
+            lang: Some("German".to_string()),
+        };
+        assert!(request.validate().is_err());
+    }
+}
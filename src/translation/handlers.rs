@@ -0,0 +1,161 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use sqlx::types::Json as SqlxJson;
+use uuid::Uuid;
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    entities::UserPreferences,
+    jobs::{Outbox, TRANSLATE_CONTENT_JOB_KIND, TranslateContentPayload},
+    middleware::transaction::RequestTransaction,
+    repositories::TranslationRepository,
+    translation::dtos::{TranslateItemRequest, TranslationRequestedResponse, TranslationResponse},
+};
+
+#[utoipa::path(
+    post,
+    path = "/v1/items/{id}/translations",
+    tag = "items",
+    params(
+        ("id" = Uuid, Path, description = "Item ID")
+    ),
+    request_body = TranslateItemRequest,
+    responses(
+        (status = 202, description = "Translation queued", body = TranslationRequestedResponse),
+        (status = 400, description = "No target language given or preferred", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn request_translation(
+    auth_user: AuthenticatedUser,
+    transaction: RequestTransaction,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<TranslateItemRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    let owner = sqlx::query_scalar!(
+        r#"
+        SELECT u.prefs AS "prefs: SqlxJson<UserPreferences>"
+        FROM items i
+        JOIN users u ON u.id = i.user_id
+        WHERE i.id = $1 AND i.user_id = $2
+        "#,
+        id,
+        auth_user.user_id
+    )
+    .fetch_optional(&mut *transaction.conn().await)
+    .await;
+
+    let prefs = match owner {
+        Ok(Some(SqlxJson(prefs))) => prefs,
+        Ok(None) => return not_found("Item not found"),
+        Err(_) => return database_error(),
+    };
+    let Some(lang) = payload.lang.or(prefs.translate_to) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "lang is required when no translate_to preference is set".to_string(),
+            }),
+        )
+            .into_response();
+    };
+
+    let job_payload = TranslateContentPayload {
+        item_id: id,
+        lang: lang.clone(),
+    };
+    let job_payload = match serde_json::to_value(&job_payload) {
+        Ok(job_payload) => job_payload,
+        Err(_) => return database_error(),
+    };
+
+    // Committed by the transaction middleware once we return 202
+    let job_id = match Outbox::enqueue(
+        &mut *transaction.conn().await,
+        TRANSLATE_CONTENT_JOB_KIND,
+        job_payload,
+        None,
+    )
+    .await
+    {
+        Ok(job_id) => job_id,
+        Err(_) => return database_error(),
+    };
+
+    (
+        StatusCode::ACCEPTED,
+        Json(TranslationRequestedResponse {
+            item_id: id,
+            lang,
+            job_id,
+        }),
+    )
+        .into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/items/{id}/translations/{lang}",
+    tag = "items",
+    params(
+        ("id" = Uuid, Path, description = "Item ID"),
+        ("lang" = String, Path, description = "ISO 639-1 code of the translation")
+    ),
+    responses(
+        (status = 200, description = "The item's text translated into the language", body = TranslationResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Item not found or not translated into the language", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_translation(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((id, lang)): Path<(Uuid, String)>,
+) -> Response {
+    let repo = TranslationRepository::new(&state.db_pool);
+    match repo.find_for_user(auth_user.user_id, id, &lang).await {
+        Ok(Some(translation)) => {
+            (StatusCode::OK, Json(TranslationResponse::from(translation))).into_response()
+        }
+        Ok(None) => not_found("Translation not found"),
+        Err(_) => database_error(),
+    }
+}
+
+fn not_found(message: &str) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: message.to_string(),
+        }),
+    )
+        .into_response()
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
@@ -0,0 +1,83 @@
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+
+use crate::translation::TranslationProvider;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Translates through a [LibreTranslate](https://libretranslate.com) server,
+/// self-hosted or public
+pub struct LibreTranslate {
+    client: Client,
+    endpoint: Url,
+    api_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TranslateRequest<'a> {
+    q: &'a str,
+    source: &'a str,
+    target: &'a str,
+    format: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct TranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+impl LibreTranslate {
+    /// A provider for the server at `base_url`, e.g. `http://localhost:5000`
+    pub fn new(base_url: &str, api_key: Option<String>) -> anyhow::Result<Self> {
+        let endpoint = Url::parse(base_url)
+            .and_then(|url| url.join("translate"))
+            .with_context(|| format!("Invalid LibreTranslate URL: {}", base_url))?;
+        let client = Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+        Ok(Self {
+            client,
+            endpoint,
+            api_key,
+        })
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for LibreTranslate {
+    fn name(&self) -> &'static str {
+        "libretranslate"
+    }
+
+    async fn translate(
+        &self,
+        text: &str,
+        source: Option<&str>,
+        target: &str,
+    ) -> anyhow::Result<String> {
+        let response = self
+            .client
+            .post(self.endpoint.clone())
+            .json(&TranslateRequest {
+                q: text,
+                source: source.unwrap_or("auto"),
+                target,
+                format: "text",
+                api_key: self.api_key.as_deref(),
+            })
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("LibreTranslate returned {}: {}", status, body));
+        }
+        Ok(response.json::<TranslateResponse>().await?.translated_text)
+    }
+}
@@ -0,0 +1,106 @@
+pub mod dtos;
+pub mod handlers;
+pub mod libretranslate;
+
+pub use libretranslate::LibreTranslate;
+
+use async_trait::async_trait;
+
+/// Most characters sent to a provider in one request; longer texts are
+/// translated a few paragraphs at a time
+pub const MAX_CHUNK_CHARS: usize = 4000;
+
+/// A machine translation service. The `translate_content` job hands each
+/// item's text to the configured provider, a chunk at a time.
+#[async_trait]
+pub trait TranslationProvider: Send + Sync {
+    /// Recorded with each translation, e.g. `libretranslate`
+    fn name(&self) -> &'static str;
+
+    /// Translate plain `text` into the `target` language. `source` is the
+    /// detected language of the text; without it the provider guesses.
+    async fn translate(
+        &self,
+        text: &str,
+        source: Option<&str>,
+        target: &str,
+    ) -> anyhow::Result<String>;
+}
+
+/// Whether `code` looks like an ISO 639-1 language code, the form the
+/// extractor detects languages in
+pub fn is_language_code(code: &str) -> bool {
+    code.len() == 2 && code.bytes().all(|b| b.is_ascii_lowercase())
+}
+
+/// Split extracted text into chunks of at most `max_chars` characters,
+/// keeping paragraphs together where they fit. Paragraphs are separated by a
+/// blank line in extracted text, and chunks should be rejoined the same way.
+pub fn chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        for piece in split_long(paragraph, max_chars) {
+            let joined = current.chars().count() + 2 + piece.chars().count();
+            if !current.is_empty() && joined > max_chars {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(piece);
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Break a paragraph longer than `max_chars` at the last whitespace that
+/// fits, or mid-word when a single word is longer than that
+fn split_long(mut paragraph: &str, max_chars: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    while paragraph.chars().count() > max_chars {
+        let limit = paragraph
+            .char_indices()
+            .nth(max_chars)
+            .map_or(paragraph.len(), |(index, _)| index);
+        let split = if paragraph[limit..].starts_with(char::is_whitespace) {
+            limit
+        } else {
+            paragraph[..limit]
+                .rfind(char::is_whitespace)
+                .filter(|&index| index > 0)
+                .unwrap_or(limit)
+        };
+        pieces.push(paragraph[..split].trim_end());
+        paragraph = paragraph[split..].trim_start();
+    }
+    if !paragraph.is_empty() {
+        pieces.push(paragraph);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_keep_paragraphs_together() {
+        let text = "One two.\n\nThree four.\n\nFive six seven.";
+        assert_eq!(chunks(text, 100), [text]);
+        assert_eq!(
+            chunks(text, 25),
+            ["One two.\n\nThree four.", "Five six seven."]
+        );
+    }
+
+    #[test]
+    fn test_chunks_split_long_paragraphs() {
+        assert_eq!(chunks("aaa bbb ccc", 7), ["aaa bbb", "ccc"]);
+        assert_eq!(chunks("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+        assert_eq!(chunks("äöü äöü", 3), ["äöü", "äöü"]);
+    }
+}
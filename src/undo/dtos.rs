@@ -0,0 +1,10 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UndoResponse {
+    /// Items the change was reversed on; deleted items are back, and items
+    /// deleted since the change are left out
+    pub item_ids: Vec<Uuid>,
+}
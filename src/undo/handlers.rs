@@ -0,0 +1,110 @@
+use axum::{
+    Json,
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    jobs::{EMBED_CONTENT_JOB_KIND, EmbedContentPayload, Outbox, stage_fetch_jobs},
+    middleware::transaction::RequestTransaction,
+    repositories::{ItemRepository, UndoRepository, UndoStep},
+    undo::dtos::UndoResponse,
+};
+
+#[utoipa::path(
+    post,
+    path = "/v1/undo/{token}",
+    tag = "items",
+    params(
+        ("token" = Uuid, Path, description = "Undo token from the `X-Undo-Token` header of a delete, archive or bulk change")
+    ),
+    responses(
+        (status = 200, description = "The change was reversed", body = UndoResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No such change, or its undo window has passed or it was already undone", body = ErrorResponse),
+        (status = 500, description = "Internal server error; nothing was changed", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn undo(
+    auth_user: AuthenticatedUser,
+    transaction: RequestTransaction,
+    Path(token): Path<Uuid>,
+) -> Response {
+    let user_id = auth_user.user_id;
+    let mut conn = transaction.conn().await;
+    let steps = match UndoRepository::take_in(&mut conn, user_id, token).await {
+        Ok(Some(steps)) => steps,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse {
+                    error: "Nothing to undo".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        Err(_) => return database_error(),
+    };
+
+    // Later steps were applied on top of earlier ones
+    let mut item_ids = Vec::new();
+    for step in steps.iter().rev() {
+        let touched = match UndoRepository::apply_in(&mut conn, user_id, step).await {
+            Ok(touched) => touched,
+            Err(_) => return database_error(),
+        };
+        // A delete cancelled the item's jobs; start over on unfinished ones,
+        // and embed the rest again as their embeddings went with them
+        if matches!(step, UndoStep::Restore { .. }) {
+            let unfinished = match ItemRepository::unfinished_in(&mut conn, &touched).await {
+                Ok(unfinished) => unfinished,
+                Err(_) => return database_error(),
+            };
+            for &item_id in &touched {
+                let staged = if unfinished.contains(&item_id) {
+                    stage_fetch_jobs(&mut conn, item_id).await
+                } else {
+                    stage_embedding(&mut conn, item_id).await
+                };
+                if staged.is_err() {
+                    return database_error();
+                }
+            }
+        }
+        for item_id in touched {
+            if !item_ids.contains(&item_id) {
+                item_ids.push(item_id);
+            }
+        }
+    }
+
+    (StatusCode::OK, Json(UndoResponse { item_ids })).into_response()
+}
+
+async fn stage_embedding(conn: &mut PgConnection, item_id: Uuid) -> anyhow::Result<()> {
+    Outbox::enqueue(
+        conn,
+        EMBED_CONTENT_JOB_KIND,
+        serde_json::to_value(EmbedContentPayload { item_id })?,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
@@ -0,0 +1,21 @@
+pub mod dtos;
+pub mod handlers;
+
+use axum::{
+    http::{HeaderName, HeaderValue},
+    response::Response,
+};
+
+use crate::repositories::UndoAction;
+
+/// Response header carrying the token that undoes the request's change
+/// through `POST /v1/undo/{token}`
+pub const UNDO_TOKEN_HEADER: HeaderName = HeaderName::from_static("x-undo-token");
+
+/// Hand the client the token for undoing the change `response` reports
+pub fn with_undo_token(mut response: Response, action: &UndoAction) -> Response {
+    let token = HeaderValue::from_str(&action.id.to_string())
+        .expect("a UUID is always a valid header value");
+    response.headers_mut().insert(UNDO_TOKEN_HEADER, token);
+    response
+}
@@ -0,0 +1,90 @@
+//! Canonical form of saved URLs.
+//!
+//! The same article reached through a share button, a newsletter or a
+//! bookmark usually differs only in tracking parameters, fragments or case.
+//! Items are saved under the canonical URL and the fetch cache, shared
+//! documents and link graph are keyed on it, so every copy maps to one page.
+//!
+//! Canonicalizing is idempotent, and a canonical URL never carries a
+//! fragment or a tracking parameter; `tests/properties.rs` checks both over
+//! generated URLs.
+
+use url::Url;
+
+/// Query parameters that only carry tracking state and never change the page,
+/// besides any starting with `utm_`
+pub const TRACKING_PARAMS: [&str; 5] = ["fbclid", "gclid", "mc_cid", "mc_eid", "igshid"];
+
+/// The canonical form of `url`: lowercase scheme and host, no default port,
+/// fragment or tracking parameters, and remaining query parameters in
+/// sorted order. Returns None for URLs that don't parse.
+pub fn canonicalize(url: &str) -> Option<String> {
+    let mut url = Url::parse(url).ok()?;
+    url.set_fragment(None);
+
+    let mut params: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| !is_tracking_param(name))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    params.sort();
+
+    if params.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(params);
+    }
+
+    // Url already lowercases the scheme and host and drops default ports
+    Some(url.to_string())
+}
+
+/// Whether `canonicalize` drops the query parameter called `name`
+pub fn is_tracking_param(name: &str) -> bool {
+    name.starts_with("utm_") || TRACKING_PARAMS.contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonicalize_normalizes_equivalent_urls() {
+        let expected = canonicalize("https://example.com/post?a=1&b=2");
+        assert!(expected.is_some());
+        assert_eq!(
+            canonicalize("HTTPS://Example.COM:443/post?b=2&utm_source=x&a=1#comments"),
+            expected
+        );
+        assert_eq!(
+            canonicalize("https://example.com/post?a=1&fbclid=abc&b=2"),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_keeps_meaningful_differences() {
+        assert_ne!(
+            canonicalize("https://example.com/post?id=1"),
+            canonicalize("https://example.com/post?id=2")
+        );
+        assert_ne!(
+            canonicalize("https://example.com/Post"),
+            canonicalize("https://example.com/post")
+        );
+        // Some sites route on `ref`, so it isn't treated as tracking
+        assert_ne!(
+            canonicalize("https://example.com/compare?ref=main"),
+            canonicalize("https://example.com/compare")
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_drops_empty_query() {
+        assert_eq!(
+            canonicalize("https://example.com/post?utm_medium=social").as_deref(),
+            Some("https://example.com/post")
+        );
+        assert_eq!(canonicalize("not a url"), None);
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::entities::{UserPreferences, UserProfile};
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateProfileRequest {
+    pub display_name: Option<String>,
+    /// Only the preference fields present are changed
+    pub preferences: Option<UserPreferences>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserProfileResponse {
+    pub id: Uuid,
+    pub email: String,
+    pub display_name: Option<String>,
+    pub preferences: UserPreferences,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<UserProfile> for UserProfileResponse {
+    fn from(profile: UserProfile) -> Self {
+        Self {
+            id: profile.id,
+            email: profile.email,
+            display_name: profile.display_name,
+            preferences: profile.prefs.0,
+            created_at: profile.created_at,
+        }
+    }
+}
+
+impl UpdateProfileRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(name) = &self.display_name {
+            if name.trim().is_empty() {
+                return Err("display_name cannot be empty".to_string());
+            }
+            if name.chars().count() > 100 {
+                return Err("display_name too long".to_string());
+            }
+        }
+        if let Some(preferences) = &self.preferences {
+            preferences.validate()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_profile_request_valid() {
+        let request = UpdateProfileRequest {
+            display_name: Some("Alice".to_string()),
+            preferences: Some(UserPreferences {
+                reading_font_size: Some(18),
+                ..Default::default()
+            }),
+        };
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn test_update_profile_request_blank_name() {
+        let request = UpdateProfileRequest {
+            display_name: Some("   ".to_string()),
+            preferences: None,
+        };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_update_profile_request_invalid_preferences() {
+        let request = UpdateProfileRequest {
+            display_name: None,
+            preferences: Some(UserPreferences {
+                locale: Some("???".to_string()),
+                ..Default::default()
+            }),
+        };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_update_profile_request_invalid_translate_to() {
+        let request = UpdateProfileRequest {
+            display_name: None,
+            preferences: Some(UserPreferences {
+                translate_to: Some("German".to_string()),
+                ..Default::default()
+            }),
+        };
+        assert!(request.validate().is_err());
+    }
+}
@@ -0,0 +1,292 @@
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+
+use crate::{
+    app_state::AppState,
+    auth::{dtos::ErrorResponse, middleware::AuthenticatedUser},
+    repositories::DocumentRepository,
+    scheduling::schedule_next_digest,
+    users::dtos::{UpdateProfileRequest, UserProfileResponse},
+};
+
+#[utoipa::path(
+    get,
+    path = "/v1/users/me",
+    tag = "users",
+    responses(
+        (status = 200, description = "Profile retrieved successfully", body = UserProfileResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_me(auth_user: AuthenticatedUser, State(state): State<AppState>) -> Response {
+    match state.user_repo.find_profile(auth_user.user_id).await {
+        Ok(Some(profile)) => {
+            (StatusCode::OK, Json(UserProfileResponse::from(profile))).into_response()
+        }
+        Ok(None) => user_not_found(),
+        Err(_) => database_error(),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/v1/users/me",
+    tag = "users",
+    request_body = UpdateProfileRequest,
+    responses(
+        (status = 200, description = "Profile updated successfully", body = UserProfileResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_me(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<UpdateProfileRequest>,
+) -> Response {
+    if let Err(error) = payload.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+    }
+
+    // Absent preference fields are skipped during serialization, so the patch
+    // only overwrites what the client sent
+    let preferences = payload.preferences.unwrap_or_default();
+    let schedule_changed = preferences.digest_schedule.is_some();
+    let timezone_changed = preferences.timezone.is_some();
+    let stop_sharing = preferences.share_content == Some(false);
+
+    let prefs_patch = match serde_json::to_value(preferences) {
+        Ok(patch) => patch,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Failed to encode preferences".to_string(),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    match state
+        .user_repo
+        .update_profile(
+            auth_user.user_id,
+            payload.display_name.map(|name| name.trim().to_string()),
+            prefs_patch,
+        )
+        .await
+    {
+        Ok(Some(profile)) => {
+            // Digest send times depend on both the schedule and the timezone;
+            // without a schedule a new timezone has nothing to move
+            let reschedule_digest =
+                schedule_changed || (timezone_changed && profile.prefs.0.digest_schedule.is_some());
+            if reschedule_digest
+                && schedule_next_digest(&state.db_pool, profile.id, &profile.prefs.0, Utc::now())
+                    .await
+                    .is_err()
+            {
+                return database_error();
+            }
+            // Opting out takes the user's existing items out of the shared store too
+            if stop_sharing
+                && DocumentRepository::new(&state.db_pool)
+                    .unshare_user(profile.id)
+                    .await
+                    .is_err()
+            {
+                return database_error();
+            }
+            (StatusCode::OK, Json(UserProfileResponse::from(profile))).into_response()
+        }
+        Ok(None) => user_not_found(),
+        Err(_) => database_error(),
+    }
+}
+
+fn user_not_found() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse {
+            error: "User not found".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+fn database_error() -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: "Database error".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        auth::jwt::JwtService,
+        config::Config,
+        entities::{UserPreferences, UserProfile},
+        repositories::{item::MockItemRepositoryTrait, user::MockUserRepositoryTrait},
+    };
+    use axum::{
+        Router,
+        body::{Body, to_bytes},
+        http::{Request, header::AUTHORIZATION},
+        routing::get,
+    };
+    use mockall::predicate::eq;
+    use serde_json::{Value, json};
+    use sqlx::{Pool, Postgres, types::Json as SqlxJson};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    fn create_test_pool() -> Pool<Postgres> {
+        // Create a dummy pool for testing - won't actually be used
+        Pool::<Postgres>::connect_lazy("postgresql://dummy").expect("Failed to create test pool")
+    }
+
+    fn create_test_app(mock_repo: MockUserRepositoryTrait) -> Router {
+        let state = AppState {
+            user_repo: Arc::new(mock_repo),
+            item_repo: Arc::new(MockItemRepositoryTrait::new()),
+            db_pool: create_test_pool(),
+            embedder: None,
+        };
+
+        Router::new()
+            .route("/users/me", get(get_me).patch(update_me))
+            .with_state(state)
+    }
+
+    fn create_jwt_token(user_id: Uuid) -> String {
+        let config = Config::from_env().expect("Failed to load config");
+        let jwt_service = JwtService::new(config.jwt_secret());
+        jwt_service
+            .generate_token(user_id)
+            .expect("Failed to generate token")
+    }
+
+    fn profile(user_id: Uuid, prefs: UserPreferences) -> UserProfile {
+        UserProfile {
+            id: user_id,
+            email: "alice@example.com".to_string(),
+            display_name: Some("Alice".to_string()),
+            prefs: SqlxJson(prefs),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_me_returns_profile() {
+        let user_id = Uuid::new_v4();
+        let mut mock_repo = MockUserRepositoryTrait::new();
+        mock_repo
+            .expect_find_profile()
+            .with(eq(user_id))
+            .returning(move |id| Ok(Some(profile(id, UserPreferences::default()))));
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("/users/me")
+            .header(
+                AUTHORIZATION,
+                format!("Bearer {}", create_jwt_token(user_id)),
+            )
+            .body(Body::empty())
+            .unwrap();
+
+        let response = create_test_app(mock_repo).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["email"], "alice@example.com");
+        assert_eq!(json["preferences"], json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_update_me_sends_only_present_preferences() {
+        let user_id = Uuid::new_v4();
+        let mut mock_repo = MockUserRepositoryTrait::new();
+        mock_repo
+            .expect_update_profile()
+            .with(
+                eq(user_id),
+                eq(None::<String>),
+                eq(json!({"timezone": "Europe/Berlin"})),
+            )
+            .returning(|id, _, _| {
+                Ok(Some(profile(
+                    id,
+                    UserPreferences {
+                        timezone: Some("Europe/Berlin".to_string()),
+                        ..Default::default()
+                    },
+                )))
+            });
+
+        let request = Request::builder()
+            .method("PATCH")
+            .uri("/users/me")
+            .header(
+                AUTHORIZATION,
+                format!("Bearer {}", create_jwt_token(user_id)),
+            )
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"preferences": {"timezone": "Europe/Berlin"}}).to_string(),
+            ))
+            .unwrap();
+
+        let response = create_test_app(mock_repo).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["preferences"], json!({"timezone": "Europe/Berlin"}));
+    }
+
+    #[tokio::test]
+    async fn test_update_me_rejects_invalid_preferences() {
+        let user_id = Uuid::new_v4();
+        let mock_repo = MockUserRepositoryTrait::new();
+
+        let request = Request::builder()
+            .method("PATCH")
+            .uri("/users/me")
+            .header(
+                AUTHORIZATION,
+                format!("Bearer {}", create_jwt_token(user_id)),
+            )
+            .header("content-type", "application/json")
+            .body(Body::from(
+                json!({"preferences": {"reading_font_size": 100}}).to_string(),
+            ))
+            .unwrap();
+
+        let response = create_test_app(mock_repo).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,143 @@
+mod helpers;
+
+use capsule::{
+    backup,
+    repositories::{BackupRepository, ContentRepository},
+};
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+use std::{fs::File, io::BufReader, path::PathBuf};
+use uuid::Uuid;
+
+use helpers::{create_user_with_token, insert_item};
+
+fn backup_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("capsule-backup-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+async fn item_titles(pool: &Pool<Postgres>) -> Vec<(Uuid, Option<String>)> {
+    sqlx::query_as("SELECT id, title FROM items ORDER BY created_at, id")
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn test_full_and_incremental_backups_restore(pool: Pool<Postgres>) {
+    let dir = backup_dir();
+    let (user_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    let kept = insert_item(&pool, user_id, "https://example.com/kept").await;
+    let deleted = insert_item(&pool, user_id, "https://example.com/deleted").await;
+    ContentRepository::new(&pool)
+        .upsert_content(
+            kept,
+            "<p>Hello there</p>",
+            "Hello there",
+            Some("en"),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+
+    let full = backup::create(&pool, &dir, false).await.unwrap();
+    assert!(full.since.is_none());
+    assert!(full.previous_checksum.is_none());
+
+    sqlx::query("UPDATE items SET title = 'Renamed' WHERE id = $1")
+        .bind(kept)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM items WHERE id = $1")
+        .bind(deleted)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let added = insert_item(&pool, user_id, "https://example.com/added").await;
+
+    let incremental = backup::create(&pool, &dir, false).await.unwrap();
+    assert_eq!(incremental.previous_checksum, Some(full.checksum.clone()));
+    assert!(incremental.since.is_some());
+
+    let expected = item_titles(&pool).await;
+    assert_eq!(expected.len(), 2);
+
+    // Start over from an empty database
+    sqlx::query("DELETE FROM users")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert!(item_titles(&pool).await.is_empty());
+
+    let paths = [dir.join(&full.file_name), dir.join(&incremental.file_name)];
+    backup::restore(&pool, &paths).await.unwrap();
+
+    assert_eq!(item_titles(&pool).await, expected);
+    let ids: Vec<Uuid> = expected.iter().map(|(id, _)| *id).collect();
+    assert!(ids.contains(&added));
+    assert!(!ids.contains(&deleted));
+
+    let content = ContentRepository::new(&pool)
+        .get_content(kept)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(content.clean_html.as_deref(), Some("<p>Hello there</p>"));
+    // Generated columns aren't restored but computed again
+    let word_count: Option<i32> =
+        sqlx::query_scalar("SELECT word_count FROM contents WHERE item_id = $1")
+            .bind(kept)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(word_count, Some(2));
+    let domain: Option<String> = sqlx::query_scalar("SELECT domain FROM items WHERE id = $1")
+        .bind(kept)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(domain.as_deref(), Some("example.com"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[sqlx::test]
+async fn test_backups_out_of_order_are_refused(pool: Pool<Postgres>) {
+    let dir = backup_dir();
+    let (user_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    insert_item(&pool, user_id, "https://example.com/one").await;
+
+    let full = backup::create(&pool, &dir, false).await.unwrap();
+    let incremental = backup::create(&pool, &dir, false).await.unwrap();
+
+    let paths = [dir.join(&incremental.file_name), dir.join(&full.file_name)];
+    assert!(backup::restore(&pool, &paths).await.is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[sqlx::test]
+async fn test_backup_files_verify_and_are_recorded(pool: Pool<Postgres>) {
+    let dir = backup_dir();
+    let (user_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    insert_item(&pool, user_id, "https://example.com/one").await;
+
+    let first = backup::create(&pool, &dir, false).await.unwrap();
+    let second = backup::create(&pool, &dir, true).await.unwrap();
+    assert!(second.previous_checksum.is_none());
+
+    let file = File::open(dir.join(&first.file_name)).unwrap();
+    let (header, trailer) = backup::verify(BufReader::new(file)).unwrap();
+    assert!(header.previous.is_none());
+    assert_eq!(trailer.sha256, first.checksum);
+    assert_eq!(trailer.counts["items"], 1);
+    assert_eq!(trailer.counts["users"], 1);
+
+    let listed = BackupRepository::new(&pool).list().await.unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0].checksum, second.checksum);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
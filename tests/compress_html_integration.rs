@@ -0,0 +1,145 @@
+mod helpers;
+
+use capsule::{
+    fetcher::Deadline,
+    jobs::{CompressHtmlJobHandler, JobHandler},
+    repositories::{ContentFields, ContentRepository, DocumentRepository, url_hash},
+};
+use chrono::Utc;
+use reqwest::header::{CONTENT_LANGUAGE, HeaderMap, SET_COOKIE};
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+use tracing::Span;
+use uuid::Uuid;
+
+use helpers::{create_user_with_token, insert_item};
+
+/// Store content the way it was before compression: in the plain columns
+async fn insert_uncompressed(pool: &Pool<Postgres>, item_id: Uuid, raw: &str, clean: &str) {
+    sqlx::query(
+        r#"
+        INSERT INTO contents (item_id, raw_html, clean_html, clean_text, lang)
+        VALUES ($1, $2, $3, 'text', 'en')
+        "#,
+    )
+    .bind(item_id)
+    .bind(raw)
+    .bind(clean)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+/// (plain, compressed) column counts across contents and documents
+async fn stored_columns(pool: &Pool<Postgres>) -> (i64, i64) {
+    sqlx::query_as(
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM contents WHERE raw_html IS NOT NULL OR clean_html IS NOT NULL)
+          + (SELECT COUNT(*) FROM documents WHERE raw_html IS NOT NULL OR clean_html IS NOT NULL),
+            (SELECT COUNT(*) FROM contents WHERE raw_html_zst IS NOT NULL OR clean_html_zst IS NOT NULL)
+          + (SELECT COUNT(*) FROM documents WHERE raw_html_zst IS NOT NULL OR clean_html_zst IS NOT NULL)
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+async fn test_content_is_stored_compressed(pool: Pool<Postgres>) {
+    let (user_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://example.com/post").await;
+    let html = "<article><p>Hello there</p></article>".repeat(200);
+
+    let repo = ContentRepository::new(&pool);
+    repo.upsert_raw(item_id, &html, "raw", &HeaderMap::new())
+        .await
+        .unwrap();
+    repo.upsert_content(item_id, &html, "Hello there", Some("en"), Utc::now())
+        .await
+        .unwrap();
+
+    let (raw_bytes, clean_bytes): (i32, i32) = sqlx::query_as(
+        "SELECT octet_length(raw_html_zst), octet_length(clean_html_zst) FROM contents WHERE item_id = $1",
+    )
+    .bind(item_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!((raw_bytes as usize) < html.len() / 5);
+    assert!((clean_bytes as usize) < html.len() / 5);
+    assert_eq!(stored_columns(&pool).await, (0, 1));
+
+    let content = repo.get_content(item_id).await.unwrap().unwrap();
+    assert_eq!(content.raw_html.as_deref(), Some(html.as_str()));
+    assert_eq!(content.clean_html.as_deref(), Some(html.as_str()));
+}
+
+#[sqlx::test]
+async fn test_backfill_compresses_old_rows(pool: Pool<Postgres>) {
+    let (user_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    let own = insert_item(&pool, user_id, "https://example.com/own").await;
+    let shared = insert_item(&pool, user_id, "https://example.com/shared").await;
+    insert_uncompressed(&pool, own, "<html>own</html>", "<p>own</p>").await;
+    insert_uncompressed(&pool, shared, "<html>shared</html>", "<p>shared</p>").await;
+    let hash = url_hash("https://example.com/shared").unwrap();
+    assert!(
+        DocumentRepository::new(&pool)
+            .share(shared, &hash)
+            .await
+            .unwrap()
+    );
+    assert_eq!(stored_columns(&pool).await, (2, 0));
+
+    CompressHtmlJobHandler::new()
+        .run(json!({}), &pool, Span::none(), Deadline::none())
+        .await
+        .unwrap();
+    assert_eq!(stored_columns(&pool).await, (0, 2));
+
+    // Reads are unchanged, from the item's own copy and the shared document
+    let contents = ContentRepository::new(&pool)
+        .get_clean_contents_for_user(user_id, &[own, shared], ContentFields::ALL)
+        .await
+        .unwrap();
+    let html = |item_id| {
+        contents
+            .iter()
+            .find(|content| content.item_id == item_id)
+            .and_then(|content| content.clean_html.clone())
+    };
+    assert_eq!(html(own).as_deref(), Some("<p>own</p>"));
+    assert_eq!(html(shared).as_deref(), Some("<p>shared</p>"));
+
+    // Unsharing hands the compressed copy back to the item
+    DocumentRepository::new(&pool)
+        .unshare_user(user_id)
+        .await
+        .unwrap();
+    let content = ContentRepository::new(&pool)
+        .get_content(shared)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(content.raw_html.as_deref(), Some("<html>shared</html>"));
+}
+
+#[sqlx::test]
+async fn test_raw_content_keeps_persisted_headers(pool: Pool<Postgres>) {
+    let (user_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://example.com/post").await;
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_LANGUAGE, "de".parse().unwrap());
+    headers.insert(SET_COOKIE, "session=1".parse().unwrap());
+
+    let repo = ContentRepository::new(&pool);
+    repo.upsert_raw(item_id, "<p>Hallo</p>", "raw", &headers)
+        .await
+        .unwrap();
+
+    let content = repo.get_content(item_id).await.unwrap().unwrap();
+    let response_headers = content.response_headers.unwrap();
+    assert_eq!(response_headers.len(), 1);
+    assert_eq!(response_headers["content-language"], "de");
+}
@@ -0,0 +1,247 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::{
+    fetcher::Deadline,
+    jobs::{CONTENT_CHANGED_EVENT, JobHandler, MIN_REFRESH_INTERVAL_SECS, RefreshItemJobHandler},
+};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use tracing::Span;
+use uuid::Uuid;
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, path},
+};
+
+async fn mount_page(server: &MockServer) {
+    let paragraphs =
+        "<p>The pricing page lists every plan with its monthly price and the limits that apply.</p>"
+            .repeat(40);
+    Mock::given(method("GET"))
+        .and(path("/pricing"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(format!(
+                    "<html><head><title>Pricing</title></head>\
+                     <body><article><h1>Pricing</h1>{}</article></body></html>",
+                    paragraphs
+                ))
+                .insert_header("Content-Type", "text/html; charset=utf-8"),
+        )
+        .mount(server)
+        .await;
+}
+
+async fn set_refresh_policy(
+    app: &Router,
+    token: &str,
+    item_id: Uuid,
+    policy: Value,
+) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("PUT")
+        .uri(format!("/v1/items/{}/refresh-policy", item_id))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(policy.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+async fn refresh(pool: &Pool<Postgres>, item_id: Uuid) {
+    refresh_with(
+        RefreshItemJobHandler::new(Default::default(), false, false),
+        pool,
+        item_id,
+    )
+    .await;
+}
+
+async fn refresh_with(handler: RefreshItemJobHandler, pool: &Pool<Postgres>, item_id: Uuid) {
+    handler
+        .run(
+            json!({ "item_id": item_id }),
+            pool,
+            Span::none(),
+            Deadline::none(),
+        )
+        .await
+        .unwrap();
+}
+
+async fn change_notifications(pool: &Pool<Postgres>, user_id: Uuid) -> Vec<Value> {
+    sqlx::query_scalar("SELECT payload FROM notifications WHERE user_id = $1 AND kind = $2")
+        .bind(user_id)
+        .bind(CONTENT_CHANGED_EVENT)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+async fn is_nsfw(pool: &Pool<Postgres>, item_id: Uuid) -> bool {
+    sqlx::query_scalar("SELECT nsfw FROM items WHERE id = $1")
+        .bind(item_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn test_watched_item_notifies_on_material_change(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let server = helpers::start_mock_server().await;
+    mount_page(&server).await;
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, &format!("{}/pricing", server.uri())).await;
+    helpers::insert_content(&pool, item_id, "Everything used to be free.", "en").await;
+
+    let (status, body) = set_refresh_policy(
+        &app,
+        &token,
+        item_id,
+        json!({ "interval_secs": MIN_REFRESH_INTERVAL_SECS, "watch": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["watch"], true);
+
+    refresh(&pool, item_id).await;
+
+    let notifications = change_notifications(&pool, user_id).await;
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0]["item_id"], item_id.to_string());
+    assert_eq!(
+        notifications[0]["removed"],
+        json!(["Everything used to be free."])
+    );
+    assert!(notifications[0]["change_ratio"].as_f64().unwrap() > 0.5);
+    assert!(
+        notifications[0]["added"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|section| section.as_str().unwrap().contains("pricing page"))
+    );
+
+    // Refetching the same page again changes nothing worth telling
+    refresh(&pool, item_id).await;
+    assert_eq!(change_notifications(&pool, user_id).await.len(), 1);
+}
+
+#[sqlx::test]
+async fn test_unwatched_item_refreshes_silently(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let server = helpers::start_mock_server().await;
+    mount_page(&server).await;
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, &format!("{}/pricing", server.uri())).await;
+    helpers::insert_content(&pool, item_id, "Everything used to be free.", "en").await;
+
+    // Watching only makes sense for items that get refreshed
+    let (status, _) = set_refresh_policy(&app, &token, item_id, json!({ "watch": true })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = set_refresh_policy(
+        &app,
+        &token,
+        item_id,
+        json!({ "interval_secs": MIN_REFRESH_INTERVAL_SECS }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["watch"], false);
+
+    refresh(&pool, item_id).await;
+
+    assert!(change_notifications(&pool, user_id).await.is_empty());
+    let status: String = sqlx::query_scalar("SELECT status::text FROM items WHERE id = $1")
+        .bind(item_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "fetched");
+
+    // The item's word count and reading time follow the new text
+    let (word_count, reading_time): (Option<i32>, Option<i32>) =
+        sqlx::query_as("SELECT word_count, reading_time_minutes FROM items WHERE id = $1")
+            .bind(item_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(word_count.unwrap() > 500);
+    assert!(reading_time.unwrap() >= 3);
+}
+
+#[sqlx::test]
+async fn test_refresh_leaves_archived_items_archived(pool: Pool<Postgres>) {
+    let server = helpers::start_mock_server().await;
+    mount_page(&server).await;
+    let (user_id, _) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, &format!("{}/pricing", server.uri())).await;
+    helpers::insert_content(&pool, item_id, "Everything used to be free.", "en").await;
+    sqlx::query("UPDATE items SET status = 'archived' WHERE id = $1")
+        .bind(item_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    refresh(&pool, item_id).await;
+
+    let status: String = sqlx::query_scalar("SELECT status::text FROM items WHERE id = $1")
+        .bind(item_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "archived");
+}
+
+#[sqlx::test]
+async fn test_refresh_reclassifies_nsfw_when_enabled(pool: Pool<Postgres>) {
+    let server = helpers::start_mock_server().await;
+    let paragraphs =
+        "<p>The gallery has been updated with new photographs from the shoot.</p>".repeat(40);
+    Mock::given(method("GET"))
+        .and(path("/gallery"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(format!(
+                    "<html><head><title>Gallery</title><meta name=\"rating\" content=\"adult\"></head>\
+                     <body><article><h1>Gallery</h1>{}</article></body></html>",
+                    paragraphs
+                ))
+                .insert_header("Content-Type", "text/html; charset=utf-8"),
+        )
+        .mount(&server)
+        .await;
+    let (user_id, _) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, &format!("{}/gallery", server.uri())).await;
+    sqlx::query("UPDATE items SET refresh_interval_secs = $2 WHERE id = $1")
+        .bind(item_id)
+        .bind(MIN_REFRESH_INTERVAL_SECS as i32)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Flagging is opt-in
+    refresh(&pool, item_id).await;
+    assert!(!is_nsfw(&pool, item_id).await);
+
+    refresh_with(
+        RefreshItemJobHandler::new(Default::default(), false, true),
+        &pool,
+        item_id,
+    )
+    .await;
+    assert!(is_nsfw(&pool, item_id).await);
+}
@@ -0,0 +1,174 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{
+        Request, StatusCode,
+        header::{AUTHORIZATION, CONTENT_DISPOSITION},
+    },
+};
+use capsule::{
+    fetcher::Deadline,
+    jobs::{BUILD_DATA_PACKAGE_JOB_KIND, BuildDataPackageJobHandler, JobHandler},
+    repositories::ContentRepository,
+};
+use chrono::Utc;
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use tracing::Span;
+
+async fn send(
+    app: &Router,
+    token: &str,
+    method: &str,
+    uri: &str,
+    body: Value,
+) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// Run the package job the API staged, as the worker would
+async fn run_package_job(pool: &Pool<Postgres>) {
+    let payload: Value = sqlx::query_scalar("SELECT payload FROM job_outbox WHERE kind = $1")
+        .bind(BUILD_DATA_PACKAGE_JOB_KIND)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    BuildDataPackageJobHandler::new()
+        .run(payload, pool, Span::none(), Deadline::none())
+        .await
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn test_admin_builds_data_package(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, _) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (other_id, _) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    let (admin_id, admin) = helpers::create_user_with_token(&pool, "admin@example.com").await;
+    helpers::make_admin(&pool, admin_id).await;
+
+    let item_id = helpers::insert_item(&pool, user_id, "https://example.com/mine").await;
+    helpers::insert_item(&pool, other_id, "https://example.com/theirs").await;
+    ContentRepository::new(&pool)
+        .upsert_content(item_id, "<p>Mine</p>", "Mine", Some("en"), Utc::now())
+        .await
+        .unwrap();
+
+    let uri = format!("/v1/admin/users/{}/data-requests", user_id);
+    let (status, request) = send(
+        &app,
+        &admin,
+        "POST",
+        &uri,
+        json!({"reason": "Subject access request #42"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(request["state"], "pending");
+    assert_eq!(request["requested_by"], admin_id.to_string());
+    let id = request["id"].as_str().unwrap().to_string();
+
+    let package_uri = format!("/v1/admin/data-requests/{}/package", id);
+    let (status, _) = send(&app, &admin, "GET", &package_uri, Value::Null).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    run_package_job(&pool).await;
+
+    let (status, request) = send(
+        &app,
+        &admin,
+        "GET",
+        &format!("/v1/admin/data-requests/{}", id),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(request["state"], "succeeded");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&package_uri)
+                .header(AUTHORIZATION, format!("Bearer {}", admin))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        response.headers()[CONTENT_DISPOSITION]
+            .to_str()
+            .unwrap()
+            .starts_with("attachment")
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let package: Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(package["format"], "capsule-data-package");
+    assert_eq!(package["user_id"], user_id.to_string());
+    let tables = &package["tables"];
+    assert_eq!(tables["users"][0]["email"], "alice@example.com");
+    assert!(tables["users"][0].get("pw_hash").is_none());
+    // Only the user's own items, and content reached through them
+    assert_eq!(tables["items"].as_array().unwrap().len(), 1);
+    assert_eq!(tables["items"][0]["id"], item_id.to_string());
+    assert_eq!(tables["contents"][0]["clean_html"], "<p>Mine</p>");
+    assert!(tables.get("data_requests").is_none());
+
+    // The audit trail records who asked, and why
+    let (status, audit) = send(&app, &admin, "GET", &uri, Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(audit["requests"].as_array().unwrap().len(), 1);
+    assert_eq!(audit["requests"][0]["requested_by"], admin_id.to_string());
+    assert_eq!(audit["requests"][0]["reason"], "Subject access request #42");
+}
+
+#[sqlx::test]
+async fn test_data_requests_are_admin_only_and_need_a_reason(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (admin_id, admin) = helpers::create_user_with_token(&pool, "admin@example.com").await;
+    helpers::make_admin(&pool, admin_id).await;
+
+    let uri = format!("/v1/admin/users/{}/data-requests", user_id);
+    let (status, _) = send(&app, &token, "POST", &uri, json!({"reason": "curious"})).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(&app, &admin, "POST", &uri, json!({"reason": " "})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = send(
+        &app,
+        &admin,
+        "POST",
+        &format!("/v1/admin/users/{}/data-requests", uuid::Uuid::new_v4()),
+        json!({"reason": "SAR"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM data_requests")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
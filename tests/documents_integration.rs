@@ -0,0 +1,129 @@
+mod helpers;
+
+use axum::{
+    body::{Body, to_bytes},
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use capsule::repositories::{DocumentRepository, url_hash};
+use helpers::{create_user_with_token, insert_content, insert_item};
+
+async fn document_count(pool: &Pool<Postgres>) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM documents")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+/// Read an item's cleaned text back through the batch content endpoint
+async fn clean_text_for(pool: &Pool<Postgres>, token: &str, item_id: Uuid) -> Option<String> {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/items/content:batchGet")
+        .header(AUTHORIZATION, format!("Bearer {token}"))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "item_ids": [item_id] }).to_string()))
+        .unwrap();
+
+    let response = helpers::test_app(pool.clone())
+        .oneshot(request)
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+    json["contents"][0]["clean_text"]
+        .as_str()
+        .map(str::to_string)
+}
+
+#[sqlx::test]
+async fn test_items_for_the_same_url_share_one_document(pool: Pool<Postgres>) {
+    let (alice, alice_token) = create_user_with_token(&pool, "alice@example.com").await;
+    let (bob, bob_token) = create_user_with_token(&pool, "bob@example.com").await;
+    let hash = url_hash("https://example.com/post").unwrap();
+
+    let alice_item = insert_item(&pool, alice, "https://example.com/post?utm_source=rss").await;
+    let bob_item = insert_item(&pool, bob, "https://example.com/post").await;
+    insert_content(&pool, alice_item, "shared article text", "en").await;
+    insert_content(&pool, bob_item, "shared article text", "en").await;
+
+    let documents = DocumentRepository::new(&pool);
+    assert!(documents.share(alice_item, &hash).await.unwrap());
+    assert!(documents.share(bob_item, &hash).await.unwrap());
+    assert_eq!(document_count(&pool).await, 1);
+
+    // The per-item rows no longer hold the text, but reads still see it
+    let stored: Option<String> =
+        sqlx::query_scalar("SELECT clean_text FROM contents WHERE item_id = $1")
+            .bind(alice_item)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(stored, None);
+    assert_eq!(
+        clean_text_for(&pool, &alice_token, alice_item)
+            .await
+            .as_deref(),
+        Some("shared article text")
+    );
+    assert_eq!(
+        clean_text_for(&pool, &bob_token, bob_item).await.as_deref(),
+        Some("shared article text")
+    );
+}
+
+#[sqlx::test]
+async fn test_opted_out_user_keeps_private_content(pool: Pool<Postgres>) {
+    let (user_id, token) = create_user_with_token(&pool, "private@example.com").await;
+    sqlx::query("UPDATE users SET prefs = '{\"share_content\": false}' WHERE id = $1")
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let item_id = insert_item(&pool, user_id, "https://example.com/secret").await;
+    insert_content(&pool, item_id, "private text", "en").await;
+
+    let documents = DocumentRepository::new(&pool);
+    assert!(!documents.sharing_enabled(user_id).await.unwrap());
+    let hash = url_hash("https://example.com/secret").unwrap();
+    assert!(!documents.share(item_id, &hash).await.unwrap());
+
+    assert_eq!(document_count(&pool).await, 0);
+    assert_eq!(
+        clean_text_for(&pool, &token, item_id).await.as_deref(),
+        Some("private text")
+    );
+}
+
+#[sqlx::test]
+async fn test_unshare_user_restores_private_copies(pool: Pool<Postgres>) {
+    let (user_id, _) = create_user_with_token(&pool, "leaver@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://example.com/post").await;
+    insert_content(&pool, item_id, "article text", "en").await;
+
+    let documents = DocumentRepository::new(&pool);
+    let hash = url_hash("https://example.com/post").unwrap();
+    assert!(documents.share(item_id, &hash).await.unwrap());
+
+    assert_eq!(documents.unshare_user(user_id).await.unwrap(), 1);
+
+    let (document_id, clean_text): (Option<Uuid>, Option<String>) = sqlx::query_as(
+        "SELECT i.document_id, c.clean_text FROM items i JOIN contents c ON c.item_id = i.id WHERE i.id = $1",
+    )
+    .bind(item_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(document_id, None);
+    assert_eq!(clean_text.as_deref(), Some("article text"));
+
+    // Nobody else referenced the document, so it's gone
+    assert_eq!(document_count(&pool).await, 0);
+}
@@ -0,0 +1,53 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+
+async fn put_pref(app: &Router, token: &str, domain: &str, body: Value) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("PUT")
+        .uri(format!("/v1/domain-prefs/{}", domain))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[sqlx::test]
+async fn test_headless_render_is_refused_without_a_renderer(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+
+    let (status, body) = put_pref(
+        &app,
+        &token,
+        "example.com",
+        json!({ "headless_render": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+    assert!(body["error"].as_str().unwrap().contains("not available"));
+
+    let (status, body) = put_pref(
+        &app,
+        &token,
+        "example.com",
+        json!({ "skip_images": true, "default_tag": "news" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["headless_render"], false);
+    assert_eq!(body["skip_images"], true);
+}
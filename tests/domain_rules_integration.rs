@@ -0,0 +1,141 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::{
+    fetcher::Deadline,
+    jobs::{IMPORT_URLS_JOB_KIND, ImportUrlsJobHandler, JobHandler},
+    repositories::{DomainRulesRepository, ImportRepository},
+};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use tracing::Span;
+use uuid::Uuid;
+
+async fn put_rule(app: &Router, token: &str, domain: &str, body: Value) -> StatusCode {
+    let request = Request::builder()
+        .method("PUT")
+        .uri(format!("/v1/admin/domain-rules/{}", domain))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    app.clone().oneshot(request).await.unwrap().status()
+}
+
+#[sqlx::test]
+async fn test_domain_rules_require_admin(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+
+    let status = put_rule(&app, &token, "evil.example", json!({"action": "block"})).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/admin/domain-rules")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test]
+async fn test_most_specific_rule_decides(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (admin_id, token) = helpers::create_user_with_token(&pool, "admin@example.com").await;
+    helpers::make_admin(&pool, admin_id).await;
+
+    let status = put_rule(
+        &app,
+        &token,
+        "WWW.Evil.Example",
+        json!({"action": "block", "reason": "malware"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let status = put_rule(
+        &app,
+        &token,
+        "docs.evil.example",
+        json!({"action": "allow"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let rules = DomainRulesRepository::new(&pool);
+    assert_eq!(
+        rules.blocked_reason("cdn.evil.example").await.unwrap(),
+        Some("domain evil.example is blocked: malware".to_string())
+    );
+    assert_eq!(
+        rules.blocked_reason("docs.evil.example").await.unwrap(),
+        None
+    );
+
+    // With an allow rule in place, domains no rule covers are refused
+    assert_eq!(
+        rules.blocked_reason("www.other.example").await.unwrap(),
+        Some("domain other.example is not on the allowlist".to_string())
+    );
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri("/v1/admin/domain-rules/docs.evil.example")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(rules.blocked_reason("other.example").await.unwrap(), None);
+}
+
+#[sqlx::test]
+async fn test_import_refuses_blocked_domains(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (admin_id, token) = helpers::create_user_with_token(&pool, "admin@example.com").await;
+    helpers::make_admin(&pool, admin_id).await;
+    let status = put_rule(&app, &token, "evil.example", json!({"action": "block"})).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/imports")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"urls": ["https://good.example/a", "https://www.evil.example/b"]}).to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let operation: Value = serde_json::from_slice(&body).unwrap();
+    let id: Uuid = operation["id"].as_str().unwrap().parse().unwrap();
+
+    let payload: Value = sqlx::query_scalar("SELECT payload FROM job_outbox WHERE kind = $1")
+        .bind(IMPORT_URLS_JOB_KIND)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    ImportUrlsJobHandler::new()
+        .run(payload, &pool, Span::none(), Deadline::none())
+        .await
+        .unwrap();
+
+    let failures = ImportRepository::new(&pool).failures(id).await.unwrap();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].row_number, 2);
+    assert_eq!(failures[0].reason.as_str(), "blocked_domain");
+    assert_eq!(
+        failures[0].detail.as_deref(),
+        Some("domain evil.example is blocked")
+    );
+}
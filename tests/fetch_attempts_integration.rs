@@ -0,0 +1,149 @@
+mod helpers;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::{
+    fetcher::Deadline,
+    jobs::{FetchPageConfig, FetchPageJobHandler, JobHandler},
+};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use tracing::Span;
+use uuid::Uuid;
+use wiremock::{
+    Mock, ResponseTemplate,
+    matchers::{method, path},
+};
+
+async fn run_fetch_job(pool: &Pool<Postgres>, item_id: Uuid) -> anyhow::Result<()> {
+    FetchPageJobHandler::with_config(FetchPageConfig {
+        cache_ttl_secs: 0,
+        ..Default::default()
+    })
+    .run(
+        json!({ "item_id": item_id }),
+        pool,
+        Span::none(),
+        Deadline::none(),
+    )
+    .await
+}
+
+#[sqlx::test]
+async fn test_fetch_attempts_are_listed_with_item_events(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let server = helpers::start_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/post"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/post"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(
+                    "<html><head><title>Post</title></head><body><p>Hi</p></body></html>",
+                )
+                .insert_header("Content-Type", "text/html; charset=utf-8"),
+        )
+        .mount(&server)
+        .await;
+
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let url = format!("{}/post", server.uri());
+    let item_id = helpers::insert_item(&pool, user_id, &url).await;
+
+    assert!(run_fetch_job(&pool, item_id).await.is_err());
+    run_fetch_job(&pool, item_id).await.unwrap();
+
+    let request = Request::builder()
+        .uri(format!("/v1/items/{}/events", item_id))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let timeline: Value = serde_json::from_slice(&body).unwrap();
+
+    let attempts = timeline["fetch_attempts"].as_array().unwrap();
+    assert_eq!(attempts.len(), 2);
+
+    assert_eq!(attempts[0]["status"], 503);
+    assert_eq!(attempts[0]["error_class"], "http");
+    assert_eq!(attempts[0]["final_url"], Value::Null);
+    assert_eq!(attempts[0]["bytes"], Value::Null);
+
+    assert_eq!(attempts[1]["status"], 200);
+    assert_eq!(attempts[1]["error_class"], Value::Null);
+    assert_eq!(attempts[1]["final_url"], url.as_str());
+    assert!(attempts[1]["bytes"].as_i64().unwrap() > 0);
+    assert!(attempts[1]["duration_ms"].as_i64().unwrap() >= 0);
+
+    // Phase timings are kept with the attempt; only a body gets downloaded
+    assert!(attempts[0]["ttfb_ms"].as_i64().is_some());
+    assert_eq!(attempts[0]["download_ms"], Value::Null);
+    assert!(attempts[1]["ttfb_ms"].as_i64().is_some());
+    assert!(attempts[1]["download_ms"].as_i64().is_some());
+}
+
+#[sqlx::test]
+async fn test_permanent_fetch_failure_marks_the_item_failed(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let server = helpers::start_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/gone"))
+        .respond_with(ResponseTemplate::new(404))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/gone"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(
+                    "<html><head><title>Back</title></head><body><p>Hi</p></body></html>",
+                )
+                .insert_header("Content-Type", "text/html; charset=utf-8"),
+        )
+        .mount(&server)
+        .await;
+
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, &format!("{}/gone", server.uri())).await;
+    let get_item = || {
+        let app = app.clone();
+        let token = token.clone();
+        async move {
+            let request = Request::builder()
+                .uri(format!("/v1/items/{}", item_id))
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap();
+            let response = app.oneshot(request).await.unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<Value>(&body).unwrap()
+        }
+    };
+
+    assert!(run_fetch_job(&pool, item_id).await.is_err());
+    let item = get_item().await;
+    assert_eq!(item["status"], "failed");
+    assert_eq!(item["processing_state"], "failed_permanent");
+    assert!(item["last_error"].as_str().unwrap().contains("404"));
+
+    // A later fetch that works puts it right
+    run_fetch_job(&pool, item_id).await.unwrap();
+    let item = get_item().await;
+    assert_eq!(item["status"], "fetched");
+    assert_eq!(item["last_error"], Value::Null);
+}
@@ -0,0 +1,128 @@
+use bytes::Bytes;
+use chrono::{Duration, Utc};
+use reqwest::{
+    StatusCode,
+    header::{CONTENT_LANGUAGE, ETAG, HeaderMap},
+};
+use sqlx::{Pool, Postgres};
+
+use capsule::{
+    entities::ExtractionFailure,
+    extractor::ExtractedContent,
+    fetcher::{Charset, PageResponse},
+    repositories::FetchCacheRepository,
+    urlnorm::canonicalize,
+};
+
+fn page(url: &str, body: &str) -> PageResponse {
+    let mut headers = HeaderMap::new();
+    headers.insert(ETAG, "\"v1\"".parse().unwrap());
+    headers.insert(CONTENT_LANGUAGE, "en".parse().unwrap());
+    PageResponse {
+        url_final: url.parse().unwrap(),
+        status: StatusCode::OK,
+        headers,
+        body_raw: Bytes::from(body.to_string()),
+        body_utf8: body.to_string(),
+        charset: Charset::Utf8,
+        fetched_at: Utc::now(),
+    }
+}
+
+fn extracted(url: &str) -> ExtractedContent {
+    ExtractedContent {
+        url: url.parse().unwrap(),
+        title: "Cached article".to_string(),
+        site_name: Some("Example".to_string()),
+        byline: None,
+        language: Some("en".to_string()),
+        text: "Body text".to_string(),
+        html: "<p>Body text</p>".to_string(),
+        fetched_at: Utc::now(),
+        outline: Vec::new(),
+    }
+}
+
+#[sqlx::test]
+async fn test_fetch_cache_round_trip(pool: Pool<Postgres>) {
+    let url = "https://example.com/article?utm_source=feed";
+    let key = canonicalize(url).unwrap();
+    let response = page("https://example.com/article", "<html>hello</html>");
+    let cache = FetchCacheRepository::new(&pool);
+
+    cache
+        .put(
+            &key,
+            &response,
+            "abc123",
+            &Ok(extracted(url)),
+            Duration::minutes(10),
+        )
+        .await
+        .expect("Failed to cache fetch");
+
+    // The same page saved without tracking parameters hits the same entry
+    let other_key = canonicalize("https://example.com/article#intro").unwrap();
+    let cached = cache
+        .get_fresh(&other_key)
+        .await
+        .expect("Failed to read cache")
+        .expect("Expected a cached fetch");
+
+    assert_eq!(cached.checksum, "abc123");
+    assert_eq!(cached.http_etag.as_deref(), Some("\"v1\""));
+    let content = cached
+        .extraction
+        .0
+        .as_ref()
+        .expect("Expected extracted content");
+    assert_eq!(content.title, "Cached article");
+
+    let rebuilt = cached.page_response().expect("Failed to rebuild response");
+    assert_eq!(rebuilt.url_final.as_str(), "https://example.com/article");
+    assert_eq!(rebuilt.body_utf8, "<html>hello</html>");
+    assert_eq!(rebuilt.headers.get(ETAG).unwrap(), "\"v1\"");
+    assert_eq!(rebuilt.headers.get(CONTENT_LANGUAGE).unwrap(), "en");
+}
+
+#[sqlx::test]
+async fn test_fetch_cache_keeps_extraction_failures(pool: Pool<Postgres>) {
+    let key = canonicalize("https://example.com/app").unwrap();
+    let cache = FetchCacheRepository::new(&pool);
+
+    cache
+        .put(
+            &key,
+            &page("https://example.com/app", "<div id=\"root\"></div>"),
+            "def456",
+            &Err(ExtractionFailure::RenderRequired),
+            Duration::minutes(10),
+        )
+        .await
+        .expect("Failed to cache fetch");
+
+    let cached = cache.get_fresh(&key).await.unwrap().unwrap();
+    assert_eq!(
+        cached.extraction.0.unwrap_err(),
+        ExtractionFailure::RenderRequired
+    );
+}
+
+#[sqlx::test]
+async fn test_fetch_cache_ignores_expired_entries(pool: Pool<Postgres>) {
+    let key = canonicalize("https://example.com/old").unwrap();
+    let cache = FetchCacheRepository::new(&pool);
+
+    cache
+        .put(
+            &key,
+            &page("https://example.com/old", "<html>old</html>"),
+            "0ld",
+            &Err(ExtractionFailure::TooShort),
+            Duration::seconds(-1),
+        )
+        .await
+        .expect("Failed to cache fetch");
+
+    assert!(cache.get_fresh(&key).await.unwrap().is_none());
+}
@@ -0,0 +1,46 @@
+use capsule::fetcher::{
+    fetch,
+    identity::{
+        ENV_FETCHER_CONTACT_URL, ENV_FETCHER_FROM, ENV_FETCHER_INSTANCE, ENV_FETCHER_USER_AGENT,
+    },
+    url_policy::{ENV_URL_ALLOW_PRIVATE_ADDRESSES, ENV_URL_ALLOWED_PORTS},
+};
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{header, method, path},
+};
+
+// The identity is read once per process, so this binary configures it
+// before its only fetch
+#[tokio::test]
+async fn test_fetch_sends_configured_identity() {
+    unsafe {
+        std::env::set_var(ENV_URL_ALLOWED_PORTS, "*");
+        std::env::set_var(ENV_URL_ALLOW_PRIVATE_ADDRESSES, "true");
+        std::env::set_var(ENV_FETCHER_USER_AGENT, "ReadLaterBot/2.0");
+        std::env::set_var(ENV_FETCHER_CONTACT_URL, "https://reader.example.org/bot");
+        std::env::set_var(ENV_FETCHER_FROM, "ops@example.org");
+        std::env::set_var(ENV_FETCHER_INSTANCE, "eu-1");
+    }
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .and(header(
+            "user-agent",
+            "ReadLaterBot/2.0 (+https://reader.example.org/bot)",
+        ))
+        .and(header("from", "ops@example.org"))
+        .and(header("x-capsule-instance", "eu-1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes("<html><body>Hello</body></html>")
+                .insert_header("Content-Type", "text/html; charset=utf-8"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let page = fetch(&format!("{}/page", server.uri())).await.unwrap();
+    assert_eq!(page.status.as_u16(), 200);
+}
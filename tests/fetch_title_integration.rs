@@ -0,0 +1,138 @@
+mod helpers;
+
+use capsule::{
+    entities::DomainRuleAction,
+    fetcher::Deadline,
+    jobs::{
+        FETCH_PAGE_JOB_KIND, FETCH_TITLE_JOB_KIND, FetchTitleJobHandler, JobHandler,
+        stage_fetch_jobs,
+    },
+    repositories::DomainRulesRepository,
+};
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+use tracing::Span;
+use uuid::Uuid;
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, path},
+};
+
+async fn mount_page(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/post"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(
+                    r#"<html><head>
+                    <title>Post | Example</title>
+                    <meta property="og:title" content="A Post">
+                    <meta property="og:site_name" content="Example">
+                    </head><body><p>Hello</p></body></html>"#,
+                )
+                .insert_header("Content-Type", "text/html; charset=utf-8"),
+        )
+        .mount(server)
+        .await;
+}
+
+async fn run_title_job(pool: &Pool<Postgres>, item_id: Uuid) {
+    FetchTitleJobHandler::new()
+        .run(
+            json!({ "item_id": item_id }),
+            pool,
+            Span::none(),
+            Deadline::none(),
+        )
+        .await
+        .unwrap();
+}
+
+async fn title_and_site(pool: &Pool<Postgres>, item_id: Uuid) -> (Option<String>, Option<String>) {
+    sqlx::query_as(
+        "SELECT i.title, s.name FROM items i LEFT JOIN sites s ON s.host = i.domain WHERE i.id = $1",
+    )
+    .bind(item_id)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+async fn test_title_job_fills_in_title(pool: Pool<Postgres>) {
+    let server = helpers::start_mock_server().await;
+    mount_page(&server).await;
+    let (user_id, _) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, &format!("{}/post", server.uri())).await;
+
+    run_title_job(&pool, item_id).await;
+
+    assert_eq!(
+        title_and_site(&pool, item_id).await,
+        (Some("A Post".to_string()), Some("Example".to_string()))
+    );
+}
+
+#[sqlx::test]
+async fn test_title_job_keeps_existing_title(pool: Pool<Postgres>) {
+    let server = helpers::start_mock_server().await;
+    mount_page(&server).await;
+    let (user_id, _) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, &format!("{}/post", server.uri())).await;
+    sqlx::query("UPDATE items SET title = 'Mine' WHERE id = $1")
+        .bind(item_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    run_title_job(&pool, item_id).await;
+
+    assert_eq!(
+        title_and_site(&pool, item_id).await,
+        (Some("Mine".to_string()), None)
+    );
+}
+
+#[sqlx::test]
+async fn test_title_job_gives_up_quietly(pool: Pool<Postgres>) {
+    let server = helpers::start_mock_server().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    let (user_id, _) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let broken = helpers::insert_item(&pool, user_id, &format!("{}/post", server.uri())).await;
+
+    // Not retried; the full fetch reports the failure
+    run_title_job(&pool, broken).await;
+    assert_eq!(title_and_site(&pool, broken).await, (None, None));
+
+    // Deleted items and blocked domains are skipped without a request
+    run_title_job(&pool, Uuid::new_v4()).await;
+    let admin = helpers::create_user_with_token(&pool, "admin@example.com")
+        .await
+        .0;
+    DomainRulesRepository::new(&pool)
+        .upsert("blocked.example", DomainRuleAction::Block, None, admin)
+        .await
+        .unwrap();
+    let blocked = helpers::insert_item(&pool, user_id, "https://blocked.example/post").await;
+    run_title_job(&pool, blocked).await;
+    assert_eq!(title_and_site(&pool, blocked).await, (None, None));
+}
+
+#[sqlx::test]
+async fn test_title_job_staged_ahead_of_full_fetch(pool: Pool<Postgres>) {
+    let (user_id, _) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, "https://example.com/post").await;
+
+    let mut tx = pool.begin().await.unwrap();
+    stage_fetch_jobs(&mut tx, item_id).await.unwrap();
+    tx.commit().await.unwrap();
+
+    let kinds: Vec<String> = sqlx::query_scalar("SELECT kind FROM job_outbox ORDER BY run_at, id")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(kinds, vec![FETCH_TITLE_JOB_KIND, FETCH_PAGE_JOB_KIND]);
+}
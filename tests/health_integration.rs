@@ -0,0 +1,82 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+};
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+
+async fn health(app: &Router) -> Value {
+    let request = Request::builder()
+        .method("GET")
+        .uri("/healthz")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[sqlx::test]
+async fn test_health_reports_idle_queue(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+
+    let body = health(&app).await;
+    assert_eq!(body["status"], "OK");
+    assert_eq!(body["jobs"]["status"], "healthy");
+    assert!(body["jobs"]["oldest_queued_age_secs"].is_null());
+}
+
+#[sqlx::test]
+async fn test_health_reports_stuck_jobs(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+
+    // Due ten minutes ago and never picked up
+    sqlx::query(
+        "INSERT INTO jobs (kind, payload, run_at) VALUES ('example', '{}', NOW() - INTERVAL '10 minutes')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    // Not due yet, so not lag
+    sqlx::query(
+        "INSERT INTO jobs (kind, payload, run_at) VALUES ('example', '{}', NOW() + INTERVAL '1 day')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let body = health(&app).await;
+    assert_eq!(body["status"], "DEGRADED");
+    assert_eq!(body["jobs"]["status"], "lagging");
+    assert!(body["jobs"]["oldest_queued_age_secs"].as_f64().unwrap() >= 600.0);
+}
+
+#[sqlx::test]
+async fn test_health_reports_failure_rate(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+
+    sqlx::query(
+        r#"
+        INSERT INTO jobs (kind, payload, run_at, status)
+        SELECT 'example', '{}', NOW(),
+               CASE WHEN n % 2 = 0 THEN 'failed'::job_status ELSE 'succeeded'::job_status END
+        FROM generate_series(1, 40) n
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let body = health(&app).await;
+    assert_eq!(body["status"], "DEGRADED");
+    assert_eq!(body["jobs"]["finished_last_hour"], 40);
+    assert_eq!(body["jobs"]["failed_last_hour"], 20);
+    assert_eq!(body["jobs"]["failure_rate"], 0.5);
+}
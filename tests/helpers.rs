@@ -1,23 +1,301 @@
-use axum::{Router, routing::post};
+use axum::{
+    Router,
+    extract::DefaultBodyLimit,
+    middleware::from_fn_with_state,
+    routing::{delete, get, patch, post, put},
+};
 use sqlx::{Pool, Postgres};
-use std::sync::Arc;
+use std::sync::{Arc, Once};
+use uuid::Uuid;
+use wiremock::MockServer;
 
 use capsule::{
+    annotations,
     app_state::AppState,
-    auth::handlers::{login, signup},
-    repositories::{UserRepository, UserRepositoryTrait},
+    auth::{
+        handlers::{login, signup},
+        jwt::JwtService,
+    },
+    config::Config,
+    data_requests, domain_prefs, domain_rules,
+    embeddings::EmbeddingProvider,
+    fetcher::url_policy::{ENV_URL_ALLOW_PRIVATE_ADDRESSES, ENV_URL_ALLOWED_PORTS},
+    health, history, imports, items,
+    middleware::{throttle::save_throttle_middleware, transaction::transaction_middleware},
+    operations,
+    repositories::{ItemRepository, UserRepository, UserRepositoryTrait, item::WORDS_PER_MINUTE},
+    schema, search, shares, sites, stats, tags, throttles, topics, translation, undo,
 };
 
+#[allow(dead_code)]
 pub fn test_app(pool: Pool<Postgres>) -> Router {
+    test_app_with_embedder(pool, None)
+}
+
+/// Like [`test_app`], with semantic search embedding queries through
+/// `embedder`.
+#[allow(dead_code)]
+pub fn test_app_with_embedder(
+    pool: Pool<Postgres>,
+    embedder: Option<Arc<dyn EmbeddingProvider>>,
+) -> Router {
     let user_repo: Arc<dyn UserRepositoryTrait + Send + Sync> =
         Arc::new(UserRepository::new(pool.clone()));
     let state = AppState {
         user_repo,
-        db_pool: pool,
+        item_repo: Arc::new(ItemRepository::new(pool.clone())),
+        db_pool: pool.clone(),
+        embedder,
     };
 
     Router::new()
+        .route("/healthz", get(health::health_check))
         .route("/v1/auth/signup", post(signup))
         .route("/v1/auth/login", post(login))
+        .route("/v1/items", get(items::handlers::list_items))
+        .route(
+            "/v1/items",
+            post(items::handlers::create_item)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware))
+                .route_layer(from_fn_with_state(pool.clone(), save_throttle_middleware)),
+        )
+        .route(
+            "/v1/items/quick-reads",
+            get(items::handlers::list_quick_reads),
+        )
+        .route(
+            "/v1/items/long-reads",
+            get(items::handlers::list_long_reads),
+        )
+        .route("/v1/items/random", get(items::handlers::random_item))
+        .route("/v1/items/lookup", get(items::handlers::lookup_item))
+        .route("/v1/items/{id}", get(items::handlers::get_item))
+        .route("/v1/items/{id}", patch(items::handlers::update_item))
+        .route(
+            "/v1/items/{id}",
+            delete(items::handlers::delete_item)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route(
+            "/v1/items/bulk",
+            post(items::handlers::bulk_items)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route(
+            "/v1/items/triage",
+            post(items::handlers::triage_items)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route(
+            "/v1/items/content:batchGet",
+            post(items::handlers::batch_get_content),
+        )
+        .route("/v1/items/preview", post(items::handlers::preview_item))
+        .route(
+            "/v1/items/{id}/highlights",
+            post(annotations::handlers::create_highlight),
+        )
+        .route("/v1/items/{id}/note", put(annotations::handlers::set_note))
+        .route(
+            "/v1/items/{id}/progress",
+            put(items::handlers::set_progress),
+        )
+        .route(
+            "/v1/items/{id}/refresh-policy",
+            put(items::handlers::set_refresh_policy),
+        )
+        .route(
+            "/v1/items/{id}/transitions",
+            get(items::handlers::list_transitions),
+        )
+        .route("/v1/items/{id}/events", get(items::handlers::list_events))
+        .route("/v1/items/{id}/links", get(items::handlers::list_links))
+        .route(
+            "/v1/items/{id}/opened",
+            post(history::handlers::record_open),
+        )
+        .route(
+            "/v1/items/{id}/share",
+            post(shares::handlers::create_share).delete(shares::handlers::revoke_shares),
+        )
+        .route(
+            "/v1/items/{id}/translations",
+            post(translation::handlers::request_translation)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route(
+            "/v1/items/{id}/translations/{lang}",
+            get(translation::handlers::get_translation),
+        )
+        .route(
+            "/v1/items/{id}/extraction:retry",
+            post(items::handlers::retry_extraction)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route(
+            "/v1/domain-prefs/{domain}",
+            put(domain_prefs::handlers::upsert_domain_pref),
+        )
+        .route("/v1/search", get(search::handlers::search))
+        .route("/v1/history", get(history::handlers::list_history))
+        .route("/shared/{token}", get(shares::handlers::shared_page))
+        .route("/v1/sites", get(sites::handlers::list_sites))
+        .route("/v1/sites/{host}", get(sites::handlers::get_site))
+        .route("/v1/sites/{host}/icon", get(sites::handlers::site_icon))
+        .route("/v1/stats/languages", get(stats::handlers::language_stats))
+        .route("/v1/stats/sites", get(stats::handlers::site_stats))
+        .route("/v1/items/stats", get(stats::handlers::item_stats))
+        .route(
+            "/v1/tags/{id}",
+            patch(tags::handlers::rename_tag)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route("/v1/topics", get(topics::handlers::list_topics))
+        .route(
+            "/v1/undo/{token}",
+            post(undo::handlers::undo)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+        )
+        .route(
+            "/v1/operations/{id}",
+            get(operations::handlers::get_operation),
+        )
+        .route(
+            "/v1/imports",
+            post(imports::handlers::create_import)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware))
+                .route_layer(from_fn_with_state(pool.clone(), save_throttle_middleware)),
+        )
+        .route(
+            "/v1/imports/history",
+            post(imports::handlers::import_history)
+                .route_layer(from_fn_with_state(pool.clone(), transaction_middleware))
+                .route_layer(from_fn_with_state(pool.clone(), save_throttle_middleware))
+                .layer(DefaultBodyLimit::max(imports::history::MAX_HISTORY_BYTES)),
+        )
+        .route(
+            "/v1/imports/{id}/report",
+            get(imports::handlers::get_import_report),
+        )
+        .route(
+            "/v1/admin/domain-rules",
+            get(domain_rules::handlers::list_domain_rules),
+        )
+        .route(
+            "/v1/admin/domain-rules/{domain}",
+            put(domain_rules::handlers::upsert_domain_rule)
+                .delete(domain_rules::handlers::delete_domain_rule),
+        )
+        .route(
+            "/v1/admin/throttles",
+            get(throttles::handlers::list_throttles),
+        )
+        .route(
+            "/v1/admin/throttles/{user_id}",
+            put(throttles::handlers::override_throttle),
+        )
+        .route(
+            "/v1/admin/throttles/{user_id}/events",
+            get(throttles::handlers::list_abuse_events),
+        )
+        .route("/v1/admin/schema", get(schema::handlers::get_schema))
+        .route(
+            "/v1/admin/users/{user_id}/data-requests",
+            get(data_requests::handlers::list_data_requests),
+        )
+        .route(
+            "/v1/admin/users/{user_id}/data-requests",
+            post(data_requests::handlers::create_data_request)
+                .route_layer(from_fn_with_state(pool, transaction_middleware)),
+        )
+        .route(
+            "/v1/admin/data-requests/{id}",
+            get(data_requests::handlers::get_data_request),
+        )
+        .route(
+            "/v1/admin/data-requests/{id}/package",
+            get(data_requests::handlers::get_data_package),
+        )
         .with_state(state)
 }
+
+/// Insert a user directly and return its ID alongside a valid bearer token.
+#[allow(dead_code)]
+pub async fn create_user_with_token(pool: &Pool<Postgres>, email: &str) -> (Uuid, String) {
+    let user_id: Uuid =
+        sqlx::query_scalar("INSERT INTO users (email, pw_hash) VALUES ($1, 'x') RETURNING id")
+            .bind(email)
+            .fetch_one(pool)
+            .await
+            .expect("Failed to insert user");
+
+    let config = Config::from_env().expect("Failed to load config");
+    let token = JwtService::new(config.jwt_secret())
+        .generate_token(user_id)
+        .expect("Failed to generate token");
+
+    (user_id, token)
+}
+
+/// Insert an item for the given user and return its ID.
+#[allow(dead_code)]
+pub async fn insert_item(pool: &Pool<Postgres>, user_id: Uuid, url: &str) -> Uuid {
+    sqlx::query_scalar("INSERT INTO items (user_id, url) VALUES ($1, $2) RETURNING id")
+        .bind(user_id)
+        .bind(url)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to insert item")
+}
+
+/// Store extracted content with a detected language for an item, and its
+/// word count and reading time on the item as extraction does.
+#[allow(dead_code)]
+pub async fn insert_content(pool: &Pool<Postgres>, item_id: Uuid, clean_text: &str, lang: &str) {
+    sqlx::query("INSERT INTO contents (item_id, clean_text, lang) VALUES ($1, $2, $3)")
+        .bind(item_id)
+        .bind(clean_text)
+        .bind(lang)
+        .execute(pool)
+        .await
+        .expect("Failed to insert content");
+    sqlx::query(
+        r#"
+        UPDATE items i
+        SET word_count = c.word_count,
+            reading_time_minutes = CEIL(c.word_count / $2)::int,
+            lang = lower(c.lang)
+        FROM contents c
+        WHERE i.id = $1 AND c.item_id = i.id
+        "#,
+    )
+    .bind(item_id)
+    .bind(WORDS_PER_MINUTE)
+    .execute(pool)
+    .await
+    .expect("Failed to copy content stats to the item");
+}
+
+/// Let the user change instance-wide settings.
+#[allow(dead_code)]
+pub async fn make_admin(pool: &Pool<Postgres>, user_id: Uuid) {
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE id = $1")
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .expect("Failed to make user an admin");
+}
+
+static ALLOW_MOCK_SERVERS: Once = Once::new();
+
+/// Start a mock server, letting the fetcher reach its random port on
+/// loopback. The URL policy is read once per process, so this runs before
+/// any fetch.
+#[allow(dead_code)]
+pub async fn start_mock_server() -> MockServer {
+    ALLOW_MOCK_SERVERS.call_once(|| unsafe {
+        std::env::set_var(ENV_URL_ALLOWED_PORTS, "*");
+        std::env::set_var(ENV_URL_ALLOW_PRIVATE_ADDRESSES, "true");
+    });
+    MockServer::start().await
+}
@@ -0,0 +1,96 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode, header::AUTHORIZATION},
+};
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use helpers::{create_user_with_token, insert_item};
+
+async fn send(app: &Router, method: Method, token: &str, uri: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn open(app: &Router, token: &str, item_id: Uuid) -> StatusCode {
+    let uri = format!("/v1/items/{}/opened", item_id);
+    send(app, Method::POST, token, &uri).await.0
+}
+
+#[sqlx::test]
+async fn test_history_lists_opens_newest_first(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let first = insert_item(&pool, user_id, "https://example.com/first").await;
+    let second = insert_item(&pool, user_id, "https://example.com/second").await;
+
+    assert_eq!(open(&app, &token, first).await, StatusCode::CREATED);
+    assert_eq!(open(&app, &token, second).await, StatusCode::CREATED);
+    assert_eq!(open(&app, &token, first).await, StatusCode::CREATED);
+
+    let (status, page) = send(&app, Method::GET, &token, "/v1/history").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["approximate_total"], 3);
+    let opened: Vec<_> = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["item_id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(
+        opened,
+        [first.to_string(), second.to_string(), first.to_string()]
+    );
+    assert_eq!(page["items"][0]["url"], "https://example.com/first");
+    assert!(page["next_cursor"].is_null());
+
+    // Page through one entry at a time
+    let (_, page) = send(&app, Method::GET, &token, "/v1/history?limit=2").await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 2);
+    let cursor = page["next_cursor"].as_str().unwrap();
+    let uri = format!("/v1/history?limit=2&cursor={}", cursor);
+    let (status, page) = send(&app, Method::GET, &token, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["items"].as_array().unwrap().len(), 1);
+    assert_eq!(page["items"][0]["item_id"], first.to_string());
+    assert!(page["next_cursor"].is_null());
+}
+
+#[sqlx::test]
+async fn test_history_is_per_user(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let (_, other_token) = create_user_with_token(&pool, "bob@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://example.com/post").await;
+
+    assert_eq!(
+        open(&app, &other_token, item_id).await,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        open(&app, &token, Uuid::new_v4()).await,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(open(&app, &token, item_id).await, StatusCode::CREATED);
+
+    let (_, page) = send(&app, Method::GET, &other_token, "/v1/history").await;
+    assert!(page["items"].as_array().unwrap().is_empty());
+
+    let (status, _) = send(&app, Method::GET, &token, "/v1/history?cursor=20").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,414 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{
+        Request, StatusCode,
+        header::{AUTHORIZATION, CONTENT_DISPOSITION},
+    },
+};
+use capsule::{
+    fetcher::Deadline,
+    jobs::{IMPORT_URLS_JOB_KIND, ImportUrlsJobHandler, JobHandler},
+    repositories::ImportRepository,
+};
+use serde_json::{Value, json};
+use sqlx::{
+    ConnectOptions, Connection, Pool, Postgres,
+    sqlite::{SqliteConnectOptions, SqliteConnection},
+};
+use tower::ServiceExt;
+use tracing::Span;
+use uuid::Uuid;
+
+async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Option<String>, String) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let disposition = response
+        .headers()
+        .get(CONTENT_DISPOSITION)
+        .map(|value| value.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        disposition,
+        String::from_utf8(body.to_vec()).unwrap(),
+    )
+}
+
+async fn get_report(
+    app: &Router,
+    token: &str,
+    id: Uuid,
+    format: &str,
+) -> (StatusCode, Option<String>, String) {
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/v1/imports/{}/report?format={}", id, format))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    send(app, request).await
+}
+
+/// Run the import job the API staged, as the worker would
+async fn run_import_job(pool: &Pool<Postgres>) {
+    let payload: Value = sqlx::query_scalar("SELECT payload FROM job_outbox WHERE kind = $1")
+        .bind(IMPORT_URLS_JOB_KIND)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    ImportUrlsJobHandler::new()
+        .run(payload, pool, Span::none(), Deadline::none())
+        .await
+        .unwrap();
+}
+
+async fn post_history(
+    app: &Router,
+    token: &str,
+    query: &str,
+    upload: Vec<u8>,
+) -> (StatusCode, String) {
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/v1/imports/history?{}", query))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/octet-stream")
+        .body(Body::from(upload))
+        .unwrap();
+    let (status, _, body) = send(app, request).await;
+    (status, body)
+}
+
+/// URLs of the import job the API staged
+async fn staged_urls(pool: &Pool<Postgres>) -> Vec<String> {
+    let payload: Value = sqlx::query_scalar("SELECT payload FROM job_outbox WHERE kind = $1")
+        .bind(IMPORT_URLS_JOB_KIND)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    serde_json::from_value(payload["urls"].clone()).unwrap()
+}
+
+#[sqlx::test]
+async fn test_import_history_json_keeps_pages_read_long_enough(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+
+    let upload = json!([
+        {"url": "https://example.com/skimmed", "duration_secs": 10},
+        {"url": "https://example.com/read", "duration_secs": 50},
+        {"url": "https://example.com/studied", "duration_secs": 600},
+        {"url": "https://example.com/read", "duration_secs": 40},
+        {"url": "about:blank", "duration_secs": 3600},
+    ]);
+    let (status, body) = post_history(
+        &app,
+        &token,
+        "format=json&min_visit_secs=60",
+        upload.to_string().into_bytes(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let operation: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(operation["kind"], "import");
+
+    assert_eq!(
+        staged_urls(&pool).await,
+        vec!["https://example.com/studied", "https://example.com/read"]
+    );
+}
+
+#[sqlx::test]
+async fn test_import_history_chrome_database(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+
+    // The tables of Chrome's History database the import reads
+    let path = std::env::temp_dir().join(format!("chrome-history-{}.sqlite", Uuid::new_v4()));
+    let mut conn: SqliteConnection = SqliteConnectOptions::new()
+        .filename(&path)
+        .create_if_missing(true)
+        .connect()
+        .await
+        .unwrap();
+    for statement in [
+        "CREATE TABLE urls (id INTEGER PRIMARY KEY, url LONGVARCHAR, title LONGVARCHAR)",
+        "CREATE TABLE visits (id INTEGER PRIMARY KEY, url INTEGER NOT NULL, visit_duration INTEGER DEFAULT 0 NOT NULL)",
+        "INSERT INTO urls VALUES (1, 'https://example.com/read', 'Read'), (2, 'https://example.com/skimmed', 'Skimmed')",
+        // Microseconds: 20s + 20s for the first page, 5s for the second
+        "INSERT INTO visits VALUES (1, 1, 20000000), (2, 1, 20000000), (3, 2, 5000000)",
+    ] {
+        sqlx::query(statement).execute(&mut conn).await.unwrap();
+    }
+    conn.close().await.unwrap();
+    let upload = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let (status, _) = post_history(&app, &token, "format=chrome", upload.clone()).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(staged_urls(&pool).await, vec!["https://example.com/read"]);
+
+    // Nothing was open for a whole minute
+    let (status, body) =
+        post_history(&app, &token, "format=chrome&min_visit_secs=60", upload).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("60 seconds"));
+}
+
+#[sqlx::test]
+async fn test_import_history_rejects_bad_uploads(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+
+    let (status, body) =
+        post_history(&app, &token, "format=firefox", b"not a database".to_vec()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("Not a Firefox history database"));
+
+    let (status, _) = post_history(&app, &token, "format=json", b"{}".to_vec()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = post_history(&app, &token, "format=opera", b"[]".to_vec()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("format"));
+
+    let (status, _) = post_history(
+        &app,
+        &token,
+        "format=json&min_visit_secs=100000",
+        b"[]".to_vec(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let staged: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM job_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(staged, 0);
+}
+
+#[sqlx::test]
+async fn test_import_report_lists_failed_rows(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    helpers::insert_item(&pool, user_id, "https://example.com/saved").await;
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/imports")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({
+                "urls": [
+                    "https://example.com/a",
+                    "not a url",
+                    "https://example.com/saved",
+                    "https://example.com/a?utm_source=rss",
+                    "https://example.com/b",
+                ]
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let (status, _, body) = send(&app, request).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let operation: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(operation["kind"], "import");
+    let id: Uuid = operation["id"].as_str().unwrap().parse().unwrap();
+
+    // Nothing to report until the import has run
+    let (status, _, _) = get_report(&app, &token, id, "json").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    run_import_job(&pool).await;
+
+    // The site behind row 5 refuses the fetch
+    let item_id: Uuid = sqlx::query_scalar("SELECT id FROM items WHERE url = $1")
+        .bind("https://example.com/b")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    ImportRepository::new(&pool)
+        .record_fetch_forbidden(item_id, "http error 403 Forbidden")
+        .await
+        .unwrap();
+
+    let (status, disposition, body) = get_report(&app, &token, id, "json").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        disposition.unwrap(),
+        format!("attachment; filename=\"import-{}-failures.json\"", id)
+    );
+    let report: Value = serde_json::from_str(&body).unwrap();
+    let failures = report["failures"].as_array().unwrap();
+    let rows: Vec<(i64, &str)> = failures
+        .iter()
+        .map(|f| (f["row"].as_i64().unwrap(), f["reason"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            (2, "bad_url"),
+            (3, "duplicate"),
+            (4, "duplicate"),
+            (5, "fetch_forbidden")
+        ]
+    );
+
+    let (status, disposition, csv) = get_report(&app, &token, id, "csv").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(disposition.unwrap().ends_with(".csv\""));
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "row,url,reason,detail");
+    assert_eq!(lines.len(), 5);
+    assert_eq!(
+        lines[4],
+        "5,https://example.com/b,fetch_forbidden,http error 403 Forbidden"
+    );
+
+    let (status, _, _) = get_report(&app, &token, id, "xml").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_import_skips_pages_saved_under_another_url(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+
+    let save = Request::builder()
+        .method("POST")
+        .uri("/v1/items")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"url": "https://example.com/post?utm_source=feed"}).to_string(),
+        ))
+        .unwrap();
+    let (status, _, _) = send(&app, save).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/imports")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"urls": ["https://example.com/post#comments", "https://example.com/new?utm_campaign=x"]})
+                .to_string(),
+        ))
+        .unwrap();
+    let (status, _, body) = send(&app, request).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let id: Uuid = serde_json::from_str::<Value>(&body).unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    run_import_job(&pool).await;
+
+    let items: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(items, 2);
+    // Imported items are found by their hash like any other save
+    let hashed: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM items WHERE user_id = $1 AND url_hash IS NOT NULL",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(hashed, 2);
+    // Saved under the canonical URL, as the API would
+    let imported: String =
+        sqlx::query_scalar("SELECT url FROM items WHERE user_id = $1 AND import_row = 2")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(imported, "https://example.com/new");
+
+    let (_, _, body) = get_report(&app, &token, id, "json").await;
+    let report: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(report["failures"][0]["row"], 1);
+    assert_eq!(report["failures"][0]["reason"], "duplicate");
+    assert_eq!(report["failures"].as_array().unwrap().len(), 1);
+}
+
+#[sqlx::test]
+async fn test_import_report_is_private(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, alice) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (_, bob) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/imports")
+        .header(AUTHORIZATION, format!("Bearer {}", alice))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({"urls": ["not a url"]}).to_string()))
+        .unwrap();
+    let (_, _, body) = send(&app, request).await;
+    let operation: Value = serde_json::from_str(&body).unwrap();
+    let id: Uuid = operation["id"].as_str().unwrap().parse().unwrap();
+    run_import_job(&pool).await;
+
+    let (status, _, _) = get_report(&app, &bob, id, "csv").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, _) = get_report(&app, &alice, id, "csv").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_import_stops_when_lease_runs_out(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/imports")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"urls": ["https://example.com/a", "https://example.com/b"]}).to_string(),
+        ))
+        .unwrap();
+    let (status, _, _) = send(&app, request).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+
+    let payload: Value = sqlx::query_scalar("SELECT payload FROM job_outbox WHERE kind = $1")
+        .bind(IMPORT_URLS_JOB_KIND)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let expired = Deadline::at(chrono::Utc::now() - chrono::Duration::seconds(1));
+    let result = ImportUrlsJobHandler::new()
+        .run(payload, &pool, Span::none(), expired)
+        .await;
+    assert!(result.is_err());
+
+    let items: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(items, 0);
+
+    // The retry picks the import back up
+    run_import_job(&pool).await;
+    let items: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(items, 2);
+}
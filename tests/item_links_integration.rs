@@ -0,0 +1,106 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::repositories::LinkRepository;
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use url::Url;
+use uuid::Uuid;
+
+use helpers::{create_user_with_token, insert_item};
+
+async fn get_links(app: &Router, token: &str, item_id: Uuid) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .uri(format!("/v1/items/{}/links", item_id))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+fn urls(urls: &[&str]) -> Vec<Url> {
+    urls.iter().map(|url| Url::parse(url).unwrap()).collect()
+}
+
+#[sqlx::test]
+async fn test_links_connect_saved_items(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let essay = insert_item(&pool, user_id, "https://example.com/essay").await;
+    let source = insert_item(&pool, user_id, "https://other.example/source").await;
+
+    LinkRepository::new(&pool)
+        .record(
+            essay,
+            &urls(&[
+                "https://unsaved.example/page",
+                // Tracking parameters don't stop the match
+                "https://other.example/source?utm_source=essay",
+            ]),
+        )
+        .await
+        .unwrap();
+
+    let (status, links) = get_links(&app, &token, essay).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(links["cites"].as_array().unwrap().len(), 1);
+    assert_eq!(links["cites"][0]["id"], source.to_string());
+    assert!(links["cited_by"].as_array().unwrap().is_empty());
+    assert_eq!(
+        links["unsaved_links"],
+        serde_json::json!(["https://unsaved.example/page"])
+    );
+
+    let (_, links) = get_links(&app, &token, source).await;
+    assert_eq!(links["cited_by"].as_array().unwrap().len(), 1);
+    assert_eq!(links["cited_by"][0]["id"], essay.to_string());
+}
+
+#[sqlx::test]
+async fn test_links_find_items_saved_later(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let essay = insert_item(&pool, user_id, "https://example.com/essay").await;
+
+    LinkRepository::new(&pool)
+        .record(essay, &urls(&["https://other.example/source"]))
+        .await
+        .unwrap();
+    let (_, links) = get_links(&app, &token, essay).await;
+    assert!(links["cites"].as_array().unwrap().is_empty());
+
+    let source = insert_item(&pool, user_id, "https://other.example/source").await;
+    let (_, links) = get_links(&app, &token, essay).await;
+    assert_eq!(links["cites"][0]["id"], source.to_string());
+    assert!(links["unsaved_links"].as_array().unwrap().is_empty());
+}
+
+#[sqlx::test]
+async fn test_links_stay_within_the_users_library(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (alice_id, alice) = create_user_with_token(&pool, "alice@example.com").await;
+    let (bob_id, bob) = create_user_with_token(&pool, "bob@example.com").await;
+    let essay = insert_item(&pool, alice_id, "https://example.com/essay").await;
+    insert_item(&pool, bob_id, "https://other.example/source").await;
+
+    LinkRepository::new(&pool)
+        .record(essay, &urls(&["https://other.example/source"]))
+        .await
+        .unwrap();
+
+    let (_, links) = get_links(&app, &alice, essay).await;
+    assert!(links["cites"].as_array().unwrap().is_empty());
+
+    let (status, _) = get_links(&app, &bob, essay).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,123 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::repositories::TagRepository;
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use std::{
+    fmt,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+use tower::ServiceExt;
+use tracing::{
+    Event, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{
+    layer::{Context, Layer, SubscriberExt},
+    registry,
+};
+use uuid::Uuid;
+
+/// Counts the statements sqlx logs, one event per query. The catalog
+/// lookups sqlx makes to resolve types on a new connection aren't counted.
+struct QueryCounter(Arc<AtomicUsize>);
+
+#[derive(Default)]
+struct Summary(String);
+
+impl Visit for Summary {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "summary" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for QueryCounter {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != "sqlx::query" {
+            return;
+        }
+        let mut summary = Summary::default();
+        event.record(&mut summary);
+        if !summary.0.contains("regtype") && !summary.0.contains("pg_catalog") {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// List the user's items, returning the body and how many queries it took
+async fn list_items(app: &Router, token: &str) -> (Value, usize) {
+    let queries = Arc::new(AtomicUsize::new(0));
+    let _guard = tracing::subscriber::set_default(registry().with(QueryCounter(queries.clone())));
+
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/items")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+
+    (
+        serde_json::from_slice(&body).unwrap(),
+        queries.load(Ordering::SeqCst),
+    )
+}
+
+async fn insert_tagged_item(pool: &Pool<Postgres>, user_id: Uuid, url: &str) -> Uuid {
+    let item_id = helpers::insert_item(pool, user_id, url).await;
+    helpers::insert_content(pool, item_id, &"word ".repeat(500), "en").await;
+
+    let tags = TagRepository::new(pool);
+    for name in ["rust", "databases"] {
+        let tag_id = tags.find_or_create(user_id, name).await.unwrap();
+        tags.attach(item_id, tag_id).await.unwrap();
+    }
+    item_id
+}
+
+#[sqlx::test]
+async fn test_list_items_query_count_does_not_grow_with_items(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_tagged_item(&pool, user_id, "https://example.com/0").await;
+
+    let request = Request::builder()
+        .method("PUT")
+        .uri(format!("/v1/items/{}/progress", item_id))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({"progress": 0.5}).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let (list, single_item_queries) = list_items(&app, &token).await;
+    let item = &list["items"][0];
+    assert_eq!(item["tags"], json!(["databases", "rust"]));
+    assert_eq!(item["reading_time_minutes"], 3);
+    assert_eq!(item["read_progress"], 0.5);
+
+    for n in 1..10 {
+        insert_tagged_item(&pool, user_id, &format!("https://example.com/{}", n)).await;
+    }
+    let (list, many_items_queries) = list_items(&app, &token).await;
+    assert_eq!(list["items"].as_array().unwrap().len(), 10);
+
+    // The ETag fingerprint and the list itself, however many items there are
+    assert_eq!(single_item_queries, 2);
+    assert_eq!(many_items_queries, 2);
+}
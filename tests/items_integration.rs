@@ -0,0 +1,1789 @@
+mod helpers;
+
+use axum::{
+    body::Body,
+    http::{
+        Request, StatusCode,
+        header::{AUTHORIZATION, ETAG, IF_MATCH, IF_NONE_MATCH},
+    },
+};
+use capsule::{
+    entities::{ItemEventKind, ProcessingState},
+    repositories::{ItemEventRepository, ItemStateRepository},
+};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+
+fn list_request(token: &str, if_none_match: Option<&str>) -> Request<Body> {
+    let mut builder = Request::builder()
+        .method("GET")
+        .uri("/v1/items")
+        .header(AUTHORIZATION, format!("Bearer {}", token));
+    if let Some(etag) = if_none_match {
+        builder = builder.header(IF_NONE_MATCH, etag);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+#[sqlx::test]
+async fn test_list_items_returns_only_own_items(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (alice, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (bob, _) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    helpers::insert_item(&pool, alice, "https://example.com/a").await;
+    helpers::insert_item(&pool, bob, "https://example.com/b").await;
+
+    let response = app.oneshot(list_request(&token, None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let list: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let items = list["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["url"], "https://example.com/a");
+    assert_eq!(list["approximate_total"], 1);
+    assert!(list["next_cursor"].is_null());
+}
+
+#[sqlx::test]
+async fn test_list_items_etag_not_modified(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+
+    let response = app
+        .clone()
+        .oneshot(list_request(&token, None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .expect("ETag header missing")
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    // Unchanged list short-circuits with 304
+    let response = app
+        .clone()
+        .oneshot(list_request(&token, Some(&etag)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    // Adding an item invalidates the ETag
+    helpers::insert_item(&pool, user_id, "https://example.com/b").await;
+    let response = app
+        .oneshot(list_request(&token, Some(&etag)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(
+        response.headers().get(ETAG).unwrap().to_str().unwrap(),
+        etag
+    );
+}
+
+async fn get_json(app: axum::Router, token: &str, uri: &str) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(uri)
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[sqlx::test]
+async fn test_list_items_filters_by_lang(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let english = helpers::insert_item(&pool, user_id, "https://example.com/en").await;
+    let german = helpers::insert_item(&pool, user_id, "https://example.com/de").await;
+    helpers::insert_item(&pool, user_id, "https://example.com/pending").await;
+    helpers::insert_content(&pool, english, "Hello", "en").await;
+    helpers::insert_content(&pool, german, "Hallo", "de").await;
+
+    let (status, list) = get_json(app.clone(), &token, "/v1/items?lang=DE").await;
+    assert_eq!(status, StatusCode::OK);
+    let items = list["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["url"], "https://example.com/de");
+
+    let (status, _) = get_json(app, &token, "/v1/items?lang=german").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_list_items_filters_by_site_and_date(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let september = helpers::insert_item(&pool, user_id, "https://www.Example.com/old").await;
+    let october = helpers::insert_item(&pool, user_id, "https://example.com/new").await;
+    let blog = helpers::insert_item(&pool, user_id, "https://blog.example.com/post").await;
+    for (item_id, created_at) in [
+        (september, "2025-09-15T12:00:00Z"),
+        (october, "2025-10-15T12:00:00Z"),
+        (blog, "2025-10-20T12:00:00Z"),
+    ] {
+        sqlx::query("UPDATE items SET created_at = $2::timestamptz WHERE id = $1")
+            .bind(item_id)
+            .bind(created_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let (status, list) = get_json(app.clone(), &token, "/v1/items?site=example.com").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        urls(&list),
+        vec!["https://example.com/new", "https://www.Example.com/old"]
+    );
+
+    let (_, list) = get_json(
+        app.clone(),
+        &token,
+        "/v1/items?created_after=2025-10-01T00:00:00Z",
+    )
+    .await;
+    assert_eq!(
+        urls(&list),
+        vec!["https://blog.example.com/post", "https://example.com/new"]
+    );
+
+    let (_, list) = get_json(
+        app.clone(),
+        &token,
+        "/v1/items?site=www.example.com&created_before=2025-10-01T00:00:00Z",
+    )
+    .await;
+    assert_eq!(urls(&list), vec!["https://www.Example.com/old"]);
+
+    let (status, _) = get_json(
+        app,
+        &token,
+        "/v1/items?created_after=2025-10-01T00:00:00Z&created_before=2025-09-01T00:00:00Z",
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+fn urls(list: &serde_json::Value) -> Vec<&str> {
+    list["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["url"].as_str().unwrap())
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_list_items_filters_by_status_tag_and_text(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let tagged = helpers::insert_item(&pool, user_id, "https://example.com/tagged").await;
+    let archived = helpers::insert_item(&pool, user_id, "https://example.com/archived").await;
+    helpers::insert_item(&pool, user_id, "https://example.com/plain").await;
+
+    sqlx::query(
+        "UPDATE items SET status = 'archived', title = 'Async Rust in 100% detail' WHERE id = $1",
+    )
+    .bind(archived)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let tag_id: uuid::Uuid =
+        sqlx::query_scalar("INSERT INTO tags (user_id, name) VALUES ($1, 'rust') RETURNING id")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    sqlx::query("INSERT INTO item_tags (item_id, tag_id) VALUES ($1, $2)")
+        .bind(tagged)
+        .bind(tag_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, list) = get_json(app.clone(), &token, "/v1/items?status=archived").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(urls(&list), vec!["https://example.com/archived"]);
+    assert_eq!(list["approximate_total"], 1);
+
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?tag=rust").await;
+    assert_eq!(urls(&list), vec!["https://example.com/tagged"]);
+    assert_eq!(list["items"][0]["tags"], serde_json::json!(["rust"]));
+
+    // Matches titles and URLs, ignoring case, with wildcards taken literally
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?q=ASYNC").await;
+    assert_eq!(urls(&list), vec!["https://example.com/archived"]);
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?q=PLAIN").await;
+    assert_eq!(urls(&list), vec!["https://example.com/plain"]);
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?q=100%25").await;
+    assert_eq!(urls(&list), vec!["https://example.com/archived"]);
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?q=%25").await;
+    assert_eq!(urls(&list), vec!["https://example.com/archived"]);
+
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?status=pending&tag=rust").await;
+    assert_eq!(urls(&list), vec!["https://example.com/tagged"]);
+
+    let (status, _) = get_json(app, &token, "/v1/items?status=deleted").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_list_items_paginates_with_cursor(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    for n in 0..5 {
+        helpers::insert_item(&pool, user_id, &format!("https://example.com/{}", n)).await;
+    }
+
+    let mut seen = Vec::new();
+    let mut uri = "/v1/items?limit=2".to_string();
+    loop {
+        let (status, list) = get_json(app.clone(), &token, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(list["approximate_total"], 5);
+        seen.extend(urls(&list).into_iter().map(str::to_string));
+        match list["next_cursor"].as_str() {
+            Some(cursor) => uri = format!("/v1/items?limit=2&cursor={}", cursor),
+            None => break,
+        }
+    }
+    let expected: Vec<String> = (0..5)
+        .rev()
+        .map(|n| format!("https://example.com/{}", n))
+        .collect();
+    assert_eq!(seen, expected);
+
+    // A full last page has no cursor either
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?limit=5").await;
+    assert_eq!(urls(&list).len(), 5);
+    assert!(list["next_cursor"].is_null());
+
+    // Offsets still jump to a position
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?limit=2&offset=3").await;
+    assert_eq!(
+        urls(&list),
+        vec!["https://example.com/1", "https://example.com/0"]
+    );
+
+    let (status, _) = get_json(app.clone(), &token, "/v1/items?limit=0").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get_json(app.clone(), &token, "/v1/items?offset=-1").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = get_json(app, &token, "/v1/items?cursor=2").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["field"], "cursor");
+}
+
+/// Every URL in the list, following `next_cursor` two at a time
+async fn all_pages(app: &axum::Router, token: &str, query: &str) -> Vec<String> {
+    let mut seen = Vec::new();
+    let mut uri = format!("/v1/items?limit=2&{}", query);
+    loop {
+        let (status, list) = get_json(app.clone(), token, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        seen.extend(urls(&list).into_iter().map(str::to_string));
+        match list["next_cursor"].as_str() {
+            Some(cursor) => uri = format!("/v1/items?limit=2&{}&cursor={}", query, cursor),
+            None => return seen,
+        }
+    }
+}
+
+#[sqlx::test]
+async fn test_list_items_sorts(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let mut ids = Vec::new();
+    for (path, title, words) in [
+        ("a", Some("bravo"), 476),
+        ("b", Some("alpha"), 10),
+        ("c", None, 0),
+        ("d", Some("delta"), 1000),
+    ] {
+        let id =
+            helpers::insert_item(&pool, user_id, &format!("https://example.com/{}", path)).await;
+        sqlx::query("UPDATE items SET title = $2 WHERE id = $1")
+            .bind(id)
+            .bind(title)
+            .execute(&pool)
+            .await
+            .unwrap();
+        if words > 0 {
+            helpers::insert_content(&pool, id, &vec!["word"; words].join(" "), "en").await;
+        }
+        ids.push(id);
+    }
+    // Touch the oldest item last
+    sqlx::query("UPDATE items SET favorite = TRUE WHERE id = $1")
+        .bind(ids[0])
+        .execute(&pool)
+        .await
+        .unwrap();
+    let url = |path: &str| format!("https://example.com/{}", path);
+
+    // Items without a title sort by URL
+    assert_eq!(
+        all_pages(&app, &token, "sort=title").await,
+        vec![url("b"), url("a"), url("d"), url("c")]
+    );
+    assert_eq!(
+        all_pages(&app, &token, "sort=-title").await,
+        vec![url("c"), url("d"), url("a"), url("b")]
+    );
+    // Five, two and one minutes; unextracted items count as none
+    assert_eq!(
+        all_pages(&app, &token, "sort=-reading_time").await,
+        vec![url("d"), url("a"), url("b"), url("c")]
+    );
+    assert_eq!(
+        all_pages(&app, &token, "sort=created_at").await,
+        vec![url("a"), url("b"), url("c"), url("d")]
+    );
+    assert_eq!(
+        all_pages(&app, &token, "sort=-updated_at").await[0],
+        url("a")
+    );
+
+    let (status, body) = get_json(app.clone(), &token, "/v1/items?sort=url").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["field"], "sort");
+
+    // A cursor only continues a list in the order it came from
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?limit=2&sort=title").await;
+    let cursor = list["next_cursor"].as_str().unwrap();
+    let (status, body) = get_json(
+        app,
+        &token,
+        &format!("/v1/items?limit=2&sort=-reading_time&cursor={}", cursor),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["field"], "cursor");
+}
+
+#[sqlx::test]
+async fn test_list_items_cursor_is_stable_under_inserts(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    for n in 0..4 {
+        helpers::insert_item(&pool, user_id, &format!("https://example.com/{}", n)).await;
+    }
+    // Same timestamps, so only the id orders these
+    sqlx::query("UPDATE items SET created_at = '2025-10-01T00:00:00Z' WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (_, first) = get_json(app.clone(), &token, "/v1/items?limit=2").await;
+    let cursor = first["next_cursor"].as_str().unwrap().to_string();
+
+    // Saving more doesn't shift the next page like an offset would
+    helpers::insert_item(&pool, user_id, "https://example.com/new").await;
+
+    let (status, second) = get_json(
+        app.clone(),
+        &token,
+        &format!("/v1/items?limit=2&cursor={}", cursor),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(second["next_cursor"].is_null());
+
+    let mut seen: Vec<&str> = urls(&first);
+    seen.extend(urls(&second));
+    seen.sort();
+    assert_eq!(
+        seen,
+        vec![
+            "https://example.com/0",
+            "https://example.com/1",
+            "https://example.com/2",
+            "https://example.com/3"
+        ]
+    );
+}
+
+#[sqlx::test]
+async fn test_list_and_retry_failed_extractions(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let failed = helpers::insert_item(&pool, user_id, "https://app.example.com/").await;
+    let ok = helpers::insert_item(&pool, user_id, "https://example.com/ok").await;
+    sqlx::query("UPDATE items SET extraction_error = 'render_required' WHERE id = $1")
+        .bind(failed)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, list) = get_json(app.clone(), &token, "/v1/items?extraction=failed").await;
+    assert_eq!(status, StatusCode::OK);
+    let items = list["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["extraction_error"], "render_required");
+
+    let retry = |id: uuid::Uuid| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/v1/items/{}/extraction:retry", id))
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(retry(failed)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let payload: serde_json::Value =
+        sqlx::query_scalar("SELECT payload FROM job_outbox WHERE kind = 'fetch_page'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(payload["item_id"], failed.to_string());
+    assert_eq!(payload["refetch"], true);
+
+    let response = app.oneshot(retry(ok)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[sqlx::test]
+async fn test_processing_state_transitions(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+    let states = ItemStateRepository::new(&pool);
+
+    assert!(
+        states
+            .transition(item_id, ProcessingState::Fetching)
+            .await
+            .unwrap()
+    );
+    // Can't skip extraction
+    assert!(
+        !states
+            .transition(item_id, ProcessingState::Ready)
+            .await
+            .unwrap()
+    );
+    assert!(
+        states
+            .transition(item_id, ProcessingState::Extracting)
+            .await
+            .unwrap()
+    );
+    assert!(
+        states
+            .transition(item_id, ProcessingState::Ready)
+            .await
+            .unwrap()
+    );
+
+    let (status, list) = get_json(app.clone(), &token, "/v1/items").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(list["items"][0]["processing_state"], "ready");
+
+    let uri = format!("/v1/items/{}/transitions", item_id);
+    let (status, history) = get_json(app.clone(), &token, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    let steps: Vec<(&str, &str)> = history["transitions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| {
+            (
+                t["from_state"].as_str().unwrap(),
+                t["to_state"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            ("pending", "fetching"),
+            ("fetching", "extracting"),
+            ("extracting", "ready")
+        ]
+    );
+
+    let (_, other_token) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    let (status, _) = get_json(app, &other_token, &uri).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_item_events_timeline(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+
+    let events = ItemEventRepository::new(&pool);
+    events
+        .record(item_id, ItemEventKind::Fetched, Some("HTTP 200"))
+        .await
+        .unwrap();
+    events
+        .record(item_id, ItemEventKind::Extracted, None)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE items SET status = 'archived' WHERE id = $1")
+        .bind(item_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let uri = format!("/v1/items/{}/events", item_id);
+    let (status, timeline) = get_json(app.clone(), &token, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    let kinds: Vec<&str> = timeline["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, vec!["saved", "fetched", "extracted", "archived"]);
+    assert_eq!(timeline["events"][1]["detail"], "HTTP 200");
+
+    let (_, other_token) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    let (status, _) = get_json(app, &other_token, &uri).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_language_stats_breakdown(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    for (url, lang) in [
+        ("https://example.com/1", "en"),
+        ("https://example.com/2", "en"),
+        ("https://example.com/3", "fr"),
+    ] {
+        let item_id = helpers::insert_item(&pool, user_id, url).await;
+        helpers::insert_content(&pool, item_id, "text", lang).await;
+    }
+    helpers::insert_item(&pool, user_id, "https://example.com/pending").await;
+
+    let (status, stats) = get_json(app, &token, "/v1/stats/languages").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        stats["languages"],
+        serde_json::json!([
+            {"lang": "en", "count": 2},
+            {"lang": "fr", "count": 1},
+            {"lang": null, "count": 1}
+        ])
+    );
+}
+
+#[sqlx::test]
+async fn test_batch_get_content_selects_fields(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+    helpers::insert_content(&pool, item_id, "Some article text.", "en").await;
+
+    let batch_get = |fields: &str| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/v1/items/content:batchGet{}", fields))
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::json!({ "item_ids": [item_id] }).to_string(),
+            ))
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(batch_get("?fields=metadata"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["contents"][0]["lang"], "en");
+    assert!(json["contents"][0]["clean_text"].is_null());
+    assert!(json["contents"][0]["clean_html"].is_null());
+
+    let response = app
+        .clone()
+        .oneshot(batch_get("?fields=text"))
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["contents"][0]["clean_text"], "Some article text.");
+    assert!(json["contents"][0]["lang"].is_null());
+
+    let response = app.oneshot(batch_get("?fields=raw")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+async fn create_item(app: axum::Router, token: &str, url: &str) -> (StatusCode, serde_json::Value) {
+    create_item_with(app, token, serde_json::json!({ "url": url })).await
+}
+
+async fn create_item_with(
+    app: axum::Router,
+    token: &str,
+    body: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/items")
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[sqlx::test]
+async fn test_create_item_saves_and_stages_fetch(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+
+    let (status, item) = create_item(app.clone(), &token, "https://example.com/post").await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(item["url"], "https://example.com/post");
+    assert_eq!(item["user_id"], user_id.to_string());
+    assert_eq!(item["status"], "pending");
+    assert_eq!(item["tags"], serde_json::json!([]));
+    let item_id: uuid::Uuid = item["id"].as_str().unwrap().parse().unwrap();
+
+    // The title fetch is staged ahead of the full fetch
+    let jobs: Vec<(String, serde_json::Value)> =
+        sqlx::query_as("SELECT kind, payload FROM job_outbox ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    let kinds: Vec<&str> = jobs.iter().map(|(kind, _)| kind.as_str()).collect();
+    assert_eq!(kinds, vec!["fetch_title", "fetch_page"]);
+    assert_eq!(jobs[1].1["item_id"], item_id.to_string());
+
+    let hash: Option<String> = sqlx::query_scalar("SELECT url_hash FROM items WHERE id = $1")
+        .bind(item_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        hash,
+        capsule::repositories::url_hash("https://example.com/post")
+    );
+
+    let (_, list) = get_json(app, &token, "/v1/items").await;
+    assert_eq!(list["items"][0]["id"], item["id"]);
+}
+
+#[sqlx::test]
+async fn test_create_item_applies_save_settings_and_default_tags(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    sqlx::query(
+        r#"UPDATE users SET prefs = '{"default_tags": ["inbox"], "default_tags_sources": ["extension"]}' WHERE id = $1"#,
+    )
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (status, item) = create_item_with(
+        app.clone(),
+        &token,
+        serde_json::json!({
+            "url": "https://example.com/a",
+            "tags": ["rust", " inbox "],
+            "favorite": true,
+            "note": " for the talk ",
+            "source": "extension"
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(item["tags"], serde_json::json!(["inbox", "rust"]));
+    assert_eq!(item["favorite"], true);
+    let note: Option<String> =
+        sqlx::query_scalar("SELECT note FROM items WHERE id = $1::text::uuid")
+            .bind(item["id"].as_str().unwrap())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(note.as_deref(), Some("for the talk"));
+
+    // Saves from elsewhere don't get the default tags
+    let (_, item) = create_item(app.clone(), &token, "https://example.com/b").await;
+    assert_eq!(item["tags"], serde_json::json!([]));
+    assert_eq!(item["favorite"], false);
+
+    // A bad tag saves nothing
+    let (status, _) = create_item_with(
+        app,
+        &token,
+        serde_json::json!({ "url": "https://example.com/c", "tags": [""] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let items: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(items, 2);
+}
+
+#[sqlx::test]
+async fn test_create_item_saves_canonical_url(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+
+    let (status, first) = create_item(
+        app.clone(),
+        &token,
+        "HTTPS://Example.COM:443/post?b=2&utm_source=newsletter&a=1#comments",
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(first["url"], "https://example.com/post?a=1&b=2");
+
+    // The same page again is the item saved before
+    let (status, second) =
+        create_item(app, &token, "https://example.com/post?a=1&fbclid=xyz&b=2").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(second["id"], first["id"]);
+    assert_eq!(second["duplicate_of"], first["id"]);
+    assert!(first.get("duplicate_of").is_none());
+}
+
+#[sqlx::test]
+async fn test_create_item_merges_a_repeat_save(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (_, other_token) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+
+    let (_, first) = create_item_with(
+        app.clone(),
+        &token,
+        serde_json::json!({ "url": "https://example.com/post", "tags": ["rust"] }),
+    )
+    .await;
+    let (status, merged) = create_item_with(
+        app.clone(),
+        &token,
+        serde_json::json!({
+            "url": "https://example.com/post#intro",
+            "tags": ["talks"],
+            "favorite": true,
+            "refresh": true
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(merged["id"], first["id"]);
+    assert_eq!(merged["tags"], serde_json::json!(["rust", "talks"]));
+    assert_eq!(merged["favorite"], true);
+
+    // Asking for a refresh fetches the page again
+    let fetches: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM job_outbox WHERE kind = 'fetch_page'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(fetches, 2);
+
+    // Someone else saving the page gets their own item
+    let (status, theirs) = create_item(app, &other_token, "https://example.com/post").await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_ne!(theirs["id"], first["id"]);
+    let items: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(items, 2);
+}
+
+#[sqlx::test]
+async fn test_lookup_item_finds_a_saved_url(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (_, other_token) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    let (_, saved) = create_item(app.clone(), &token, "https://example.com/post?a=1").await;
+
+    // Equivalent URLs find it, as a save of them would
+    let (status, item) = get_json(
+        app.clone(),
+        &token,
+        "/v1/items/lookup?url=https%3A%2F%2Fexample.com%2Fpost%3Futm_source%3Dx%26a%3D1%23top",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["id"], saved["id"]);
+    assert_eq!(item["created_at"], saved["created_at"]);
+
+    let (status, _) = get_json(
+        app.clone(),
+        &token,
+        "/v1/items/lookup?url=https%3A%2F%2Fexample.com%2Fother",
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = get_json(
+        app.clone(),
+        &other_token,
+        "/v1/items/lookup?url=https%3A%2F%2Fexample.com%2Fpost%3Fa%3D1",
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = get_json(app, &token, "/v1/items/lookup?url=not-a-url").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["field"], "url");
+}
+
+#[sqlx::test]
+async fn test_create_item_rejects_bad_and_blocked_urls(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    sqlx::query(
+        "INSERT INTO domain_rules (domain, action, reason) VALUES ('spam.example', 'block', 'spam')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (status, _) = create_item(app.clone(), &token, "").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = create_item(app.clone(), &token, "file:///etc/passwd").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = create_item(app, &token, "https://news.spam.example/a").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "domain spam.example is blocked: spam");
+
+    // Nothing was saved or queued
+    let items: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let jobs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM job_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!((items, jobs), (0, 0));
+}
+
+#[sqlx::test]
+async fn test_get_item_returns_own_item_only(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (alice, alice_token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (_, bob_token) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    let item_id = helpers::insert_item(&pool, alice, "https://example.com/a").await;
+    let uri = format!("/v1/items/{}", item_id);
+
+    let (status, item) = get_json(app.clone(), &alice_token, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["id"], item_id.to_string());
+    assert_eq!(item["url"], "https://example.com/a");
+    // Content is only embedded on request
+    assert!(item.get("content").is_none());
+
+    let (status, body) = get_json(app.clone(), &bob_token, &uri).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "Item not found");
+
+    let (status, _) = get_json(
+        app,
+        &alice_token,
+        &format!("/v1/items/{}", uuid::Uuid::new_v4()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_random_item_picks_only_unread_items(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (_, bob_token) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    let unread = helpers::insert_item(&pool, user_id, "https://example.com/unread").await;
+    let pending = helpers::insert_item(&pool, user_id, "https://example.com/pending").await;
+    let read = helpers::insert_item(&pool, user_id, "https://example.com/read").await;
+    let archived = helpers::insert_item(&pool, user_id, "https://example.com/archived").await;
+    for (status, progress, item_id) in [
+        ("fetched", 0.3_f32, unread),
+        ("fetched", 1.0, read),
+        ("archived", 0.0, archived),
+    ] {
+        sqlx::query("UPDATE items SET status = $1::item_status, read_progress = $2 WHERE id = $3")
+            .bind(status)
+            .bind(progress)
+            .bind(item_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    for _ in 0..10 {
+        let (status, item) = get_json(app.clone(), &token, "/v1/items/random").await;
+        assert_eq!(status, StatusCode::OK);
+        let id = item["id"].as_str().unwrap();
+        assert!(id == unread.to_string() || id == pending.to_string());
+
+        let (status, item) = get_json(app.clone(), &token, "/v1/items/random?status=fetched").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(item["id"], unread.to_string());
+    }
+
+    let (status, _) = get_json(app.clone(), &token, "/v1/items/random?status=archived").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = get_json(app, &bob_token, "/v1/items/random").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "No unread items");
+}
+
+#[sqlx::test]
+async fn test_snoozed_items_are_hidden_until_they_wake(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let snoozed = helpers::insert_item(&pool, user_id, "https://example.com/snoozed").await;
+    helpers::insert_content(&pool, snoozed, &"word ".repeat(100), "en").await;
+    let woken = helpers::insert_item(&pool, user_id, "https://example.com/woken").await;
+    helpers::insert_content(&pool, woken, &"word ".repeat(100), "en").await;
+    for (item_id, offset) in [(snoozed, "1 hour"), (woken, "-1 hour")] {
+        sqlx::query("UPDATE items SET snoozed_until = NOW() + $1::interval WHERE id = $2")
+            .bind(offset)
+            .bind(item_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let ids = |list: &serde_json::Value| {
+        list["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    for uri in ["/v1/items", "/v1/items/quick-reads"] {
+        let (status, list) = get_json(app.clone(), &token, uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ids(&list), vec![woken.to_string()]);
+        assert_eq!(list["approximate_total"], 1);
+
+        let (_, list) = get_json(
+            app.clone(),
+            &token,
+            &format!("{}?include_snoozed=true", uri),
+        )
+        .await;
+        assert_eq!(ids(&list).len(), 2);
+    }
+
+    for _ in 0..10 {
+        let (status, item) = get_json(app.clone(), &token, "/v1/items/random").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(item["id"], woken.to_string());
+    }
+
+    // Still reachable directly while asleep
+    let (status, _) = get_json(app.clone(), &token, &format!("/v1/items/{}", snoozed)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    sqlx::query("UPDATE items SET read_progress = 1 WHERE id = $1")
+        .bind(woken)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (status, _) = get_json(app.clone(), &token, "/v1/items/random").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, item) = get_json(app, &token, "/v1/items/random?include_snoozed=true").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["id"], snoozed.to_string());
+}
+
+#[sqlx::test]
+async fn test_get_item_includes_content(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let extracted = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+    helpers::insert_content(&pool, extracted, "Hello from the article.", "en").await;
+    let pending = helpers::insert_item(&pool, user_id, "https://example.com/b").await;
+
+    let uri = format!("/v1/items/{}?include=content", extracted);
+    let (status, item) = get_json(app.clone(), &token, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["content"]["item_id"], extracted.to_string());
+    assert_eq!(item["content"]["clean_text"], "Hello from the article.");
+    assert_eq!(item["content"]["lang"], "en");
+    assert_eq!(item["content"]["too_large"], false);
+
+    sqlx::query("UPDATE contents SET clean_html = '<p>Hello</p>' WHERE item_id = $1")
+        .bind(extracted)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (_, item) = get_json(app.clone(), &token, &uri).await;
+    assert_eq!(item["content"]["clean_html"], "<p>Hello</p>");
+    let uri = format!("/v1/items/{}?include=content&fields=text", extracted);
+    let (status, item) = get_json(app.clone(), &token, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["content"]["clean_text"], "Hello from the article.");
+    assert_eq!(item["content"]["clean_html"], serde_json::Value::Null);
+    assert_eq!(item["content"]["lang"], serde_json::Value::Null);
+
+    // Not extracted yet: the key is there but null
+    let uri = format!("/v1/items/{}?include=content", pending);
+    let (status, item) = get_json(app.clone(), &token, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["content"], serde_json::Value::Null);
+    assert!(item.as_object().unwrap().contains_key("content"));
+
+    let uri = format!("/v1/items/{}?include=highlights", extracted);
+    let (status, body) = get_json(app.clone(), &token, &uri).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["field"], "include");
+
+    let uri = format!("/v1/items/{}?include=content&fields=raw", extracted);
+    let (status, body) = get_json(app, &token, &uri).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["field"], "fields");
+}
+
+async fn patch_item(
+    app: axum::Router,
+    token: &str,
+    id: uuid::Uuid,
+    body: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/v1/items/{}", id))
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[sqlx::test]
+async fn test_update_item_applies_present_fields(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+    sqlx::query("UPDATE items SET updated_at = NOW() - INTERVAL '1 day' WHERE id = $1")
+        .bind(item_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (_, before) = get_json(app.clone(), &token, &format!("/v1/items/{}", item_id)).await;
+
+    let (status, item) = patch_item(
+        app.clone(),
+        &token,
+        item_id,
+        serde_json::json!({"title": "  Renamed  "}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["title"], "Renamed");
+    assert_eq!(item["status"], before["status"]);
+    assert_ne!(item["updated_at"], before["updated_at"]);
+
+    let (status, item) = patch_item(
+        app.clone(),
+        &token,
+        item_id,
+        serde_json::json!({"status": "archived"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["status"], "archived");
+    assert_eq!(item["title"], "Renamed");
+
+    let (status, item) = patch_item(
+        app.clone(),
+        &token,
+        item_id,
+        serde_json::json!({"status": "fetched"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["status"], "fetched");
+
+    // Reading sets the item apart from the rest of the backlog
+    helpers::insert_item(&pool, user_id, "https://example.com/b").await;
+    let (status, item) = patch_item(
+        app.clone(),
+        &token,
+        item_id,
+        serde_json::json!({"status": "reading"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["status"], "reading");
+    let (_, list) = get_json(app, &token, "/v1/items?status=reading").await;
+    assert_eq!(urls(&list), vec!["https://example.com/a"]);
+}
+
+#[sqlx::test]
+async fn test_update_item_rejects_bad_updates(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (alice, alice_token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (_, bob_token) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    let item_id = helpers::insert_item(&pool, alice, "https://example.com/a").await;
+
+    let (status, _) = patch_item(
+        app.clone(),
+        &bob_token,
+        item_id,
+        serde_json::json!({"title": "Mine now"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = patch_item(
+        app.clone(),
+        &alice_token,
+        item_id,
+        serde_json::json!({"title": " "}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    patch_item(
+        app.clone(),
+        &alice_token,
+        item_id,
+        serde_json::json!({"status": "archived"}),
+    )
+    .await;
+    // Archived items can't go back to pending, and nothing else changes
+    let (status, _) = patch_item(
+        app.clone(),
+        &alice_token,
+        item_id,
+        serde_json::json!({"title": "Renamed", "status": "pending"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (_, item) = get_json(app, &alice_token, &format!("/v1/items/{}", item_id)).await;
+    assert_eq!(item["status"], "archived");
+    assert_ne!(item["title"], "Renamed");
+}
+
+async fn edit_item(
+    app: axum::Router,
+    token: &str,
+    method: &str,
+    uri: &str,
+    if_match: Option<&str>,
+    body: serde_json::Value,
+) -> (StatusCode, Option<String>, serde_json::Value) {
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json");
+    if let Some(etag) = if_match {
+        builder = builder.header(IF_MATCH, etag);
+    }
+    let response = app
+        .oneshot(builder.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .map(|etag| etag.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        etag,
+        serde_json::from_slice(&body).unwrap_or_default(),
+    )
+}
+
+#[sqlx::test]
+async fn test_item_edits_against_an_older_version_conflict(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+    let item_uri = format!("/v1/items/{}", item_id);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(&item_uri)
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.headers()[ETAG], "\"v1\"");
+
+    let (status, etag, item) = edit_item(
+        app.clone(),
+        &token,
+        "PATCH",
+        &item_uri,
+        Some("\"v1\""),
+        serde_json::json!({"title": "Mine"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(etag.as_deref(), Some("\"v2\""));
+    assert_eq!(item["version"], 2);
+
+    // A second client still holding v1 gets the current item instead
+    let (status, etag, item) = edit_item(
+        app.clone(),
+        &token,
+        "PATCH",
+        &item_uri,
+        Some("\"v1\""),
+        serde_json::json!({"title": "Theirs"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(etag.as_deref(), Some("\"v2\""));
+    assert_eq!(item["title"], "Mine");
+
+    let (status, etag, _) = edit_item(
+        app.clone(),
+        &token,
+        "PUT",
+        &format!("{}/note", item_uri),
+        Some("\"v2\""),
+        serde_json::json!({"note": "Worth a reread"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(etag.as_deref(), Some("\"v3\""));
+
+    let (status, _, item) = edit_item(
+        app.clone(),
+        &token,
+        "PUT",
+        &format!("{}/progress", item_uri),
+        Some("\"v2\""),
+        serde_json::json!({"progress": 0.5}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(item["version"], 3);
+
+    // Without If-Match, or with `*`, the edit goes through regardless
+    let (status, etag, _) = edit_item(
+        app.clone(),
+        &token,
+        "PUT",
+        &format!("{}/progress", item_uri),
+        Some("*"),
+        serde_json::json!({"progress": 0.5}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(etag.as_deref(), Some("\"v4\""));
+
+    let (status, _, item) = edit_item(
+        app.clone(),
+        &token,
+        "PATCH",
+        &item_uri,
+        None,
+        serde_json::json!({"status": "archived"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["version"], 5);
+
+    // Fetches don't bump the version
+    sqlx::query("UPDATE items SET processing_state = 'ready' WHERE id = $1")
+        .bind(item_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (_, item) = get_json(app, &token, &item_uri).await;
+    assert_eq!(item["version"], 5);
+}
+
+#[sqlx::test]
+async fn test_list_items_by_reading_time(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let short = helpers::insert_item(&pool, user_id, "https://example.com/short").await;
+    helpers::insert_content(&pool, short, &"word ".repeat(100), "en").await;
+    let medium = helpers::insert_item(&pool, user_id, "https://example.com/medium").await;
+    helpers::insert_content(&pool, medium, &"word ".repeat(2_000), "en").await;
+    let long = helpers::insert_item(&pool, user_id, "https://example.com/long").await;
+    helpers::insert_content(&pool, long, &"word ".repeat(6_000), "en").await;
+    // Not extracted yet, so it's in no reading time list
+    helpers::insert_item(&pool, user_id, "https://example.com/pending").await;
+
+    let ids = |list: &serde_json::Value| {
+        let mut ids: Vec<String> = list["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    };
+    let sorted = |mut expected: Vec<uuid::Uuid>| {
+        expected.sort();
+        expected
+            .iter()
+            .map(uuid::Uuid::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    let (status, list) = get_json(app.clone(), &token, "/v1/items/quick-reads").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&list), sorted(vec![short]));
+    assert_eq!(list["items"][0]["reading_time_minutes"], 1);
+
+    let (status, list) = get_json(app.clone(), &token, "/v1/items/long-reads").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ids(&list), sorted(vec![long]));
+
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?reading_time=%3C10").await;
+    assert_eq!(ids(&list), sorted(vec![short, medium]));
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?reading_time=%3E5").await;
+    assert_eq!(ids(&list), sorted(vec![medium, long]));
+
+    // Short reads by the stored reading time, in whole minutes
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?max_reading_time=9").await;
+    assert_eq!(ids(&list), sorted(vec![short, medium]));
+    let (_, list) = get_json(app.clone(), &token, "/v1/items?max_reading_time=8").await;
+    assert_eq!(ids(&list), sorted(vec![short]));
+    assert_eq!(list["items"][0]["word_count"], 100);
+
+    // Other filters still apply on the convenience views
+    let (_, list) = get_json(app.clone(), &token, "/v1/items/quick-reads?status=archived").await;
+    assert_eq!(list["items"], serde_json::json!([]));
+
+    let (status, body) = get_json(app.clone(), &token, "/v1/items?reading_time=5").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["field"], "reading_time");
+    let (status, body) = get_json(app, &token, "/v1/items?max_reading_time=0").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["field"], "max_reading_time");
+}
+
+async fn delete_item(app: axum::Router, token: &str, id: uuid::Uuid) -> StatusCode {
+    app.oneshot(
+        Request::builder()
+            .method("DELETE")
+            .uri(format!("/v1/items/{}", id))
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+    .status()
+}
+
+#[sqlx::test]
+async fn test_delete_item_removes_item_and_pending_work(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+    let kept = helpers::insert_item(&pool, user_id, "https://example.com/b").await;
+    helpers::insert_content(&pool, item_id, "Some text", "en").await;
+    let tag_id: uuid::Uuid =
+        sqlx::query_scalar("INSERT INTO tags (user_id, name) VALUES ($1, 'rust') RETURNING id")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    sqlx::query("INSERT INTO item_tags (item_id, tag_id) VALUES ($1, $2)")
+        .bind(item_id)
+        .bind(tag_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    for id in [item_id, kept] {
+        sqlx::query("INSERT INTO jobs (kind, payload, run_at) VALUES ('fetch_page', $1, NOW())")
+            .bind(serde_json::json!({"item_id": id}))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO job_outbox (kind, payload) VALUES ('fetch_title', $1)")
+            .bind(serde_json::json!({"item_id": id}))
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(
+        delete_item(app.clone(), &token, item_id).await,
+        StatusCode::NO_CONTENT
+    );
+
+    let count = |sql: &'static str| {
+        let pool = pool.clone();
+        async move {
+            sqlx::query_scalar::<_, i64>(sql)
+                .bind(item_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        }
+    };
+    assert_eq!(count("SELECT COUNT(*) FROM items WHERE id = $1").await, 0);
+    assert_eq!(
+        count("SELECT COUNT(*) FROM contents WHERE item_id = $1").await,
+        0
+    );
+    assert_eq!(
+        count("SELECT COUNT(*) FROM item_tags WHERE item_id = $1").await,
+        0
+    );
+    assert_eq!(
+        count("SELECT COUNT(*) FROM jobs WHERE payload->>'item_id' = $1::text").await,
+        0
+    );
+    assert_eq!(
+        count("SELECT COUNT(*) FROM job_outbox WHERE payload->>'item_id' = $1::text").await,
+        0
+    );
+
+    // The other item and its jobs are untouched
+    let remaining: (i64, i64) =
+        sqlx::query_as("SELECT (SELECT COUNT(*) FROM jobs), (SELECT COUNT(*) FROM job_outbox)")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(remaining, (1, 1));
+    let (status, _) = get_json(app.clone(), &token, &format!("/v1/items/{}", kept)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Already gone
+    assert_eq!(
+        delete_item(app, &token, item_id).await,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[sqlx::test]
+async fn test_delete_item_of_another_user_is_not_found(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (alice, _) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (_, bob_token) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    let item_id = helpers::insert_item(&pool, alice, "https://example.com/a").await;
+
+    assert_eq!(
+        delete_item(app, &bob_token, item_id).await,
+        StatusCode::NOT_FOUND
+    );
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM items WHERE id = $1)")
+        .bind(item_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(exists);
+}
+
+async fn bulk(
+    app: axum::Router,
+    token: &str,
+    operations: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/items/bulk")
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "operations": operations }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[sqlx::test]
+async fn test_bulk_operations_report_each_item(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (other_id, _) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    let a = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+    let b = helpers::insert_item(&pool, user_id, "https://example.com/b").await;
+    let c = helpers::insert_item(&pool, user_id, "https://example.com/c").await;
+    let theirs = helpers::insert_item(&pool, other_id, "https://example.com/theirs").await;
+    sqlx::query("INSERT INTO jobs (kind, payload, run_at) VALUES ('fetch_page', $1, NOW())")
+        .bind(serde_json::json!({"item_id": c}))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (status, body) = bulk(
+        app.clone(),
+        &token,
+        serde_json::json!([
+            {"op": "tag", "item_ids": [a, b, theirs], "tag": " rust "},
+            {"op": "favorite", "item_ids": [a]},
+            {"op": "archive", "item_ids": [b]},
+            {"op": "delete", "item_ids": [c, theirs]},
+            {"op": "archive", "item_ids": [c]},
+        ]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let results: Vec<(u64, String, &str)> = body["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| {
+            (
+                result["operation"].as_u64().unwrap(),
+                result["item_id"].as_str().unwrap().to_string(),
+                result["status"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    let expected: Vec<(u64, String, &str)> = vec![
+        (0, a.to_string(), "ok"),
+        (0, b.to_string(), "ok"),
+        (0, theirs.to_string(), "not_found"),
+        (1, a.to_string(), "ok"),
+        (2, b.to_string(), "ok"),
+        (3, c.to_string(), "ok"),
+        (3, theirs.to_string(), "not_found"),
+        // Already deleted by the operation before
+        (4, c.to_string(), "not_found"),
+    ];
+    assert_eq!(results, expected);
+
+    let (_, item) = get_json(app.clone(), &token, &format!("/v1/items/{}", a)).await;
+    assert_eq!(item["tags"], serde_json::json!(["rust"]));
+    assert_eq!(item["favorite"], true);
+    let (_, item) = get_json(app.clone(), &token, &format!("/v1/items/{}", b)).await;
+    assert_eq!(item["status"], "archived");
+    assert_eq!(item["favorite"], false);
+    let (status, _) = get_json(app, &token, &format!("/v1/items/{}", c)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let jobs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs WHERE payload->>'item_id' = $1")
+        .bind(c.to_string())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(jobs, 0);
+
+    // Someone else's item is untouched, and no tag was made for them
+    let theirs_tags: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tags WHERE user_id = $1")
+        .bind(other_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(theirs_tags, 0);
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM items WHERE id = $1)")
+        .bind(theirs)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(exists);
+}
+
+#[sqlx::test]
+async fn test_bulk_rejects_invalid_requests(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let a = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+
+    let (status, _) = bulk(app.clone(), &token, serde_json::json!([])).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // An invalid operation anywhere means none are applied
+    let (status, _) = bulk(
+        app.clone(),
+        &token,
+        serde_json::json!([
+            {"op": "archive", "item_ids": [a]},
+            {"op": "tag", "item_ids": [a], "tag": ""},
+        ]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (_, item) = get_json(app, &token, &format!("/v1/items/{}", a)).await;
+    assert_eq!(item["status"], "pending");
+}
+
+async fn triage(
+    app: axum::Router,
+    token: &str,
+    decisions: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/items/triage")
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "decisions": decisions }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[sqlx::test]
+async fn test_triage_applies_decisions_and_records_session(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let a = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+    let b = helpers::insert_item(&pool, user_id, "https://example.com/b").await;
+    let c = helpers::insert_item(&pool, user_id, "https://example.com/c").await;
+    let d = helpers::insert_item(&pool, user_id, "https://example.com/d").await;
+    let e = helpers::insert_item(&pool, user_id, "https://example.com/e").await;
+
+    let (status, body) = triage(
+        app.clone(),
+        &token,
+        serde_json::json!([
+            {"id": a, "action": "archive"},
+            {"id": b, "action": "keep"},
+            {"id": c, "action": "tag:later"},
+            {"id": d, "action": "delete"},
+            {"id": e, "action": "tag:later"},
+        ]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body,
+        serde_json::json!({"archived": 1, "kept": 1, "tagged": 2, "deleted": 1})
+    );
+
+    let (_, item) = get_json(app.clone(), &token, &format!("/v1/items/{}", a)).await;
+    assert_eq!(item["status"], "archived");
+    let (_, item) = get_json(app.clone(), &token, &format!("/v1/items/{}", b)).await;
+    assert_eq!(item["status"], "pending");
+    let (_, item) = get_json(app.clone(), &token, &format!("/v1/items/{}", e)).await;
+    assert_eq!(item["tags"], serde_json::json!(["later"]));
+    let (status, _) = get_json(app, &token, &format!("/v1/items/{}", d)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let session: (i32, i32, i32, i32) = sqlx::query_as(
+        "SELECT archived, kept, tagged, deleted FROM triage_sessions WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(session, (1, 1, 2, 1));
+}
+
+#[sqlx::test]
+async fn test_triage_is_all_or_nothing(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (other_id, _) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    let a = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+    let theirs = helpers::insert_item(&pool, other_id, "https://example.com/theirs").await;
+
+    let (status, _) = triage(
+        app.clone(),
+        &token,
+        serde_json::json!([
+            {"id": a, "action": "archive"},
+            {"id": theirs, "action": "delete"},
+        ]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = triage(
+        app.clone(),
+        &token,
+        serde_json::json!([
+            {"id": a, "action": "archive"},
+            {"id": a, "action": "keep"},
+        ]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = triage(
+        app.clone(),
+        &token,
+        serde_json::json!([{"id": a, "action": "snooze"}]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, item) = get_json(app, &token, &format!("/v1/items/{}", a)).await;
+    assert_eq!(item["status"], "pending");
+    let sessions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM triage_sessions")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(sessions, 0);
+}
@@ -3,7 +3,10 @@ use serde_json::json;
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
-use capsule::{entities::JobStatus, jobs::JobRepository};
+use capsule::{
+    entities::JobStatus,
+    jobs::{JobRepository, Outbox},
+};
 
 /// Test that basic job repository operations work correctly
 #[sqlx::test]
@@ -211,3 +214,117 @@ async fn test_multiple_job_processing(pool: Pool<Postgres>) {
         assert_eq!(job.status, Some("succeeded".to_string()));
     }
 }
+
+/// Test that outbox jobs only reach the queue when their transaction commits
+#[sqlx::test]
+async fn test_outbox_relays_only_committed_jobs(pool: Pool<Postgres>) {
+    let mut tx = pool.begin().await.expect("Failed to begin transaction");
+    Outbox::enqueue(&mut tx, "test_job", json!({"rolled": "back"}), None)
+        .await
+        .expect("Failed to stage job");
+    tx.rollback().await.expect("Failed to roll back");
+
+    let mut tx = pool.begin().await.expect("Failed to begin transaction");
+    let job_id = Outbox::enqueue(&mut tx, "test_job", json!({"test": "data"}), None)
+        .await
+        .expect("Failed to stage job");
+
+    // Not visible to the relay until committed
+    assert_eq!(Outbox::relay(&pool, 10).await.unwrap(), 0);
+    tx.commit().await.expect("Failed to commit");
+
+    assert_eq!(Outbox::relay(&pool, 10).await.unwrap(), 1);
+    assert_eq!(Outbox::relay(&pool, 10).await.unwrap(), 0);
+
+    let jobs = JobRepository::fetch_due_jobs(&pool, 10, Uuid::new_v4(), 300)
+        .await
+        .expect("Failed to fetch due jobs");
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].id, job_id);
+    assert_eq!(jobs[0].payload, json!({"test": "data"}));
+
+    let staged: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM job_outbox")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(staged, 0);
+}
+
+/// Test that the relay respects its batch size
+#[sqlx::test]
+async fn test_outbox_relay_batches(pool: Pool<Postgres>) {
+    let mut conn = pool.acquire().await.expect("Failed to acquire connection");
+    for i in 0..5 {
+        Outbox::enqueue(&mut conn, "test_job", json!({"n": i}), None)
+            .await
+            .expect("Failed to stage job");
+    }
+
+    assert_eq!(Outbox::relay(&pool, 2).await.unwrap(), 2);
+    assert_eq!(Outbox::relay(&pool, 10).await.unwrap(), 3);
+}
+
+/// Test that malformed payloads for known job kinds are refused at enqueue
+#[sqlx::test]
+async fn test_enqueue_rejects_invalid_payload(pool: Pool<Postgres>) {
+    let result =
+        JobRepository::enqueue(&pool, "fetch_page", json!({"item_id": 42}), None, None).await;
+    assert!(result.is_err());
+
+    let mut tx = pool.begin().await.expect("Failed to begin transaction");
+    let staged = Outbox::enqueue(&mut tx, "fetch_page", json!({}), None).await;
+    assert!(staged.is_err());
+    tx.rollback().await.expect("Failed to roll back");
+
+    let jobs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(jobs, 0);
+
+    JobRepository::enqueue(
+        &pool,
+        "fetch_page",
+        json!({"item_id": Uuid::new_v4()}),
+        None,
+        None,
+    )
+    .await
+    .expect("Valid payload should be accepted");
+}
+
+/// Test that released jobs are immediately available to other workers
+#[sqlx::test]
+async fn test_release_unstarted_jobs(pool: Pool<Postgres>) {
+    for i in 0..3 {
+        JobRepository::enqueue(&pool, "test_job", json!({"index": i}), None, None)
+            .await
+            .expect("Failed to enqueue job");
+    }
+
+    let worker_id = Uuid::new_v4();
+    let jobs = JobRepository::fetch_due_jobs(&pool, 3, worker_id, 300)
+        .await
+        .expect("Failed to fetch due jobs");
+    let ids: Vec<Uuid> = jobs.iter().map(|job| job.id).collect();
+
+    // Another worker can't release leases it doesn't hold
+    let released = JobRepository::release(&pool, Uuid::new_v4(), &ids)
+        .await
+        .expect("Failed to release jobs");
+    assert_eq!(released, 0);
+
+    let released = JobRepository::release(&pool, worker_id, &ids[..2])
+        .await
+        .expect("Failed to release jobs");
+    assert_eq!(released, 2);
+
+    let other_worker = Uuid::new_v4();
+    let jobs = JobRepository::fetch_due_jobs(&pool, 10, other_worker, 300)
+        .await
+        .expect("Failed to fetch due jobs");
+    let refetched: Vec<Uuid> = jobs.iter().map(|job| job.id).collect();
+    assert_eq!(refetched.len(), 2);
+    assert!(refetched.iter().all(|id| ids[..2].contains(id)));
+    assert!(jobs.iter().all(|job| job.attempts == 0));
+}
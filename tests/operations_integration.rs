@@ -0,0 +1,87 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::{
+    entities::{OperationErrorSample, OperationKind, OperationState},
+    repositories::{MAX_OPERATION_ERROR_SAMPLES, OperationRepository},
+};
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn get_operation(app: &Router, token: &str, id: Uuid) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/v1/operations/{}", id))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[sqlx::test]
+async fn test_operation_progress(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let repo = OperationRepository::new(&pool);
+    let operation = repo.create(user_id, OperationKind::Import).await.unwrap();
+
+    let (status, body) = get_operation(&app, &token, operation.id).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["state"], "pending");
+    assert!(body["total"].is_null());
+
+    // What an import handler does as it works through its rows
+    repo.start(operation.id, Some(20)).await.unwrap();
+    repo.advance(operation.id, 15).await.unwrap();
+    for n in 0..MAX_OPERATION_ERROR_SAMPLES + 2 {
+        let sample = OperationErrorSample {
+            subject: format!("https://example.com/{}", n),
+            error: "bad URL".to_string(),
+        };
+        repo.record_error(operation.id, &sample).await.unwrap();
+    }
+
+    let (_, body) = get_operation(&app, &token, operation.id).await;
+    assert_eq!(body["state"], "running");
+    assert_eq!(body["processed"], 15);
+    assert_eq!(body["total"], 20);
+    assert_eq!(body["error_count"], MAX_OPERATION_ERROR_SAMPLES + 2);
+    let samples = body["error_samples"].as_array().unwrap();
+    assert_eq!(samples.len(), MAX_OPERATION_ERROR_SAMPLES as usize);
+    assert_eq!(samples[0]["subject"], "https://example.com/0");
+
+    repo.advance(operation.id, 5).await.unwrap();
+    repo.finish(operation.id, OperationState::Succeeded)
+        .await
+        .unwrap();
+    let (_, body) = get_operation(&app, &token, operation.id).await;
+    assert_eq!(body["state"], "succeeded");
+    assert!(body["finished_at"].is_string());
+}
+
+#[sqlx::test]
+async fn test_operation_not_visible_to_other_users(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (alice, _) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (_, bob_token) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    let operation = OperationRepository::new(&pool)
+        .create(alice, OperationKind::Export)
+        .await
+        .unwrap();
+
+    let (status, _) = get_operation(&app, &bob_token, operation.id).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = get_operation(&app, &bob_token, Uuid::new_v4()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,287 @@
+//! The save pipeline end to end: the API saves an item, a worker fetches its
+//! page from a mock site, extracts it and runs the follow-up jobs, and the
+//! API then serves the clean article.
+
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{
+        Method, Request, StatusCode,
+        header::{AUTHORIZATION, ETAG, IF_MATCH},
+    },
+};
+use capsule::jobs::{
+    ClassifyTopicsJobHandler, EmbedContentJobHandler, FetchPageJobHandler, FetchSiteIconJobHandler,
+    FetchTitleJobHandler, JobRegistry, SummarizeContentJobHandler, WorkerConfig, WorkerSupervisor,
+};
+use serde_json::{Value, json};
+use sqlx::{PgPool, Pool, Postgres};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tower::ServiceExt;
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, path},
+};
+
+const ARTICLE: &str = include_str!("../benches/fixtures/longform.html");
+
+/// A worker running the jobs a save sets off, as the worker binary
+/// registers them without any providers configured
+fn start_worker(pool: &PgPool) -> (CancellationToken, JoinHandle<anyhow::Result<()>>) {
+    let mut registry = JobRegistry::new();
+    registry.register(FetchTitleJobHandler::new());
+    registry.register(FetchPageJobHandler::new());
+    registry.register(FetchSiteIconJobHandler::new());
+    registry.register(SummarizeContentJobHandler::new());
+    registry.register(EmbedContentJobHandler::new(None));
+    registry.register(ClassifyTopicsJobHandler::new());
+
+    let config = WorkerConfig {
+        poll_interval_ms: 10,
+        outbox_relay_interval_ms: 10,
+        base_backoff_secs: 0,
+        ..WorkerConfig::default()
+    };
+    let supervisor = WorkerSupervisor::new(pool.clone(), registry, config);
+    let token = supervisor.shutdown_token();
+    (token, tokio::spawn(supervisor.run()))
+}
+
+/// Wait until nothing is staged, queued or running
+async fn wait_for_idle_queue(pool: &PgPool) {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    loop {
+        let pending: i64 = sqlx::query_scalar(
+            r#"
+            SELECT (SELECT COUNT(*) FROM job_outbox)
+                 + (SELECT COUNT(*) FROM jobs
+                    WHERE status IN ('queued'::job_status, 'running'::job_status))
+            "#,
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        if pending == 0 {
+            return;
+        }
+        assert!(Instant::now() < deadline, "jobs did not finish in time");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+async fn send(
+    app: &Router,
+    token: &str,
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// A mock site serving the article at `/small-decisions`
+async fn serve_article() -> MockServer {
+    let server = helpers::start_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/small-decisions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(ARTICLE)
+                .insert_header("Content-Type", "text/html; charset=utf-8"),
+        )
+        .mount(&server)
+        .await;
+    server
+}
+
+async fn run_worker_until_idle(pool: &PgPool) {
+    let (worker, handle) = start_worker(pool);
+    wait_for_idle_queue(pool).await;
+    worker.cancel();
+    handle.await.unwrap().unwrap();
+}
+
+#[sqlx::test]
+async fn test_saved_item_is_fetched_extracted_and_served(pool: Pool<Postgres>) {
+    let server = serve_article().await;
+
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let url = format!("{}/small-decisions", server.uri());
+
+    let (status, item) = send(
+        &app,
+        &token,
+        Method::POST,
+        "/v1/items",
+        Some(json!({ "url": url })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(item["status"], "pending");
+    assert_eq!(item["processing_state"], "pending");
+    let item_id = item["id"].as_str().unwrap().to_string();
+
+    run_worker_until_idle(&pool).await;
+
+    let failed: Vec<(String, Option<String>)> =
+        sqlx::query_as("SELECT kind, last_error FROM jobs WHERE status <> 'succeeded'::job_status")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert!(failed.is_empty(), "jobs didn't succeed: {failed:?}");
+
+    let (status, item) = send(
+        &app,
+        &token,
+        Method::GET,
+        &format!("/v1/items/{}", item_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["status"], "fetched");
+    assert_eq!(item["processing_state"], "ready");
+    assert_eq!(item["last_error"], Value::Null);
+    assert!(
+        item["title"]
+            .as_str()
+            .unwrap()
+            .contains("How Small Decisions Add Up to Outages")
+    );
+    assert!(item["word_count"].as_i64().unwrap() > 0);
+
+    let (status, history) = send(
+        &app,
+        &token,
+        Method::GET,
+        &format!("/v1/items/{}/transitions", item_id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let steps: Vec<(&str, &str)> = history["transitions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| {
+            (
+                t["from_state"].as_str().unwrap(),
+                t["to_state"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            ("pending", "fetching"),
+            ("fetching", "extracting"),
+            ("extracting", "ready")
+        ]
+    );
+
+    let (status, batch) = send(
+        &app,
+        &token,
+        Method::POST,
+        "/v1/items/content:batchGet",
+        Some(json!({ "item_ids": [item_id] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(batch["missing"], json!([]));
+    let content = &batch["contents"][0];
+    let text = content["clean_text"].as_str().unwrap();
+    assert!(text.contains("Software systems rarely fail for a single reason"));
+    // Page chrome and scripts are stripped
+    assert!(!text.contains("analytics"));
+    assert!(!text.contains("Archive"));
+    let html = content["clean_html"].as_str().unwrap();
+    assert!(html.contains("<p>"));
+    assert!(!html.contains("<script"));
+    assert!(!html.contains("<nav"));
+    assert_eq!(content["lang"], "en");
+    assert!(content["summary"].as_str().is_some_and(|s| !s.is_empty()));
+}
+
+#[sqlx::test]
+async fn test_edit_against_the_saved_version_survives_the_fetch(pool: Pool<Postgres>) {
+    let server = serve_article().await;
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+
+    let (status, item) = send(
+        &app,
+        &token,
+        Method::POST,
+        "/v1/items",
+        Some(json!({
+            "url": format!("{}/small-decisions", server.uri()),
+            "favorite": true,
+            "note": "Read before the retro",
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    // Created, starred and noted in the save's transaction
+    assert_eq!(item["version"], 3);
+    let item_id = item["id"].as_str().unwrap().to_string();
+
+    // Fetching fills in the title and moves the item to fetched, neither
+    // of which is the user's edit
+    run_worker_until_idle(&pool).await;
+    let (_, fetched) = send(
+        &app,
+        &token,
+        Method::GET,
+        &format!("/v1/items/{}", item_id),
+        None,
+    )
+    .await;
+    assert_eq!(fetched["status"], "fetched");
+    assert!(fetched["title"].is_string());
+    assert_eq!(fetched["version"], 3);
+
+    let request = Request::builder()
+        .method(Method::PATCH)
+        .uri(format!("/v1/items/{}", item_id))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header(IF_MATCH, "\"v3\"")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "status": "archived" }).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[ETAG], "\"v4\"");
+
+    // Saving it again returns the version the merge left it at
+    let (status, item) = send(
+        &app,
+        &token,
+        Method::POST,
+        "/v1/items",
+        Some(json!({
+            "url": format!("{}/small-decisions", server.uri()),
+            "note": "Read it",
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["version"], 5);
+}
@@ -0,0 +1,112 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::{entities::DomainRuleAction, repositories::DomainRulesRepository};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use wiremock::{
+    Mock, ResponseTemplate,
+    matchers::{method, path},
+};
+
+fn article() -> String {
+    let paragraphs =
+        "<p>The quick brown fox jumps over the lazy dog and keeps on running far away.</p>"
+            .repeat(60);
+    format!(
+        r#"<html><head>
+        <title>Fox News | Example</title>
+        <meta property="og:title" content="The Fox">
+        <meta property="og:site_name" content="Example">
+        <meta property="og:image" content="/fox.jpg">
+        </head><body><article><h1>The Fox</h1>{}</article></body></html>"#,
+        paragraphs
+    )
+}
+
+async fn preview(app: &Router, token: &str, url: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/items/preview")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "url": url }).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[sqlx::test]
+async fn test_preview_fetches_once(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let mock_server = helpers::start_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/fox"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(article())
+                .insert_header("Content-Type", "text/html; charset=utf-8"),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let url = format!("{}/fox", mock_server.uri());
+    let (status, body) = preview(&app, &token, &url).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["url"], url.as_str());
+    assert_eq!(body["site"], "Example");
+    assert_eq!(body["lead_image"], format!("{}/fox.jpg", mock_server.uri()));
+    assert!(body["title"].as_str().unwrap().contains("Fox"));
+    assert!(body["reading_time_minutes"].as_i64().unwrap() >= 1);
+
+    // Served from the cache; the mock verifies it was only fetched once
+    let (status, again) = preview(&app, &token, &url).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(again, body);
+}
+
+#[sqlx::test]
+async fn test_preview_rejects_bad_and_blocked_urls(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (admin_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let mock_server = helpers::start_mock_server().await;
+
+    let (status, _) = preview(&app, &token, "ftp://example.com/file").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    DomainRulesRepository::new(&pool)
+        .upsert("127.0.0.1", DomainRuleAction::Block, None, admin_id)
+        .await
+        .unwrap();
+    let (status, body) = preview(&app, &token, &format!("{}/fox", mock_server.uri())).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(body["error"].as_str().unwrap().contains("blocked"));
+}
+
+#[sqlx::test]
+async fn test_preview_reports_unreachable_pages(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let mock_server = helpers::start_mock_server().await;
+
+    Mock::given(method("GET"))
+        .and(path("/gone"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+
+    let (status, _) = preview(&app, &token, &format!("{}/gone", mock_server.uri())).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+}
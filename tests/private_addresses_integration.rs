@@ -0,0 +1,99 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::fetcher::{FetchError, UrlPolicyError, fetch, url_policy::ENV_URL_ALLOWED_PORTS};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use std::sync::Once;
+use tower::ServiceExt;
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, path},
+};
+
+// Unlike the other fetching tests, this binary leaves private addresses
+// disallowed, so it starts its mock servers itself rather than through
+// `helpers::start_mock_server`
+static ALLOW_ANY_PORT: Once = Once::new();
+
+async fn start_loopback_server() -> MockServer {
+    ALLOW_ANY_PORT.call_once(|| unsafe {
+        std::env::set_var(ENV_URL_ALLOWED_PORTS, "*");
+    });
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/internal"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("<html><head><title>Internal</title></head></html>")
+                .insert_header("Content-Type", "text/html; charset=utf-8"),
+        )
+        .expect(0)
+        .mount(&server)
+        .await;
+    server
+}
+
+async fn preview(app: &Router, token: &str, url: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/items/preview")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "url": url }).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[sqlx::test]
+async fn test_preview_refuses_loopback(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let server = start_loopback_server().await;
+    let port = server.address().port();
+
+    let (status, body) = preview(&app, &token, &format!("http://127.0.0.1:{port}/internal")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(
+        body["error"]
+            .as_str()
+            .unwrap()
+            .contains("not a public address")
+    );
+
+    // A host name that resolves to loopback is refused once resolved
+    let (status, body) = preview(&app, &token, &format!("http://localhost:{port}/internal")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(
+        body["error"]
+            .as_str()
+            .unwrap()
+            .contains("not a public address")
+    );
+}
+
+#[tokio::test]
+async fn test_fetch_refuses_private_addresses() {
+    let server = start_loopback_server().await;
+    let port = server.address().port();
+
+    for url in [
+        format!("http://127.0.0.1:{port}/internal"),
+        format!("http://localhost:{port}/internal"),
+        "http://169.254.169.254/latest/meta-data/".to_string(),
+    ] {
+        match fetch(&url).await {
+            Err(FetchError::UrlNotAllowed(UrlPolicyError::PrivateAddress(_))) => {}
+            other => panic!("{url}: expected a private address error, got {other:?}"),
+        }
+    }
+}
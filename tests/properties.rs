@@ -0,0 +1,107 @@
+//! Property tests of the policies everything else leans on: URL
+//! canonicalization and job retry backoff. Run with `--features fuzz`.
+#![cfg(feature = "fuzz")]
+
+use capsule::{
+    jobs::{MAX_BACKOFF_EXPONENT, backoff_base_delay, backoff_bounds, calculate_backoff_delay},
+    urlnorm::{TRACKING_PARAMS, canonicalize, is_tracking_param},
+};
+use proptest::prelude::*;
+use url::Url;
+
+/// Query parameters, some of them tracking ones
+fn params() -> impl Strategy<Value = Vec<(String, String)>> {
+    let name = prop_oneof![
+        "[a-z]{1,6}",
+        "utm_[a-z]{1,8}",
+        proptest::sample::select(TRACKING_PARAMS.to_vec()).prop_map(str::to_string),
+    ];
+    proptest::collection::vec((name, "[a-zA-Z0-9 %&=+]{0,6}"), 0..6)
+}
+
+/// URLs as they get shared: any case, default ports, fragments and
+/// tracking parameters mixed in with ones that matter
+fn shared_url() -> impl Strategy<Value = (String, Vec<(String, String)>)> {
+    (
+        prop_oneof!["http", "https", "HTTPS"],
+        "[a-zA-Z]{1,10}\\.(com|org|example)",
+        proptest::option::of(prop_oneof![Just(80u16), Just(443u16), 1024u16..65535]),
+        "(/[a-zA-Z0-9_.-]{0,8}){0,3}",
+        params(),
+        proptest::option::of("[a-z0-9]{0,8}"),
+    )
+        .prop_map(|(scheme, host, port, path, params, fragment)| {
+            let mut url = Url::parse(&format!("{scheme}://{host}{path}")).unwrap();
+            url.set_port(port).unwrap();
+            if !params.is_empty() {
+                url.query_pairs_mut().extend_pairs(&params);
+            }
+            url.set_fragment(fragment.as_deref());
+            (url.to_string(), params)
+        })
+}
+
+proptest! {
+    #[test]
+    fn test_canonicalize_is_idempotent((url, _) in shared_url()) {
+        let canonical = canonicalize(&url).unwrap();
+        prop_assert_eq!(canonicalize(&canonical), Some(canonical));
+    }
+
+    #[test]
+    fn test_canonicalize_never_panics_and_is_idempotent_on_anything(url in ".*") {
+        if let Some(canonical) = canonicalize(&url) {
+            prop_assert_eq!(canonicalize(&canonical), Some(canonical));
+        }
+    }
+
+    #[test]
+    fn test_canonical_urls_drop_fragments_and_tracking((url, params) in shared_url()) {
+        let canonical = Url::parse(&canonicalize(&url).unwrap()).unwrap();
+        prop_assert_eq!(canonical.fragment(), None);
+        for (name, _) in canonical.query_pairs() {
+            prop_assert!(!is_tracking_param(&name), "{} kept in {}", name, canonical);
+        }
+        // Every parameter that matters survives
+        let kept = params.iter().filter(|(name, _)| !is_tracking_param(name)).count();
+        prop_assert_eq!(canonical.query_pairs().count(), kept);
+    }
+
+    #[test]
+    fn test_canonicalize_ignores_parameter_order((url, params) in shared_url()) {
+        let mut reordered = Url::parse(&url).unwrap();
+        reordered.set_query(None);
+        if !params.is_empty() {
+            reordered.query_pairs_mut().extend_pairs(params.iter().rev());
+        }
+        prop_assert_eq!(canonicalize(reordered.as_str()), canonicalize(&url));
+    }
+
+    #[test]
+    fn test_backoff_never_shrinks_as_attempts_go_up(
+        attempt in any::<i32>(),
+        later in 0i32..64,
+        base in any::<u32>(),
+    ) {
+        let next = attempt.saturating_add(later);
+        prop_assert!(backoff_base_delay(attempt, base) <= backoff_base_delay(next, base));
+    }
+
+    #[test]
+    fn test_backoff_is_bounded(attempt in any::<i32>(), base in any::<u32>()) {
+        let ceiling = backoff_base_delay(MAX_BACKOFF_EXPONENT as i32, base);
+        prop_assert!(backoff_base_delay(attempt, base) <= ceiling);
+        prop_assert!(ceiling.as_secs() <= u64::from(base) << MAX_BACKOFF_EXPONENT);
+
+        let (shortest, longest) = backoff_bounds(attempt, base);
+        prop_assert!(shortest <= longest);
+        let delay = calculate_backoff_delay(attempt, base);
+        prop_assert!(
+            shortest <= delay && delay <= longest,
+            "{:?} outside {:?}..={:?}",
+            delay,
+            shortest,
+            longest
+        );
+    }
+}
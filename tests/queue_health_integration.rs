@@ -0,0 +1,106 @@
+mod helpers;
+
+use capsule::{
+    fetcher::Deadline,
+    jobs::{JobHandler, QUEUE_HEALTH_JOB_KIND, QueueHealthConfig, QueueHealthJobHandler},
+};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tracing::Span;
+use uuid::Uuid;
+
+use helpers::{create_user_with_token, insert_content, insert_item};
+
+async fn run_check(pool: &Pool<Postgres>) {
+    QueueHealthJobHandler::new(QueueHealthConfig::default())
+        .run(json!({}), pool, Span::none(), Deadline::none())
+        .await
+        .unwrap();
+}
+
+async fn nudges(pool: &Pool<Postgres>, user_id: Uuid) -> Vec<Value> {
+    sqlx::query_scalar(
+        "SELECT payload FROM notifications WHERE user_id = $1 AND kind = 'queue_nudge' ORDER BY id",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+async fn test_growing_queue_gets_a_nudge_with_suggestions(pool: Pool<Postgres>) {
+    let (user_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    let oldest = insert_item(&pool, user_id, "https://example.com/old").await;
+    sqlx::query("UPDATE items SET created_at = NOW() - INTERVAL '30 days' WHERE id = $1")
+        .bind(oldest)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let quick = insert_item(&pool, user_id, "https://example.com/quick").await;
+    insert_content(&pool, quick, "A short note that takes a minute.", "en").await;
+    let long = insert_item(&pool, user_id, "https://example.com/long").await;
+    insert_content(&pool, long, &"word ".repeat(5_000), "en").await;
+
+    run_check(&pool).await;
+
+    let sent = nudges(&pool, user_id).await;
+    assert_eq!(sent.len(), 1);
+    let nudge = &sent[0];
+    assert_eq!(nudge["unread"], 3);
+    assert_eq!(nudge["saved"], 2);
+    assert_eq!(nudge["read"], 0);
+    assert_eq!(nudge["oldest_unread"]["id"], oldest.to_string());
+    let quick_reads: Vec<&str> = nudge["quick_reads"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(quick_reads, vec![quick.to_string()]);
+    assert_eq!(nudge["quick_reads"][0]["reading_time_minutes"], 1);
+
+    // A second run in the same period doesn't nudge again, and the check
+    // stays scheduled
+    run_check(&pool).await;
+    assert_eq!(nudges(&pool, user_id).await.len(), 1);
+    let queued: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM jobs WHERE kind = $1 AND status = 'queued'::job_status",
+    )
+    .bind(QUEUE_HEALTH_JOB_KIND)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(queued, 1);
+}
+
+#[sqlx::test]
+async fn test_no_nudge_when_reading_keeps_up_or_opted_out(pool: Pool<Postgres>) {
+    // Saved one and read one this week
+    let (reader, _) = create_user_with_token(&pool, "alice@example.com").await;
+    let unread = insert_item(&pool, reader, "https://example.com/a").await;
+    sqlx::query("UPDATE items SET created_at = NOW() - INTERVAL '30 days' WHERE id = $1")
+        .bind(unread)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let read = insert_item(&pool, reader, "https://example.com/b").await;
+    sqlx::query("UPDATE items SET status = 'archived' WHERE id = $1")
+        .bind(read)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let (opted_out, _) = create_user_with_token(&pool, "bob@example.com").await;
+    insert_item(&pool, opted_out, "https://example.com/c").await;
+    sqlx::query("UPDATE users SET prefs = '{\"queue_nudges\": false}' WHERE id = $1")
+        .bind(opted_out)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    run_check(&pool).await;
+
+    assert!(nudges(&pool, reader).await.is_empty());
+    assert!(nudges(&pool, opted_out).await.is_empty());
+}
@@ -0,0 +1,84 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::{config::MigrationMode, schema::migrations};
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+
+async fn get_schema(app: &Router, token: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("GET")
+        .uri("/v1/admin/schema")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[sqlx::test]
+async fn test_schema_reports_applied_version(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (admin_id, admin) = helpers::create_user_with_token(&pool, "admin@example.com").await;
+    helpers::make_admin(&pool, admin_id).await;
+
+    let (status, body) = get_schema(&app, &admin).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["up_to_date"], true);
+    assert!(body["version"].as_i64().is_some());
+    assert_eq!(body["version"], body["expected_version"]);
+    assert_eq!(body["pending"], Value::Array(vec![]));
+}
+
+#[sqlx::test]
+async fn test_schema_reports_drift(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (admin_id, admin) = helpers::create_user_with_token(&pool, "admin@example.com").await;
+    helpers::make_admin(&pool, admin_id).await;
+
+    // As if a newer build had migrated this database
+    sqlx::query(
+        r#"
+        INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+        VALUES (99990101000000, 'from the future', TRUE, '\x00', 0)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (status, body) = get_schema(&app, &admin).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["up_to_date"], false);
+    assert_eq!(body["version"], 99990101000000i64);
+    assert_eq!(body["unknown"][0], 99990101000000i64);
+
+    assert!(
+        migrations::prepare(&pool, MigrationMode::Verify)
+            .await
+            .is_err()
+    );
+    assert!(
+        migrations::prepare(&pool, MigrationMode::Skip)
+            .await
+            .is_ok()
+    );
+}
+
+#[sqlx::test]
+async fn test_schema_requires_admin(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "user@example.com").await;
+
+    let (status, _) = get_schema(&app, &token).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,205 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+
+async fn send(app: &Router, token: &str, method: &str, uri: &str, body: Option<Value>) -> Response {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json");
+    let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+
+    let response = app
+        .clone()
+        .oneshot(builder.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+
+    Response { status, json }
+}
+
+struct Response {
+    status: StatusCode,
+    json: Value,
+}
+
+fn sources(response: &Response) -> Vec<&str> {
+    response.json["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["source"].as_str().unwrap())
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_search_scopes_notes_and_highlights(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+
+    let response = send(
+        &app,
+        &token,
+        "POST",
+        &format!("/v1/items/{}/highlights", item_id),
+        Some(json!({"quote": "Zettelkasten keeps ideas connected"})),
+    )
+    .await;
+    assert_eq!(response.status, StatusCode::CREATED);
+
+    let response = send(
+        &app,
+        &token,
+        "PUT",
+        &format!("/v1/items/{}/note", item_id),
+        Some(json!({"note": "Try a zettelkasten for the reading list"})),
+    )
+    .await;
+    assert_eq!(response.status, StatusCode::NO_CONTENT);
+
+    let all = send(&app, &token, "GET", "/v1/search?q=zettelkasten", None).await;
+    assert_eq!(all.status, StatusCode::OK);
+    let mut all_sources = sources(&all);
+    all_sources.sort();
+    assert_eq!(all_sources, vec!["highlights", "notes"]);
+
+    let notes = send(
+        &app,
+        &token,
+        "GET",
+        "/v1/search?q=zettelkasten&scope=notes",
+        None,
+    )
+    .await;
+    assert_eq!(sources(&notes), vec!["notes"]);
+
+    let content = send(
+        &app,
+        &token,
+        "GET",
+        "/v1/search?q=zettelkasten&scope=content",
+        None,
+    )
+    .await;
+    assert!(sources(&content).is_empty());
+}
+
+#[sqlx::test]
+async fn test_search_snippets_escape_stored_markup(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = helpers::insert_item(&pool, user_id, "https://example.com/a").await;
+
+    let response = send(
+        &app,
+        &token,
+        "PUT",
+        &format!("/v1/items/{}/note", item_id),
+        Some(json!({"note": "zettelkasten <img src=x onerror=alert(1)> & more"})),
+    )
+    .await;
+    assert_eq!(response.status, StatusCode::NO_CONTENT);
+
+    let response = send(&app, &token, "GET", "/v1/search?q=zettelkasten", None).await;
+    assert_eq!(response.status, StatusCode::OK);
+    let snippet = response.json["items"][0]["snippet"].as_str().unwrap();
+    assert!(snippet.contains("<b>zettelkasten</b>"));
+    assert!(snippet.contains("&lt;img"));
+    assert!(snippet.contains("&amp; more"));
+    assert!(!snippet.contains("<img"));
+}
+
+#[sqlx::test]
+async fn test_search_does_not_return_other_users_annotations(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (alice, _) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let (_, bob_token) = helpers::create_user_with_token(&pool, "bob@example.com").await;
+    let item_id = helpers::insert_item(&pool, alice, "https://example.com/a").await;
+
+    // Bob can't annotate Alice's item
+    let response = send(
+        &app,
+        &bob_token,
+        "POST",
+        &format!("/v1/items/{}/highlights", item_id),
+        Some(json!({"quote": "secret"})),
+    )
+    .await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+
+    sqlx::query("UPDATE items SET note = 'secret plans' WHERE id = $1")
+        .bind(item_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let response = send(&app, &bob_token, "GET", "/v1/search?q=secret", None).await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert!(sources(&response).is_empty());
+}
+
+#[sqlx::test]
+async fn test_search_pages_with_cursor(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    for url in ["https://example.com/a", "https://example.com/b"] {
+        let item_id = helpers::insert_item(&pool, user_id, url).await;
+        sqlx::query("UPDATE items SET note = 'zettelkasten notes' WHERE id = $1")
+            .bind(item_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let first = send(
+        &app,
+        &token,
+        "GET",
+        "/v1/search?q=zettelkasten&limit=1",
+        None,
+    )
+    .await;
+    assert_eq!(first.status, StatusCode::OK);
+    assert_eq!(sources(&first).len(), 1);
+    assert!(first.json["approximate_total"].is_i64());
+    let cursor = first.json["next_cursor"].as_str().unwrap().to_string();
+
+    let second = send(
+        &app,
+        &token,
+        "GET",
+        &format!("/v1/search?q=zettelkasten&limit=1&cursor={}", cursor),
+        None,
+    )
+    .await;
+    assert_eq!(sources(&second).len(), 1);
+    assert_ne!(
+        first.json["items"][0]["item_id"],
+        second.json["items"][0]["item_id"]
+    );
+
+    let bad = send(
+        &app,
+        &token,
+        "GET",
+        "/v1/search?q=zettelkasten&cursor=x",
+        None,
+    )
+    .await;
+    assert_eq!(bad.status, StatusCode::BAD_REQUEST);
+    assert_eq!(bad.json["field"], "cursor");
+}
@@ -0,0 +1,53 @@
+use capsule::seed::{DEMO_USER_ID, seed};
+use sqlx::{Pool, Postgres};
+
+async fn statuses(pool: &Pool<Postgres>) -> Vec<(String, String)> {
+    sqlx::query_as("SELECT url, status::text FROM items WHERE user_id = $1 ORDER BY created_at")
+        .bind(DEMO_USER_ID)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn test_seed_creates_the_same_demo_library_each_time(pool: Pool<Postgres>) {
+    let seeded = seed(&pool, false).await.unwrap();
+    assert_eq!(seeded.user_id, DEMO_USER_ID);
+    let first = statuses(&pool).await;
+    assert_eq!(first.len(), seeded.items);
+    let kinds: Vec<&str> = first.iter().map(|(_, status)| status.as_str()).collect();
+    for status in ["pending", "fetched", "reading", "failed", "archived"] {
+        assert!(kinds.contains(&status), "{}", status);
+    }
+
+    // Pages went through extraction, and the empty one was refused
+    let extracted: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM contents c JOIN items i ON i.id = c.item_id \
+         WHERE i.user_id = $1 AND c.clean_text IS NOT NULL",
+    )
+    .bind(DEMO_USER_ID)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(extracted, 4);
+    let rejected: Option<String> = sqlx::query_scalar(
+        "SELECT extraction_error FROM items WHERE url = 'https://blank.example/'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(rejected.is_some());
+
+    let job_states: Vec<String> = sqlx::query_scalar(
+        "SELECT DISTINCT status::text FROM jobs WHERE kind = 'fetch_page' ORDER BY 1",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(job_states, ["failed", "queued", "running", "succeeded"]);
+
+    // Seeding again needs --reset, which recreates the same library
+    assert!(seed(&pool, false).await.is_err());
+    seed(&pool, true).await.unwrap();
+    assert_eq!(statuses(&pool).await, first);
+}
@@ -0,0 +1,174 @@
+mod helpers;
+
+use async_trait::async_trait;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::{
+    embeddings::EmbeddingProvider,
+    fetcher::Deadline,
+    jobs::{EmbedContentJobHandler, EmbedContentPayload, JobHandler},
+};
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use tower::ServiceExt;
+use tracing::Span;
+use uuid::Uuid;
+
+use helpers::{create_user_with_token, insert_content, insert_item};
+
+/// Stands in for a model: one dimension per topic, set when the text
+/// mentions any of its words
+struct Topics;
+
+const TOPICS: [&[&str]; 2] = [&["cat", "kitten", "feline"], &["car", "engine", "motor"]];
+
+#[async_trait]
+impl EmbeddingProvider for Topics {
+    fn name(&self) -> &str {
+        "topics"
+    }
+
+    async fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(texts
+            .iter()
+            .map(|text| {
+                let text = text.to_lowercase();
+                let mut vector: Vec<f32> = TOPICS
+                    .iter()
+                    .map(|words| {
+                        if words.iter().any(|word| text.contains(word)) {
+                            1.0
+                        } else {
+                            0.0
+                        }
+                    })
+                    .collect();
+                vector.push(0.1);
+                vector
+            })
+            .collect())
+    }
+}
+
+async fn pgvector_enabled(pool: &Pool<Postgres>) -> bool {
+    sqlx::query_scalar("SELECT to_regclass('item_embeddings') IS NOT NULL")
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+async fn embed(pool: &Pool<Postgres>, item_id: Uuid) {
+    let handler = EmbedContentJobHandler::new(Some(Arc::new(Topics)));
+    let payload = serde_json::to_value(EmbedContentPayload { item_id }).unwrap();
+    handler
+        .run(payload, pool, Span::none(), Deadline::none())
+        .await
+        .unwrap();
+}
+
+async fn search(app: &Router, token: &str, query: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .uri(format!("/v1/search?{}", query))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+fn item_ids(body: &Value) -> Vec<String> {
+    body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["item_id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_semantic_search_without_embedder_is_unavailable(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = create_user_with_token(&pool, "alice@example.com").await;
+
+    let (status, body) = search(&app, &token, "q=kitten&mode=semantic").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["error"], "Semantic search is not enabled");
+
+    // Keyword search is unaffected
+    let (status, _) = search(&app, &token, "q=kitten").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn test_semantic_search_finds_items_by_meaning(pool: Pool<Postgres>) {
+    let app = helpers::test_app_with_embedder(pool.clone(), Some(Arc::new(Topics)));
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+
+    if !pgvector_enabled(&pool).await {
+        let (status, _) = search(&app, &token, "q=kitten&mode=semantic").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        return;
+    }
+
+    let cats = insert_item(&pool, user_id, "https://example.com/cats").await;
+    insert_content(&pool, cats, "Our cat naps in the sun all afternoon.", "en").await;
+    let cars = insert_item(&pool, user_id, "https://example.com/cars").await;
+    insert_content(&pool, cars, "Tuning an engine for more torque.", "en").await;
+    embed(&pool, cats).await;
+    embed(&pool, cars).await;
+
+    // No item contains "kitten", so only the embeddings can find the cat
+    let (status, body) = search(&app, &token, "q=kitten&mode=semantic").await;
+    assert_eq!(status, StatusCode::OK);
+    let ids = item_ids(&body);
+    assert_eq!(ids.first(), Some(&cats.to_string()));
+    assert!(body["approximate_total"].is_null());
+
+    // An item matching both ways outranks one only close in meaning
+    let (status, body) = search(&app, &token, "q=engine&mode=semantic&limit=1").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item_ids(&body), vec![cars.to_string()]);
+    assert!(
+        body["items"][0]["snippet"]
+            .as_str()
+            .unwrap()
+            .contains("<b>")
+    );
+    let cursor = body["next_cursor"].as_str().unwrap().to_string();
+
+    let (status, body) = search(
+        &app,
+        &token,
+        &format!("q=engine&mode=semantic&limit=1&cursor={}", cursor),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item_ids(&body), vec![cats.to_string()]);
+    assert!(body["next_cursor"].is_null());
+}
+
+#[sqlx::test]
+async fn test_semantic_search_only_sees_own_items(pool: Pool<Postgres>) {
+    let app = helpers::test_app_with_embedder(pool.clone(), Some(Arc::new(Topics)));
+    if !pgvector_enabled(&pool).await {
+        return;
+    }
+    let (alice_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    let (_, bob_token) = create_user_with_token(&pool, "bob@example.com").await;
+
+    let item_id = insert_item(&pool, alice_id, "https://example.com/cats").await;
+    insert_content(&pool, item_id, "A kitten chasing string.", "en").await;
+    embed(&pool, item_id).await;
+
+    let (status, body) = search(&app, &bob_token, "q=feline&mode=semantic").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(item_ids(&body).is_empty());
+}
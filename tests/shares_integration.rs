@@ -0,0 +1,199 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{
+        Method, Request, StatusCode,
+        header::{AUTHORIZATION, CONTENT_TYPE},
+    },
+};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use capsule::repositories::{DocumentRepository, url_hash};
+use helpers::{create_user_with_token, insert_content, insert_item};
+
+async fn send(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    token: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Option<String>, String) {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let request = match body {
+        Some(body) => request
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .map(|value| value.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        content_type,
+        String::from_utf8(body.to_vec()).unwrap(),
+    )
+}
+
+async fn insert_article(pool: &Pool<Postgres>, user_id: Uuid) -> Uuid {
+    let item_id = insert_item(pool, user_id, "https://example.com/post").await;
+    insert_content(pool, item_id, "Shared words", "en").await;
+    sqlx::query("UPDATE contents SET clean_html = '<p>Shared words</p>' WHERE item_id = $1")
+        .bind(item_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE items SET title = 'A <b>bold</b> post' WHERE id = $1")
+        .bind(item_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    item_id
+}
+
+#[sqlx::test]
+async fn test_share_link_serves_article_until_revoked(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_article(&pool, user_id).await;
+    let uri = format!("/v1/items/{}/share", item_id);
+
+    let (status, _, body) = send(&app, Method::POST, &uri, Some(&token), Some(json!({}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let share: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(share["item_id"], item_id.to_string());
+    assert_eq!(share["expires_at"], Value::Null);
+    let url = share["url"].as_str().unwrap().to_string();
+
+    // No bearer token needed to read it
+    let (status, content_type, page) = send(&app, Method::GET, &url, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("text/html; charset=utf-8"));
+    assert!(page.contains("<p>Shared words</p>"));
+    assert!(page.contains("A &lt;b&gt;bold&lt;/b&gt; post"));
+
+    let (status, _, body) = send(&app, Method::DELETE, &uri, Some(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(serde_json::from_str::<Value>(&body).unwrap()["revoked"], 1);
+
+    let (status, _, _) = send(&app, Method::GET, &url, None, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_share_link_serves_article_from_shared_document(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_article(&pool, user_id).await;
+
+    // The item's copy moves to the document, leaving its own columns empty
+    let hash = url_hash("https://example.com/post").unwrap();
+    assert!(
+        DocumentRepository::new(&pool)
+            .share(item_id, &hash)
+            .await
+            .unwrap()
+    );
+
+    let uri = format!("/v1/items/{}/share", item_id);
+    let (status, _, body) = send(&app, Method::POST, &uri, Some(&token), Some(json!({}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let share: Value = serde_json::from_str(&body).unwrap();
+
+    let (status, _, page) = send(
+        &app,
+        Method::GET,
+        share["url"].as_str().unwrap(),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(page.contains("<p>Shared words</p>"));
+}
+
+#[sqlx::test]
+async fn test_share_links_cannot_be_forged_or_outlive_their_ttl(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_article(&pool, user_id).await;
+    let uri = format!("/v1/items/{}/share", item_id);
+
+    let (status, _, body) = send(
+        &app,
+        Method::POST,
+        &uri,
+        Some(&token),
+        Some(json!({ "ttl_secs": 3600 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let share: Value = serde_json::from_str(&body).unwrap();
+    assert!(share["expires_at"].is_string());
+
+    // A share ID with someone else's signature
+    let forged = format!("/shared/{}.{}", Uuid::new_v4().simple(), "0".repeat(64));
+    let (status, _, _) = send(&app, Method::GET, &forged, None, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    sqlx::query("UPDATE shares SET expires_at = NOW() - INTERVAL '1 second'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (status, _, _) = send(
+        &app,
+        Method::GET,
+        share["url"].as_str().unwrap(),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_only_the_owner_can_share_an_item(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    let (_, other_token) = create_user_with_token(&pool, "bob@example.com").await;
+    let item_id = insert_article(&pool, user_id).await;
+    let uri = format!("/v1/items/{}/share", item_id);
+
+    let (status, _, _) = send(
+        &app,
+        Method::POST,
+        &uri,
+        Some(&other_token),
+        Some(json!({})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, _) = send(&app, Method::DELETE, &uri, Some(&other_token), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _, _) = send(
+        &app,
+        Method::POST,
+        &uri,
+        Some(&other_token),
+        Some(json!({ "ttl_secs": 0 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,167 @@
+mod helpers;
+
+use axum::{
+    body::Body,
+    http::{
+        Request, StatusCode,
+        header::{AUTHORIZATION, CONTENT_SECURITY_POLICY, CONTENT_TYPE},
+    },
+};
+use capsule::{
+    fetcher::Deadline,
+    jobs::{FETCH_SITE_ICON_JOB_KIND, FetchSiteIconJobHandler, JobHandler, stage_site_icon},
+};
+use serde_json::json;
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use tracing::Span;
+use url::Url;
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, path},
+};
+
+async fn run_icon_job(pool: &Pool<Postgres>, server: &MockServer, icon_url: Option<String>) {
+    FetchSiteIconJobHandler::new()
+        .run(
+            json!({
+                "host": "127.0.0.1",
+                "page_url": format!("{}/posts/1", server.uri()),
+                "icon_url": icon_url,
+            }),
+            pool,
+            Span::none(),
+            Deadline::none(),
+        )
+        .await
+        .unwrap();
+}
+
+async fn get_icon(
+    pool: &Pool<Postgres>,
+    token: &str,
+    host: &str,
+) -> (StatusCode, Option<String>, Vec<u8>) {
+    let app = helpers::test_app(pool.clone());
+    let request = Request::builder()
+        .uri(format!("/v1/sites/{}/icon", host))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    if status == StatusCode::OK {
+        assert_eq!(
+            response.headers()[CONTENT_SECURITY_POLICY],
+            "default-src 'none'"
+        );
+    }
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .map(|value| value.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, content_type, body.to_vec())
+}
+
+#[sqlx::test]
+async fn test_icon_job_stores_the_linked_icon(pool: Pool<Postgres>) {
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let server = helpers::start_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/static/icon.png"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(b"\x89PNG icon".to_vec())
+                .insert_header("Content-Type", "image/png"),
+        )
+        .mount(&server)
+        .await;
+
+    assert_eq!(
+        get_icon(&pool, &token, "127.0.0.1").await.0,
+        StatusCode::NOT_FOUND
+    );
+
+    run_icon_job(
+        &pool,
+        &server,
+        Some(format!("{}/static/icon.png", server.uri())),
+    )
+    .await;
+
+    let (status, content_type, body) = get_icon(&pool, &token, "127.0.0.1").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("image/png"));
+    assert_eq!(body, b"\x89PNG icon");
+}
+
+#[sqlx::test]
+async fn test_icon_job_falls_back_to_favicon_ico(pool: Pool<Postgres>) {
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let server = helpers::start_mock_server().await;
+    // The linked icon isn't an image; the site root has one
+    Mock::given(method("GET"))
+        .and(path("/icon"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes("<html></html>")
+                .insert_header("Content-Type", "text/html"),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/favicon.ico"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(b"ico".to_vec())
+                .insert_header("Content-Type", "image/x-icon"),
+        )
+        .mount(&server)
+        .await;
+
+    run_icon_job(&pool, &server, Some(format!("{}/icon", server.uri()))).await;
+
+    let (status, content_type, body) = get_icon(&pool, &token, "127.0.0.1").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("image/x-icon"));
+    assert_eq!(body, b"ico");
+}
+
+#[sqlx::test]
+async fn test_site_without_icon_gives_up_quietly(pool: Pool<Postgres>) {
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+    let server = helpers::start_mock_server().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    run_icon_job(&pool, &server, None).await;
+
+    assert_eq!(
+        get_icon(&pool, &token, "127.0.0.1").await.0,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[sqlx::test]
+async fn test_icon_fetched_once_per_site(pool: Pool<Postgres>) {
+    let first = Url::parse("https://www.example.com/a").unwrap();
+    let second = Url::parse("https://example.com/b").unwrap();
+    let icon = Url::parse("https://example.com/icon.svg").unwrap();
+
+    stage_site_icon(&pool, &first, Some(&icon)).await.unwrap();
+    stage_site_icon(&pool, &second, None).await.unwrap();
+
+    let payloads: Vec<serde_json::Value> =
+        sqlx::query_scalar("SELECT payload FROM jobs WHERE kind = $1")
+            .bind(FETCH_SITE_ICON_JOB_KIND)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(payloads.len(), 1);
+    assert_eq!(payloads[0]["icon_url"], "https://example.com/icon.svg");
+}
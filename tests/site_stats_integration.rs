@@ -0,0 +1,152 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use helpers::{create_user_with_token, insert_content, insert_item};
+
+async fn get_json(app: &Router, token: &str, uri: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn set_progress(pool: &Pool<Postgres>, item_id: Uuid, progress: f32) {
+    sqlx::query("UPDATE items SET read_progress = $2 WHERE id = $1")
+        .bind(item_id)
+        .bind(progress)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn test_site_stats_group_items_by_domain(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let (other_id, _) = create_user_with_token(&pool, "bob@example.com").await;
+
+    let finished = insert_item(&pool, user_id, "https://www.Example.com/a").await;
+    let skimmed = insert_item(&pool, user_id, "https://example.com/b").await;
+    insert_item(&pool, user_id, "http://example.com:8080/c?x=1").await;
+    let other = insert_item(&pool, user_id, "https://blog.other.example/post").await;
+    insert_item(&pool, other_id, "https://blog.other.example/theirs").await;
+
+    set_progress(&pool, finished, 1.0).await;
+    set_progress(&pool, skimmed, 0.5).await;
+    set_progress(&pool, other, 0.95).await;
+    // 476 words at 238 per minute reads in two minutes, 10 words in one
+    insert_content(&pool, finished, &vec!["word"; 476].join(" "), "en").await;
+    insert_content(&pool, skimmed, &["word"; 10].join(" "), "en").await;
+
+    let (status, stats) = get_json(&app, &token, "/v1/stats/sites").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        stats["sites"],
+        serde_json::json!([
+            {
+                "domain": "example.com",
+                "saved": 3,
+                "read": 1,
+                "read_ratio": 1.0 / 3.0,
+                "avg_reading_time_minutes": 1.5
+            },
+            {
+                "domain": "blog.other.example",
+                "saved": 1,
+                "read": 1,
+                "read_ratio": 1.0,
+                "avg_reading_time_minutes": null
+            }
+        ])
+    );
+}
+
+#[sqlx::test]
+async fn test_site_stats_limit(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    insert_item(&pool, user_id, "https://a.example/1").await;
+    insert_item(&pool, user_id, "https://a.example/2").await;
+    insert_item(&pool, user_id, "https://b.example/1").await;
+
+    let (status, stats) = get_json(&app, &token, "/v1/stats/sites?limit=1").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(stats["sites"].as_array().unwrap().len(), 1);
+    assert_eq!(stats["sites"][0]["domain"], "a.example");
+
+    let (status, _) = get_json(&app, &token, "/v1/stats/sites?limit=0").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_item_stats_counts_the_library(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let (other_id, _) = create_user_with_token(&pool, "bob@example.com").await;
+
+    let read = insert_item(&pool, user_id, "https://example.com/read").await;
+    let archived = insert_item(&pool, user_id, "https://example.com/archived").await;
+    insert_item(&pool, user_id, "https://example.com/unread").await;
+    insert_item(&pool, other_id, "https://example.com/theirs").await;
+
+    set_progress(&pool, read, 0.95).await;
+    sqlx::query("UPDATE items SET status = 'archived' WHERE id = $1")
+        .bind(archived)
+        .execute(&pool)
+        .await
+        .unwrap();
+    insert_content(&pool, read, "twelve bytes", "en").await;
+    for (name, items) in [("rust", vec![read, archived]), ("unused", vec![])] {
+        let tag_id: Uuid =
+            sqlx::query_scalar("INSERT INTO tags (user_id, name) VALUES ($1, $2) RETURNING id")
+                .bind(user_id)
+                .bind(name)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        for item_id in items {
+            sqlx::query("INSERT INTO item_tags (item_id, tag_id) VALUES ($1, $2)")
+                .bind(item_id)
+                .bind(tag_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    let (status, stats) = get_json(&app, &token, "/v1/items/stats").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        stats,
+        serde_json::json!({
+            "total": 3,
+            "by_status": {"pending": 2, "fetched": 0, "reading": 0, "failed": 0, "archived": 1},
+            "tags": [{"name": "rust", "count": 2}, {"name": "unused", "count": 0}],
+            "unread": 1,
+            "storage_bytes": 12
+        })
+    );
+
+    // A new user's library is empty rather than missing
+    let (_, token) = create_user_with_token(&pool, "carol@example.com").await;
+    let (status, stats) = get_json(&app, &token, "/v1/items/stats").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(stats["total"], 0);
+    assert_eq!(stats["tags"], serde_json::json!([]));
+}
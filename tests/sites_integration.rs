@@ -0,0 +1,149 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::{
+    entities::DomainRuleAction,
+    repositories::{DomainRulesRepository, SiteRepository},
+};
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+
+use helpers::{create_user_with_token, insert_item};
+
+async fn get_json(app: &Router, token: &str, uri: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[sqlx::test]
+async fn test_sites_list_the_users_items_by_site(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let (other_id, _) = create_user_with_token(&pool, "bob@example.com").await;
+
+    let first = insert_item(&pool, user_id, "https://www.example.com/a").await;
+    insert_item(&pool, user_id, "https://example.com/b").await;
+    insert_item(&pool, user_id, "https://blog.other.example/post").await;
+    insert_item(&pool, other_id, "https://theirs.example/post").await;
+
+    let sites = SiteRepository::new(&pool);
+    let host = sites
+        .record_for_item(first, Some("Example"), Some("https://example.com/feed"))
+        .await
+        .unwrap();
+    assert_eq!(host.as_deref(), Some("example.com"));
+    sites
+        .store_icon(
+            "example.com",
+            "https://example.com/icon.png",
+            "image/png",
+            b"png",
+        )
+        .await
+        .unwrap();
+
+    let (status, page) = get_json(&app, &token, "/v1/sites").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["approximate_total"], 2);
+    let hosts: Vec<_> = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|site| site["host"].as_str().unwrap())
+        .collect();
+    assert_eq!(hosts, ["example.com", "blog.other.example"]);
+
+    let site = &page["items"][0];
+    assert_eq!(site["name"], "Example");
+    assert_eq!(site["feed_url"], "https://example.com/feed");
+    assert_eq!(site["icon_url"], "/v1/sites/example.com/icon");
+    assert_eq!(site["item_count"], 2);
+    // Pages of the other site haven't been fetched yet
+    assert_eq!(page["items"][1]["name"], Value::Null);
+    assert_eq!(page["items"][1]["icon_url"], Value::Null);
+
+    let (status, page) = get_json(&app, &token, "/v1/sites?limit=1&cursor=1").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["items"][0]["host"], "blog.other.example");
+
+    let (status, site) = get_json(&app, &token, "/v1/sites/www.example.com").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(site["item_count"], 2);
+
+    let (status, _) = get_json(&app, &token, "/v1/sites/theirs.example").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Items carry the site's name
+    let (_, list) = get_json(&app, &token, "/v1/items?site=example.com").await;
+    assert_eq!(list["items"][0]["site"], "Example");
+}
+
+#[sqlx::test]
+async fn test_site_keeps_what_pages_said_before(pool: Pool<Postgres>) {
+    let (user_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    let first = insert_item(&pool, user_id, "https://example.com/a").await;
+    let second = insert_item(&pool, user_id, "https://example.com/b").await;
+
+    let sites = SiteRepository::new(&pool);
+    sites
+        .record_for_item(first, Some("Example"), Some("https://example.com/feed"))
+        .await
+        .unwrap();
+    sites.record_for_item(second, None, None).await.unwrap();
+
+    let site = sites.find("example.com").await.unwrap().unwrap();
+    assert_eq!(site.name.as_deref(), Some("Example"));
+    assert_eq!(site.feed_url.as_deref(), Some("https://example.com/feed"));
+    assert!(!site.has_icon);
+}
+
+#[sqlx::test]
+async fn test_site_follows_its_most_specific_domain_rule(pool: Pool<Postgres>) {
+    let (user_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://blog.example.com/post").await;
+    let rules = DomainRulesRepository::new(&pool);
+    let sites = SiteRepository::new(&pool);
+
+    rules
+        .upsert("example.com", DomainRuleAction::Allow, None, user_id)
+        .await
+        .unwrap();
+    sites.record_for_item(item_id, None, None).await.unwrap();
+    let rule = |site: Option<capsule::entities::Site>| site.unwrap().domain_rule;
+    assert_eq!(
+        rule(sites.find("blog.example.com").await.unwrap()).as_deref(),
+        Some("example.com")
+    );
+
+    rules
+        .upsert("blog.example.com", DomainRuleAction::Block, None, user_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        rule(sites.find("blog.example.com").await.unwrap()).as_deref(),
+        Some("blog.example.com")
+    );
+
+    rules.delete("blog.example.com").await.unwrap();
+    assert_eq!(
+        rule(sites.find("blog.example.com").await.unwrap()).as_deref(),
+        Some("example.com")
+    );
+
+    rules.delete("example.com").await.unwrap();
+    assert_eq!(rule(sites.find("blog.example.com").await.unwrap()), None);
+}
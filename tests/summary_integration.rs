@@ -0,0 +1,111 @@
+mod helpers;
+
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::{
+    fetcher::Deadline,
+    jobs::{JobHandler, SummarizeContentJobHandler, SummarizeContentPayload},
+    summarizer::Summarizer,
+};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use tower::ServiceExt;
+use tracing::Span;
+use uuid::Uuid;
+
+use helpers::{create_user_with_token, insert_content, insert_item};
+
+/// Stands in for a model by summing up every text the same way
+struct Canned;
+
+#[async_trait]
+impl Summarizer for Canned {
+    fn name(&self) -> &'static str {
+        "canned"
+    }
+
+    async fn summarize(&self, title: Option<&str>, _text: &str) -> anyhow::Result<String> {
+        Ok(format!("All about {}", title.unwrap_or("nothing")))
+    }
+}
+
+async fn summarize(pool: &Pool<Postgres>, handler: SummarizeContentJobHandler, item_id: Uuid) {
+    let payload = serde_json::to_value(SummarizeContentPayload { item_id }).unwrap();
+    handler
+        .run(payload, pool, Span::none(), Deadline::none())
+        .await
+        .unwrap();
+}
+
+async fn summary_of(pool: &Pool<Postgres>, token: &str, item_id: Uuid) -> Value {
+    let app = helpers::test_app(pool.clone());
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/items/content:batchGet?fields=metadata")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "item_ids": [item_id] }).to_string()))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    body["contents"][0]["summary"].clone()
+}
+
+#[sqlx::test]
+async fn test_extractive_summary_by_default(pool: Pool<Postgres>) {
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://example.com/a").await;
+    insert_content(
+        &pool,
+        item_id,
+        "Rust compilers check memory safety. The weather was nice.\n\n\
+         Memory safety without garbage collection is what Rust offers. \
+         Lunch was late. Dinner was early. Breakfast was skipped.",
+        "en",
+    )
+    .await;
+    assert_eq!(summary_of(&pool, &token, item_id).await, Value::Null);
+
+    summarize(&pool, SummarizeContentJobHandler::new(), item_id).await;
+
+    let summary = summary_of(&pool, &token, item_id).await;
+    let summary = summary.as_str().unwrap();
+    assert!(summary.starts_with("Rust compilers check memory safety."));
+    assert!(summary.contains("Memory safety without garbage collection"));
+    let provider: String =
+        sqlx::query_scalar("SELECT summary_provider FROM contents WHERE item_id = $1")
+            .bind(item_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(provider, "extractive");
+}
+
+#[sqlx::test]
+async fn test_summarizer_is_pluggable(pool: Pool<Postgres>) {
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://example.com/a").await;
+    sqlx::query("UPDATE items SET title = 'Rust' WHERE id = $1")
+        .bind(item_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    insert_content(&pool, item_id, "Some text about Rust.", "en").await;
+
+    summarize(
+        &pool,
+        SummarizeContentJobHandler::with_summarizer(Arc::new(Canned)),
+        item_id,
+    )
+    .await;
+
+    assert_eq!(summary_of(&pool, &token, item_id).await, "All about Rust");
+}
@@ -0,0 +1,126 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::repositories::TagRepository;
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use helpers::{create_user_with_token, insert_item};
+
+async fn rename(app: &Router, token: &str, tag_id: Uuid, name: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method("PATCH")
+        .uri(format!("/v1/tags/{}", tag_id))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "name": name }).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+async fn tag_names(pool: &Pool<Postgres>, item_id: Uuid) -> Vec<String> {
+    sqlx::query_scalar(
+        r#"
+        SELECT t.name FROM item_tags it JOIN tags t ON t.id = it.tag_id
+        WHERE it.item_id = $1 ORDER BY t.name
+        "#,
+    )
+    .bind(item_id)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+async fn test_rename_tag_to_a_new_name(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://example.com/a").await;
+
+    let tags = TagRepository::new(&pool);
+    let tag_id = tags.find_or_create(user_id, "reading").await.unwrap();
+    tags.attach(item_id, tag_id).await.unwrap();
+
+    let (status, tag) = rename(&app, &token, tag_id, "  to read ").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(tag["id"], tag_id.to_string());
+    assert_eq!(tag["name"], "to read");
+    assert_eq!(tag["item_count"], 1);
+    assert_eq!(tag["merged"], false);
+    assert_eq!(tag_names(&pool, item_id).await, ["to read"]);
+
+    // Renaming to the name it has already is a no-op
+    let (status, tag) = rename(&app, &token, tag_id, "to read").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(tag["merged"], false);
+
+    let (status, _) = rename(&app, &token, tag_id, " ").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_rename_tag_to_an_existing_name_merges_them(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let (other_id, _) = create_user_with_token(&pool, "bob@example.com").await;
+    let both = insert_item(&pool, user_id, "https://example.com/both").await;
+    let old_only = insert_item(&pool, user_id, "https://example.com/old").await;
+    let new_only = insert_item(&pool, user_id, "https://example.com/new").await;
+    let theirs = insert_item(&pool, other_id, "https://example.com/theirs").await;
+
+    let tags = TagRepository::new(&pool);
+    let old = tags.find_or_create(user_id, "rust-lang").await.unwrap();
+    let new = tags.find_or_create(user_id, "rust").await.unwrap();
+    let their_tag = tags.find_or_create(other_id, "rust").await.unwrap();
+    for (item_id, tag_id) in [
+        (both, old),
+        (both, new),
+        (old_only, old),
+        (new_only, new),
+        (theirs, their_tag),
+    ] {
+        tags.attach(item_id, tag_id).await.unwrap();
+    }
+
+    let (status, tag) = rename(&app, &token, old, "rust").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(tag["id"], new.to_string());
+    assert_eq!(tag["name"], "rust");
+    assert_eq!(tag["item_count"], 3);
+    assert_eq!(tag["merged"], true);
+
+    for item_id in [both, old_only, new_only] {
+        assert_eq!(tag_names(&pool, item_id).await, ["rust"]);
+    }
+    let old_exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM tags WHERE id = $1)")
+        .bind(old)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(!old_exists);
+
+    // The other user's tag of that name is untouched
+    let their_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item_tags WHERE tag_id = $1")
+        .bind(their_tag)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(their_count, 1);
+
+    // Tags are only the user's to rename
+    let (status, _) = rename(&app, &token, their_tag, "mine now").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = rename(&app, &token, old, "gone").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "telemetry")]
+
+mod helpers;
+
+use capsule::{
+    fetcher::Deadline,
+    jobs::{JobHandler, TELEMETRY_REPORT_JOB_KIND, TelemetryReportJobHandler},
+    telemetry::TelemetryConfig,
+};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tracing::Span;
+use url::Url;
+use wiremock::{
+    Mock, MockServer, ResponseTemplate,
+    matchers::{method, path},
+};
+
+use helpers::{create_user_with_token, insert_item};
+
+#[sqlx::test]
+async fn test_report_is_anonymized_and_rescheduled(pool: Pool<Postgres>) {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/report"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (user_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    for i in 0..12 {
+        insert_item(&pool, user_id, &format!("https://example.com/{}", i)).await;
+    }
+
+    let endpoint = Url::parse(&format!("{}/report", server.uri())).unwrap();
+    TelemetryReportJobHandler::new(TelemetryConfig::new(endpoint))
+        .unwrap()
+        .run(json!({}), &pool, Span::none(), Deadline::none())
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let report: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(report["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(report["users"], "1-9");
+    assert_eq!(report["items"], "10-99");
+    assert_eq!(report["job_failure_rate"], Value::Null);
+    let body = String::from_utf8(requests[0].body.clone()).unwrap();
+    assert!(!body.contains("alice"));
+    assert!(!body.contains("example.com"));
+
+    let queued: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM jobs WHERE kind = $1 AND status = 'queued'::job_status",
+    )
+    .bind(TELEMETRY_REPORT_JOB_KIND)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(queued, 1);
+}
+
+#[sqlx::test]
+async fn test_failed_delivery_fails_the_job(pool: Pool<Postgres>) {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+
+    let endpoint = Url::parse(&server.uri()).unwrap();
+    let result = TelemetryReportJobHandler::new(TelemetryConfig::new(endpoint))
+        .unwrap()
+        .run(json!({}), &pool, Span::none(), Deadline::none())
+        .await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,156 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{
+        Request, StatusCode,
+        header::{AUTHORIZATION, RETRY_AFTER},
+    },
+};
+use capsule::middleware::throttle::AbuseConfig;
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn import(app: &Router, token: &str) -> (StatusCode, bool) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/v1/imports")
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({"urls": ["https://example.com/new"]}).to_string(),
+        ))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    (
+        response.status(),
+        response.headers().contains_key(RETRY_AFTER),
+    )
+}
+
+async fn admin_request(app: &Router, token: &str, method: &str, uri: &str, body: Value) -> Value {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[sqlx::test]
+async fn test_save_burst_throttles_until_admin_override(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "spammer@example.com").await;
+    let (admin_id, admin) = helpers::create_user_with_token(&pool, "admin@example.com").await;
+    helpers::make_admin(&pool, admin_id).await;
+
+    sqlx::query(
+        "INSERT INTO items (user_id, url) SELECT $1, 'https://example.com/' || n FROM generate_series(1, $2) n",
+    )
+    .bind(user_id)
+    .bind(AbuseConfig::default().max_saves_per_minute as i32)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(
+        import(&app, &token).await,
+        (StatusCode::TOO_MANY_REQUESTS, true)
+    );
+    // Still throttled, without a second audit event
+    assert_eq!(import(&app, &token).await.0, StatusCode::TOO_MANY_REQUESTS);
+
+    let throttles = admin_request(&app, &admin, "GET", "/v1/admin/throttles", Value::Null).await;
+    assert_eq!(throttles["throttles"][0]["user_id"], user_id.to_string());
+    assert_eq!(
+        throttles["throttles"][0]["reason"],
+        "200 saves in the last minute"
+    );
+
+    let throttle = admin_request(
+        &app,
+        &admin,
+        "PUT",
+        &format!("/v1/admin/throttles/{}", user_id),
+        json!({"exempt": true}),
+    )
+    .await;
+    assert!(throttle["throttled_until"].is_null());
+    assert_eq!(throttle["exempt"], true);
+
+    // Exempt users aren't throttled again despite the burst
+    assert_eq!(import(&app, &token).await.0, StatusCode::ACCEPTED);
+
+    let events = admin_request(
+        &app,
+        &admin,
+        "GET",
+        &format!("/v1/admin/throttles/{}/events", user_id),
+        Value::Null,
+    )
+    .await;
+    let kinds: Vec<&str> = events["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, ["exempted", "lifted", "throttled"]);
+    assert_eq!(events["events"][0]["actor_id"], admin_id.to_string());
+    assert!(events["events"][2]["actor_id"].is_null());
+}
+
+#[sqlx::test]
+async fn test_repeated_failing_domain_throttles(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+
+    // Saved a while ago, so only the failures count
+    sqlx::query(
+        r#"
+        INSERT INTO items (user_id, url, processing_state, created_at)
+        SELECT $1, 'https://dead.example/' || n, 'failed_permanent', NOW() - INTERVAL '10 minutes'
+        FROM generate_series(1, $2) n
+        "#,
+    )
+    .bind(user_id)
+    .bind(AbuseConfig::default().max_domain_failures as i32)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    assert_eq!(import(&app, &token).await.0, StatusCode::TOO_MANY_REQUESTS);
+
+    let reason: String = sqlx::query_scalar("SELECT reason FROM user_throttles WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(reason, "25 failed fetches from dead.example");
+}
+
+#[sqlx::test]
+async fn test_override_requires_admin(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (_, token) = helpers::create_user_with_token(&pool, "alice@example.com").await;
+
+    let request = Request::builder()
+        .method("PUT")
+        .uri(format!("/v1/admin/throttles/{}", Uuid::new_v4()))
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(json!({"exempt": true}).to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,153 @@
+mod helpers;
+
+use async_trait::async_trait;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::{
+    fetcher::Deadline,
+    jobs::{ClassifyTopicsJobHandler, ClassifyTopicsPayload, JobHandler},
+    topics::TopicClassifier,
+};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use tower::ServiceExt;
+use tracing::Span;
+use uuid::Uuid;
+
+use helpers::{create_user_with_token, insert_content, insert_item};
+
+const TECH_TEXT: &str = "Developers rewrote the compiler in Rust. The software now builds \
+                         faster, and the code runs on every Linux server.";
+const TRAVEL_TEXT: &str = "Our flight landed at dawn. From the airport we took a ferry to the \
+                           island, checked into a hotel and spent the afternoon on the beach.";
+
+/// Stands in for a model by filing everything under one topic
+struct Always(&'static str);
+
+#[async_trait]
+impl TopicClassifier for Always {
+    fn name(&self) -> &'static str {
+        "always"
+    }
+
+    async fn classify(&self, _title: Option<&str>, _text: &str) -> anyhow::Result<Vec<String>> {
+        Ok(vec![self.0.to_string()])
+    }
+}
+
+async fn classify(pool: &Pool<Postgres>, handler: &ClassifyTopicsJobHandler, item_id: Uuid) {
+    let payload = serde_json::to_value(ClassifyTopicsPayload { item_id }).unwrap();
+    handler
+        .run(payload, pool, Span::none(), Deadline::none())
+        .await
+        .unwrap();
+}
+
+async fn get_json(app: &Router, token: &str, uri: &str) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+fn item_ids(list: &Value) -> Vec<String> {
+    list["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| {
+            item.get("item_id")
+                .or_else(|| item.get("id"))
+                .and_then(Value::as_str)
+                .unwrap()
+                .to_string()
+        })
+        .collect()
+}
+
+#[sqlx::test]
+async fn test_classified_items_are_browsable_by_topic(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let tech = insert_item(&pool, user_id, "https://example.com/compilers").await;
+    insert_content(&pool, tech, TECH_TEXT, "en").await;
+    let travel = insert_item(&pool, user_id, "https://example.com/islands").await;
+    insert_content(&pool, travel, TRAVEL_TEXT, "en").await;
+    let unextracted = insert_item(&pool, user_id, "https://example.com/pending").await;
+
+    let handler = ClassifyTopicsJobHandler::new();
+    for item_id in [tech, travel, unextracted] {
+        classify(&pool, &handler, item_id).await;
+    }
+
+    let (status, list) = get_json(&app, &token, "/v1/items?topic=tech").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item_ids(&list), vec![tech.to_string()]);
+    assert_eq!(list["items"][0]["topics"], json!(["tech"]));
+
+    let (_, list) = get_json(&app, &token, "/v1/items?topic=travel").await;
+    assert_eq!(item_ids(&list), vec![travel.to_string()]);
+
+    let (status, topics) = get_json(&app, &token, "/v1/topics").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        topics,
+        json!({"topics": [
+            {"topic": "tech", "count": 1},
+            {"topic": "travel", "count": 1}
+        ]})
+    );
+
+    // Search narrows to the topic too
+    let (status, hits) = get_json(&app, &token, "/v1/search?q=the&topic=travel").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item_ids(&hits), vec![travel.to_string()]);
+
+    let (status, _) = get_json(&app, &token, "/v1/items?topic=Not%20A%20Topic").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+async fn test_reclassifying_replaces_topics(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://example.com/a").await;
+    insert_content(&pool, item_id, TECH_TEXT, "en").await;
+
+    classify(&pool, &ClassifyTopicsJobHandler::new(), item_id).await;
+    let handler = ClassifyTopicsJobHandler::with_classifier(Arc::new(Always("science")));
+    classify(&pool, &handler, item_id).await;
+
+    let (_, list) = get_json(&app, &token, "/v1/items").await;
+    assert_eq!(list["items"][0]["topics"], json!(["science"]));
+    let (_, topics) = get_json(&app, &token, "/v1/topics").await;
+    assert_eq!(
+        topics,
+        json!({"topics": [{"topic": "science", "count": 1}]})
+    );
+}
+
+#[sqlx::test]
+async fn test_topics_are_per_user(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (alice_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    let (_, bob_token) = create_user_with_token(&pool, "bob@example.com").await;
+    let item_id = insert_item(&pool, alice_id, "https://example.com/a").await;
+    insert_content(&pool, item_id, TECH_TEXT, "en").await;
+    classify(&pool, &ClassifyTopicsJobHandler::new(), item_id).await;
+
+    let (status, topics) = get_json(&app, &bob_token, "/v1/topics").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(topics, json!({"topics": []}));
+}
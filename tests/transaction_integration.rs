@@ -0,0 +1,67 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    middleware::from_fn_with_state,
+    routing::post,
+};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use capsule::middleware::transaction::{RequestTransaction, transaction_middleware};
+
+/// Insert a tag for the user in the path, then answer with the status the
+/// test asked for
+async fn tag_then_respond(
+    transaction: RequestTransaction,
+    axum::extract::Path((user_id, status)): axum::extract::Path<(Uuid, u16)>,
+) -> StatusCode {
+    sqlx::query("INSERT INTO tags (user_id, name) VALUES ($1, 'tx')")
+        .bind(user_id)
+        .execute(&mut *transaction.conn().await)
+        .await
+        .unwrap();
+    StatusCode::from_u16(status).unwrap()
+}
+
+async fn tag_count(pool: &Pool<Postgres>, user_id: Uuid) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM tags WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+async fn call(pool: &Pool<Postgres>, user_id: Uuid, status: u16) -> StatusCode {
+    let app = Router::new().route(
+        "/{user_id}/{status}",
+        post(tag_then_respond)
+            .route_layer(from_fn_with_state(pool.clone(), transaction_middleware)),
+    );
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/{}/{}", user_id, status))
+        .body(Body::empty())
+        .unwrap();
+    app.oneshot(request).await.unwrap().status()
+}
+
+#[sqlx::test]
+async fn test_failed_request_rolls_back(pool: Pool<Postgres>) {
+    let user_id: Uuid =
+        sqlx::query_scalar("INSERT INTO users (email, pw_hash) VALUES ($1, 'x') RETURNING id")
+            .bind("alice@example.com")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+    assert_eq!(
+        call(&pool, user_id, 422).await,
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+    assert_eq!(tag_count(&pool, user_id).await, 0);
+
+    assert_eq!(call(&pool, user_id, 201).await, StatusCode::CREATED);
+    assert_eq!(tag_count(&pool, user_id).await, 1);
+}
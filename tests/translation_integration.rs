@@ -0,0 +1,160 @@
+mod helpers;
+
+use async_trait::async_trait;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header::AUTHORIZATION},
+};
+use capsule::{
+    fetcher::Deadline,
+    jobs::{JobHandler, TRANSLATE_CONTENT_JOB_KIND, TranslateContentJobHandler},
+    translation::TranslationProvider,
+};
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use tower::ServiceExt;
+use tracing::Span;
+use uuid::Uuid;
+
+use helpers::{create_user_with_token, insert_content, insert_item};
+
+/// Stands in for a translation service by shouting the text
+struct Uppercase;
+
+#[async_trait]
+impl TranslationProvider for Uppercase {
+    fn name(&self) -> &'static str {
+        "uppercase"
+    }
+
+    async fn translate(
+        &self,
+        text: &str,
+        _source: Option<&str>,
+        _target: &str,
+    ) -> anyhow::Result<String> {
+        Ok(text.to_uppercase())
+    }
+}
+
+async fn send(
+    app: &Router,
+    token: &str,
+    method: &str,
+    uri: &str,
+    body: Value,
+) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// Run the translation jobs the API staged, as the worker would
+async fn run_translation_jobs(pool: &Pool<Postgres>) {
+    let payloads: Vec<Value> =
+        sqlx::query_scalar("SELECT payload FROM job_outbox WHERE kind = $1 ORDER BY id")
+            .bind(TRANSLATE_CONTENT_JOB_KIND)
+            .fetch_all(pool)
+            .await
+            .unwrap();
+    let handler = TranslateContentJobHandler::new(Some(Arc::new(Uppercase)));
+    for payload in payloads {
+        handler
+            .run(payload, pool, Span::none(), Deadline::none())
+            .await
+            .unwrap();
+    }
+}
+
+async fn set_translate_to(pool: &Pool<Postgres>, user_id: Uuid, lang: &str) {
+    sqlx::query(
+        "UPDATE users SET prefs = jsonb_build_object('translate_to', $2::text) WHERE id = $1",
+    )
+    .bind(user_id)
+    .bind(lang)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[sqlx::test]
+async fn test_translation_is_stored_as_alternate_content(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://example.com/a").await;
+    insert_content(&pool, item_id, "Hello there.\n\nGeneral Kenobi.", "en").await;
+
+    let uri = format!("/v1/items/{}/translations", item_id);
+    let (status, _) = send(&app, &token, "GET", &format!("{}/de", uri), Value::Null).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, requested) = send(&app, &token, "POST", &uri, json!({"lang": "de"})).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(requested["lang"], "de");
+
+    run_translation_jobs(&pool).await;
+
+    let (status, translation) =
+        send(&app, &token, "GET", &format!("{}/de", uri), Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(translation["text"], "HELLO THERE.\n\nGENERAL KENOBI.");
+    assert_eq!(translation["source_lang"], "en");
+    assert_eq!(translation["provider"], "uppercase");
+}
+
+#[sqlx::test]
+async fn test_translation_defaults_to_preferred_language(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://example.com/a").await;
+    insert_content(&pool, item_id, "Bonjour.", "fr").await;
+    let uri = format!("/v1/items/{}/translations", item_id);
+
+    let (status, _) = send(&app, &token, "POST", &uri, json!({})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    set_translate_to(&pool, user_id, "en").await;
+    let (status, requested) = send(&app, &token, "POST", &uri, json!({})).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(requested["lang"], "en");
+
+    run_translation_jobs(&pool).await;
+    let (status, translation) =
+        send(&app, &token, "GET", &format!("{}/en", uri), Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(translation["text"], "BONJOUR.");
+}
+
+#[sqlx::test]
+async fn test_translations_are_private(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (alice_id, _) = create_user_with_token(&pool, "alice@example.com").await;
+    let (_, bob) = create_user_with_token(&pool, "bob@example.com").await;
+    let item_id = insert_item(&pool, alice_id, "https://example.com/a").await;
+    insert_content(&pool, item_id, "Hello.", "en").await;
+
+    let uri = format!("/v1/items/{}/translations", item_id);
+    let (status, _) = send(&app, &bob, "POST", &uri, json!({"lang": "de"})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, &bob, "POST", &uri, json!({"lang": "Deutsch"})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM job_outbox WHERE kind = $1")
+        .bind(TRANSLATE_CONTENT_JOB_KIND)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
@@ -0,0 +1,234 @@
+mod helpers;
+
+use axum::{
+    Router,
+    body::Body,
+    http::{
+        Method, Request, StatusCode,
+        header::{AUTHORIZATION, CONTENT_TYPE},
+    },
+};
+use capsule::repositories::LinkRepository;
+use serde_json::{Value, json};
+use sqlx::{Pool, Postgres};
+use tower::ServiceExt;
+use url::Url;
+use uuid::Uuid;
+
+use helpers::{create_user_with_token, insert_content, insert_item};
+
+/// Status, `X-Undo-Token` header and JSON body of the response
+async fn send(
+    app: &Router,
+    method: Method,
+    token: &str,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, Option<String>, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(AUTHORIZATION, format!("Bearer {}", token));
+    let request = match body {
+        Some(body) => request
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let undo_token = response
+        .headers()
+        .get("x-undo-token")
+        .map(|value| value.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        undo_token,
+        serde_json::from_slice(&body).unwrap_or(Value::Null),
+    )
+}
+
+async fn undo(app: &Router, token: &str, undo_token: &str) -> (StatusCode, Value) {
+    let uri = format!("/v1/undo/{}", undo_token);
+    let (status, _, body) = send(app, Method::POST, token, &uri, None).await;
+    (status, body)
+}
+
+#[sqlx::test]
+async fn test_undo_puts_a_deleted_item_back(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://example.com/post").await;
+    insert_content(&pool, item_id, "Some words", "en").await;
+    let (status, _, _) = send(
+        &app,
+        Method::POST,
+        &token,
+        "/v1/items/bulk",
+        Some(json!({ "operations": [{ "op": "tag", "item_ids": [item_id], "tag": "keep" }] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let uri = format!("/v1/items/{}", item_id);
+    let (status, undo_token, _) = send(&app, Method::DELETE, &token, &uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let undo_token = undo_token.unwrap();
+    let (status, _, _) = send(&app, Method::GET, &token, &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body) = undo(&app, &token, &undo_token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["item_ids"], json!([item_id]));
+
+    let (status, _, item) = send(&app, Method::GET, &token, &uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["tags"], json!(["keep"]));
+    assert_eq!(item["word_count"], 2);
+
+    // Each token undoes its change once
+    let (status, _) = undo(&app, &token, &undo_token).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+async fn test_undo_restores_links_and_embeds_again(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let essay = insert_item(&pool, user_id, "https://example.com/essay").await;
+    insert_content(&pool, essay, "Some words", "en").await;
+    let source = insert_item(&pool, user_id, "https://other.example/source").await;
+    sqlx::query("UPDATE items SET processing_state = 'ready' WHERE id = $1")
+        .bind(essay)
+        .execute(&pool)
+        .await
+        .unwrap();
+    LinkRepository::new(&pool)
+        .record(
+            essay,
+            &[Url::parse("https://other.example/source").unwrap()],
+        )
+        .await
+        .unwrap();
+
+    let uri = format!("/v1/items/{}", essay);
+    let (status, undo_token, _) = send(&app, Method::DELETE, &token, &uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = undo(&app, &token, &undo_token.unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let links_uri = format!("/v1/items/{}/links", source);
+    let (status, _, links) = send(&app, Method::GET, &token, &links_uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(links["cited_by"][0]["id"], essay.to_string());
+
+    // Finished items aren't fetched again, only embedded
+    let kinds: Vec<String> = sqlx::query_scalar(
+        "SELECT kind FROM job_outbox WHERE payload->>'item_id' = $1 ORDER BY kind",
+    )
+    .bind(essay.to_string())
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(kinds, ["embed_content"]);
+}
+
+#[sqlx::test]
+async fn test_undo_reverses_archive_and_bulk_changes(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let first = insert_item(&pool, user_id, "https://example.com/first").await;
+    let second = insert_item(&pool, user_id, "https://example.com/second").await;
+    let first_uri = format!("/v1/items/{}", first);
+
+    let (status, undo_token, item) = send(
+        &app,
+        Method::PATCH,
+        &token,
+        &first_uri,
+        Some(json!({ "status": "archived" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(item["status"], "archived");
+    let (status, _) = undo(&app, &token, &undo_token.unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, _, item) = send(&app, Method::GET, &token, &first_uri, None).await;
+    assert_eq!(item["status"], "pending");
+
+    // Renaming isn't destructive, so there's nothing to undo
+    let (_, undo_token, _) = send(
+        &app,
+        Method::PATCH,
+        &token,
+        &first_uri,
+        Some(json!({ "title": "Renamed" })),
+    )
+    .await;
+    assert_eq!(undo_token, None);
+
+    // The first item already carries the tag, so undoing leaves it on
+    send(
+        &app,
+        Method::POST,
+        &token,
+        "/v1/items/bulk",
+        Some(json!({ "operations": [{ "op": "tag", "item_ids": [first], "tag": "later" }] })),
+    )
+    .await;
+    let (status, undo_token, _) = send(
+        &app,
+        Method::POST,
+        &token,
+        "/v1/items/bulk",
+        Some(json!({ "operations": [
+            { "op": "tag", "item_ids": [first, second], "tag": "later" },
+            { "op": "favorite", "item_ids": [second] },
+            { "op": "archive", "item_ids": [first, second] },
+        ] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = undo(&app, &token, &undo_token.unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, _, item) = send(&app, Method::GET, &token, &first_uri, None).await;
+    assert_eq!(item["status"], "pending");
+    assert_eq!(item["tags"], json!(["later"]));
+    let second_uri = format!("/v1/items/{}", second);
+    let (_, _, item) = send(&app, Method::GET, &token, &second_uri, None).await;
+    assert_eq!(item["status"], "pending");
+    assert_eq!(item["favorite"], false);
+    assert_eq!(item["tags"], json!([]));
+}
+
+#[sqlx::test]
+async fn test_undo_tokens_expire_and_belong_to_their_user(pool: Pool<Postgres>) {
+    let app = helpers::test_app(pool.clone());
+    let (user_id, token) = create_user_with_token(&pool, "alice@example.com").await;
+    let (_, other_token) = create_user_with_token(&pool, "bob@example.com").await;
+    let item_id = insert_item(&pool, user_id, "https://example.com/post").await;
+
+    let uri = format!("/v1/items/{}", item_id);
+    let (_, undo_token, _) = send(&app, Method::DELETE, &token, &uri, None).await;
+    let undo_token = undo_token.unwrap();
+
+    let (status, _) = undo(&app, &other_token, &undo_token).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = undo(&app, &token, &Uuid::new_v4().to_string()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    sqlx::query("UPDATE undo_actions SET expires_at = NOW() - INTERVAL '1 second'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (status, _) = undo(&app, &token, &undo_token).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, _) = send(&app, Method::GET, &token, &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,208 @@
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use sqlx::{PgPool, Pool, Postgres};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::Span;
+
+use capsule::fetcher::Deadline;
+use capsule::jobs::{
+    ChaosConfig, INJECTED_FAILURE, JobHandler, JobRegistry, JobRepository, WorkerConfig,
+    WorkerSupervisor,
+};
+
+const KIND: &str = "chaos_test";
+
+/// Records the jobs it finished
+#[derive(Clone)]
+struct RecordingHandler {
+    done: Arc<Mutex<Vec<i64>>>,
+}
+
+#[async_trait]
+impl JobHandler for RecordingHandler {
+    async fn run(
+        &self,
+        payload: Value,
+        _pool: &PgPool,
+        _span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        self.done
+            .lock()
+            .unwrap()
+            .push(payload["n"].as_i64().unwrap());
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        KIND
+    }
+}
+
+async fn enqueue_jobs(pool: &PgPool, count: i64, max_attempts: i32) {
+    for n in 0..count {
+        JobRepository::enqueue(pool, KIND, json!({"n": n}), None, Some(max_attempts))
+            .await
+            .expect("Failed to enqueue job");
+    }
+}
+
+fn supervisor(pool: &PgPool, done: &Arc<Mutex<Vec<i64>>>, chaos: ChaosConfig) -> WorkerSupervisor {
+    let mut registry = JobRegistry::new();
+    registry.register(RecordingHandler { done: done.clone() });
+    let config = WorkerConfig {
+        concurrency: 4,
+        poll_interval_ms: 10,
+        visibility_timeout_secs: 1,
+        base_backoff_secs: 0,
+        chaos: Some(chaos),
+        ..WorkerConfig::default()
+    };
+    WorkerSupervisor::new(pool.clone(), registry, config)
+}
+
+async fn count_jobs(pool: &PgPool, status: &str) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM jobs WHERE status::text = $1")
+        .bind(status)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn test_jobs_survive_failures_delays_and_abandoned_leases(pool: Pool<Postgres>) {
+    enqueue_jobs(&pool, 30, 50).await;
+
+    let done = Arc::new(Mutex::new(Vec::new()));
+    let chaos = ChaosConfig {
+        failure_rate: 0.3,
+        db_delay_rate: 0.3,
+        db_delay_ms: 20,
+        abandon_rate: 0.1,
+    };
+    let mut tokens = Vec::new();
+    let mut handles = Vec::new();
+    for _ in 0..2 {
+        let supervisor = supervisor(&pool, &done, chaos.clone());
+        tokens.push(supervisor.shutdown_token());
+        handles.push(tokio::spawn(supervisor.run()));
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+    while count_jobs(&pool, "succeeded").await < 30 {
+        assert!(Instant::now() < deadline, "jobs did not finish in time");
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    for token in tokens {
+        token.cancel();
+    }
+    for handle in handles {
+        tokio::time::timeout(Duration::from_secs(10), handle)
+            .await
+            .expect("worker did not shut down")
+            .unwrap()
+            .unwrap();
+    }
+
+    // Each job finished once, however often it was failed or abandoned
+    let done = done.lock().unwrap().clone();
+    let unique: HashSet<i64> = done.iter().copied().collect();
+    assert_eq!(unique.len(), 30);
+    assert_eq!(done.len(), 30);
+
+    let retried: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM jobs WHERE last_error = $1 AND status = 'succeeded'::job_status",
+    )
+    .bind(INJECTED_FAILURE)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(retried > 0, "no job was retried after an injected failure");
+}
+
+#[sqlx::test]
+async fn test_injected_failures_use_up_attempts(pool: Pool<Postgres>) {
+    enqueue_jobs(&pool, 3, 3).await;
+
+    let done = Arc::new(Mutex::new(Vec::new()));
+    let supervisor = supervisor(
+        &pool,
+        &done,
+        ChaosConfig {
+            failure_rate: 1.0,
+            ..ChaosConfig::default()
+        },
+    );
+    let token = supervisor.shutdown_token();
+    let handle = tokio::spawn(supervisor.run());
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while count_jobs(&pool, "failed").await < 3 {
+        assert!(Instant::now() < deadline, "jobs were not failed in time");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    token.cancel();
+    handle.await.unwrap().unwrap();
+
+    assert!(done.lock().unwrap().is_empty());
+    let jobs: Vec<(i32, Option<String>)> = sqlx::query_as("SELECT attempts, last_error FROM jobs")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    for (attempts, last_error) in jobs {
+        assert_eq!(attempts, 3);
+        assert_eq!(last_error.as_deref(), Some(INJECTED_FAILURE));
+    }
+}
+
+#[sqlx::test]
+async fn test_abandoned_jobs_run_once_their_lease_expires(pool: Pool<Postgres>) {
+    enqueue_jobs(&pool, 5, 1).await;
+
+    let done = Arc::new(Mutex::new(Vec::new()));
+    let reckless = supervisor(
+        &pool,
+        &done,
+        ChaosConfig {
+            abandon_rate: 1.0,
+            ..ChaosConfig::default()
+        },
+    );
+    let token = reckless.shutdown_token();
+    let handle = tokio::spawn(reckless.run());
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while count_jobs(&pool, "running").await < 5 {
+        assert!(Instant::now() < deadline, "jobs were not leased in time");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    token.cancel();
+    handle.await.unwrap().unwrap();
+
+    // Shutdown doesn't release them either: the worker never knew it had them
+    let leased: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs WHERE reserved_by IS NOT NULL")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(leased, 5);
+
+    // A healthy worker gets them once the lease is up
+    let healthy = supervisor(&pool, &done, ChaosConfig::default());
+    let token = healthy.shutdown_token();
+    let handle = tokio::spawn(healthy.run());
+
+    while count_jobs(&pool, "succeeded").await < 5 {
+        assert!(Instant::now() < deadline, "abandoned jobs never ran");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    token.cancel();
+    handle.await.unwrap().unwrap();
+
+    assert_eq!(done.lock().unwrap().len(), 5);
+}
@@ -0,0 +1,283 @@
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use sqlx::{PgPool, Pool, Postgres};
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tracing::Span;
+use uuid::Uuid;
+
+use capsule::fetcher::Deadline;
+use capsule::jobs::{JobHandler, JobRegistry, JobRepository, WorkerConfig, WorkerSupervisor};
+
+const KIND: &str = "scaling_test";
+
+/// Records which worker ran which job, taking `delay` per job, and the most
+/// jobs running at once across all workers
+#[derive(Clone)]
+struct RecordingHandler {
+    worker: usize,
+    delay: Duration,
+    runs: Arc<Mutex<Vec<(usize, i64)>>>,
+    running: Arc<AtomicUsize>,
+    peak: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl JobHandler for RecordingHandler {
+    async fn run(
+        &self,
+        payload: Value,
+        _pool: &PgPool,
+        _span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        let n = payload["n"].as_i64().unwrap();
+        self.runs.lock().unwrap().push((self.worker, n));
+        Ok(())
+    }
+
+    fn kind(&self) -> &'static str {
+        KIND
+    }
+}
+
+async fn enqueue_jobs(pool: &PgPool, count: i64) {
+    for n in 0..count {
+        JobRepository::enqueue(pool, KIND, json!({"n": n}), None, Some(1))
+            .await
+            .expect("Failed to enqueue job");
+    }
+}
+
+/// Run `workers` supervisors against the pool until every job has succeeded.
+/// Returns the recorded runs and the most jobs that ran at once.
+async fn run_workers(
+    pool: &PgPool,
+    workers: usize,
+    concurrency: usize,
+    delay: Duration,
+    expected: i64,
+) -> (Vec<(usize, i64)>, usize) {
+    let runs = Arc::new(Mutex::new(Vec::new()));
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let config = WorkerConfig {
+        concurrency,
+        poll_interval_ms: 10,
+        ..WorkerConfig::default()
+    };
+
+    let mut tokens = Vec::new();
+    let mut handles = Vec::new();
+    for worker in 0..workers {
+        let mut registry = JobRegistry::new();
+        registry.register(RecordingHandler {
+            worker,
+            delay,
+            runs: runs.clone(),
+            running: running.clone(),
+            peak: peak.clone(),
+        });
+        let supervisor = WorkerSupervisor::new(pool.clone(), registry, config.clone());
+        tokens.push(supervisor.shutdown_token());
+        handles.push(tokio::spawn(supervisor.run()));
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    loop {
+        let succeeded: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM jobs WHERE status = 'succeeded'::job_status")
+                .fetch_one(pool)
+                .await
+                .unwrap();
+        if succeeded == expected {
+            break;
+        }
+        assert!(Instant::now() < deadline, "jobs did not finish in time");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    for token in tokens {
+        token.cancel();
+    }
+    for handle in handles {
+        handle.await.unwrap().unwrap();
+    }
+
+    let runs = runs.lock().unwrap().clone();
+    (runs, peak.load(Ordering::SeqCst))
+}
+
+#[sqlx::test]
+async fn test_workers_never_double_process(pool: Pool<Postgres>) {
+    enqueue_jobs(&pool, 60).await;
+
+    let (runs, _) = run_workers(&pool, 3, 4, Duration::from_millis(5), 60).await;
+
+    assert_eq!(runs.len(), 60);
+    let unique: HashSet<i64> = runs.iter().map(|(_, n)| *n).collect();
+    assert_eq!(unique.len(), 60);
+
+    // Work is shared rather than drained by whichever worker started first
+    let busy_workers: HashSet<usize> = runs.iter().map(|(worker, _)| *worker).collect();
+    assert!(
+        busy_workers.len() > 1,
+        "only worker(s) {busy_workers:?} ran jobs"
+    );
+}
+
+#[sqlx::test]
+async fn test_leases_are_disjoint_under_contention(pool: Pool<Postgres>) {
+    enqueue_jobs(&pool, 50).await;
+
+    let mut tasks = Vec::new();
+    for _ in 0..10 {
+        let pool = pool.clone();
+        tasks.push(tokio::spawn(async move {
+            let worker_id = Uuid::new_v4();
+            let mut leased = Vec::new();
+            loop {
+                let jobs = JobRepository::fetch_due_jobs(&pool, 3, worker_id, 300)
+                    .await
+                    .unwrap();
+                if jobs.is_empty() {
+                    break;
+                }
+                for job in jobs {
+                    assert_eq!(job.reserved_by, Some(worker_id));
+                    leased.push(job.id);
+                }
+            }
+            leased
+        }));
+    }
+
+    let mut all = Vec::new();
+    for task in tasks {
+        all.extend(task.await.unwrap());
+    }
+    let unique: HashSet<Uuid> = all.iter().copied().collect();
+    assert_eq!(all.len(), 50);
+    assert_eq!(unique.len(), 50);
+}
+
+#[sqlx::test]
+async fn test_unexpired_lease_is_not_taken(pool: Pool<Postgres>) {
+    enqueue_jobs(&pool, 1).await;
+
+    let holder = Uuid::new_v4();
+    let leased = JobRepository::fetch_due_jobs(&pool, 1, holder, 300)
+        .await
+        .unwrap();
+    assert_eq!(leased.len(), 1);
+
+    let other = JobRepository::fetch_due_jobs(&pool, 1, Uuid::new_v4(), 300)
+        .await
+        .unwrap();
+    assert!(other.is_empty());
+
+    let reserved_by: Option<Uuid> = sqlx::query_scalar("SELECT reserved_by FROM jobs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(reserved_by, Some(holder));
+}
+
+#[sqlx::test]
+async fn test_throughput_scales_with_workers(pool: Pool<Postgres>) {
+    let delay = Duration::from_millis(50);
+
+    enqueue_jobs(&pool, 24).await;
+    let (_, single) = run_workers(&pool, 1, 1, delay, 24).await;
+
+    sqlx::query("DELETE FROM jobs")
+        .execute(&pool)
+        .await
+        .unwrap();
+    enqueue_jobs(&pool, 24).await;
+    let (_, many) = run_workers(&pool, 4, 1, delay, 24).await;
+
+    // Each worker takes one job at a time, so only more workers explain
+    // more jobs running at once
+    assert_eq!(single, 1);
+    assert_eq!(many, 4, "at most {many} of 4 workers ran jobs at once");
+}
+
+#[derive(Clone)]
+struct PanickingHandler;
+
+#[async_trait]
+impl JobHandler for PanickingHandler {
+    async fn run(
+        &self,
+        _payload: Value,
+        _pool: &PgPool,
+        _span: Span,
+        _deadline: Deadline,
+    ) -> anyhow::Result<()> {
+        panic!("handler exploded");
+    }
+
+    fn kind(&self) -> &'static str {
+        "panicking_test"
+    }
+}
+
+#[sqlx::test]
+async fn test_panicking_job_is_dead_lettered(pool: Pool<Postgres>) {
+    let job_id = JobRepository::enqueue(&pool, "panicking_test", json!({}), None, Some(10))
+        .await
+        .expect("Failed to enqueue job");
+
+    let mut registry = JobRegistry::new();
+    registry.register(PanickingHandler);
+    let config = WorkerConfig {
+        poll_interval_ms: 10,
+        base_backoff_secs: 0,
+        max_consecutive_panics: 2,
+        ..WorkerConfig::default()
+    };
+    let supervisor = WorkerSupervisor::new(pool.clone(), registry, config);
+    let token = supervisor.shutdown_token();
+    let handle = tokio::spawn(supervisor.run());
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    let (attempts, panic_count, last_error) = loop {
+        let row: (i32, i32, Option<String>, bool) = sqlx::query_as(
+            "SELECT attempts, panic_count, last_error, dead_lettered_at IS NOT NULL FROM jobs WHERE id = $1",
+        )
+        .bind(job_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        if row.3 {
+            break (row.0, row.1, row.2);
+        }
+        assert!(Instant::now() < deadline, "job was not dead-lettered");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    };
+
+    token.cancel();
+    handle.await.unwrap().unwrap();
+
+    // The worker survived both panics and gave up well before max_attempts
+    assert_eq!(attempts, 2);
+    assert_eq!(panic_count, 2);
+    assert!(last_error.unwrap().contains("handler exploded"));
+
+    let status: String = sqlx::query_scalar("SELECT status::text FROM jobs WHERE id = $1")
+        .bind(job_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "failed");
+}